@@ -4,17 +4,18 @@ use crate::context_store::ContextStore;
 use crate::context_strip::{ContextStrip, ContextStripEvent, SuggestContextKind};
 use crate::message_editor::{extract_message_creases, insert_message_creases};
 use crate::thread::{
-    LastRestoreCheckpoint, MessageCrease, MessageId, MessageSegment, Thread, ThreadError,
-    ThreadEvent, ThreadFeedback, ThreadSummary,
+    ComparisonResponseStatus, LastRestoreCheckpoint, MessageCrease, MessageId, MessageSegment,
+    ResponseMetrics, Thread, ThreadError, ThreadEvent, ThreadFeedback, ThreadSummary,
 };
 use crate::thread_store::{RulesLoadingError, TextThreadStore, ThreadStore};
 use crate::tool_use::{PendingToolUseStatus, ToolUse};
 use crate::ui::{
     AddedContext, AgentNotification, AgentNotificationEvent, AnimatedLabel, ContextPill,
 };
-use crate::{AgentPanel, ModelUsageContext};
+use crate::{AgentDiffPane, AgentPanel, ModelUsageContext};
 use agent_settings::{AgentSettings, NotifyWhenAgentWaiting};
 use anyhow::Context as _;
+use assistant_context_editor::humanize_token_count;
 use assistant_tool::ToolUseStatus;
 use audio::{Audio, Sound};
 use collections::{HashMap, HashSet};
@@ -24,11 +25,12 @@ use editor::{Editor, EditorElement, EditorEvent, EditorStyle, MultiBuffer};
 use gpui::{
     AbsoluteLength, Animation, AnimationExt, AnyElement, App, ClickEvent, ClipboardEntry,
     ClipboardItem, DefiniteLength, EdgesRefinement, Empty, Entity, EventEmitter, Focusable, Hsla,
-    ListAlignment, ListState, MouseButton, PlatformDisplay, ScrollHandle, Stateful,
+    ListAlignment, ListState, MouseButton, PlatformDisplay, PromptLevel, ScrollHandle, Stateful,
     StyleRefinement, Subscription, Task, TextStyle, TextStyleRefinement, Transformation,
     UnderlineStyle, WeakEntity, WindowHandle, linear_color_stop, linear_gradient, list, percentage,
     pulsating_between,
 };
+use itertools::Itertools as _;
 use language::{Buffer, Language, LanguageRegistry};
 use language_model::{
     LanguageModelRequestMessage, LanguageModelToolUseId, MessageContent, Role, StopReason,
@@ -72,10 +74,12 @@ pub struct ActiveThread {
     hide_scrollbar_task: Option<Task<()>>,
     rendered_messages_by_id: HashMap<MessageId, RenderedMessage>,
     rendered_tool_uses: HashMap<LanguageModelToolUseId, RenderedToolUse>,
+    rendered_comparison: Vec<Entity<Markdown>>,
     editing_message: Option<(MessageId, EditingMessageState)>,
     expanded_tool_uses: HashMap<LanguageModelToolUseId, bool>,
     expanded_thinking_segments: HashMap<(MessageId, usize), bool>,
     expanded_code_blocks: HashMap<(MessageId, usize), bool>,
+    expanded_response_metrics: HashSet<MessageId>,
     last_error: Option<ThreadError>,
     notifications: Vec<WindowHandle<AgentNotification>>,
     copied_code_block_ids: HashSet<(MessageId, usize)>,
@@ -336,6 +340,10 @@ fn tool_use_markdown_style(window: &Window, cx: &mut App) -> MarkdownStyle {
 
 const CODEBLOCK_CONTAINER_GROUP: &str = "codeblock_container";
 
+/// Tool calls that run at least this long notify the user when they finish, as
+/// the user has likely moved on to something else in the meantime.
+const LONG_TOOL_CALL_DURATION: Duration = Duration::from_secs(30);
+
 fn render_markdown_code_block(
     message_id: MessageId,
     ix: usize,
@@ -794,6 +802,7 @@ impl ActiveThread {
             expanded_tool_uses: HashMap::default(),
             expanded_thinking_segments: HashMap::default(),
             expanded_code_blocks: HashMap::default(),
+            expanded_response_metrics: HashSet::default(),
             list_state: list_state.clone(),
             scrollbar_state: ScrollbarState::new(list_state),
             show_scrollbar: false,
@@ -806,6 +815,7 @@ impl ActiveThread {
             notification_subscriptions: HashMap::default(),
             open_feedback_editors: HashMap::default(),
             _load_edited_message_context_task: None,
+            rendered_comparison: Vec::new(),
         };
 
         for message in thread.read(cx).messages().cloned().collect::<Vec<_>>() {
@@ -916,6 +926,39 @@ impl ActiveThread {
         self.rendered_messages_by_id.remove(id);
     }
 
+    fn sync_rendered_comparison(&mut self, cx: &mut Context<Self>) {
+        let texts = self
+            .thread
+            .read(cx)
+            .comparison()
+            .map(|comparison| {
+                comparison
+                    .responses()
+                    .iter()
+                    .map(|response| response.text.clone())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        self.rendered_comparison.truncate(texts.len());
+        for (ix, text) in texts.into_iter().enumerate() {
+            match self.rendered_comparison.get(ix) {
+                Some(markdown) => markdown.update(cx, |markdown, cx| {
+                    if markdown.source() != text {
+                        markdown.replace(text, cx);
+                    }
+                }),
+                None => {
+                    let language_registry = self.language_registry.clone();
+                    self.rendered_comparison.push(
+                        cx.new(|cx| Markdown::new(text.into(), Some(language_registry), None, cx)),
+                    );
+                }
+            }
+        }
+        cx.notify();
+    }
+
     fn render_tool_use_markdown(
         &mut self,
         tool_use_id: LanguageModelToolUseId,
@@ -993,7 +1036,8 @@ impl ActiveThread {
             }
             ThreadEvent::StreamedCompletion
             | ThreadEvent::SummaryGenerated
-            | ThreadEvent::SummaryChanged => {
+            | ThreadEvent::SummaryChanged
+            | ThreadEvent::DescriptionGenerated => {
                 self.save_thread(cx);
             }
             ThreadEvent::Stopped(reason) => match reason {
@@ -1007,6 +1051,7 @@ impl ActiveThread {
                             "New message"
                         },
                         IconName::ZedAssistant,
+                        used_tools,
                         window,
                         cx,
                     );
@@ -1015,13 +1060,20 @@ impl ActiveThread {
             },
             ThreadEvent::ToolConfirmationNeeded => {
                 self.play_notification_sound(window, cx);
-                self.show_notification("Waiting for tool confirmation", IconName::Info, window, cx);
+                self.show_notification(
+                    "Waiting for tool confirmation",
+                    IconName::Info,
+                    false,
+                    window,
+                    cx,
+                );
             }
             ThreadEvent::ToolUseLimitReached => {
                 self.play_notification_sound(window, cx);
                 self.show_notification(
                     "Consecutive tool use limit reached.",
                     IconName::Warning,
+                    false,
                     window,
                     cx,
                 );
@@ -1107,9 +1159,23 @@ impl ActiveThread {
                             .unwrap_or("".into()),
                         cx,
                     );
+
+                    if let PendingToolUseStatus::Running { started_at, .. } = &tool_use.status {
+                        if started_at.elapsed() >= LONG_TOOL_CALL_DURATION {
+                            self.play_notification_sound(window, cx);
+                            self.show_notification(
+                                format!("Finished: {}", tool_use.ui_text),
+                                IconName::Hammer,
+                                tool_use.may_perform_edits,
+                                window,
+                                cx,
+                            );
+                        }
+                    }
                 }
             }
             ThreadEvent::CheckpointChanged => cx.notify(),
+            ThreadEvent::ComparisonChanged => self.sync_rendered_comparison(cx),
             ThreadEvent::ReceivedTextChunk => {}
             ThreadEvent::InvalidToolInput {
                 tool_use_id,
@@ -1167,10 +1233,14 @@ impl ActiveThread {
         }
     }
 
+    /// Notifies the user about the thread while its window isn't active.
+    /// Accepting the notification brings them back to the thread, and to its
+    /// diff when `open_diff` is set and the thread has unreviewed changes.
     fn show_notification(
         &mut self,
         caption: impl Into<SharedString>,
         icon: IconName,
+        open_diff: bool,
         window: &mut Window,
         cx: &mut Context<ActiveThread>,
     ) {
@@ -1179,30 +1249,79 @@ impl ActiveThread {
         }
 
         let title = self.thread.read(cx).summary().unwrap_or("Agent Panel");
+        let caption = caption.into();
+        let notify_when_agent_waiting = AgentSettings::get_global(cx).notify_when_agent_waiting;
+        if notify_when_agent_waiting == NotifyWhenAgentWaiting::Never {
+            return;
+        }
+
+        // The pop-up only shows on top of Zed's own windows, so use a system notification when
+        // another application is in front instead of showing both.
+        if cx.active_window().is_none() {
+            self.post_system_notification(title, caption, open_diff, window, cx);
+            return;
+        }
 
-        match AgentSettings::get_global(cx).notify_when_agent_waiting {
+        match notify_when_agent_waiting {
             NotifyWhenAgentWaiting::PrimaryScreen => {
                 if let Some(primary) = cx.primary_display() {
-                    self.pop_up(icon, caption.into(), title.clone(), window, primary, cx);
+                    self.pop_up(
+                        icon,
+                        caption.clone(),
+                        title.clone(),
+                        open_diff,
+                        window,
+                        primary,
+                        cx,
+                    );
                 }
             }
             NotifyWhenAgentWaiting::AllScreens => {
-                let caption = caption.into();
                 for screen in cx.displays() {
-                    self.pop_up(icon, caption.clone(), title.clone(), window, screen, cx);
+                    self.pop_up(
+                        icon,
+                        caption.clone(),
+                        title.clone(),
+                        open_diff,
+                        window,
+                        screen,
+                        cx,
+                    );
                 }
             }
-            NotifyWhenAgentWaiting::Never => {
-                // Don't show anything
-            }
+            NotifyWhenAgentWaiting::Never => {}
         }
     }
 
+    fn post_system_notification(
+        &mut self,
+        title: SharedString,
+        caption: SharedString,
+        open_diff: bool,
+        window: &mut Window,
+        cx: &mut Context<ActiveThread>,
+    ) {
+        let handle = window.window_handle();
+        let workspace = self.workspace.clone();
+        let thread = self.thread.clone();
+        cx.post_notification_with_action(&title, &caption, move |cx| {
+            cx.activate(true);
+            handle
+                .update(cx, |_, window, cx| {
+                    window.activate_window();
+                    Self::reveal_thread(thread, workspace, open_diff, window, cx);
+                })
+                .log_err();
+        })
+        .detach_and_log_err(cx);
+    }
+
     fn pop_up(
         &mut self,
         icon: IconName,
         caption: SharedString,
         title: SharedString,
+        open_diff: bool,
         window: &mut Window,
         screen: Rc<dyn PlatformDisplay>,
         cx: &mut Context<'_, ActiveThread>,
@@ -1232,24 +1351,26 @@ impl ActiveThread {
                     .entry(screen_window)
                     .or_insert_with(Vec::new)
                     .push(cx.subscribe_in(&pop_up, window, {
-                        |this, _, event, window, cx| match event {
+                        move |this, _, event, window, cx| match event {
                             AgentNotificationEvent::Accepted => {
                                 let handle = window.window_handle();
                                 cx.activate(true);
 
                                 let workspace_handle = this.workspace.clone();
+                                let thread = this.thread.clone();
 
                                 // If there are multiple Zed windows, activate the correct one.
                                 cx.defer(move |cx| {
                                     handle
-                                        .update(cx, |_view, window, _cx| {
+                                        .update(cx, |_view, window, cx| {
                                             window.activate_window();
-
-                                            if let Some(workspace) = workspace_handle.upgrade() {
-                                                workspace.update(_cx, |workspace, cx| {
-                                                    workspace.focus_panel::<AgentPanel>(window, cx);
-                                                });
-                                            }
+                                            Self::reveal_thread(
+                                                thread,
+                                                workspace_handle,
+                                                open_diff,
+                                                window,
+                                                cx,
+                                            );
                                         })
                                         .log_err();
                                 });
@@ -1457,6 +1578,7 @@ impl ActiveThread {
                             &configured_model.model,
                             cx,
                         ),
+                        response_schema: None,
                     };
 
                     Some(configured_model.model.count_tokens(request, cx))
@@ -1636,6 +1758,50 @@ impl ActiveThread {
         self.confirm_editing_message(&menu::Confirm, window, cx);
     }
 
+    fn fork_thread(&mut self, message_id: MessageId, window: &mut Window, cx: &mut Context<Self>) {
+        let has_later_edits = self
+            .thread
+            .read(cx)
+            .checkpoint_after_message(message_id)
+            .is_some();
+        let answer = has_later_edits.then(|| {
+            window.prompt(
+                PromptLevel::Info,
+                "Restore files to this message?",
+                Some(
+                    "The agent changed files after this message. Restoring them lets the new \
+                    thread start from the same files, but discards those changes unless \
+                    they're committed.",
+                ),
+                &["Restore Files", "Keep Current Files", "Cancel"],
+                cx,
+            )
+        });
+        let workspace = self.workspace.clone();
+        cx.spawn_in(window, async move |this, cx| {
+            let restore_checkpoint = match answer {
+                Some(answer) => match answer.await? {
+                    0 => true,
+                    1 => false,
+                    _ => return Ok(()),
+                },
+                None => false,
+            };
+            let fork_task = this.update(cx, |this, cx| {
+                this.thread_store.update(cx, |thread_store, cx| {
+                    thread_store.fork_thread(&this.thread, message_id, restore_checkpoint, cx)
+                })
+            })?;
+            let thread = fork_task.await?;
+            workspace.update_in(cx, |workspace, window, cx| {
+                if let Some(panel) = workspace.panel::<AgentPanel>(cx) {
+                    panel.update(cx, |panel, cx| panel.open_thread(thread, window, cx));
+                }
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
     fn handle_feedback_click(
         &mut self,
         message_id: MessageId,
@@ -1811,14 +1977,20 @@ impl ActiveThread {
 
         let tool_uses = thread.tool_uses_for_message(message_id, cx);
         let has_tool_uses = !tool_uses.is_empty();
+        let response_metrics = message.response_metrics.clone();
         let is_generating = thread.is_generating();
         let is_generating_stale = thread.is_generation_stale().unwrap_or(false);
 
         let is_first_message = ix == 0;
         let is_last_message = ix == self.messages.len() - 1;
 
-        let loading_dots = (is_generating_stale && is_last_message)
-            .then(|| AnimatedLabel::new("").size(LabelSize::Small));
+        let loading_dots = if !is_last_message {
+            None
+        } else if let Some(position) = thread.queue_position() {
+            Some(AnimatedLabel::new(format!("Queued (position {position})")).size(LabelSize::Small))
+        } else {
+            is_generating_stale.then(|| AnimatedLabel::new("").size(LabelSize::Small))
+        };
 
         let editing_message_state = self
             .editing_message
@@ -1845,6 +2017,14 @@ impl ActiveThread {
                 }
             });
 
+        let fork_thread = IconButton::new(("fork-thread", ix), IconName::GitBranch)
+            .icon_size(IconSize::XSmall)
+            .icon_color(Color::Ignored)
+            .tooltip(Tooltip::text("Fork Thread from Here"))
+            .on_click(cx.listener(move |this, _, window, cx| {
+                this.fork_thread(message_id, window, cx);
+            }));
+
         // For all items that should be aligned with the LLM's response.
         const RESPONSE_PADDING_X: Pixels = px(19.);
 
@@ -1910,6 +2090,7 @@ impl ActiveThread {
                                     );
                                 })),
                         )
+                        .child(fork_thread)
                         .child(open_as_markdown),
                 )
                 .into_any_element(),
@@ -1954,11 +2135,12 @@ impl ActiveThread {
                                     );
                                 })),
                         )
+                        .child(fork_thread)
                         .child(open_as_markdown),
                 )
                 .into_any_element(),
             None => feedback_container
-                .child(h_flex().child(open_as_markdown))
+                .child(h_flex().child(fork_thread).child(open_as_markdown))
                 .into_any_element(),
         };
 
@@ -2129,6 +2311,9 @@ impl ActiveThread {
                     parent.children(tool_uses.into_iter().map(|tool_use| {
                         self.render_tool_use(tool_use, window, workspace.clone(), cx)
                     }))
+                })
+                .when_some(response_metrics, |parent, metrics| {
+                    parent.child(self.render_response_metrics(ix, message_id, &metrics, cx))
                 }),
             Role::System => div().id(("message-container", ix)).py_1().px_2().child(
                 v_flex()
@@ -2329,6 +2514,101 @@ impl ActiveThread {
             .into_any()
     }
 
+    fn render_response_metrics(
+        &self,
+        ix: usize,
+        message_id: MessageId,
+        metrics: &ResponseMetrics,
+        cx: &Context<Self>,
+    ) -> impl IntoElement {
+        let is_open = self.expanded_response_metrics.contains(&message_id);
+        let usage = metrics.token_usage;
+        let summary = format!(
+            "{} tokens · {}",
+            humanize_token_count(usage.total_tokens() as usize),
+            format_response_duration(metrics.duration)
+        );
+
+        let row = |label: &'static str, value: String| {
+            h_flex()
+                .gap_2()
+                .justify_between()
+                .child(
+                    Label::new(label)
+                        .size(LabelSize::XSmall)
+                        .color(Color::Muted),
+                )
+                .child(Label::new(value).size(LabelSize::XSmall))
+        };
+
+        v_flex()
+            .child(
+                h_flex()
+                    .id(("response-metrics", ix))
+                    .gap_1()
+                    .cursor_pointer()
+                    .opacity(0.6)
+                    .hover(|style| style.opacity(1.))
+                    .child(
+                        Label::new(summary)
+                            .size(LabelSize::XSmall)
+                            .color(Color::Muted),
+                    )
+                    .child(
+                        Icon::new(if is_open {
+                            IconName::ChevronUp
+                        } else {
+                            IconName::ChevronDown
+                        })
+                        .size(IconSize::XSmall)
+                        .color(Color::Muted),
+                    )
+                    .on_click(cx.listener(move |this, _, _window, cx| {
+                        if !this.expanded_response_metrics.remove(&message_id) {
+                            this.expanded_response_metrics.insert(message_id);
+                        }
+                        cx.notify();
+                    })),
+            )
+            .when(is_open, |parent| {
+                parent.child(
+                    v_flex()
+                        .mt_1()
+                        .p_2()
+                        .w(px(260.))
+                        .gap_0p5()
+                        .rounded_md()
+                        .border_1()
+                        .border_color(cx.theme().colors().border_variant)
+                        .child(row("Model", metrics.model.to_string()))
+                        .child(row("Input tokens", usage.input_tokens.to_string()))
+                        .child(row("Output tokens", usage.output_tokens.to_string()))
+                        .when(usage.cache_read_input_tokens > 0, |parent| {
+                            parent.child(row(
+                                "Cache read tokens",
+                                usage.cache_read_input_tokens.to_string(),
+                            ))
+                        })
+                        .when(usage.cache_creation_input_tokens > 0, |parent| {
+                            parent.child(row(
+                                "Cache write tokens",
+                                usage.cache_creation_input_tokens.to_string(),
+                            ))
+                        })
+                        .when_some(metrics.time_to_first_token, |parent, duration| {
+                            parent.child(row(
+                                "Time to first token",
+                                format_response_duration(duration),
+                            ))
+                        })
+                        .child(row(
+                            "Total duration",
+                            format_response_duration(metrics.duration),
+                        )),
+                )
+            })
+    }
+
     fn render_message_content(
         &self,
         message_id: MessageId,
@@ -3211,7 +3491,7 @@ impl ActiveThread {
         let rules_files = project_context
             .worktrees
             .iter()
-            .filter_map(|worktree| worktree.rules_file.as_ref())
+            .flat_map(|worktree| worktree.rules_files())
             .collect::<Vec<_>>();
 
         let rules_file_text = match rules_files.as_slice() {
@@ -3222,6 +3502,11 @@ impl ActiveThread {
             )),
             rules_files => Some(format!("Using {} project rules files", rules_files.len())),
         };
+        let rules_file_paths: SharedString = rules_files
+            .iter()
+            .map(|rules_file| rules_file.path_in_worktree.to_string_lossy())
+            .join("\n")
+            .into();
 
         if user_rules_text.is_none() && rules_file_text.is_none() {
             return div().into_any();
@@ -3290,7 +3575,15 @@ impl ActiveThread {
                                 .icon_size(IconSize::XSmall)
                                 .icon_color(Color::Ignored)
                                 .on_click(cx.listener(Self::handle_open_rules))
-                                .tooltip(Tooltip::text("View Rules")),
+                                .tooltip(move |window, cx| {
+                                    Tooltip::with_meta(
+                                        "View Rules",
+                                        None,
+                                        rules_file_paths.clone(),
+                                        window,
+                                        cx,
+                                    )
+                                }),
                         ),
                 )
             })
@@ -3351,7 +3644,7 @@ impl ActiveThread {
         let project_entry_ids = project_context
             .worktrees
             .iter()
-            .flat_map(|worktree| worktree.rules_file.as_ref())
+            .flat_map(|worktree| worktree.rules_files())
             .map(|rules_file| ProjectEntryId::from_usize(rules_file.project_entry_id))
             .collect::<Vec<_>>();
 
@@ -3374,6 +3667,40 @@ impl ActiveThread {
             .ok();
     }
 
+    /// Shows `thread` in the agent panel of its workspace, which may have moved
+    /// on to another thread since the notification was shown.
+    fn reveal_thread(
+        thread: Entity<Thread>,
+        workspace: WeakEntity<Workspace>,
+        open_diff: bool,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let Some(workspace) = workspace.upgrade() else {
+            return;
+        };
+        let Some(panel) = workspace.update(cx, |workspace, cx| {
+            workspace.focus_panel::<AgentPanel>(window, cx)
+        }) else {
+            return;
+        };
+        panel.update(cx, |panel, cx| {
+            if panel.active_thread() != Some(thread.clone()) {
+                panel.open_thread(thread.clone(), window, cx);
+            }
+        });
+
+        let has_changes = !thread
+            .read(cx)
+            .action_log()
+            .read(cx)
+            .changed_buffers(cx)
+            .is_empty();
+        if open_diff && has_changes {
+            AgentDiffPane::deploy(thread, workspace.downgrade(), window, cx).log_err();
+        }
+    }
+
     fn dismiss_notifications(&mut self, cx: &mut Context<ActiveThread>) {
         for window in self.notifications.drain(..) {
             window
@@ -3386,6 +3713,114 @@ impl ActiveThread {
         }
     }
 
+    fn render_comparison(&self, window: &Window, cx: &mut Context<Self>) -> Option<AnyElement> {
+        let responses = self
+            .thread
+            .read(cx)
+            .comparison()?
+            .responses()
+            .iter()
+            .zip(self.rendered_comparison.iter().cloned())
+            .map(|(response, markdown)| {
+                (
+                    response.model.model.name().0,
+                    response.status.clone(),
+                    markdown,
+                )
+            })
+            .collect::<Vec<_>>();
+        let colors = cx.theme().colors();
+
+        Some(
+            v_flex()
+                .flex_shrink_0()
+                .gap_2()
+                .p_2()
+                .border_t_1()
+                .border_color(colors.border)
+                .child(
+                    h_flex()
+                        .justify_between()
+                        .child(
+                            Label::new("Compare Responses")
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                        .child(
+                            IconButton::new("discard-comparison", IconName::Close)
+                                .icon_size(IconSize::Small)
+                                .icon_color(Color::Muted)
+                                .tooltip(Tooltip::text("Discard Both Responses"))
+                                .on_click(cx.listener(|this, _, _window, cx| {
+                                    this.thread
+                                        .update(cx, |thread, cx| thread.discard_comparison(cx));
+                                })),
+                        ),
+                )
+                .child(h_flex().items_start().gap_2().children(
+                    responses.into_iter().enumerate().map(
+                        |(ix, (model_name, status, markdown))| {
+                            let header_action = match status {
+                                ComparisonResponseStatus::Generating => {
+                                    AnimatedLabel::new("Generating")
+                                        .size(LabelSize::Small)
+                                        .into_any_element()
+                                }
+                                ComparisonResponseStatus::Done => Button::new(
+                                    ("choose-comparison-response", ix),
+                                    "Continue with This",
+                                )
+                                .label_size(LabelSize::Small)
+                                .on_click(cx.listener(move |this, _, _window, cx| {
+                                    this.thread.update(cx, |thread, cx| {
+                                        thread.choose_comparison_response(ix, cx);
+                                    });
+                                }))
+                                .into_any_element(),
+                                ComparisonResponseStatus::Failed(error) => Label::new(error)
+                                    .size(LabelSize::Small)
+                                    .color(Color::Error)
+                                    .into_any_element(),
+                            };
+
+                            v_flex()
+                                .flex_1()
+                                .min_w_0()
+                                .gap_1()
+                                .p_2()
+                                .rounded_md()
+                                .border_1()
+                                .border_color(colors.border_variant)
+                                .bg(colors.editor_background)
+                                .child(
+                                    h_flex()
+                                        .justify_between()
+                                        .gap_1()
+                                        .child(
+                                            Label::new(model_name)
+                                                .size(LabelSize::Small)
+                                                .truncate(),
+                                        )
+                                        .child(header_action),
+                                )
+                                .child(
+                                    div()
+                                        .id(("comparison-response", ix))
+                                        .max_h(rems(20.))
+                                        .overflow_y_scroll()
+                                        .text_ui(cx)
+                                        .child(MarkdownElement::new(
+                                            markdown,
+                                            default_markdown_style(window, cx),
+                                        )),
+                                )
+                        },
+                    ),
+                ))
+                .into_any_element(),
+        )
+    }
+
     fn render_vertical_scrollbar(&self, cx: &mut Context<Self>) -> Option<Stateful<Div>> {
         if !self.show_scrollbar && !self.scrollbar_state.is_dragging() {
             return None;
@@ -3470,7 +3905,9 @@ pub enum ActiveThreadEvent {
 impl EventEmitter<ActiveThreadEvent> for ActiveThread {}
 
 impl Render for ActiveThread {
-    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let comparison = self.render_comparison(window, cx);
+
         v_flex()
             .size_full()
             .relative()
@@ -3492,6 +3929,7 @@ impl Render for ActiveThread {
                 }),
             )
             .child(list(self.list_state.clone()).flex_grow())
+            .children(comparison)
             .when_some(self.render_vertical_scrollbar(cx), |this, scrollbar| {
                 this.child(scrollbar)
             })
@@ -3625,7 +4063,7 @@ pub(crate) fn open_context(
             cx,
         ),
 
-        AgentContextHandle::Image(_) => {}
+        AgentContextHandle::Image(_) | AgentContextHandle::ExtensionEntry(_) => {}
     }
 }
 
@@ -3661,6 +4099,14 @@ pub(crate) fn attach_pasted_images_as_context(
     true
 }
 
+fn format_response_duration(duration: Duration) -> String {
+    if duration < Duration::from_secs(1) {
+        format!("{}ms", duration.as_millis())
+    } else {
+        format!("{:.1}s", duration.as_secs_f64())
+    }
+}
+
 fn open_editor_at_position(
     project_path: project::ProjectPath,
     target_position: Point,
@@ -3708,6 +4154,22 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn test_format_response_duration() {
+        assert_eq!(
+            format_response_duration(Duration::from_millis(850)),
+            "850ms"
+        );
+        assert_eq!(
+            format_response_duration(Duration::from_millis(1000)),
+            "1.0s"
+        );
+        assert_eq!(
+            format_response_duration(Duration::from_millis(12_345)),
+            "12.3s"
+        );
+    }
+
     #[gpui::test]
     async fn test_agent_is_unfollowed_after_cancelling_completion(cx: &mut TestAppContext) {
         init_test_settings(cx);
@@ -5,24 +5,34 @@ mod agent_model_selector;
 mod agent_panel;
 mod buffer_codegen;
 mod context;
+mod context_gathering;
 mod context_picker;
+mod context_ranking;
 mod context_server_configuration;
 mod context_server_tool;
 mod context_store;
 mod context_strip;
 mod debug;
+mod dictation;
+mod eval_fixture;
 mod history_store;
 mod inline_assistant;
 mod inline_prompt_editor;
+mod memory_store;
 mod message_editor;
+mod playbook_picker;
 mod profile_selector;
+mod remember_tool;
+mod scheduled_tasks;
 mod slash_command_settings;
 mod terminal_codegen;
 mod terminal_inline_assistant;
 mod thread;
+mod thread_export;
 mod thread_history;
 mod thread_store;
 mod tool_compatibility;
+mod tool_metrics;
 mod tool_use;
 mod ui;
 
@@ -30,6 +40,7 @@ use std::sync::Arc;
 
 use agent_settings::{AgentProfileId, AgentSettings, LanguageModelSelection};
 use assistant_slash_command::SlashCommandRegistry;
+use assistant_tool::ToolRegistry;
 use client::Client;
 use feature_flags::FeatureFlagAppExt as _;
 use fs::Fs;
@@ -45,12 +56,16 @@ use settings::{Settings as _, SettingsStore};
 use thread::ThreadId;
 
 pub use crate::active_thread::ActiveThread;
-use crate::agent_configuration::{AddContextServerModal, ManageProfilesModal};
+use crate::agent_configuration::{
+    AddContextServerModal, ManageMemoriesModal, ManageProfilesModal, ToolMetricsModal,
+};
 pub use crate::agent_panel::{AgentPanel, ConcreteAssistantPanelDelegate};
 pub use crate::context::{ContextLoadResult, LoadedContext};
 pub use crate::inline_assistant::InlineAssistant;
+use crate::remember_tool::RememberTool;
 use crate::slash_command_settings::SlashCommandSettings;
 pub use crate::thread::{Message, MessageSegment, Thread, ThreadEvent};
+pub use crate::thread_export::ThreadExportFormat;
 pub use crate::thread_store::{SerializedThread, TextThreadStore, ThreadStore};
 pub use agent_diff::{AgentDiffPane, AgentDiffToolbar};
 pub use context_store::ContextStore;
@@ -86,12 +101,23 @@ actions!(
         Reject,
         RejectAll,
         KeepAll,
+        RequestChanges,
+        ToggleDiffFileList,
         Follow,
         ResetTrialUpsell,
         ResetTrialEndUpsell,
         ContinueThread,
         ContinueWithBurnMode,
         ToggleBurnMode,
+        OpenPlaybooks,
+        ExportThreadAsEvalFixture,
+        ManageMemories,
+        OpenToolMetrics,
+        ToggleCompareModels,
+        TogglePlanOnly,
+        ApplyPlan,
+        DiscardPlan,
+        ToggleDictation,
     ]
 );
 
@@ -115,11 +141,36 @@ impl ManageProfiles {
     }
 }
 
-impl_actions!(agent, [NewThread, ManageProfiles]);
+/// Saves the active thread to a file, for sharing outside of Zed.
+#[derive(PartialEq, Clone, Debug, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct ExportThread {
+    pub format: ThreadExportFormat,
+    /// Whether each tool call is collapsed into a summary line that can be expanded to show its
+    /// input and output.
+    pub collapse_tool_calls: bool,
+    /// Whether attached context and images are embedded in the export, rather than listed by
+    /// name and path.
+    pub inline_attachments: bool,
+}
+
+impl Default for ExportThread {
+    fn default() -> Self {
+        Self {
+            format: ThreadExportFormat::default(),
+            collapse_tool_calls: true,
+            inline_attachments: true,
+        }
+    }
+}
+
+impl_actions!(agent, [NewThread, ManageProfiles, ExportThread]);
 
 #[derive(Clone)]
 pub(crate) enum ModelUsageContext {
     Thread(Entity<Thread>),
+    /// The second model that answers each prompt when comparing models in a thread.
+    ComparisonModel(Entity<Thread>),
     InlineAssistant,
 }
 
@@ -127,6 +178,7 @@ impl ModelUsageContext {
     pub fn configured_model(&self, cx: &App) -> Option<ConfiguredModel> {
         match self {
             Self::Thread(thread) => thread.read(cx).configured_model(),
+            Self::ComparisonModel(thread) => thread.read(cx).comparison_model(),
             Self::InlineAssistant => {
                 LanguageModelRegistry::read_global(cx).inline_assistant_model()
             }
@@ -157,11 +209,13 @@ pub fn init(
         // Initializing the language model from the user settings messes with the eval, so we only initialize them when
         // we're not running inside of the eval.
         init_language_model_settings(cx);
+        context_ranking::init(paths::embeddings_dir().join("context-ranking"), cx);
     }
     assistant_slash_command::init(cx);
     thread_store::init(cx);
     agent_panel::init(cx);
     context_server_configuration::init(language_registry, cx);
+    context_picker::init(cx);
 
     register_slash_commands(cx);
     inline_assistant::init(
@@ -179,6 +233,10 @@ pub fn init(
     indexed_docs::init(cx);
     cx.observe_new(AddContextServerModal::register).detach();
     cx.observe_new(ManageProfilesModal::register).detach();
+    cx.observe_new(ManageMemoriesModal::register).detach();
+    cx.observe_new(ToolMetricsModal::register).detach();
+
+    ToolRegistry::default_global(cx).register_tool(RememberTool);
 }
 
 fn init_language_model_settings(cx: &mut App) {
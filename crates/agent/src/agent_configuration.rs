@@ -1,6 +1,8 @@
 mod add_context_server_modal;
 mod configure_context_server_modal;
+mod manage_memories_modal;
 mod manage_profiles_modal;
+mod tool_metrics_modal;
 mod tool_picker;
 
 use std::{sync::Arc, time::Duration};
@@ -12,24 +14,29 @@ use context_server::ContextServerId;
 use fs::Fs;
 use gpui::{
     Action, Animation, AnimationExt as _, AnyView, App, Entity, EventEmitter, FocusHandle,
-    Focusable, ScrollHandle, Subscription, pulsating_between,
+    Focusable, NotificationPermission, ScrollHandle, Subscription, pulsating_between,
 };
 use language_model::{LanguageModelProvider, LanguageModelProviderId, LanguageModelRegistry};
 use project::context_server_store::{ContextServerStatus, ContextServerStore};
 use settings::{Settings, update_settings_file};
 use ui::{
-    Disclosure, ElevationIndex, Indicator, Scrollbar, ScrollbarState, Switch, SwitchColor, Tooltip,
-    prelude::*,
+    Banner, Disclosure, ElevationIndex, Indicator, Scrollbar, ScrollbarState, Switch, SwitchColor,
+    Tooltip, prelude::*,
 };
 use util::ResultExt as _;
 use zed_actions::ExtensionCategoryFilter;
 
 pub(crate) use add_context_server_modal::AddContextServerModal;
 pub(crate) use configure_context_server_modal::ConfigureContextServerModal;
+pub(crate) use manage_memories_modal::ManageMemoriesModal;
 pub(crate) use manage_profiles_modal::ManageProfilesModal;
+pub(crate) use tool_metrics_modal::ToolMetricsModal;
 
 use crate::AddContextServer;
 
+const NOTIFICATION_SETTINGS_URL: &str =
+    "x-apple.systempreferences:com.apple.preference.notifications";
+
 pub struct AgentConfiguration {
     fs: Arc<dyn Fs>,
     focus_handle: FocusHandle,
@@ -38,6 +45,7 @@ pub struct AgentConfiguration {
     expanded_context_server_tools: HashMap<ContextServerId, bool>,
     expanded_provider_configurations: HashMap<LanguageModelProviderId, bool>,
     tools: Entity<ToolWorkingSet>,
+    notification_permission: Option<NotificationPermission>,
     _registry_subscription: Subscription,
     scroll_handle: ScrollHandle,
     scrollbar_state: ScrollbarState,
@@ -81,14 +89,28 @@ impl AgentConfiguration {
             expanded_context_server_tools: HashMap::default(),
             expanded_provider_configurations: HashMap::default(),
             tools,
+            notification_permission: None,
             _registry_subscription: registry_subscription,
             scroll_handle,
             scrollbar_state,
         };
         this.build_provider_configuration_views(window, cx);
+        this.refresh_notification_permission(cx);
         this
     }
 
+    fn refresh_notification_permission(&mut self, cx: &mut Context<Self>) {
+        let permission = cx.notification_permission();
+        cx.spawn(async move |this, cx| {
+            let permission = permission.await?;
+            this.update(cx, |this, cx| {
+                this.notification_permission = Some(permission);
+                cx.notify();
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
     fn build_provider_configuration_views(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         let providers = LanguageModelRegistry::read_global(cx).providers();
         for provider in providers {
@@ -365,6 +387,29 @@ impl AgentConfiguration {
             )
     }
 
+    fn render_notification_permission_banner(&self) -> Option<impl IntoElement> {
+        if self.notification_permission != Some(NotificationPermission::Denied) {
+            return None;
+        }
+
+        Some(
+            Banner::new()
+                .severity(ui::Severity::Warning)
+                .child(
+                    Label::new(
+                        "System notifications are turned off for Zed, so you won't be notified when the agent needs your input.",
+                    )
+                    .size(LabelSize::Small),
+                )
+                .action_slot(
+                    Button::new("open-notification-settings", "Open System Settings")
+                        .style(ButtonStyle::Tinted(ui::TintColor::Warning))
+                        .label_size(LabelSize::Small)
+                        .on_click(|_event, _window, cx| cx.open_url(NOTIFICATION_SETTINGS_URL)),
+                ),
+        )
+    }
+
     fn render_general_settings_section(&mut self, cx: &mut Context<Self>) -> impl IntoElement {
         v_flex()
             .p(DynamicSpacing::Base16.rems(cx))
@@ -376,6 +421,7 @@ impl AgentConfiguration {
             .child(self.render_command_permission(cx))
             .child(self.render_single_file_review(cx))
             .child(self.render_sound_notification(cx))
+            .children(self.render_notification_permission_banner())
     }
 
     fn render_context_servers_section(
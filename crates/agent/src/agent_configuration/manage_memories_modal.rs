@@ -0,0 +1,236 @@
+use editor::Editor;
+use gpui::{
+    DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, ScrollHandle, Subscription,
+    prelude::*,
+};
+use project::Project;
+use ui::{Modal, ModalHeader, Section, Tooltip, prelude::*};
+use workspace::{ModalView, Workspace};
+
+use crate::ManageMemories;
+use crate::memory_store::{MemoryId, MemoryStore, ProjectKey};
+
+struct EditingMemory {
+    id: MemoryId,
+    editor: Entity<Editor>,
+    error: Option<SharedString>,
+}
+
+/// Lets the user review, edit, and delete the facts the agent remembered about the current project.
+pub struct ManageMemoriesModal {
+    memory_store: Entity<MemoryStore>,
+    project_key: Option<ProjectKey>,
+    editing: Option<EditingMemory>,
+    focus_handle: FocusHandle,
+    scroll_handle: ScrollHandle,
+    _subscription: Subscription,
+}
+
+impl ManageMemoriesModal {
+    pub fn register(
+        workspace: &mut Workspace,
+        _window: Option<&mut Window>,
+        _cx: &mut Context<Workspace>,
+    ) {
+        workspace.register_action(|workspace, _: &ManageMemories, window, cx| {
+            let project = workspace.project().clone();
+            workspace.toggle_modal(window, cx, |window, cx| Self::new(project, window, cx))
+        });
+    }
+
+    pub fn new(project: Entity<Project>, _window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let memory_store = MemoryStore::global(cx);
+        let project_key = ProjectKey::for_project(&project, cx);
+        if let Some(project_key) = project_key.as_ref() {
+            // The store notifies when the memories finish loading, which re-renders the modal.
+            memory_store.update(cx, |store, cx| store.load(project_key, cx));
+        }
+        Self {
+            _subscription: cx.observe(&memory_store, |_, _, cx| cx.notify()),
+            memory_store,
+            project_key,
+            editing: None,
+            focus_handle: cx.focus_handle(),
+            scroll_handle: ScrollHandle::new(),
+        }
+    }
+
+    fn start_editing(
+        &mut self,
+        id: MemoryId,
+        text: String,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_text(text, window, cx);
+            editor
+        });
+        editor.focus_handle(cx).focus(window);
+        self.editing = Some(EditingMemory {
+            id,
+            editor,
+            error: None,
+        });
+        cx.notify();
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        let (Some(project_key), Some(editing)) = (self.project_key.as_ref(), self.editing.as_mut())
+        else {
+            return;
+        };
+        let text = editing.editor.read(cx).text(cx);
+        let result = self.memory_store.update(cx, |store, cx| {
+            store.edit(project_key, editing.id, &text, cx)
+        });
+        match result {
+            Ok(()) => {
+                self.editing = None;
+                self.focus_handle.focus(window);
+            }
+            Err(error) => editing.error = Some(error.to_string().into()),
+        }
+        cx.notify();
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, window: &mut Window, cx: &mut Context<Self>) {
+        if self.editing.take().is_some() {
+            self.focus_handle.focus(window);
+            cx.notify();
+        } else {
+            cx.emit(DismissEvent);
+        }
+    }
+
+    fn delete(&mut self, id: MemoryId, cx: &mut Context<Self>) {
+        let Some(project_key) = self.project_key.as_ref() else {
+            return;
+        };
+        if self
+            .editing
+            .as_ref()
+            .is_some_and(|editing| editing.id == id)
+        {
+            self.editing = None;
+        }
+        self.memory_store
+            .update(cx, |store, cx| store.delete(project_key, id, cx));
+    }
+
+    fn render_memories(&mut self, cx: &mut Context<Self>) -> impl IntoElement {
+        let memories = match self.project_key.as_ref() {
+            Some(project_key) => self.memory_store.read(cx).memories(project_key).to_vec(),
+            None => Vec::new(),
+        };
+
+        if memories.is_empty() {
+            let message = if self.project_key.is_some() {
+                "The agent hasn't remembered anything about this project yet. It remembers facts with the `remember` tool."
+            } else {
+                "Open a folder to see what the agent remembered about it."
+            };
+            return v_flex()
+                .child(Label::new(message).color(Color::Muted))
+                .into_any_element();
+        }
+
+        v_flex()
+            .id("memories")
+            .max_h(rems(24.))
+            .overflow_y_scroll()
+            .track_scroll(&self.scroll_handle)
+            .gap_1()
+            .children(memories.into_iter().enumerate().map(|(ix, memory)| {
+                let id = memory.id;
+                let editing = self.editing.as_ref().filter(|editing| editing.id == id);
+
+                h_flex()
+                    .id(("memory", ix))
+                    .w_full()
+                    .gap_2()
+                    .px_2()
+                    .py_1()
+                    .rounded_sm()
+                    .hover(|style| style.bg(cx.theme().colors().element_hover))
+                    .child(match editing {
+                        Some(editing) => v_flex()
+                            .flex_1()
+                            .gap_1()
+                            .child(editing.editor.clone())
+                            .children(editing.error.clone().map(|error| {
+                                Label::new(error).size(LabelSize::Small).color(Color::Error)
+                            }))
+                            .into_any_element(),
+                        None => div()
+                            .flex_1()
+                            .child(Label::new(memory.text.clone()))
+                            .into_any_element(),
+                    })
+                    .child(
+                        h_flex()
+                            .gap_1()
+                            .when(editing.is_none(), |this| {
+                                let text = memory.text.clone();
+                                this.child(
+                                    IconButton::new(("edit-memory", ix), IconName::Pencil)
+                                        .icon_size(IconSize::Small)
+                                        .icon_color(Color::Muted)
+                                        .tooltip(Tooltip::text("Edit Memory"))
+                                        .on_click(cx.listener(move |this, _, window, cx| {
+                                            this.start_editing(id, text.clone(), window, cx)
+                                        })),
+                                )
+                            })
+                            .child(
+                                IconButton::new(("delete-memory", ix), IconName::Trash)
+                                    .icon_size(IconSize::Small)
+                                    .icon_color(Color::Muted)
+                                    .tooltip(Tooltip::text("Delete Memory"))
+                                    .on_click(
+                                        cx.listener(move |this, _, _window, cx| {
+                                            this.delete(id, cx)
+                                        }),
+                                    ),
+                            ),
+                    )
+            }))
+            .into_any_element()
+    }
+}
+
+impl ModalView for ManageMemoriesModal {}
+
+impl Focusable for ManageMemoriesModal {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        match &self.editing {
+            Some(editing) => editing.editor.focus_handle(cx),
+            None => self.focus_handle.clone(),
+        }
+    }
+}
+
+impl EventEmitter<DismissEvent> for ManageMemoriesModal {}
+
+impl Render for ManageMemoriesModal {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .elevation_3(cx)
+            .w(rems(34.))
+            .key_context("ManageMemoriesModal")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .on_mouse_down_out(cx.listener(|_this, _, _, cx| cx.emit(DismissEvent)))
+            .child(
+                Modal::new("manage-memories", None)
+                    .header(ModalHeader::new().headline("Memories"))
+                    .section(
+                        Section::new()
+                            .meta("Facts the agent remembered about this project. They're included in every new thread.")
+                            .child(self.render_memories(cx)),
+                    ),
+            )
+    }
+}
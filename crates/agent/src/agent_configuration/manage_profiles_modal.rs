@@ -13,7 +13,8 @@ use gpui::{
 };
 use settings::{Settings as _, update_settings_file};
 use ui::{
-    KeyBinding, ListItem, ListItemSpacing, ListSeparator, Navigable, NavigableEntry, prelude::*,
+    KeyBinding, ListItem, ListItemSpacing, ListSeparator, Navigable, NavigableEntry, Switch,
+    prelude::*,
 };
 use util::ResultExt as _;
 use workspace::{ModalView, Workspace};
@@ -91,6 +92,7 @@ pub struct ViewProfileMode {
     fork_profile: NavigableEntry,
     configure_tools: NavigableEntry,
     configure_mcps: NavigableEntry,
+    gather_context: NavigableEntry,
     cancel_item: NavigableEntry,
 }
 
@@ -185,6 +187,7 @@ impl ManageProfilesModal {
             fork_profile: NavigableEntry::focusable(cx),
             configure_tools: NavigableEntry::focusable(cx),
             configure_mcps: NavigableEntry::focusable(cx),
+            gather_context: NavigableEntry::focusable(cx),
             cancel_item: NavigableEntry::focusable(cx),
         });
         self.focus_handle(cx).focus(window);
@@ -290,6 +293,10 @@ impl ManageProfilesModal {
                         .as_ref()
                         .map(|profile| profile.enable_all_context_servers)
                         .unwrap_or_default(),
+                    gather_context: base_profile
+                        .as_ref()
+                        .map(|profile| profile.gather_context)
+                        .unwrap_or_default(),
                     context_servers: base_profile
                         .map(|profile| profile.context_servers)
                         .unwrap_or_default(),
@@ -326,6 +333,20 @@ impl ManageProfilesModal {
         }
     }
 
+    fn toggle_gather_context(&mut self, profile_id: AgentProfileId, cx: &mut Context<Self>) {
+        let settings = AgentSettings::get_global(cx);
+        let Some(profile) = settings.profiles.get(&profile_id).cloned() else {
+            return;
+        };
+
+        let gather_context = !profile.gather_context;
+        update_settings_file::<AgentSettings>(self.fs.clone(), cx, move |settings, _cx| {
+            settings
+                .set_profile_gather_context(profile_id, profile, gather_context)
+                .log_err();
+        });
+    }
+
     fn create_profile(
         &self,
         profile_id: AgentProfileId,
@@ -521,11 +542,11 @@ impl ManageProfilesModal {
         let settings = AgentSettings::get_global(cx);
 
         let profile_id = &settings.default_profile;
-        let profile_name = settings
-            .profiles
-            .get(&mode.profile_id)
+        let profile = settings.profiles.get(&mode.profile_id);
+        let profile_name = profile
             .map(|profile| profile.name.clone())
             .unwrap_or_else(|| "Unknown".into());
+        let gather_context = profile.is_some_and(|profile| profile.gather_context);
 
         let icon = match profile_id.as_str() {
             "write" => IconName::Pencil,
@@ -657,6 +678,43 @@ impl ManageProfilesModal {
                                         }),
                                 ),
                         )
+                        .child(
+                            div()
+                                .id("gather-context")
+                                .track_focus(&mode.gather_context.focus_handle)
+                                .on_action({
+                                    let profile_id = mode.profile_id.clone();
+                                    cx.listener(move |this, _: &menu::Confirm, _window, cx| {
+                                        this.toggle_gather_context(profile_id.clone(), cx);
+                                    })
+                                })
+                                .child(
+                                    ListItem::new("gather-context-item")
+                                        .toggle_state(
+                                            mode.gather_context
+                                                .focus_handle
+                                                .contains_focused(window, cx),
+                                        )
+                                        .inset(true)
+                                        .spacing(ListItemSpacing::Sparse)
+                                        .start_slot(
+                                            Icon::new(IconName::MagnifyingGlass)
+                                                .size(IconSize::Small)
+                                                .color(Color::Muted),
+                                        )
+                                        .child(Label::new("Gather Context Automatically"))
+                                        .end_slot(Switch::new(
+                                            "gather-context-switch",
+                                            gather_context.into(),
+                                        ))
+                                        .on_click({
+                                            let profile_id = mode.profile_id.clone();
+                                            cx.listener(move |this, _, _window, cx| {
+                                                this.toggle_gather_context(profile_id.clone(), cx);
+                                            })
+                                        }),
+                                ),
+                        )
                         .child(ListSeparator)
                         .child(
                             div()
@@ -706,6 +764,7 @@ impl ManageProfilesModal {
         .entry(mode.fork_profile)
         .entry(mode.configure_tools)
         .entry(mode.configure_mcps)
+        .entry(mode.gather_context)
         .entry(mode.cancel_item)
     }
 }
@@ -0,0 +1,213 @@
+use std::time::Duration;
+
+use agent_settings::{AgentProfileId, AgentSettings};
+use gpui::{
+    DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, ScrollHandle, Subscription,
+    prelude::*,
+};
+use settings::Settings as _;
+use ui::{Modal, ModalHeader, Section, Table, ToggleButton, prelude::*};
+use workspace::{ModalView, Workspace};
+
+use crate::thread::ThreadId;
+use crate::tool_metrics::{ToolMetricsScope, ToolMetricsStore};
+use crate::{AgentPanel, OpenToolMetrics};
+
+/// Summarizes how often each tool ran, how long it took, and how often it failed
+/// or had its output truncated.
+pub struct ToolMetricsModal {
+    store: Entity<ToolMetricsStore>,
+    thread_id: Option<ThreadId>,
+    profile_id: AgentProfileId,
+    scope: ToolMetricsScope,
+    focus_handle: FocusHandle,
+    scroll_handle: ScrollHandle,
+    _subscription: Subscription,
+}
+
+impl ToolMetricsModal {
+    pub fn register(
+        workspace: &mut Workspace,
+        _window: Option<&mut Window>,
+        _cx: &mut Context<Workspace>,
+    ) {
+        workspace.register_action(|workspace, _: &OpenToolMetrics, window, cx| {
+            let thread_id = workspace
+                .panel::<AgentPanel>(cx)
+                .and_then(|panel| panel.read(cx).active_thread())
+                .map(|thread| thread.read(cx).id().clone());
+            workspace.toggle_modal(window, cx, |_window, cx| Self::new(thread_id, cx))
+        });
+    }
+
+    pub fn new(thread_id: Option<ThreadId>, cx: &mut Context<Self>) -> Self {
+        let store = ToolMetricsStore::global(cx);
+        let scope = match &thread_id {
+            Some(thread_id) => ToolMetricsScope::Thread(thread_id.clone()),
+            None => ToolMetricsScope::All,
+        };
+        Self {
+            _subscription: cx.observe(&store, |_, _, cx| cx.notify()),
+            store,
+            thread_id,
+            profile_id: AgentSettings::get_global(cx).default_profile.clone(),
+            scope,
+            focus_handle: cx.focus_handle(),
+            scroll_handle: ScrollHandle::new(),
+        }
+    }
+
+    fn set_scope(&mut self, scope: ToolMetricsScope, cx: &mut Context<Self>) {
+        self.scope = scope;
+        cx.notify();
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _window: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_scope_toggle(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let profile_name = AgentSettings::get_global(cx)
+            .profiles
+            .get(&self.profile_id)
+            .map(|profile| profile.name.clone())
+            .unwrap_or_else(|| self.profile_id.0.clone().into());
+
+        let mut scopes = vec![(ToolMetricsScope::All, SharedString::from("All Threads"))];
+        if let Some(thread_id) = &self.thread_id {
+            scopes.push((
+                ToolMetricsScope::Thread(thread_id.clone()),
+                "This Thread".into(),
+            ));
+        }
+        scopes.push((
+            ToolMetricsScope::Profile(self.profile_id.clone()),
+            format!("{profile_name} Profile").into(),
+        ));
+
+        let last_ix = scopes.len() - 1;
+        h_flex().children(scopes.into_iter().enumerate().map(|(ix, (scope, label))| {
+            let button = ToggleButton::new(("tool-metrics-scope", ix), label)
+                .style(ButtonStyle::Filled)
+                .size(ButtonSize::Compact)
+                .toggle_state(self.scope == scope)
+                .on_click(
+                    cx.listener(move |this, _, _window, cx| this.set_scope(scope.clone(), cx)),
+                );
+            if ix == 0 {
+                button.first()
+            } else if ix == last_ix {
+                button.last()
+            } else {
+                button.middle()
+            }
+        }))
+    }
+
+    fn render_summaries(&mut self, cx: &mut Context<Self>) -> impl IntoElement {
+        let summaries = self
+            .store
+            .update(cx, |store, _| store.summarize(&self.scope));
+
+        if summaries.is_empty() {
+            return v_flex()
+                .child(Label::new("No tools have run yet.").color(Color::Muted))
+                .into_any_element();
+        }
+
+        let rows = summaries
+            .into_iter()
+            .map(|summary| {
+                vec![
+                    SharedString::from(summary.tool_name.to_string()),
+                    summary.invocations.to_string().into(),
+                    format_duration(summary.mean_duration()).into(),
+                    format_duration(summary.max_duration).into(),
+                    format_duration(summary.total_duration).into(),
+                    format_rate(summary.failure_rate()).into(),
+                    format_rate(summary.truncation_rate()).into(),
+                ]
+            })
+            .collect::<Vec<_>>();
+
+        v_flex()
+            .id("tool-metrics")
+            .max_h(rems(24.))
+            .overflow_y_scroll()
+            .track_scroll(&self.scroll_handle)
+            .child(
+                Table::new(vec![
+                    "Tool",
+                    "Calls",
+                    "Mean",
+                    "Max",
+                    "Total",
+                    "Failed",
+                    "Truncated",
+                ])
+                .width(relative(1.))
+                .striped()
+                .rows(rows),
+            )
+            .into_any_element()
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    if duration < Duration::from_secs(1) {
+        format!("{}ms", duration.as_millis())
+    } else {
+        format!("{:.1}s", duration.as_secs_f32())
+    }
+}
+
+fn format_rate(rate: f32) -> String {
+    format!("{:.0}%", rate * 100.)
+}
+
+impl ModalView for ToolMetricsModal {}
+
+impl Focusable for ToolMetricsModal {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<DismissEvent> for ToolMetricsModal {}
+
+impl Render for ToolMetricsModal {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .elevation_3(cx)
+            .w(rems(44.))
+            .key_context("ToolMetricsModal")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::cancel))
+            .on_mouse_down_out(cx.listener(|_this, _, _, cx| cx.emit(DismissEvent)))
+            .child(
+                Modal::new("tool-metrics", None)
+                    .header(
+                        ModalHeader::new().headline("Tool Metrics").child(
+                            h_flex()
+                                .w_full()
+                                .justify_end()
+                                .gap_2()
+                                .child(self.render_scope_toggle(cx))
+                                .child(
+                                    Button::new("clear-tool-metrics", "Clear")
+                                        .style(ButtonStyle::Subtle)
+                                        .size(ButtonSize::Compact)
+                                        .on_click(cx.listener(|this, _, _window, cx| {
+                                            this.store.update(cx, |store, cx| store.clear(cx))
+                                        })),
+                                ),
+                        ),
+                    )
+                    .section(
+                        Section::new()
+                            .meta("How the agent's tools performed, slowest first. These metrics are only stored on this machine.")
+                            .child(self.render_summaries(cx)),
+                    ),
+            )
+    }
+}
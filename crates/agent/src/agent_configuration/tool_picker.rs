@@ -298,6 +298,7 @@ impl PickerDelegate for ToolPickerDelegate {
                                             )
                                         })
                                         .collect(),
+                                    gather_context: Some(default_profile.gather_context),
                                 });
 
                         if let Some(server_id) = server_id {
@@ -1,20 +1,31 @@
-use crate::{Keep, KeepAll, OpenAgentDiff, Reject, RejectAll, Thread, ThreadEvent};
+use crate::context::ContextLoadResult;
+use crate::{
+    ApplyPlan, DiscardPlan, Keep, KeepAll, OpenAgentDiff, Reject, RejectAll, RequestChanges,
+    Thread, ThreadEvent, ToggleDiffFileList,
+};
 use agent_settings::AgentSettings;
 use anyhow::Result;
-use buffer_diff::DiffHunkStatus;
+use assistant_tool::{PendingHunk, ReviewOutcome, ReviewState};
+use buffer_diff::{BufferDiff, DiffHunkStatus};
 use collections::{HashMap, HashSet};
 use editor::{
-    Direction, Editor, EditorEvent, EditorSettings, MultiBuffer, MultiBufferSnapshot, ToPoint,
+    Direction, DisplayPoint, Editor, EditorEvent, EditorSettings, MultiBuffer, MultiBufferSnapshot,
+    ToPoint,
     actions::{GoToHunk, GoToPreviousHunk},
+    display_map::{
+        BlockPlacement, BlockProperties, BlockStyle, CustomBlockId, DisplayRow, RenderBlock,
+    },
     scroll::Autoscroll,
 };
 use gpui::{
-    Action, Animation, AnimationExt, AnyElement, AnyView, App, AppContext, Empty, Entity,
-    EventEmitter, FocusHandle, Focusable, Global, SharedString, Subscription, Task, Transformation,
-    WeakEntity, Window, percentage, prelude::*,
+    Action, Animation, AnimationExt, AnyElement, AnyView, App, AppContext, DismissEvent, Empty,
+    Entity, EventEmitter, FocusHandle, Focusable, Global, SharedString, Subscription, Task,
+    Transformation, WeakEntity, Window, percentage, prelude::*,
 };
 
-use language::{Buffer, Capability, DiskState, OffsetRangeExt, Point};
+use language::{
+    Buffer, BufferId, Capability, DiffOptions, DiskState, LanguageScope, OffsetRangeExt, Point,
+};
 use language_model::StopReason;
 use multi_buffer::PathKey;
 use project::{Project, ProjectItem, ProjectPath};
@@ -22,19 +33,22 @@ use settings::{Settings, SettingsStore};
 use std::{
     any::{Any, TypeId},
     collections::hash_map::Entry,
+    hash::{DefaultHasher, Hash, Hasher},
     ops::Range,
+    path::PathBuf,
     sync::Arc,
     time::Duration,
 };
-use ui::{IconButtonShape, KeyBinding, Tooltip, prelude::*, vertical_divider};
-use util::ResultExt;
+use ui::{Checkbox, IconButtonShape, KeyBinding, Tooltip, prelude::*, vertical_divider};
+use util::{ResultExt, paths::PathMatcher};
 use workspace::{
-    Item, ItemHandle, ItemNavHistory, ToolbarItemEvent, ToolbarItemLocation, ToolbarItemView,
-    Workspace,
+    Item, ItemHandle, ItemNavHistory, ModalView, ToolbarItemEvent, ToolbarItemLocation,
+    ToolbarItemView, Workspace,
     item::{BreadcrumbText, ItemEvent, TabContentParams},
     searchable::SearchableItemHandle,
 };
 use zed_actions::assistant::ToggleFocus;
+use zed_llm_client::CompletionIntent;
 
 pub struct AgentDiffPane {
     multibuffer: Entity<MultiBuffer>,
@@ -43,9 +57,151 @@ pub struct AgentDiffPane {
     focus_handle: FocusHandle,
     workspace: WeakEntity<Workspace>,
     title: SharedString,
+    hunk_comments: Vec<HunkComment>,
+    changed_files: Vec<ChangedFile>,
+    /// The buffer version of each reviewed file at the time it was marked as reviewed,
+    /// so that further edits clear the review state.
+    reviewed_files: HashMap<PathKey, clock::Global>,
+    show_file_list: bool,
+    review_filter: ReviewFilter,
+    path_filter_editor: Entity<Editor>,
+    /// Restricts the diff to files whose paths match these globs.
+    path_filter: Option<PathMatcher>,
+    /// Word diffs of each hunk, keyed by a hash of the hunk's old and new text.
+    word_diffs: HashMap<u64, Arc<WordDiff>>,
+    compute_word_diffs_task: Option<Task<()>>,
+    /// Diffs of the accepted or rejected edits to each buffer, while one of those is shown.
+    reviewed_edit_diffs: HashMap<BufferId, Entity<BufferDiff>>,
+    _update_reviewed_edit_diffs: Vec<Task<()>>,
     _subscriptions: Vec<Subscription>,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ReviewFilter {
+    All,
+    Unreviewed,
+    Reviewed,
+    Accepted,
+    Rejected,
+}
+
+impl ReviewFilter {
+    fn label(&self) -> &'static str {
+        match self {
+            Self::All => "All",
+            Self::Unreviewed => "Unreviewed",
+            Self::Reviewed => "Reviewed",
+            Self::Accepted => "Accepted",
+            Self::Rejected => "Rejected",
+        }
+    }
+
+    /// The edits the filter shows in place of the pending changes, if any.
+    fn outcome(&self) -> Option<ReviewOutcome> {
+        match self {
+            Self::Accepted => Some(ReviewOutcome::Kept),
+            Self::Rejected => Some(ReviewOutcome::Rejected),
+            Self::All | Self::Unreviewed | Self::Reviewed => None,
+        }
+    }
+}
+
+struct ChangedFile {
+    path_key: PathKey,
+    buffer: Entity<Buffer>,
+    added_lines: u32,
+    removed_lines: u32,
+    /// The number of edits whose old text only approximately matched the file.
+    fuzzy_edit_count: usize,
+}
+
+/// Highlights edits whose old text only approximately matched the file, as they
+/// are the most likely to have landed in the wrong place.
+enum FuzzyAgentEdit {}
+
+/// Highlights the tokens that were removed within a hunk.
+enum AgentWordDeletion {}
+
+/// Highlights the tokens that were inserted within a hunk.
+enum AgentWordInsertion {}
+
+/// Planned edits are listed after the edits that were made, under their own path keys, since
+/// they're made to copies of the same files.
+const PLANNED_NAMESPACE: u32 = 2;
+
+/// When more files than this have changed, each file's excerpts are only built once it's
+/// scrolled near the viewport, since building hundreds of them up front stalls the UI.
+const MAX_EAGER_FILE_COUNT: usize = 50;
+
+/// How many deferred files on either side of the viewport have their excerpts built ahead of
+/// being scrolled into view.
+const DEFERRED_FILE_MARGIN: usize = 5;
+
+/// Used until the editor has been laid out and knows how many lines fit in the viewport.
+const DEFAULT_VISIBLE_LINE_COUNT: f32 = 40.;
+
+const MAX_WORD_DIFF_LEN: usize = 4096;
+const MAX_WORD_DIFF_LINE_COUNT: usize = 8;
+
+/// The tokens that changed within a hunk, as byte ranges relative to the start
+/// of the hunk's old and new text.
+#[derive(Debug, Default, PartialEq)]
+struct WordDiff {
+    deleted: Vec<Range<usize>>,
+    inserted: Vec<Range<usize>>,
+}
+
+fn word_diff(old_text: &str, new_text: &str, language_scope: Option<LanguageScope>) -> WordDiff {
+    let edits = language::text_diff_with_options(
+        old_text,
+        new_text,
+        DiffOptions {
+            language_scope,
+            max_word_diff_len: MAX_WORD_DIFF_LEN,
+            max_word_diff_line_count: MAX_WORD_DIFF_LINE_COUNT,
+        },
+    );
+
+    let mut word_diff = WordDiff::default();
+    let mut delta = 0isize;
+    for (old_range, replacement) in edits {
+        let new_start = (old_range.start as isize + delta) as usize;
+        let new_range = new_start..new_start + replacement.len();
+        delta += replacement.len() as isize - old_range.len() as isize;
+        if !old_range.is_empty() {
+            word_diff.deleted.push(old_range);
+        }
+        if !new_range.is_empty() {
+            word_diff.inserted.push(new_range);
+        }
+    }
+
+    // Hunks that are too large to diff by word are replaced wholesale, which the
+    // hunk's own highlighting already conveys.
+    if word_diff.deleted == [0..old_text.len()] && word_diff.inserted == [0..new_text.len()] {
+        return WordDiff::default();
+    }
+    word_diff
+}
+
+fn word_diff_key(old_text: &str, new_text: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    (old_text, new_text).hash(&mut hasher);
+    hasher.finish()
+}
+
+struct HunkComment {
+    range: Range<editor::Anchor>,
+    text: SharedString,
+    block_id: CustomBlockId,
+}
+
+/// Identifies a buffer's file in a [`ReviewState`].
+fn review_state_key(buffer: &Entity<Buffer>, cx: &App) -> Option<(u64, PathBuf)> {
+    let file = buffer.read(cx).file()?;
+    Some((file.worktree_id(cx).to_proto(), file.path().to_path_buf()))
+}
+
 impl AgentDiffPane {
     pub fn deploy(
         thread: Entity<Thread>,
@@ -88,27 +244,65 @@ impl AgentDiffPane {
         let multibuffer = cx.new(|_| MultiBuffer::new(Capability::ReadWrite));
 
         let project = thread.read(cx).project().clone();
+        let this = cx.weak_entity();
         let editor = cx.new(|cx| {
             let mut editor =
                 Editor::for_multibuffer(multibuffer.clone(), Some(project.clone()), window, cx);
             editor.disable_inline_diagnostics();
             editor.set_expand_all_diff_hunks(cx);
-            editor.set_render_diff_hunk_controls(diff_hunk_controls(&thread), cx);
+            editor.set_render_diff_hunk_controls(diff_hunk_controls(&thread, Some(this)), cx);
             editor.register_addon(AgentDiffAddon);
             editor
         });
 
+        let path_filter_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Filter paths, e.g. src/**/*.rs", cx);
+            editor
+        });
+
         let action_log = thread.read(cx).action_log().clone();
         let mut this = Self {
             _subscriptions: vec![
+                cx.subscribe_in(
+                    &path_filter_editor,
+                    window,
+                    |this, _editor, event: &EditorEvent, window, cx| {
+                        if let EditorEvent::BufferEdited = event {
+                            this.update_path_filter(window, cx);
+                        }
+                    },
+                ),
                 cx.observe_in(&action_log, window, |this, _action_log, window, cx| {
                     this.update_excerpts(window, cx)
                 }),
+                cx.subscribe_in(
+                    &editor,
+                    window,
+                    |this, _editor, event: &EditorEvent, window, cx| {
+                        if let EditorEvent::ScrollPositionChanged { .. } = event {
+                            if this.materialize_visible_excerpts(window, cx) {
+                                this.update_excerpts(window, cx);
+                            }
+                        }
+                    },
+                ),
                 cx.subscribe(&thread, |this, _thread, event, cx| {
                     this.handle_thread_event(event, cx)
                 }),
             ],
             title: SharedString::default(),
+            hunk_comments: Vec::new(),
+            changed_files: Vec::new(),
+            reviewed_files: HashMap::default(),
+            show_file_list: true,
+            review_filter: ReviewFilter::All,
+            path_filter_editor,
+            path_filter: None,
+            word_diffs: HashMap::default(),
+            compute_word_diffs_task: None,
+            reviewed_edit_diffs: HashMap::default(),
+            _update_reviewed_edit_diffs: Vec::new(),
             multibuffer,
             editor,
             thread,
@@ -122,8 +316,12 @@ impl AgentDiffPane {
 
     fn update_excerpts(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         let thread = self.thread.read(cx);
-        let changed_buffers = thread.action_log().read(cx).changed_buffers(cx);
+        let action_log = thread.action_log().clone();
+        let changed_buffers = action_log.read(cx).changed_buffers(cx);
+        let defer_excerpts = changed_buffers.len() > MAX_EAGER_FILE_COUNT;
         let mut paths_to_delete = self.multibuffer.read(cx).paths().collect::<HashSet<_>>();
+        let mut changed_files = Vec::new();
+        let mut fuzzy_edit_ranges = Vec::new();
 
         for (buffer, diff_handle) in changed_buffers {
             if buffer.read(cx).file().is_none() {
@@ -131,31 +329,97 @@ impl AgentDiffPane {
             }
 
             let path_key = PathKey::for_buffer(&buffer, cx);
-            paths_to_delete.remove(&path_key);
 
             let snapshot = buffer.read(cx).snapshot();
             let diff = diff_handle.read(cx);
 
-            let diff_hunk_ranges = diff
+            let diff_hunks = diff
                 .hunks_intersecting_range(
                     language::Anchor::MIN..language::Anchor::MAX,
                     &snapshot,
                     cx,
                 )
+                .collect::<Vec<_>>();
+            let diff_hunk_ranges = diff_hunks
+                .iter()
                 .map(|diff_hunk| diff_hunk.buffer_range.to_point(&snapshot))
                 .collect::<Vec<_>>();
 
+            let base_text = diff.base_text();
+            let (added_lines, removed_lines) =
+                diff_hunks
+                    .iter()
+                    .fold((0, 0), |(added_lines, removed_lines), hunk| {
+                        let removed_range = base_text
+                            .offset_to_point(hunk.diff_base_byte_range.start)
+                            ..base_text.offset_to_point(hunk.diff_base_byte_range.end);
+                        (
+                            added_lines + line_count(&hunk.range),
+                            removed_lines + line_count(&removed_range),
+                        )
+                    });
+            if self.reviewed_files.get(&path_key) != Some(snapshot.version()) {
+                self.reviewed_files.remove(&path_key);
+            }
+            let fuzzy_edits = action_log.read(cx).fuzzy_edits(&buffer).to_vec();
+            changed_files.push(ChangedFile {
+                path_key: path_key.clone(),
+                buffer: buffer.clone(),
+                added_lines,
+                removed_lines,
+                fuzzy_edit_count: fuzzy_edits.len(),
+            });
+
+            if self.review_filter.outcome().is_some()
+                || !self.matches_filter(&path_key, &buffer, cx)
+            {
+                continue;
+            }
+            paths_to_delete.remove(&path_key);
+            let is_reviewed = self.reviewed_files.contains_key(&path_key);
+
             let (was_empty, is_excerpt_newly_added) =
                 self.multibuffer.update(cx, |multibuffer, cx| {
                     let was_empty = multibuffer.is_empty();
-                    let (_, is_excerpt_newly_added) = multibuffer.set_excerpts_for_path(
-                        path_key.clone(),
-                        buffer.clone(),
-                        diff_hunk_ranges,
-                        editor::DEFAULT_MULTIBUFFER_CONTEXT,
-                        cx,
-                    );
+                    let (_, is_excerpt_newly_added) = if defer_excerpts {
+                        multibuffer.set_deferred_excerpts_for_path(
+                            path_key.clone(),
+                            buffer.clone(),
+                            diff_hunk_ranges,
+                            editor::DEFAULT_MULTIBUFFER_CONTEXT,
+                            cx,
+                        )
+                    } else {
+                        multibuffer.set_excerpts_for_path(
+                            path_key.clone(),
+                            buffer.clone(),
+                            diff_hunk_ranges,
+                            editor::DEFAULT_MULTIBUFFER_CONTEXT,
+                            cx,
+                        )
+                    };
                     multibuffer.add_diff(diff_handle, cx);
+
+                    let multibuffer_snapshot = multibuffer.snapshot(cx);
+                    for (excerpt_id, excerpt_range) in
+                        multibuffer.excerpts_for_buffer(snapshot.remote_id(), cx)
+                    {
+                        let context = excerpt_range.context;
+                        for range in &fuzzy_edits {
+                            if range.start.cmp(&context.end, &snapshot).is_gt()
+                                || range.end.cmp(&context.start, &snapshot).is_lt()
+                            {
+                                continue;
+                            }
+                            if let Some((start, end)) = multibuffer_snapshot
+                                .anchor_in_excerpt(excerpt_id, range.start)
+                                .zip(multibuffer_snapshot.anchor_in_excerpt(excerpt_id, range.end))
+                            {
+                                fuzzy_edit_ranges.push(start..end);
+                            }
+                        }
+                    }
+
                     (was_empty, is_excerpt_newly_added)
                 });
 
@@ -182,22 +446,85 @@ impl AgentDiffPane {
                     }
                 }
 
+                // Reviewed files are folded, including when they're shown again after
+                // being filtered out.
                 if is_excerpt_newly_added
-                    && buffer
-                        .read(cx)
-                        .file()
-                        .map_or(false, |file| file.disk_state() == DiskState::Deleted)
+                    && (is_reviewed
+                        || buffer
+                            .read(cx)
+                            .file()
+                            .map_or(false, |file| file.disk_state() == DiskState::Deleted))
                 {
                     editor.fold_buffer(snapshot.text.remote_id(), cx)
                 }
             });
         }
 
+        for planned_edit in action_log.read(cx).planned_edits(cx) {
+            let Some(file) = planned_edit.buffer.read(cx).file() else {
+                continue;
+            };
+            let path_key = PathKey::namespaced(PLANNED_NAMESPACE, file.full_path(cx).into());
+            if self.review_filter.outcome().is_some()
+                || !self.matches_filter(&path_key, &planned_edit.buffer, cx)
+            {
+                continue;
+            }
+            paths_to_delete.remove(&path_key);
+
+            let snapshot = planned_edit.planned_buffer.read(cx).snapshot();
+            let diff_hunk_ranges = planned_edit
+                .diff
+                .read(cx)
+                .hunks_intersecting_range(
+                    language::Anchor::MIN..language::Anchor::MAX,
+                    &snapshot,
+                    cx,
+                )
+                .map(|diff_hunk| diff_hunk.buffer_range.to_point(&snapshot))
+                .collect::<Vec<_>>();
+            self.multibuffer.update(cx, |multibuffer, cx| {
+                multibuffer.set_excerpts_for_path(
+                    path_key,
+                    planned_edit.planned_buffer,
+                    diff_hunk_ranges,
+                    editor::DEFAULT_MULTIBUFFER_CONTEXT,
+                    cx,
+                );
+                multibuffer.add_diff(planned_edit.diff, cx);
+            });
+        }
+
+        if let Some(outcome) = self.review_filter.outcome() {
+            self.update_reviewed_edit_excerpts(outcome, &mut paths_to_delete, cx);
+        } else {
+            self.reviewed_edit_diffs.clear();
+            self._update_reviewed_edit_diffs.clear();
+        }
+
         self.multibuffer.update(cx, |multibuffer, cx| {
             for path in paths_to_delete {
                 multibuffer.remove_excerpts_for_path(path, cx);
             }
         });
+        self.editor.update(cx, |editor, cx| {
+            editor.highlight_background::<FuzzyAgentEdit>(
+                &fuzzy_edit_ranges,
+                |colors| colors.version_control_modified.opacity(0.15),
+                cx,
+            );
+        });
+        self.reviewed_files.retain(|path_key, _| {
+            changed_files
+                .iter()
+                .any(|changed_file| &changed_file.path_key == path_key)
+        });
+        self.changed_files = changed_files;
+        if self.materialize_visible_excerpts(window, cx) {
+            self.update_excerpts(window, cx);
+            return;
+        }
+        self.update_word_diffs(cx);
 
         if self.multibuffer.read(cx).is_empty()
             && self
@@ -214,6 +541,166 @@ impl AgentDiffPane {
         }
     }
 
+    /// Builds the excerpts of deferred files that are in or near the viewport, returning
+    /// whether there were any.
+    fn materialize_visible_excerpts(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        if !self.multibuffer.read(cx).has_deferred_excerpts() {
+            return false;
+        }
+
+        let visible_range = self.editor.update(cx, |editor, cx| {
+            let visible_line_count = editor
+                .visible_line_count()
+                .unwrap_or(DEFAULT_VISIBLE_LINE_COUNT);
+            let snapshot = editor.snapshot(window, cx);
+            let max_row = snapshot.display_snapshot.max_point().row();
+            let top_row = snapshot.scroll_position().y.max(0.) as u32;
+            let bottom_row = (top_row + visible_line_count.ceil() as u32).min(max_row.0);
+            let start = DisplayPoint::new(DisplayRow(top_row.min(max_row.0)), 0)
+                .to_point(&snapshot.display_snapshot);
+            let end =
+                DisplayPoint::new(DisplayRow(bottom_row), 0).to_point(&snapshot.display_snapshot);
+            start..end
+        });
+        self.multibuffer.update(cx, |multibuffer, cx| {
+            multibuffer.materialize_excerpts_in_range(visible_range, DEFERRED_FILE_MARGIN, cx)
+        })
+    }
+
+    /// Highlights the tokens that changed within each hunk, so that small edits to
+    /// long lines stand out. Word diffs are computed in the background and cached.
+    fn update_word_diffs(&mut self, cx: &mut Context<Self>) {
+        let action_log = self.thread.read(cx).action_log().clone();
+        let multibuffer = self.multibuffer.read(cx);
+        let multibuffer_snapshot = multibuffer.snapshot(cx);
+        let mut used_keys = HashSet::default();
+        let mut missing_word_diffs = Vec::new();
+        let mut deleted_ranges = Vec::new();
+        let mut inserted_ranges = Vec::new();
+
+        for (buffer, diff) in action_log.read(cx).changed_buffers(cx) {
+            let snapshot = buffer.read(cx).snapshot();
+            let diff = diff.read(cx);
+            let base_text = diff.base_text();
+            let excerpts = multibuffer.excerpts_for_buffer(snapshot.remote_id(), cx);
+
+            for hunk in diff.hunks_intersecting_range(
+                language::Anchor::MIN..language::Anchor::MAX,
+                &snapshot,
+                cx,
+            ) {
+                let old_range = hunk.diff_base_byte_range.clone();
+                let new_range = hunk.buffer_range.to_offset(&snapshot);
+                if old_range.is_empty() || new_range.is_empty() {
+                    continue;
+                }
+
+                let old_text = base_text
+                    .text_for_range(old_range.clone())
+                    .collect::<String>();
+                let new_text = snapshot
+                    .text_for_range(new_range.clone())
+                    .collect::<String>();
+                let key = word_diff_key(&old_text, &new_text);
+                used_keys.insert(key);
+                let Some(word_diff) = self.word_diffs.get(&key) else {
+                    let language_scope = snapshot.language_scope_at(new_range.start);
+                    missing_word_diffs.push((key, old_text, new_text, language_scope));
+                    continue;
+                };
+
+                let Some(excerpt_id) = excerpts.iter().find_map(|(excerpt_id, range)| {
+                    let contains_hunk = range
+                        .context
+                        .start
+                        .cmp(&hunk.buffer_range.start, &snapshot)
+                        .is_le()
+                        && range
+                            .context
+                            .end
+                            .cmp(&hunk.buffer_range.start, &snapshot)
+                            .is_ge();
+                    contains_hunk.then_some(*excerpt_id)
+                }) else {
+                    continue;
+                };
+                let Some(hunk_start) =
+                    multibuffer_snapshot.anchor_in_excerpt(excerpt_id, hunk.buffer_range.start)
+                else {
+                    continue;
+                };
+
+                // Deleted text is only present in the multibuffer as part of the diff,
+                // so it's addressed by anchors into the base text.
+                for range in &word_diff.deleted {
+                    let start = base_text.anchor_after(old_range.start + range.start);
+                    let end = base_text.anchor_before(old_range.start + range.end);
+                    deleted_ranges.push(
+                        editor::Anchor {
+                            diff_base_anchor: Some(start),
+                            ..hunk_start
+                        }..editor::Anchor {
+                            diff_base_anchor: Some(end),
+                            ..hunk_start
+                        },
+                    );
+                }
+                for range in &word_diff.inserted {
+                    let start = snapshot.anchor_after(new_range.start + range.start);
+                    let end = snapshot.anchor_before(new_range.start + range.end);
+                    if let Some((start, end)) = multibuffer_snapshot
+                        .anchor_in_excerpt(excerpt_id, start)
+                        .zip(multibuffer_snapshot.anchor_in_excerpt(excerpt_id, end))
+                    {
+                        inserted_ranges.push(start..end);
+                    }
+                }
+            }
+        }
+
+        self.word_diffs.retain(|key, _| used_keys.contains(key));
+        self.editor.update(cx, |editor, cx| {
+            editor.highlight_background::<AgentWordDeletion>(
+                &deleted_ranges,
+                |colors| colors.version_control_deleted.opacity(0.3),
+                cx,
+            );
+            editor.highlight_background::<AgentWordInsertion>(
+                &inserted_ranges,
+                |colors| colors.version_control_added.opacity(0.3),
+                cx,
+            );
+        });
+
+        if missing_word_diffs.is_empty() {
+            return;
+        }
+        self.compute_word_diffs_task = Some(cx.spawn(async move |this, cx| {
+            let word_diffs = cx
+                .background_spawn(async move {
+                    missing_word_diffs
+                        .into_iter()
+                        .map(|(key, old_text, new_text, language_scope)| {
+                            (
+                                key,
+                                Arc::new(word_diff(&old_text, &new_text, language_scope)),
+                            )
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .await;
+            this.update(cx, |this, cx| {
+                this.word_diffs.extend(word_diffs);
+                this.update_word_diffs(cx);
+            })
+            .ok();
+        }));
+    }
+
     fn update_title(&mut self, cx: &mut Context<Self>) {
         let new_title = self.thread.read(cx).summary().unwrap_or("Agent Changes");
         if new_title != self.title {
@@ -249,80 +736,840 @@ impl AgentDiffPane {
         }
     }
 
-    fn keep(&mut self, _: &Keep, window: &mut Window, cx: &mut Context<Self>) {
-        self.editor.update(cx, |editor, cx| {
-            let snapshot = editor.buffer().read(cx).snapshot(cx);
-            keep_edits_in_selection(editor, &snapshot, &self.thread, window, cx);
-        });
+    fn toggle_file_list(
+        &mut self,
+        _: &ToggleDiffFileList,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.show_file_list = !self.show_file_list;
+        cx.notify();
     }
 
-    fn reject(&mut self, _: &Reject, window: &mut Window, cx: &mut Context<Self>) {
-        self.editor.update(cx, |editor, cx| {
-            let snapshot = editor.buffer().read(cx).snapshot(cx);
-            reject_edits_in_selection(editor, &snapshot, &self.thread, window, cx);
-        });
+    fn matches_filter(&self, path_key: &PathKey, buffer: &Entity<Buffer>, cx: &App) -> bool {
+        let is_reviewed = self.reviewed_files.contains_key(path_key);
+        let matches_review_filter = match self.review_filter {
+            ReviewFilter::All => true,
+            ReviewFilter::Unreviewed => !is_reviewed,
+            ReviewFilter::Reviewed => is_reviewed,
+            ReviewFilter::Accepted | ReviewFilter::Rejected => {
+                let action_log = self.thread.read(cx).action_log().read(cx);
+                action_log.reviewed_edits().any(|(reviewed_buffer, edits)| {
+                    reviewed_buffer == buffer
+                        && edits
+                            .iter()
+                            .any(|edit| Some(edit.outcome) == self.review_filter.outcome())
+                })
+            }
+        };
+        matches_review_filter && self.matches_path_filter(buffer, cx)
     }
 
-    fn reject_all(&mut self, _: &RejectAll, window: &mut Window, cx: &mut Context<Self>) {
-        self.editor.update(cx, |editor, cx| {
-            let snapshot = editor.buffer().read(cx).snapshot(cx);
-            reject_edits_in_ranges(
-                editor,
-                &snapshot,
-                &self.thread,
-                vec![editor::Anchor::min()..editor::Anchor::max()],
-                window,
-                cx,
-            );
-        });
+    fn matches_path_filter(&self, buffer: &Entity<Buffer>, cx: &App) -> bool {
+        self.path_filter.as_ref().map_or(true, |path_filter| {
+            buffer.read(cx).file().is_some_and(|file| {
+                path_filter.is_match(file.path()) || path_filter.is_match(file.full_path(cx))
+            })
+        })
     }
 
-    fn keep_all(&mut self, _: &KeepAll, _window: &mut Window, cx: &mut Context<Self>) {
-        self.thread
-            .update(cx, |thread, cx| thread.keep_all_edits(cx));
-    }
-}
+    /// Shows the edits the user accepted or rejected. Each file is diffed against its
+    /// contents with those decisions undone, so an accepted edit appears as the change the
+    /// agent made, and a rejected one as the change the rejection made.
+    fn update_reviewed_edit_excerpts(
+        &mut self,
+        outcome: ReviewOutcome,
+        paths_to_delete: &mut HashSet<PathKey>,
+        cx: &mut Context<Self>,
+    ) {
+        let reviewed_edits = self
+            .thread
+            .read(cx)
+            .action_log()
+            .read(cx)
+            .reviewed_edits()
+            .map(|(buffer, edits)| (buffer.clone(), edits.to_vec()))
+            .collect::<Vec<_>>();
+        let mut reviewed_edit_diffs = HashMap::default();
+        let mut update_diff_tasks = Vec::new();
+        for (buffer, edits) in &reviewed_edits {
+            if buffer.read(cx).file().is_none() || !self.matches_path_filter(buffer, cx) {
+                continue;
+            }
 
-fn keep_edits_in_selection(
-    editor: &mut Editor,
-    buffer_snapshot: &MultiBufferSnapshot,
-    thread: &Entity<Thread>,
-    window: &mut Window,
-    cx: &mut Context<Editor>,
-) {
-    let ranges = editor
-        .selections
-        .disjoint_anchor_ranges()
-        .collect::<Vec<_>>();
+            let snapshot = buffer.read(cx).snapshot();
+            let mut edits = edits
+                .iter()
+                .filter(|edit| edit.outcome == outcome)
+                .map(|edit| (edit.range.to_offset(&snapshot), edit.other_text.as_str()))
+                .collect::<Vec<_>>();
+            if edits.is_empty() {
+                continue;
+            }
+            edits.sort_by_key(|(range, _)| range.start);
+
+            let mut base_text = String::new();
+            let mut offset = 0;
+            for (range, other_text) in &edits {
+                // Later edits by the agent can overlap an earlier decision, in which case
+                // only the earliest one is undone.
+                if range.start < offset {
+                    continue;
+                }
+                base_text.extend(snapshot.text_for_range(offset..range.start));
+                base_text.push_str(other_text);
+                offset = range.end;
+            }
+            base_text.extend(snapshot.text_for_range(offset..snapshot.len()));
 
-    keep_edits_in_ranges(editor, buffer_snapshot, &thread, ranges, window, cx)
-}
+            let path_key = PathKey::for_buffer(buffer, cx);
+            paths_to_delete.remove(&path_key);
+            let buffer_id = snapshot.remote_id();
+            let diff = self
+                .reviewed_edit_diffs
+                .remove(&buffer_id)
+                .unwrap_or_else(|| cx.new(|cx| BufferDiff::new(&snapshot.text, cx)));
+            self.multibuffer.update(cx, |multibuffer, cx| {
+                multibuffer.set_excerpts_for_path(
+                    path_key,
+                    buffer.clone(),
+                    edits.iter().map(|(range, _)| range.to_point(&snapshot)),
+                    editor::DEFAULT_MULTIBUFFER_CONTEXT,
+                    cx,
+                );
+                multibuffer.add_diff(diff.clone(), cx);
+            });
 
-fn reject_edits_in_selection(
-    editor: &mut Editor,
-    buffer_snapshot: &MultiBufferSnapshot,
-    thread: &Entity<Thread>,
-    window: &mut Window,
-    cx: &mut Context<Editor>,
-) {
-    let ranges = editor
-        .selections
-        .disjoint_anchor_ranges()
-        .collect::<Vec<_>>();
-    reject_edits_in_ranges(editor, buffer_snapshot, &thread, ranges, window, cx)
-}
+            let language_registry = buffer.read(cx).language_registry();
+            update_diff_tasks.push(cx.spawn({
+                let diff = diff.clone();
+                async move |_, cx| {
+                    let text_snapshot = snapshot.text.clone();
+                    let Some(diff_snapshot) = BufferDiff::update_diff(
+                        diff.clone(),
+                        text_snapshot.clone(),
+                        Some(Arc::new(base_text)),
+                        true,
+                        false,
+                        snapshot.language().cloned(),
+                        language_registry,
+                        cx,
+                    )
+                    .await
+                    .log_err() else {
+                        return;
+                    };
+                    diff.update(cx, |diff, cx| {
+                        diff.set_snapshot(diff_snapshot, &text_snapshot, cx)
+                    })
+                    .log_err();
+                }
+            }));
+            reviewed_edit_diffs.insert(buffer_id, diff);
+        }
+        self.reviewed_edit_diffs = reviewed_edit_diffs;
+        self._update_reviewed_edit_diffs = update_diff_tasks;
+    }
 
-fn keep_edits_in_ranges(
-    editor: &mut Editor,
-    buffer_snapshot: &MultiBufferSnapshot,
-    thread: &Entity<Thread>,
-    ranges: Vec<Range<editor::Anchor>>,
-    window: &mut Window,
-    cx: &mut Context<Editor>,
-) {
-    let diff_hunks_in_ranges = editor
-        .diff_hunks_in_ranges(&ranges, buffer_snapshot)
-        .collect::<Vec<_>>();
+    fn set_review_filter(
+        &mut self,
+        review_filter: ReviewFilter,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.review_filter != review_filter {
+            self.review_filter = review_filter;
+            self.update_excerpts(window, cx);
+            cx.notify();
+        }
+    }
+
+    fn update_path_filter(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let query = self.path_filter_editor.read(cx).text(cx);
+        let globs = query
+            .split(',')
+            .map(str::trim)
+            .filter(|glob| !glob.is_empty())
+            .collect::<Vec<_>>();
+        // Leave the diff unfiltered while a glob is being typed and isn't valid yet.
+        self.path_filter = if globs.is_empty() {
+            None
+        } else {
+            PathMatcher::new(globs).ok()
+        };
+        self.update_excerpts(window, cx);
+        cx.notify();
+    }
+
+    fn toggle_file_reviewed(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(changed_file) = self.changed_files.get(ix) else {
+            return;
+        };
+        let buffer_id = changed_file.buffer.read(cx).remote_id();
+        let is_reviewed = self.reviewed_files.remove(&changed_file.path_key).is_some();
+        if !is_reviewed {
+            self.reviewed_files.insert(
+                changed_file.path_key.clone(),
+                changed_file.buffer.read(cx).version(),
+            );
+        }
+        self.editor.update(cx, |editor, cx| {
+            if is_reviewed {
+                editor.unfold_buffer(buffer_id, cx);
+            } else {
+                editor.fold_buffer(buffer_id, cx);
+            }
+        });
+        if self.review_filter != ReviewFilter::All {
+            self.update_excerpts(window, cx);
+        }
+        cx.notify();
+    }
+
+    fn render_filter_bar(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        h_flex()
+            .px_2()
+            .py_1()
+            .gap_2()
+            .w_full()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                h_flex().gap_0p5().children(
+                    [
+                        ReviewFilter::All,
+                        ReviewFilter::Unreviewed,
+                        ReviewFilter::Reviewed,
+                        ReviewFilter::Accepted,
+                        ReviewFilter::Rejected,
+                    ]
+                    .into_iter()
+                    .map(|review_filter| {
+                        Button::new(
+                            ("agent-diff-review-filter", review_filter as usize),
+                            review_filter.label(),
+                        )
+                        .label_size(LabelSize::Small)
+                        .toggle_state(self.review_filter == review_filter)
+                        .on_click(cx.listener(
+                            move |this, _, window, cx| {
+                                this.set_review_filter(review_filter, window, cx);
+                            },
+                        ))
+                    }),
+                ),
+            )
+            .child(
+                h_flex()
+                    .flex_1()
+                    .gap_1()
+                    .child(
+                        Icon::new(IconName::Filter)
+                            .size(IconSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .child(self.path_filter_editor.clone()),
+            )
+    }
+
+    fn render_file_list(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let (added_lines, removed_lines) = self
+            .changed_files
+            .iter()
+            .fold((0, 0), |(added, removed), file| {
+                (added + file.added_lines, removed + file.removed_lines)
+            });
+        let file_count = self.changed_files.len();
+        let visible_files = self
+            .changed_files
+            .iter()
+            .enumerate()
+            .filter(|(_, file)| self.matches_filter(&file.path_key, &file.buffer, cx))
+            .map(|(ix, _)| ix)
+            .collect::<Vec<_>>();
+
+        v_flex()
+            .h_full()
+            .w(rems(18.))
+            .flex_none()
+            .border_r_1()
+            .border_color(cx.theme().colors().border)
+            .bg(cx.theme().colors().panel_background)
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .gap_2()
+                    .justify_between()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(
+                        Label::new(format!(
+                            "{file_count} {} Changed",
+                            if file_count == 1 { "File" } else { "Files" }
+                        ))
+                        .size(LabelSize::Small),
+                    )
+                    .child(render_line_counts(added_lines, removed_lines)),
+            )
+            .child(
+                v_flex()
+                    .id("agent-diff-file-list")
+                    .flex_1()
+                    .overflow_y_scroll()
+                    .children(
+                        visible_files
+                            .into_iter()
+                            .map(|ix| self.render_changed_file(ix, &self.changed_files[ix], cx)),
+                    ),
+            )
+    }
+
+    fn render_changed_file(
+        &self,
+        ix: usize,
+        changed_file: &ChangedFile,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let is_reviewed = self.reviewed_files.contains_key(&changed_file.path_key);
+        let path = changed_file
+            .buffer
+            .read(cx)
+            .file()
+            .map(|file| file.path().clone());
+        let file_name = path
+            .as_ref()
+            .and_then(|path| path.file_name())
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| "untitled".to_string());
+        let directory = path
+            .as_ref()
+            .and_then(|path| path.parent())
+            .map(|parent| parent.to_string_lossy().to_string())
+            .filter(|parent| !parent.is_empty());
+
+        h_flex()
+            .id(("agent-diff-file", ix))
+            .px_2()
+            .py_0p5()
+            .gap_1p5()
+            .w_full()
+            .cursor_pointer()
+            .hover(|style| style.bg(cx.theme().colors().element_hover))
+            .child(
+                div()
+                    .id(("agent-diff-file-reviewed", ix))
+                    .flex_none()
+                    .child(
+                        Checkbox::new(("reviewed", ix), is_reviewed.into())
+                            .tooltip(Tooltip::text(if is_reviewed {
+                                "Mark as Not Reviewed"
+                            } else {
+                                "Mark as Reviewed"
+                            }))
+                            .on_click(cx.listener(move |this, _, window, cx| {
+                                this.toggle_file_reviewed(ix, window, cx);
+                                cx.stop_propagation();
+                            })),
+                    ),
+            )
+            .child(
+                h_flex()
+                    .flex_1()
+                    .min_w_0()
+                    .gap_1()
+                    .overflow_hidden()
+                    .child(
+                        Label::new(file_name)
+                            .size(LabelSize::Small)
+                            .when(is_reviewed, |label| label.color(Color::Muted)),
+                    )
+                    .when_some(directory, |el, directory| {
+                        el.child(
+                            Label::new(directory)
+                                .size(LabelSize::Small)
+                                .color(Color::Muted)
+                                .truncate(),
+                        )
+                    }),
+            )
+            .when(changed_file.fuzzy_edit_count > 0, |el| {
+                let tooltip = if changed_file.fuzzy_edit_count == 1 {
+                    "1 edit was applied with fuzzy matching".to_string()
+                } else {
+                    format!(
+                        "{} edits were applied with fuzzy matching",
+                        changed_file.fuzzy_edit_count
+                    )
+                };
+                el.child(
+                    div()
+                        .id(("agent-diff-file-fuzzy-edits", ix))
+                        .flex_none()
+                        .child(
+                            Icon::new(IconName::Warning)
+                                .size(IconSize::Small)
+                                .color(Color::Warning),
+                        )
+                        .tooltip(Tooltip::text(tooltip)),
+                )
+            })
+            .child(render_line_counts(
+                changed_file.added_lines,
+                changed_file.removed_lines,
+            ))
+            .on_click({
+                let path_key = changed_file.path_key.clone();
+                cx.listener(move |this, _, window, cx| {
+                    this.move_to_path(path_key.clone(), window, cx);
+                    this.editor.focus_handle(cx).focus(window);
+                })
+            })
+    }
+
+    /// Returns the agent's edits that haven't been kept or rejected yet.
+    pub fn pending_hunks(&self, cx: &App) -> Vec<PendingHunk> {
+        self.thread.read(cx).action_log().read(cx).pending_hunks(cx)
+    }
+
+    /// Keeps the hunks intersecting the given ranges of the diff.
+    pub fn keep_edits_in_ranges(
+        &mut self,
+        ranges: Vec<Range<editor::Anchor>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.editor.update(cx, |editor, cx| {
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            keep_edits_in_ranges(editor, &snapshot, &self.thread, ranges, window, cx);
+        });
+    }
+
+    /// Rejects the hunks intersecting the given ranges of the diff.
+    pub fn reject_edits_in_ranges(
+        &mut self,
+        ranges: Vec<Range<editor::Anchor>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.editor.update(cx, |editor, cx| {
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            reject_edits_in_ranges(editor, &snapshot, &self.thread, ranges, window, cx);
+        });
+    }
+
+    /// Returns the edits pending review, including which files the user marked as reviewed.
+    pub fn review_state(&self, cx: &App) -> ReviewState {
+        let reviewed_files = self
+            .changed_files
+            .iter()
+            .filter(|changed_file| self.reviewed_files.contains_key(&changed_file.path_key))
+            .filter_map(|changed_file| review_state_key(&changed_file.buffer, cx))
+            .collect::<HashSet<_>>();
+        let mut review_state = self.thread.read(cx).action_log().read(cx).review_state(cx);
+        for file in &mut review_state.files {
+            file.reviewed = reviewed_files.contains(&(file.worktree_id, file.path.clone()));
+        }
+        review_state
+    }
+
+    /// Marks the files that were reviewed in a state previously returned by
+    /// [`Self::review_state`] as reviewed again. Files whose pending edits changed since
+    /// then are left for the user to review.
+    pub fn restore_review_state(
+        &mut self,
+        review_state: &ReviewState,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let current_state = self.thread.read(cx).action_log().read(cx).review_state(cx);
+        for file in review_state.files.iter().filter(|file| file.reviewed) {
+            let is_unchanged = current_state.files.iter().any(|current_file| {
+                current_file.worktree_id == file.worktree_id
+                    && current_file.path == file.path
+                    && current_file.hunks == file.hunks
+            });
+            if !is_unchanged {
+                continue;
+            }
+            let key = (file.worktree_id, file.path.clone());
+            let Some(ix) = self.changed_files.iter().position(|changed_file| {
+                review_state_key(&changed_file.buffer, cx).as_ref() == Some(&key)
+            }) else {
+                continue;
+            };
+            if !self
+                .reviewed_files
+                .contains_key(&self.changed_files[ix].path_key)
+            {
+                self.toggle_file_reviewed(ix, window, cx);
+            }
+        }
+    }
+
+    fn keep(&mut self, _: &Keep, window: &mut Window, cx: &mut Context<Self>) {
+        self.editor.update(cx, |editor, cx| {
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            keep_edits_in_selection(editor, &snapshot, &self.thread, window, cx);
+        });
+    }
+
+    fn reject(&mut self, _: &Reject, window: &mut Window, cx: &mut Context<Self>) {
+        self.editor.update(cx, |editor, cx| {
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            reject_edits_in_selection(editor, &snapshot, &self.thread, window, cx);
+        });
+    }
+
+    fn reject_all(&mut self, _: &RejectAll, window: &mut Window, cx: &mut Context<Self>) {
+        self.editor.update(cx, |editor, cx| {
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            reject_edits_in_ranges(
+                editor,
+                &snapshot,
+                &self.thread,
+                vec![editor::Anchor::min()..editor::Anchor::max()],
+                window,
+                cx,
+            );
+        });
+    }
+
+    fn keep_all(&mut self, _: &KeepAll, _window: &mut Window, cx: &mut Context<Self>) {
+        self.thread
+            .update(cx, |thread, cx| thread.keep_all_edits(cx));
+    }
+
+    fn apply_plan(&mut self, _: &ApplyPlan, _window: &mut Window, cx: &mut Context<Self>) {
+        self.thread
+            .update(cx, |thread, cx| thread.apply_plan(cx))
+            .detach_and_log_err(cx);
+    }
+
+    fn discard_plan(&mut self, _: &DiscardPlan, _window: &mut Window, cx: &mut Context<Self>) {
+        self.thread.update(cx, |thread, cx| thread.discard_plan(cx));
+    }
+
+    fn hunk_comment_index(&self, hunk_range: &Range<editor::Anchor>, cx: &App) -> Option<usize> {
+        let snapshot = self.multibuffer.read(cx).snapshot(cx);
+        self.hunk_comments.iter().position(|comment| {
+            comment
+                .range
+                .start
+                .cmp(&hunk_range.start, &snapshot)
+                .is_eq()
+        })
+    }
+
+    fn has_hunk_comment(&self, hunk_range: &Range<editor::Anchor>, cx: &App) -> bool {
+        self.hunk_comment_index(hunk_range, cx).is_some()
+    }
+
+    fn edit_hunk_comment(
+        &mut self,
+        hunk_range: Range<editor::Anchor>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        let text = self
+            .hunk_comment_index(&hunk_range, cx)
+            .map(|ix| self.hunk_comments[ix].text.clone())
+            .unwrap_or_default();
+        let this = cx.weak_entity();
+        workspace.update(cx, |workspace, cx| {
+            workspace.toggle_modal(window, cx, |window, cx| {
+                HunkCommentModal::new(text, this, hunk_range, window, cx)
+            });
+        });
+    }
+
+    fn set_hunk_comment(
+        &mut self,
+        hunk_range: Range<editor::Anchor>,
+        text: String,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(ix) = self.hunk_comment_index(&hunk_range, cx) {
+            let comment = self.hunk_comments.remove(ix);
+            self.editor.update(cx, |editor, cx| {
+                editor.remove_blocks(HashSet::from_iter([comment.block_id]), None, cx)
+            });
+        }
+
+        let text = text.trim();
+        if !text.is_empty() {
+            let text = SharedString::from(text.to_string());
+            let block_id = self.editor.update(cx, |editor, cx| {
+                let block = BlockProperties {
+                    placement: BlockPlacement::Above(hunk_range.end),
+                    height: Some(text.lines().count() as u32 + 1),
+                    style: BlockStyle::Flex,
+                    render: render_hunk_comment(text.clone()),
+                    priority: 0,
+                    render_in_minimap: false,
+                };
+                editor.insert_blocks([block], None, cx)
+            });
+            if let Some(block_id) = block_id.into_iter().next() {
+                self.hunk_comments.push(HunkComment {
+                    range: hunk_range,
+                    text,
+                    block_id,
+                });
+            }
+        }
+        cx.notify();
+    }
+
+    fn clear_hunk_comments(&mut self, cx: &mut Context<Self>) {
+        let block_ids = self
+            .hunk_comments
+            .drain(..)
+            .map(|comment| comment.block_id)
+            .collect::<HashSet<_>>();
+        self.editor
+            .update(cx, |editor, cx| editor.remove_blocks(block_ids, None, cx));
+        cx.notify();
+    }
+
+    fn hunk_comments_message(&self, cx: &App) -> String {
+        let snapshot = self.multibuffer.read(cx).snapshot(cx);
+        let mut message = String::from(
+            "I reviewed your changes and left comments on some of them. \
+            Please address each comment:\n",
+        );
+        for comment in &self.hunk_comments {
+            // Resolve against the edited buffer rather than the multibuffer, since
+            // expanded hunks interleave deleted base text with the current contents.
+            let Some(buffer) = snapshot.buffer_for_excerpt(comment.range.start.excerpt_id) else {
+                continue;
+            };
+            let start = buffer.summary_for_anchor::<Point>(&comment.range.start.text_anchor);
+            let mut end = buffer
+                .summary_for_anchor::<Point>(&comment.range.end.text_anchor)
+                .max(start);
+            if end.column == 0 && end.row > start.row {
+                end = Point::new(end.row - 1, buffer.line_len(end.row - 1));
+            }
+            let path = buffer
+                .file()
+                .map(|file| file.path().to_string_lossy().to_string())
+                .unwrap_or_else(|| "untitled".to_string());
+            let hunk_text = buffer.text_for_range(start..end).collect::<String>();
+
+            message.push_str(&format!(
+                "\n## `{path}` (lines {}-{})\n\n```\n{}\n```\n\n{}\n",
+                start.row + 1,
+                end.row + 1,
+                hunk_text,
+                comment.text
+            ));
+        }
+        message
+    }
+
+    fn request_changes(&mut self, _: &RequestChanges, window: &mut Window, cx: &mut Context<Self>) {
+        if self.hunk_comments.is_empty() || self.thread.read(cx).is_generating() {
+            return;
+        }
+        let Some(model) = self
+            .thread
+            .update(cx, |thread, cx| thread.get_or_init_configured_model(cx))
+        else {
+            return;
+        };
+
+        let message = self.hunk_comments_message(cx);
+        self.clear_hunk_comments(cx);
+
+        let window_handle = window.window_handle();
+        self.thread.update(cx, |thread, cx| {
+            thread.insert_user_message(message, ContextLoadResult::default(), None, Vec::new(), cx);
+            thread.advance_prompt_id();
+            thread.send_to_model(
+                model.model,
+                CompletionIntent::UserPrompt,
+                Some(window_handle),
+                cx,
+            );
+        });
+        window.dispatch_action(ToggleFocus.boxed_clone(), cx);
+    }
+}
+
+fn line_count(range: &Range<Point>) -> u32 {
+    range.end.row - range.start.row + (range.end.column > 0) as u32
+}
+
+fn render_line_counts(added_lines: u32, removed_lines: u32) -> impl IntoElement {
+    h_flex()
+        .flex_none()
+        .gap_1()
+        .child(
+            Label::new(format!("+{added_lines}"))
+                .size(LabelSize::Small)
+                .color(Color::Created),
+        )
+        .child(
+            Label::new(format!("-{removed_lines}"))
+                .size(LabelSize::Small)
+                .color(Color::Deleted),
+        )
+}
+
+fn render_hunk_comment(text: SharedString) -> RenderBlock {
+    Arc::new(move |cx| {
+        h_flex()
+            .id(cx.block_id)
+            .block_mouse_except_scroll()
+            .pl(cx.margins.gutter.full_width())
+            .py_1()
+            .gap_2()
+            .items_start()
+            .w_full()
+            .border_y_1()
+            .border_color(cx.theme().colors().border_variant)
+            .bg(cx.theme().colors().editor_subheader_background)
+            .child(
+                Icon::new(IconName::MessageBubbles)
+                    .size(IconSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(Label::new(text.clone()).size(LabelSize::Small))
+            .into_any_element()
+    })
+}
+
+struct HunkCommentModal {
+    editor: Entity<Editor>,
+    agent_diff: WeakEntity<AgentDiffPane>,
+    hunk_range: Range<editor::Anchor>,
+}
+
+impl EventEmitter<DismissEvent> for HunkCommentModal {}
+impl ModalView for HunkCommentModal {}
+
+impl Focusable for HunkCommentModal {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.editor.focus_handle(cx)
+    }
+}
+
+impl HunkCommentModal {
+    fn new(
+        text: SharedString,
+        agent_diff: WeakEntity<AgentDiffPane>,
+        hunk_range: Range<editor::Anchor>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Leave a comment for the agent…", cx);
+            editor.set_text(text, window, cx);
+            editor.select_all(&editor::actions::SelectAll, window, cx);
+            editor
+        });
+        Self {
+            editor,
+            agent_diff,
+            hunk_range,
+        }
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _window: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, _window: &mut Window, cx: &mut Context<Self>) {
+        let text = self.editor.read(cx).text(cx);
+        let hunk_range = self.hunk_range.clone();
+        self.agent_diff
+            .update(cx, |agent_diff, cx| {
+                agent_diff.set_hunk_comment(hunk_range, text, cx)
+            })
+            .log_err();
+        cx.emit(DismissEvent);
+    }
+}
+
+impl Render for HunkCommentModal {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("HunkCommentModal")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .elevation_2(cx)
+            .w(rems(34.))
+            .child(
+                h_flex()
+                    .px_3()
+                    .pt_2()
+                    .pb_1()
+                    .gap_1p5()
+                    .child(
+                        Icon::new(IconName::MessageBubbles)
+                            .size(IconSize::XSmall)
+                            .color(Color::Muted),
+                    )
+                    .child(Headline::new("Comment on Hunk").size(HeadlineSize::XSmall)),
+            )
+            .child(
+                div()
+                    .py_2()
+                    .px_3()
+                    .bg(cx.theme().colors().editor_background)
+                    .border_t_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .rounded_b_md()
+                    .child(self.editor.clone()),
+            )
+    }
+}
+
+fn keep_edits_in_selection(
+    editor: &mut Editor,
+    buffer_snapshot: &MultiBufferSnapshot,
+    thread: &Entity<Thread>,
+    window: &mut Window,
+    cx: &mut Context<Editor>,
+) {
+    let ranges = editor
+        .selections
+        .disjoint_anchor_ranges()
+        .collect::<Vec<_>>();
+
+    keep_edits_in_ranges(editor, buffer_snapshot, &thread, ranges, window, cx)
+}
+
+fn reject_edits_in_selection(
+    editor: &mut Editor,
+    buffer_snapshot: &MultiBufferSnapshot,
+    thread: &Entity<Thread>,
+    window: &mut Window,
+    cx: &mut Context<Editor>,
+) {
+    let ranges = editor
+        .selections
+        .disjoint_anchor_ranges()
+        .collect::<Vec<_>>();
+    reject_edits_in_ranges(editor, buffer_snapshot, &thread, ranges, window, cx)
+}
+
+fn keep_edits_in_ranges(
+    editor: &mut Editor,
+    buffer_snapshot: &MultiBufferSnapshot,
+    thread: &Entity<Thread>,
+    ranges: Vec<Range<editor::Anchor>>,
+    window: &mut Window,
+    cx: &mut Context<Editor>,
+) {
+    let diff_hunks_in_ranges = editor
+        .diff_hunks_in_ranges(&ranges, buffer_snapshot)
+        .collect::<Vec<_>>();
 
     update_editor_selection(editor, buffer_snapshot, &diff_hunks_in_ranges, window, cx);
 
@@ -600,7 +1847,16 @@ impl Item for AgentDiffPane {
 
 impl Render for AgentDiffPane {
     fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
-        let is_empty = self.multibuffer.read(cx).is_empty();
+        // A filter that excludes every file shouldn't hide the filter bar.
+        let is_empty = self.changed_files.is_empty()
+            && self
+                .thread
+                .read(cx)
+                .action_log()
+                .read(cx)
+                .reviewed_edits()
+                .next()
+                .is_none();
         let focus_handle = &self.focus_handle;
 
         div()
@@ -610,6 +1866,10 @@ impl Render for AgentDiffPane {
             .on_action(cx.listener(Self::reject))
             .on_action(cx.listener(Self::reject_all))
             .on_action(cx.listener(Self::keep_all))
+            .on_action(cx.listener(Self::apply_plan))
+            .on_action(cx.listener(Self::discard_plan))
+            .on_action(cx.listener(Self::request_changes))
+            .on_action(cx.listener(Self::toggle_file_list))
             .bg(cx.theme().colors().editor_background)
             .flex()
             .items_center()
@@ -641,11 +1901,25 @@ impl Render for AgentDiffPane {
                         ),
                 )
             })
-            .when(!is_empty, |el| el.child(self.editor.clone()))
+            .when(!is_empty, |el| {
+                el.when(self.show_file_list, |el| {
+                    el.child(self.render_file_list(cx))
+                })
+                .child(
+                    v_flex()
+                        .flex_1()
+                        .size_full()
+                        .child(self.render_filter_bar(cx))
+                        .child(div().flex_1().size_full().child(self.editor.clone())),
+                )
+            })
     }
 }
 
-fn diff_hunk_controls(thread: &Entity<Thread>) -> editor::RenderDiffHunkControlsFn {
+fn diff_hunk_controls(
+    thread: &Entity<Thread>,
+    agent_diff: Option<WeakEntity<AgentDiffPane>>,
+) -> editor::RenderDiffHunkControlsFn {
     let thread = thread.clone();
 
     Arc::new(
@@ -665,6 +1939,7 @@ fn diff_hunk_controls(thread: &Entity<Thread>) -> editor::RenderDiffHunkControls
                     is_created_file,
                     line_height,
                     &thread,
+                    agent_diff.as_ref(),
                     editor,
                     window,
                     cx,
@@ -681,11 +1956,23 @@ fn render_diff_hunk_controls(
     is_created_file: bool,
     line_height: Pixels,
     thread: &Entity<Thread>,
+    agent_diff: Option<&WeakEntity<AgentDiffPane>>,
     editor: &Entity<Editor>,
     window: &mut Window,
     cx: &mut App,
 ) -> AnyElement {
     let editor = editor.clone();
+    let agent_diff = agent_diff.and_then(|agent_diff| agent_diff.upgrade());
+    // Planned edits can only be applied or discarded as a whole.
+    let is_planned = hunk_range.start.buffer_id.map_or(false, |buffer_id| {
+        thread
+            .read(cx)
+            .action_log()
+            .read(cx)
+            .planned_edits(cx)
+            .iter()
+            .any(|planned_edit| planned_edit.planned_buffer.read(cx).remote_id() == buffer_id)
+    });
 
     h_flex()
         .h(line_height)
@@ -701,58 +1988,96 @@ fn render_diff_hunk_controls(
         .gap_1()
         .block_mouse_except_scroll()
         .shadow_md()
-        .children(vec![
-            Button::new(("reject", row as u64), "Reject")
-                .disabled(is_created_file)
-                .key_binding(
-                    KeyBinding::for_action_in(
-                        &Reject,
-                        &editor.read(cx).focus_handle(cx),
-                        window,
-                        cx,
+        .when_some(agent_diff, |el, agent_diff| {
+            let has_comment = agent_diff.read(cx).has_hunk_comment(&hunk_range, cx);
+            el.child(
+                IconButton::new(("comment", row as u64), IconName::MessageBubbles)
+                    .shape(IconButtonShape::Square)
+                    .icon_size(IconSize::Small)
+                    .toggle_state(has_comment)
+                    .tooltip(Tooltip::text(if has_comment {
+                        "Edit Comment"
+                    } else {
+                        "Comment on Hunk"
+                    }))
+                    .on_click({
+                        let hunk_range = hunk_range.clone();
+                        move |_event, window, cx| {
+                            agent_diff.update(cx, |agent_diff, cx| {
+                                agent_diff.edit_hunk_comment(hunk_range.clone(), window, cx)
+                            });
+                        }
+                    }),
+            )
+        })
+        .when(is_planned, |el| {
+            el.child(
+                div().px_1().child(
+                    Label::new("Proposed")
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                ),
+            )
+        })
+        .when(!is_planned, |el| {
+            el.children(vec![
+                Button::new(("reject", row as u64), "Reject")
+                    .disabled(is_created_file)
+                    .key_binding(
+                        KeyBinding::for_action_in(
+                            &Reject,
+                            &editor.read(cx).focus_handle(cx),
+                            window,
+                            cx,
+                        )
+                        .map(|kb| kb.size(rems_from_px(12.))),
                     )
-                    .map(|kb| kb.size(rems_from_px(12.))),
-                )
-                .on_click({
-                    let editor = editor.clone();
-                    let thread = thread.clone();
-                    move |_event, window, cx| {
-                        editor.update(cx, |editor, cx| {
-                            let snapshot = editor.buffer().read(cx).snapshot(cx);
-                            reject_edits_in_ranges(
-                                editor,
-                                &snapshot,
-                                &thread,
-                                vec![hunk_range.start..hunk_range.start],
-                                window,
-                                cx,
-                            );
-                        })
-                    }
-                }),
-            Button::new(("keep", row as u64), "Keep")
-                .key_binding(
-                    KeyBinding::for_action_in(&Keep, &editor.read(cx).focus_handle(cx), window, cx)
+                    .on_click({
+                        let editor = editor.clone();
+                        let thread = thread.clone();
+                        move |_event, window, cx| {
+                            editor.update(cx, |editor, cx| {
+                                let snapshot = editor.buffer().read(cx).snapshot(cx);
+                                reject_edits_in_ranges(
+                                    editor,
+                                    &snapshot,
+                                    &thread,
+                                    vec![hunk_range.start..hunk_range.start],
+                                    window,
+                                    cx,
+                                );
+                            })
+                        }
+                    }),
+                Button::new(("keep", row as u64), "Keep")
+                    .key_binding(
+                        KeyBinding::for_action_in(
+                            &Keep,
+                            &editor.read(cx).focus_handle(cx),
+                            window,
+                            cx,
+                        )
                         .map(|kb| kb.size(rems_from_px(12.))),
-                )
-                .on_click({
-                    let editor = editor.clone();
-                    let thread = thread.clone();
-                    move |_event, window, cx| {
-                        editor.update(cx, |editor, cx| {
-                            let snapshot = editor.buffer().read(cx).snapshot(cx);
-                            keep_edits_in_ranges(
-                                editor,
-                                &snapshot,
-                                &thread,
-                                vec![hunk_range.start..hunk_range.start],
-                                window,
-                                cx,
-                            );
-                        });
-                    }
-                }),
-        ])
+                    )
+                    .on_click({
+                        let editor = editor.clone();
+                        let thread = thread.clone();
+                        move |_event, window, cx| {
+                            editor.update(cx, |editor, cx| {
+                                let snapshot = editor.buffer().read(cx).snapshot(cx);
+                                keep_edits_in_ranges(
+                                    editor,
+                                    &snapshot,
+                                    &thread,
+                                    vec![hunk_range.start..hunk_range.start],
+                                    window,
+                                    cx,
+                                );
+                            });
+                        }
+                    }),
+            ])
+        })
         .when(
             !editor.read(cx).buffer().read(cx).all_diff_hunks_expanded(),
             |el| {
@@ -1132,12 +2457,39 @@ impl Render for AgentDiffToolbar {
                 }
 
                 let focus_handle = agent_diff.focus_handle(cx);
+                let comment_count = agent_diff.read(cx).hunk_comments.len();
+                let show_file_list = agent_diff.read(cx).show_file_list;
+                let has_planned_edits = agent_diff
+                    .read(cx)
+                    .thread
+                    .read(cx)
+                    .action_log()
+                    .read(cx)
+                    .has_planned_edits(cx);
 
                 h_group_xl()
                     .my_neg_1()
                     .py_1()
                     .items_center()
                     .flex_wrap()
+                    .child(
+                        IconButton::new("toggle-file-list", IconName::ListTree)
+                            .icon_size(IconSize::Small)
+                            .toggle_state(show_file_list)
+                            .tooltip(Tooltip::for_action_title_in(
+                                if show_file_list {
+                                    "Hide File List"
+                                } else {
+                                    "Show File List"
+                                },
+                                &ToggleDiffFileList,
+                                &focus_handle,
+                            ))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.dispatch_action(&ToggleDiffFileList, window, cx)
+                            })),
+                    )
+                    .child(vertical_divider())
                     .child(
                         h_group_sm()
                             .child(
@@ -1171,6 +2523,53 @@ impl Render for AgentDiffToolbar {
                                     })),
                             ),
                     )
+                    .when(has_planned_edits, |el| {
+                        el.child(vertical_divider()).child(
+                            h_group_sm()
+                                .child(Button::new("discard-plan", "Discard Plan").on_click(
+                                    cx.listener(|this, _, window, cx| {
+                                        this.dispatch_action(&DiscardPlan, window, cx)
+                                    }),
+                                ))
+                                .child(
+                                    Button::new("apply-plan", "Apply Plan")
+                                        .key_binding({
+                                            KeyBinding::for_action_in(
+                                                &ApplyPlan,
+                                                &focus_handle,
+                                                window,
+                                                cx,
+                                            )
+                                            .map(|kb| kb.size(rems_from_px(12.)))
+                                        })
+                                        .on_click(cx.listener(|this, _, window, cx| {
+                                            this.dispatch_action(&ApplyPlan, window, cx)
+                                        })),
+                                ),
+                        )
+                    })
+                    .when(comment_count > 0, |el| {
+                        el.child(vertical_divider()).child(
+                            Button::new(
+                                "request-changes",
+                                format!("Request Changes ({comment_count})"),
+                            )
+                            .key_binding({
+                                KeyBinding::for_action_in(
+                                    &RequestChanges,
+                                    &focus_handle,
+                                    window,
+                                    cx,
+                                )
+                                .map(|kb| kb.size(rems_from_px(12.)))
+                            })
+                            .on_click(cx.listener(
+                                |this, _, window, cx| {
+                                    this.dispatch_action(&RequestChanges, window, cx)
+                                },
+                            )),
+                        )
+                    })
                     .into_any()
             }
         }
@@ -1373,12 +2772,14 @@ impl AgentDiff {
             | ThreadEvent::MessageDeleted(_)
             | ThreadEvent::SummaryGenerated
             | ThreadEvent::SummaryChanged
+            | ThreadEvent::DescriptionGenerated
             | ThreadEvent::UsePendingTools { .. }
             | ThreadEvent::ToolFinished { .. }
             | ThreadEvent::CheckpointChanged
             | ThreadEvent::ToolConfirmationNeeded
             | ThreadEvent::ToolUseLimitReached
-            | ThreadEvent::CancelEditing => {}
+            | ThreadEvent::CancelEditing
+            | ThreadEvent::ComparisonChanged => {}
         }
     }
 
@@ -1525,7 +2926,7 @@ impl AgentDiff {
                 if previous_state.is_none() {
                     editor.update(cx, |editor, cx| {
                         editor.start_temporary_diff_override();
-                        editor.set_render_diff_hunk_controls(diff_hunk_controls(&thread), cx);
+                        editor.set_render_diff_hunk_controls(diff_hunk_controls(&thread, None), cx);
                         editor.set_expand_all_diff_hunks(cx);
                         editor.register_addon(EditorAgentDiffAddon);
                     });
@@ -1672,93 +3073,565 @@ impl AgentDiff {
         })
     }
 
-    fn post_review_state(snapshot: &MultiBufferSnapshot) -> PostReviewState {
-        for (i, _) in snapshot.diff_hunks().enumerate() {
-            if i > 0 {
-                return PostReviewState::Pending;
-            }
+    fn post_review_state(snapshot: &MultiBufferSnapshot) -> PostReviewState {
+        for (i, _) in snapshot.diff_hunks().enumerate() {
+            if i > 0 {
+                return PostReviewState::Pending;
+            }
+        }
+        PostReviewState::AllReviewed
+    }
+
+    fn review_in_active_editor(
+        &mut self,
+        workspace: &mut Workspace,
+        review: impl Fn(&Entity<Editor>, &Entity<Thread>, &mut Window, &mut App) -> PostReviewState,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Option<Task<Result<()>>> {
+        let active_item = workspace.active_item(cx)?;
+        let editor = active_item.act_as::<Editor>(cx)?;
+
+        if !matches!(
+            self.editor_state(&editor.downgrade()),
+            EditorState::Reviewing
+        ) {
+            return None;
+        }
+
+        let WorkspaceThread { thread, .. } =
+            self.workspace_threads.get(&workspace.weak_handle())?;
+
+        let thread = thread.upgrade()?;
+
+        if let PostReviewState::AllReviewed = review(&editor, &thread, window, cx) {
+            if let Some(curr_buffer) = editor.read(cx).buffer().read(cx).as_singleton() {
+                let changed_buffers = thread.read(cx).action_log().read(cx).changed_buffers(cx);
+
+                let mut keys = changed_buffers.keys().cycle();
+                keys.find(|k| *k == &curr_buffer);
+                let next_project_path = keys
+                    .next()
+                    .filter(|k| *k != &curr_buffer)
+                    .and_then(|after| after.read(cx).project_path(cx));
+
+                if let Some(path) = next_project_path {
+                    let task = workspace.open_path(path, None, true, window, cx);
+                    let task = cx.spawn(async move |_, _cx| task.await.map(|_| ()));
+                    return Some(task);
+                }
+            }
+        }
+
+        return Some(Task::ready(Ok(())));
+    }
+}
+
+enum PostReviewState {
+    AllReviewed,
+    Pending,
+}
+
+pub struct EditorAgentDiffAddon;
+
+impl editor::Addon for EditorAgentDiffAddon {
+    fn to_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn extend_key_context(&self, key_context: &mut gpui::KeyContext, _: &App) {
+        key_context.add("agent_diff");
+        key_context.add("editor_agent_diff");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Keep, ThreadStore, thread_store};
+    use agent_settings::AgentSettings;
+    use assistant_tool::ToolWorkingSet;
+    use editor::EditorSettings;
+    use gpui::{TestAppContext, UpdateGlobal, VisualTestContext};
+    use project::{FakeFs, Project};
+    use prompt_store::PromptBuilder;
+    use serde_json::json;
+    use settings::{Settings, SettingsStore};
+    use std::sync::Arc;
+    use theme::ThemeSettings;
+    use util::path;
+
+    #[test]
+    fn test_word_diff() {
+        let old_text = "let total = items.iter().map(|item| item.price).sum();\n";
+        let new_text = "let total = items.iter().map(|item| item.cost).sum();\n";
+        let diff = word_diff(old_text, new_text, None);
+        assert_eq!(
+            diff.deleted
+                .iter()
+                .map(|range| &old_text[range.clone()])
+                .collect::<Vec<_>>(),
+            ["price"]
+        );
+        assert_eq!(
+            diff.inserted
+                .iter()
+                .map(|range| &new_text[range.clone()])
+                .collect::<Vec<_>>(),
+            ["cost"]
+        );
+
+        let old_text = "fn a() {}\n".repeat(20);
+        let new_text = "fn b() {}\n".repeat(20);
+        assert_eq!(word_diff(&old_text, &new_text, None), WordDiff::default());
+    }
+
+    #[gpui::test]
+    async fn test_agent_diff_filters(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            language::init(cx);
+            Project::init_settings(cx);
+            AgentSettings::register(cx);
+            prompt_store::init(cx);
+            thread_store::init(cx);
+            workspace::init_settings(cx);
+            ThemeSettings::register(cx);
+            EditorSettings::register(cx);
+            language_model::init_settings(cx);
+        });
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/test"),
+            json!({"main.rs": "fn main() {}\n", "notes.txt": "todo\n"}),
+        )
+        .await;
+        let project = Project::test(fs, [path!("/test").as_ref()], cx).await;
+
+        let thread_store = cx
+            .update(|cx| {
+                ThreadStore::load(
+                    project.clone(),
+                    cx.new(|_| ToolWorkingSet::default()),
+                    None,
+                    Arc::new(PromptBuilder::new(None).unwrap()),
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+        let thread = thread_store.update(cx, |store, cx| store.create_thread(cx));
+        let action_log = thread.read_with(cx, |thread, _| thread.action_log().clone());
+
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        let agent_diff = cx.new_window_entity(|window, cx| {
+            AgentDiffPane::new(thread.clone(), workspace.downgrade(), window, cx)
+        });
+
+        let mut buffers = Vec::new();
+        for path in ["test/main.rs", "test/notes.txt"] {
+            let project_path = project
+                .read_with(cx, |project, cx| project.find_project_path(path, cx))
+                .unwrap();
+            let buffer = project
+                .update(cx, |project, cx| project.open_buffer(project_path, cx))
+                .await
+                .unwrap();
+            cx.update(|_, cx| {
+                action_log.update(cx, |log, cx| log.buffer_read(buffer.clone(), cx));
+                buffer.update(cx, |buffer, cx| {
+                    buffer.edit([(0..0, "// edited\n")], None, cx)
+                });
+                action_log.update(cx, |log, cx| log.buffer_edited(buffer.clone(), cx));
+            });
+            buffers.push(buffer);
+        }
+        cx.run_until_parked();
+
+        let shown_paths = |cx: &mut VisualTestContext| {
+            agent_diff.read_with(cx, |diff, cx| diff.multibuffer.read(cx).paths().count())
+        };
+        assert_eq!(shown_paths(cx), 2);
+
+        let path_filter_editor =
+            agent_diff.read_with(cx, |diff, _| diff.path_filter_editor.clone());
+        path_filter_editor.update_in(cx, |editor, window, cx| {
+            editor.set_text("**/*.rs", window, cx)
+        });
+        cx.run_until_parked();
+        assert_eq!(shown_paths(cx), 1);
+
+        agent_diff.update_in(cx, |diff, window, cx| {
+            diff.set_review_filter(ReviewFilter::Reviewed, window, cx)
+        });
+        assert_eq!(shown_paths(cx), 0);
+
+        agent_diff.update_in(cx, |diff, window, cx| {
+            let ix = diff
+                .changed_files
+                .iter()
+                .position(|file| {
+                    file.buffer.read(cx).file().unwrap().path().as_ref()
+                        == std::path::Path::new("main.rs")
+                })
+                .unwrap();
+            diff.toggle_file_reviewed(ix, window, cx)
+        });
+        assert_eq!(shown_paths(cx), 1);
+
+        path_filter_editor.update_in(cx, |editor, window, cx| editor.set_text("", window, cx));
+        agent_diff.update_in(cx, |diff, window, cx| {
+            diff.set_review_filter(ReviewFilter::Unreviewed, window, cx)
+        });
+        cx.run_until_parked();
+        assert_eq!(shown_paths(cx), 1);
+
+        // Accept the edit to main.rs and reject the one to notes.txt.
+        cx.update(|_, cx| {
+            action_log.update(cx, |log, cx| {
+                log.keep_edits_in_range(buffers[0].clone(), Point::new(0, 0)..Point::new(1, 0), cx);
+                log.reject_edits_in_ranges(
+                    buffers[1].clone(),
+                    vec![Point::new(0, 0)..Point::new(1, 0)],
+                    cx,
+                )
+                .detach_and_log_err(cx);
+            })
+        });
+        cx.run_until_parked();
+        assert_eq!(shown_paths(cx), 0);
+
+        let shown_hunks = |cx: &mut VisualTestContext| {
+            agent_diff.read_with(cx, |diff, cx| {
+                let multibuffer = diff.multibuffer.read(cx);
+                let snapshot = multibuffer.snapshot(cx);
+                let hunk_count = diff
+                    .editor
+                    .read(cx)
+                    .diff_hunks_in_ranges(
+                        &[editor::Anchor::min()..editor::Anchor::max()],
+                        &snapshot,
+                    )
+                    .count();
+                (multibuffer.paths().collect::<Vec<_>>(), hunk_count)
+            })
+        };
+        let path_keys = cx.update(|_, cx| {
+            buffers
+                .iter()
+                .map(|buffer| PathKey::for_buffer(buffer, cx))
+                .collect::<Vec<_>>()
+        });
+
+        agent_diff.update_in(cx, |diff, window, cx| {
+            diff.set_review_filter(ReviewFilter::Accepted, window, cx)
+        });
+        cx.run_until_parked();
+        assert_eq!(shown_hunks(cx), (vec![path_keys[0].clone()], 1));
+
+        agent_diff.update_in(cx, |diff, window, cx| {
+            diff.set_review_filter(ReviewFilter::Rejected, window, cx)
+        });
+        cx.run_until_parked();
+        assert_eq!(shown_hunks(cx), (vec![path_keys[1].clone()], 1));
+
+        // Going back to the pending changes drops the diffs of the reviewed edits.
+        agent_diff.update_in(cx, |diff, window, cx| {
+            diff.set_review_filter(ReviewFilter::All, window, cx)
+        });
+        cx.run_until_parked();
+        assert_eq!(shown_paths(cx), 0);
+    }
+
+    #[gpui::test]
+    async fn test_agent_diff_review_api(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            language::init(cx);
+            Project::init_settings(cx);
+            AgentSettings::register(cx);
+            prompt_store::init(cx);
+            thread_store::init(cx);
+            workspace::init_settings(cx);
+            ThemeSettings::register(cx);
+            EditorSettings::register(cx);
+            language_model::init_settings(cx);
+        });
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/test"),
+            json!({"main.rs": "fn main() {}\n", "notes.txt": "todo\n"}),
+        )
+        .await;
+        let project = Project::test(fs, [path!("/test").as_ref()], cx).await;
+        let worktree_id = project.read_with(cx, |project, cx| {
+            project
+                .worktrees(cx)
+                .next()
+                .unwrap()
+                .read(cx)
+                .id()
+                .to_proto()
+        });
+
+        let thread_store = cx
+            .update(|cx| {
+                ThreadStore::load(
+                    project.clone(),
+                    cx.new(|_| ToolWorkingSet::default()),
+                    None,
+                    Arc::new(PromptBuilder::new(None).unwrap()),
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+        let thread = thread_store.update(cx, |store, cx| store.create_thread(cx));
+        let action_log = thread.read_with(cx, |thread, _| thread.action_log().clone());
+
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        let agent_diff = cx.new_window_entity(|window, cx| {
+            AgentDiffPane::new(thread.clone(), workspace.downgrade(), window, cx)
+        });
+
+        let mut buffers = Vec::new();
+        for path in ["test/main.rs", "test/notes.txt"] {
+            let project_path = project
+                .read_with(cx, |project, cx| project.find_project_path(path, cx))
+                .unwrap();
+            let buffer = project
+                .update(cx, |project, cx| project.open_buffer(project_path, cx))
+                .await
+                .unwrap();
+            cx.update(|_, cx| {
+                action_log.update(cx, |log, cx| log.buffer_read(buffer.clone(), cx));
+                buffer.update(cx, |buffer, cx| {
+                    buffer.edit([(0..0, "// edited\n")], None, cx)
+                });
+                action_log.update(cx, |log, cx| log.buffer_edited(buffer.clone(), cx));
+            });
+            buffers.push(buffer);
         }
-        PostReviewState::AllReviewed
-    }
+        cx.run_until_parked();
 
-    fn review_in_active_editor(
-        &mut self,
-        workspace: &mut Workspace,
-        review: impl Fn(&Entity<Editor>, &Entity<Thread>, &mut Window, &mut App) -> PostReviewState,
-        window: &mut Window,
-        cx: &mut Context<Self>,
-    ) -> Option<Task<Result<()>>> {
-        let active_item = workspace.active_item(cx)?;
-        let editor = active_item.act_as::<Editor>(cx)?;
+        assert_eq!(
+            agent_diff.read_with(cx, |diff, cx| diff.pending_hunks(cx).len()),
+            2
+        );
 
-        if !matches!(
-            self.editor_state(&editor.downgrade()),
-            EditorState::Reviewing
-        ) {
-            return None;
-        }
+        agent_diff.update_in(cx, |diff, window, cx| {
+            diff.toggle_file_reviewed(0, window, cx)
+        });
+        assert_eq!(
+            serde_json::to_value(agent_diff.read_with(cx, |diff, cx| diff.review_state(cx)))
+                .unwrap(),
+            json!({
+                "files": [
+                    {
+                        "worktree_id": worktree_id,
+                        "path": "main.rs",
+                        "reviewed": true,
+                        "hunks": [{
+                            "rows": {"start": 0, "end": 1},
+                            "kind": "added",
+                            "old_text": "",
+                            "new_text": "// edited\n",
+                        }],
+                    },
+                    {
+                        "worktree_id": worktree_id,
+                        "path": "notes.txt",
+                        "reviewed": false,
+                        "hunks": [{
+                            "rows": {"start": 0, "end": 1},
+                            "kind": "added",
+                            "old_text": "",
+                            "new_text": "// edited\n",
+                        }],
+                    },
+                ],
+            })
+        );
 
-        let WorkspaceThread { thread, .. } =
-            self.workspace_threads.get(&workspace.weak_handle())?;
+        let hunk_ranges = |cx: &mut VisualTestContext| {
+            let editor = agent_diff.read_with(cx, |diff, _| diff.editor.clone());
+            editor.update(cx, |editor, cx| {
+                let snapshot = editor.buffer().read(cx).snapshot(cx);
+                editor
+                    .diff_hunks_in_ranges(
+                        &[editor::Anchor::min()..editor::Anchor::max()],
+                        &snapshot,
+                    )
+                    .map(|hunk| hunk.multi_buffer_range())
+                    .collect::<Vec<_>>()
+            })
+        };
 
-        let thread = thread.upgrade()?;
+        // Keeping and rejecting by range only affects the hunks in those ranges.
+        let ranges = hunk_ranges(cx);
+        agent_diff.update_in(cx, |diff, window, cx| {
+            diff.keep_edits_in_ranges(vec![ranges[0].clone()], window, cx)
+        });
+        cx.run_until_parked();
+        let pending_hunks = agent_diff.read_with(cx, |diff, cx| diff.pending_hunks(cx));
+        assert_eq!(pending_hunks.len(), 1);
+        assert_eq!(pending_hunks[0].buffer, buffers[1]);
 
-        if let PostReviewState::AllReviewed = review(&editor, &thread, window, cx) {
-            if let Some(curr_buffer) = editor.read(cx).buffer().read(cx).as_singleton() {
-                let changed_buffers = thread.read(cx).action_log().read(cx).changed_buffers(cx);
+        let ranges = hunk_ranges(cx);
+        agent_diff.update_in(cx, |diff, window, cx| {
+            diff.reject_edits_in_ranges(ranges, window, cx)
+        });
+        cx.run_until_parked();
+        assert!(agent_diff.read_with(cx, |diff, cx| diff.pending_hunks(cx).is_empty()));
+        assert_eq!(
+            buffers[0].read_with(cx, |buffer, _| buffer.text()),
+            "// edited\nfn main() {}\n"
+        );
+        assert_eq!(
+            buffers[1].read_with(cx, |buffer, _| buffer.text()),
+            "todo\n"
+        );
+    }
 
-                let mut keys = changed_buffers.keys().cycle();
-                keys.find(|k| *k == &curr_buffer);
-                let next_project_path = keys
-                    .next()
-                    .filter(|k| *k != &curr_buffer)
-                    .and_then(|after| after.read(cx).project_path(cx));
+    #[gpui::test]
+    async fn test_agent_diff_review_state_across_worktrees(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            language::init(cx);
+            Project::init_settings(cx);
+            AgentSettings::register(cx);
+            prompt_store::init(cx);
+            thread_store::init(cx);
+            workspace::init_settings(cx);
+            ThemeSettings::register(cx);
+            EditorSettings::register(cx);
+            language_model::init_settings(cx);
+        });
 
-                if let Some(path) = next_project_path {
-                    let task = workspace.open_path(path, None, true, window, cx);
-                    let task = cx.spawn(async move |_, _cx| task.await.map(|_| ()));
-                    return Some(task);
-                }
-            }
-        }
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/a"), json!({"main.rs": "fn a() {}\n"}))
+            .await;
+        fs.insert_tree(path!("/b"), json!({"main.rs": "fn b() {}\n"}))
+            .await;
+        let project = Project::test(fs, [path!("/a").as_ref(), path!("/b").as_ref()], cx).await;
 
-        return Some(Task::ready(Ok(())));
-    }
-}
+        let thread_store = cx
+            .update(|cx| {
+                ThreadStore::load(
+                    project.clone(),
+                    cx.new(|_| ToolWorkingSet::default()),
+                    None,
+                    Arc::new(PromptBuilder::new(None).unwrap()),
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+        let thread = thread_store.update(cx, |store, cx| store.create_thread(cx));
+        let action_log = thread.read_with(cx, |thread, _| thread.action_log().clone());
 
-enum PostReviewState {
-    AllReviewed,
-    Pending,
-}
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        let agent_diff = cx.new_window_entity(|window, cx| {
+            AgentDiffPane::new(thread.clone(), workspace.downgrade(), window, cx)
+        });
 
-pub struct EditorAgentDiffAddon;
+        let mut buffers = Vec::new();
+        for path in ["a/main.rs", "b/main.rs"] {
+            let project_path = project
+                .read_with(cx, |project, cx| project.find_project_path(path, cx))
+                .unwrap();
+            let buffer = project
+                .update(cx, |project, cx| project.open_buffer(project_path, cx))
+                .await
+                .unwrap();
+            cx.update(|_, cx| {
+                action_log.update(cx, |log, cx| log.buffer_read(buffer.clone(), cx));
+                buffer.update(cx, |buffer, cx| {
+                    buffer.edit([(0..0, "// edited\n")], None, cx)
+                });
+                action_log.update(cx, |log, cx| log.buffer_edited(buffer.clone(), cx));
+            });
+            buffers.push(buffer);
+        }
+        cx.run_until_parked();
 
-impl editor::Addon for EditorAgentDiffAddon {
-    fn to_any(&self) -> &dyn std::any::Any {
-        self
-    }
+        let worktree_ids = project.read_with(cx, |project, cx| {
+            project
+                .worktrees(cx)
+                .map(|worktree| worktree.read(cx).id().to_proto())
+                .collect::<Vec<_>>()
+        });
+        let reviewed_files = |diff: &Entity<AgentDiffPane>, cx: &mut VisualTestContext| {
+            diff.read_with(cx, |diff, cx| {
+                diff.review_state(cx)
+                    .files
+                    .into_iter()
+                    .map(|file| {
+                        assert_eq!(file.path, PathBuf::from("main.rs"));
+                        (file.worktree_id, file.reviewed)
+                    })
+                    .collect::<Vec<_>>()
+            })
+        };
 
-    fn extend_key_context(&self, key_context: &mut gpui::KeyContext, _: &App) {
-        key_context.add("agent_diff");
-        key_context.add("editor_agent_diff");
-    }
-}
+        // Files with the same path in different worktrees are reviewed separately.
+        agent_diff.update_in(cx, |diff, window, cx| {
+            let ix = diff
+                .changed_files
+                .iter()
+                .position(|changed_file| changed_file.buffer == buffers[0])
+                .unwrap();
+            diff.toggle_file_reviewed(ix, window, cx)
+        });
+        assert_eq!(
+            reviewed_files(&agent_diff, cx),
+            [(worktree_ids[0], true), (worktree_ids[1], false)]
+        );
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::{Keep, ThreadStore, thread_store};
-    use agent_settings::AgentSettings;
-    use assistant_tool::ToolWorkingSet;
-    use editor::EditorSettings;
-    use gpui::{TestAppContext, UpdateGlobal, VisualTestContext};
-    use project::{FakeFs, Project};
-    use prompt_store::PromptBuilder;
-    use serde_json::json;
-    use settings::{Settings, SettingsStore};
-    use std::sync::Arc;
-    use theme::ThemeSettings;
-    use util::path;
+        // A serialized review can be picked up by another pane.
+        let review_state = agent_diff.read_with(cx, |diff, cx| diff.review_state(cx));
+        let serialized = serde_json::to_string(&review_state).unwrap();
+        let restored_state: ReviewState = serde_json::from_str(&serialized).unwrap();
+        let restored_diff = cx.new_window_entity(|window, cx| {
+            AgentDiffPane::new(thread.clone(), workspace.downgrade(), window, cx)
+        });
+        cx.run_until_parked();
+        restored_diff.update_in(cx, |diff, window, cx| {
+            diff.restore_review_state(&restored_state, window, cx)
+        });
+        assert_eq!(
+            restored_diff.read_with(cx, |diff, cx| diff.review_state(cx)),
+            review_state
+        );
+
+        // Files the agent edited again since the review need to be reviewed again.
+        cx.update(|_, cx| {
+            buffers[0].update(cx, |buffer, cx| {
+                buffer.edit([(0..0, "// again\n")], None, cx)
+            });
+            action_log.update(cx, |log, cx| log.buffer_edited(buffers[0].clone(), cx));
+        });
+        cx.run_until_parked();
+        let restored_diff = cx.new_window_entity(|window, cx| {
+            AgentDiffPane::new(thread.clone(), workspace.downgrade(), window, cx)
+        });
+        cx.run_until_parked();
+        restored_diff.update_in(cx, |diff, window, cx| {
+            diff.restore_review_state(&restored_state, window, cx)
+        });
+        assert_eq!(
+            reviewed_files(&restored_diff, cx),
+            [(worktree_ids[0], false), (worktree_ids[1], false)]
+        );
+    }
 
     #[gpui::test]
     async fn test_multibuffer_agent_diff(cx: &mut TestAppContext) {
@@ -1847,10 +3720,25 @@ mod tests {
                 .range(),
             Point::new(1, 0)..Point::new(1, 0)
         );
+        agent_diff.update(cx, |diff, cx| {
+            assert_eq!(diff.changed_files.len(), 1);
+            assert_eq!(diff.changed_files[0].added_lines, 4);
+            assert_eq!(diff.changed_files[0].removed_lines, 4);
+
+            let path_key = diff.changed_files[0].path_key.clone();
+            let version = diff.changed_files[0].buffer.read(cx).version();
+            diff.reviewed_files.insert(path_key, version);
+        });
 
         // After keeping a hunk, the cursor should be positioned on the second hunk.
         agent_diff.update_in(cx, |diff, window, cx| diff.keep(&Keep, window, cx));
         cx.run_until_parked();
+        agent_diff.read_with(cx, |diff, _| {
+            assert_eq!(diff.changed_files[0].added_lines, 3);
+            assert_eq!(diff.changed_files[0].removed_lines, 3);
+            // Keeping a hunk leaves the file untouched, so it stays reviewed.
+            assert_eq!(diff.reviewed_files.len(), 1);
+        });
         assert_eq!(
             editor.read_with(cx, |editor, cx| editor.text(cx)),
             "abc\ndEf\nghi\njkl\njkL\nmno\npqr\nPqr\nstu\nvwx\nvWx\nyz"
@@ -1872,6 +3760,10 @@ mod tests {
             diff.reject(&crate::Reject, window, cx)
         });
         cx.run_until_parked();
+        agent_diff.read_with(cx, |diff, _| {
+            // Further changes to a reviewed file require reviewing it again.
+            assert!(diff.reviewed_files.is_empty());
+        });
         assert_eq!(
             editor.read_with(cx, |editor, cx| editor.text(cx)),
             "abc\ndEf\nghi\njkl\njkL\nmno\npqr\nPqr\nstu\nvwx\nyz"
@@ -1916,6 +3808,118 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_agent_diff_hunk_comments(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            language::init(cx);
+            Project::init_settings(cx);
+            AgentSettings::register(cx);
+            prompt_store::init(cx);
+            thread_store::init(cx);
+            workspace::init_settings(cx);
+            ThemeSettings::register(cx);
+            EditorSettings::register(cx);
+            language_model::init_settings(cx);
+        });
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/test"),
+            json!({"file1": "abc\ndef\nghi\njkl\nmno\npqr\nstu\nvwx\nyz"}),
+        )
+        .await;
+        let project = Project::test(fs, [path!("/test").as_ref()], cx).await;
+        let buffer_path = project
+            .read_with(cx, |project, cx| {
+                project.find_project_path("test/file1", cx)
+            })
+            .unwrap();
+
+        let thread_store = cx
+            .update(|cx| {
+                ThreadStore::load(
+                    project.clone(),
+                    cx.new(|_| ToolWorkingSet::default()),
+                    None,
+                    Arc::new(PromptBuilder::new(None).unwrap()),
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+        let thread = thread_store.update(cx, |store, cx| store.create_thread(cx));
+        let action_log = thread.read_with(cx, |thread, _| thread.action_log().clone());
+
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        let agent_diff = cx.new_window_entity(|window, cx| {
+            AgentDiffPane::new(thread.clone(), workspace.downgrade(), window, cx)
+        });
+        let editor = agent_diff.read_with(cx, |diff, _cx| diff.editor.clone());
+
+        let buffer = project
+            .update(cx, |project, cx| project.open_buffer(buffer_path, cx))
+            .await
+            .unwrap();
+        cx.update(|_, cx| {
+            action_log.update(cx, |log, cx| log.buffer_read(buffer.clone(), cx));
+            buffer.update(cx, |buffer, cx| {
+                buffer
+                    .edit(
+                        [
+                            (Point::new(1, 1)..Point::new(1, 2), "E"),
+                            (Point::new(5, 0)..Point::new(5, 1), "P"),
+                        ],
+                        None,
+                        cx,
+                    )
+                    .unwrap()
+            });
+            action_log.update(cx, |log, cx| log.buffer_edited(buffer.clone(), cx));
+        });
+        cx.run_until_parked();
+
+        let hunk_ranges = editor.update(cx, |editor, cx| {
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            editor
+                .diff_hunks_in_ranges(&[editor::Anchor::min()..editor::Anchor::max()], &snapshot)
+                .map(|hunk| hunk.multi_buffer_range())
+                .collect::<Vec<_>>()
+        });
+        assert_eq!(hunk_ranges.len(), 2);
+
+        agent_diff.update(cx, |diff, cx| {
+            diff.set_hunk_comment(
+                hunk_ranges[1].clone(),
+                "Keep the original casing".into(),
+                cx,
+            );
+            diff.set_hunk_comment(hunk_ranges[0].clone(), "  ".into(), cx);
+        });
+        agent_diff.read_with(cx, |diff, cx| {
+            assert!(!diff.has_hunk_comment(&hunk_ranges[0], cx));
+            assert!(diff.has_hunk_comment(&hunk_ranges[1], cx));
+            assert_eq!(
+                diff.hunk_comments_message(cx),
+                "I reviewed your changes and left comments on some of them. \
+                Please address each comment:\n\
+                \n## `file1` (lines 6-6)\n\n```\nPqr\n```\n\nKeep the original casing\n"
+            );
+        });
+
+        // Editing an existing comment replaces it instead of adding another one.
+        agent_diff.update(cx, |diff, cx| {
+            diff.set_hunk_comment(hunk_ranges[1].clone(), "Revert this".into(), cx);
+            assert_eq!(diff.hunk_comments.len(), 1);
+            assert_eq!(diff.hunk_comments[0].text.as_ref(), "Revert this");
+
+            diff.clear_hunk_comments(cx);
+            assert!(diff.hunk_comments.is_empty());
+        });
+    }
+
     #[gpui::test]
     async fn test_singleton_agent_diff(cx: &mut TestAppContext) {
         cx.update(|cx| {
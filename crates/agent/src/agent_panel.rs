@@ -7,7 +7,10 @@ use std::time::Duration;
 use db::kvp::{Dismissable, KEY_VALUE_STORE};
 use serde::{Deserialize, Serialize};
 
-use agent_settings::{AgentDockPosition, AgentSettings, CompletionMode, DefaultView};
+use agent_settings::{
+    AgentDockPosition, AgentProfileId, AgentSettings, CompletionMode, DefaultView,
+    RevertEditsOnCancel,
+};
 use anyhow::{Result, anyhow};
 use assistant_context_editor::{
     AgentPanelDelegate, AssistantContext, ConfigurationError, ContextEditor, ContextEvent,
@@ -24,21 +27,23 @@ use fs::Fs;
 use gpui::{
     Action, Animation, AnimationExt as _, AnyElement, App, AsyncWindowContext, ClipboardItem,
     Corner, DismissEvent, Entity, EventEmitter, ExternalPaths, FocusHandle, Focusable, FontWeight,
-    KeyContext, Pixels, Subscription, Task, UpdateGlobal, WeakEntity, linear_color_stop,
-    linear_gradient, prelude::*, pulsating_between,
+    KeyContext, PathPromptOptions, Pixels, PromptLevel, Subscription, Task, UpdateGlobal,
+    WeakEntity, linear_color_stop, linear_gradient, prelude::*, pulsating_between,
 };
 use language::LanguageRegistry;
 use language_model::{
     LanguageModelProviderTosView, LanguageModelRegistry, RequestUsage, ZED_CLOUD_PROVIDER_ID,
 };
-use project::{Project, ProjectPath, Worktree};
-use prompt_store::{PromptBuilder, PromptStore, UserPromptId};
+use project::{Project, ProjectPath, Worktree, image_store::is_image_path};
+use prompt_store::{
+    Playbook, PlaybookId, PlaybookVariables, PromptBuilder, PromptStore, UserPromptId,
+};
 use proto::Plan;
 use rules_library::{RulesLibrary, open_rules_library};
 use search::{BufferSearchBar, buffer_search};
 use settings::{Settings, update_settings_file};
 use theme::ThemeSettings;
-use time::UtcOffset;
+use time::{OffsetDateTime, UtcOffset};
 use ui::utils::WithRemSize;
 use ui::{
     Banner, CheckboxWithLabel, ContextMenu, ElevationIndex, KeyBinding, PopoverMenu,
@@ -46,10 +51,12 @@ use ui::{
 };
 use util::{ResultExt as _, maybe};
 use workspace::dock::{DockPosition, Panel, PanelEvent};
+use workspace::notifications::DetachAndPromptErr as _;
 use workspace::{
-    CollaboratorId, DraggedSelection, DraggedTab, ToggleZoom, ToolbarItemView, Workspace,
+    CollaboratorId, DraggedSelection, DraggedTab, OpenOptions, ToggleZoom, ToolbarItemView,
+    Workspace,
 };
-use zed_actions::agent::{OpenConfiguration, OpenOnboardingModal, ResetOnboarding};
+use zed_actions::agent::{OpenConfiguration, OpenOnboardingModal, OpenThread, ResetOnboarding};
 use zed_actions::assistant::{OpenRulesLibrary, ToggleFocus};
 use zed_actions::{DecreaseBufferFontSize, IncreaseBufferFontSize, ResetBufferFontSize};
 use zed_llm_client::{CompletionIntent, UsageLimit};
@@ -57,18 +64,23 @@ use zed_llm_client::{CompletionIntent, UsageLimit};
 use crate::active_thread::{self, ActiveThread, ActiveThreadEvent};
 use crate::agent_configuration::{AgentConfiguration, AssistantConfigurationEvent};
 use crate::agent_diff::AgentDiff;
+use crate::eval_fixture::EvalFixture;
 use crate::history_store::{HistoryStore, RecentEntry};
 use crate::message_editor::{MessageEditor, MessageEditorEvent};
+use crate::playbook_picker::PlaybookPicker;
+use crate::scheduled_tasks::TaskScheduler;
 use crate::thread::{Thread, ThreadError, ThreadId, ThreadSummary, TokenUsageRatio};
+use crate::thread_export::{export_thread, with_extension};
 use crate::thread_history::{HistoryEntryElement, ThreadHistory};
 use crate::thread_store::ThreadStore;
 use crate::ui::AgentOnboardingModal;
 use crate::{
     AddContextServer, AgentDiffPane, ContextStore, ContinueThread, ContinueWithBurnMode,
-    DeleteRecentlyOpenThread, ExpandMessageEditor, Follow, InlineAssistant, NewTextThread,
-    NewThread, OpenActiveThreadAsMarkdown, OpenAgentDiff, OpenHistory, ResetTrialEndUpsell,
-    ResetTrialUpsell, TextThreadStore, ThreadEvent, ToggleBurnMode, ToggleContextPicker,
-    ToggleNavigationMenu, ToggleOptionsMenu,
+    DeleteRecentlyOpenThread, ExpandMessageEditor, ExportThread, ExportThreadAsEvalFixture, Follow,
+    InlineAssistant, ManageMemories, NewTextThread, NewThread, OpenActiveThreadAsMarkdown,
+    OpenAgentDiff, OpenHistory, OpenPlaybooks, OpenToolMetrics, ResetTrialEndUpsell,
+    ResetTrialUpsell, TextThreadStore, ThreadEvent, ThreadExportFormat, ToggleBurnMode,
+    ToggleContextPicker, ToggleNavigationMenu, ToggleOptionsMenu,
 };
 
 const AGENT_PANEL_KEY: &str = "agent_panel";
@@ -100,6 +112,17 @@ pub fn init(cx: &mut App) {
                         panel.update(cx, |panel, cx| panel.open_configuration(window, cx));
                     }
                 })
+                .register_action(|workspace, action: &OpenThread, window, cx| {
+                    if let Some(panel) = workspace.panel::<AgentPanel>(cx) {
+                        workspace.focus_panel::<AgentPanel>(window, cx);
+                        let thread_id = ThreadId::from(action.thread_id.as_str());
+                        panel.update(cx, |panel, cx| {
+                            panel
+                                .open_thread_by_id(&thread_id, window, cx)
+                                .detach_and_log_err(cx)
+                        });
+                    }
+                })
                 .register_action(|workspace, _: &NewTextThread, window, cx| {
                     if let Some(panel) = workspace.panel::<AgentPanel>(cx) {
                         workspace.focus_panel::<AgentPanel>(window, cx);
@@ -121,6 +144,9 @@ pub fn init(cx: &mut App) {
                         AgentDiffPane::deploy_in_workspace(thread, workspace, window, cx);
                     }
                 })
+                .register_action(|workspace, _: &OpenPlaybooks, window, cx| {
+                    PlaybookPicker::toggle(workspace, window, cx);
+                })
                 .register_action(|workspace, _: &Follow, window, cx| {
                     workspace.follow(CollaboratorId::Agent, window, cx);
                 })
@@ -372,6 +398,7 @@ pub struct AgentPanel {
     zoomed: bool,
     pending_serialization: Option<Task<Result<()>>>,
     hide_upsell: bool,
+    _task_scheduler: Entity<TaskScheduler>,
 }
 
 impl AgentPanel {
@@ -685,6 +712,8 @@ impl AgentPanel {
                 .ok();
         });
 
+        let task_scheduler = cx.new(|cx| TaskScheduler::new(thread_store.downgrade(), cx));
+
         let _default_model_subscription = cx.subscribe(
             &LanguageModelRegistry::global(cx),
             |this, _, event: &language_model::Event, cx| match event {
@@ -736,6 +765,7 @@ impl AgentPanel {
             zoomed: false,
             pending_serialization: None,
             hide_upsell: false,
+            _task_scheduler: task_scheduler,
         }
     }
 
@@ -776,8 +806,43 @@ impl AgentPanel {
     }
 
     fn cancel(&mut self, _: &editor::actions::Cancel, window: &mut Window, cx: &mut Context<Self>) {
-        self.thread
+        let canceled = self
+            .thread
             .update(cx, |thread, cx| thread.cancel_last_completion(window, cx));
+        if canceled {
+            self.revert_canceled_turn(window, cx);
+        }
+    }
+
+    /// Reverts the edits the agent made since the last user message, if the user wants to.
+    fn revert_canceled_turn(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let action_log = self.thread.read(cx).thread().read(cx).action_log().clone();
+        if !action_log.read(cx).has_edits_in_turn(cx) {
+            return;
+        }
+
+        let answer = match AgentSettings::get_global(cx).revert_edits_on_cancel {
+            RevertEditsOnCancel::Never => return,
+            RevertEditsOnCancel::Always => None,
+            RevertEditsOnCancel::Ask => Some(window.prompt(
+                PromptLevel::Info,
+                "Revert the edits made since your last message?",
+                Some("The agent was stopped before it finished, so its edits may be incomplete."),
+                &["Revert Edits", "Keep Edits"],
+                cx,
+            )),
+        };
+        cx.spawn_in(window, async move |_, cx| {
+            if let Some(answer) = answer {
+                if answer.await != Ok(0) {
+                    return anyhow::Ok(());
+                }
+            }
+            action_log
+                .update(cx, |action_log, cx| action_log.revert_turn(cx))?
+                .await
+        })
+        .detach_and_prompt_err("Failed to revert edits", window, cx, |_, _, _| None);
     }
 
     fn new_thread(&mut self, action: &NewThread, window: &mut Window, cx: &mut Context<Self>) {
@@ -873,6 +938,77 @@ impl AgentPanel {
         ];
     }
 
+    /// Starts a new thread whose first message is the rendered playbook, switching to the
+    /// playbook's profile first.
+    pub(crate) fn run_playbook(
+        &mut self,
+        playbook: &Playbook,
+        variables: &PlaybookVariables,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Result<()> {
+        let message = playbook.render(variables)?;
+
+        if let Some(profile_id) = playbook.profile_id.as_ref() {
+            let profile_id = AgentProfileId(profile_id.as_ref().into());
+            if !AgentSettings::get_global(cx)
+                .profiles
+                .contains_key(&profile_id)
+            {
+                return Err(anyhow!(
+                    "playbook \"{}\" uses the profile \"{profile_id}\", which no longer exists",
+                    playbook.title
+                ));
+            }
+            update_settings_file::<AgentSettings>(self.fs.clone(), cx, {
+                let profile_id = profile_id.clone();
+                move |settings, _cx| settings.set_profile(profile_id)
+            });
+            self.thread_store.update(cx, |thread_store, cx| {
+                thread_store.load_profile_by_id(profile_id, cx)
+            });
+        }
+
+        self.new_thread(&NewThread::default(), window, cx);
+        self.message_editor.update(cx, |message_editor, cx| {
+            message_editor.send_message(message, window, cx)
+        });
+        Ok(())
+    }
+
+    /// Saves the message being composed as a playbook that uses the current profile.
+    pub(crate) fn save_message_as_playbook(
+        &mut self,
+        title: SharedString,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let Some(prompt_store) = self.prompt_store.clone() else {
+            return Task::ready(Err(anyhow!("the prompt store failed to load")));
+        };
+        let template = self.message_editor.read(cx).text(cx);
+        if template.trim().is_empty() {
+            return Task::ready(Err(anyhow!(
+                "write the playbook's prompt in the message editor before saving it"
+            )));
+        }
+        let playbook = Playbook {
+            id: PlaybookId::new(),
+            title,
+            template,
+            profile_id: Some(
+                AgentSettings::get_global(cx)
+                    .default_profile
+                    .as_str()
+                    .to_string()
+                    .into(),
+            ),
+            saved_at: chrono::Utc::now(),
+        };
+        prompt_store.update(cx, |prompt_store, cx| {
+            prompt_store.save_playbook(playbook, cx)
+        })
+    }
+
     fn new_prompt_editor(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         let context = self
             .context_store
@@ -1251,6 +1387,88 @@ impl AgentPanel {
             .detach_and_log_err(cx);
     }
 
+    fn export_thread(
+        &mut self,
+        action: &ExportThread,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(thread) = self.active_thread() else {
+            return;
+        };
+
+        let task = match export_thread(thread.read(cx), action, cx) {
+            Ok(contents) => {
+                let fs = self.fs.clone();
+                let format = action.format;
+                let directory = self
+                    .project
+                    .read(cx)
+                    .visible_worktrees(cx)
+                    .next()
+                    .map(|worktree| worktree.read(cx).abs_path().to_path_buf())
+                    .unwrap_or_else(|| paths::home_dir().clone());
+                let path = cx.prompt_for_new_path(&directory);
+                cx.spawn_in(window, async move |_, cx| {
+                    let Some(path) = path.await?? else {
+                        return Ok(());
+                    };
+                    fs.atomic_write(with_extension(path, format), contents)
+                        .await
+                })
+            }
+            Err(error) => Task::ready(Err(error)),
+        };
+        task.detach_and_prompt_err("Failed to export thread", window, cx, |error, _, _| {
+            Some(error.to_string())
+        });
+    }
+
+    fn export_thread_as_eval_fixture(
+        &mut self,
+        _: &ExportThreadAsEvalFixture,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(thread) = self.active_thread() else {
+            return;
+        };
+
+        let task = match EvalFixture::from_thread(thread.read(cx), cx) {
+            Ok(fixture) => {
+                let fs = self.fs.clone();
+                let workspace = self.workspace.clone();
+                let fixtures_dir = cx.prompt_for_paths(PathPromptOptions {
+                    files: false,
+                    directories: true,
+                    multiple: false,
+                });
+                cx.spawn_in(window, async move |_, cx| {
+                    let Some(fixtures_dir) = fixtures_dir
+                        .await??
+                        .and_then(|paths| paths.into_iter().next())
+                    else {
+                        return Ok(());
+                    };
+                    let test_path = fixture.save(&fixtures_dir, fs.as_ref()).await?;
+                    workspace
+                        .update_in(cx, |workspace, window, cx| {
+                            workspace.open_abs_path(test_path, OpenOptions::default(), window, cx)
+                        })?
+                        .await?;
+                    anyhow::Ok(())
+                })
+            }
+            Err(error) => Task::ready(Err(error)),
+        };
+        task.detach_and_prompt_err(
+            "Failed to export eval fixture",
+            window,
+            cx,
+            |error, _, _| Some(error.to_string()),
+        );
+    }
+
     fn handle_agent_configuration_event(
         &mut self,
         _entity: &Entity<AgentConfiguration>,
@@ -1556,8 +1774,10 @@ impl AgentPanel {
                     ThreadSummary::Generating => Label::new(LOADING_SUMMARY_PLACEHOLDER)
                         .truncate()
                         .into_any_element(),
-                    ThreadSummary::Ready(_) => div()
+                    ThreadSummary::Ready(_) => h_flex()
                         .w_full()
+                        .gap_1()
+                        .children(self.render_branch_switcher(cx))
                         .child(change_title_editor.clone())
                         .into_any_element(),
                     ThreadSummary::Error => h_flex()
@@ -1646,6 +1866,77 @@ impl AgentPanel {
             .into_any()
     }
 
+    fn render_branch_switcher(&self, cx: &Context<Self>) -> Option<impl IntoElement> {
+        let thread = self.thread.read(cx).thread().read(cx);
+        let branches = self.thread_store.read(cx).thread_branches(thread);
+        if branches.len() < 2 {
+            return None;
+        }
+
+        let current_id = thread.id().clone();
+        let root_id = thread.branch_root_id();
+        let timezone = self.local_timezone();
+        let panel = cx.entity().downgrade();
+
+        Some(
+            PopoverMenu::new("thread-branch-menu")
+                .trigger_with_tooltip(
+                    IconButton::new("thread-branch-menu", IconName::GitBranch)
+                        .icon_size(IconSize::Small),
+                    Tooltip::text("Switch Branch"),
+                )
+                .anchor(Corner::TopLeft)
+                .menu(move |window, cx| {
+                    let branches = branches.clone();
+                    let current_id = current_id.clone();
+                    let root_id = root_id.clone();
+                    let panel = panel.clone();
+                    Some(ContextMenu::build(
+                        window,
+                        cx,
+                        move |mut menu, _window, _cx| {
+                            for branch in branches {
+                                let label = if branch.id == root_id {
+                                    "Original".to_string()
+                                } else {
+                                    let updated_at = OffsetDateTime::from_unix_timestamp(
+                                        branch.updated_at.timestamp(),
+                                    )
+                                    .unwrap_or(OffsetDateTime::UNIX_EPOCH);
+                                    format!(
+                                        "Branch · {}",
+                                        time_format::format_localized_timestamp(
+                                            updated_at,
+                                            OffsetDateTime::now_utc(),
+                                            timezone,
+                                            time_format::TimestampFormat::EnhancedAbsolute,
+                                        )
+                                    )
+                                };
+                                let panel = panel.clone();
+                                menu = menu.toggleable_entry(
+                                    label,
+                                    branch.id == current_id,
+                                    IconPosition::Start,
+                                    None,
+                                    move |window, cx| {
+                                        panel
+                                            .update(cx, |panel, cx| {
+                                                panel
+                                                    .open_thread_by_id(&branch.id, window, cx)
+                                                    .detach_and_log_err(cx);
+                                            })
+                                            .ok();
+                                    },
+                                );
+                            }
+                            menu
+                        },
+                    ))
+                }),
+        )
+    }
+
     fn render_toolbar(&self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let active_thread = self.thread.read(cx);
         let user_store = self.user_store.read(cx);
@@ -1775,6 +2066,15 @@ impl AgentPanel {
                                     from_thread_id: Some(thread_id.clone()),
                                 }),
                             )
+                            .separator()
+                            .action("Export as Markdown…", Box::new(ExportThread::default()))
+                            .action(
+                                "Export as HTML…",
+                                Box::new(ExportThread {
+                                    format: ThreadExportFormat::Html,
+                                    ..Default::default()
+                                }),
+                            )
                         })
                         .separator();
 
@@ -1829,7 +2129,10 @@ impl AgentPanel {
                     }
 
                     menu = menu
+                        .action("Playbooks…", Box::new(OpenPlaybooks))
                         .action("Rules…", Box::new(OpenRulesLibrary::default()))
+                        .action("Memories…", Box::new(ManageMemories))
+                        .action("Tool Metrics…", Box::new(OpenToolMetrics))
                         .action("Settings", Box::new(OpenConfiguration))
                         .action(zoom_in_label, Box::new(ToggleZoom));
                     menu
@@ -2974,8 +3277,22 @@ impl AgentPanel {
                 }),
             )
             .on_drop(cx.listener(move |this, paths: &ExternalPaths, window, cx| {
+                let mut paths = paths.paths().to_vec();
+                if let ActiveView::Thread { .. } = &this.active_view {
+                    let context_store = this.thread.read(cx).context_store().clone();
+                    paths.retain(|path| {
+                        if !is_image_path(path) {
+                            return true;
+                        }
+                        context_store
+                            .update(cx, |context_store, cx| {
+                                context_store.add_image_from_abs_path(path.clone(), cx)
+                            })
+                            .detach_and_log_err(cx);
+                        false
+                    });
+                }
                 let tasks = paths
-                    .paths()
                     .into_iter()
                     .map(|path| {
                         Workspace::project_path_for_path(this.project.clone(), &path, false, cx)
@@ -3088,6 +3405,8 @@ impl Render for AgentPanel {
                 this.open_configuration(window, cx);
             }))
             .on_action(cx.listener(Self::open_active_thread_as_markdown))
+            .on_action(cx.listener(Self::export_thread))
+            .on_action(cx.listener(Self::export_thread_as_eval_fixture))
             .on_action(cx.listener(Self::deploy_rules_library))
             .on_action(cx.listener(Self::open_agent_diff))
             .on_action(cx.listener(Self::go_back))
@@ -472,6 +472,7 @@ impl CodegenAlternative {
                 stop: Vec::new(),
                 temperature,
                 messages: vec![request_message],
+                response_schema: None,
             }
         }))
     }
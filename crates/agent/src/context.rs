@@ -37,6 +37,7 @@ pub enum ContextKind {
     TextThread,
     Rules,
     Image,
+    ExtensionEntry,
 }
 
 impl ContextKind {
@@ -51,6 +52,7 @@ impl ContextKind {
             ContextKind::TextThread => IconName::MessageBubbles,
             ContextKind::Rules => RULES_ICON,
             ContextKind::Image => IconName::Image,
+            ContextKind::ExtensionEntry => IconName::Blocks,
         }
     }
 }
@@ -71,6 +73,7 @@ pub enum AgentContextHandle {
     TextThread(TextThreadContextHandle),
     Rules(RulesContextHandle),
     Image(ImageContext),
+    ExtensionEntry(ExtensionEntryContext),
 }
 
 impl AgentContextHandle {
@@ -85,6 +88,7 @@ impl AgentContextHandle {
             Self::TextThread(context) => context.context_id,
             Self::Rules(context) => context.context_id,
             Self::Image(context) => context.context_id,
+            Self::ExtensionEntry(context) => context.context_id,
         }
     }
 
@@ -106,6 +110,7 @@ pub enum AgentContext {
     TextThread(TextThreadContext),
     Rules(RulesContext),
     Image(ImageContext),
+    ExtensionEntry(ExtensionEntryContext),
 }
 
 impl AgentContext {
@@ -126,6 +131,9 @@ impl AgentContext {
             }
             AgentContext::Rules(context) => AgentContextHandle::Rules(context.handle.clone()),
             AgentContext::Image(context) => AgentContextHandle::Image(context.clone()),
+            AgentContext::ExtensionEntry(context) => {
+                AgentContextHandle::ExtensionEntry(context.clone())
+            }
         }
     }
 }
@@ -527,6 +535,8 @@ impl Display for SelectionContext {
 #[derive(Debug, Clone)]
 pub struct FetchedUrlContext {
     pub url: SharedString,
+    /// The title of the fetched page, if it has one.
+    pub title: Option<SharedString>,
     /// Text contents of the fetched url. Unlike other context types, the contents of this gets
     /// populated when added rather than when sending the message. Not used by `PartialEq` or `Hash`
     /// for `AgentContextKey`.
@@ -546,6 +556,7 @@ impl FetchedUrlContext {
     pub fn lookup_key(url: SharedString) -> AgentContextKey {
         AgentContextKey(AgentContextHandle::FetchedUrl(FetchedUrlContext {
             url,
+            title: None,
             text: "".into(),
             context_id: ContextId::for_lookup(),
         }))
@@ -558,8 +569,73 @@ impl FetchedUrlContext {
 
 impl Display for FetchedUrlContext {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        // TODO: Better format - url and contents are not delimited.
-        write!(f, "{}\n{}\n", self.url, self.text)
+        // The URL is kept alongside the contents so that the model can cite it.
+        let url = escape_attribute(&self.url);
+        match &self.title {
+            Some(title) => writeln!(
+                f,
+                "<page url=\"{}\" title=\"{}\">",
+                url,
+                escape_attribute(title)
+            )?,
+            None => writeln!(f, "<page url=\"{}\">", url)?,
+        }
+        write!(f, "{}\n</page>\n", self.text)
+    }
+}
+
+/// Escapes `value` for use in a double-quoted attribute, as page titles come from untrusted
+/// pages and could otherwise close the tag early.
+fn escape_attribute(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Context contributed by an extension's context picker source.
+#[derive(Debug, Clone)]
+pub struct ExtensionEntryContext {
+    pub source_id: Arc<str>,
+    pub source_name: SharedString,
+    pub entry_id: SharedString,
+    pub label: SharedString,
+    /// Like [`FetchedUrlContext`], the contents are populated when added rather than when sending
+    /// the message. Not used by `PartialEq` or `Hash` for `AgentContextKey`.
+    pub text: SharedString,
+    pub context_id: ContextId,
+}
+
+impl ExtensionEntryContext {
+    pub fn eq_for_key(&self, other: &Self) -> bool {
+        self.source_id == other.source_id && self.entry_id == other.entry_id
+    }
+
+    pub fn hash_for_key<H: Hasher>(&self, state: &mut H) {
+        self.source_id.hash(state);
+        self.entry_id.hash(state);
+    }
+
+    pub fn lookup_key(source_id: Arc<str>, entry_id: SharedString) -> AgentContextKey {
+        AgentContextKey(AgentContextHandle::ExtensionEntry(ExtensionEntryContext {
+            source_id,
+            source_name: "".into(),
+            entry_id,
+            label: "".into(),
+            text: "".into(),
+            context_id: ContextId::for_lookup(),
+        }))
+    }
+
+    pub fn load(self) -> Task<Option<(AgentContext, Vec<Entity<Buffer>>)>> {
+        Task::ready(Some((AgentContext::ExtensionEntry(self), vec![])))
+    }
+}
+
+impl Display for ExtensionEntryContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}\n{}\n", self.source_name, self.label, self.text)
     }
 }
 
@@ -845,6 +921,7 @@ pub fn load_context(
             AgentContextHandle::TextThread(context) => context.load(cx),
             AgentContextHandle::Rules(context) => context.load(prompt_store, cx),
             AgentContextHandle::Image(context) => context.load(cx),
+            AgentContextHandle::ExtensionEntry(context) => context.load(),
         })
         .collect();
 
@@ -870,6 +947,7 @@ pub fn load_context(
         let mut thread_context = Vec::new();
         let mut text_thread_context = Vec::new();
         let mut rules_context = Vec::new();
+        let mut extension_entry_context = Vec::new();
         let mut images = Vec::new();
         for context in &contexts {
             match context {
@@ -882,6 +960,7 @@ pub fn load_context(
                 AgentContext::TextThread(context) => text_thread_context.push(context),
                 AgentContext::Rules(context) => rules_context.push(context),
                 AgentContext::Image(context) => images.extend(context.image()),
+                AgentContext::ExtensionEntry(context) => extension_entry_context.push(context),
             }
         }
 
@@ -895,6 +974,7 @@ pub fn load_context(
             && thread_context.is_empty()
             && text_thread_context.is_empty()
             && rules_context.is_empty()
+            && extension_entry_context.is_empty()
         {
             return ContextLoadResult {
                 loaded_context: LoadedContext {
@@ -957,6 +1037,15 @@ pub fn load_context(
             text.push_str("</fetched_urls>\n");
         }
 
+        if !extension_entry_context.is_empty() {
+            text.push_str("<extension_context>");
+            for context in extension_entry_context {
+                text.push('\n');
+                let _ = write!(text, "{context}");
+            }
+            text.push_str("</extension_context>\n");
+        }
+
         if !thread_context.is_empty() {
             text.push_str("<conversation_threads>");
             for context in thread_context {
@@ -1096,6 +1185,11 @@ impl PartialEq for AgentContextKey {
                     return context.eq_for_key(other_context);
                 }
             }
+            AgentContextHandle::ExtensionEntry(context) => {
+                if let AgentContextHandle::ExtensionEntry(other_context) = &other.0 {
+                    return context.eq_for_key(other_context);
+                }
+            }
         }
         false
     }
@@ -1113,6 +1207,7 @@ impl Hash for AgentContextKey {
             AgentContextHandle::TextThread(context) => context.hash_for_key(state),
             AgentContextHandle::Rules(context) => context.hash_for_key(state),
             AgentContextHandle::Image(context) => context.hash_for_key(state),
+            AgentContextHandle::ExtensionEntry(context) => context.hash_for_key(state),
         }
     }
 }
@@ -1208,6 +1303,21 @@ mod tests {
         Project::test(fs, [path!("/test").as_ref()], cx).await
     }
 
+    #[test]
+    fn test_fetched_url_attributes_are_escaped() {
+        let context = FetchedUrlContext {
+            url: "https://example.com/?a=1&b=2".into(),
+            title: Some("\"><script>alert(1)</script>".into()),
+            text: "Body".into(),
+            context_id: ContextId::zero(),
+        };
+        assert_eq!(
+            context.to_string(),
+            "<page url=\"https://example.com/?a=1&amp;b=2\" \
+             title=\"&quot;&gt;&lt;script&gt;alert(1)&lt;/script&gt;\">\nBody\n</page>\n"
+        );
+    }
+
     #[gpui::test]
     async fn test_large_file_uses_outline(cx: &mut TestAppContext) {
         init_test_settings(cx);
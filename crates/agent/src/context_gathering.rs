@@ -0,0 +1,176 @@
+use std::path::Path;
+
+use gpui::{App, Entity, Task, WeakEntity};
+use util::ResultExt as _;
+use workspace::Workspace;
+
+use crate::context_picker::add_symbol;
+use crate::context_store::ContextStore;
+
+const MAX_GATHERED_FILES: usize = 5;
+const MAX_GATHERED_SYMBOLS: usize = 5;
+
+/// The files and symbols a prompt refers to by name.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct PromptMentions {
+    paths: Vec<String>,
+    symbols: Vec<String>,
+}
+
+/// Adds the files and symbols mentioned in `prompt` to the context store, so
+/// the model doesn't need to spend tool calls locating them.
+///
+/// Large files are attached as outlines by the file context itself.
+pub fn gather_context(
+    prompt: &str,
+    workspace: Entity<Workspace>,
+    context_store: WeakEntity<ContextStore>,
+    cx: &mut App,
+) -> Task<()> {
+    let mentions = extract_mentions(prompt);
+    let project = workspace.read(cx).project().clone();
+
+    let mut file_tasks = Vec::new();
+    for path in &mentions.paths {
+        if file_tasks.len() == MAX_GATHERED_FILES {
+            break;
+        }
+
+        let Some(project_path) = project.read(cx).find_project_path(path, cx) else {
+            continue;
+        };
+        if !project
+            .read(cx)
+            .entry_for_path(&project_path, cx)
+            .is_some_and(|entry| entry.is_file())
+        {
+            continue;
+        }
+
+        let Some(task) = context_store
+            .update(cx, |context_store, cx| {
+                context_store.add_file_from_path(project_path, false, cx)
+            })
+            .log_err()
+        else {
+            return Task::ready(());
+        };
+        file_tasks.push(task);
+    }
+
+    let symbol_searches = mentions
+        .symbols
+        .into_iter()
+        .take(MAX_GATHERED_SYMBOLS)
+        .map(|name| {
+            let search = project.update(cx, |project, cx| project.symbols(&name, cx));
+            (name, search)
+        })
+        .collect::<Vec<_>>();
+
+    cx.spawn(async move |cx| {
+        for task in file_tasks {
+            task.await.log_err();
+        }
+
+        for (name, search) in symbol_searches {
+            let Some(symbols) = search.await.log_err() else {
+                continue;
+            };
+            let Some(symbol) = symbols.into_iter().find(|symbol| symbol.name == name) else {
+                continue;
+            };
+            let Ok(task) = cx.update(|cx| {
+                add_symbol(symbol, false, workspace.clone(), context_store.clone(), cx)
+            }) else {
+                return;
+            };
+            task.await.log_err();
+        }
+    })
+}
+
+fn extract_mentions(prompt: &str) -> PromptMentions {
+    let mut mentions = PromptMentions::default();
+    for word in prompt.split_whitespace() {
+        let is_quoted = word.starts_with('`');
+        let word = word
+            .trim_matches(|c: char| !(c.is_alphanumeric() || matches!(c, '_' | '/' | '.' | '-')))
+            .trim_end_matches('.');
+        if word.is_empty() {
+            continue;
+        }
+
+        if looks_like_path(word) {
+            if !mentions.paths.iter().any(|path| path == word) {
+                mentions.paths.push(word.to_string());
+            }
+            continue;
+        }
+
+        // `Thread::send_to_model` should look up `send_to_model`.
+        let Some(symbol) = word.rsplit("::").next() else {
+            continue;
+        };
+        if (is_quoted || looks_like_symbol(symbol))
+            && symbol.chars().all(|c| c.is_alphanumeric() || c == '_')
+            && !mentions.symbols.iter().any(|existing| existing == symbol)
+        {
+            mentions.symbols.push(symbol.to_string());
+        }
+    }
+    mentions
+}
+
+fn looks_like_path(word: &str) -> bool {
+    if word.contains("://") {
+        return false;
+    }
+
+    word.contains('/')
+        || Path::new(word)
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .is_some_and(|extension| {
+                !extension.is_empty()
+                    && extension.len() <= 5
+                    && extension.chars().all(|c| c.is_ascii_alphanumeric())
+            })
+}
+
+/// Plain words are far more common in prompts than identifiers, so only
+/// words that are unambiguously code (snake_case or camelCase) are searched.
+fn looks_like_symbol(word: &str) -> bool {
+    if word.len() < 3 || word.starts_with(|c: char| c.is_ascii_digit()) {
+        return false;
+    }
+
+    let is_snake_case = word.trim_matches('_').contains('_');
+    let is_camel_case =
+        word.chars().skip(1).any(|c| c.is_uppercase()) && word.chars().any(|c| c.is_lowercase());
+    is_snake_case || is_camel_case
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_mentions() {
+        let mentions = extract_mentions(
+            "Why does `Thread::send_to_model` ignore src/thread.rs? See ContextStore and \
+             the `render` fn in main.rs (https://zed.dev/docs). Also check main.rs.",
+        );
+        assert_eq!(
+            mentions,
+            PromptMentions {
+                paths: vec!["src/thread.rs".to_string(), "main.rs".to_string()],
+                symbols: vec![
+                    "send_to_model".to_string(),
+                    "ContextStore".to_string(),
+                    "render".to_string(),
+                ],
+            }
+        );
+    }
+}
@@ -1,4 +1,5 @@
 mod completion_provider;
+mod extension_context_picker;
 mod fetch_context_picker;
 mod file_context_picker;
 mod rules_context_picker;
@@ -13,7 +14,11 @@ use anyhow::{Result, anyhow};
 pub use completion_provider::ContextPickerCompletionProvider;
 use editor::display_map::{Crease, CreaseId, CreaseMetadata, FoldId};
 use editor::{Anchor, AnchorRangeExt as _, Editor, ExcerptId, FoldPlaceholder, ToOffset};
-use fetch_context_picker::FetchContextPicker;
+use extension_context_picker::{
+    ExtensionContextPicker, ExtensionContextPickerSource, ExtensionContextPickerSourceRegistry,
+};
+pub(crate) use fetch_context_picker::FetchedPage;
+use fetch_context_picker::{FetchContextPicker, fetch_url_content};
 use file_context_picker::FileContextPicker;
 use file_context_picker::render_file_context_entry;
 use gpui::{
@@ -27,6 +32,7 @@ use project::{Entry, ProjectPath};
 use prompt_store::{PromptStore, UserPromptId};
 use rules_context_picker::{RulesContextEntry, RulesContextPicker};
 use symbol_context_picker::SymbolContextPicker;
+pub(crate) use symbol_context_picker::add_symbol;
 use thread_context_picker::{
     ThreadContextEntry, ThreadContextPicker, render_thread_context_entry, unordered_thread_entries,
 };
@@ -37,7 +43,7 @@ use uuid::Uuid;
 use workspace::{Workspace, notifications::NotifyResultExt};
 
 use crate::AgentPanel;
-use crate::context::RULES_ICON;
+use crate::context::{ContextKind, RULES_ICON};
 use crate::context_store::ContextStore;
 use crate::thread::ThreadId;
 use crate::thread_store::{TextThreadStore, ThreadStore};
@@ -83,24 +89,28 @@ enum ContextPickerMode {
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum ContextPickerAction {
     AddSelections,
+    PasteUrl,
 }
 
 impl ContextPickerAction {
     pub fn keyword(&self) -> &'static str {
         match self {
             Self::AddSelections => "selection",
+            Self::PasteUrl => "paste-url",
         }
     }
 
     pub fn label(&self) -> &'static str {
         match self {
             Self::AddSelections => "Selection",
+            Self::PasteUrl => "Paste URL",
         }
     }
 
     pub fn icon(&self) -> IconName {
         match self {
             Self::AddSelections => IconName::Context,
+            Self::PasteUrl => IconName::Link,
         }
     }
 }
@@ -160,6 +170,7 @@ enum ContextPickerState {
     Fetch(Entity<FetchContextPicker>),
     Thread(Entity<ThreadContextPicker>),
     Rules(Entity<RulesContextPicker>),
+    Extension(Entity<ExtensionContextPicker>),
 }
 
 pub(super) struct ContextPicker {
@@ -172,6 +183,10 @@ pub(super) struct ContextPicker {
     _subscriptions: Vec<Subscription>,
 }
 
+pub(crate) fn init(cx: &mut App) {
+    extension_context_picker::init(cx);
+}
+
 impl ContextPicker {
     pub fn new(
         workspace: WeakEntity<Workspace>,
@@ -247,6 +262,9 @@ impl ContextPicker {
                     )
                 })
                 .unwrap_or_default();
+            let extension_sources = ExtensionContextPickerSourceRegistry::try_global(cx)
+                .map(|registry| registry.sources())
+                .unwrap_or_default();
 
             menu.when(has_recent, |menu| {
                 menu.custom_row(|_, _| {
@@ -273,6 +291,19 @@ impl ContextPicker {
                         context_picker.update(cx, |this, cx| this.select_entry(entry, window, cx))
                     })
             }))
+            .extend(extension_sources.into_iter().map(|source| {
+                let context_picker = context_picker.clone();
+
+                ContextMenuEntry::new(source.name.clone())
+                    .icon(ContextKind::ExtensionEntry.icon())
+                    .icon_size(IconSize::XSmall)
+                    .icon_color(Color::Muted)
+                    .handler(move |window, cx| {
+                        context_picker.update(cx, |this, cx| {
+                            this.select_extension_source(source.clone(), window, cx)
+                        })
+                    })
+            }))
             .keep_open_on_confirm(true)
         });
 
@@ -372,6 +403,18 @@ impl ContextPicker {
                         add_selections_as_context(&context_store, &workspace, cx);
                     }
 
+                    cx.emit(DismissEvent);
+                }
+                ContextPickerAction::PasteUrl => {
+                    if let Some((context_store, workspace)) =
+                        self.context_store.upgrade().zip(self.workspace.upgrade())
+                    {
+                        if let Some(url) = clipboard_url(cx) {
+                            add_url_as_context(url, context_store, &workspace, cx)
+                                .detach_and_log_err(cx);
+                        }
+                    }
+
                     cx.emit(DismissEvent);
                 }
             },
@@ -381,6 +424,27 @@ impl ContextPicker {
         cx.focus_self(window);
     }
 
+    fn select_extension_source(
+        &mut self,
+        source: ExtensionContextPickerSource,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let context_picker = cx.entity().downgrade();
+        self.mode = ContextPickerState::Extension(cx.new(|cx| {
+            ExtensionContextPicker::new(
+                source,
+                context_picker,
+                self.context_store.clone(),
+                window,
+                cx,
+            )
+        }));
+
+        cx.notify();
+        cx.focus_self(window);
+    }
+
     pub fn select_first(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         match &self.mode {
             ContextPickerState::Default(entity) => entity.update(cx, |entity, cx| {
@@ -545,6 +609,7 @@ impl ContextPicker {
             ContextPickerState::Fetch(entity) => entity.update(cx, |_, cx| cx.notify()),
             ContextPickerState::Thread(entity) => entity.update(cx, |_, cx| cx.notify()),
             ContextPickerState::Rules(entity) => entity.update(cx, |_, cx| cx.notify()),
+            ContextPickerState::Extension(entity) => entity.update(cx, |_, cx| cx.notify()),
         }
     }
 }
@@ -560,6 +625,7 @@ impl Focusable for ContextPicker {
             ContextPickerState::Fetch(fetch_picker) => fetch_picker.focus_handle(cx),
             ContextPickerState::Thread(thread_picker) => thread_picker.focus_handle(cx),
             ContextPickerState::Rules(user_rules_picker) => user_rules_picker.focus_handle(cx),
+            ContextPickerState::Extension(extension_picker) => extension_picker.focus_handle(cx),
         }
     }
 }
@@ -578,6 +644,9 @@ impl Render for ContextPicker {
                 ContextPickerState::Rules(user_rules_picker) => {
                     parent.child(user_rules_picker.clone())
                 }
+                ContextPickerState::Extension(extension_picker) => {
+                    parent.child(extension_picker.clone())
+                }
             })
     }
 }
@@ -621,11 +690,46 @@ fn available_context_picker_entries(
         entries.push(ContextPickerEntry::Mode(ContextPickerMode::Rules));
     }
 
+    if clipboard_url(cx).is_some() {
+        entries.push(ContextPickerEntry::Action(ContextPickerAction::PasteUrl));
+    }
+
     entries.push(ContextPickerEntry::Mode(ContextPickerMode::Fetch));
 
     entries
 }
 
+/// Returns the URL on the clipboard, if that's all the clipboard contains.
+fn clipboard_url(cx: &App) -> Option<String> {
+    let text = cx.read_from_clipboard()?.text()?;
+    let text = text.trim();
+    let is_url = (text.starts_with("https://") || text.starts_with("http://"))
+        && !text.contains(char::is_whitespace);
+    is_url.then(|| text.to_string())
+}
+
+/// Fetches the page at `url`, and adds its main content to the context.
+fn add_url_as_context(
+    url: String,
+    context_store: Entity<ContextStore>,
+    workspace: &Entity<Workspace>,
+    cx: &mut App,
+) -> Task<Result<()>> {
+    if context_store.read(cx).includes_url(&url) {
+        return Task::ready(Ok(()));
+    }
+
+    let http_client = workspace.read(cx).client().http_client();
+    cx.spawn(async move |cx| {
+        let page = cx
+            .background_spawn(fetch_url_content(http_client, url.clone()))
+            .await?;
+        context_store.update(cx, |context_store, cx| {
+            context_store.add_fetched_url(url, page, cx);
+        })
+    })
+}
+
 fn recent_context_picker_entries(
     context_store: Entity<ContextStore>,
     thread_store: Option<WeakEntity<ThreadStore>>,
@@ -939,7 +1043,7 @@ impl MentionLink {
 
     pub fn for_thread(thread: &ThreadContextEntry) -> String {
         match thread {
-            ThreadContextEntry::Thread { id, title } => {
+            ThreadContextEntry::Thread { id, title, .. } => {
                 format!("[@{}]({}:{})", title, Self::THREAD, id)
             }
             ThreadContextEntry::Context { path, title } => {
@@ -14,6 +14,7 @@ use http_client::HttpClientWithUrl;
 use itertools::Itertools;
 use language::{Buffer, CodeLabel, HighlightId};
 use lsp::CompletionContext;
+use project::lsp_store::CompletionDocumentation;
 use project::{Completion, CompletionIntent, CompletionResponse, ProjectPath, Symbol, WorktreeId};
 use prompt_store::PromptStore;
 use rope::Point;
@@ -35,7 +36,8 @@ use super::symbol_context_picker::search_symbols;
 use super::thread_context_picker::{ThreadContextEntry, ThreadMatch, search_threads};
 use super::{
     ContextPickerAction, ContextPickerEntry, ContextPickerMode, MentionLink, RecentEntry,
-    available_context_picker_entries, recent_context_picker_entries, selection_ranges,
+    available_context_picker_entries, clipboard_url, recent_context_picker_entries,
+    selection_ranges,
 };
 
 pub(crate) enum Match {
@@ -293,6 +295,18 @@ impl ContextPickerCompletionProvider {
                 // inserted
                 confirm: Some(Arc::new(|_, _, _| true)),
             }),
+            ContextPickerEntry::Action(ContextPickerAction::PasteUrl) => {
+                let url = clipboard_url(cx)?;
+                let http_client = workspace.read(cx).client().http_client();
+                Some(Self::completion_for_fetch(
+                    source_range,
+                    url.into(),
+                    excerpt_id,
+                    editor,
+                    context_store,
+                    http_client,
+                ))
+            }
             ContextPickerEntry::Action(action) => {
                 let (new_text, on_action) = match action {
                     ContextPickerAction::AddSelections => {
@@ -389,6 +403,7 @@ impl ContextPickerCompletionProvider {
 
                         (new_text, callback)
                     }
+                    ContextPickerAction::PasteUrl => return None,
                 };
 
                 Some(Completion {
@@ -429,7 +444,10 @@ impl ContextPickerCompletionProvider {
             replace_range: source_range.clone(),
             new_text,
             label: CodeLabel::plain(thread_entry.title().to_string(), None),
-            documentation: None,
+            documentation: thread_entry
+                .description()
+                .cloned()
+                .map(CompletionDocumentation::SingleLine),
             insert_text_mode: None,
             source: project::CompletionSource::Custom,
             icon_path: Some(icon_for_completion.path().into()),
@@ -0,0 +1,283 @@
+use std::sync::Arc;
+
+use collections::BTreeMap;
+use extension::{
+    ContextPickerEntry, Extension, ExtensionContextPickerSourceProxy, ExtensionHostProxy,
+};
+use gpui::{App, DismissEvent, Entity, FocusHandle, Focusable, Global, Task, WeakEntity};
+use parking_lot::RwLock;
+use picker::{Picker, PickerDelegate};
+use ui::{ListItem, prelude::*};
+
+use crate::context::ContextKind;
+use crate::context_picker::ContextPicker;
+use crate::context_store::ContextStore;
+
+pub(crate) fn init(cx: &mut App) {
+    let registry = ExtensionContextPickerSourceRegistry::default_global(cx);
+    ExtensionHostProxy::default_global(cx)
+        .register_context_picker_source_proxy(ContextPickerSourceRegistryProxy { registry });
+}
+
+/// A source of context picker entries contributed by an extension.
+#[derive(Clone)]
+pub struct ExtensionContextPickerSource {
+    pub id: Arc<str>,
+    pub name: SharedString,
+    extension: Arc<dyn Extension>,
+}
+
+#[derive(Default)]
+struct GlobalExtensionContextPickerSourceRegistry(Arc<ExtensionContextPickerSourceRegistry>);
+
+impl Global for GlobalExtensionContextPickerSourceRegistry {}
+
+#[derive(Default)]
+pub struct ExtensionContextPickerSourceRegistry {
+    sources: RwLock<BTreeMap<Arc<str>, ExtensionContextPickerSource>>,
+}
+
+impl ExtensionContextPickerSourceRegistry {
+    /// Returns the global [`ExtensionContextPickerSourceRegistry`].
+    ///
+    /// Inserts a default [`ExtensionContextPickerSourceRegistry`] if one does not yet exist.
+    pub fn default_global(cx: &mut App) -> Arc<Self> {
+        cx.default_global::<GlobalExtensionContextPickerSourceRegistry>()
+            .0
+            .clone()
+    }
+
+    pub fn try_global(cx: &App) -> Option<Arc<Self>> {
+        cx.try_global::<GlobalExtensionContextPickerSourceRegistry>()
+            .map(|registry| registry.0.clone())
+    }
+
+    /// Returns the registered sources, ordered by name.
+    pub fn sources(&self) -> Vec<ExtensionContextPickerSource> {
+        let mut sources = self.sources.read().values().cloned().collect::<Vec<_>>();
+        sources.sort_by(|a, b| a.name.cmp(&b.name));
+        sources
+    }
+}
+
+struct ContextPickerSourceRegistryProxy {
+    registry: Arc<ExtensionContextPickerSourceRegistry>,
+}
+
+impl ExtensionContextPickerSourceProxy for ContextPickerSourceRegistryProxy {
+    fn register_context_picker_source(
+        &self,
+        extension: Arc<dyn Extension>,
+        source_id: Arc<str>,
+        name: SharedString,
+    ) {
+        self.registry.sources.write().insert(
+            source_id.clone(),
+            ExtensionContextPickerSource {
+                id: source_id,
+                name,
+                extension,
+            },
+        );
+    }
+
+    fn unregister_context_picker_source(&self, source_id: Arc<str>) {
+        self.registry.sources.write().remove(&source_id);
+    }
+}
+
+pub struct ExtensionContextPicker {
+    picker: Entity<Picker<ExtensionContextPickerDelegate>>,
+}
+
+impl ExtensionContextPicker {
+    pub fn new(
+        source: ExtensionContextPickerSource,
+        context_picker: WeakEntity<ContextPicker>,
+        context_store: WeakEntity<ContextStore>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let delegate = ExtensionContextPickerDelegate::new(source, context_picker, context_store);
+        let picker = cx.new(|cx| Picker::uniform_list(delegate, window, cx));
+
+        Self { picker }
+    }
+}
+
+impl Focusable for ExtensionContextPicker {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for ExtensionContextPicker {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        self.picker.clone()
+    }
+}
+
+pub struct ExtensionContextPickerDelegate {
+    source: ExtensionContextPickerSource,
+    context_picker: WeakEntity<ContextPicker>,
+    context_store: WeakEntity<ContextStore>,
+    matches: Vec<ContextPickerEntry>,
+    selected_index: usize,
+}
+
+impl ExtensionContextPickerDelegate {
+    pub fn new(
+        source: ExtensionContextPickerSource,
+        context_picker: WeakEntity<ContextPicker>,
+        context_store: WeakEntity<ContextStore>,
+    ) -> Self {
+        Self {
+            source,
+            context_picker,
+            context_store,
+            matches: Vec::new(),
+            selected_index: 0,
+        }
+    }
+}
+
+impl PickerDelegate for ExtensionContextPickerDelegate {
+    type ListItem = ListItem;
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _cx: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        format!("Search {}…", self.source.name).into()
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let extension = self.source.extension.clone();
+        let source_id = self.source.id.clone();
+        cx.spawn_in(window, async move |this, cx| {
+            let matches = match extension.context_picker_entries(source_id, query).await {
+                Ok(matches) => matches,
+                Err(error) => {
+                    log::error!("failed to fetch context picker entries: {error:#}");
+                    Vec::new()
+                }
+            };
+            this.update(cx, |this, cx| {
+                this.delegate.matches = matches;
+                this.delegate.selected_index = 0;
+                cx.notify();
+            })
+            .ok();
+        })
+    }
+
+    fn confirm(&mut self, _secondary: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(entry) = self.matches.get(self.selected_index).cloned() else {
+            return;
+        };
+
+        let source = self.source.clone();
+        cx.spawn_in(window, async move |this, cx| {
+            let text = source
+                .extension
+                .context_picker_entry_content(source.id.clone(), entry.id.clone())
+                .await?;
+
+            this.update(cx, |this, cx| {
+                this.delegate.context_store.update(cx, |context_store, cx| {
+                    context_store.add_extension_entry(
+                        source.id,
+                        source.name,
+                        entry.id.into(),
+                        entry.label.into(),
+                        text,
+                        cx,
+                    )
+                })
+            })??;
+
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn dismissed(&mut self, _window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        self.context_picker
+            .update(cx, |_, cx| {
+                cx.emit(DismissEvent);
+            })
+            .ok();
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let entry = self.matches.get(ix)?;
+        let added = self.context_store.upgrade().map_or(false, |context_store| {
+            context_store
+                .read(cx)
+                .includes_extension_entry(self.source.id.clone(), entry.id.clone().into())
+        });
+
+        Some(
+            ListItem::new(ix).inset(true).toggle_state(selected).child(
+                h_flex()
+                    .gap_1p5()
+                    .w_full()
+                    .justify_between()
+                    .child(
+                        h_flex()
+                            .gap_1p5()
+                            .max_w_72()
+                            .child(
+                                Icon::new(ContextKind::ExtensionEntry.icon())
+                                    .size(IconSize::XSmall)
+                                    .color(Color::Muted),
+                            )
+                            .child(Label::new(entry.label.clone()).truncate())
+                            .children(entry.description.clone().map(|description| {
+                                Label::new(description)
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted)
+                                    .truncate()
+                            })),
+                    )
+                    .when(added, |el| {
+                        el.child(
+                            h_flex()
+                                .gap_1()
+                                .child(
+                                    Icon::new(IconName::Check)
+                                        .size(IconSize::Small)
+                                        .color(Color::Success),
+                                )
+                                .child(Label::new("Added").size(LabelSize::Small)),
+                        )
+                    }),
+            ),
+        )
+    }
+}
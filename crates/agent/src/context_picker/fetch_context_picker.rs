@@ -1,12 +1,15 @@
 use std::cell::RefCell;
 use std::rc::Rc;
-use std::sync::Arc;
+use std::sync::{Arc, LazyLock};
+use std::time::{Duration, Instant};
 
 use anyhow::{Context as _, Result, bail};
+use collections::HashMap;
 use futures::AsyncReadExt as _;
 use gpui::{App, DismissEvent, Entity, FocusHandle, Focusable, Task, WeakEntity};
-use html_to_markdown::{TagHandler, convert_html_to_markdown, markdown};
+use html_to_markdown::{TagHandler, convert_html_to_readable_markdown, markdown};
 use http_client::{AsyncBody, HttpClientWithUrl};
+use parking_lot::Mutex;
 use picker::{Picker, PickerDelegate};
 use ui::{Context, ListItem, Window, prelude::*};
 use workspace::Workspace;
@@ -74,17 +77,91 @@ impl FetchContextPickerDelegate {
     }
 }
 
+/// How long fetched pages are reused for, instead of being fetched again.
+const FETCHED_PAGE_TTL: Duration = Duration::from_secs(15 * 60);
+
+/// The main content of a fetched page.
+#[derive(Debug, Clone)]
+pub(crate) struct FetchedPage {
+    pub title: Option<SharedString>,
+    pub text: SharedString,
+}
+
+/// How many fetched pages are kept, evicting the least recently used ones beyond that.
+const MAX_FETCHED_PAGES: usize = 32;
+
+struct CachedPage {
+    fetched_at: Instant,
+    /// When the page was last used, in terms of [`FetchedPages::uses`].
+    last_used: usize,
+    page: FetchedPage,
+}
+
+#[derive(Default)]
+struct FetchedPages {
+    pages: HashMap<String, CachedPage>,
+    uses: usize,
+}
+
+impl FetchedPages {
+    fn get(&mut self, url: &str) -> Option<FetchedPage> {
+        let cached = self.pages.get_mut(url)?;
+        if cached.fetched_at.elapsed() >= FETCHED_PAGE_TTL {
+            self.pages.remove(url);
+            return None;
+        }
+        self.uses += 1;
+        cached.last_used = self.uses;
+        Some(cached.page.clone())
+    }
+
+    fn insert(&mut self, url: String, page: FetchedPage) {
+        self.pages
+            .retain(|_, cached| cached.fetched_at.elapsed() < FETCHED_PAGE_TTL);
+        if self.pages.len() >= MAX_FETCHED_PAGES && !self.pages.contains_key(&url) {
+            let least_recently_used = self
+                .pages
+                .iter()
+                .min_by_key(|(_, cached)| cached.last_used)
+                .map(|(url, _)| url.clone());
+            if let Some(least_recently_used) = least_recently_used {
+                self.pages.remove(&least_recently_used);
+            }
+        }
+        self.uses += 1;
+        self.pages.insert(
+            url,
+            CachedPage {
+                fetched_at: Instant::now(),
+                last_used: self.uses,
+                page,
+            },
+        );
+    }
+}
+
+static FETCHED_PAGES: LazyLock<Mutex<FetchedPages>> = LazyLock::new(Default::default);
+
 pub(crate) async fn fetch_url_content(
     http_client: Arc<HttpClientWithUrl>,
     url: String,
-) -> Result<String> {
+) -> Result<FetchedPage> {
     let url = if !url.starts_with("https://") && !url.starts_with("http://") {
         format!("https://{url}")
     } else {
         url
     };
 
-    let mut response = http_client.get(&url, AsyncBody::default(), true).await?;
+    if let Some(page) = FETCHED_PAGES.lock().get(&url) {
+        return Ok(page);
+    }
+    let page = fetch_page(&http_client, &url).await?;
+    FETCHED_PAGES.lock().insert(url, page.clone());
+    Ok(page)
+}
+
+async fn fetch_page(http_client: &HttpClientWithUrl, url: &str) -> Result<FetchedPage> {
+    let mut response = http_client.get(url, AsyncBody::default(), true).await?;
 
     let mut body = Vec::new();
     response
@@ -136,16 +213,23 @@ pub(crate) async fn fetch_url_content(
                 handlers.push(Rc::new(RefCell::new(markdown::CodeHandler)));
             }
 
-            convert_html_to_markdown(&body[..], &mut handlers)
+            let content = convert_html_to_readable_markdown(&body[..], &mut handlers)?;
+            Ok(FetchedPage {
+                title: content.title.map(SharedString::from),
+                text: content.markdown.into(),
+            })
         }
-        ContentType::Plaintext => Ok(std::str::from_utf8(&body)?.to_owned()),
+        ContentType::Plaintext => Ok(FetchedPage {
+            title: None,
+            text: std::str::from_utf8(&body)?.to_owned().into(),
+        }),
         ContentType::Json => {
             let json: serde_json::Value = serde_json::from_slice(&body)?;
 
-            Ok(format!(
-                "```json\n{}\n```",
-                serde_json::to_string_pretty(&json)?
-            ))
+            Ok(FetchedPage {
+                title: None,
+                text: format!("```json\n{}\n```", serde_json::to_string_pretty(&json)?).into(),
+            })
         }
     }
 }
@@ -196,13 +280,13 @@ impl PickerDelegate for FetchContextPickerDelegate {
         let http_client = workspace.read(cx).client().http_client();
         let url = self.url.clone();
         cx.spawn_in(window, async move |this, cx| {
-            let text = cx
+            let page = cx
                 .background_spawn(fetch_url_content(http_client, url.clone()))
                 .await?;
 
             this.update(cx, |this, cx| {
                 this.delegate.context_store.update(cx, |context_store, cx| {
-                    context_store.add_fetched_url(url, text, cx)
+                    context_store.add_fetched_url(url, page, cx)
                 })
             })??;
 
@@ -250,3 +334,31 @@ impl PickerDelegate for FetchContextPickerDelegate {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fetched_pages_are_bounded() {
+        let page = |text: &str| FetchedPage {
+            title: None,
+            text: SharedString::from(text.to_string()),
+        };
+        let mut cache = FetchedPages::default();
+        for ix in 0..MAX_FETCHED_PAGES {
+            cache.insert(format!("https://{ix}.com"), page("old"));
+        }
+        // Using the first page makes the second one the least recently used.
+        assert!(cache.get("https://0.com").is_some());
+
+        cache.insert("https://new.com".into(), page("new"));
+        assert_eq!(cache.pages.len(), MAX_FETCHED_PAGES);
+        assert!(cache.get("https://0.com").is_some());
+        assert!(cache.get("https://1.com").is_none());
+        assert_eq!(
+            cache.get("https://new.com").unwrap().text.to_string(),
+            "new"
+        );
+    }
+}
@@ -55,6 +55,7 @@ pub enum ThreadContextEntry {
     Thread {
         id: ThreadId,
         title: SharedString,
+        description: Option<SharedString>,
     },
     Context {
         path: Arc<Path>,
@@ -69,6 +70,13 @@ impl ThreadContextEntry {
             Self::Context { title, .. } => title,
         }
     }
+
+    pub fn description(&self) -> Option<&SharedString> {
+        match self {
+            Self::Thread { description, .. } => description.as_ref(),
+            Self::Context { .. } => None,
+        }
+    }
 }
 
 pub struct ThreadContextPickerDelegate {
@@ -255,7 +263,15 @@ pub fn render_thread_context_entry(
                         .size(IconSize::XSmall)
                         .color(Color::Muted),
                 )
-                .child(Label::new(entry.title().clone()).truncate()),
+                .child(Label::new(entry.title().clone()).truncate())
+                .when_some(entry.description(), |this, description| {
+                    this.child(
+                        Label::new(description.clone())
+                            .size(LabelSize::Small)
+                            .color(Color::Muted)
+                            .truncate(),
+                    )
+                }),
         )
         .when(is_added, |el| {
             el.child(
@@ -288,6 +304,7 @@ pub fn unordered_thread_entries(
             ThreadContextEntry::Thread {
                 id: thread.id.clone(),
                 title: thread.summary.clone(),
+                description: thread.description.clone(),
             },
         )
     });
@@ -333,7 +350,13 @@ pub(crate) fn search_threads(
             let candidates = threads
                 .iter()
                 .enumerate()
-                .map(|(id, (_, thread))| StringMatchCandidate::new(id, &thread.title()))
+                .map(|(id, (_, thread))| match thread.description() {
+                    Some(description) => StringMatchCandidate::new(
+                        id,
+                        &format!("{} {}", thread.title(), description),
+                    ),
+                    None => StringMatchCandidate::new(id, &thread.title()),
+                })
                 .collect::<Vec<_>>();
             let matches = fuzzy::match_strings(
                 &candidates,
@@ -0,0 +1,215 @@
+use std::cell::Cell;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use anyhow::Result;
+use collections::HashMap;
+use gpui::{App, Entity, Task};
+use language_model::{EmbeddingModel, LanguageModelRegistry};
+use project::{Project, ProjectPath};
+use semantic_index::{LanguageModelEmbeddingProvider, SemanticDb};
+use util::ResultExt as _;
+
+/// How many files are suggested as context for a prompt.
+const MAX_RANKED_FILES: usize = 5;
+
+/// Search results are chunks rather than files, so we fetch more of them than
+/// the number of files we suggest.
+const MAX_SEARCH_RESULTS: usize = 32;
+
+/// Installs the [`SemanticDb`] global once an authenticated language model provider offers an
+/// embedding model, keeping its index in `db_dir`.
+pub(crate) fn init(db_dir: PathBuf, cx: &mut App) {
+    let installing = Rc::new(Cell::new(false));
+    install_semantic_db(&db_dir, &installing, cx);
+
+    // Providers usually authenticate after startup, so keep checking until one can embed.
+    let registry = LanguageModelRegistry::global(cx);
+    cx.subscribe(
+        &registry,
+        move |_, event: &language_model::Event, cx| match event {
+            language_model::Event::ProviderStateChanged
+            | language_model::Event::AddedProvider(_) => {
+                install_semantic_db(&db_dir, &installing, cx)
+            }
+            _ => {}
+        },
+    )
+    .detach();
+}
+
+fn install_semantic_db(db_dir: &PathBuf, installing: &Rc<Cell<bool>>, cx: &mut App) {
+    if installing.get() || cx.has_global::<SemanticDb>() {
+        return;
+    }
+    let Some(model) = embedding_model(cx) else {
+        return;
+    };
+
+    // Embeddings from different models can't be compared, so each gets its own index.
+    let db_name = format!("{}-{}", model.provider_id().0, model.id().0).replace(
+        |c: char| !c.is_ascii_alphanumeric() && c != '-' && c != '.',
+        "_",
+    );
+    let db_path = db_dir.join(db_name);
+    installing.set(true);
+    let installing = installing.clone();
+    cx.spawn(async move |cx| {
+        let provider = Arc::new(LanguageModelEmbeddingProvider::new(model));
+        let db = SemanticDb::new(db_path, provider, cx).await.log_err();
+        cx.update(|cx| match db {
+            Some(db) => cx.set_global(db),
+            None => installing.set(false),
+        })
+        .ok();
+    })
+    .detach();
+}
+
+fn embedding_model(cx: &App) -> Option<Arc<dyn EmbeddingModel>> {
+    let registry = LanguageModelRegistry::read_global(cx);
+    registry.available_embedding_models(cx).find(|model| {
+        registry
+            .provider(&model.provider_id())
+            .is_some_and(|provider| provider.is_authenticated(cx))
+    })
+}
+
+/// Ranks the project's files by their semantic similarity to `query`, returning
+/// the most relevant ones.
+///
+/// Returns no files until an embedding model is available. Projects that were opened before
+/// then start being indexed on their first query.
+pub fn rank_context_files(
+    query: String,
+    project: Entity<Project>,
+    cx: &mut App,
+) -> Task<Result<Vec<ProjectPath>>> {
+    if !cx.has_global::<SemanticDb>() {
+        return Task::ready(Ok(Vec::new()));
+    }
+    let project_index = cx.update_global(|db: &mut SemanticDb, cx| {
+        db.project_index(project.clone(), cx)
+            .unwrap_or_else(|| db.create_project_index(project, cx))
+    });
+
+    let search = project_index
+        .read(cx)
+        .search(vec![query], MAX_SEARCH_RESULTS, cx);
+    cx.spawn(async move |cx| {
+        let results = search.await?;
+        let scored_paths = cx.update(|cx| {
+            results
+                .into_iter()
+                .map(|result| {
+                    let project_path = ProjectPath {
+                        worktree_id: result.worktree.read(cx).id(),
+                        path: result.path,
+                    };
+                    (project_path, result.score)
+                })
+                .collect::<Vec<_>>()
+        })?;
+        Ok(top_files(scored_paths, MAX_RANKED_FILES))
+    })
+}
+
+/// Scores each file by its best matching chunk, so that a file isn't ranked
+/// highly just because it's long.
+fn top_files(scored_paths: Vec<(ProjectPath, f32)>, limit: usize) -> Vec<ProjectPath> {
+    let mut best_scores = HashMap::<ProjectPath, f32>::default();
+    for (project_path, score) in scored_paths {
+        let best_score = best_scores.entry(project_path).or_insert(score);
+        *best_score = best_score.max(score);
+    }
+
+    let mut files = best_scores.into_iter().collect::<Vec<_>>();
+    files.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+    files
+        .into_iter()
+        .take(limit)
+        .map(|(project_path, _)| project_path)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use feature_flags::FeatureFlagAppExt as _;
+    use fs::FakeFs;
+    use gpui::TestAppContext;
+    use project::WorktreeId;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use std::path::Path;
+    use util::path;
+
+    #[gpui::test]
+    async fn test_rank_context_files(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            language::init(cx);
+            Project::init_settings(cx);
+            // Indexing is staff-flagged.
+            cx.update_flags(true, vec![]);
+            LanguageModelRegistry::test(cx);
+        });
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/project"),
+            json!({
+                "parser.rs": "fn parse(input: &str) -> Ast { todo!() }",
+                "renderer.rs": "fn render(ast: &Ast) -> String { todo!() }",
+            }),
+        )
+        .await;
+        let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+
+        let db_dir = tempfile::tempdir().unwrap();
+        cx.update(|cx| init(db_dir.path().into(), cx));
+        cx.run_until_parked();
+        assert!(cx.update(|cx| cx.has_global::<SemanticDb>()));
+
+        // The project was opened before the database existed, so the first query starts
+        // indexing it.
+        cx.update(|cx| rank_context_files("parse".into(), project.clone(), cx))
+            .await
+            .unwrap();
+        cx.run_until_parked();
+
+        let files = cx
+            .update(|cx| rank_context_files("parse the input".into(), project.clone(), cx))
+            .await
+            .unwrap();
+        let mut paths = files
+            .iter()
+            .map(|project_path| project_path.path.to_string_lossy().into_owned())
+            .collect::<Vec<_>>();
+        paths.sort();
+        assert_eq!(paths, ["parser.rs", "renderer.rs"]);
+    }
+
+    #[test]
+    fn test_top_files() {
+        let path = |path: &str| ProjectPath {
+            worktree_id: WorktreeId::from_usize(1),
+            path: Arc::from(Path::new(path)),
+        };
+
+        let files = top_files(
+            vec![
+                (path("a.rs"), 0.5),
+                (path("b.rs"), 0.7),
+                (path("a.rs"), 0.9),
+                (path("c.rs"), 0.6),
+                (path("b.rs"), 0.1),
+            ],
+            2,
+        );
+        assert_eq!(files, vec![path("a.rs"), path("b.rs")]);
+    }
+}
@@ -4,12 +4,12 @@ use std::sync::Arc;
 
 use anyhow::{Context as _, Result, anyhow};
 use assistant_context_editor::AssistantContext;
-use collections::{HashSet, IndexSet};
+use collections::{HashMap, HashSet, IndexSet};
 use futures::{self, FutureExt};
-use gpui::{App, Context, Entity, EventEmitter, Image, SharedString, Task, WeakEntity};
+use gpui::{App, Context, Entity, EntityId, EventEmitter, Image, SharedString, Task, WeakEntity};
 use language::{Buffer, File as _};
 use language_model::LanguageModelImage;
-use project::image_store::is_image_file;
+use project::image_store::{create_gpui_image, is_image_file};
 use project::{Project, ProjectItem, ProjectPath, Symbol};
 use prompt_store::UserPromptId;
 use ref_cast::RefCast as _;
@@ -17,10 +17,11 @@ use text::{Anchor, OffsetRangeExt};
 
 use crate::ThreadStore;
 use crate::context::{
-    AgentContextHandle, AgentContextKey, ContextId, DirectoryContextHandle, FetchedUrlContext,
-    FileContextHandle, ImageContext, RulesContextHandle, SelectionContextHandle,
+    AgentContextHandle, AgentContextKey, ContextId, DirectoryContextHandle, ExtensionEntryContext,
+    FetchedUrlContext, FileContextHandle, ImageContext, RulesContextHandle, SelectionContextHandle,
     SymbolContextHandle, TextThreadContextHandle, ThreadContextHandle,
 };
+use crate::context_picker::FetchedPage;
 use crate::context_strip::SuggestedContext;
 use crate::thread::{MessageId, Thread, ThreadId};
 
@@ -31,6 +32,10 @@ pub struct ContextStore {
     context_set: IndexSet<AgentContextKey>,
     context_thread_ids: HashSet<ThreadId>,
     context_text_thread_paths: HashSet<Arc<Path>>,
+    /// Buffer versions of file context as of the last message it was sent with.
+    sent_file_versions: HashMap<EntityId, clock::Global>,
+    /// Context that was already sent, but should be sent again with the next message.
+    refreshed_context: HashSet<AgentContextKey>,
 }
 
 pub enum ContextStoreEvent {
@@ -51,6 +56,8 @@ impl ContextStore {
             context_set: IndexSet::default(),
             context_thread_ids: HashSet::default(),
             context_text_thread_paths: HashSet::default(),
+            sent_file_versions: HashMap::default(),
+            refreshed_context: HashSet::default(),
         }
     }
 
@@ -61,6 +68,8 @@ impl ContextStore {
     pub fn clear(&mut self, cx: &mut Context<Self>) {
         self.context_set.clear();
         self.context_thread_ids.clear();
+        self.sent_file_versions.clear();
+        self.refreshed_context.clear();
         cx.notify();
     }
 
@@ -82,11 +91,67 @@ impl ContextStore {
             .collect::<HashSet<_>>();
         self.context_set
             .iter()
-            .filter(|context| !existing_context.contains(context))
+            .filter(|context| {
+                !existing_context.contains(context) || self.refreshed_context.contains(context)
+            })
             .map(|entry| entry.0.clone())
             .collect::<Vec<_>>()
     }
 
+    /// Records the buffer versions of the attached files once they have been sent, so that later
+    /// edits to them can be detected.
+    pub fn context_sent(&mut self, cx: &mut Context<Self>) {
+        self.refreshed_context.clear();
+        for context in &self.context_set {
+            if let AgentContextHandle::File(file_context) = context.as_ref() {
+                self.sent_file_versions.insert(
+                    file_context.buffer.entity_id(),
+                    file_context.buffer.read(cx).version(),
+                );
+            }
+        }
+        cx.notify();
+    }
+
+    /// Returns the attached files whose buffers changed since they were last sent, and so won't
+    /// be reflected in the thread unless they're refreshed.
+    pub fn stale_file_contexts(&self, cx: &App) -> Vec<FileContextHandle> {
+        self.context_set
+            .iter()
+            .filter(|key| !self.refreshed_context.contains(*key))
+            .filter_map(|key| match key.as_ref() {
+                AgentContextHandle::File(file_context) => Some(file_context),
+                _ => None,
+            })
+            .filter(|file_context| {
+                self.sent_file_versions
+                    .get(&file_context.buffer.entity_id())
+                    .is_some_and(|version| {
+                        file_context
+                            .buffer
+                            .read(cx)
+                            .version()
+                            .changed_since(version)
+                    })
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Marks stale files to be loaded again and sent with the next message.
+    pub fn refresh_stale_context(&mut self, cx: &mut Context<Self>) {
+        let stale_file_contexts = self.stale_file_contexts(cx);
+        if stale_file_contexts.is_empty() {
+            return;
+        }
+        self.refreshed_context.extend(
+            stale_file_contexts
+                .into_iter()
+                .map(|file_context| AgentContextKey(AgentContextHandle::File(file_context))),
+        );
+        cx.notify();
+    }
+
     pub fn add_file_from_path(
         &mut self,
         project_path: ProjectPath,
@@ -166,12 +231,35 @@ impl ContextStore {
                 }
             } else {
                 self.insert_context(context.clone(), cx);
+                self.remove_files_included_in_directory(project_path, cx);
                 Some(context)
             };
 
         anyhow::Ok(context)
     }
 
+    /// Removes files that were attached directly, but are now also attached via the directory at
+    /// `directory_path`, so that their contents aren't sent twice.
+    fn remove_files_included_in_directory(
+        &mut self,
+        directory_path: &ProjectPath,
+        cx: &mut Context<Self>,
+    ) {
+        let duplicates = self
+            .context()
+            .filter(|context| match context {
+                AgentContextHandle::File(file_context) => file_context
+                    .project_path(cx)
+                    .is_some_and(|path| path.starts_with(directory_path)),
+                _ => false,
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        for context in duplicates {
+            self.remove_context(&context, cx);
+        }
+    }
+
     pub fn add_symbol(
         &mut self,
         buffer: Entity<Buffer>,
@@ -279,11 +367,34 @@ impl ContextStore {
     pub fn add_fetched_url(
         &mut self,
         url: String,
-        text: impl Into<SharedString>,
+        page: FetchedPage,
         cx: &mut Context<ContextStore>,
     ) -> AgentContextHandle {
         let context = AgentContextHandle::FetchedUrl(FetchedUrlContext {
             url: url.into(),
+            title: page.title,
+            text: page.text,
+            context_id: self.next_context_id.post_inc(),
+        });
+
+        self.insert_context(context.clone(), cx);
+        context
+    }
+
+    pub fn add_extension_entry(
+        &mut self,
+        source_id: Arc<str>,
+        source_name: SharedString,
+        entry_id: SharedString,
+        label: SharedString,
+        text: impl Into<SharedString>,
+        cx: &mut Context<ContextStore>,
+    ) -> AgentContextHandle {
+        let context = AgentContextHandle::ExtensionEntry(ExtensionEntryContext {
+            source_id,
+            source_name,
+            entry_id,
+            label,
             text: text.into(),
             context_id: self.next_context_id.post_inc(),
         });
@@ -318,6 +429,29 @@ impl ContextStore {
         })
     }
 
+    /// Adds an image that lives outside of the project, such as a screenshot dropped from the
+    /// desktop, without opening a worktree for it.
+    pub fn add_image_from_abs_path(
+        &mut self,
+        abs_path: PathBuf,
+        cx: &mut Context<ContextStore>,
+    ) -> Task<Result<Option<AgentContextHandle>>> {
+        let Some(project) = self.project.upgrade() else {
+            return Task::ready(Err(anyhow!("failed to read project")));
+        };
+        let fs = project.read(cx).fs().clone();
+        cx.spawn(async move |this, cx| {
+            let bytes = fs
+                .load_bytes(&abs_path)
+                .await
+                .with_context(|| format!("failed to read image {abs_path:?}"))?;
+            let image = create_gpui_image(bytes)?;
+            this.update(cx, |this, cx| {
+                this.insert_image(None, Some(abs_path.into()), image, false, cx)
+            })
+        })
+    }
+
     pub fn add_image_instance(&mut self, image: Arc<Image>, cx: &mut Context<ContextStore>) {
         self.insert_image(None, None, image, false, cx);
     }
@@ -448,8 +582,13 @@ impl ContextStore {
                         self.context_text_thread_paths.remove(path);
                     }
                 }
+                AgentContextHandle::File(file_context) => {
+                    self.sent_file_versions
+                        .remove(&file_context.buffer.entity_id());
+                }
                 _ => {}
             }
+            self.refreshed_context.remove(&key);
             cx.emit(ContextStoreEvent::ContextRemoved(key));
             cx.notify();
         }
@@ -531,6 +670,11 @@ impl ContextStore {
             .contains(&FetchedUrlContext::lookup_key(url.into()))
     }
 
+    pub fn includes_extension_entry(&self, source_id: Arc<str>, entry_id: SharedString) -> bool {
+        self.context_set
+            .contains(&ExtensionEntryContext::lookup_key(source_id, entry_id))
+    }
+
     pub fn get_url_context(&self, url: SharedString) -> Option<AgentContextHandle> {
         self.context_set
             .get(&FetchedUrlContext::lookup_key(url))
@@ -551,7 +695,8 @@ impl ContextStore {
                 | AgentContextHandle::Thread(_)
                 | AgentContextHandle::TextThread(_)
                 | AgentContextHandle::Rules(_)
-                | AgentContextHandle::Image(_) => None,
+                | AgentContextHandle::Image(_)
+                | AgentContextHandle::ExtensionEntry(_) => None,
             })
             .collect()
     }
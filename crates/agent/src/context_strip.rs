@@ -11,7 +11,7 @@ use gpui::{
 };
 use itertools::Itertools;
 use language::Buffer;
-use project::ProjectItem;
+use project::{ProjectItem, ProjectPath};
 use ui::{PopoverMenu, PopoverMenuHandle, Tooltip, prelude::*};
 use workspace::Workspace;
 
@@ -32,6 +32,7 @@ pub struct ContextStrip {
     context_picker_menu_handle: PopoverMenuHandle<ContextPicker>,
     focus_handle: FocusHandle,
     suggest_context_kind: SuggestContextKind,
+    ranked_files: Vec<ProjectPath>,
     workspace: WeakEntity<Workspace>,
     thread_store: Option<WeakEntity<ThreadStore>>,
     _subscriptions: Vec<Subscription>,
@@ -78,6 +79,7 @@ impl ContextStrip {
             context_picker_menu_handle,
             focus_handle,
             suggest_context_kind,
+            ranked_files: Vec::new(),
             workspace,
             thread_store,
             _subscriptions: subscriptions,
@@ -91,6 +93,36 @@ impl ContextStrip {
     pub fn has_context_items(&self, cx: &App) -> bool {
         self.context_store.read(cx).context().next().is_some()
             || self.suggested_context(cx).is_some()
+            || !self.ranked_files(cx).is_empty()
+    }
+
+    /// Sets the files that are relevant to the message being composed, which are
+    /// suggested alongside the added context.
+    pub fn set_ranked_files(&mut self, ranked_files: Vec<ProjectPath>, cx: &mut Context<Self>) {
+        if self.ranked_files != ranked_files {
+            self.ranked_files = ranked_files;
+            cx.notify();
+        }
+    }
+
+    fn ranked_files(&self, cx: &App) -> Vec<ProjectPath> {
+        let context_store = self.context_store.read(cx);
+        self.ranked_files
+            .iter()
+            .filter(|project_path| context_store.file_path_included(project_path, cx).is_none())
+            .cloned()
+            .collect()
+    }
+
+    fn add_ranked_files(&mut self, ranked_files: Vec<ProjectPath>, cx: &mut Context<Self>) {
+        self.context_store.update(cx, |context_store, cx| {
+            for project_path in ranked_files {
+                context_store
+                    .add_file_from_path(project_path, false, cx)
+                    .detach_and_log_err(cx);
+            }
+        });
+        cx.notify();
     }
 
     fn added_contexts(&self, cx: &App) -> Vec<AddedContext> {
@@ -407,6 +439,7 @@ impl Render for ContextStrip {
             .map(|(a, _)| a)
             .collect::<HashSet<SharedString>>();
         let no_added_context = added_contexts.is_empty();
+        let stale_file_count = self.context_store.read(cx).stale_file_contexts(cx).len();
 
         let suggested_context = self.suggested_context(cx).map(|suggested_context| {
             (
@@ -414,6 +447,7 @@ impl Render for ContextStrip {
                 self.is_suggested_focused(&added_contexts),
             )
         });
+        let ranked_files = self.ranked_files(cx);
 
         h_flex()
             .flex_wrap()
@@ -532,6 +566,60 @@ impl Render for ContextStrip {
                     ))),
                 )
             })
+            .children(ranked_files.iter().enumerate().map(|(ix, project_path)| {
+                let name: SharedString = project_path
+                    .path
+                    .file_name()
+                    .unwrap_or_else(|| project_path.path.as_os_str())
+                    .to_string_lossy()
+                    .into_owned()
+                    .into();
+                let icon_path = FileIcons::get_icon(&project_path.path, cx);
+                let project_path = project_path.clone();
+                // Suggested pills share an element id, so give each one its own scope.
+                div().id(("ranked-file", ix)).child(
+                    ContextPill::suggested(name, icon_path, ContextKind::File, false).on_click(
+                        Rc::new(cx.listener(move |this, _event, _window, cx| {
+                            this.add_ranked_files(vec![project_path.clone()], cx);
+                        })),
+                    ),
+                )
+            }))
+            .when(ranked_files.len() > 1, |parent| {
+                parent.child(
+                    Button::new("attach-all-ranked-files", "Attach All")
+                        .label_size(LabelSize::Small)
+                        .tooltip(Tooltip::text("Attach All Relevant Files"))
+                        .on_click(cx.listener(move |this, _event, _window, cx| {
+                            this.add_ranked_files(ranked_files.clone(), cx);
+                        })),
+                )
+            })
+            .when(stale_file_count > 0, |parent| {
+                let meta: SharedString = if stale_file_count == 1 {
+                    "1 file changed since it was sent".into()
+                } else {
+                    format!("{stale_file_count} files changed since they were sent").into()
+                };
+                parent.child(
+                    IconButton::new("refresh-stale-context", IconName::RotateCw)
+                        .icon_size(IconSize::Small)
+                        .tooltip(move |window, cx| {
+                            Tooltip::with_meta(
+                                "Refresh Stale Context",
+                                None,
+                                meta.clone(),
+                                window,
+                                cx,
+                            )
+                        })
+                        .on_click(cx.listener(|this, _event, _window, cx| {
+                            this.context_store.update(cx, |context_store, cx| {
+                                context_store.refresh_stale_context(cx);
+                            });
+                        })),
+                )
+            })
             .when(!no_added_context, {
                 move |parent| {
                     parent.child(
@@ -0,0 +1,137 @@
+use agent_settings::{AgentSettings, DictationBackend};
+use anyhow::{Result, anyhow};
+use gpui::{App, Task};
+use language_model::{LanguageModelProviderId, LanguageModelRegistry};
+use settings::Settings as _;
+
+/// Transcribes WAV-encoded speech with the backend configured in the `agent.dictation` setting.
+pub(crate) fn transcribe(wav: Vec<u8>, cx: &App) -> Task<Result<String>> {
+    match AgentSettings::get_global(cx).dictation.clone() {
+        DictationBackend::Provider { provider } => {
+            let provider_id = LanguageModelProviderId(provider.into());
+            let Some(provider) = LanguageModelRegistry::read_global(cx).provider(&provider_id)
+            else {
+                return Task::ready(Err(anyhow!(
+                    "No language model provider with id {provider_id} is available for dictation"
+                )));
+            };
+            provider.transcribe(wav, cx)
+        }
+        DictationBackend::Local { api_url, model } => {
+            let http_client = cx.http_client();
+            cx.background_spawn(async move {
+                open_ai::transcribe(
+                    http_client.as_ref(),
+                    api_url.trim_end_matches('/'),
+                    "",
+                    model
+                        .as_deref()
+                        .unwrap_or(open_ai::DEFAULT_TRANSCRIPTION_MODEL),
+                    wav,
+                )
+                .await
+            })
+        }
+    }
+}
+
+/// Whether to transcribe the recording while the user is still speaking. Each interim
+/// transcription sends everything recorded so far, so this is only done when transcribing
+/// locally, where it doesn't cost anything or send audio anywhere.
+pub(crate) fn shows_interim_transcripts(cx: &App) -> bool {
+    matches!(
+        AgentSettings::get_global(cx).dictation,
+        DictationBackend::Local { .. }
+    )
+}
+
+/// Normalizes a partial transcript so it can be shown while the user is still speaking.
+pub(crate) fn interim_transcript(text: &str) -> String {
+    strip_annotations(text)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Tidies up a finished transcript: speech-to-text models tend to return text with stray
+/// spacing around punctuation, lowercase sentence starts, and no closing punctuation.
+pub(crate) fn clean_up_transcript(text: &str) -> String {
+    let text = strip_annotations(text);
+    let mut result = String::with_capacity(text.len());
+    let mut capitalize_next = true;
+
+    for word in text.split_whitespace() {
+        let attaches_to_previous = word.starts_with([',', '.', '!', '?', ';', ':']);
+        if !result.is_empty() && !attaches_to_previous {
+            result.push(' ');
+        }
+
+        for c in word.chars() {
+            if capitalize_next && c.is_alphabetic() {
+                result.extend(c.to_uppercase());
+                capitalize_next = false;
+            } else {
+                result.push(c);
+                if c.is_alphanumeric() {
+                    capitalize_next = false;
+                }
+            }
+
+            if matches!(c, '.' | '!' | '?') {
+                capitalize_next = true;
+            }
+        }
+    }
+
+    if result.ends_with(char::is_alphanumeric) {
+        result.push('.');
+    }
+    result
+}
+
+/// Removes bracketed annotations such as `[BLANK_AUDIO]` that Whisper-based models emit for
+/// non-speech audio.
+fn strip_annotations(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut depth = 0;
+    for c in text.chars() {
+        match c {
+            '[' => depth += 1,
+            ']' if depth > 0 => depth -= 1,
+            _ if depth == 0 => result.push(c),
+            _ => {}
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clean_up_transcript() {
+        assert_eq!(
+            clean_up_transcript("  rename the   function , then run the tests "),
+            "Rename the function, then run the tests."
+        );
+        assert_eq!(
+            clean_up_transcript("what does this do? explain it . thanks"),
+            "What does this do? Explain it. Thanks."
+        );
+        assert_eq!(
+            clean_up_transcript("[BLANK_AUDIO] bump the version to 3.5"),
+            "Bump the version to 3.5."
+        );
+        assert_eq!(clean_up_transcript("Done!"), "Done!");
+        assert_eq!(clean_up_transcript(" [BLANK_AUDIO] "), "");
+    }
+
+    #[test]
+    fn test_interim_transcript() {
+        assert_eq!(
+            interim_transcript(" add a  test [music] for"),
+            "add a test for"
+        );
+    }
+}
@@ -0,0 +1,278 @@
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context as _, Result};
+use fs::Fs;
+use gpui::App;
+use indoc::formatdoc;
+use language_model::Role;
+use serde::Deserialize;
+
+use crate::thread::{MessageSegment, Thread};
+
+const EDIT_FILE_TOOL_NAME: &str = "edit_file";
+
+/// The last file edit of a thread, in the format of the edit agent evals in `assistant_tools`: the
+/// file before and after the edit, plus an eval test that replays the conversation leading to it.
+pub(crate) struct EvalFixture {
+    name: String,
+    before_file_name: String,
+    after_file_name: String,
+    before: String,
+    after: String,
+    test: String,
+}
+
+/// The subset of the edit file tool's output that a fixture needs.
+#[derive(Deserialize)]
+struct EditFileToolOutput {
+    old_text: String,
+    new_text: String,
+}
+
+impl EvalFixture {
+    pub(crate) fn from_thread(thread: &Thread, cx: &App) -> Result<Self> {
+        let (message_ix, edit_tool_use_id, edit_path, edit_output) = thread
+            .messages()
+            .enumerate()
+            .flat_map(|(ix, message)| {
+                thread
+                    .tool_uses_for_message(message.id, cx)
+                    .into_iter()
+                    .map(move |tool_use| (ix, tool_use))
+            })
+            .filter(|(_, tool_use)| tool_use.name.as_ref() == EDIT_FILE_TOOL_NAME)
+            .filter_map(|(ix, tool_use)| {
+                let result = thread.tool_result(&tool_use.id)?;
+                if result.is_error {
+                    return None;
+                }
+                let output =
+                    serde_json::from_value::<EditFileToolOutput>(result.output.clone()?).ok()?;
+                let path = tool_use.input.get("path")?.as_str()?.to_string();
+                Some((ix, tool_use.id, path, output))
+            })
+            .last()
+            .context("The thread doesn't have any completed file edits")?;
+
+        let name = fixture_name(&thread.summary().or_default());
+        let edit_path = Path::new(&edit_path);
+        let file_name = edit_path
+            .file_name()
+            .map(|file_name| file_name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "file".to_string());
+        // Evals run in a project with a single worktree named `root`.
+        let input_file_path = format!("root/{file_name}");
+        let extension = edit_path
+            .extension()
+            .map(|extension| format!(".{}", extension.to_string_lossy()))
+            .unwrap_or_default();
+        let before_file_name = format!("before{extension}");
+        let after_file_name = format!("after{extension}");
+
+        let mut conversation = String::new();
+        for (ix, message) in thread.messages().enumerate().take(message_ix + 1) {
+            let is_edit_message = ix == message_ix;
+            let mut contents = Vec::new();
+            if !message.loaded_context.text.is_empty() {
+                contents.push(format!(
+                    "text({})",
+                    string_literal(&message.loaded_context.text)
+                ));
+            }
+            for segment in &message.segments {
+                if let MessageSegment::Text(text) = segment {
+                    if !text.is_empty() {
+                        contents.push(format!("text({})", string_literal(text)));
+                    }
+                }
+            }
+
+            // The eval replays the edit from the final tool use, so the conversation has to end
+            // with it rather than with the tools that ran alongside it.
+            let tool_uses = thread
+                .tool_uses_for_message(message.id, cx)
+                .into_iter()
+                .filter(|tool_use| !is_edit_message || tool_use.id == edit_tool_use_id);
+            for tool_use in tool_uses {
+                let mut input = tool_use.input;
+                if input.get("path").and_then(|path| path.as_str()) == edit_path.to_str() {
+                    input["path"] = input_file_path.clone().into();
+                }
+                contents.push(format!(
+                    "tool_use({:?}, {:?}, json!({}))",
+                    tool_use.id.to_string(),
+                    tool_use.name.as_ref(),
+                    json_literal(&input)
+                ));
+            }
+            push_message(&mut conversation, message.role, &contents);
+
+            if !is_edit_message {
+                let tool_results = thread
+                    .tool_results_for_message(message.id)
+                    .into_iter()
+                    .map(|tool_result| {
+                        format!(
+                            "tool_result({:?}, {:?}, {})",
+                            tool_result.tool_use_id.to_string(),
+                            tool_result.tool_name.as_ref(),
+                            string_literal(tool_result.content.to_str().unwrap_or_default())
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                push_message(&mut conversation, Role::User, &tool_results);
+            }
+        }
+
+        let test = formatdoc! {r#"
+            #[test]
+            #[cfg_attr(not(feature = "eval"), ignore)]
+            fn eval_{name}() {{
+                let input_file_content = include_str!("evals/fixtures/{name}/{before_file_name}");
+                let output_file_content = include_str!("evals/fixtures/{name}/{after_file_name}");
+                eval(
+                    100,
+                    0.95,
+                    EvalInput::from_conversation(
+                        vec![
+            {conversation}            ],
+                        Some(input_file_content.into()),
+                        EvalAssertion::assert_eq(output_file_content),
+                    ),
+                );
+            }}
+        "#};
+
+        Ok(Self {
+            name,
+            before_file_name,
+            after_file_name,
+            before: edit_output.old_text,
+            after: edit_output.new_text,
+            test,
+        })
+    }
+
+    /// Writes the fixture to its own directory in `fixtures_dir`, returning the path of the
+    /// generated test, which goes in `evals.rs`.
+    pub(crate) async fn save(&self, fixtures_dir: &Path, fs: &dyn Fs) -> Result<PathBuf> {
+        let dir = fixtures_dir.join(&self.name);
+        fs.create_dir(&dir).await?;
+        fs.atomic_write(dir.join(&self.before_file_name), self.before.clone())
+            .await?;
+        fs.atomic_write(dir.join(&self.after_file_name), self.after.clone())
+            .await?;
+        let test_path = dir.join("eval.rs");
+        fs.atomic_write(test_path.clone(), self.test.clone())
+            .await?;
+        Ok(test_path)
+    }
+}
+
+fn push_message(conversation: &mut String, role: Role, contents: &[String]) {
+    if contents.is_empty() {
+        return;
+    }
+    let role = match role {
+        Role::User => "User",
+        Role::Assistant => "Assistant",
+        Role::System => "System",
+    };
+    writeln!(conversation, "                message(").ok();
+    writeln!(conversation, "                    {role},").ok();
+    writeln!(conversation, "                    [").ok();
+    for content in contents {
+        writeln!(conversation, "                        {content},").ok();
+    }
+    writeln!(conversation, "                    ],").ok();
+    writeln!(conversation, "                ),").ok();
+}
+
+fn fixture_name(summary: &str) -> String {
+    let mut name = String::new();
+    for word in summary
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+    {
+        if !name.is_empty() {
+            name.push('_');
+        }
+        name.push_str(&word.to_ascii_lowercase());
+    }
+    if name.is_empty() {
+        name.push_str("exported_thread");
+    }
+    name
+}
+
+/// Returns a raw string literal containing `text`, with enough `#`s that nothing in it ends the
+/// literal early.
+fn string_literal(text: &str) -> String {
+    let mut hashes = 0;
+    for (ix, _) in text.match_indices('"') {
+        let run = text[ix + 1..].chars().take_while(|c| *c == '#').count();
+        hashes = hashes.max(run + 1);
+    }
+    let hashes = "#".repeat(hashes);
+    format!("r{hashes}\"{text}\"{hashes}")
+}
+
+/// Renders `value` as the input of a `json!` macro. Strings use Rust's escapes rather than JSON's,
+/// which differ for control characters.
+fn json_literal(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(string) => format!("{string:?}"),
+        serde_json::Value::Array(values) => {
+            let values = values.iter().map(json_literal).collect::<Vec<_>>();
+            format!("[{}]", values.join(", "))
+        }
+        serde_json::Value::Object(entries) => {
+            let entries = entries
+                .iter()
+                .map(|(key, value)| format!("{key:?}: {}", json_literal(value)))
+                .collect::<Vec<_>>();
+            format!("{{ {} }}", entries.join(", "))
+        }
+        value => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_fixture_name() {
+        assert_eq!(
+            fixture_name("Extract `handle_command_output` method"),
+            "extract_handle_command_output_method"
+        );
+        assert_eq!(
+            fixture_name("Fix off-by-one in parser!"),
+            "fix_off_by_one_in_parser"
+        );
+        assert_eq!(fixture_name("…"), "exported_thread");
+    }
+
+    #[test]
+    fn test_string_literal() {
+        assert_eq!(string_literal("fn a() {}"), "r\"fn a() {}\"");
+        assert_eq!(string_literal("let s = \"a\";"), "r#\"let s = \"a\";\"#");
+        assert_eq!(string_literal("r#\"nested\"#"), "r##\"r#\"nested\"#\"##");
+    }
+
+    #[test]
+    fn test_json_literal() {
+        assert_eq!(
+            json_literal(&json!({
+                "path": "root/lib.rs",
+                "start_line": null,
+                "lines": [1, 2],
+                "text": "a\u{1b}b",
+            })),
+            r#"{ "path": "root/lib.rs", "start_line": null, "lines": [1, 2], "text": "a\u{1b}b" }"#
+        );
+    }
+}
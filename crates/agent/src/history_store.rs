@@ -14,7 +14,7 @@ use util::ResultExt as _;
 use crate::{
     Thread,
     thread::ThreadId,
-    thread_store::{SerializedThreadMetadata, ThreadStore},
+    thread_store::{SerializedThreadMetadata, ThreadSearchMatch, ThreadStore},
 };
 
 const MAX_RECENTLY_OPENED_ENTRIES: usize = 6;
@@ -202,6 +202,16 @@ impl HistoryStore {
         history_entries
     }
 
+    pub fn search_threads(
+        &self,
+        query: &str,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<Vec<ThreadSearchMatch>>> {
+        self.thread_store.update(cx, |thread_store, cx| {
+            thread_store.search_threads(query, cx)
+        })
+    }
+
     pub fn recent_entries(&self, limit: usize, cx: &mut Context<Self>) -> Vec<HistoryEntry> {
         self.entries(cx).into_iter().take(limit).collect()
     }
@@ -0,0 +1,329 @@
+use anyhow::{Context as _, Result, anyhow};
+use chrono::{DateTime, Utc};
+use collections::HashMap;
+use db::kvp::KEY_VALUE_STORE;
+use futures::FutureExt as _;
+use futures::future::Shared;
+use gpui::{App, AppContext as _, Context, Entity, EventEmitter, Global, Task};
+use project::Project;
+use serde::{Deserialize, Serialize};
+use util::ResultExt as _;
+use uuid::Uuid;
+
+/// Memories are included in the system prompt of every thread, so they're kept short.
+pub const MAX_MEMORY_LEN: usize = 300;
+
+/// Only the most recent memories are included in the system prompt, so that a long-lived
+/// project doesn't crowd out the rest of the context window.
+const MAX_MEMORIES_IN_PROMPT: usize = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct MemoryId(Uuid);
+
+/// A short fact about a project that the agent learned in one thread and
+/// should know about in later ones.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Memory {
+    pub id: MemoryId,
+    pub text: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Identifies a project across sessions by the absolute paths of its visible worktrees.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ProjectKey(String);
+
+impl ProjectKey {
+    pub fn for_project(project: &Entity<Project>, cx: &App) -> Option<Self> {
+        let mut paths = project
+            .read(cx)
+            .visible_worktrees(cx)
+            .map(|worktree| worktree.read(cx).abs_path().to_string_lossy().into_owned())
+            .collect::<Vec<_>>();
+        if paths.is_empty() {
+            return None;
+        }
+        paths.sort();
+        Some(Self(paths.join("\n")))
+    }
+
+    fn kvp_key(&self) -> String {
+        format!("agent-memories-{}", self.0)
+    }
+}
+
+pub enum MemoryStoreEvent {
+    MemoriesChanged(ProjectKey),
+}
+
+struct GlobalMemoryStore(Entity<MemoryStore>);
+
+impl Global for GlobalMemoryStore {}
+
+/// Stores the memories of every project opened in this session, loading
+/// each project's memories from the database in the background the first
+/// time they're needed.
+pub struct MemoryStore {
+    memories_by_project: HashMap<ProjectKey, Vec<Memory>>,
+    loading: HashMap<ProjectKey, Shared<Task<()>>>,
+}
+
+impl EventEmitter<MemoryStoreEvent> for MemoryStore {}
+
+impl MemoryStore {
+    pub fn global(cx: &mut App) -> Entity<Self> {
+        if let Some(store) = cx.try_global::<GlobalMemoryStore>() {
+            return store.0.clone();
+        }
+        let store = cx.new(|_| Self::new());
+        cx.set_global(GlobalMemoryStore(store.clone()));
+        store
+    }
+
+    fn new() -> Self {
+        Self {
+            memories_by_project: HashMap::default(),
+            loading: HashMap::default(),
+        }
+    }
+
+    /// The project's memories, or none if they haven't been [loaded](Self::load) yet.
+    pub fn memories(&self, project: &ProjectKey) -> &[Memory] {
+        self.memories_by_project
+            .get(project)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// The text of the memories to include in the system prompt, oldest first.
+    pub fn memories_for_prompt(&self, project: &ProjectKey) -> Vec<String> {
+        let memories = self.memories(project);
+        memories[memories.len().saturating_sub(MAX_MEMORIES_IN_PROMPT)..]
+            .iter()
+            .map(|memory| memory.text.clone())
+            .collect()
+    }
+
+    /// Reads the project's memories from the database, unless they're already loaded.
+    pub fn load(&mut self, project: &ProjectKey, cx: &mut Context<Self>) -> Shared<Task<()>> {
+        if self.memories_by_project.contains_key(project) {
+            return Task::ready(()).shared();
+        }
+        if let Some(task) = self.loading.get(project) {
+            return task.clone();
+        }
+
+        let key = project.kvp_key();
+        let project = project.clone();
+        let task = cx
+            .spawn({
+                let project = project.clone();
+                async move |this, cx| {
+                    let memories = cx
+                        .background_spawn(async move {
+                            KEY_VALUE_STORE
+                                .read_kvp(&key)
+                                .log_err()
+                                .flatten()
+                                .and_then(|json| serde_json::from_str(&json).log_err())
+                                .unwrap_or_default()
+                        })
+                        .await;
+                    this.update(cx, |this, cx| {
+                        this.loading.remove(&project);
+                        this.memories_by_project.insert(project, memories);
+                        cx.notify();
+                    })
+                    .ok();
+                }
+            })
+            .shared();
+        self.loading.insert(project, task.clone());
+        task
+    }
+
+    pub fn remember(
+        &mut self,
+        project: &ProjectKey,
+        text: &str,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<MemoryId>> {
+        let text = match normalize_memory(text) {
+            Ok(text) => text,
+            Err(error) => return Task::ready(Err(error)),
+        };
+        let load = self.load(project, cx);
+        let project = project.clone();
+        cx.spawn(async move |this, cx| {
+            load.await;
+            this.update(cx, |this, cx| {
+                let memories = this
+                    .memories_by_project
+                    .get_mut(&project)
+                    .context("failed to load memories")?;
+                if let Some(existing) = memories.iter().find(|memory| memory.text == text) {
+                    return Ok(existing.id);
+                }
+
+                let id = MemoryId(Uuid::new_v4());
+                memories.push(Memory {
+                    id,
+                    text,
+                    created_at: Utc::now(),
+                });
+                this.save(&project, cx);
+                Ok(id)
+            })?
+        })
+    }
+
+    pub fn edit(
+        &mut self,
+        project: &ProjectKey,
+        id: MemoryId,
+        text: &str,
+        cx: &mut Context<Self>,
+    ) -> Result<()> {
+        let text = normalize_memory(text)?;
+        let memory = self
+            .memories_by_project
+            .get_mut(project)
+            .context("memories are still loading")?
+            .iter_mut()
+            .find(|memory| memory.id == id)
+            .context("memory not found")?;
+        memory.text = text;
+        self.save(project, cx);
+        Ok(())
+    }
+
+    pub fn delete(&mut self, project: &ProjectKey, id: MemoryId, cx: &mut Context<Self>) {
+        let Some(memories) = self.memories_by_project.get_mut(project) else {
+            return;
+        };
+        memories.retain(|memory| memory.id != id);
+        self.save(project, cx);
+    }
+
+    fn save(&mut self, project: &ProjectKey, cx: &mut Context<Self>) {
+        let key = project.kvp_key();
+        let memories = self.memories(project);
+        if memories.is_empty() {
+            db::write_and_log(
+                cx,
+                move || async move { KEY_VALUE_STORE.delete_kvp(key).await },
+            );
+        } else if let Some(json) = serde_json::to_string(memories).log_err() {
+            db::write_and_log(cx, move || async move {
+                KEY_VALUE_STORE.write_kvp(key, json).await
+            });
+        }
+        cx.emit(MemoryStoreEvent::MemoriesChanged(project.clone()));
+        cx.notify();
+    }
+}
+
+fn normalize_memory(text: &str) -> Result<String> {
+    // Memories are rendered as list items in the system prompt, so keep them on one line.
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.is_empty() {
+        return Err(anyhow!("memory is empty"));
+    }
+    if text.chars().count() > MAX_MEMORY_LEN {
+        return Err(anyhow!(
+            "memory is longer than {MAX_MEMORY_LEN} characters; remember a shorter fact"
+        ));
+    }
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+
+    #[test]
+    fn test_normalize_memory() {
+        assert_eq!(
+            normalize_memory("  build with\n  `cargo nextest run`  ").unwrap(),
+            "build with `cargo nextest run`"
+        );
+        assert!(normalize_memory(" \n ").is_err());
+        assert!(normalize_memory(&"a".repeat(MAX_MEMORY_LEN)).is_ok());
+        assert!(normalize_memory(&"a".repeat(MAX_MEMORY_LEN + 1)).is_err());
+    }
+
+    async fn load_memories(project: &ProjectKey, cx: &mut TestAppContext) -> Vec<Memory> {
+        let store = cx.new(|_| MemoryStore::new());
+        store.update(cx, |store, cx| store.load(project, cx)).await;
+        store.read_with(cx, |store, _| store.memories(project).to_vec())
+    }
+
+    #[gpui::test]
+    async fn test_memories_round_trip(cx: &mut TestAppContext) {
+        let project = ProjectKey("/test_memories_round_trip".into());
+        let store = cx.new(|_| MemoryStore::new());
+
+        let build_id = store
+            .update(cx, |store, cx| {
+                store.remember(&project, "build with `cargo build`", cx)
+            })
+            .await
+            .unwrap();
+        let test_id = store
+            .update(cx, |store, cx| {
+                store.remember(&project, "test with\n`cargo nextest run`", cx)
+            })
+            .await
+            .unwrap();
+        let duplicate_id = store
+            .update(cx, |store, cx| {
+                store.remember(&project, "build with `cargo build`", cx)
+            })
+            .await
+            .unwrap();
+        assert_eq!(duplicate_id, build_id);
+        store
+            .update(cx, |store, cx| {
+                store.edit(&project, build_id, "build with `cargo build --release`", cx)
+            })
+            .unwrap();
+        cx.run_until_parked();
+
+        let memories = store.read_with(cx, |store, _| store.memories(&project).to_vec());
+        assert_eq!(
+            memories
+                .iter()
+                .map(|memory| memory.text.as_str())
+                .collect::<Vec<_>>(),
+            [
+                "build with `cargo build --release`",
+                "test with `cargo nextest run`"
+            ]
+        );
+        assert_eq!(load_memories(&project, cx).await, memories);
+
+        store.update(cx, |store, cx| {
+            store.delete(&project, build_id, cx);
+            store.delete(&project, test_id, cx);
+        });
+        cx.run_until_parked();
+        assert_eq!(load_memories(&project, cx).await, []);
+        assert_eq!(KEY_VALUE_STORE.read_kvp(&project.kvp_key()).unwrap(), None);
+    }
+
+    #[gpui::test]
+    async fn test_memories_are_scoped_to_project(cx: &mut TestAppContext) {
+        let first = ProjectKey("/test_memories_are_scoped_to_project/first".into());
+        let second = ProjectKey("/test_memories_are_scoped_to_project/second".into());
+        let store = cx.new(|_| MemoryStore::new());
+        store
+            .update(cx, |store, cx| store.remember(&first, "uses tabs", cx))
+            .await
+            .unwrap();
+        cx.run_until_parked();
+
+        assert_eq!(load_memories(&first, cx).await.len(), 1);
+        assert_eq!(load_memories(&second, cx).await, []);
+    }
+}
@@ -1,9 +1,12 @@
 use std::collections::BTreeMap;
+use std::ops::Range;
 use std::rc::Rc;
 use std::sync::Arc;
 
 use crate::agent_model_selector::AgentModelSelector;
-use crate::context::{AgentContextKey, ContextCreasesAddon, ContextLoadResult, load_context};
+use crate::context::{
+    AgentContextHandle, AgentContextKey, ContextCreasesAddon, ContextLoadResult, load_context,
+};
 use crate::tool_compatibility::{IncompatibleToolsState, IncompatibleToolsTooltip};
 use crate::ui::{
     MaxModeTooltip,
@@ -11,6 +14,7 @@ use crate::ui::{
 };
 use agent_settings::{AgentSettings, CompletionMode};
 use assistant_context_editor::language_model_selector::ToggleModelSelector;
+use audio::{MicrophoneCapture, RecordedAudio};
 use buffer_diff::BufferDiff;
 use client::UserStore;
 use collections::{HashMap, HashSet};
@@ -24,36 +28,41 @@ use fs::Fs;
 use futures::future::Shared;
 use futures::{FutureExt as _, future};
 use gpui::{
-    Animation, AnimationExt, App, Entity, EventEmitter, Focusable, Subscription, Task, TextStyle,
-    WeakEntity, linear_color_stop, linear_gradient, point, pulsating_between,
+    Animation, AnimationExt, App, Entity, EventEmitter, Focusable, HighlightStyle, MouseButton,
+    Subscription, Task, TextStyle, WeakEntity, linear_color_stop, linear_gradient, point,
+    pulsating_between,
 };
+use http_client::HttpClient as _;
 use language::{Buffer, Language, Point};
 use language_model::{
-    ConfiguredModel, LanguageModelRequestMessage, MessageContent, RequestUsage,
-    ZED_CLOUD_PROVIDER_ID,
+    LanguageModelRequestMessage, MessageContent, RequestUsage, ZED_CLOUD_PROVIDER_ID,
 };
 use multi_buffer;
 use project::Project;
 use prompt_store::PromptStore;
 use proto::Plan;
 use settings::Settings;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use theme::ThemeSettings;
 use ui::{Disclosure, KeyBinding, PopoverMenuHandle, Tooltip, prelude::*};
 use util::{ResultExt as _, maybe};
 use workspace::{CollaboratorId, Workspace};
 use zed_llm_client::CompletionIntent;
 
+use crate::context_gathering;
 use crate::context_picker::{ContextPicker, ContextPickerCompletionProvider, crease_for_mention};
+use crate::context_ranking;
 use crate::context_store::ContextStore;
 use crate::context_strip::{ContextStrip, ContextStripEvent, SuggestContextKind};
+use crate::dictation;
 use crate::profile_selector::ProfileSelector;
-use crate::thread::{MessageCrease, Thread, TokenUsageRatio};
+use crate::thread::{MessageCrease, Thread, ThreadError, ThreadEvent, TokenUsageRatio};
 use crate::thread_store::{TextThreadStore, ThreadStore};
 use crate::{
-    ActiveThread, AgentDiffPane, Chat, ChatWithFollow, ExpandMessageEditor, Follow, KeepAll,
-    ModelUsageContext, NewThread, OpenAgentDiff, RejectAll, RemoveAllContext, ToggleBurnMode,
-    ToggleContextPicker, ToggleProfileSelector, register_agent_preview,
+    ActiveThread, AgentDiffPane, ApplyPlan, Chat, ChatWithFollow, DiscardPlan, ExpandMessageEditor,
+    Follow, KeepAll, ModelUsageContext, NewThread, OpenAgentDiff, RejectAll, RemoveAllContext,
+    ToggleBurnMode, ToggleCompareModels, ToggleContextPicker, ToggleDictation, TogglePlanOnly,
+    ToggleProfileSelector, register_agent_preview,
 };
 
 #[derive(RegisterComponent)]
@@ -69,6 +78,7 @@ pub struct MessageEditor {
     context_strip: Entity<ContextStrip>,
     context_picker_menu_handle: PopoverMenuHandle<ContextPicker>,
     model_selector: Entity<AgentModelSelector>,
+    comparison_model_selector: Entity<AgentModelSelector>,
     last_loaded_context: Option<ContextLoadResult>,
     load_context_task: Option<Shared<Task<()>>>,
     profile_selector: Entity<ProfileSelector>,
@@ -76,11 +86,43 @@ pub struct MessageEditor {
     editor_is_expanded: bool,
     last_estimated_token_count: Option<usize>,
     update_token_count_task: Option<Task<()>>,
+    rank_context_task: Option<Task<()>>,
+    dictation: Option<DictationState>,
+    last_connection_warm_up: Option<Instant>,
     _subscriptions: Vec<Subscription>,
 }
 
 const MAX_EDITOR_LINES: usize = 8;
 
+/// How long to wait before connecting to the model's provider again when the editor regains
+/// focus. Idle connections are kept alive for longer than this, so reconnecting sooner wouldn't
+/// make the next request any faster.
+const CONNECTION_WARM_UP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How often the recording is re-transcribed to show interim text while the user is speaking,
+/// when transcribing locally.
+const DICTATION_INTERIM_INTERVAL: Duration = Duration::from_millis(1500);
+
+/// Holding the microphone button for longer than this records until it is released. Shorter
+/// presses keep recording until the button is clicked again.
+const PUSH_TO_TALK_THRESHOLD: Duration = Duration::from_millis(500);
+
+enum DictationState {
+    Starting {
+        _start: Task<()>,
+    },
+    Recording {
+        capture: MicrophoneCapture,
+        range: Range<editor::Anchor>,
+        _update_interim: Task<()>,
+    },
+    Finalizing {
+        _transcribe: Task<()>,
+    },
+}
+
+struct DictationHighlight;
+
 pub(crate) fn create_editor(
     workspace: WeakEntity<Workspace>,
     context_store: WeakEntity<ContextStore>,
@@ -191,6 +233,9 @@ impl MessageEditor {
             cx.observe(&thread.read(cx).action_log().clone(), |_, _, cx| {
                 cx.notify()
             }),
+            cx.on_focus_in(&editor.focus_handle(cx), window, |this, _window, cx| {
+                this.warm_up_model_connection(cx)
+            }),
         ];
 
         let model_selector = cx.new(|cx| {
@@ -204,6 +249,17 @@ impl MessageEditor {
             )
         });
 
+        let comparison_model_selector = cx.new(|cx| {
+            AgentModelSelector::new(
+                fs.clone(),
+                PopoverMenuHandle::default(),
+                editor.focus_handle(cx),
+                ModelUsageContext::ComparisonModel(thread.clone()),
+                window,
+                cx,
+            )
+        });
+
         let profile_selector = cx.new(|cx| {
             ProfileSelector::new(
                 fs,
@@ -228,19 +284,69 @@ impl MessageEditor {
             load_context_task: None,
             last_loaded_context: None,
             model_selector,
+            comparison_model_selector,
             edits_expanded: false,
             editor_is_expanded: false,
             profile_selector,
             last_estimated_token_count: None,
             update_token_count_task: None,
+            rank_context_task: None,
+            dictation: None,
+            last_connection_warm_up: None,
             _subscriptions: subscriptions,
         }
     }
 
+    /// Connects to the current model's provider ahead of time, so that the first message sent
+    /// after a while doesn't have to wait for the connection to be established.
+    fn warm_up_model_connection(&mut self, cx: &mut Context<Self>) {
+        if !AgentSettings::get_global(cx).warm_up_connections {
+            return;
+        }
+        if self
+            .last_connection_warm_up
+            .is_some_and(|last_warm_up| last_warm_up.elapsed() < CONNECTION_WARM_UP_INTERVAL)
+        {
+            return;
+        }
+        let Some(url) = self
+            .thread
+            .read(cx)
+            .configured_model()
+            .and_then(|model| model.provider.api_url(cx))
+        else {
+            return;
+        };
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+
+        self.last_connection_warm_up = Some(Instant::now());
+        let http_client = workspace.read(cx).client().http_client();
+        cx.background_spawn(async move {
+            // Failing to connect early isn't a problem, as the request will connect again.
+            if let Err(error) = http_client.warm_up(&url).await {
+                log::debug!("failed to warm up connection to {url}: {error:#}");
+            }
+        })
+        .detach();
+    }
+
     pub fn context_store(&self) -> &Entity<ContextStore> {
         &self.context_store
     }
 
+    pub fn text(&self, cx: &App) -> String {
+        self.editor.read(cx).text(cx)
+    }
+
+    /// Replaces the message being composed with `text` and sends it.
+    pub fn send_message(&mut self, text: String, window: &mut Window, cx: &mut Context<Self>) {
+        self.editor
+            .update(cx, |editor, cx| editor.set_text(text, window, cx));
+        self.chat(&Chat, window, cx);
+    }
+
     pub fn expand_message_editor(
         &mut self,
         _: &ExpandMessageEditor,
@@ -331,19 +437,50 @@ impl MessageEditor {
     }
 
     fn send_to_model(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        let Some(ConfiguredModel { model, provider }) = self
+        let Some(configured_model) = self
             .thread
             .update(cx, |thread, cx| thread.get_or_init_configured_model(cx))
         else {
             return;
         };
+        let comparison_model = self.thread.read(cx).comparison_model();
 
-        if provider.must_accept_terms(cx) {
+        if configured_model.provider.must_accept_terms(cx)
+            || comparison_model
+                .as_ref()
+                .is_some_and(|model| model.provider.must_accept_terms(cx))
+        {
             cx.notify();
             return;
         }
 
+        let has_images = self
+            .context_store
+            .read(cx)
+            .context()
+            .any(|context| matches!(context, AgentContextHandle::Image(_)));
+        if has_images {
+            let model_without_images = std::iter::once(&configured_model)
+                .chain(comparison_model.as_ref())
+                .find(|model| !model.model.supports_images());
+            if let Some(model) = model_without_images {
+                let message = format!(
+                    "{} doesn't support images. Remove the attached images or choose a model that supports them.",
+                    model.model.name().0
+                );
+                self.thread.update(cx, |_, cx| {
+                    cx.emit(ThreadEvent::ShowError(ThreadError::Message {
+                        header: "Images not supported".into(),
+                        message: message.into(),
+                    }));
+                });
+                return;
+            }
+        }
+
+        self.dictation.take();
         let (user_message, user_message_creases) = self.editor.update(cx, |editor, cx| {
+            editor.clear_highlights::<DictationHighlight>(cx);
             let creases = extract_message_creases(editor, cx);
             let text = editor.text(cx);
             editor.clear(window, cx);
@@ -356,8 +493,21 @@ impl MessageEditor {
         let thread = self.thread.clone();
         let git_store = self.project.read(cx).git_store().clone();
         let checkpoint = git_store.update(cx, |git_store, cx| git_store.checkpoint(cx));
-        let context_task = self.reload_context(cx);
+        let context_task = match self.gather_context_for_first_message(&user_message, cx) {
+            Some(gather_context_task) => cx.spawn(async move |this, cx| {
+                gather_context_task.await;
+                this.update(cx, |this, cx| this.reload_context(cx))
+                    .ok()?
+                    .await
+            }),
+            None => self.reload_context(cx),
+        };
         let window_handle = window.window_handle();
+        let context_store = self.context_store.clone();
+        let active_buffer = self.workspace.upgrade().and_then(|workspace| {
+            let editor = workspace.read(cx).active_item_as::<Editor>(cx)?;
+            editor.read(cx).buffer().read(cx).as_singleton()
+        });
 
         cx.spawn(async move |_this, cx| {
             let (checkpoint, loaded_context) = future::join(checkpoint, context_task).await;
@@ -365,6 +515,8 @@ impl MessageEditor {
 
             thread
                 .update(cx, |thread, cx| {
+                    thread.discard_comparison(cx);
+                    thread.set_active_buffer(active_buffer);
                     thread.insert_user_message(
                         user_message,
                         loaded_context,
@@ -374,22 +526,52 @@ impl MessageEditor {
                     );
                 })
                 .log_err();
+            context_store
+                .update(cx, |context_store, cx| context_store.context_sent(cx))
+                .log_err();
 
             thread
                 .update(cx, |thread, cx| {
                     thread.advance_prompt_id();
-                    thread.send_to_model(
-                        model,
-                        CompletionIntent::UserPrompt,
-                        Some(window_handle),
-                        cx,
-                    );
+                    if let Some(comparison_model) = comparison_model {
+                        thread.compare_models([configured_model, comparison_model], cx);
+                    } else {
+                        thread.send_to_model(
+                            configured_model.model,
+                            CompletionIntent::UserPrompt,
+                            Some(window_handle),
+                            cx,
+                        );
+                    }
                 })
                 .log_err();
         })
         .detach();
     }
 
+    fn gather_context_for_first_message(
+        &self,
+        prompt: &str,
+        cx: &mut Context<Self>,
+    ) -> Option<Task<()>> {
+        let settings = AgentSettings::get_global(cx);
+        let gather_context = settings
+            .profiles
+            .get(&settings.default_profile)
+            .is_some_and(|profile| profile.gather_context);
+        if !gather_context || !self.thread.read(cx).is_empty() {
+            return None;
+        }
+
+        let workspace = self.workspace.upgrade()?;
+        Some(context_gathering::gather_context(
+            prompt,
+            workspace,
+            self.context_store.downgrade(),
+            cx,
+        ))
+    }
+
     fn stop_current_and_send_new_message(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         self.thread.update(cx, |thread, cx| {
             thread.cancel_editing(cx);
@@ -480,6 +662,254 @@ impl MessageEditor {
         });
     }
 
+    fn toggle_compare_models(
+        &mut self,
+        _: &ToggleCompareModels,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let is_comparing = self.thread.update(cx, |thread, cx| {
+            let comparison_model = match thread.comparison_model() {
+                Some(_) => None,
+                None => thread.get_or_init_configured_model(cx),
+            };
+            let is_comparing = comparison_model.is_some();
+            thread.set_comparison_model(comparison_model, cx);
+            is_comparing
+        });
+
+        if is_comparing {
+            self.comparison_model_selector
+                .update(cx, |model_selector, cx| model_selector.toggle(window, cx));
+        }
+    }
+
+    fn toggle_plan_only(
+        &mut self,
+        _: &TogglePlanOnly,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.thread.update(cx, |thread, cx| {
+            let plan_only = thread.plan_only();
+            thread.set_plan_only(!plan_only, cx);
+        });
+    }
+
+    fn apply_plan(&mut self, _: &ApplyPlan, _window: &mut Window, cx: &mut Context<Self>) {
+        if self.thread.read(cx).has_pending_edit_tool_uses() {
+            return;
+        }
+
+        self.thread
+            .update(cx, |thread, cx| thread.apply_plan(cx))
+            .detach_and_log_err(cx);
+    }
+
+    fn discard_plan(&mut self, _: &DiscardPlan, _window: &mut Window, cx: &mut Context<Self>) {
+        if self.thread.read(cx).has_pending_edit_tool_uses() {
+            return;
+        }
+
+        self.thread.update(cx, |thread, cx| thread.discard_plan(cx));
+    }
+
+    fn toggle_dictation(
+        &mut self,
+        _: &ToggleDictation,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        match self.dictation {
+            Some(DictationState::Recording { .. }) => self.stop_dictation(window, cx),
+            Some(DictationState::Starting { .. } | DictationState::Finalizing { .. }) => {}
+            None => self.start_dictation(window, cx),
+        }
+    }
+
+    fn release_push_to_talk(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(DictationState::Recording { capture, .. }) = &self.dictation {
+            if capture.duration() >= PUSH_TO_TALK_THRESHOLD {
+                self.stop_dictation(window, cx);
+            }
+        }
+    }
+
+    fn start_dictation(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let range = self.editor.update(cx, |editor, cx| {
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            let cursor = editor.selections.newest::<usize>(cx).head();
+            snapshot.anchor_before(cursor)..snapshot.anchor_after(cursor)
+        });
+
+        let capture = cx.background_spawn(async { MicrophoneCapture::start() });
+        let start = cx.spawn_in(window, async move |this, cx| {
+            let capture = capture.await;
+            this.update(cx, |this, cx| {
+                if !matches!(this.dictation, Some(DictationState::Starting { .. })) {
+                    return;
+                }
+                match capture {
+                    Ok(capture) => {
+                        let update_interim = if dictation::shows_interim_transcripts(cx) {
+                            this.update_interim_transcript(capture.recording(), cx)
+                        } else {
+                            Task::ready(())
+                        };
+                        this.dictation = Some(DictationState::Recording {
+                            capture,
+                            range,
+                            _update_interim: update_interim,
+                        });
+                    }
+                    Err(error) => {
+                        this.dictation = None;
+                        this.show_dictation_error(error, cx);
+                    }
+                }
+                cx.notify();
+            })
+            .log_err();
+        });
+
+        self.dictation = Some(DictationState::Starting { _start: start });
+        cx.notify();
+    }
+
+    fn update_interim_transcript(
+        &mut self,
+        recording: RecordedAudio,
+        cx: &mut Context<Self>,
+    ) -> Task<()> {
+        cx.spawn(async move |this, cx| {
+            loop {
+                cx.background_executor()
+                    .timer(DICTATION_INTERIM_INTERVAL)
+                    .await;
+                let wav = cx
+                    .background_spawn({
+                        let recording = recording.clone();
+                        async move { recording.to_wav() }
+                    })
+                    .await;
+                let Ok(transcription) = this.update(cx, |_, cx| dictation::transcribe(wav, cx))
+                else {
+                    return;
+                };
+
+                let transcription = transcription.await;
+                let Ok(()) = this.update(cx, |this, cx| {
+                    let Some(DictationState::Recording { range, .. }) = &this.dictation else {
+                        return;
+                    };
+                    let range = range.clone();
+                    match transcription {
+                        Ok(text) => {
+                            let text = dictation::interim_transcript(&text);
+                            this.set_dictated_text(range, text, true, cx);
+                        }
+                        Err(error) => {
+                            this.dictation = None;
+                            this.set_dictated_text(range, String::new(), false, cx);
+                            this.show_dictation_error(error, cx);
+                        }
+                    }
+                }) else {
+                    return;
+                };
+            }
+        })
+    }
+
+    fn stop_dictation(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(DictationState::Recording { capture, range, .. }) = self.dictation.take() else {
+            return;
+        };
+        let wav = cx.background_spawn(async move {
+            let recording = capture.recording();
+            drop(capture);
+            recording.to_wav()
+        });
+
+        let transcribe = cx.spawn_in(window, async move |this, cx| {
+            let wav = wav.await;
+            let Ok(transcription) = this.update(cx, |_, cx| dictation::transcribe(wav, cx)) else {
+                return;
+            };
+            let transcription = transcription.await;
+            this.update_in(cx, |this, window, cx| {
+                this.dictation = None;
+                match transcription {
+                    Ok(text) => {
+                        let text = dictation::clean_up_transcript(&text);
+                        this.set_dictated_text(range.clone(), text, false, cx);
+                        this.editor.update(cx, |editor, cx| {
+                            editor.change_selections(None, window, cx, |selections| {
+                                selections.select_anchor_ranges([range.end..range.end]);
+                            });
+                        });
+                    }
+                    Err(error) => {
+                        this.set_dictated_text(range, String::new(), false, cx);
+                        this.show_dictation_error(error, cx);
+                    }
+                }
+                cx.notify();
+            })
+            .log_err();
+        });
+        self.dictation = Some(DictationState::Finalizing {
+            _transcribe: transcribe,
+        });
+        cx.notify();
+    }
+
+    fn set_dictated_text(
+        &mut self,
+        range: Range<editor::Anchor>,
+        text: String,
+        is_interim: bool,
+        cx: &mut Context<Self>,
+    ) {
+        self.editor.update(cx, |editor, cx| {
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            let follows_word = snapshot
+                .reversed_chars_at(range.start)
+                .next()
+                .is_some_and(|c| !c.is_whitespace());
+            let text = if follows_word && !text.is_empty() {
+                format!(" {text}")
+            } else {
+                text
+            };
+            editor.edit([(range.clone(), text)], cx);
+
+            if is_interim {
+                editor.highlight_text::<DictationHighlight>(
+                    vec![range],
+                    HighlightStyle {
+                        color: Some(cx.theme().colors().text_muted),
+                        ..Default::default()
+                    },
+                    cx,
+                );
+            } else {
+                editor.clear_highlights::<DictationHighlight>(cx);
+            }
+        });
+    }
+
+    fn show_dictation_error(&mut self, error: anyhow::Error, cx: &mut Context<Self>) {
+        log::error!("dictation failed: {error:#}");
+        self.thread.update(cx, |_, cx| {
+            cx.emit(ThreadEvent::ShowError(ThreadError::Message {
+                header: "Dictation failed".into(),
+                message: format!("{error:#}").into(),
+            }));
+        });
+        cx.notify();
+    }
+
     fn handle_accept_all(&mut self, _window: &mut Window, cx: &mut Context<Self>) {
         if self.thread.read(cx).has_pending_edit_tool_uses() {
             return;
@@ -546,6 +976,106 @@ impl MessageEditor {
         )
     }
 
+    fn render_compare_models_toggle(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let is_comparing = self.thread.read(cx).comparison_model().is_some();
+
+        IconButton::new("compare-models", IconName::ArrowRightLeft)
+            .icon_size(IconSize::Small)
+            .icon_color(Color::Muted)
+            .toggle_state(is_comparing)
+            .selected_icon_color(Color::Accent)
+            .tooltip(move |window, cx| {
+                if is_comparing {
+                    Tooltip::for_action("Stop Comparing Models", &ToggleCompareModels, window, cx)
+                } else {
+                    Tooltip::with_meta(
+                        "Compare Models",
+                        Some(&ToggleCompareModels),
+                        "Send each message to two models and choose which response to continue with.",
+                        window,
+                        cx,
+                    )
+                }
+            })
+            .on_click(cx.listener(|this, _, window, cx| {
+                this.toggle_compare_models(&ToggleCompareModels, window, cx);
+            }))
+    }
+
+    fn render_plan_only_toggle(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let plan_only = self.thread.read(cx).plan_only();
+
+        IconButton::new("plan-only", IconName::Route)
+            .icon_size(IconSize::Small)
+            .icon_color(Color::Muted)
+            .toggle_state(plan_only)
+            .selected_icon_color(Color::Accent)
+            .tooltip(move |window, cx| {
+                if plan_only {
+                    Tooltip::for_action("Stop Planning Edits", &TogglePlanOnly, window, cx)
+                } else {
+                    Tooltip::with_meta(
+                        "Plan Edits Only",
+                        Some(&TogglePlanOnly),
+                        "Preview the agent's edits in the diff without changing any files.",
+                        window,
+                        cx,
+                    )
+                }
+            })
+            .on_click(cx.listener(|this, _, window, cx| {
+                this.toggle_plan_only(&TogglePlanOnly, window, cx);
+            }))
+    }
+
+    fn render_dictation_button(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let is_recording = matches!(self.dictation, Some(DictationState::Recording { .. }));
+        let is_starting = matches!(self.dictation, Some(DictationState::Starting { .. }));
+        let is_finalizing = matches!(self.dictation, Some(DictationState::Finalizing { .. }));
+
+        div()
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(|this, _, window, cx| {
+                    cx.stop_propagation();
+                    this.toggle_dictation(&ToggleDictation, window, cx);
+                }),
+            )
+            .on_mouse_up(
+                MouseButton::Left,
+                cx.listener(|this, _, window, cx| this.release_push_to_talk(window, cx)),
+            )
+            .on_mouse_up_out(
+                MouseButton::Left,
+                cx.listener(|this, _, window, cx| this.release_push_to_talk(window, cx)),
+            )
+            .child(
+                IconButton::new("dictation", IconName::Mic)
+                    .icon_size(IconSize::Small)
+                    .icon_color(Color::Muted)
+                    .toggle_state(is_recording)
+                    .selected_icon_color(Color::Error)
+                    .disabled(is_starting || is_finalizing)
+                    .tooltip(move |window, cx| {
+                        if is_starting {
+                            Tooltip::simple("Starting Microphone…", cx)
+                        } else if is_finalizing {
+                            Tooltip::simple("Transcribing…", cx)
+                        } else if is_recording {
+                            Tooltip::for_action("Stop Dictation", &ToggleDictation, window, cx)
+                        } else {
+                            Tooltip::with_meta(
+                                "Dictate",
+                                Some(&ToggleDictation),
+                                "Hold to talk, or click to start and stop.",
+                                window,
+                                cx,
+                            )
+                        }
+                    }),
+            )
+    }
+
     fn render_follow_toggle(&self, cx: &mut Context<Self>) -> impl IntoElement {
         let following = self
             .workspace
@@ -594,6 +1124,7 @@ impl MessageEditor {
         let focus_handle = self.editor.focus_handle(cx);
 
         let is_model_selected = model.is_some();
+        let is_comparing = thread.comparison_model().is_some();
         let is_editor_empty = self.is_editor_empty(cx);
 
         let incompatible_tools = model
@@ -635,6 +1166,11 @@ impl MessageEditor {
             .on_action(cx.listener(Self::move_up))
             .on_action(cx.listener(Self::expand_message_editor))
             .on_action(cx.listener(Self::toggle_burn_mode))
+            .on_action(cx.listener(Self::toggle_compare_models))
+            .on_action(cx.listener(Self::toggle_plan_only))
+            .on_action(cx.listener(Self::apply_plan))
+            .on_action(cx.listener(Self::discard_plan))
+            .on_action(cx.listener(Self::toggle_dictation))
             .on_action(
                 cx.listener(|this, _: &KeepAll, window, cx| this.handle_accept_all(window, cx)),
             )
@@ -734,7 +1270,10 @@ impl MessageEditor {
                             .child(
                                 h_flex()
                                     .child(self.render_follow_toggle(cx))
-                                    .children(self.render_max_mode_toggle(cx)),
+                                    .child(self.render_compare_models_toggle(cx))
+                                    .child(self.render_plan_only_toggle(cx))
+                                    .children(self.render_max_mode_toggle(cx))
+                                    .child(self.render_dictation_button(cx)),
                             )
                             .child(
                                 h_flex()
@@ -760,6 +1299,14 @@ impl MessageEditor {
                                     })
                                     .child(self.profile_selector.clone())
                                     .child(self.model_selector.clone())
+                                    .when(is_comparing, |this| {
+                                        this.child(
+                                            Label::new("vs")
+                                                .size(LabelSize::Small)
+                                                .color(Color::Muted),
+                                        )
+                                        .child(self.comparison_model_selector.clone())
+                                    })
                                     .map({
                                         let focus_handle = focus_handle.clone();
                                         move |parent| {
@@ -882,6 +1429,99 @@ impl MessageEditor {
             )
     }
 
+    fn render_planned_edits(
+        &self,
+        planned_file_count: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let focus_handle = self.editor.focus_handle(cx);
+        let pending_edits = self.thread.read(cx).has_pending_edit_tool_uses();
+
+        h_flex()
+            .mt_1()
+            .mx_2()
+            .p_1()
+            .justify_between()
+            .bg(cx.theme().colors().editor_background)
+            .border_1()
+            .border_b_0()
+            .border_color(cx.theme().colors().border)
+            .rounded_t_md()
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(
+                        Icon::new(IconName::Route)
+                            .size(IconSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .child(
+                        Label::new("Plan")
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .child(Label::new("•").size(LabelSize::XSmall).color(Color::Muted))
+                    .child(
+                        Label::new(format!(
+                            "{} {}",
+                            planned_file_count,
+                            if planned_file_count == 1 {
+                                "file"
+                            } else {
+                                "files"
+                            }
+                        ))
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                    ),
+            )
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(
+                        IconButton::new("review-plan", IconName::ListTodo)
+                            .icon_size(IconSize::Small)
+                            .tooltip({
+                                let focus_handle = focus_handle.clone();
+                                move |window, cx| {
+                                    Tooltip::for_action_in(
+                                        "Review Plan",
+                                        &OpenAgentDiff,
+                                        &focus_handle,
+                                        window,
+                                        cx,
+                                    )
+                                }
+                            })
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.handle_review_click(window, cx)
+                            })),
+                    )
+                    .child(ui::Divider::vertical().color(ui::DividerColor::Border))
+                    .child(
+                        Button::new("discard-plan", "Discard")
+                            .label_size(LabelSize::Small)
+                            .disabled(pending_edits)
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.discard_plan(&DiscardPlan, window, cx)
+                            })),
+                    )
+                    .child(
+                        Button::new("apply-plan", "Apply Plan")
+                            .label_size(LabelSize::Small)
+                            .disabled(pending_edits)
+                            .key_binding(
+                                KeyBinding::for_action_in(&ApplyPlan, &focus_handle, window, cx)
+                                    .map(|kb| kb.size(rems_from_px(10.))),
+                            )
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.apply_plan(&ApplyPlan, window, cx)
+                            })),
+                    ),
+            )
+    }
+
     fn render_changed_buffers(
         &self,
         changed_buffers: &BTreeMap<Entity<Buffer>, Entity<BufferDiff>>,
@@ -1319,6 +1959,48 @@ impl MessageEditor {
 
     fn handle_message_changed(&mut self, cx: &mut Context<Self>) {
         self.message_or_context_changed(true, cx);
+        self.rank_context_files(cx);
+    }
+
+    /// Suggests the project files most relevant to the message, when the user
+    /// hasn't attached any context themselves.
+    fn rank_context_files(&mut self, cx: &mut Context<Self>) {
+        self.rank_context_task.take();
+
+        let query = self.editor.read(cx).text(cx);
+        if query.trim().is_empty() {
+            self.context_strip.update(cx, |context_strip, cx| {
+                context_strip.set_ranked_files(Vec::new(), cx)
+            });
+            return;
+        }
+        // Keep the existing suggestions so the user can attach them one by one.
+        if self.context_store.read(cx).context().next().is_some() {
+            return;
+        }
+
+        self.rank_context_task = Some(cx.spawn(async move |this, cx| {
+            cx.background_executor()
+                .timer(Duration::from_millis(500))
+                .await;
+
+            let Ok(ranking) = this.update(cx, |this, cx| {
+                context_ranking::rank_context_files(query, this.project.clone(), cx)
+            }) else {
+                return;
+            };
+            let Some(ranked_files) = ranking.await.log_err() else {
+                return;
+            };
+
+            this.update(cx, |this, cx| {
+                this.context_strip.update(cx, |context_strip, cx| {
+                    context_strip.set_ranked_files(ranked_files, cx)
+                });
+                this.rank_context_task = None;
+            })
+            .ok();
+        }));
     }
 
     fn message_or_context_changed(&mut self, debounce: bool, cx: &mut Context<Self>) {
@@ -1379,6 +2061,7 @@ impl MessageEditor {
                         tool_choice: None,
                         stop: vec![],
                         temperature: AgentSettings::temperature_for_model(&model.model, cx),
+                        response_schema: None,
                     };
 
                     Some(model.model.count_tokens(request, cx))
@@ -1474,11 +2157,15 @@ impl Render for MessageEditor {
 
         let action_log = self.thread.read(cx).action_log();
         let changed_buffers = action_log.read(cx).changed_buffers(cx);
+        let planned_file_count = action_log.read(cx).planned_edits(cx).len();
 
         let line_height = TextSize::Small.rems(cx).to_pixels(window.rem_size()) * 1.5;
 
         v_flex()
             .size_full()
+            .when(planned_file_count > 0, |parent| {
+                parent.child(self.render_planned_edits(planned_file_count, window, cx))
+            })
             .when(changed_buffers.len() > 0, |parent| {
                 parent.child(self.render_changed_buffers(&changed_buffers, window, cx))
             })
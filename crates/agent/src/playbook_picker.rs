@@ -0,0 +1,335 @@
+use std::sync::Arc;
+
+use editor::Editor;
+use fuzzy::StringMatchCandidate;
+use gpui::{
+    App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Render, Subscription,
+    Task, WeakEntity, Window,
+};
+use picker::{Picker, PickerDelegate};
+use prompt_store::{Playbook, PlaybookVariables, PromptStore};
+use ui::{HighlightedLabel, ListItem, ListItemSpacing, Tooltip, prelude::*};
+use util::ResultExt as _;
+use workspace::notifications::DetachAndPromptErr as _;
+use workspace::{ModalView, Workspace};
+
+use crate::AgentPanel;
+
+/// Lists the saved playbooks so one can be run, and saves the message being composed in the agent
+/// panel as a new playbook when a title that doesn't exist yet is typed.
+pub(crate) struct PlaybookPicker {
+    picker: Entity<Picker<PlaybookPickerDelegate>>,
+    _subscription: Subscription,
+}
+
+impl PlaybookPicker {
+    pub(crate) fn toggle(
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let Some(panel) = workspace.panel::<AgentPanel>(cx) else {
+            return;
+        };
+        let Some(prompt_store) = panel.read(cx).prompt_store().clone() else {
+            return;
+        };
+        // Read these before the modal takes focus, while the editor the user was in is active.
+        let variables = playbook_variables(workspace, cx);
+        let workspace_handle = cx.weak_entity();
+        workspace.toggle_modal(window, cx, |window, cx| {
+            Self::new(prompt_store, variables, workspace_handle, window, cx)
+        });
+    }
+
+    fn new(
+        prompt_store: Entity<PromptStore>,
+        variables: PlaybookVariables,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let playbooks = prompt_store.read(cx).playbooks(cx);
+        cx.spawn_in(window, async move |this, cx| {
+            let playbooks = playbooks.await?;
+            this.update_in(cx, |this, window, cx| {
+                this.picker.update(cx, |picker, cx| {
+                    picker.delegate.playbooks = playbooks;
+                    picker.refresh(window, cx);
+                })
+            })?;
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+
+        let delegate = PlaybookPickerDelegate {
+            prompt_store,
+            variables,
+            workspace,
+            playbooks: Vec::new(),
+            matches: Vec::new(),
+            selected_index: 0,
+        };
+        let picker = cx.new(|cx| Picker::uniform_list(delegate, window, cx));
+        let _subscription = cx.subscribe(&picker, |_, _, _, cx| cx.emit(DismissEvent));
+        Self {
+            picker,
+            _subscription,
+        }
+    }
+}
+
+fn playbook_variables(workspace: &Workspace, cx: &mut App) -> PlaybookVariables {
+    let Some(editor) = workspace.active_item_as::<Editor>(cx) else {
+        return PlaybookVariables::default();
+    };
+    editor.update(cx, |editor, cx| {
+        let selection = editor.selections.newest_adjusted(cx);
+        let snapshot = editor.buffer().read(cx).snapshot(cx);
+        let selection = (!selection.is_empty()).then(|| {
+            snapshot
+                .text_for_range(selection.range())
+                .collect::<String>()
+        });
+        let buffer = editor
+            .active_excerpt(cx)
+            .map(|(_, buffer, _)| buffer.read(cx));
+        PlaybookVariables {
+            selection,
+            file: buffer
+                .and_then(|buffer| buffer.file())
+                .map(|file| file.path().to_string_lossy().into_owned()),
+            language: buffer
+                .and_then(|buffer| buffer.language())
+                .map(|language| language.name().to_string()),
+        }
+    })
+}
+
+impl ModalView for PlaybookPicker {}
+impl EventEmitter<DismissEvent> for PlaybookPicker {}
+
+impl Focusable for PlaybookPicker {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for PlaybookPicker {
+    fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+enum PlaybookMatch {
+    Playbook { ix: usize, positions: Vec<usize> },
+    New { title: SharedString },
+}
+
+pub(crate) struct PlaybookPickerDelegate {
+    prompt_store: Entity<PromptStore>,
+    variables: PlaybookVariables,
+    workspace: WeakEntity<Workspace>,
+    playbooks: Vec<Playbook>,
+    matches: Vec<PlaybookMatch>,
+    selected_index: usize,
+}
+
+impl PlaybookPickerDelegate {
+    fn delete_playbook(&mut self, ix: usize, cx: &mut Context<Picker<Self>>) {
+        let playbook = self.playbooks.remove(ix);
+        self.prompt_store
+            .update(cx, |prompt_store, cx| {
+                prompt_store.delete_playbook(playbook.id, cx)
+            })
+            .detach_and_log_err(cx);
+    }
+}
+
+impl PickerDelegate for PlaybookPickerDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        "Run a playbook, or name a new one…".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let candidates = self
+            .playbooks
+            .iter()
+            .enumerate()
+            .map(|(ix, playbook)| StringMatchCandidate::new(ix, &playbook.title))
+            .collect::<Vec<_>>();
+        let titles = self
+            .playbooks
+            .iter()
+            .map(|playbook| playbook.title.clone())
+            .collect::<Vec<_>>();
+        cx.spawn_in(window, async move |picker, cx| {
+            let mut matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| PlaybookMatch::Playbook {
+                        ix: candidate.id,
+                        positions: Vec::new(),
+                    })
+                    .collect::<Vec<_>>()
+            } else {
+                fuzzy::match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    100,
+                    &Default::default(),
+                    cx.background_executor().clone(),
+                )
+                .await
+                .into_iter()
+                .map(|candidate| PlaybookMatch::Playbook {
+                    ix: candidate.candidate_id,
+                    positions: candidate.positions,
+                })
+                .collect()
+            };
+
+            let query = query.trim();
+            if !query.is_empty() && !titles.iter().any(|title| title == query) {
+                matches.push(PlaybookMatch::New {
+                    title: query.to_string().into(),
+                });
+            }
+
+            picker
+                .update(cx, |picker, _| {
+                    let delegate = &mut picker.delegate;
+                    delegate.matches = matches;
+                    delegate.selected_index = delegate
+                        .selected_index
+                        .min(delegate.matches.len().saturating_sub(1));
+                })
+                .log_err();
+        })
+    }
+
+    fn confirm(&mut self, _secondary: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(entry) = self.matches.get(self.selected_index) else {
+            return;
+        };
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        let Some(panel) = workspace.read(cx).panel::<AgentPanel>(cx) else {
+            return;
+        };
+
+        match entry {
+            PlaybookMatch::Playbook { ix, .. } => {
+                let Some(playbook) = self.playbooks.get(*ix) else {
+                    return;
+                };
+                workspace.update(cx, |workspace, cx| {
+                    workspace.focus_panel::<AgentPanel>(window, cx);
+                    let result = panel.update(cx, |panel, cx| {
+                        panel.run_playbook(playbook, &self.variables, window, cx)
+                    });
+                    if let Err(error) = result {
+                        workspace.show_error(&error, cx);
+                    }
+                });
+            }
+            PlaybookMatch::New { title } => {
+                panel
+                    .update(cx, |panel, cx| {
+                        panel.save_message_as_playbook(title.clone(), cx)
+                    })
+                    .detach_and_prompt_err("Failed to save playbook", window, cx, |error, _, _| {
+                        Some(error.to_string())
+                    });
+            }
+        }
+        cx.emit(DismissEvent);
+    }
+
+    fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let item = ListItem::new(ix)
+            .inset(true)
+            .spacing(ListItemSpacing::Sparse)
+            .toggle_state(selected);
+
+        match self.matches.get(ix)? {
+            PlaybookMatch::Playbook {
+                ix: playbook_ix,
+                positions,
+            } => {
+                let playbook = self.playbooks.get(*playbook_ix)?;
+                let playbook_ix = *playbook_ix;
+                Some(
+                    item.child(HighlightedLabel::new(
+                        playbook.title.clone(),
+                        positions.clone(),
+                    ))
+                    .end_slot(playbook.profile_id.clone().map(|profile_id| {
+                        Label::new(profile_id)
+                            .size(LabelSize::Small)
+                            .color(Color::Muted)
+                    }))
+                    .end_hover_slot(
+                        IconButton::new(("delete-playbook", ix), IconName::Trash)
+                            .icon_size(IconSize::Small)
+                            .icon_color(Color::Muted)
+                            .tooltip(Tooltip::text("Delete Playbook"))
+                            .on_click(cx.listener(move |picker, _, window, cx| {
+                                picker.delegate.delete_playbook(playbook_ix, cx);
+                                picker.refresh(window, cx);
+                                cx.stop_propagation();
+                            })),
+                    ),
+                )
+            }
+            PlaybookMatch::New { title } => Some(
+                item.start_slot(
+                    Icon::new(IconName::Plus)
+                        .size(IconSize::Small)
+                        .color(Color::Muted),
+                )
+                .child(Label::new(format!("Save Message as \"{title}\"")).single_line()),
+            ),
+        }
+    }
+
+    fn no_matches_text(&self, _window: &mut Window, _cx: &mut App) -> Option<SharedString> {
+        Some("No playbooks yet. Type a title to save the current message as one.".into())
+    }
+}
@@ -0,0 +1,98 @@
+use std::sync::Arc;
+
+use anyhow::{Result, anyhow};
+use assistant_tool::{ActionLog, Tool, ToolResult, ToolResultOutput};
+use gpui::{AnyWindowHandle, App, AppContext as _, Entity, Task};
+use language_model::{LanguageModel, LanguageModelRequest, LanguageModelToolSchemaFormat};
+use project::Project;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use ui::IconName;
+use util::markdown::MarkdownInlineCode;
+
+use crate::memory_store::{MAX_MEMORY_LEN, MemoryStore, ProjectKey};
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct RememberToolInput {
+    /// A short, self-contained fact about this project, such as "The build command is `cargo nextest run`".
+    pub fact: String,
+}
+
+pub struct RememberTool;
+
+impl Tool for RememberTool {
+    fn name(&self) -> String {
+        "remember".into()
+    }
+
+    fn description(&self) -> String {
+        format!(
+            "Saves a short fact about this project, which will be included in future conversations about it. \
+            Use this for durable facts that you had to discover and that will still be true later, such as \
+            build and test commands, project conventions, or where something is configured. \
+            Don't remember anything about the current task, and don't remember secrets. \
+            Facts must be at most {MAX_MEMORY_LEN} characters."
+        )
+    }
+
+    fn icon(&self) -> IconName {
+        IconName::Brain
+    }
+
+    fn needs_confirmation(&self, _: &serde_json::Value, _: &App) -> bool {
+        // Memories end up in the system prompt of every future thread in this project,
+        // so text injected into a single conversation could otherwise persist indefinitely.
+        true
+    }
+
+    fn may_perform_edits(&self) -> bool {
+        false
+    }
+
+    fn input_schema(&self, format: LanguageModelToolSchemaFormat) -> Result<serde_json::Value> {
+        let mut schema = serde_json::to_value(schemars::schema_for!(RememberToolInput))?;
+        assistant_tool::adapt_schema_to_format(&mut schema, format)?;
+        Ok(schema)
+    }
+
+    fn ui_text(&self, input: &serde_json::Value) -> String {
+        match serde_json::from_value::<RememberToolInput>(input.clone()) {
+            Ok(input) => format!("Remember {}", MarkdownInlineCode(&input.fact)),
+            Err(_) => "Remember fact".to_string(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: serde_json::Value,
+        _request: Arc<LanguageModelRequest>,
+        project: Entity<Project>,
+        _action_log: Entity<ActionLog>,
+        _model: Arc<dyn LanguageModel>,
+        _window: Option<AnyWindowHandle>,
+        cx: &mut App,
+    ) -> ToolResult {
+        let input = match serde_json::from_value::<RememberToolInput>(input) {
+            Ok(input) => input,
+            Err(err) => return Task::ready(Err(anyhow!(err))).into(),
+        };
+        let Some(project_key) = ProjectKey::for_project(&project, cx) else {
+            return Task::ready(Err(anyhow!(
+                "Facts can only be remembered in projects that have at least one folder open"
+            )))
+            .into();
+        };
+
+        let remember = MemoryStore::global(cx).update(cx, |store, cx| {
+            store.remember(&project_key, &input.fact, cx)
+        });
+        cx.background_spawn(async move {
+            remember.await?;
+            Ok(ToolResultOutput::from(
+                "Remembered. This fact will be included in future conversations about this project."
+                    .to_string(),
+            ))
+        })
+        .into()
+    }
+}
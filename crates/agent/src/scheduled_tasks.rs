@@ -0,0 +1,408 @@
+use std::str::FromStr;
+
+use agent_settings::{AgentSettings, ScheduledTask};
+use anyhow::{Context as _, Result, anyhow};
+use chrono::{Datelike as _, Duration, Local, NaiveDate, NaiveDateTime, Timelike as _};
+use collections::HashMap;
+use gpui::{App, Context, Entity, Global, Subscription, Task, WeakEntity};
+use language_model::{Role, StopReason};
+use notifications::NotificationStore;
+use rpc::Notification;
+use settings::{Settings as _, SettingsStore};
+use util::ResultExt as _;
+use zed_llm_client::CompletionIntent;
+
+use crate::context::ContextLoadResult;
+use crate::thread::{MessageSegment, Thread, ThreadEvent, ThreadId};
+use crate::thread_store::ThreadStore;
+
+const MAX_SUMMARY_LEN: usize = 200;
+
+/// A cron-like schedule with five fields: minute, hour, day of month, month, and day of week.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schedule {
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    /// Like cron, when both day fields are restricted, a day matches if either of them does.
+    days_restricted: bool,
+}
+
+impl FromStr for Schedule {
+    type Err = anyhow::Error;
+
+    fn from_str(schedule: &str) -> Result<Self> {
+        let schedule = match schedule.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@nightly" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            schedule => schedule,
+        };
+        let fields = schedule.split_whitespace().collect::<Vec<_>>();
+        let [minute, hour, day_of_month, month, day_of_week] = fields[..] else {
+            anyhow::bail!("expected 5 fields, found {}", fields.len());
+        };
+
+        let mut days_of_week = parse_field(day_of_week, 0, 7).context("day of week")?;
+        // Both 0 and 7 mean Sunday.
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week = (days_of_week | 1) & !(1 << 7);
+        }
+
+        Ok(Self {
+            minutes: parse_field(minute, 0, 59).context("minute")?,
+            hours: parse_field(hour, 0, 23).context("hour")?,
+            days_of_month: parse_field(day_of_month, 1, 31).context("day of month")?,
+            months: parse_field(month, 1, 12).context("month")?,
+            days_of_week,
+            days_restricted: !day_of_month.starts_with('*') && !day_of_week.starts_with('*'),
+        })
+    }
+}
+
+fn parse_field(field: &str, min: u32, max: u32) -> Result<u64> {
+    let mut values = 0;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, Some(step.parse::<usize>()?)),
+            None => (part, None),
+        };
+        let (start, end) = match range.split_once('-') {
+            _ if range == "*" => (min, max),
+            Some((start, end)) => (start.parse()?, end.parse()?),
+            None => {
+                let start = range.parse()?;
+                (start, if step.is_some() { max } else { start })
+            }
+        };
+        if start < min || end > max || start > end {
+            return Err(anyhow!("{part} is outside of {min}-{max}"));
+        }
+        let step = step.unwrap_or(1);
+        anyhow::ensure!(step > 0, "step must be greater than zero");
+        for value in (start..=end).step_by(step) {
+            values |= 1 << value;
+        }
+    }
+    Ok(values)
+}
+
+impl Schedule {
+    /// Returns the first time matching the schedule that's strictly after `after`.
+    pub fn next_after(&self, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        let mut time = after.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        // Every valid schedule matches at least once every four years, on February 29th.
+        let limit = time + Duration::days(366 * 4 + 1);
+        while time < limit {
+            if !contains(self.months, time.month()) {
+                let (year, month) = if time.month() == 12 {
+                    (time.year() + 1, 1)
+                } else {
+                    (time.year(), time.month() + 1)
+                };
+                time = NaiveDate::from_ymd_opt(year, month, 1)?.and_hms_opt(0, 0, 0)?;
+            } else if !self.matches_day(time.date()) {
+                time = time.date().succ_opt()?.and_hms_opt(0, 0, 0)?;
+            } else if !contains(self.hours, time.hour()) {
+                time = time.with_minute(0)? + Duration::hours(1);
+            } else if !contains(self.minutes, time.minute()) {
+                time += Duration::minutes(1);
+            } else {
+                return Some(time);
+            }
+        }
+        None
+    }
+
+    fn matches_day(&self, date: NaiveDate) -> bool {
+        let day_of_month = contains(self.days_of_month, date.day());
+        let day_of_week = contains(self.days_of_week, date.weekday().num_days_from_sunday());
+        if self.days_restricted {
+            day_of_month || day_of_week
+        } else {
+            day_of_month && day_of_week
+        }
+    }
+}
+
+fn contains(values: u64, value: u32) -> bool {
+    values & (1 << value) != 0
+}
+
+/// The time each task was last started, shared across windows so that a task only runs once
+/// even when several windows have an agent panel.
+#[derive(Default)]
+struct ScheduledRuns(HashMap<String, NaiveDateTime>);
+
+impl Global for ScheduledRuns {}
+
+/// Runs the scheduled tasks from the agent settings in the background, and reports their
+/// results in the notification center.
+pub struct TaskScheduler {
+    thread_store: WeakEntity<ThreadStore>,
+    tasks: Vec<ScheduledTask>,
+    _timers: Vec<Task<()>>,
+    running: HashMap<ThreadId, RunningTask>,
+    _settings_subscription: Subscription,
+}
+
+struct RunningTask {
+    _thread: Entity<Thread>,
+    _subscription: Subscription,
+}
+
+impl TaskScheduler {
+    pub fn new(thread_store: WeakEntity<ThreadStore>, cx: &mut Context<Self>) -> Self {
+        let mut this = Self {
+            thread_store,
+            tasks: Vec::new(),
+            _timers: Vec::new(),
+            running: HashMap::default(),
+            _settings_subscription: cx.observe_global::<SettingsStore>(Self::reschedule),
+        };
+        this.reschedule(cx);
+        this
+    }
+
+    fn reschedule(&mut self, cx: &mut Context<Self>) {
+        let tasks = AgentSettings::get_global(cx).scheduled_tasks.clone();
+        if tasks == self.tasks {
+            return;
+        }
+
+        self._timers = tasks
+            .iter()
+            .filter(|task| task.enabled)
+            .filter_map(|task| {
+                let schedule = task
+                    .schedule
+                    .parse::<Schedule>()
+                    .with_context(|| {
+                        format!(
+                            "invalid schedule for task {:?}: {}",
+                            task.name, task.schedule
+                        )
+                    })
+                    .log_err()?;
+                Some(Self::schedule_task(task.clone(), schedule, cx))
+            })
+            .collect();
+        self.tasks = tasks;
+    }
+
+    fn schedule_task(task: ScheduledTask, schedule: Schedule, cx: &mut Context<Self>) -> Task<()> {
+        cx.spawn(async move |this, cx| {
+            loop {
+                let now = Local::now().naive_local();
+                let Some(next) = schedule.next_after(now) else {
+                    break;
+                };
+                let delay = (next - now).to_std().unwrap_or_default();
+                cx.background_executor().timer(delay).await;
+                if this
+                    .update(cx, |this, cx| this.run_task(&task, next, cx))
+                    .is_err()
+                {
+                    break;
+                }
+            }
+        })
+    }
+
+    fn run_task(
+        &mut self,
+        task: &ScheduledTask,
+        scheduled_for: NaiveDateTime,
+        cx: &mut Context<Self>,
+    ) {
+        let previous_run = cx
+            .default_global::<ScheduledRuns>()
+            .0
+            .insert(task.name.clone(), scheduled_for);
+        if previous_run == Some(scheduled_for) {
+            return;
+        }
+        let Some(thread_store) = self.thread_store.upgrade() else {
+            return;
+        };
+
+        let thread = thread_store.update(cx, |thread_store, cx| thread_store.create_thread(cx));
+        let thread_id = thread.read(cx).id().clone();
+        let model = thread.update(cx, |thread, cx| {
+            thread.set_summary(task.name.clone(), cx);
+            thread.insert_user_message(
+                task.prompt.clone(),
+                ContextLoadResult::default(),
+                None,
+                Vec::new(),
+                cx,
+            );
+            thread.get_or_init_configured_model(cx)
+        });
+        let Some(model) = model else {
+            notify(
+                &task.name,
+                &thread_id,
+                "No language model is configured".into(),
+                cx,
+            );
+            return;
+        };
+
+        let task_name = task.name.clone();
+        let subscription = cx.subscribe(&thread, move |this, thread, event, cx| match event {
+            ThreadEvent::Stopped(Ok(StopReason::ToolUse)) => {}
+            ThreadEvent::Stopped(result) => {
+                let summary = match result {
+                    Ok(_) => last_assistant_text(thread.read(cx)),
+                    Err(error) => format!("Failed: {error}"),
+                };
+                let thread_id = thread.read(cx).id().clone();
+                if let Some(thread_store) = this.thread_store.upgrade() {
+                    thread_store
+                        .update(cx, |thread_store, cx| thread_store.save_thread(&thread, cx))
+                        .detach_and_log_err(cx);
+                }
+                notify(&task_name, &thread_id, summary, cx);
+                this.running.remove(&thread_id);
+            }
+            ThreadEvent::ToolConfirmationNeeded => {
+                let thread_id = thread.read(cx).id().clone();
+                notify(
+                    &task_name,
+                    &thread_id,
+                    "Waiting for a tool call to be confirmed".into(),
+                    cx,
+                );
+            }
+            _ => {}
+        });
+        thread.update(cx, |thread, cx| {
+            thread.send_to_model(model.model, CompletionIntent::UserPrompt, None, cx)
+        });
+        self.running.insert(
+            thread_id,
+            RunningTask {
+                _thread: thread,
+                _subscription: subscription,
+            },
+        );
+    }
+}
+
+fn last_assistant_text(thread: &Thread) -> String {
+    let text = thread
+        .messages()
+        .filter(|message| message.role == Role::Assistant)
+        .last()
+        .map(|message| {
+            message
+                .segments
+                .iter()
+                .filter_map(|segment| match segment {
+                    MessageSegment::Text(text) => Some(text.as_str()),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .unwrap_or_default();
+    let text = text.trim();
+    if text.chars().count() > MAX_SUMMARY_LEN {
+        let truncated = text.chars().take(MAX_SUMMARY_LEN).collect::<String>();
+        format!("{}…", truncated.trim_end())
+    } else {
+        text.to_string()
+    }
+}
+
+fn notify(task_name: &str, thread_id: &ThreadId, summary: String, cx: &mut App) {
+    cx.post_notification(task_name, &summary)
+        .detach_and_log_err(cx);
+    if let Some(notification_store) = NotificationStore::try_global(cx) {
+        notification_store.update(cx, |store, cx| {
+            store.add_local_notification(
+                Notification::AgentTaskFinished {
+                    task_name: task_name.to_string(),
+                    thread_id: thread_id.to_string(),
+                    summary,
+                },
+                cx,
+            )
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    fn next(schedule: &str, after: &str) -> NaiveDateTime {
+        schedule
+            .parse::<Schedule>()
+            .unwrap()
+            .next_after(time(after))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_next_after() {
+        assert_eq!(
+            next("@nightly", "2025-03-10 12:30"),
+            time("2025-03-11 00:00")
+        );
+        assert_eq!(
+            next("*/15 * * * *", "2025-03-10 12:30"),
+            time("2025-03-10 12:45")
+        );
+        assert_eq!(
+            next("30 9 * * 1-5", "2025-03-14 10:00"),
+            time("2025-03-17 09:30")
+        );
+        assert_eq!(
+            next("0 0 1 */3 *", "2025-02-10 00:00"),
+            time("2025-04-01 00:00")
+        );
+        assert_eq!(
+            next("0 12 29 2 *", "2025-03-01 00:00"),
+            time("2028-02-29 12:00")
+        );
+        assert_eq!(
+            next("0 8 * * 7", "2025-03-10 00:00"),
+            time("2025-03-16 08:00")
+        );
+        // With both day fields restricted, either one matching is enough.
+        assert_eq!(
+            next("0 0 13 * 5", "2025-03-10 00:00"),
+            time("2025-03-13 00:00")
+        );
+        assert_eq!(
+            next("59 23 31 12 *", "2025-12-31 23:59"),
+            time("2026-12-31 23:59")
+        );
+    }
+
+    #[test]
+    fn test_invalid_schedules() {
+        for schedule in [
+            "* * * *",
+            "60 * * * *",
+            "0 24 * * *",
+            "0 0 0 * *",
+            "*/0 * * * *",
+            "5-1 * * * *",
+        ] {
+            assert!(schedule.parse::<Schedule>().is_err(), "{schedule}");
+        }
+
+        let never = "0 0 30 2 *".parse::<Schedule>().unwrap();
+        assert_eq!(never.next_after(time("2025-01-01 00:00")), None);
+    }
+}
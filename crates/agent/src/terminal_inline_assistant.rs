@@ -298,6 +298,7 @@ impl TerminalInlineAssistant {
                 tool_choice: None,
                 stop: Vec::new(),
                 temperature,
+                response_schema: None,
             }
         }))
     }
@@ -2,7 +2,7 @@ use std::fmt::Write as _;
 use std::io::Write;
 use std::ops::Range;
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use agent_settings::{AgentSettings, CompletionMode};
 use anyhow::{Result, anyhow};
@@ -21,15 +21,15 @@ use gpui::{
 use language_model::{
     ConfiguredModel, LanguageModel, LanguageModelCompletionError, LanguageModelCompletionEvent,
     LanguageModelId, LanguageModelKnownError, LanguageModelRegistry, LanguageModelRequest,
-    LanguageModelRequestMessage, LanguageModelRequestTool, LanguageModelToolResult,
-    LanguageModelToolResultContent, LanguageModelToolUseId, MessageContent,
-    ModelRequestLimitReachedError, PaymentRequiredError, RequestUsage, Role, SelectedModel,
-    StopReason, TokenUsage,
+    LanguageModelRequestMessage, LanguageModelRequestTool, LanguageModelToolChoice,
+    LanguageModelToolResult, LanguageModelToolResultContent, LanguageModelToolUseId,
+    MessageContent, ModelRequestLimitReachedError, PaymentRequiredError, QueuedRequestId,
+    RateLimiter, RequestUsage, Role, SelectedModel, StopReason, TokenUsage,
 };
 use postage::stream::Stream as _;
 use project::Project;
 use project::git_store::{GitStore, GitStoreCheckpoint, RepositoryState};
-use prompt_store::{ModelContext, PromptBuilder};
+use prompt_store::{ModelContext, PromptBuilder, ScopedFile};
 use proto::Plan;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -46,6 +46,7 @@ use crate::thread_store::{
     SerializedCrease, SerializedLanguageModel, SerializedMessage, SerializedMessageSegment,
     SerializedThread, SerializedToolResult, SerializedToolUse, SharedProjectContext,
 };
+use crate::tool_metrics::{ToolInvocation, ToolMetricsStore};
 use crate::tool_use::{PendingToolUse, ToolUse, ToolUseMetadata, ToolUseState};
 
 #[derive(
@@ -98,6 +99,16 @@ impl MessageId {
     }
 }
 
+/// Records where a thread was forked from another thread.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ThreadBranch {
+    /// The thread that every branch in the family descends from.
+    pub root_id: ThreadId,
+    pub parent_id: ThreadId,
+    /// The last message the branch shares with its parent.
+    pub forked_at: MessageId,
+}
+
 /// Stored information that can be used to resurrect a context crease when creating an editor for a past message.
 #[derive(Clone, Debug)]
 pub struct MessageCrease {
@@ -116,6 +127,19 @@ pub struct Message {
     pub loaded_context: LoadedContext,
     pub creases: Vec<MessageCrease>,
     pub is_hidden: bool,
+    /// Information about the model response that produced this message, if it's an assistant
+    /// message whose response has finished streaming.
+    pub response_metrics: Option<ResponseMetrics>,
+}
+
+/// Information about a single model response, as reported by the language model.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResponseMetrics {
+    pub model: SharedString,
+    pub token_usage: TokenUsage,
+    /// How long it took for the first chunk of the response to arrive.
+    pub time_to_first_token: Option<Duration>,
+    pub duration: Duration,
 }
 
 impl Message {
@@ -221,6 +245,12 @@ pub struct ThreadCheckpoint {
     git_checkpoint: GitStoreCheckpoint,
 }
 
+impl ThreadCheckpoint {
+    pub fn git_checkpoint(&self) -> &GitStoreCheckpoint {
+        &self.git_checkpoint
+    }
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum ThreadFeedback {
     Positive,
@@ -321,12 +351,17 @@ pub enum QueueState {
     Started,
 }
 
+/// The number of user messages a thread needs before it is given a description.
+const DESCRIBE_THREAD_AFTER_EXCHANGES: usize = 3;
+
 /// A thread of conversation with the LLM.
 pub struct Thread {
     id: ThreadId,
     updated_at: DateTime<Utc>,
     summary: ThreadSummary,
     pending_summary: Task<Option<()>>,
+    description: Option<SharedString>,
+    pending_description: Task<Option<()>>,
     detailed_summary_task: Task<Option<()>>,
     detailed_summary_tx: postage::watch::Sender<DetailedSummaryState>,
     detailed_summary_rx: postage::watch::Receiver<DetailedSummaryState>,
@@ -360,6 +395,51 @@ pub struct Thread {
     >,
     remaining_turns: u32,
     configured_model: Option<ConfiguredModel>,
+    comparison_model: Option<ConfiguredModel>,
+    comparison: Option<ModelComparison>,
+    branch: Option<ThreadBranch>,
+    /// Whether turns started from now on only plan their edits.
+    plan_only: bool,
+    /// The buffer that was active in the workspace when the latest user message was sent, which
+    /// decides along with the attached context which scoped rules apply.
+    active_buffer: Option<WeakEntity<language::Buffer>>,
+}
+
+/// The responses of two models to the same user message, shown side by side until the user
+/// picks the one the thread should continue with.
+pub struct ModelComparison {
+    user_message_id: MessageId,
+    responses: Vec<ComparisonResponse>,
+    tasks: Vec<Task<()>>,
+}
+
+impl ModelComparison {
+    pub fn user_message_id(&self) -> MessageId {
+        self.user_message_id
+    }
+
+    pub fn responses(&self) -> &[ComparisonResponse] {
+        &self.responses
+    }
+
+    pub fn is_generating(&self) -> bool {
+        self.responses
+            .iter()
+            .any(|response| response.status == ComparisonResponseStatus::Generating)
+    }
+}
+
+pub struct ComparisonResponse {
+    pub model: ConfiguredModel,
+    pub text: String,
+    pub status: ComparisonResponseStatus,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ComparisonResponseStatus {
+    Generating,
+    Done,
+    Failed(SharedString),
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -389,6 +469,13 @@ impl ThreadSummary {
     }
 }
 
+/// The structured response requested when generating a thread's title.
+#[derive(Deserialize, JsonSchema)]
+struct ThreadTitle {
+    /// A concise 3-7 word title for the conversation.
+    title: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExceededWindowError {
     /// Model used when last message exceeded context window
@@ -413,10 +500,13 @@ impl Thread {
             updated_at: Utc::now(),
             summary: ThreadSummary::Pending,
             pending_summary: Task::ready(None),
+            description: None,
+            pending_description: Task::ready(None),
             detailed_summary_task: Task::ready(None),
             detailed_summary_tx,
             detailed_summary_rx,
             completion_mode: AgentSettings::get_global(cx).preferred_completion_mode,
+            branch: None,
             messages: Vec::new(),
             next_message_id: MessageId(0),
             last_prompt_id: PromptId::new(),
@@ -449,6 +539,10 @@ impl Thread {
             request_callback: None,
             remaining_turns: u32::MAX,
             configured_model,
+            comparison_model: None,
+            comparison: None,
+            plan_only: false,
+            active_buffer: None,
         }
     }
 
@@ -500,7 +594,10 @@ impl Thread {
             id,
             updated_at: serialized.updated_at,
             summary: ThreadSummary::Ready(serialized.summary),
+            branch: serialized.branch,
             pending_summary: Task::ready(None),
+            description: serialized.description,
+            pending_description: Task::ready(None),
             detailed_summary_task: Task::ready(None),
             detailed_summary_tx,
             detailed_summary_rx,
@@ -542,6 +639,7 @@ impl Thread {
                         })
                         .collect(),
                     is_hidden: message.is_hidden,
+                    response_metrics: message.response_metrics,
                 })
                 .collect(),
             next_message_id,
@@ -570,6 +668,10 @@ impl Thread {
             request_callback: None,
             remaining_turns: u32::MAX,
             configured_model,
+            comparison_model: None,
+            comparison: None,
+            plan_only: false,
+            active_buffer: None,
         }
     }
 
@@ -589,6 +691,17 @@ impl Thread {
         self.messages.is_empty()
     }
 
+    pub fn branch(&self) -> Option<&ThreadBranch> {
+        self.branch.as_ref()
+    }
+
+    /// Returns the ID shared by every branch forked from the same thread.
+    pub fn branch_root_id(&self) -> ThreadId {
+        self.branch
+            .as_ref()
+            .map_or_else(|| self.id.clone(), |branch| branch.root_id.clone())
+    }
+
     pub fn updated_at(&self) -> DateTime<Utc> {
         self.updated_at
     }
@@ -621,10 +734,104 @@ impl Thread {
         cx.notify();
     }
 
+    /// The model whose response is compared with the configured model's when sending the next
+    /// message. Comparison mode is off when this is `None`.
+    pub fn comparison_model(&self) -> Option<ConfiguredModel> {
+        self.comparison_model.clone()
+    }
+
+    pub fn set_comparison_model(&mut self, model: Option<ConfiguredModel>, cx: &mut Context<Self>) {
+        self.comparison_model = model;
+        cx.notify();
+    }
+
+    pub fn plan_only(&self) -> bool {
+        self.plan_only
+    }
+
+    /// Sets whether the next turns only plan their edits, so they can be previewed in the agent
+    /// diff before being applied. Doesn't affect the turn in progress.
+    pub fn set_plan_only(&mut self, plan_only: bool, cx: &mut Context<Self>) {
+        self.plan_only = plan_only;
+        cx.notify();
+    }
+
+    pub fn set_active_buffer(&mut self, buffer: Option<Entity<language::Buffer>>) {
+        self.active_buffer = buffer.map(|buffer| buffer.downgrade());
+    }
+
+    /// Returns the files that decide which scoped user rules and nested rules files apply to the
+    /// thread.
+    fn scoped_files(&self, cx: &App) -> Vec<ScopedFile> {
+        let project = self.project.read(cx);
+        let root_name = |worktree_id| {
+            Some(
+                project
+                    .worktree_for_id(worktree_id, cx)?
+                    .read(cx)
+                    .root_name()
+                    .to_string(),
+            )
+        };
+        let context_buffers = self
+            .messages
+            .iter()
+            .flat_map(|message| &message.loaded_context.contexts)
+            .filter_map(|context| match context {
+                AgentContext::File(context) => Some(context.handle.buffer.clone()),
+                AgentContext::Symbol(context) => Some(context.handle.buffer.clone()),
+                AgentContext::Selection(context) => Some(context.handle.buffer.clone()),
+                _ => None,
+            });
+        let active_buffer = self
+            .active_buffer
+            .as_ref()
+            .and_then(|buffer| buffer.upgrade());
+
+        // Files the agent reads or edits through tools count too, so that rules are followed even
+        // when the user didn't attach the files they apply to.
+        let tool_files = self
+            .tool_use
+            .tool_use_inputs()
+            .filter_map(|input| input.get("path")?.as_str())
+            .filter_map(|path| {
+                let project_path = project.find_project_path(path, cx)?;
+                Some(ScopedFile {
+                    root_name: root_name(project_path.worktree_id)?,
+                    path: project_path.path,
+                    language: None,
+                })
+            });
+
+        context_buffers
+            .chain(active_buffer)
+            .filter_map(|buffer| {
+                let buffer = buffer.read(cx);
+                let file = buffer.file()?;
+                Some(ScopedFile {
+                    root_name: root_name(file.worktree_id(cx))?,
+                    path: file.path().clone(),
+                    language: buffer.language().map(|language| language.name()),
+                })
+            })
+            .chain(tool_files)
+            .collect()
+    }
+
+    pub fn comparison(&self) -> Option<&ModelComparison> {
+        self.comparison.as_ref()
+    }
+
     pub fn summary(&self) -> &ThreadSummary {
         &self.summary
     }
 
+    /// A sentence describing what the thread is about, generated once the thread has had a few
+    /// exchanges.
+    pub fn description(&self) -> Option<&SharedString> {
+        self.description.as_ref()
+    }
+
     pub fn set_summary(&mut self, new_summary: impl Into<SharedString>, cx: &mut Context<Self>) {
         let current_summary = match &self.summary {
             ThreadSummary::Pending | ThreadSummary::Generating => return,
@@ -666,7 +873,12 @@ impl Thread {
     }
 
     pub fn is_generating(&self) -> bool {
-        !self.pending_completions.is_empty() || !self.all_tools_finished()
+        !self.pending_completions.is_empty()
+            || !self.all_tools_finished()
+            || self
+                .comparison
+                .as_ref()
+                .is_some_and(|comparison| comparison.is_generating())
     }
 
     /// Indicates whether streaming of language model events is stale.
@@ -688,6 +900,22 @@ impl Thread {
             .map(|pending_completion| pending_completion.queue_state)
     }
 
+    /// Returns the position of the pending completion in the queue, whether it
+    /// was queued by the provider or is still waiting on the provider's local
+    /// rate limits.
+    pub fn queue_position(&self) -> Option<usize> {
+        let pending_completion = self.pending_completions.first()?;
+        match pending_completion.queue_state {
+            QueueState::Queued { position } => Some(position),
+            QueueState::Sending => {
+                let (rate_limiter, request_id) =
+                    pending_completion.rate_limited_request.as_ref()?;
+                rate_limiter.queue_position(*request_id)
+            }
+            QueueState::Started => None,
+        }
+    }
+
     pub fn tools(&self) -> &Entity<ToolWorkingSet> {
         &self.tools
     }
@@ -714,6 +942,22 @@ impl Thread {
         self.checkpoints_by_message.get(&id).cloned()
     }
 
+    /// The checkpoint that puts the project back the way it was when the message with the given
+    /// id was the last one, i.e. that of the first later message whose turn changed the project.
+    pub fn checkpoint_after_message(&self, id: MessageId) -> Option<ThreadCheckpoint> {
+        self.messages
+            .iter()
+            .skip_while(|message| message.id != id)
+            .skip(1)
+            .find_map(|message| {
+                self.checkpoints_by_message.get(&message.id).or(self
+                    .pending_checkpoint
+                    .as_ref()
+                    .filter(|checkpoint| checkpoint.message_id == message.id))
+            })
+            .cloned()
+    }
+
     pub fn restore_checkpoint(
         &mut self,
         checkpoint: ThreadCheckpoint,
@@ -919,10 +1163,12 @@ impl Thread {
         model: Arc<dyn LanguageModel>,
     ) -> Vec<LanguageModelRequestTool> {
         if model.supports_tools() {
+            let plan_only = self.action_log.read(cx).is_plan_only();
             self.tools()
                 .read(cx)
                 .enabled_tools(cx)
                 .into_iter()
+                .filter(|tool| !plan_only || tool.supports_plan_only())
                 .filter_map(|tool| {
                     // Skip tools that cannot be supported
                     let input_schema = tool.input_schema(model.tool_input_format()).ok()?;
@@ -946,13 +1192,14 @@ impl Thread {
         creases: Vec<MessageCrease>,
         cx: &mut Context<Self>,
     ) -> MessageId {
-        if !loaded_context.referenced_buffers.is_empty() {
-            self.action_log.update(cx, |log, cx| {
-                for buffer in loaded_context.referenced_buffers {
-                    log.buffer_read(buffer, cx);
-                }
-            });
-        }
+        let plan_only = self.plan_only;
+        self.action_log.update(cx, |log, cx| {
+            log.start_turn();
+            log.set_plan_only(plan_only);
+            for buffer in loaded_context.referenced_buffers {
+                log.buffer_read(buffer, cx);
+            }
+        });
 
         let message_id = self.insert_message(
             Role::User,
@@ -976,6 +1223,7 @@ impl Thread {
     }
 
     pub fn insert_invisible_continue_message(&mut self, cx: &mut Context<Self>) -> MessageId {
+        self.action_log.update(cx, |log, _| log.start_turn());
         let id = self.insert_message(
             Role::User,
             vec![MessageSegment::Text("Continue where you left off".into())],
@@ -1021,6 +1269,7 @@ impl Thread {
             loaded_context,
             creases,
             is_hidden,
+            response_metrics: None,
         });
         self.touch_updated_at();
         cx.emit(ThreadEvent::MessageAdded(id));
@@ -1164,6 +1413,7 @@ impl Thread {
                             })
                             .collect(),
                         is_hidden: message.is_hidden,
+                        response_metrics: message.response_metrics.clone(),
                     })
                     .collect(),
                 initial_project_snapshot,
@@ -1180,6 +1430,8 @@ impl Thread {
                     }),
                 completion_mode: Some(this.completion_mode),
                 tool_use_limit_reached: this.tool_use_limit_reached,
+                branch: this.branch.clone(),
+                description: this.description.clone(),
             })
         })
     }
@@ -1210,6 +1462,121 @@ impl Thread {
         self.stream_completion(request, model, window, cx);
     }
 
+    /// Streams a response to the last user message from each of `models`, without running any
+    /// tools, so that the user can compare them before choosing the one to continue with.
+    pub fn compare_models(&mut self, models: [ConfiguredModel; 2], cx: &mut Context<Self>) {
+        let Some(user_message_id) = self
+            .messages
+            .last()
+            .filter(|message| message.role == Role::User)
+            .map(|message| message.id)
+        else {
+            return;
+        };
+
+        let mut responses = Vec::with_capacity(models.len());
+        let mut tasks = Vec::with_capacity(models.len());
+        for (ix, configured_model) in models.into_iter().enumerate() {
+            let model = configured_model.model.clone();
+            let mut request =
+                self.to_completion_request(model.clone(), CompletionIntent::UserPrompt, cx);
+            // Both models answer the same prompt, so neither of them may act on the project.
+            // The tools stay in the request so that earlier tool uses in the thread remain
+            // valid, and any tool use from models that can't be told not to use tools is
+            // dropped from the text stream.
+            if model.supports_tool_choice(LanguageModelToolChoice::None) {
+                request.tool_choice = Some(LanguageModelToolChoice::None);
+            }
+
+            tasks.push(cx.spawn(async move |this, cx| {
+                let result = async {
+                    let mut response = model.stream_completion_text(request, &cx).await?;
+                    while let Some(chunk) = response.stream.next().await {
+                        let chunk = chunk?;
+                        this.update(cx, |this, cx| {
+                            this.update_comparison_response(ix, cx, |response| {
+                                response.text.push_str(&chunk)
+                            })
+                        })?;
+                    }
+                    anyhow::Ok(())
+                }
+                .await;
+
+                let status = match result {
+                    Ok(()) => ComparisonResponseStatus::Done,
+                    Err(error) => ComparisonResponseStatus::Failed(error.to_string().into()),
+                };
+                this.update(cx, |this, cx| {
+                    this.update_comparison_response(ix, cx, |response| response.status = status)
+                })
+                .ok();
+            }));
+            responses.push(ComparisonResponse {
+                model: configured_model,
+                text: String::new(),
+                status: ComparisonResponseStatus::Generating,
+            });
+        }
+
+        self.comparison = Some(ModelComparison {
+            user_message_id,
+            responses,
+            tasks,
+        });
+        cx.emit(ThreadEvent::ComparisonChanged);
+        cx.notify();
+    }
+
+    fn update_comparison_response(
+        &mut self,
+        ix: usize,
+        cx: &mut Context<Self>,
+        update: impl FnOnce(&mut ComparisonResponse),
+    ) {
+        let Some(response) = self
+            .comparison
+            .as_mut()
+            .and_then(|comparison| comparison.responses.get_mut(ix))
+        else {
+            return;
+        };
+        update(response);
+        cx.emit(ThreadEvent::ComparisonChanged);
+        cx.notify();
+    }
+
+    /// Adds the response at `ix` of the current comparison to the thread as the assistant's
+    /// reply, and continues the thread with the model that wrote it.
+    pub fn choose_comparison_response(
+        &mut self,
+        ix: usize,
+        cx: &mut Context<Self>,
+    ) -> Option<MessageId> {
+        let response = self.comparison.as_ref()?.responses.get(ix)?;
+        if response.status != ComparisonResponseStatus::Done {
+            return None;
+        }
+
+        let response = self.comparison.take()?.responses.into_iter().nth(ix)?;
+        self.configured_model = Some(response.model);
+        let message_id =
+            self.insert_assistant_message(vec![MessageSegment::Text(response.text)], cx);
+        self.finalize_pending_checkpoint(cx);
+        if matches!(self.summary, ThreadSummary::Pending) {
+            self.summarize(cx);
+        }
+        cx.emit(ThreadEvent::ComparisonChanged);
+        Some(message_id)
+    }
+
+    pub fn discard_comparison(&mut self, cx: &mut Context<Self>) {
+        if self.comparison.take().is_some() {
+            cx.emit(ThreadEvent::ComparisonChanged);
+            cx.notify();
+        }
+    }
+
     pub fn used_tools_since_last_user_message(&self) -> bool {
         for message in self.messages.iter().rev() {
             if self.tool_use.message_has_tool_results(message.id) {
@@ -1238,6 +1605,7 @@ impl Thread {
             tool_choice: None,
             stop: Vec::new(),
             temperature: AgentSettings::temperature_for_model(&model, cx),
+            response_schema: None,
         };
 
         let available_tools = self.available_tools(cx, model.clone());
@@ -1251,9 +1619,10 @@ impl Thread {
         };
 
         if let Some(project_context) = self.project_context.borrow().as_ref() {
+            let project_context = project_context.scoped_to_files(&self.scoped_files(cx));
             match self
                 .prompt_builder
-                .generate_assistant_system_prompt(project_context, model_context)
+                .generate_assistant_system_prompt(&project_context, model_context)
             {
                 Err(err) => {
                     let message = format!("{err:?}").into();
@@ -1399,6 +1768,7 @@ impl Thread {
             tool_choice: None,
             stop: Vec::new(),
             temperature: AgentSettings::temperature_for_model(model, cx),
+            response_schema: None,
         };
 
         for message in &self.messages {
@@ -1440,6 +1810,7 @@ impl Thread {
         cx: &App,
     ) {
         const STALE_FILES_HEADER: &str = include_str!("./prompts/stale_files_prompt_header.txt");
+        const PLAN_ONLY_PROMPT: &str = include_str!("./prompts/plan_only_prompt.txt");
 
         let mut stale_message = String::new();
 
@@ -1463,6 +1834,10 @@ impl Thread {
             content.push(stale_message.into());
         }
 
+        if action_log.is_plan_only() {
+            content.push(PLAN_ONLY_PROMPT.trim().into());
+        }
+
         if !content.is_empty() {
             let context_message = LanguageModelRequestMessage {
                 role: Role::User,
@@ -1497,16 +1872,35 @@ impl Thread {
         };
 
         self.last_received_chunk_at = Some(Instant::now());
+        let requested_at = Instant::now();
 
         let task = cx.spawn(async move |thread, cx| {
+            // The provider queues the request as soon as the completion is requested.
+            let rate_limiter = RateLimiter::for_provider(&model.provider_id());
+            let request_id = rate_limiter.next_request_id();
             let stream_completion_future = model.stream_completion(request, &cx);
+            thread
+                .update(cx, |thread, _| {
+                    if let Some(pending_completion) = thread
+                        .pending_completions
+                        .iter_mut()
+                        .find(|completion| completion.id == pending_completion_id)
+                    {
+                        pending_completion.rate_limited_request =
+                            Some((rate_limiter.clone(), request_id));
+                    }
+                })
+                .ok();
             let initial_token_usage =
                 thread.read_with(cx, |thread, _cx| thread.cumulative_token_usage);
             let stream_completion = async {
                 let mut events = stream_completion_future.await?;
+                // Time spent waiting for the rate limiter isn't the provider's latency.
+                let started_at = rate_limiter.started_at(request_id).unwrap_or(requested_at);
 
                 let mut stop_reason = StopReason::EndTurn;
                 let mut current_token_usage = TokenUsage::default();
+                let mut first_token_at = None;
 
                 thread
                     .update(cx, |_thread, cx| {
@@ -1546,6 +1940,15 @@ impl Thread {
                             }
                         };
 
+                        if matches!(
+                            event,
+                            LanguageModelCompletionEvent::Text(_)
+                                | LanguageModelCompletionEvent::Thinking { .. }
+                                | LanguageModelCompletionEvent::ToolUse(_)
+                        ) {
+                            first_token_at.get_or_insert_with(Instant::now);
+                        }
+
                         match event {
                             LanguageModelCompletionEvent::StartMessage { .. } => {
                                 request_assistant_message_id =
@@ -1708,6 +2111,18 @@ impl Thread {
                         .pending_completions
                         .retain(|completion| completion.id != pending_completion_id);
 
+                    if let Some(message) = request_assistant_message_id
+                        .and_then(|id| thread.messages.iter_mut().find(|message| message.id == id))
+                    {
+                        message.response_metrics = Some(ResponseMetrics {
+                            model: model.name().0,
+                            token_usage: current_token_usage,
+                            time_to_first_token: first_token_at
+                                .map(|first_token_at| first_token_at - started_at),
+                            duration: started_at.elapsed(),
+                        });
+                    }
+
                     // If there is a response without tool use, summarize the message. Otherwise,
                     // allow two tool uses before summarizing.
                     if matches!(thread.summary, ThreadSummary::Pending)
@@ -1716,6 +2131,18 @@ impl Thread {
                     {
                         thread.summarize(cx);
                     }
+
+                    if thread.description.is_none()
+                        && !thread.has_pending_tool_uses()
+                        && thread
+                            .messages
+                            .iter()
+                            .filter(|message| message.role == Role::User && !message.is_hidden)
+                            .count()
+                            >= DESCRIBE_THREAD_AFTER_EXCHANGES
+                    {
+                        thread.describe(cx);
+                    }
                 })?;
 
                 anyhow::Ok(stop_reason)
@@ -1852,6 +2279,7 @@ impl Thread {
         self.pending_completions.push(PendingCompletion {
             id: pending_completion_id,
             queue_state: QueueState::Sending,
+            rate_limited_request: None,
             _task: task,
         });
     }
@@ -1879,38 +2307,24 @@ impl Thread {
 
         self.pending_summary = cx.spawn(async move |this, cx| {
             let result = async {
-                let mut messages = model.model.stream_completion(request, &cx).await?;
-
-                let mut new_summary = String::new();
-                while let Some(event) = messages.next().await {
-                    let Ok(event) = event else {
-                        continue;
-                    };
-                    let text = match event {
-                        LanguageModelCompletionEvent::Text(text) => text,
-                        LanguageModelCompletionEvent::StatusUpdate(
-                            CompletionRequestStatus::UsageUpdated { amount, limit },
-                        ) => {
-                            this.update(cx, |thread, _cx| {
-                                thread.last_usage = Some(RequestUsage {
-                                    limit,
-                                    amount: amount as i32,
-                                });
-                            })?;
-                            continue;
-                        }
-                        _ => continue,
-                    };
-
-                    let mut lines = text.lines();
-                    new_summary.extend(lines.next());
-
-                    // Stop if the LLM generated multiple lines.
-                    if lines.next().is_some() {
-                        break;
-                    }
+                let response = model
+                    .model
+                    .request_structured::<ThreadTitle>(request, &cx)
+                    .await?;
+                if let Some(usage) = response.usage {
+                    this.update(cx, |thread, _cx| {
+                        thread.last_usage = Some(usage);
+                    })?;
                 }
 
+                let new_summary = response
+                    .value
+                    .title
+                    .lines()
+                    .next()
+                    .unwrap_or_default()
+                    .trim()
+                    .to_string();
                 anyhow::Ok(new_summary)
             }
             .await;
@@ -1937,6 +2351,52 @@ impl Thread {
         });
     }
 
+    /// Generates the thread's description with the thread summary model, which is expected to
+    /// be cheap.
+    pub fn describe(&mut self, cx: &mut Context<Self>) {
+        let Some(ConfiguredModel { model, provider }) =
+            LanguageModelRegistry::read_global(cx).thread_summary_model()
+        else {
+            return;
+        };
+
+        if !provider.is_authenticated(cx) {
+            return;
+        }
+
+        let added_user_message = include_str!("./prompts/describe_thread_prompt.txt");
+
+        let request = self.to_summarize_request(
+            &model,
+            CompletionIntent::ThreadSummarization,
+            added_user_message.into(),
+            cx,
+        );
+
+        self.pending_description = cx.spawn(async move |this, cx| {
+            let stream = model.stream_completion_text(request, &cx);
+            let mut messages = stream.await.log_err()?;
+
+            let mut new_description = String::new();
+            while let Some(chunk) = messages.stream.next().await {
+                if let Some(chunk) = chunk.log_err() {
+                    new_description.push_str(&chunk);
+                }
+            }
+
+            let new_description = new_description.split_whitespace().collect::<Vec<_>>();
+            if new_description.is_empty() {
+                return None;
+            }
+
+            this.update(cx, |this, cx| {
+                this.description = Some(new_description.join(" ").into());
+                cx.emit(ThreadEvent::DescriptionGenerated);
+            })
+            .log_err()
+        });
+    }
+
     pub fn start_generating_detailed_summary_if_needed(
         &mut self,
         thread_store: WeakEntity<ThreadStore>,
@@ -2076,9 +2536,21 @@ impl Thread {
             .cloned()
             .collect::<Vec<_>>();
 
+        let plan_only = self.action_log.read(cx).is_plan_only();
         for tool_use in pending_tool_uses.iter() {
             if let Some(tool) = self.tools.read(cx).tool(&tool_use.name, cx) {
-                if tool.needs_confirmation(&tool_use.input, cx)
+                if plan_only && !tool.supports_plan_only() {
+                    let pending_tool_use = self.tool_use.insert_tool_output(
+                        tool_use.id.clone(),
+                        tool_use.name.clone(),
+                        Err(anyhow!(
+                            "The tool '{}' can't be used in a plan-only turn.",
+                            tool_use.name
+                        )),
+                        self.configured_model.as_ref(),
+                    );
+                    self.tool_finished(tool_use.id.clone(), pending_tool_use, false, window, cx);
+                } else if tool.needs_confirmation(&tool_use.input, cx)
                     && !AgentSettings::get_global(cx).always_allow_tool_actions
                 {
                     self.tool_use.confirm_tool_use(
@@ -2212,6 +2684,7 @@ impl Thread {
         cx: &mut Context<Thread>,
     ) -> Task<()> {
         let tool_name: Arc<str> = tool.name().into();
+        let started_at = Instant::now();
 
         let tool_result = if self.tools.read(cx).is_disabled(&tool.source(), &tool_name) {
             Task::ready(Err(anyhow!("tool is disabled: {tool_name}"))).into()
@@ -2236,9 +2709,22 @@ impl Thread {
         cx.spawn({
             async move |thread: WeakEntity<Thread>, cx| {
                 let output = tool_result.output.await;
+                let duration = started_at.elapsed();
 
                 thread
                     .update(cx, |thread, cx| {
+                        let invocation = ToolInvocation {
+                            tool_name: tool_name.clone(),
+                            thread_id: thread.id.clone(),
+                            profile_id: AgentSettings::get_global(cx).default_profile.clone(),
+                            duration,
+                            succeeded: output.is_ok(),
+                            truncated: output.as_ref().is_ok_and(|output| output.truncated),
+                            finished_at: Utc::now(),
+                        };
+                        ToolMetricsStore::global(cx)
+                            .update(cx, |store, cx| store.record(invocation, cx));
+
                         let pending_tool_use = thread.tool_use.insert_tool_output(
                             tool_use_id.clone(),
                             tool_name,
@@ -2285,6 +2771,21 @@ impl Thread {
     ) -> bool {
         let mut canceled = self.pending_completions.pop().is_some();
 
+        if let Some(comparison) = self
+            .comparison
+            .as_mut()
+            .filter(|comparison| comparison.is_generating())
+        {
+            comparison.tasks.clear();
+            for response in &mut comparison.responses {
+                if response.status == ComparisonResponseStatus::Generating {
+                    response.status = ComparisonResponseStatus::Failed("Canceled".into());
+                }
+            }
+            cx.emit(ThreadEvent::ComparisonChanged);
+            canceled = true;
+        }
+
         for pending_tool_use in self.tool_use.cancel_pending() {
             canceled = true;
             self.tool_finished(
@@ -2642,6 +3143,18 @@ impl Thread {
         });
     }
 
+    /// Makes the edits planned in plan-only turns, leaving them to be reviewed like any other
+    /// edits.
+    pub fn apply_plan(&mut self, cx: &mut Context<Self>) -> Task<Result<()>> {
+        self.action_log
+            .update(cx, |action_log, cx| action_log.apply_plan(cx))
+    }
+
+    pub fn discard_plan(&mut self, cx: &mut Context<Self>) {
+        self.action_log
+            .update(cx, |action_log, cx| action_log.discard_plan(cx));
+    }
+
     pub fn keep_all_edits(&mut self, cx: &mut Context<Self>) {
         self.action_log
             .update(cx, |action_log, cx| action_log.keep_all_edits(cx));
@@ -2844,6 +3357,7 @@ pub enum ThreadEvent {
     MessageDeleted(MessageId),
     SummaryGenerated,
     SummaryChanged,
+    DescriptionGenerated,
     UsePendingTools {
         tool_uses: Vec<PendingToolUse>,
     },
@@ -2858,6 +3372,7 @@ pub enum ThreadEvent {
     ToolUseLimitReached,
     CancelEditing,
     CompletionCanceled,
+    ComparisonChanged,
 }
 
 impl EventEmitter<ThreadEvent> for Thread {}
@@ -2865,6 +3380,8 @@ impl EventEmitter<ThreadEvent> for Thread {}
 struct PendingCompletion {
     id: usize,
     queue_state: QueueState,
+    /// The request waiting on the provider's local rate limits, if any.
+    rate_limited_request: Option<(RateLimiter, QueuedRequestId)>,
     _task: Task<()>,
 }
 
@@ -3119,6 +3636,81 @@ fn main() {{
         assert!(!loaded_context.text.contains("file4.rs"));
     }
 
+    #[gpui::test]
+    async fn test_refresh_stale_context(cx: &mut TestAppContext) {
+        init_test_settings(cx);
+
+        let project = create_test_project(
+            cx,
+            json!({
+                "file1.rs": "fn function1() {}\n",
+                "file2.rs": "fn function2() {}\n",
+            }),
+        )
+        .await;
+
+        let (_, _thread_store, thread, context_store, _model) =
+            setup_test_environment(cx, project.clone()).await;
+
+        let buffer = add_file_to_context(&project, &context_store, "test/file1.rs", cx)
+            .await
+            .unwrap();
+        add_file_to_context(&project, &context_store, "test/file2.rs", cx)
+            .await
+            .unwrap();
+        let new_contexts = context_store.update(cx, |store, cx| {
+            store.new_context_for_thread(thread.read(cx), None)
+        });
+        let loaded_context = cx
+            .update(|cx| load_context(new_contexts, &project, &None, cx))
+            .await;
+        thread.update(cx, |thread, cx| {
+            thread.insert_user_message("Message 1", loaded_context, None, Vec::new(), cx)
+        });
+        context_store.update(cx, |store, cx| store.context_sent(cx));
+
+        // Nothing changed since the files were sent
+        context_store.read_with(cx, |store, cx| {
+            assert!(store.stale_file_contexts(cx).is_empty());
+        });
+
+        buffer.update(cx, |buffer, cx| {
+            buffer.edit([(0..0, "// edited\n")], None, cx);
+        });
+        let stale_contexts = context_store.read_with(cx, |store, cx| store.stale_file_contexts(cx));
+        assert_eq!(stale_contexts.len(), 1);
+        assert_eq!(stale_contexts[0].buffer, buffer);
+
+        // Without a refresh, already-sent files aren't sent again
+        let new_contexts = context_store.update(cx, |store, cx| {
+            store.new_context_for_thread(thread.read(cx), None)
+        });
+        assert!(new_contexts.is_empty());
+
+        // After a refresh, only the changed file is sent again
+        context_store.update(cx, |store, cx| store.refresh_stale_context(cx));
+        context_store.read_with(cx, |store, cx| {
+            assert!(store.stale_file_contexts(cx).is_empty());
+        });
+        let new_contexts = context_store.update(cx, |store, cx| {
+            store.new_context_for_thread(thread.read(cx), None)
+        });
+        assert_eq!(new_contexts.len(), 1);
+        let loaded_context = cx
+            .update(|cx| load_context(new_contexts, &project, &None, cx))
+            .await;
+        assert!(loaded_context.loaded_context.text.contains("// edited"));
+        thread.update(cx, |thread, cx| {
+            thread.insert_user_message("Message 2", loaded_context, None, Vec::new(), cx)
+        });
+        context_store.update(cx, |store, cx| store.context_sent(cx));
+
+        let new_contexts = context_store.update(cx, |store, cx| {
+            store.new_context_for_thread(thread.read(cx), None)
+        });
+        assert!(new_contexts.is_empty());
+    }
+
     #[gpui::test]
     async fn test_message_without_files(cx: &mut TestAppContext) {
         init_test_settings(cx);
@@ -3433,8 +4025,8 @@ fn main() {{
         });
 
         cx.run_until_parked();
-        fake_model.stream_last_completion_response("Brief");
-        fake_model.stream_last_completion_response(" Introduction");
+        fake_model.stream_last_completion_response(r#"{"title": "Brief"#);
+        fake_model.stream_last_completion_response(r#" Introduction"}"#);
         fake_model.end_last_completion_stream();
         cx.run_until_parked();
 
@@ -3464,6 +4056,95 @@ fn main() {{
         });
     }
 
+    #[gpui::test]
+    async fn test_thread_description(cx: &mut TestAppContext) {
+        init_test_settings(cx);
+
+        let project = create_test_project(cx, json!({})).await;
+
+        let (_, _thread_store, thread, _context_store, model) =
+            setup_test_environment(cx, project.clone()).await;
+        let fake_model = model.as_fake();
+
+        for (ix, message) in ["Hi!", "Can you help me?", "Fix the parser"]
+            .into_iter()
+            .enumerate()
+        {
+            thread.read_with(cx, |thread, _| assert_eq!(thread.description(), None));
+
+            thread.update(cx, |thread, cx| {
+                thread.insert_user_message(message, ContextLoadResult::default(), None, vec![], cx);
+                thread.send_to_model(model.clone(), CompletionIntent::UserPrompt, None, cx);
+            });
+            simulate_successful_response(&fake_model, cx);
+
+            // The title is generated after the first exchange
+            if ix == 0 {
+                fake_model.stream_last_completion_response(r#"{"title": "Greeting"}"#);
+                fake_model.end_last_completion_stream();
+                cx.run_until_parked();
+            }
+        }
+
+        // The description is generated after the third exchange
+        fake_model.stream_last_completion_response("Fixes a bug\n in the parser.");
+        fake_model.end_last_completion_stream();
+        cx.run_until_parked();
+
+        thread.read_with(cx, |thread, _| {
+            assert_eq!(thread.summary().or_default(), "Greeting");
+            assert_eq!(
+                thread.description().map(|description| description.as_ref()),
+                Some("Fixes a bug in the parser.")
+            );
+        });
+    }
+
+    #[gpui::test]
+    async fn test_response_metrics(cx: &mut TestAppContext) {
+        init_test_settings(cx);
+
+        let project = create_test_project(cx, json!({})).await;
+
+        let (_, _thread_store, thread, _context_store, model) =
+            setup_test_environment(cx, project.clone()).await;
+        let fake_model = model.as_fake();
+
+        thread.update(cx, |thread, cx| {
+            thread.insert_user_message("Hi!", ContextLoadResult::default(), None, vec![], cx);
+            thread.send_to_model(model.clone(), CompletionIntent::UserPrompt, None, cx);
+        });
+        cx.run_until_parked();
+        fake_model.stream_last_completion_response("Hello!");
+        cx.run_until_parked();
+
+        // Metrics are only recorded once the response has finished.
+        thread.read_with(cx, |thread, _| {
+            let message = thread.messages().last().unwrap();
+            assert_eq!(message.role, Role::Assistant);
+            assert_eq!(message.response_metrics, None);
+        });
+
+        fake_model.end_last_completion_stream();
+        cx.run_until_parked();
+
+        thread.read_with(cx, |thread, _| {
+            let user_message = thread.messages().next().unwrap();
+            assert_eq!(user_message.response_metrics, None);
+
+            let metrics = thread
+                .messages()
+                .last()
+                .unwrap()
+                .response_metrics
+                .clone()
+                .unwrap();
+            assert_eq!(metrics.model, model.name().0);
+            assert!(metrics.time_to_first_token.is_some());
+            assert!(metrics.time_to_first_token <= Some(metrics.duration));
+        });
+    }
+
     #[gpui::test]
     async fn test_thread_summary_error_set_manually(cx: &mut TestAppContext) {
         init_test_settings(cx);
@@ -3527,7 +4208,7 @@ fn main() {{
         });
 
         cx.run_until_parked();
-        fake_model.stream_last_completion_response("A successful summary");
+        fake_model.stream_last_completion_response(r#"{"title": "A successful summary"}"#);
         fake_model.end_last_completion_stream();
         cx.run_until_parked();
 
@@ -3560,7 +4241,10 @@ fn main() {{
             assert_eq!(thread.summary().or_default(), ThreadSummary::DEFAULT);
         });
 
-        // Simulate summary request ending
+        // Simulate the summary request, and the request to repair its response, ending
+        // without a title
+        cx.run_until_parked();
+        fake_model.end_last_completion_stream();
         cx.run_until_parked();
         fake_model.end_last_completion_stream();
         cx.run_until_parked();
@@ -3572,6 +4256,85 @@ fn main() {{
         });
     }
 
+    #[gpui::test]
+    async fn test_compare_models(cx: &mut TestAppContext) {
+        init_test_settings(cx);
+
+        let project = create_test_project(cx, json!({})).await;
+        let (_, _thread_store, thread, _context_store, model) =
+            setup_test_environment(cx, project.clone()).await;
+
+        let provider = Arc::new(FakeLanguageModelProvider);
+        let other_model: Arc<dyn LanguageModel> = Arc::new(provider.test_model());
+        let models = [
+            ConfiguredModel {
+                provider: provider.clone(),
+                model: model.clone(),
+            },
+            ConfiguredModel {
+                provider,
+                model: other_model.clone(),
+            },
+        ];
+
+        let user_message_id = thread.update(cx, |thread, cx| {
+            let id =
+                thread.insert_user_message("Hi!", ContextLoadResult::default(), None, vec![], cx);
+            thread.compare_models(models, cx);
+            id
+        });
+        cx.run_until_parked();
+
+        model.as_fake().stream_last_completion_response("Hello");
+        model.as_fake().end_last_completion_stream();
+        other_model
+            .as_fake()
+            .stream_last_completion_response("Howdy");
+        cx.run_until_parked();
+
+        thread.read_with(cx, |thread, _| {
+            let comparison = thread.comparison().unwrap();
+            assert_eq!(comparison.user_message_id(), user_message_id);
+            let responses = comparison
+                .responses()
+                .iter()
+                .map(|response| (response.text.as_str(), response.status.clone()))
+                .collect::<Vec<_>>();
+            assert_eq!(
+                responses,
+                [
+                    ("Hello", ComparisonResponseStatus::Done),
+                    ("Howdy", ComparisonResponseStatus::Generating),
+                ]
+            );
+            assert!(thread.is_generating());
+        });
+
+        // A response can only be chosen once it's complete.
+        thread.update(cx, |thread, cx| {
+            assert_eq!(thread.choose_comparison_response(1, cx), None);
+        });
+
+        other_model.as_fake().end_last_completion_stream();
+        cx.run_until_parked();
+
+        thread.update(cx, |thread, cx| {
+            assert!(thread.choose_comparison_response(1, cx).is_some());
+        });
+
+        thread.read_with(cx, |thread, _| {
+            assert!(thread.comparison().is_none());
+            assert!(!thread.is_generating());
+            let last_message = thread.messages().last().unwrap();
+            assert_eq!(last_message.role, Role::Assistant);
+            assert_eq!(last_message.to_string(), "Howdy");
+            assert!(Arc::ptr_eq(
+                &thread.configured_model().unwrap().model,
+                &other_model
+            ));
+        });
+    }
+
     fn simulate_successful_response(fake_model: &FakeLanguageModel, cx: &mut TestAppContext) {
         cx.run_until_parked();
         fake_model.stream_last_completion_response("Assistant response");
@@ -3579,6 +4342,78 @@ fn main() {{
         cx.run_until_parked();
     }
 
+    #[gpui::test]
+    async fn test_fork_thread_restoring_checkpoint(cx: &mut TestAppContext) {
+        init_test_settings(cx);
+
+        let project = create_test_project(cx, json!({"code.rs": "fn main() {}"})).await;
+        let (_workspace, thread_store, thread, _context_store, _model) =
+            setup_test_environment(cx, project.clone()).await;
+        let git_checkpoint = project
+            .update(cx, |project, cx| {
+                project
+                    .git_store()
+                    .update(cx, |git_store, cx| git_store.checkpoint(cx))
+            })
+            .await
+            .unwrap();
+
+        let (plan_id, reply_id, implement_id) = thread.update(cx, |thread, cx| {
+            let plan_id = thread.insert_user_message(
+                "Plan the change",
+                ContextLoadResult::default(),
+                None,
+                Vec::new(),
+                cx,
+            );
+            let reply_id = thread
+                .insert_assistant_message(vec![MessageSegment::Text("Here's the plan".into())], cx);
+            let implement_id = thread.insert_user_message(
+                "Implement it",
+                ContextLoadResult::default(),
+                None,
+                Vec::new(),
+                cx,
+            );
+            // The implementation changed the project.
+            thread.insert_checkpoint(
+                ThreadCheckpoint {
+                    message_id: implement_id,
+                    git_checkpoint,
+                },
+                cx,
+            );
+            (plan_id, reply_id, implement_id)
+        });
+
+        thread.read_with(cx, |thread, _| {
+            let checkpoint_after = |id| {
+                thread
+                    .checkpoint_after_message(id)
+                    .map(|checkpoint| checkpoint.message_id)
+            };
+            assert_eq!(checkpoint_after(plan_id), Some(implement_id));
+            assert_eq!(checkpoint_after(reply_id), Some(implement_id));
+            assert_eq!(checkpoint_after(implement_id), None);
+        });
+
+        let branch = thread_store
+            .update(cx, |thread_store, cx| {
+                thread_store.fork_thread(&thread, reply_id, true, cx)
+            })
+            .await
+            .unwrap();
+        branch.read_with(cx, |branch, _| {
+            assert_eq!(
+                branch
+                    .messages()
+                    .map(|message| message.id)
+                    .collect::<Vec<_>>(),
+                vec![plan_id, reply_id]
+            );
+        });
+    }
+
     fn init_test_settings(cx: &mut TestAppContext) {
         cx.update(|cx| {
             let settings_store = SettingsStore::test(cx);
@@ -0,0 +1,387 @@
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use collections::IndexMap;
+use gpui::App;
+use language_model::{LanguageModelImage, LanguageModelToolResultContent, Role};
+use serde::Deserialize;
+
+use crate::ExportThread;
+use crate::context::AgentContext;
+use crate::thread::{MessageSegment, Thread};
+
+const EDIT_FILE_TOOL_NAME: &str = "edit_file";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ThreadExportFormat {
+    #[default]
+    Markdown,
+    /// A standalone HTML document, with no external stylesheets or scripts.
+    Html,
+}
+
+impl ThreadExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Markdown => "md",
+            Self::Html => "html",
+        }
+    }
+}
+
+/// The subset of the edit file tool's output needed to show the changes it made.
+#[derive(Deserialize)]
+struct EditFileToolOutput {
+    original_path: PathBuf,
+    old_text: String,
+    new_text: String,
+}
+
+/// Renders the whole thread, followed by the net changes it made to each edited file.
+pub(crate) fn export_thread(thread: &Thread, options: &ExportThread, cx: &App) -> Result<String> {
+    let title = thread.summary().or_default();
+    let mut markdown = String::new();
+    writeln!(markdown, "# {title}\n")?;
+
+    // The first and last contents of every edited file, in the order they were first edited.
+    let mut edited_files = IndexMap::<PathBuf, (String, String)>::default();
+
+    for message in thread.messages() {
+        let role = match message.role {
+            Role::User => "User",
+            Role::Assistant => "Agent",
+            Role::System => "System",
+        };
+        writeln!(markdown, "## {role}\n")?;
+
+        let context = &message.loaded_context;
+        if options.inline_attachments {
+            if !context.text.is_empty() {
+                // Attachments are shown verbatim, so that markup in files and fetched pages
+                // isn't rendered.
+                write_details(
+                    &mut markdown,
+                    "Attached context",
+                    &fenced(&context.text, ""),
+                )?;
+            }
+            for (ix, image) in context.images.iter().enumerate() {
+                writeln!(markdown, "{}\n", image_markdown(ix + 1, image))?;
+            }
+        } else if !context.contexts.is_empty() {
+            writeln!(markdown, "Attached context:\n")?;
+            for context in &context.contexts {
+                writeln!(markdown, "- {}", attachment_link(context))?;
+            }
+            writeln!(markdown)?;
+        }
+
+        for segment in &message.segments {
+            match segment {
+                MessageSegment::Text(text) => writeln!(markdown, "{}\n", text.trim_end())?,
+                MessageSegment::Thinking { text, .. } => {
+                    write_details(&mut markdown, "Thinking", text.trim_end())?
+                }
+                MessageSegment::RedactedThinking(_) => {}
+            }
+        }
+
+        for tool_use in thread.tool_uses_for_message(message.id, cx) {
+            let mut body = String::new();
+            writeln!(
+                body,
+                "{}",
+                fenced(&serde_json::to_string_pretty(&tool_use.input)?, "json")
+            )?;
+
+            let mut summary = format!("Tool: {}", tool_use.ui_text);
+            if let Some(result) = thread.tool_result(&tool_use.id) {
+                if result.is_error {
+                    summary.push_str(" (error)");
+                }
+                match &result.content {
+                    LanguageModelToolResultContent::Text(text) => {
+                        writeln!(body, "{}", fenced(text, ""))?
+                    }
+                    LanguageModelToolResultContent::Image(image) if options.inline_attachments => {
+                        writeln!(body, "{}\n", image_markdown(1, image))?
+                    }
+                    LanguageModelToolResultContent::Image(_) => {
+                        writeln!(body, "The tool returned an image.\n")?
+                    }
+                }
+
+                if tool_use.name.as_ref() == EDIT_FILE_TOOL_NAME && !result.is_error {
+                    if let Some(output) = result.output.clone().and_then(|output| {
+                        serde_json::from_value::<EditFileToolOutput>(output).ok()
+                    }) {
+                        edited_files
+                            .entry(output.original_path)
+                            .and_modify(|(_, new_text)| *new_text = output.new_text.clone())
+                            .or_insert((output.old_text, output.new_text));
+                    }
+                }
+            }
+
+            if options.collapse_tool_calls {
+                write_details(&mut markdown, &summary, &body)?;
+            } else {
+                writeln!(markdown, "**{summary}**\n\n{body}")?;
+            }
+        }
+    }
+
+    let changes = edited_files
+        .iter()
+        .filter(|(_, (old_text, new_text))| old_text != new_text)
+        .collect::<Vec<_>>();
+    if !changes.is_empty() {
+        writeln!(markdown, "## Changes\n")?;
+        for (path, (old_text, new_text)) in changes {
+            let diff = language::unified_diff(old_text, new_text);
+            writeln!(markdown, "### `{}`\n", path.display())?;
+            writeln!(markdown, "{}", fenced(&diff, "diff"))?;
+        }
+    }
+
+    Ok(match options.format {
+        ThreadExportFormat::Markdown => markdown,
+        ThreadExportFormat::Html => html_document(&title, &markdown),
+    })
+}
+
+/// Adds the format's extension to `path` if it doesn't have one.
+pub(crate) fn with_extension(path: PathBuf, format: ThreadExportFormat) -> PathBuf {
+    if path.extension().is_some() {
+        path
+    } else {
+        path.with_extension(format.extension())
+    }
+}
+
+/// Writes a `<details>` block, which both GitHub and the HTML export show collapsed.
+fn write_details(markdown: &mut String, summary: &str, body: &str) -> std::fmt::Result {
+    writeln!(
+        markdown,
+        "<details>\n<summary>{}</summary>\n\n{}\n\n</details>\n",
+        escape_html(summary),
+        body.trim_end()
+    )
+}
+
+/// Wraps `text` in a code fence that's longer than any run of backticks within it.
+fn fenced(text: &str, language: &str) -> String {
+    let longest_run = text
+        .split(|c| c != '`')
+        .map(str::len)
+        .max()
+        .unwrap_or_default();
+    let fence = "`".repeat(longest_run.max(2) + 1);
+    let text = text.trim_end_matches('\n');
+    format!("{fence}{language}\n{text}\n{fence}\n")
+}
+
+fn image_markdown(number: usize, image: &LanguageModelImage) -> String {
+    format!("![Image {number}](data:image/png;base64,{})", image.source)
+}
+
+fn attachment_link(context: &AgentContext) -> String {
+    fn path_link(path: &Path, anchor: &str) -> String {
+        let path = path.to_string_lossy();
+        format!("[{path}](<{path}{anchor}>)")
+    }
+
+    match context {
+        AgentContext::File(context) => path_link(&context.full_path, ""),
+        AgentContext::Directory(context) => path_link(&context.full_path, ""),
+        AgentContext::Symbol(context) => path_link(
+            &context.full_path,
+            &format!(
+                "#L{}-L{}",
+                context.line_range.start.row + 1,
+                context.line_range.end.row + 1
+            ),
+        ),
+        AgentContext::Selection(context) => path_link(
+            &context.full_path,
+            &format!(
+                "#L{}-L{}",
+                context.line_range.start.row + 1,
+                context.line_range.end.row + 1
+            ),
+        ),
+        AgentContext::FetchedUrl(context) => match &context.title {
+            Some(title) => format!("[{title}](<{}>)", context.url),
+            None => format!("<{}>", context.url),
+        },
+        AgentContext::Thread(context) => format!("Thread: {}", context.title),
+        AgentContext::TextThread(context) => format!("Text thread: {}", context.title),
+        AgentContext::Rules(context) => {
+            format!("Rules: {}", context.title.as_deref().unwrap_or("Untitled"))
+        }
+        AgentContext::Image(context) => match context.image() {
+            Some(image) => format!("Image ({}×{})", image.size.width.0, image.size.height.0),
+            None => "Image".to_string(),
+        },
+        AgentContext::ExtensionEntry(context) => {
+            format!("{}: {}", context.source_name, context.label)
+        }
+    }
+}
+
+fn html_document(title: &str, markdown: &str) -> String {
+    let options = pulldown_cmark::Options::ENABLE_TABLES
+        | pulldown_cmark::Options::ENABLE_STRIKETHROUGH
+        | pulldown_cmark::Options::ENABLE_TASKLISTS;
+    let mut body = String::new();
+    pulldown_cmark::html::push_html(
+        &mut body,
+        pulldown_cmark::Parser::new_ext(markdown, options).map(sanitize_event),
+    );
+
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>{title}</title>
+<style>
+body {{ max-width: 860px; margin: 2rem auto; padding: 0 1rem; font-family: system-ui, sans-serif; line-height: 1.5; color: #1f2328; }}
+h2 {{ border-bottom: 1px solid #d1d9e0; padding-bottom: 0.25rem; margin-top: 2rem; }}
+pre {{ background: #f6f8fa; padding: 0.75rem; overflow-x: auto; border-radius: 6px; }}
+code {{ font-family: ui-monospace, monospace; font-size: 0.875em; }}
+details {{ border: 1px solid #d1d9e0; border-radius: 6px; padding: 0.25rem 0.75rem; margin: 0.5rem 0; }}
+summary {{ cursor: pointer; font-weight: 600; }}
+img {{ max-width: 100%; }}
+</style>
+</head>
+<body>
+{body}</body>
+</html>
+"#,
+        title = escape_html(title),
+    )
+}
+
+/// Threads contain untrusted text, such as model output and fetched pages, so raw HTML is shown
+/// as text and links can't run scripts. Only the `<details>` blocks written by
+/// [`write_details`] are kept as markup.
+fn sanitize_event(event: pulldown_cmark::Event) -> pulldown_cmark::Event {
+    use pulldown_cmark::{Event, Tag};
+
+    match event {
+        Event::Html(html) | Event::InlineHtml(html) if !is_details_markup(&html) => {
+            Event::Text(html)
+        }
+        Event::Start(Tag::Link {
+            link_type,
+            dest_url,
+            title,
+            id,
+        }) if !is_safe_link(&dest_url) => Event::Start(Tag::Link {
+            link_type,
+            dest_url: "#".into(),
+            title,
+            id,
+        }),
+        event => event,
+    }
+}
+
+fn is_details_markup(html: &str) -> bool {
+    html.lines().all(|line| {
+        let line = line.trim();
+        line.is_empty()
+            || line == "<details>"
+            || line == "</details>"
+            || line
+                .strip_prefix("<summary>")
+                .and_then(|line| line.strip_suffix("</summary>"))
+                .is_some_and(|summary| !summary.contains('<'))
+    })
+}
+
+fn is_safe_link(url: &str) -> bool {
+    let url = url.trim_start().to_ascii_lowercase();
+    !["javascript:", "vbscript:", "data:"]
+        .iter()
+        .any(|scheme| url.starts_with(scheme))
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fenced() {
+        assert_eq!(fenced("let a = 1;\n", "rust"), "```rust\nlet a = 1;\n```\n");
+        assert_eq!(fenced("```\ncode\n```", ""), "````\n```\ncode\n```\n````\n");
+    }
+
+    #[test]
+    fn test_html_document() {
+        let html = html_document(
+            "Fix <Parser>",
+            "# Title\n\n<details>\n<summary>Tool: Read file</summary>\n\n```json\n{}\n```\n\n</details>\n",
+        );
+        assert!(html.contains("<title>Fix &lt;Parser&gt;</title>"));
+        assert!(html.contains("<h1>Title</h1>"));
+        assert!(html.contains("<summary>Tool: Read file</summary>"));
+        assert!(html.contains("<pre><code class=\"language-json\">{}\n</code></pre>"));
+    }
+
+    #[test]
+    fn test_html_document_escapes_untrusted_markup() {
+        let mut markdown = String::new();
+        write_details(
+            &mut markdown,
+            "Attached context",
+            &fenced("<script>alert(1)</script>", ""),
+        )
+        .unwrap();
+        write_details(&mut markdown, "Thinking", "<img src=x onerror=alert(1)>").unwrap();
+        markdown.push_str("<script>alert(2)</script>\n\n");
+        markdown.push_str("Inline <b onclick=alert(3)>bold</b> text\n\n");
+        markdown.push_str("[click](javascript:alert(4)) and [docs](https://zed.dev)\n");
+
+        let html = html_document("Thread", &markdown);
+        assert!(html.contains("<details>\n<summary>Thinking</summary>"));
+        assert!(html.contains("&lt;script&gt;alert(1)&lt;/script&gt;"));
+        assert!(html.contains("&lt;img src=x onerror=alert(1)&gt;"));
+        assert!(html.contains("&lt;script&gt;alert(2)&lt;/script&gt;"));
+        assert!(html.contains("&lt;b onclick=alert(3)&gt;"));
+        assert!(html.contains("<a href=\"#\">click</a>"));
+        assert!(html.contains("<a href=\"https://zed.dev\">docs</a>"));
+        assert!(!html.contains("<script"));
+        assert!(!html.contains("<img src=x"));
+    }
+
+    #[test]
+    fn test_with_extension() {
+        assert_eq!(
+            with_extension("/tmp/thread".into(), ThreadExportFormat::Html),
+            PathBuf::from("/tmp/thread.html")
+        );
+        assert_eq!(
+            with_extension("/tmp/thread.txt".into(), ThreadExportFormat::Markdown),
+            PathBuf::from("/tmp/thread.txt")
+        );
+    }
+}
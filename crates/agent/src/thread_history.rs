@@ -3,6 +3,7 @@ use std::ops::Range;
 use std::sync::Arc;
 
 use chrono::{Datelike as _, Local, NaiveDate, TimeDelta};
+use collections::{HashMap, HashSet};
 use editor::{Editor, EditorEvent};
 use fuzzy::{StringMatch, StringMatchCandidate};
 use gpui::{
@@ -48,6 +49,8 @@ enum SearchState {
     Searched {
         query: SharedString,
         matches: Vec<StringMatch>,
+        /// Snippets of thread contents that matched the query, keyed by entry index.
+        snippets: HashMap<usize, SharedString>,
     },
 }
 
@@ -200,6 +203,7 @@ impl ThreadHistory {
         }
 
         let all_entries = self.all_entries.clone();
+        let all_entries_for_content = self.all_entries.clone();
 
         let fuzzy_search_task = cx.background_spawn({
             let query = query.clone();
@@ -231,11 +235,44 @@ impl ThreadHistory {
                 .await
             }
         });
+        let content_search_task = self.history_store.update(cx, |history_store, cx| {
+            history_store.search_threads(&query, cx)
+        });
 
         let task = cx.spawn({
             let query = query.clone();
             async move |this, cx| {
-                let matches = fuzzy_search_task.await;
+                let mut matches = fuzzy_search_task.await;
+                let content_matches = content_search_task.await.log_err().unwrap_or_default();
+
+                // Threads whose contents match are listed after those whose titles match.
+                let mut snippets = HashMap::default();
+                let matched_indices = matches
+                    .iter()
+                    .map(|string_match| string_match.candidate_id)
+                    .collect::<HashSet<_>>();
+                let thread_indices = all_entries_for_content
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(index, entry)| match entry {
+                        HistoryEntry::Thread(thread) => Some((thread.id.clone(), index)),
+                        HistoryEntry::Context(_) => None,
+                    })
+                    .collect::<HashMap<_, _>>();
+                for content_match in content_matches {
+                    let Some(&index) = thread_indices.get(&content_match.thread.id) else {
+                        continue;
+                    };
+                    snippets.insert(index, content_match.snippet);
+                    if !matched_indices.contains(&index) {
+                        matches.push(StringMatch {
+                            candidate_id: index,
+                            score: 0.,
+                            positions: Vec::new(),
+                            string: content_match.thread.summary.to_string(),
+                        });
+                    }
+                }
 
                 this.update(cx, |this, cx| {
                     let SearchState::Searching {
@@ -250,6 +287,7 @@ impl ThreadHistory {
                         this.search_state = SearchState::Searched {
                             query: query.clone(),
                             matches,
+                            snippets,
                         };
 
                         this.set_selected_entry_index(0, cx);
@@ -461,15 +499,26 @@ impl ThreadHistory {
                 .get(range)
                 .iter()
                 .flat_map(|items| {
-                    items
-                        .iter()
-                        .map(|item| self.render_list_item(item.entry_index(), item, vec![], cx))
+                    items.iter().map(|item| {
+                        self.render_list_item(item.entry_index(), item, vec![], None, cx)
+                    })
                 })
                 .collect(),
-            SearchState::Searched { matches, .. } => matches[range]
+            SearchState::Searched {
+                matches, snippets, ..
+            } => matches[range]
                 .iter()
                 .enumerate()
                 .map(|(ix, m)| {
+                    // Every search result reserves a snippet line, since list items must share a height.
+                    let snippet = snippets
+                        .get(&m.candidate_id)
+                        .cloned()
+                        .or_else(|| match self.all_entries.get(m.candidate_id)? {
+                            HistoryEntry::Thread(thread) => thread.description.clone(),
+                            HistoryEntry::Context(_) => None,
+                        })
+                        .unwrap_or_default();
                     self.render_list_item(
                         Some(range_start + ix),
                         &ListItemType::Entry {
@@ -477,6 +526,7 @@ impl ThreadHistory {
                             format: EntryTimeFormat::DateAndTime,
                         },
                         m.positions.clone(),
+                        Some(snippet),
                         cx,
                     )
                 })
@@ -492,6 +542,7 @@ impl ThreadHistory {
         list_entry_ix: Option<usize>,
         item: &ListItemType,
         highlight_positions: Vec<usize>,
+        snippet: Option<SharedString>,
         cx: &Context<Self>,
     ) -> AnyElement {
         match item {
@@ -502,6 +553,7 @@ impl ThreadHistory {
                     .child(
                         HistoryEntryElement::new(entry.clone(), self.agent_panel.clone())
                             .highlight_positions(highlight_positions)
+                            .snippet(snippet)
                             .timestamp_format(*format)
                             .selected(list_entry_ix == Some(self.selected_index))
                             .hovered(list_entry_ix == self.hovered_index)
@@ -618,6 +670,7 @@ pub struct HistoryEntryElement {
     selected: bool,
     hovered: bool,
     highlight_positions: Vec<usize>,
+    snippet: Option<SharedString>,
     timestamp_format: EntryTimeFormat,
     on_hover: Box<dyn Fn(&bool, &mut Window, &mut App) + 'static>,
 }
@@ -630,6 +683,7 @@ impl HistoryEntryElement {
             selected: false,
             hovered: false,
             highlight_positions: vec![],
+            snippet: None,
             timestamp_format: EntryTimeFormat::DateAndTime,
             on_hover: Box::new(|_, _, _| {}),
         }
@@ -650,6 +704,11 @@ impl HistoryEntryElement {
         self
     }
 
+    pub fn snippet(mut self, snippet: Option<SharedString>) -> Self {
+        self.snippet = snippet;
+        self
+    }
+
     pub fn on_hover(mut self, on_hover: impl Fn(&bool, &mut Window, &mut App) + 'static) -> Self {
         self.on_hover = Box::new(on_hover);
         self
@@ -679,28 +738,49 @@ impl RenderOnce for HistoryEntryElement {
         let thread_timestamp =
             self.timestamp_format
                 .format_timestamp(&self.agent_panel, timestamp, cx);
+        let description = match &self.entry {
+            HistoryEntry::Thread(thread) => thread.description.clone(),
+            HistoryEntry::Context(_) => None,
+        };
 
         ListItem::new(SharedString::from(id))
             .rounded()
             .toggle_state(self.selected)
             .spacing(ListItemSpacing::Sparse)
             .start_slot(
-                h_flex()
+                v_flex()
                     .w_full()
-                    .gap_2()
-                    .justify_between()
                     .child(
-                        HighlightedLabel::new(summary, self.highlight_positions)
-                            .size(LabelSize::Small)
-                            .truncate(),
+                        h_flex()
+                            .w_full()
+                            .gap_2()
+                            .justify_between()
+                            .child(
+                                HighlightedLabel::new(summary, self.highlight_positions)
+                                    .size(LabelSize::Small)
+                                    .truncate(),
+                            )
+                            .child(
+                                Label::new(thread_timestamp)
+                                    .color(Color::Muted)
+                                    .size(LabelSize::XSmall),
+                            ),
                     )
-                    .child(
-                        Label::new(thread_timestamp)
-                            .color(Color::Muted)
-                            .size(LabelSize::XSmall),
-                    ),
+                    .when_some(self.snippet, |this, snippet| {
+                        this.child(
+                            div().h(rems_from_px(16.)).child(
+                                Label::new(snippet)
+                                    .color(Color::Muted)
+                                    .size(LabelSize::XSmall)
+                                    .truncate(),
+                            ),
+                        )
+                    }),
             )
             .on_hover(self.on_hover)
+            .when_some(description, |item, description| {
+                item.tooltip(Tooltip::text(description))
+            })
             .end_slot::<IconButton>(if self.hovered || self.selected {
                 Some(
                     IconButton::new("delete", IconName::TrashAlt)
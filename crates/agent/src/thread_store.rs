@@ -30,12 +30,14 @@ use ui::Window;
 use util::ResultExt as _;
 
 use crate::context_server_tool::ContextServerTool;
+use crate::memory_store::{MemoryStore, MemoryStoreEvent, ProjectKey};
 use crate::thread::{
-    DetailedSummaryState, ExceededWindowError, MessageId, ProjectSnapshot, Thread, ThreadId,
+    DetailedSummaryState, ExceededWindowError, MessageId, ProjectSnapshot, ResponseMetrics, Thread,
+    ThreadBranch, ThreadId,
 };
 use indoc::indoc;
 use sqlez::{
-    bindable::{Bind, Column},
+    bindable::{Bind, Column, StaticColumnCount},
     connection::Connection,
     statement::Statement,
 };
@@ -81,10 +83,62 @@ const RULES_FILE_NAMES: [&'static str; 8] = [
     "AGENTS.md",
 ];
 
+/// The maximum number of rules files discovered in subdirectories of a worktree, to keep large
+/// monorepos from flooding the system prompt.
+const MAX_NESTED_RULES_FILES: usize = 32;
+
 pub fn init(cx: &mut App) {
     ThreadsDatabase::init(cx);
 }
 
+/// Whether `path` is a rules file with the given name located in a subdirectory of the worktree.
+/// Only single-component names are considered, so `.github/copilot-instructions.md` is only ever
+/// picked up at the root.
+fn is_nested_rules_file(path: &Path, name: &str) -> bool {
+    !name.contains('/')
+        && path.file_name().is_some_and(|file_name| file_name == name)
+        && path
+            .parent()
+            .is_some_and(|parent| !parent.as_os_str().is_empty())
+}
+
+/// Finds rules files in subdirectories of the worktree, picking at most one file per directory
+/// using the same priority as for the root rules file. Shallower directories come first.
+fn find_nested_rules_files(snapshot: &worktree::Snapshot) -> Vec<Arc<Path>> {
+    let mut selected_by_directory: Vec<(Arc<Path>, usize)> = Vec::new();
+    for entry in snapshot.files(false, 0) {
+        let Some(priority) = RULES_FILE_NAMES
+            .iter()
+            .position(|name| is_nested_rules_file(&entry.path, name))
+        else {
+            continue;
+        };
+        let directory = entry.path.parent();
+        match selected_by_directory
+            .iter_mut()
+            .find(|(selected, _)| selected.parent() == directory)
+        {
+            Some((selected, selected_priority)) => {
+                if priority < *selected_priority {
+                    *selected = entry.path.clone();
+                    *selected_priority = priority;
+                }
+            }
+            None => {
+                if selected_by_directory.len() == MAX_NESTED_RULES_FILES {
+                    continue;
+                }
+                selected_by_directory.push((entry.path.clone(), priority));
+            }
+        }
+    }
+    selected_by_directory.sort_by_key(|(path, _)| path.components().count());
+    selected_by_directory
+        .into_iter()
+        .map(|(path, _)| path)
+        .collect()
+}
+
 /// A system prompt shared by all threads created by this ThreadStore
 #[derive(Clone, Default)]
 pub struct SharedProjectContext(Rc<RefCell<Option<ProjectContext>>>);
@@ -105,6 +159,7 @@ pub struct ThreadStore {
     context_server_tool_ids: HashMap<ContextServerId, Vec<ToolId>>,
     threads: Vec<SerializedThreadMetadata>,
     project_context: SharedProjectContext,
+    memory_store: Entity<MemoryStore>,
     reload_system_prompt_tx: mpsc::Sender<()>,
     _reload_system_prompt_task: Task<()>,
     _subscriptions: Vec<Subscription>,
@@ -147,11 +202,20 @@ impl ThreadStore {
         prompt_store: Option<Entity<PromptStore>>,
         cx: &mut Context<Self>,
     ) -> (Self, oneshot::Receiver<()>) {
+        let memory_store = MemoryStore::global(cx);
         let mut subscriptions = vec![
             cx.observe_global::<SettingsStore>(move |this: &mut Self, cx| {
                 this.load_default_profile(cx);
             }),
             cx.subscribe(&project, Self::handle_project_event),
+            cx.subscribe(
+                &memory_store,
+                |this, _, MemoryStoreEvent::MemoriesChanged(project_key), cx| {
+                    if ProjectKey::for_project(&this.project, cx).as_ref() == Some(project_key) {
+                        this.enqueue_system_prompt_reload();
+                    }
+                },
+            ),
         ];
 
         if let Some(prompt_store) = prompt_store.as_ref() {
@@ -196,6 +260,7 @@ impl ThreadStore {
             context_server_tool_ids: HashMap::default(),
             threads: Vec::new(),
             project_context: SharedProjectContext::default(),
+            memory_store,
             reload_system_prompt_tx,
             _reload_system_prompt_task: reload_system_prompt_task,
             _subscriptions: subscriptions,
@@ -218,9 +283,9 @@ impl ThreadStore {
             }
             project::Event::WorktreeUpdatedEntries(_, items) => {
                 if items.iter().any(|(path, _, _)| {
-                    RULES_FILE_NAMES
-                        .iter()
-                        .any(|name| path.as_ref() == Path::new(name))
+                    RULES_FILE_NAMES.iter().any(|name| {
+                        path.as_ref() == Path::new(name) || is_nested_rules_file(path, name)
+                    })
                 }) {
                     self.enqueue_system_prompt_reload();
                 }
@@ -261,10 +326,25 @@ impl ThreadStore {
                 cx.background_spawn(future::join_all(load_tasks))
             }),
         };
+        let memory_store = self.memory_store.clone();
+        let memories_project_key = ProjectKey::for_project(&self.project, cx);
+        let load_memories = memories_project_key
+            .as_ref()
+            .map(|project_key| memory_store.update(cx, |store, cx| store.load(project_key, cx)));
 
         cx.spawn(async move |this, cx| {
             let (worktrees, default_user_rules) =
                 future::join(future::join_all(worktree_tasks), default_user_rules_task).await;
+            if let Some(load_memories) = load_memories {
+                load_memories.await;
+            }
+            let memories = memories_project_key
+                .and_then(|project_key| {
+                    memory_store
+                        .read_with(cx, |store, _| store.memories_for_prompt(&project_key))
+                        .ok()
+                })
+                .unwrap_or_default();
 
             let worktrees = worktrees
                 .into_iter()
@@ -286,6 +366,7 @@ impl ThreadStore {
                         },
                         title: prompt_metadata.title.map(|title| title.to_string()),
                         contents,
+                        scope: prompt_metadata.scope,
                     }),
                     Err(err) => {
                         this.update(cx, |_, cx| {
@@ -300,8 +381,9 @@ impl ThreadStore {
                 .collect::<Vec<_>>();
 
             this.update(cx, |this, _cx| {
-                *this.project_context.0.borrow_mut() =
-                    Some(ProjectContext::new(worktrees, default_user_rules));
+                *this.project_context.0.borrow_mut() = Some(
+                    ProjectContext::new(worktrees, default_user_rules).with_memories(memories),
+                );
             })
             .ok();
         })
@@ -314,30 +396,43 @@ impl ThreadStore {
     ) -> Task<(WorktreeContext, Option<RulesLoadingError>)> {
         let root_name = worktree.read(cx).root_name().into();
 
-        let rules_task = Self::load_worktree_rules_file(worktree, project, cx);
-        let Some(rules_task) = rules_task else {
-            return Task::ready((
-                WorktreeContext {
-                    root_name,
-                    rules_file: None,
-                },
-                None,
-            ));
-        };
+        let rules_task = Self::load_worktree_rules_file(worktree.clone(), project.clone(), cx);
+        let nested_rules_task = Self::load_nested_rules_files(worktree, project, cx);
 
         cx.spawn(async move |_| {
-            let (rules_file, rules_file_error) = match rules_task.await {
-                Ok(rules_file) => (Some(rules_file), None),
-                Err(err) => (
-                    None,
-                    Some(RulesLoadingError {
+            let rules_result = match rules_task {
+                Some(rules_task) => Some(rules_task.await),
+                None => None,
+            };
+            let nested_rules_results = nested_rules_task.await;
+
+            let mut rules_file_error = None;
+            let rules_file = match rules_result {
+                Some(Ok(rules_file)) => Some(rules_file),
+                Some(Err(err)) => {
+                    rules_file_error = Some(RulesLoadingError {
                         message: format!("{err}").into(),
-                    }),
-                ),
+                    });
+                    None
+                }
+                None => None,
             };
+            let mut nested_rules_files = Vec::new();
+            for result in nested_rules_results {
+                match result {
+                    Ok(rules_file) => nested_rules_files.push(rules_file),
+                    Err(err) => {
+                        rules_file_error.get_or_insert(RulesLoadingError {
+                            message: format!("{err}").into(),
+                        });
+                    }
+                }
+            }
+
             let worktree_info = WorktreeContext {
                 root_name,
                 rules_file,
+                nested_rules_files,
             };
             (worktree_info, rules_file_error)
         })
@@ -363,26 +458,67 @@ impl ThreadStore {
         // Note that Cline supports `.clinerules` being a directory, but that is not currently
         // supported. This doesn't seem to occur often in GitHub repositories.
         selected_rules_file.map(|path_in_worktree| {
-            let project_path = ProjectPath {
-                worktree_id,
-                path: path_in_worktree.clone(),
+            Self::load_rules_file(
+                ProjectPath {
+                    worktree_id,
+                    path: path_in_worktree,
+                },
+                project,
+                cx,
+            )
+        })
+    }
+
+    /// Loads the rules files in subdirectories of the worktree, scanning for them on a
+    /// background thread since the worktree can be large.
+    fn load_nested_rules_files(
+        worktree: Entity<Worktree>,
+        project: Entity<Project>,
+        cx: &mut App,
+    ) -> Task<Vec<Result<RulesFileContext>>> {
+        let worktree_id = worktree.read(cx).id();
+        let snapshot = worktree.read(cx).snapshot();
+        let paths = cx.background_spawn(async move { find_nested_rules_files(&snapshot) });
+        cx.spawn(async move |cx| {
+            let paths = paths.await;
+            let Ok(load_tasks) = cx.update(|cx| {
+                paths
+                    .into_iter()
+                    .map(|path| {
+                        Self::load_rules_file(
+                            ProjectPath { worktree_id, path },
+                            project.clone(),
+                            cx,
+                        )
+                    })
+                    .collect::<Vec<_>>()
+            }) else {
+                return Vec::new();
             };
-            let buffer_task =
-                project.update(cx, |project, cx| project.open_buffer(project_path, cx));
-            let rope_task = cx.spawn(async move |cx| {
-                buffer_task.await?.read_with(cx, |buffer, cx| {
-                    let project_entry_id = buffer.entry_id(cx).context("buffer has no file")?;
-                    anyhow::Ok((project_entry_id, buffer.as_rope().clone()))
-                })?
-            });
-            // Build a string from the rope on a background thread.
-            cx.background_spawn(async move {
-                let (project_entry_id, rope) = rope_task.await?;
-                anyhow::Ok(RulesFileContext {
-                    path_in_worktree,
-                    text: rope.to_string().trim().to_string(),
-                    project_entry_id: project_entry_id.to_usize(),
-                })
+            future::join_all(load_tasks).await
+        })
+    }
+
+    fn load_rules_file(
+        project_path: ProjectPath,
+        project: Entity<Project>,
+        cx: &mut App,
+    ) -> Task<Result<RulesFileContext>> {
+        let path_in_worktree = project_path.path.clone();
+        let buffer_task = project.update(cx, |project, cx| project.open_buffer(project_path, cx));
+        let rope_task = cx.spawn(async move |cx| {
+            buffer_task.await?.read_with(cx, |buffer, cx| {
+                let project_entry_id = buffer.entry_id(cx).context("buffer has no file")?;
+                anyhow::Ok((project_entry_id, buffer.as_rope().clone()))
+            })?
+        });
+        // Build a string from the rope on a background thread.
+        cx.background_spawn(async move {
+            let (project_entry_id, rope) = rope_task.await?;
+            anyhow::Ok(RulesFileContext {
+                path_in_worktree,
+                text: rope.to_string().trim().to_string(),
+                project_entry_id: project_entry_id.to_usize(),
             })
         })
     }
@@ -441,6 +577,81 @@ impl ThreadStore {
         })
     }
 
+    /// Creates a new thread that shares `thread`'s messages up to and including
+    /// `message_id`, so that an alternative approach can be explored from there.
+    ///
+    /// The branch gets its own action log, so its edits can be kept or rejected
+    /// independently of the thread it was forked from. When `restore_checkpoint` is
+    /// true, the project's files are restored to how they were at `message_id`, so
+    /// the branch doesn't start from the edits made after it.
+    pub fn fork_thread(
+        &mut self,
+        thread: &Entity<Thread>,
+        message_id: MessageId,
+        restore_checkpoint: bool,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Entity<Thread>>> {
+        let (branch, serialized_thread, checkpoint) = thread.update(cx, |thread, cx| {
+            let branch = ThreadBranch {
+                root_id: thread.branch_root_id(),
+                parent_id: thread.id().clone(),
+                forked_at: message_id,
+            };
+            let checkpoint = restore_checkpoint
+                .then(|| thread.checkpoint_after_message(message_id))
+                .flatten();
+            (branch, thread.serialize(cx), checkpoint)
+        });
+        let git_store = self.project.read(cx).git_store().clone();
+
+        cx.spawn(async move |this, cx| {
+            if let Some(checkpoint) = checkpoint {
+                git_store
+                    .update(cx, |git_store, cx| {
+                        git_store.restore_checkpoint(checkpoint.git_checkpoint().clone(), cx)
+                    })?
+                    .await?;
+            }
+
+            let mut serialized_thread = serialized_thread.await?;
+            let message_ix = serialized_thread
+                .messages
+                .iter()
+                .position(|message| message.id == message_id)
+                .context("message to fork at not found")?;
+            serialized_thread.messages.truncate(message_ix + 1);
+            serialized_thread.updated_at = Utc::now();
+            serialized_thread.branch = Some(branch);
+
+            let branch = this.update(cx, |this, cx| {
+                this.create_thread_from_serialized(serialized_thread, cx)
+            })?;
+            this.update(cx, |this, cx| this.save_thread(&branch, cx))?
+                .await?;
+            Ok(branch)
+        })
+    }
+
+    /// Returns the threads in the same branch family as `thread`, starting with the root.
+    pub fn thread_branches(&self, thread: &Thread) -> Vec<SerializedThreadMetadata> {
+        let root_id = thread.branch_root_id();
+        let mut branches = self
+            .threads
+            .iter()
+            .filter(|metadata| metadata.branch_root_id.as_ref() == Some(&root_id))
+            .cloned()
+            .collect::<Vec<_>>();
+        if branches.is_empty() {
+            return branches;
+        }
+
+        branches.sort_unstable_by_key(|metadata| std::cmp::Reverse(metadata.updated_at));
+        if let Some(root) = self.threads.iter().find(|metadata| metadata.id == root_id) {
+            branches.insert(0, root.clone());
+        }
+        branches
+    }
+
     pub fn open_thread(
         &self,
         id: &ThreadId,
@@ -490,6 +701,25 @@ impl ThreadStore {
         })
     }
 
+    /// Searches the summaries, messages, tool names, and file paths of all saved threads.
+    pub fn search_threads(
+        &self,
+        query: &str,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<ThreadSearchMatch>>> {
+        let Some(query) = ThreadsDatabase::search_query(query) else {
+            return Task::ready(Ok(Vec::new()));
+        };
+        let database_future = ThreadsDatabase::global_future(cx);
+        cx.background_spawn(async move {
+            database_future
+                .await
+                .map_err(|err| anyhow!(err))?
+                .search_threads(query)
+                .await
+        })
+    }
+
     pub fn delete_thread(&mut self, id: &ThreadId, cx: &mut Context<Self>) -> Task<Result<()>> {
         let id = id.clone();
         let database_future = ThreadsDatabase::global_future(cx);
@@ -690,6 +920,16 @@ pub struct SerializedThreadMetadata {
     pub id: ThreadId,
     pub summary: SharedString,
     pub updated_at: DateTime<Utc>,
+    /// The root of the thread's branch family, if it was forked from another thread.
+    pub branch_root_id: Option<ThreadId>,
+    pub description: Option<SharedString>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ThreadSearchMatch {
+    pub thread: SerializedThreadMetadata,
+    /// The part of the thread that matched the query.
+    pub snippet: SharedString,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -714,6 +954,10 @@ pub struct SerializedThread {
     pub completion_mode: Option<CompletionMode>,
     #[serde(default)]
     pub tool_use_limit_reached: bool,
+    #[serde(default)]
+    pub branch: Option<ThreadBranch>,
+    #[serde(default)]
+    pub description: Option<SharedString>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -797,6 +1041,8 @@ pub struct SerializedMessage {
     pub creases: Vec<SerializedCrease>,
     #[serde(default)]
     pub is_hidden: bool,
+    #[serde(default)]
+    pub response_metrics: Option<ResponseMetrics>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -856,6 +1102,8 @@ impl LegacySerializedThread {
             model: None,
             completion_mode: None,
             tool_use_limit_reached: false,
+            branch: None,
+            description: None,
         }
     }
 }
@@ -882,6 +1130,7 @@ impl LegacySerializedMessage {
             context: String::new(),
             creases: Vec::new(),
             is_hidden: false,
+            response_metrics: None,
         }
     }
 }
@@ -913,6 +1162,8 @@ impl ThreadsDatabase {
     const COMPRESSION_LEVEL: i32 = 3;
 }
 
+impl StaticColumnCount for ThreadId {}
+
 impl Bind for ThreadId {
     fn bind(&self, statement: &Statement, start_index: i32) -> Result<i32> {
         self.to_string().bind(statement, start_index)
@@ -964,16 +1215,61 @@ impl ThreadsDatabase {
                     summary TEXT NOT NULL,
                     updated_at TEXT NOT NULL,
                     data_type TEXT NOT NULL,
-                    data BLOB NOT NULL
+                    data BLOB NOT NULL,
+                    branch_root_id TEXT,
+                    description TEXT
                 )
             "})?()
         .map_err(|e| anyhow!("Failed to create threads table: {}", e))?;
 
+        // Databases created before threads could be forked lack this column.
+        let has_branch_root_column = connection.select_row::<bool>(indoc! {"
+                SELECT COUNT(*) > 0 FROM pragma_table_info('threads')
+                WHERE name = 'branch_root_id'
+            "})?()?
+        .unwrap_or(false);
+        if !has_branch_root_column {
+            connection.exec("ALTER TABLE threads ADD COLUMN branch_root_id TEXT")?()
+                .map_err(|e| anyhow!("Failed to add branch_root_id column: {}", e))?;
+        }
+
+        // Databases created before threads had descriptions lack this column.
+        let has_description_column = connection.select_row::<bool>(indoc! {"
+                SELECT COUNT(*) > 0 FROM pragma_table_info('threads')
+                WHERE name = 'description'
+            "})?()?
+        .unwrap_or(false);
+        if !has_description_column {
+            connection.exec("ALTER TABLE threads ADD COLUMN description TEXT")?()
+                .map_err(|e| anyhow!("Failed to add description column: {}", e))?;
+        }
+
+        connection.exec(indoc! {"
+                CREATE VIRTUAL TABLE IF NOT EXISTS threads_search USING fts5(
+                    id UNINDEXED,
+                    summary,
+                    messages,
+                    tool_names,
+                    file_paths
+                )
+            "})?()
+        .map_err(|e| anyhow!("Failed to create threads_search table: {}", e))?;
+
         let db = Self {
             executor: executor.clone(),
             connection: Arc::new(Mutex::new(connection)),
         };
 
+        // Threads saved before search existed are indexed in the background, so that opening
+        // the database doesn't wait on decoding every thread. Indexing resumes where it left off
+        // if it's interrupted.
+        let db_connection = db.connection();
+        executor
+            .spawn(async move {
+                Self::index_unindexed_threads(&db_connection).log_err();
+            })
+            .detach();
+
         if needs_migration_from_heed {
             let db_connection = db.connection();
             let executor_clone = executor.clone();
@@ -1044,6 +1340,124 @@ impl ThreadsDatabase {
         Ok(())
     }
 
+    fn index_unindexed_threads(connection: &Arc<Mutex<Connection>>) -> Result<()> {
+        let ids = {
+            let connection = connection.lock().unwrap();
+            let mut select = connection.select::<ThreadId>(indoc! {"
+                SELECT id FROM threads WHERE id NOT IN (SELECT id FROM threads_search)
+            "})?;
+            select()?
+        };
+
+        for id in ids {
+            // The lock is only held for one thread at a time so that saves can interleave, which
+            // is why the thread is checked for having been indexed by a save in the meantime.
+            let connection = connection.lock().unwrap();
+            let mut select = connection.select_bound::<ThreadId, (DataType, Vec<u8>)>(indoc! {"
+                SELECT data_type, data FROM threads
+                WHERE id = ? AND id NOT IN (SELECT id FROM threads_search)
+            "})?;
+            let Some((data_type, data)) = select(id.clone())?.into_iter().next() else {
+                continue;
+            };
+            match Self::decode_thread(data_type, data) {
+                Ok(thread) => Self::index_thread(&connection, id, &thread)?,
+                Err(error) => log::error!("failed to index thread {id}: {error}"),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn index_thread(
+        connection: &Connection,
+        id: ThreadId,
+        thread: &SerializedThread,
+    ) -> Result<()> {
+        let mut messages = String::new();
+        let mut tool_names = Vec::<&str>::new();
+        let mut file_paths = Vec::<&str>::new();
+        for message in &thread.messages {
+            for segment in &message.segments {
+                match segment {
+                    SerializedMessageSegment::Text { text }
+                    | SerializedMessageSegment::Thinking { text, .. } => {
+                        messages.push_str(text);
+                        messages.push('\n');
+                    }
+                    SerializedMessageSegment::RedactedThinking { .. } => {}
+                }
+            }
+
+            for tool_use in &message.tool_uses {
+                if !tool_names.contains(&tool_use.name.as_ref()) {
+                    tool_names.push(&tool_use.name);
+                }
+
+                // Tools take paths under keys like `path`, `source_path`, and `destination_path`.
+                let Some(input) = tool_use.input.as_object() else {
+                    continue;
+                };
+                for (key, value) in input {
+                    if let Some(path) = value.as_str().filter(|_| key.ends_with("path")) {
+                        if !file_paths.contains(&path) {
+                            file_paths.push(path);
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut delete = connection.exec_bound::<ThreadId>(indoc! {"
+            DELETE FROM threads_search WHERE id = ?
+        "})?;
+        delete(id.clone())?;
+
+        let mut insert = connection.exec_bound::<(ThreadId, String, String, String, String)>(indoc! {"
+            INSERT INTO threads_search (id, summary, messages, tool_names, file_paths) VALUES (?, ?, ?, ?, ?)
+        "})?;
+        let mut summary = thread.summary.to_string();
+        if let Some(description) = &thread.description {
+            summary.push('\n');
+            summary.push_str(description);
+        }
+        insert((
+            id,
+            summary,
+            messages,
+            tool_names.join(" "),
+            file_paths.join("\n"),
+        ))?;
+
+        Ok(())
+    }
+
+    /// Turns user input into an FTS5 query that matches threads containing every word, so that
+    /// punctuation in the input isn't interpreted as query syntax.
+    fn search_query(query: &str) -> Option<String> {
+        let terms = query
+            .split_whitespace()
+            .map(|term| format!("\"{}\"*", term.replace('"', "\"\"")))
+            .collect::<Vec<_>>();
+        if terms.is_empty() {
+            None
+        } else {
+            Some(terms.join(" "))
+        }
+    }
+
+    fn decode_thread(data_type: DataType, data: Vec<u8>) -> Result<SerializedThread> {
+        let json_data = match data_type {
+            DataType::Zstd => {
+                let decompressed = zstd::decode_all(&data[..])?;
+                String::from_utf8(decompressed)?
+            }
+            DataType::Json => String::from_utf8(data)?,
+        };
+
+        SerializedThread::from_json(json_data.as_bytes())
+    }
+
     fn save_thread_sync(
         connection: &Arc<Mutex<Connection>>,
         id: ThreadId,
@@ -1052,18 +1466,32 @@ impl ThreadsDatabase {
         let json_data = serde_json::to_string(&thread)?;
         let summary = thread.summary.to_string();
         let updated_at = thread.updated_at.to_rfc3339();
+        let branch_root_id = thread.branch.as_ref().map(|branch| branch.root_id.clone());
+        let description = thread
+            .description
+            .as_ref()
+            .map(|description| description.to_string());
 
         let connection = connection.lock().unwrap();
+        Self::index_thread(&connection, id.clone(), &thread)?;
 
         let compressed = zstd::encode_all(json_data.as_bytes(), Self::COMPRESSION_LEVEL)?;
         let data_type = DataType::Zstd;
         let data = compressed;
 
-        let mut insert = connection.exec_bound::<(ThreadId, String, String, DataType, Vec<u8>)>(indoc! {"
-            INSERT OR REPLACE INTO threads (id, summary, updated_at, data_type, data) VALUES (?, ?, ?, ?, ?)
+        let mut insert = connection.exec_bound::<(ThreadId, String, String, DataType, Vec<u8>, Option<ThreadId>, Option<String>)>(indoc! {"
+            INSERT OR REPLACE INTO threads (id, summary, updated_at, data_type, data, branch_root_id, description) VALUES (?, ?, ?, ?, ?, ?, ?)
         "})?;
 
-        insert((id, summary, updated_at, data_type, data))?;
+        insert((
+            id,
+            summary,
+            updated_at,
+            data_type,
+            data,
+            branch_root_id,
+            description,
+        ))?;
 
         Ok(())
     }
@@ -1073,19 +1501,21 @@ impl ThreadsDatabase {
 
         self.executor.spawn(async move {
             let connection = connection.lock().unwrap();
-            let mut select =
-                connection.select_bound::<(), (ThreadId, String, String)>(indoc! {"
-                SELECT id, summary, updated_at FROM threads ORDER BY updated_at DESC
+            let mut select = connection
+                .select_bound::<(), (ThreadId, String, String, Option<ThreadId>, Option<String>)>(indoc! {"
+                SELECT id, summary, updated_at, branch_root_id, description FROM threads ORDER BY updated_at DESC
             "})?;
 
             let rows = select(())?;
             let mut threads = Vec::new();
 
-            for (id, summary, updated_at) in rows {
+            for (id, summary, updated_at, branch_root_id, description) in rows {
                 threads.push(SerializedThreadMetadata {
                     id,
                     summary: summary.into(),
                     updated_at: DateTime::parse_from_rfc3339(&updated_at)?.with_timezone(&Utc),
+                    branch_root_id,
+                    description: description.map(Into::into),
                 });
             }
 
@@ -1104,22 +1534,59 @@ impl ThreadsDatabase {
 
             let rows = select(id)?;
             if let Some((data_type, data)) = rows.into_iter().next() {
-                let json_data = match data_type {
-                    DataType::Zstd => {
-                        let decompressed = zstd::decode_all(&data[..])?;
-                        String::from_utf8(decompressed)?
-                    }
-                    DataType::Json => String::from_utf8(data)?,
-                };
-
-                let thread = SerializedThread::from_json(json_data.as_bytes())?;
-                Ok(Some(thread))
+                Ok(Some(Self::decode_thread(data_type, data)?))
             } else {
                 Ok(None)
             }
         })
     }
 
+    pub fn search_threads(&self, query: String) -> Task<Result<Vec<ThreadSearchMatch>>> {
+        let connection = self.connection.clone();
+
+        self.executor.spawn(async move {
+            let connection = connection.lock().unwrap();
+            let mut select = connection.select_bound::<String, (
+                ThreadId,
+                String,
+                String,
+                Option<ThreadId>,
+                Option<String>,
+                String,
+            )>(indoc! {"
+                    SELECT threads.id, threads.summary, threads.updated_at, threads.branch_root_id,
+                        threads.description, snippet(threads_search, -1, '', '', '…', 16)
+                    FROM threads_search
+                    JOIN threads ON threads.id = threads_search.id
+                    WHERE threads_search MATCH ?
+                    ORDER BY rank
+                    LIMIT 100
+                "})?;
+
+            let rows = select(query)?;
+            let mut matches = Vec::new();
+
+            for (id, summary, updated_at, branch_root_id, description, snippet) in rows {
+                matches.push(ThreadSearchMatch {
+                    thread: SerializedThreadMetadata {
+                        id,
+                        summary: summary.into(),
+                        updated_at: DateTime::parse_from_rfc3339(&updated_at)?.with_timezone(&Utc),
+                        branch_root_id,
+                        description: description.map(Into::into),
+                    },
+                    snippet: snippet
+                        .split_whitespace()
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                        .into(),
+                });
+            }
+
+            Ok(matches)
+        })
+    }
+
     pub fn save_thread(&self, id: ThreadId, thread: SerializedThread) -> Task<Result<()>> {
         let connection = self.connection.clone();
 
@@ -1136,10 +1603,179 @@ impl ThreadsDatabase {
             let mut delete = connection.exec_bound::<ThreadId>(indoc! {"
                 DELETE FROM threads WHERE id = ?
             "})?;
+            delete(id.clone())?;
 
-            delete(id)?;
+            let mut delete_search = connection.exec_bound::<ThreadId>(indoc! {"
+                DELETE FROM threads_search WHERE id = ?
+            "})?;
+            delete_search(id)?;
 
             Ok(())
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use serde_json::json;
+
+    fn serialized_thread(summary: &str, text: &str, edited_path: &str) -> SerializedThread {
+        let json = json!({
+            "version": SerializedThread::VERSION,
+            "summary": summary,
+            "updated_at": "2025-06-01T12:00:00Z",
+            "messages": [
+                {
+                    "id": 0,
+                    "role": "user",
+                    "segments": [{ "type": "text", "text": text }],
+                },
+                {
+                    "id": 1,
+                    "role": "assistant",
+                    "tool_uses": [{
+                        "id": "tool_1",
+                        "name": "edit_file",
+                        "input": { "path": edited_path },
+                    }],
+                },
+            ],
+        });
+        SerializedThread::from_json(json.to_string().as_bytes()).unwrap()
+    }
+
+    async fn search(database: &ThreadsDatabase, query: &str) -> Vec<(String, String)> {
+        database
+            .search_threads(ThreadsDatabase::search_query(query).unwrap())
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|search_match| {
+                (
+                    search_match.thread.id.to_string(),
+                    search_match.snippet.to_string(),
+                )
+            })
+            .collect()
+    }
+
+    #[gpui::test]
+    async fn test_search_threads(cx: &mut TestAppContext) {
+        let threads_dir = tempfile::tempdir().unwrap();
+        let database =
+            ThreadsDatabase::new(threads_dir.path().to_path_buf(), cx.executor()).unwrap();
+        database
+            .save_thread(
+                ThreadId::from("parser"),
+                serialized_thread(
+                    "Fix the parser",
+                    "The tokenizer chokes on unicode identifiers",
+                    "src/lexer.rs",
+                ),
+            )
+            .await
+            .unwrap();
+        database
+            .save_thread(
+                ThreadId::from("docs"),
+                serialized_thread(
+                    "Update the docs",
+                    "Document how unicode is rendered",
+                    "docs/rendering.md",
+                ),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            search(&database, "tokenizer").await,
+            [(
+                "parser".to_string(),
+                "The tokenizer chokes on unicode identifiers".to_string()
+            )]
+        );
+        // Every word must match, and words match as prefixes.
+        assert_eq!(
+            search(&database, "unicode token")
+                .await
+                .into_iter()
+                .map(|(id, _)| id)
+                .collect::<Vec<_>>(),
+            ["parser"]
+        );
+        // File paths and summaries are searched too.
+        assert_eq!(
+            search(&database, "lexer")
+                .await
+                .into_iter()
+                .map(|(id, _)| id)
+                .collect::<Vec<_>>(),
+            ["parser"]
+        );
+        assert_eq!(
+            search(&database, "docs")
+                .await
+                .into_iter()
+                .map(|(id, _)| id)
+                .collect::<Vec<_>>(),
+            ["docs"]
+        );
+        // Query syntax in the input is matched literally.
+        assert_eq!(
+            search(&database, "\"unicode OR\" NEAR(").await,
+            Vec::<(String, String)>::new()
+        );
+        assert_eq!(ThreadsDatabase::search_query("  "), None);
+
+        database
+            .delete_thread(ThreadId::from("parser"))
+            .await
+            .unwrap();
+        assert_eq!(
+            search(&database, "tokenizer").await,
+            Vec::<(String, String)>::new()
+        );
+    }
+
+    #[gpui::test]
+    async fn test_index_unindexed_threads(cx: &mut TestAppContext) {
+        let threads_dir = tempfile::tempdir().unwrap();
+        let database =
+            ThreadsDatabase::new(threads_dir.path().to_path_buf(), cx.executor()).unwrap();
+        database
+            .save_thread(
+                ThreadId::from("parser"),
+                serialized_thread("Fix the parser", "The tokenizer chokes", "src/lexer.rs"),
+            )
+            .await
+            .unwrap();
+
+        // Simulate a thread saved before search existed.
+        database
+            .connection()
+            .lock()
+            .unwrap()
+            .exec("DELETE FROM threads_search")
+            .unwrap()()
+        .unwrap();
+        assert_eq!(
+            search(&database, "tokenizer").await,
+            Vec::<(String, String)>::new()
+        );
+        drop(database);
+
+        let database =
+            ThreadsDatabase::new(threads_dir.path().to_path_buf(), cx.executor()).unwrap();
+        cx.run_until_parked();
+        assert_eq!(
+            search(&database, "tokenizer")
+                .await
+                .into_iter()
+                .map(|(id, _)| id)
+                .collect::<Vec<_>>(),
+            ["parser"]
+        );
+    }
+}
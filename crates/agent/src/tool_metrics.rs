@@ -0,0 +1,286 @@
+use std::{sync::Arc, time::Duration};
+
+use agent_settings::AgentProfileId;
+use chrono::{DateTime, Utc};
+use collections::HashMap;
+use db::kvp::KEY_VALUE_STORE;
+use gpui::{App, AppContext as _, Context, Entity, Global, Task};
+use serde::{Deserialize, Serialize};
+use util::ResultExt as _;
+
+use crate::thread::ThreadId;
+
+const TOOL_METRICS_KEY: &str = "agent-tool-metrics";
+
+/// Only the most recent invocations are kept, so the store doesn't grow without bound.
+const MAX_INVOCATIONS: usize = 5000;
+
+/// Tools often run in quick succession, so saves are batched.
+const SAVE_DEBOUNCE: Duration = Duration::from_secs(2);
+
+/// A single run of a tool, as recorded when its output arrived.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolInvocation {
+    pub tool_name: Arc<str>,
+    pub thread_id: ThreadId,
+    pub profile_id: AgentProfileId,
+    pub duration: Duration,
+    pub succeeded: bool,
+    pub truncated: bool,
+    pub finished_at: DateTime<Utc>,
+}
+
+/// Which invocations to summarize.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ToolMetricsScope {
+    All,
+    Thread(ThreadId),
+    Profile(AgentProfileId),
+}
+
+impl ToolMetricsScope {
+    fn includes(&self, invocation: &ToolInvocation) -> bool {
+        match self {
+            ToolMetricsScope::All => true,
+            ToolMetricsScope::Thread(thread_id) => &invocation.thread_id == thread_id,
+            ToolMetricsScope::Profile(profile_id) => &invocation.profile_id == profile_id,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolMetricsSummary {
+    pub tool_name: Arc<str>,
+    pub invocations: usize,
+    pub failures: usize,
+    pub truncations: usize,
+    pub total_duration: Duration,
+    pub max_duration: Duration,
+}
+
+impl ToolMetricsSummary {
+    pub fn mean_duration(&self) -> Duration {
+        self.total_duration / self.invocations.max(1) as u32
+    }
+
+    pub fn failure_rate(&self) -> f32 {
+        self.failures as f32 / self.invocations.max(1) as f32
+    }
+
+    pub fn truncation_rate(&self) -> f32 {
+        self.truncations as f32 / self.invocations.max(1) as f32
+    }
+}
+
+struct GlobalToolMetricsStore(Entity<ToolMetricsStore>);
+
+impl Global for GlobalToolMetricsStore {}
+
+/// Records how long each tool invocation took and how it ended, so users can
+/// see which tools dominate an agent's latency. Nothing here leaves the machine.
+pub struct ToolMetricsStore {
+    invocations: Vec<ToolInvocation>,
+    /// Loads the saved invocations, which are prepended to any recorded in the meantime.
+    load_task: Option<Task<()>>,
+    save_task: Option<Task<()>>,
+}
+
+impl ToolMetricsStore {
+    pub fn global(cx: &mut App) -> Entity<Self> {
+        if let Some(store) = cx.try_global::<GlobalToolMetricsStore>() {
+            return store.0.clone();
+        }
+        let store = cx.new(|cx| Self {
+            invocations: Vec::new(),
+            load_task: Some(Self::load(cx)),
+            save_task: None,
+        });
+        cx.set_global(GlobalToolMetricsStore(store.clone()));
+        store
+    }
+
+    pub fn record(&mut self, invocation: ToolInvocation, cx: &mut Context<Self>) {
+        self.invocations.push(invocation);
+        self.truncate();
+        self.schedule_save(cx);
+        cx.notify();
+    }
+
+    pub fn clear(&mut self, cx: &mut Context<Self>) {
+        self.invocations.clear();
+        // The saved invocations are being cleared too, so there's no need to wait for them.
+        self.load_task = None;
+        self.schedule_save(cx);
+        cx.notify();
+    }
+
+    /// Per-tool totals for the invocations in `scope`, slowest tools first.
+    pub fn summarize(&self, scope: &ToolMetricsScope) -> Vec<ToolMetricsSummary> {
+        summarize(&self.invocations, scope)
+    }
+
+    fn truncate(&mut self) {
+        if self.invocations.len() > MAX_INVOCATIONS {
+            self.invocations
+                .drain(..self.invocations.len() - MAX_INVOCATIONS);
+        }
+    }
+
+    fn load(cx: &mut Context<Self>) -> Task<()> {
+        cx.spawn(async move |this, cx| {
+            let saved_invocations = cx
+                .background_spawn(async move {
+                    KEY_VALUE_STORE
+                        .read_kvp(TOOL_METRICS_KEY)
+                        .log_err()
+                        .flatten()
+                        .and_then(|json| {
+                            serde_json::from_str::<Vec<ToolInvocation>>(&json).log_err()
+                        })
+                        .unwrap_or_default()
+                })
+                .await;
+            this.update(cx, |this, cx| {
+                this.load_task = None;
+                let recorded_invocations =
+                    std::mem::replace(&mut this.invocations, saved_invocations);
+                this.invocations.extend(recorded_invocations);
+                this.truncate();
+                if this.save_task.is_some() {
+                    this.schedule_save(cx);
+                }
+                cx.notify();
+            })
+            .ok();
+        })
+    }
+
+    fn schedule_save(&mut self, cx: &mut Context<Self>) {
+        self.save_task = Some(cx.spawn(async move |this, cx| {
+            cx.background_executor().timer(SAVE_DEBOUNCE).await;
+            // Saving before loading finishes would overwrite the saved invocations, so loading
+            // schedules another save once it's done instead.
+            let Some(invocations) = this
+                .update(cx, |this, _| {
+                    this.load_task.is_none().then(|| this.invocations.clone())
+                })
+                .ok()
+                .flatten()
+            else {
+                return;
+            };
+            cx.background_spawn(async move {
+                let json = serde_json::to_string(&invocations)?;
+                KEY_VALUE_STORE
+                    .write_kvp(TOOL_METRICS_KEY.to_string(), json)
+                    .await
+            })
+            .await
+            .log_err();
+        }));
+    }
+}
+
+fn summarize(invocations: &[ToolInvocation], scope: &ToolMetricsScope) -> Vec<ToolMetricsSummary> {
+    let mut summaries = HashMap::<Arc<str>, ToolMetricsSummary>::default();
+    for invocation in invocations
+        .iter()
+        .filter(|invocation| scope.includes(invocation))
+    {
+        let summary = summaries
+            .entry(invocation.tool_name.clone())
+            .or_insert_with(|| ToolMetricsSummary {
+                tool_name: invocation.tool_name.clone(),
+                invocations: 0,
+                failures: 0,
+                truncations: 0,
+                total_duration: Duration::ZERO,
+                max_duration: Duration::ZERO,
+            });
+        summary.invocations += 1;
+        summary.failures += usize::from(!invocation.succeeded);
+        summary.truncations += usize::from(invocation.truncated);
+        summary.total_duration += invocation.duration;
+        summary.max_duration = summary.max_duration.max(invocation.duration);
+    }
+
+    let mut summaries = summaries.into_values().collect::<Vec<_>>();
+    summaries.sort_by(|a, b| {
+        b.total_duration
+            .cmp(&a.total_duration)
+            .then_with(|| a.tool_name.cmp(&b.tool_name))
+    });
+    summaries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn invocation(
+        tool_name: &str,
+        thread_id: &str,
+        profile_id: &str,
+        millis: u64,
+        succeeded: bool,
+        truncated: bool,
+    ) -> ToolInvocation {
+        ToolInvocation {
+            tool_name: tool_name.into(),
+            thread_id: ThreadId::from(thread_id),
+            profile_id: AgentProfileId(profile_id.into()),
+            duration: Duration::from_millis(millis),
+            succeeded,
+            truncated,
+            finished_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_summarize() {
+        let invocations = vec![
+            invocation("terminal", "a", "write", 3000, true, true),
+            invocation("read_file", "a", "write", 10, true, false),
+            invocation("terminal", "b", "write", 1000, false, false),
+            invocation("read_file", "b", "ask", 30, false, true),
+            invocation("grep", "b", "ask", 500, true, false),
+        ];
+
+        let summaries = summarize(&invocations, &ToolMetricsScope::All);
+        assert_eq!(
+            summaries
+                .iter()
+                .map(|summary| summary.tool_name.as_ref())
+                .collect::<Vec<_>>(),
+            vec!["terminal", "grep", "read_file"]
+        );
+        let terminal = &summaries[0];
+        assert_eq!(terminal.invocations, 2);
+        assert_eq!(terminal.failures, 1);
+        assert_eq!(terminal.truncations, 1);
+        assert_eq!(terminal.mean_duration(), Duration::from_millis(2000));
+        assert_eq!(terminal.max_duration, Duration::from_millis(3000));
+        assert_eq!(terminal.failure_rate(), 0.5);
+
+        let summaries = summarize(&invocations, &ToolMetricsScope::Thread(ThreadId::from("b")));
+        assert_eq!(
+            summaries
+                .iter()
+                .map(|summary| (summary.tool_name.as_ref(), summary.invocations))
+                .collect::<Vec<_>>(),
+            vec![("terminal", 1), ("grep", 1), ("read_file", 1)]
+        );
+
+        let summaries = summarize(
+            &invocations,
+            &ToolMetricsScope::Profile(AgentProfileId("ask".into())),
+        );
+        assert_eq!(
+            summaries
+                .iter()
+                .map(|summary| (summary.tool_name.as_ref(), summary.truncation_rate()))
+                .collect::<Vec<_>>(),
+            vec![("grep", 0.0), ("read_file", 1.0)]
+        );
+    }
+}
@@ -1,4 +1,5 @@
 use std::sync::Arc;
+use std::time::Instant;
 
 use anyhow::Result;
 use assistant_tool::{
@@ -164,6 +165,14 @@ impl ToolUseState {
         self.pending_tool_uses_by_id.values().collect()
     }
 
+    /// Returns the inputs of every tool use the model has requested in the thread.
+    pub fn tool_use_inputs(&self) -> impl Iterator<Item = &serde_json::Value> {
+        self.tool_uses_by_assistant_message
+            .values()
+            .flatten()
+            .map(|tool_use| &tool_use.input)
+    }
+
     pub fn tool_uses_for_message(&self, id: MessageId, cx: &App) -> Vec<ToolUse> {
         let Some(tool_uses_for_message) = &self.tool_uses_by_assistant_message.get(&id) else {
             return Vec::new();
@@ -369,6 +378,7 @@ impl ToolUseState {
             tool_use.ui_text = ui_text.into();
             tool_use.status = PendingToolUseStatus::Running {
                 _task: task.shared(),
+                started_at: Instant::now(),
             };
         }
     }
@@ -542,7 +552,10 @@ pub enum PendingToolUseStatus {
     InputStillStreaming,
     Idle,
     NeedsConfirmation(Arc<Confirmation>),
-    Running { _task: Shared<Task<()>> },
+    Running {
+        _task: Shared<Task<()>>,
+        started_at: Instant,
+    },
     Error(#[allow(unused)] Arc<str>),
 }
 
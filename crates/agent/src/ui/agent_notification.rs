@@ -58,7 +58,7 @@ impl AgentNotification {
             kind: WindowKind::PopUp,
             is_movable: false,
             display_id: Some(screen.id()),
-            window_background: WindowBackgroundAppearance::Transparent,
+            window_background: ui::popup_window_background(cx),
             app_id: Some(app_id.to_owned()),
             window_min_size: None,
             window_decorations: Some(WindowDecorations::Client),
@@ -99,11 +99,13 @@ impl Render for AgentNotification {
             .p_3()
             .gap_4()
             .justify_between()
-            .elevation_3(cx)
+            .popup_window_surface(cx)
             .text_ui(cx)
             .font(ui_font)
-            .border_color(cx.theme().colors().border)
-            .rounded_xl()
+            .when(
+                ui::popup_window_background(cx) != WindowBackgroundAppearance::Opaque,
+                |this| this.border_color(cx.theme().colors().border).rounded_xl(),
+            )
             .on_click(cx.listener(|_, _, _, cx| {
                 cx.emit(AgentNotificationEvent::Accepted);
             }))
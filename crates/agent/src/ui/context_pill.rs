@@ -14,10 +14,10 @@ use ui::{IconButtonShape, Tooltip, prelude::*, tooltip_container};
 
 use crate::context::{
     AgentContext, AgentContextHandle, ContextId, ContextKind, DirectoryContext,
-    DirectoryContextHandle, FetchedUrlContext, FileContext, FileContextHandle, ImageContext,
-    ImageStatus, RulesContext, RulesContextHandle, SelectionContext, SelectionContextHandle,
-    SymbolContext, SymbolContextHandle, TextThreadContext, TextThreadContextHandle, ThreadContext,
-    ThreadContextHandle,
+    DirectoryContextHandle, ExtensionEntryContext, FetchedUrlContext, FileContext,
+    FileContextHandle, ImageContext, ImageStatus, RulesContext, RulesContextHandle,
+    SelectionContext, SelectionContextHandle, SymbolContext, SymbolContextHandle,
+    TextThreadContext, TextThreadContextHandle, ThreadContext, ThreadContextHandle,
 };
 
 #[derive(IntoElement)]
@@ -88,6 +88,13 @@ impl ContextPill {
         }
     }
 
+    fn thumbnail(&self) -> Option<Arc<Image>> {
+        match self {
+            Self::Added { context, .. } => context.thumbnail(),
+            Self::Suggested { .. } => None,
+        }
+    }
+
     pub fn icon(&self) -> Icon {
         match self {
             Self::Suggested {
@@ -111,7 +118,15 @@ impl RenderOnce for ContextPill {
             .border_1()
             .rounded_sm()
             .gap_1()
-            .child(self.icon().size(IconSize::XSmall).color(Color::Muted));
+            .map(|pill| match self.thumbnail() {
+                Some(image) => pill.child(
+                    gpui::img(image)
+                        .size(IconSize::XSmall.rems())
+                        .rounded_xs()
+                        .flex_none(),
+                ),
+                None => pill.child(self.icon().size(IconSize::XSmall).color(Color::Muted)),
+            });
 
         match &self {
             ContextPill::Added {
@@ -280,6 +295,16 @@ pub struct AddedContext {
 }
 
 impl AddedContext {
+    /// Returns the attached image when it can be shown in place of the pill's icon.
+    pub fn thumbnail(&self) -> Option<Arc<Image>> {
+        match (&self.handle, &self.status) {
+            (AgentContextHandle::Image(image), ContextStatus::Ready) => {
+                Some(image.original_image.clone())
+            }
+            _ => None,
+        }
+    }
+
     pub fn icon(&self) -> Icon {
         match &self.status {
             ContextStatus::Warning { .. } => Icon::new(IconName::Warning).color(Color::Warning),
@@ -314,6 +339,7 @@ impl AddedContext {
             AgentContextHandle::TextThread(handle) => Some(Self::pending_text_thread(handle, cx)),
             AgentContextHandle::Rules(handle) => Self::pending_rules(handle, prompt_store, cx),
             AgentContextHandle::Image(handle) => Some(Self::image(handle, model, cx)),
+            AgentContextHandle::ExtensionEntry(handle) => Some(Self::extension_entry(handle)),
         }
     }
 
@@ -332,6 +358,7 @@ impl AddedContext {
             AgentContext::TextThread(context) => Self::attached_text_thread(context),
             AgentContext::Rules(context) => Self::attached_rules(context),
             AgentContext::Image(context) => Self::image(context.clone(), model, cx),
+            AgentContext::ExtensionEntry(context) => Self::extension_entry(context.clone()),
         }
     }
 
@@ -471,9 +498,9 @@ impl AddedContext {
     fn fetched_url(context: FetchedUrlContext) -> AddedContext {
         AddedContext {
             kind: ContextKind::FetchedUrl,
-            name: context.url.clone(),
+            name: context.title.clone().unwrap_or_else(|| context.url.clone()),
             parent: None,
-            tooltip: None,
+            tooltip: context.title.is_some().then(|| context.url.clone()),
             icon_path: None,
             status: ContextStatus::Ready,
             render_hover: None,
@@ -481,6 +508,19 @@ impl AddedContext {
         }
     }
 
+    fn extension_entry(context: ExtensionEntryContext) -> AddedContext {
+        AddedContext {
+            kind: ContextKind::ExtensionEntry,
+            name: context.label.clone(),
+            parent: Some(context.source_name.clone()),
+            tooltip: None,
+            icon_path: None,
+            status: ContextStatus::Ready,
+            render_hover: None,
+            handle: AgentContextHandle::ExtensionEntry(context),
+        }
+    }
+
     fn pending_thread(handle: ThreadContextHandle, cx: &App) -> AddedContext {
         AddedContext {
             kind: ContextKind::Thread,
@@ -897,6 +937,7 @@ mod tests {
             added_context.status,
             ContextStatus::Warning { .. }
         ));
+        assert!(added_context.thumbnail().is_none());
 
         assert!(matches!(added_context.kind, ContextKind::Image));
         assert_eq!(added_context.name.as_ref(), "Image");
@@ -920,6 +961,7 @@ mod tests {
             matches!(added_context.status, ContextStatus::Ready),
             "Expected ready status when no model provided"
         );
+        assert!(added_context.thumbnail().is_some());
 
         assert!(matches!(added_context.kind, ContextKind::Image));
         assert_eq!(added_context.name.as_ref(), "Image");
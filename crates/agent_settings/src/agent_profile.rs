@@ -69,6 +69,9 @@ pub struct AgentProfile {
     pub tools: IndexMap<Arc<str>, bool>,
     pub enable_all_context_servers: bool,
     pub context_servers: IndexMap<Arc<str>, ContextServerPreset>,
+    /// Whether to attach the files and symbols mentioned in the first message
+    /// of a thread before it is sent to the model.
+    pub gather_context: bool,
 }
 
 #[derive(Debug, Clone, Default)]
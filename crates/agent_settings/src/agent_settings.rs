@@ -48,6 +48,80 @@ pub enum NotifyWhenAgentWaiting {
     Never,
 }
 
+#[derive(Copy, Clone, Default, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EditMatchingMode {
+    /// Only edit lines that are identical to the model's `old_text`, apart from leading and
+    /// trailing whitespace.
+    Strict,
+    /// Also edit lines that differ from the model's `old_text` in whitespace, trailing commas or
+    /// small typos.
+    #[default]
+    Fuzzy,
+}
+
+/// What to do with the edits the agent made since the last user message when its response is
+/// canceled.
+#[derive(Copy, Clone, Default, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RevertEditsOnCancel {
+    /// Ask whether to revert the edits.
+    #[default]
+    Ask,
+    /// Always revert the edits.
+    Always,
+    /// Keep the edits, so they can be reviewed as usual.
+    Never,
+}
+
+/// The speech-to-text backend used for dictation in the agent panel.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(tag = "backend", rename_all = "snake_case")]
+pub enum DictationBackend {
+    /// Transcribe with a language model provider's speech-to-text API, using the credentials
+    /// configured for that provider.
+    Provider {
+        /// The id of the provider, such as `openai`.
+        provider: String,
+    },
+    /// Transcribe with a local server that implements OpenAI's `/audio/transcriptions` endpoint,
+    /// such as whisper.cpp's server.
+    Local {
+        /// The base URL of the server, such as `http://localhost:8080/v1`.
+        api_url: String,
+        /// The model to request, if the server hosts more than one.
+        model: Option<String>,
+    },
+}
+
+impl Default for DictationBackend {
+    fn default() -> Self {
+        Self::Provider {
+            provider: "openai".into(),
+        }
+    }
+}
+
+/// A prompt that's sent to the agent on a recurring schedule.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct ScheduledTask {
+    /// The name shown in the thread's title and in the notification sent when it finishes.
+    pub name: String,
+    /// When the task runs, as a cron expression in local time: minute, hour, day of month,
+    /// month, and day of week, such as `0 2 * * *` for every night at 2 AM. `@hourly`,
+    /// `@daily`, `@nightly`, `@weekly`, and `@monthly` are also accepted.
+    pub schedule: String,
+    /// The prompt that's sent to the agent.
+    pub prompt: String,
+    /// Whether the task runs.
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
 #[serde(tag = "name", rename_all = "snake_case")]
 #[schemars(deny_unknown_fields)]
@@ -107,10 +181,18 @@ pub struct AgentSettings {
     pub notify_when_agent_waiting: NotifyWhenAgentWaiting,
     pub play_sound_when_agent_done: bool,
     pub stream_edits: bool,
+    pub edit_matching_mode: EditMatchingMode,
+    pub edit_matching_min_confidence: f32,
     pub single_file_review: bool,
+    pub revert_edits_on_cancel: RevertEditsOnCancel,
     pub model_parameters: Vec<LanguageModelParameters>,
     pub preferred_completion_mode: CompletionMode,
     pub enable_feedback: bool,
+    pub redact_terminal_output: bool,
+    pub terminal_redaction_patterns: Vec<String>,
+    pub dictation: DictationBackend,
+    pub scheduled_tasks: Vec<ScheduledTask>,
+    pub warm_up_connections: bool,
 }
 
 impl AgentSettings {
@@ -274,11 +356,19 @@ impl AgentSettingsContent {
                     always_allow_tool_actions: None,
                     notify_when_agent_waiting: None,
                     stream_edits: None,
+                    edit_matching_mode: None,
+                    edit_matching_min_confidence: None,
                     single_file_review: None,
+                    revert_edits_on_cancel: None,
                     model_parameters: Vec::new(),
                     preferred_completion_mode: None,
                     enable_feedback: None,
                     play_sound_when_agent_done: None,
+                    redact_terminal_output: None,
+                    terminal_redaction_patterns: Vec::new(),
+                    dictation: None,
+                    scheduled_tasks: Vec::new(),
+                    warm_up_connections: None,
                 },
                 VersionedAgentSettingsContent::V2(ref settings) => settings.clone(),
             },
@@ -307,11 +397,19 @@ impl AgentSettingsContent {
                 always_allow_tool_actions: None,
                 notify_when_agent_waiting: None,
                 stream_edits: None,
+                edit_matching_mode: None,
+                edit_matching_min_confidence: None,
                 single_file_review: None,
+                revert_edits_on_cancel: None,
                 model_parameters: Vec::new(),
                 preferred_completion_mode: None,
                 enable_feedback: None,
                 play_sound_when_agent_done: None,
+                redact_terminal_output: None,
+                terminal_redaction_patterns: Vec::new(),
+                dictation: None,
+                scheduled_tasks: Vec::new(),
+                warm_up_connections: None,
             },
             None => AgentSettingsContentV2::default(),
         }
@@ -557,12 +655,47 @@ impl AgentSettingsContent {
                             )
                         })
                         .collect(),
+                    gather_context: Some(profile.gather_context),
                 },
             );
 
             Ok(())
         })
     }
+
+    pub fn set_profile_gather_context(
+        &mut self,
+        profile_id: AgentProfileId,
+        profile: AgentProfile,
+        gather_context: bool,
+    ) -> Result<()> {
+        self.v2_setting(|settings| {
+            let profiles = settings.profiles.get_or_insert_default();
+            let profile = profiles
+                .entry(profile_id)
+                .or_insert_with(|| AgentProfileContent {
+                    name: profile.name.into(),
+                    tools: profile.tools,
+                    enable_all_context_servers: Some(profile.enable_all_context_servers),
+                    context_servers: profile
+                        .context_servers
+                        .into_iter()
+                        .map(|(server_id, preset)| {
+                            (
+                                server_id,
+                                ContextServerPresetContent {
+                                    tools: preset.tools,
+                                },
+                            )
+                        })
+                        .collect(),
+                    gather_context: None,
+                });
+            profile.gather_context = Some(gather_context);
+
+            Ok(())
+        })
+    }
 }
 
 #[derive(Clone, Serialize, Deserialize, JsonSchema, Debug)]
@@ -594,11 +727,19 @@ impl Default for VersionedAgentSettingsContent {
             always_allow_tool_actions: None,
             notify_when_agent_waiting: None,
             stream_edits: None,
+            edit_matching_mode: None,
+            edit_matching_min_confidence: None,
             single_file_review: None,
+            revert_edits_on_cancel: None,
             model_parameters: Vec::new(),
             preferred_completion_mode: None,
             enable_feedback: None,
             play_sound_when_agent_done: None,
+            redact_terminal_output: None,
+            terminal_redaction_patterns: Vec::new(),
+            dictation: None,
+            scheduled_tasks: Vec::new(),
+            warm_up_connections: None,
         })
     }
 }
@@ -663,10 +804,24 @@ pub struct AgentSettingsContentV2 {
     ///
     /// Default: false
     stream_edits: Option<bool>,
+    /// How closely the text the agent wants to edit must match the file's content.
+    ///
+    /// Default: fuzzy
+    edit_matching_mode: Option<EditMatchingMode>,
+    /// The fraction of the lines the agent wants to edit that must match the file's content for
+    /// the edit to be applied, between 0 and 1. Values outside that range are clamped to it.
+    ///
+    /// Default: 0.8
+    edit_matching_min_confidence: Option<f32>,
     /// Whether to display agent edits in single-file editors in addition to the review multibuffer pane.
     ///
     /// Default: true
     single_file_review: Option<bool>,
+    /// What to do with the edits the agent made since the last message when you cancel its
+    /// response.
+    ///
+    /// Default: ask
+    revert_edits_on_cancel: Option<RevertEditsOnCancel>,
     /// Additional parameters for language model requests. When making a request
     /// to a model, parameters will be taken from the last entry in this list
     /// that matches the model's provider and name. In each entry, both provider
@@ -684,6 +839,33 @@ pub struct AgentSettingsContentV2 {
     ///
     /// Default: true
     enable_feedback: Option<bool>,
+    /// Whether to mask secrets in terminal output before it is sent to the model. This
+    /// covers the values of environment variables whose names look sensitive, such as
+    /// `GITHUB_TOKEN`, and well-known credential formats like AWS access keys.
+    ///
+    /// Default: true
+    redact_terminal_output: Option<bool>,
+    /// Additional regular expressions whose matches are masked in terminal output
+    /// before it is sent to the model.
+    ///
+    /// Default: []
+    #[serde(default)]
+    terminal_redaction_patterns: Vec<String>,
+    /// Where speech dictated into the agent panel's message editor is transcribed.
+    ///
+    /// Default: {"backend": "provider", "provider": "openai"}
+    dictation: Option<DictationBackend>,
+    /// Prompts that are sent to the agent on a recurring schedule, in a new thread.
+    ///
+    /// Default: []
+    #[serde(default)]
+    scheduled_tasks: Vec<ScheduledTask>,
+    /// Whether to connect to the current model's provider when the agent panel is focused, so
+    /// that the first request after a while doesn't have to wait for the connection to be
+    /// established.
+    ///
+    /// Default: false
+    warm_up_connections: Option<bool>,
 }
 
 #[derive(Clone, Copy, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Default)]
@@ -769,6 +951,9 @@ pub struct AgentProfileContent {
     pub enable_all_context_servers: Option<bool>,
     #[serde(default)]
     pub context_servers: IndexMap<Arc<str>, ContextServerPresetContent>,
+    /// Whether to attach the files and symbols mentioned in the first message
+    /// of a thread before it is sent to the model.
+    pub gather_context: Option<bool>,
 }
 
 #[derive(Debug, PartialEq, Clone, Default, Serialize, Deserialize, JsonSchema)]
@@ -891,7 +1076,19 @@ impl Settings for AgentSettings {
                 value.play_sound_when_agent_done,
             );
             merge(&mut settings.stream_edits, value.stream_edits);
+            merge(&mut settings.edit_matching_mode, value.edit_matching_mode);
+            merge(
+                &mut settings.edit_matching_min_confidence,
+                value
+                    .edit_matching_min_confidence
+                    .filter(|confidence| !confidence.is_nan())
+                    .map(|confidence| confidence.clamp(0., 1.)),
+            );
             merge(&mut settings.single_file_review, value.single_file_review);
+            merge(
+                &mut settings.revert_edits_on_cancel,
+                value.revert_edits_on_cancel,
+            );
             merge(&mut settings.default_profile, value.default_profile);
             merge(&mut settings.default_view, value.default_view);
             merge(
@@ -899,10 +1096,22 @@ impl Settings for AgentSettings {
                 value.preferred_completion_mode,
             );
             merge(&mut settings.enable_feedback, value.enable_feedback);
+            merge(
+                &mut settings.redact_terminal_output,
+                value.redact_terminal_output,
+            );
+            merge(&mut settings.dictation, value.dictation);
+            merge(&mut settings.warm_up_connections, value.warm_up_connections);
 
             settings
                 .model_parameters
                 .extend_from_slice(&value.model_parameters);
+            settings
+                .terminal_redaction_patterns
+                .extend_from_slice(&value.terminal_redaction_patterns);
+            settings
+                .scheduled_tasks
+                .extend_from_slice(&value.scheduled_tasks);
 
             if let Some(profiles) = value.profiles {
                 settings
@@ -928,6 +1137,7 @@ impl Settings for AgentSettings {
                                         )
                                     })
                                     .collect(),
+                                gather_context: profile.gather_context.unwrap_or_default(),
                             },
                         )
                     }));
@@ -1032,10 +1242,18 @@ mod tests {
                             play_sound_when_agent_done: None,
                             notify_when_agent_waiting: None,
                             stream_edits: None,
+                            edit_matching_mode: None,
+                            edit_matching_min_confidence: None,
                             single_file_review: None,
+                            revert_edits_on_cancel: None,
                             enable_feedback: None,
                             model_parameters: Vec::new(),
                             preferred_completion_mode: None,
+                            redact_terminal_output: None,
+                            terminal_redaction_patterns: Vec::new(),
+                            dictation: None,
+                            scheduled_tasks: Vec::new(),
+                            warm_up_connections: None,
                         })),
                     }
                 },
@@ -1058,6 +1276,30 @@ mod tests {
         assert!(!agent_settings.agent.is_version_outdated());
     }
 
+    #[gpui::test]
+    fn test_edit_matching_min_confidence_is_clamped(cx: &mut gpui::App) {
+        let mut test_settings = settings::SettingsStore::test(cx);
+        test_settings.register_setting::<AgentSettings>(cx);
+
+        for (value, expected) in [("-0.5", 0.), ("0.6", 0.6), ("1.5", 1.)] {
+            test_settings
+                .set_user_settings(
+                    &format!(
+                        r#"{{"agent": {{"version": "2", "edit_matching_min_confidence": {value}}}}}"#
+                    ),
+                    cx,
+                )
+                .unwrap();
+            assert_eq!(
+                test_settings
+                    .get::<AgentSettings>(None)
+                    .edit_matching_min_confidence,
+                expected,
+                "for {value}"
+            );
+        }
+    }
+
     #[gpui::test]
     async fn test_load_settings_from_old_key(cx: &mut TestAppContext) {
         let fs = fs::FakeFs::new(cx.executor().clone());
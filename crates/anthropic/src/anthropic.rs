@@ -4,7 +4,9 @@ use anyhow::{Context as _, Result, anyhow};
 use chrono::{DateTime, Utc};
 use futures::{AsyncBufReadExt, AsyncReadExt, StreamExt, io::BufReader, stream::BoxStream};
 use http_client::http::{HeaderMap, HeaderValue};
-use http_client::{AsyncBody, HttpClient, Method, Request as HttpRequest};
+use http_client::{
+    AsyncBody, HttpClient, HttpRequestExt as _, Method, Request as HttpRequest, RequestPriority,
+};
 use serde::{Deserialize, Serialize};
 use strum::{EnumIter, EnumString};
 use thiserror::Error;
@@ -467,6 +469,7 @@ pub async fn stream_completion_with_rate_limit_info(
     let request_builder = HttpRequest::builder()
         .method(Method::POST)
         .uri(uri)
+        .priority(RequestPriority::Interactive)
         .header("Anthropic-Version", "2023-06-01")
         .header("Anthropic-Beta", beta_headers)
         .header("X-Api-Key", api_key)
@@ -2280,6 +2280,7 @@ impl AssistantContext {
             tool_choice: None,
             stop: Vec::new(),
             temperature: model.and_then(|model| AgentSettings::temperature_for_model(model, cx)),
+            response_schema: None,
         };
         for message in self.messages(cx) {
             if message.status != MessageStatus::Done {
@@ -1,11 +1,12 @@
 use anyhow::{Context as _, Result};
-use buffer_diff::BufferDiff;
+use buffer_diff::{BufferDiff, DiffHunkStatusKind};
 use collections::BTreeMap;
 use futures::{FutureExt, StreamExt, channel::mpsc};
 use gpui::{App, AppContext, AsyncApp, Context, Entity, Subscription, Task, WeakEntity};
-use language::{Anchor, Buffer, BufferEvent, DiskState, Point, ToPoint};
+use language::{Anchor, Buffer, BufferEvent, Capability, DiskState, Point, ToPoint};
 use project::{Project, ProjectItem, lsp_store::OpenLspBufferHandle};
-use std::{cmp, ops::Range, sync::Arc};
+use serde::{Deserialize, Serialize};
+use std::{cmp, ops::Range, path::PathBuf, sync::Arc};
 use text::{Edit, Patch, Rope};
 use util::RangeExt;
 
@@ -17,6 +18,15 @@ pub struct ActionLog {
     edited_since_project_diagnostics_check: bool,
     /// The project this action log is associated with
     project: Entity<Project>,
+    /// The state of each buffer before the current turn first touched it, so the turn's edits
+    /// can be reverted. `None` outside of a turn.
+    turn_start_states: Option<BTreeMap<Entity<Buffer>, TurnStartState>>,
+    /// Whether edits should be planned instead of being made to the project's buffers.
+    plan_only: bool,
+    /// The planned edits to each of the project's buffers.
+    planned_buffers: BTreeMap<Entity<Buffer>, PlannedBuffer>,
+    /// The agent edits to each buffer that the user kept or rejected, oldest first.
+    reviewed_edits: BTreeMap<Entity<Buffer>, Vec<ReviewedEdit>>,
 }
 
 impl ActionLog {
@@ -26,6 +36,10 @@ impl ActionLog {
             tracked_buffers: BTreeMap::default(),
             edited_since_project_diagnostics_check: false,
             project,
+            turn_start_states: None,
+            plan_only: false,
+            planned_buffers: BTreeMap::default(),
+            reviewed_edits: BTreeMap::default(),
         }
     }
 
@@ -49,6 +63,24 @@ impl ActionLog {
         is_created: bool,
         cx: &mut Context<Self>,
     ) -> &mut TrackedBuffer {
+        if let Some(turn_start_states) = &mut self.turn_start_states {
+            if !turn_start_states.contains_key(&buffer) {
+                let buffer_ref = buffer.read(cx);
+                turn_start_states.insert(
+                    buffer.clone(),
+                    TurnStartState {
+                        base: buffer_ref.as_rope().clone(),
+                        agent_edits: Patch::default(),
+                        snapshot: buffer_ref.text_snapshot(),
+                        file_existed: buffer_ref
+                            .file()
+                            .map_or(false, |file| file.disk_state().exists()),
+                        was_tracked: self.tracked_buffers.contains_key(&buffer),
+                    },
+                );
+            }
+        }
+
         let status = if is_created {
             if let Some(tracked) = self.tracked_buffers.remove(&buffer) {
                 match tracked.status {
@@ -107,6 +139,7 @@ impl ActionLog {
                     buffer: buffer.clone(),
                     diff_base,
                     unreviewed_edits: unreviewed_edits,
+                    fuzzy_edits: Vec::new(),
                     snapshot: text_snapshot.clone(),
                     status,
                     version: buffer.read(cx).version(),
@@ -256,6 +289,22 @@ impl ActionLog {
                 .tracked_buffers
                 .get(buffer)
                 .context("buffer not tracked")?;
+            let turn_start_state = this
+                .turn_start_states
+                .as_ref()
+                .and_then(|states| states.get(buffer))
+                .filter(|state| {
+                    buffer_snapshot
+                        .version()
+                        .changed_since(&state.snapshot.version)
+                })
+                .map(|state| {
+                    (
+                        state.base.clone(),
+                        state.agent_edits.clone(),
+                        state.snapshot.clone(),
+                    )
+                });
 
             let rebase = cx.background_spawn({
                 let mut base_text = tracked_buffer.diff_base.clone();
@@ -272,13 +321,45 @@ impl ActionLog {
                             new_snapshot.as_rope(),
                         );
                     }
-                    (Arc::new(base_text.to_string()), base_text)
+
+                    // The turn is rebased separately from the review, because it starts from
+                    // the buffer as it was when the turn first touched it.
+                    let turn =
+                        turn_start_state.map(|(mut turn_base, agent_edits, turn_snapshot)| {
+                            if let ChangeAuthor::User = author {
+                                apply_non_conflicting_edits(
+                                    &agent_edits,
+                                    diff_snapshots(&turn_snapshot, &new_snapshot),
+                                    &mut turn_base,
+                                    new_snapshot.as_rope(),
+                                );
+                            }
+                            let agent_edits = row_patch(&turn_base, new_snapshot.as_rope());
+                            (turn_snapshot.version, turn_base, agent_edits)
+                        });
+
+                    (Arc::new(base_text.to_string()), base_text, turn)
                 }
             });
 
             anyhow::Ok(rebase)
         })??;
-        let (new_base_text, new_diff_base) = rebase.await;
+        let (new_base_text, new_diff_base, turn) = rebase.await;
+        if let Some((old_version, turn_base, agent_edits)) = turn {
+            this.update(cx, |this, _| {
+                // Only apply the rebase if the turn didn't change while it was computed.
+                if let Some(state) = this
+                    .turn_start_states
+                    .as_mut()
+                    .and_then(|states| states.get_mut(buffer))
+                    .filter(|state| state.snapshot.version == old_version)
+                {
+                    state.base = turn_base;
+                    state.agent_edits = agent_edits;
+                    state.snapshot = buffer_snapshot.clone();
+                }
+            })?;
+        }
         Self::update_diff(
             this,
             buffer,
@@ -458,17 +539,29 @@ impl ActionLog {
 
     /// Track a buffer as read, so we can notify the model about user edits.
     pub fn buffer_read(&mut self, buffer: Entity<Buffer>, cx: &mut Context<Self>) {
+        if self.is_planned(&buffer) {
+            return;
+        }
         self.track_buffer_internal(buffer, false, cx);
     }
 
     /// Mark a buffer as edited, so we can refresh it in the context
     pub fn buffer_created(&mut self, buffer: Entity<Buffer>, cx: &mut Context<Self>) {
+        if let Some(planned_buffer) = self.planned_buffer_mut(&buffer) {
+            planned_buffer.is_created = true;
+            self.update_planned_diff(buffer, cx);
+            return;
+        }
         self.edited_since_project_diagnostics_check = true;
         self.track_buffer_internal(buffer.clone(), true, cx);
     }
 
     /// Mark a buffer as edited, so we can refresh it in the context
     pub fn buffer_edited(&mut self, buffer: Entity<Buffer>, cx: &mut Context<Self>) {
+        if self.is_planned(&buffer) {
+            self.update_planned_diff(buffer, cx);
+            return;
+        }
         self.edited_since_project_diagnostics_check = true;
 
         let tracked_buffer = self.track_buffer_internal(buffer.clone(), false, cx);
@@ -478,6 +571,29 @@ impl ActionLog {
         tracked_buffer.schedule_diff_update(ChangeAuthor::Agent, cx);
     }
 
+    /// Records an edit whose old text only approximately matched the buffer, so that it can be
+    /// pointed out during review.
+    pub fn record_fuzzy_edit(
+        &mut self,
+        buffer: Entity<Buffer>,
+        range: Range<Anchor>,
+        cx: &mut Context<Self>,
+    ) {
+        if self.is_planned(&buffer) {
+            return;
+        }
+        let tracked_buffer = self.track_buffer_internal(buffer, false, cx);
+        tracked_buffer.fuzzy_edits.push(range);
+        cx.notify();
+    }
+
+    /// Returns the ranges of the unreviewed edits in `buffer` that were applied with fuzzy matching.
+    pub fn fuzzy_edits(&self, buffer: &Entity<Buffer>) -> &[Range<Anchor>] {
+        self.tracked_buffers
+            .get(buffer)
+            .map_or(&[], |tracked_buffer| &tracked_buffer.fuzzy_edits)
+    }
+
     pub fn will_delete_buffer(&mut self, buffer: Entity<Buffer>, cx: &mut Context<Self>) {
         let tracked_buffer = self.track_buffer_internal(buffer.clone(), false, cx);
         match tracked_buffer.status {
@@ -511,9 +627,15 @@ impl ActionLog {
                 cx.notify();
             }
             _ => {
+                let mut kept_edits = Vec::new();
+                let buffer_handle = buffer.clone();
                 let buffer = buffer.read(cx);
                 let buffer_range =
                     buffer_range.start.to_point(buffer)..buffer_range.end.to_point(buffer);
+                tracked_buffer.fuzzy_edits.retain(|range| {
+                    buffer_range.end.row < range.start.to_point(buffer).row
+                        || buffer_range.start.row > range.end.to_point(buffer).row
+                });
                 let mut delta = 0i32;
 
                 tracked_buffer.unreviewed_edits.retain_mut(|edit| {
@@ -539,6 +661,15 @@ impl ActionLog {
                                 Point::new(edit.new.end, 0),
                                 tracked_buffer.snapshot.max_point(),
                             ));
+                        kept_edits.push(ReviewedEdit {
+                            outcome: ReviewOutcome::Kept,
+                            range: tracked_buffer.snapshot.anchor_before(new_range.start)
+                                ..tracked_buffer.snapshot.anchor_after(new_range.end),
+                            other_text: tracked_buffer
+                                .diff_base
+                                .chunks_in_range(old_range.clone())
+                                .collect(),
+                        });
                         tracked_buffer.diff_base.replace(
                             old_range,
                             &tracked_buffer
@@ -551,10 +682,24 @@ impl ActionLog {
                     }
                 });
                 tracked_buffer.schedule_diff_update(ChangeAuthor::User, cx);
+                self.record_reviewed_edits(buffer_handle, kept_edits);
             }
         }
     }
 
+    fn record_reviewed_edits(&mut self, buffer: Entity<Buffer>, edits: Vec<ReviewedEdit>) {
+        if !edits.is_empty() {
+            self.reviewed_edits.entry(buffer).or_default().extend(edits);
+        }
+    }
+
+    /// The agent edits that the user kept or rejected, grouped by buffer.
+    pub fn reviewed_edits(&self) -> impl Iterator<Item = (&Entity<Buffer>, &[ReviewedEdit])> {
+        self.reviewed_edits
+            .iter()
+            .map(|(buffer, edits)| (buffer, edits.as_slice()))
+    }
+
     pub fn reject_edits_in_ranges(
         &mut self,
         buffer: Entity<Buffer>,
@@ -634,13 +779,23 @@ impl ActionLog {
                 save
             }
             TrackedBufferStatus::Modified => {
+                let mut rejected_edits = Vec::new();
                 buffer.update(cx, |buffer, cx| {
-                    let mut buffer_row_ranges = buffer_ranges
+                    let buffer_row_ranges = buffer_ranges
                         .into_iter()
                         .map(|range| {
                             range.start.to_point(buffer).row..range.end.to_point(buffer).row
                         })
-                        .peekable();
+                        .collect::<Vec<_>>();
+                    tracked_buffer.fuzzy_edits.retain(|range| {
+                        let row_range =
+                            range.start.to_point(buffer).row..range.end.to_point(buffer).row;
+                        !buffer_row_ranges.iter().any(|buffer_row_range| {
+                            buffer_row_range.start <= row_range.end
+                                && buffer_row_range.end >= row_range.start
+                        })
+                    });
+                    let mut buffer_row_ranges = buffer_row_ranges.into_iter().peekable();
 
                     let mut edits_to_revert = Vec::new();
                     for edit in tracked_buffer.unreviewed_edits.edits() {
@@ -678,24 +833,102 @@ impl ActionLog {
                                 .diff_base
                                 .chunks_in_range(old_range)
                                 .collect::<String>();
+                            rejected_edits.push(ReviewedEdit {
+                                outcome: ReviewOutcome::Rejected,
+                                range: new_range.clone(),
+                                other_text: buffer.text_for_range(new_range.clone()).collect(),
+                            });
                             edits_to_revert.push((new_range, old_text));
                         }
                     }
 
                     buffer.edit(edits_to_revert, None, cx);
                 });
+                self.record_reviewed_edits(buffer.clone(), rejected_edits);
                 self.project
                     .update(cx, |project, cx| project.save_buffer(buffer, cx))
             }
         }
     }
 
+    /// Starts a new turn, whose edits can be reverted with [`Self::revert_turn`].
+    pub fn start_turn(&mut self) {
+        self.turn_start_states = Some(BTreeMap::default());
+    }
+
+    /// Returns true if the agent changed any buffer in the current turn.
+    pub fn has_edits_in_turn(&self, _cx: &App) -> bool {
+        self.turn_start_states.as_ref().map_or(false, |states| {
+            states.values().any(|state| !state.agent_edits.is_empty())
+        })
+    }
+
+    /// Undoes the agent's edits in the current turn, keeping any edits the user made in the
+    /// meantime, and ends the turn.
+    pub fn revert_turn(&mut self, cx: &mut Context<Self>) -> Task<Result<()>> {
+        let Some(turn_start_states) = self.turn_start_states.take() else {
+            return Task::ready(Ok(()));
+        };
+
+        let mut tasks = Vec::new();
+        for (buffer, mut state) in turn_start_states {
+            if state.agent_edits.is_empty() {
+                continue;
+            }
+            // Edits that haven't been attributed yet are kept, since they may be the user's.
+            let snapshot = buffer.read(cx).text_snapshot();
+            if snapshot.version().changed_since(&state.snapshot.version) {
+                apply_non_conflicting_edits(
+                    &state.agent_edits,
+                    diff_snapshots(&state.snapshot, &snapshot),
+                    &mut state.base,
+                    snapshot.as_rope(),
+                );
+            }
+            if !state.was_tracked {
+                self.tracked_buffers.remove(&buffer);
+            }
+
+            if !state.file_existed && state.base.is_empty() {
+                // The file was created in this turn, so delete it.
+                if let Some(delete) = buffer.read(cx).entry_id(cx).and_then(|entry_id| {
+                    self.project
+                        .update(cx, |project, cx| project.delete_entry(entry_id, false, cx))
+                }) {
+                    tasks.push(delete);
+                }
+                continue;
+            }
+
+            let project = self.project.clone();
+            let diff = buffer.read(cx).diff(state.base.to_string(), cx);
+            tasks.push(cx.spawn(async move |_, cx| {
+                let diff = diff.await;
+                buffer.update(cx, |buffer, cx| {
+                    buffer.apply_diff(diff, cx);
+                })?;
+                project
+                    .update(cx, |project, cx| project.save_buffer(buffer, cx))?
+                    .await
+            }));
+        }
+        cx.notify();
+
+        cx.background_spawn(async move {
+            for result in futures::future::join_all(tasks).await {
+                result?;
+            }
+            Ok(())
+        })
+    }
+
     pub fn keep_all_edits(&mut self, cx: &mut Context<Self>) {
         self.tracked_buffers
             .retain(|_buffer, tracked_buffer| match tracked_buffer.status {
                 TrackedBufferStatus::Deleted => false,
                 _ => {
                     tracked_buffer.unreviewed_edits.clear();
+                    tracked_buffer.fuzzy_edits.clear();
                     tracked_buffer.diff_base = tracked_buffer.snapshot.as_rope().clone();
                     tracked_buffer.schedule_diff_update(ChangeAuthor::User, cx);
                     true
@@ -704,6 +937,173 @@ impl ActionLog {
         cx.notify();
     }
 
+    /// Sets whether edits made from now on are planned rather than made to the project's buffers.
+    pub fn set_plan_only(&mut self, plan_only: bool) {
+        self.plan_only = plan_only;
+    }
+
+    pub fn is_plan_only(&self) -> bool {
+        self.plan_only
+    }
+
+    /// Returns the buffer a tool should make its edits to. While planning, that's a copy of
+    /// `buffer` that collects the planned edits until they're applied with [`Self::apply_plan`].
+    pub fn buffer_to_edit(
+        &mut self,
+        buffer: Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Entity<Buffer> {
+        if !self.plan_only || self.is_planned(&buffer) {
+            return buffer;
+        }
+        if let Some(planned_buffer) = self.planned_buffers.get(&buffer) {
+            return planned_buffer.buffer.clone();
+        }
+
+        let base = buffer.read(cx).text_snapshot();
+        let planned = cx.new(|cx| {
+            let source = buffer.read(cx);
+            let text = text::Buffer::new_normalized(
+                0,
+                cx.entity_id().as_non_zero_u64().into(),
+                source.line_ending(),
+                source.as_rope().clone(),
+            );
+            let language = source.language().cloned();
+            let mut planned = Buffer::build(text, source.file().cloned(), Capability::ReadWrite);
+            planned.set_language(language, cx);
+            planned
+        });
+        let diff = cx.new(|cx| BufferDiff::new(&planned.read(cx).text_snapshot(), cx));
+        self.planned_buffers.insert(
+            buffer,
+            PlannedBuffer {
+                version: planned.read(cx).version(),
+                buffer: planned.clone(),
+                base,
+                is_created: false,
+                diff,
+                _update_diff: Task::ready(()),
+            },
+        );
+        planned
+    }
+
+    /// Returns true if `buffer` is a copy that edits are planned in.
+    pub fn is_planned(&self, buffer: &Entity<Buffer>) -> bool {
+        self.planned_buffers
+            .values()
+            .any(|planned_buffer| planned_buffer.buffer == *buffer)
+    }
+
+    fn planned_buffer_mut(&mut self, buffer: &Entity<Buffer>) -> Option<&mut PlannedBuffer> {
+        self.planned_buffers
+            .values_mut()
+            .find(|planned_buffer| planned_buffer.buffer == *buffer)
+    }
+
+    fn update_planned_diff(&mut self, buffer: Entity<Buffer>, cx: &mut Context<Self>) {
+        let Some(planned_buffer) = self.planned_buffer_mut(&buffer) else {
+            return;
+        };
+
+        let diff = planned_buffer.diff.clone();
+        let base_text = Arc::new(planned_buffer.base.text());
+        let snapshot = buffer.read(cx).text_snapshot();
+        let language = buffer.read(cx).language().cloned();
+        let language_registry = buffer.read(cx).language_registry();
+        planned_buffer._update_diff = cx.spawn(async move |this, cx| {
+            let diff_snapshot = BufferDiff::update_diff(
+                diff.clone(),
+                snapshot.clone(),
+                Some(base_text),
+                true,
+                false,
+                language,
+                language_registry,
+                cx,
+            )
+            .await;
+            if let Ok(diff_snapshot) = diff_snapshot {
+                diff.update(cx, |diff, cx| {
+                    diff.set_snapshot(diff_snapshot, &snapshot, cx)
+                })
+                .ok();
+                this.update(cx, |_, cx| cx.notify()).ok();
+            }
+        });
+    }
+
+    /// Returns the planned edits, as the project buffer they apply to, the buffer they were
+    /// made in, and their diff.
+    pub fn planned_edits(&self, cx: &App) -> Vec<PlannedEdit> {
+        self.planned_buffers
+            .iter()
+            .filter(|(_, planned_buffer)| {
+                let buffer = planned_buffer.buffer.read(cx);
+                buffer.version().changed_since(&planned_buffer.version)
+            })
+            .map(|(buffer, planned_buffer)| PlannedEdit {
+                buffer: buffer.clone(),
+                planned_buffer: planned_buffer.buffer.clone(),
+                diff: planned_buffer.diff.clone(),
+            })
+            .collect()
+    }
+
+    pub fn has_planned_edits(&self, cx: &App) -> bool {
+        !self.planned_edits(cx).is_empty()
+    }
+
+    /// Forgets the planned edits without applying them.
+    pub fn discard_plan(&mut self, cx: &mut Context<Self>) {
+        self.planned_buffers.clear();
+        cx.notify();
+    }
+
+    /// Makes the planned edits to the project's buffers and saves them. The applied edits are
+    /// tracked like any other edit, so they can be reviewed afterwards.
+    pub fn apply_plan(&mut self, cx: &mut Context<Self>) -> Task<Result<()>> {
+        let mut saves = Vec::new();
+        for (buffer, planned_buffer) in std::mem::take(&mut self.planned_buffers) {
+            // The planned buffer started out with the same text as `base`, so the planned edits'
+            // offsets can be anchored in `base` to account for edits made to the buffer since.
+            let base = &planned_buffer.base;
+            let planned = planned_buffer.buffer.read(cx).text_snapshot();
+            let edits = planned
+                .edits_since::<usize>(&planned_buffer.version)
+                .map(|edit| {
+                    let range = base.anchor_before(edit.old.start)..base.anchor_after(edit.old.end);
+                    let text = planned.text_for_range(edit.new).collect::<String>();
+                    (range, text)
+                })
+                .collect::<Vec<_>>();
+            if edits.is_empty() {
+                continue;
+            }
+
+            if planned_buffer.is_created {
+                self.buffer_created(buffer.clone(), cx);
+            } else {
+                self.buffer_read(buffer.clone(), cx);
+            }
+            buffer.update(cx, |buffer, cx| buffer.edit(edits, None, cx));
+            self.buffer_edited(buffer.clone(), cx);
+            saves.push(
+                self.project
+                    .update(cx, |project, cx| project.save_buffer(buffer, cx)),
+            );
+        }
+        cx.notify();
+
+        cx.background_spawn(async move {
+            for result in futures::future::join_all(saves).await {
+                result?;
+            }
+            Ok(())
+        })
+    }
+
     /// Returns the set of buffers that contain edits that haven't been reviewed by the user.
     pub fn changed_buffers(&self, cx: &App) -> BTreeMap<Entity<Buffer>, Entity<BufferDiff>> {
         self.tracked_buffers
@@ -713,6 +1113,76 @@ impl ActionLog {
             .collect()
     }
 
+    /// Returns the hunks of every changed buffer that haven't been kept or rejected yet.
+    pub fn pending_hunks(&self, cx: &App) -> Vec<PendingHunk> {
+        let mut pending_hunks = Vec::new();
+        for (buffer, diff) in self.changed_buffers(cx) {
+            let snapshot = buffer.read(cx).snapshot();
+            let diff = diff.read(cx);
+            for hunk in diff.hunks(&snapshot, cx) {
+                pending_hunks.push(PendingHunk {
+                    buffer: buffer.clone(),
+                    range: hunk.buffer_range.clone(),
+                    status: hunk.status().kind,
+                    old_text: diff
+                        .base_text()
+                        .text_for_range(hunk.diff_base_byte_range)
+                        .collect(),
+                });
+            }
+        }
+        pending_hunks
+    }
+
+    /// Keeps the edits in each of the given ranges of `buffer`.
+    pub fn keep_edits_in_ranges(
+        &mut self,
+        buffer: Entity<Buffer>,
+        buffer_ranges: Vec<Range<impl language::ToPoint>>,
+        cx: &mut Context<Self>,
+    ) {
+        for buffer_range in buffer_ranges {
+            self.keep_edits_in_range(buffer.clone(), buffer_range, cx);
+        }
+    }
+
+    /// Returns a serializable snapshot of the edits that are pending review, for sharing the
+    /// review with other components.
+    pub fn review_state(&self, cx: &App) -> ReviewState {
+        let mut files = BTreeMap::<(u64, PathBuf), Vec<HunkReviewState>>::default();
+        for hunk in self.pending_hunks(cx) {
+            let buffer = hunk.buffer.read(cx);
+            let Some(file) = buffer.file() else {
+                continue;
+            };
+            let range = hunk.range.start.to_point(buffer)..hunk.range.end.to_point(buffer);
+            files
+                .entry((file.worktree_id(cx).to_proto(), file.path().to_path_buf()))
+                .or_default()
+                .push(HunkReviewState {
+                    rows: range.start.row..range.end.row,
+                    kind: match hunk.status {
+                        DiffHunkStatusKind::Added => HunkReviewKind::Added,
+                        DiffHunkStatusKind::Modified => HunkReviewKind::Modified,
+                        DiffHunkStatusKind::Deleted => HunkReviewKind::Deleted,
+                    },
+                    old_text: hunk.old_text,
+                    new_text: buffer.text_for_range(range).collect(),
+                });
+        }
+        ReviewState {
+            files: files
+                .into_iter()
+                .map(|((worktree_id, path), hunks)| FileReviewState {
+                    worktree_id,
+                    path,
+                    reviewed: false,
+                    hunks,
+                })
+                .collect(),
+        }
+    }
+
     /// Iterate over buffers changed since last read or edited by the model
     pub fn stale_buffers<'a>(&'a self, cx: &'a App) -> impl Iterator<Item = &'a Entity<Buffer>> {
         self.tracked_buffers
@@ -792,6 +1262,15 @@ fn apply_non_conflicting_edits(
     }
 }
 
+fn row_patch(old_text: &Rope, new_text: &Rope) -> Patch<u32> {
+    Patch::new(
+        language::line_diff(&old_text.to_string(), &new_text.to_string())
+            .into_iter()
+            .map(|(old, new)| Edit { old, new })
+            .collect(),
+    )
+}
+
 fn diff_snapshots(
     old_snapshot: &text::BufferSnapshot,
     new_snapshot: &text::BufferSnapshot,
@@ -841,6 +1320,18 @@ fn point_to_row_edit(edit: Edit<Point>, old_text: &Rope, new_text: &Rope) -> Edi
     }
 }
 
+struct TurnStartState {
+    /// The buffer's text before the turn first touched it, with the user's edits since then
+    /// applied to it.
+    base: Rope,
+    /// The agent's edits in this turn, from `base` to `snapshot`.
+    agent_edits: Patch<u32>,
+    /// The snapshot of the buffer that `base` and `agent_edits` are up to date with.
+    snapshot: text::BufferSnapshot,
+    file_existed: bool,
+    was_tracked: bool,
+}
+
 #[derive(Copy, Clone, Debug)]
 enum ChangeAuthor {
     User,
@@ -857,6 +1348,8 @@ struct TrackedBuffer {
     buffer: Entity<Buffer>,
     diff_base: Rope,
     unreviewed_edits: Patch<u32>,
+    /// Ranges of agent edits whose old text was located with fuzzy matching.
+    fuzzy_edits: Vec<Range<Anchor>>,
     status: TrackedBufferStatus,
     version: clock::Global,
     diff: Entity<BufferDiff>,
@@ -883,14 +1376,94 @@ impl TrackedBuffer {
     }
 }
 
+/// The edits planned for one of the project's buffers.
+struct PlannedBuffer {
+    /// The copy of the project's buffer that the edits are made in.
+    buffer: Entity<Buffer>,
+    /// The project's buffer as it was when planning started.
+    base: text::BufferSnapshot,
+    /// The version of the planned buffer before any edits were made to it.
+    version: clock::Global,
+    is_created: bool,
+    diff: Entity<BufferDiff>,
+    _update_diff: Task<()>,
+}
+
+pub struct PlannedEdit {
+    pub buffer: Entity<Buffer>,
+    pub planned_buffer: Entity<Buffer>,
+    pub diff: Entity<BufferDiff>,
+}
+
 pub struct ChangedBuffer {
     pub diff: Entity<BufferDiff>,
 }
 
+/// An agent edit that the user hasn't kept or rejected yet.
+#[derive(Clone, Debug)]
+pub struct PendingHunk {
+    pub buffer: Entity<Buffer>,
+    pub range: Range<Anchor>,
+    pub status: DiffHunkStatusKind,
+    /// The text that the hunk replaced.
+    pub old_text: String,
+}
+
+/// An agent edit that the user kept or rejected.
+#[derive(Clone, Debug)]
+pub struct ReviewedEdit {
+    pub outcome: ReviewOutcome,
+    /// The edit's text in the buffer if it was kept, or the text that was restored if it
+    /// was rejected.
+    pub range: Range<Anchor>,
+    /// The text on the other side of the decision: the text a kept edit replaced, or the
+    /// agent's text that was rejected.
+    pub other_text: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReviewOutcome {
+    Kept,
+    Rejected,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReviewState {
+    pub files: Vec<FileReviewState>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileReviewState {
+    /// The worktree containing the file, as given by `WorktreeId::to_proto`.
+    pub worktree_id: u64,
+    /// The path of the file relative to its worktree.
+    pub path: PathBuf,
+    /// Whether the user has marked the whole file as reviewed.
+    #[serde(default)]
+    pub reviewed: bool,
+    pub hunks: Vec<HunkReviewState>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HunkReviewState {
+    /// The rows the hunk spans in the current contents of the file.
+    pub rows: Range<u32>,
+    pub kind: HunkReviewKind,
+    pub old_text: String,
+    pub new_text: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HunkReviewKind {
+    Added,
+    Modified,
+    Deleted,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use buffer_diff::DiffHunkStatusKind;
     use gpui::TestAppContext;
     use language::Point;
     use project::{FakeFs, Fs, Project, RemoveOptions};
@@ -992,6 +1565,80 @@ mod tests {
         assert_eq!(unreviewed_hunks(&action_log, cx), vec![]);
     }
 
+    #[gpui::test]
+    async fn test_fuzzy_edits(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/dir"), json!({"file": "abc\ndef\nghi\njkl\nmno"}))
+            .await;
+        let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+        let action_log = cx.new(|_| ActionLog::new(project.clone()));
+        let file_path = project
+            .read_with(cx, |project, cx| project.find_project_path("dir/file", cx))
+            .unwrap();
+        let buffer = project
+            .update(cx, |project, cx| project.open_buffer(file_path, cx))
+            .await
+            .unwrap();
+
+        cx.update(|cx| {
+            action_log.update(cx, |log, cx| log.buffer_read(buffer.clone(), cx));
+            buffer.update(cx, |buffer, cx| {
+                buffer
+                    .edit([(Point::new(1, 1)..Point::new(1, 2), "E")], None, cx)
+                    .unwrap();
+                buffer
+                    .edit([(Point::new(4, 2)..Point::new(4, 3), "O")], None, cx)
+                    .unwrap();
+            });
+            action_log.update(cx, |log, cx| {
+                log.buffer_edited(buffer.clone(), cx);
+                let snapshot = buffer.read(cx).snapshot();
+                for range in [
+                    Point::new(1, 0)..Point::new(1, 3),
+                    Point::new(4, 0)..Point::new(4, 3),
+                ] {
+                    let range =
+                        snapshot.anchor_before(range.start)..snapshot.anchor_after(range.end);
+                    log.record_fuzzy_edit(buffer.clone(), range, cx);
+                }
+            });
+        });
+        cx.run_until_parked();
+        let fuzzy_rows = |cx: &mut TestAppContext| {
+            cx.read(|cx| {
+                let snapshot = buffer.read(cx).snapshot();
+                action_log
+                    .read(cx)
+                    .fuzzy_edits(&buffer)
+                    .iter()
+                    .map(|range| range.start.to_point(&snapshot).row)
+                    .collect::<Vec<_>>()
+            })
+        };
+        assert_eq!(fuzzy_rows(cx), vec![1, 4]);
+
+        action_log.update(cx, |log, cx| {
+            log.keep_edits_in_range(buffer.clone(), Point::new(3, 0)..Point::new(4, 3), cx)
+        });
+        cx.run_until_parked();
+        assert_eq!(fuzzy_rows(cx), vec![1]);
+
+        action_log
+            .update(cx, |log, cx| {
+                log.reject_edits_in_ranges(
+                    buffer.clone(),
+                    vec![Point::new(1, 0)..Point::new(1, 0)],
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        assert_eq!(fuzzy_rows(cx), Vec::<u32>::new());
+    }
+
     #[gpui::test(iterations = 10)]
     async fn test_deletions(cx: &mut TestAppContext) {
         init_test(cx);
@@ -1803,6 +2450,230 @@ mod tests {
         assert_eq!(unreviewed_hunks(&action_log, cx), vec![]);
     }
 
+    #[gpui::test]
+    async fn test_revert_turn(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/dir"), json!({"file": "abc\ndef\nghi"}))
+            .await;
+        let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+        let action_log = cx.new(|_| ActionLog::new(project.clone()));
+        let file_path = project
+            .read_with(cx, |project, cx| project.find_project_path("dir/file", cx))
+            .unwrap();
+        let buffer = project
+            .update(cx, |project, cx| project.open_buffer(file_path, cx))
+            .await
+            .unwrap();
+        let new_file_path = project
+            .read_with(cx, |project, cx| {
+                project.find_project_path("dir/new_file", cx)
+            })
+            .unwrap();
+        let new_buffer = project
+            .update(cx, |project, cx| project.open_buffer(new_file_path, cx))
+            .await
+            .unwrap();
+
+        // Edits from an earlier turn aren't reverted.
+        cx.update(|cx| {
+            action_log.update(cx, |log, cx| {
+                log.start_turn();
+                log.buffer_read(buffer.clone(), cx);
+            });
+            buffer.update(cx, |buffer, cx| {
+                buffer.edit([(Point::new(0, 0)..Point::new(0, 3), "ABC")], None, cx)
+            });
+            action_log.update(cx, |log, cx| log.buffer_edited(buffer.clone(), cx));
+        });
+        cx.run_until_parked();
+
+        cx.update(|cx| {
+            action_log.update(cx, |log, cx| {
+                log.start_turn();
+                log.buffer_read(buffer.clone(), cx);
+            });
+            buffer.update(cx, |buffer, cx| {
+                buffer.edit([(Point::new(2, 0)..Point::new(2, 3), "GHI")], None, cx)
+            });
+            action_log.update(cx, |log, cx| {
+                log.buffer_edited(buffer.clone(), cx);
+                log.buffer_created(new_buffer.clone(), cx);
+            });
+            new_buffer.update(cx, |buffer, cx| buffer.set_text("content", cx));
+            action_log.update(cx, |log, cx| log.buffer_edited(new_buffer.clone(), cx));
+        });
+        project
+            .update(cx, |project, cx| {
+                project.save_buffer(new_buffer.clone(), cx)
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        assert!(action_log.read_with(cx, |log, cx| log.has_edits_in_turn(cx)));
+
+        action_log
+            .update(cx, |log, cx| log.revert_turn(cx))
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        assert_eq!(
+            buffer.read_with(cx, |buffer, _| buffer.text()),
+            "ABC\ndef\nghi"
+        );
+        assert_eq!(
+            fs.load(path!("/dir/file").as_ref()).await.unwrap(),
+            "ABC\ndef\nghi"
+        );
+        assert!(!fs.is_file(path!("/dir/new_file").as_ref()).await);
+        assert!(!action_log.read_with(cx, |log, cx| log.has_edits_in_turn(cx)));
+        assert_eq!(
+            unreviewed_hunks(&action_log, cx),
+            vec![(
+                buffer.clone(),
+                vec![HunkStatus {
+                    range: Point::new(0, 0)..Point::new(1, 0),
+                    diff_status: DiffHunkStatusKind::Modified,
+                    old_text: "abc\n".into(),
+                }],
+            )]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_revert_turn_keeps_user_edits(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/dir"), json!({"file": "abc\ndef\nghi"}))
+            .await;
+        let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+        let action_log = cx.new(|_| ActionLog::new(project.clone()));
+        let file_path = project
+            .read_with(cx, |project, cx| project.find_project_path("dir/file", cx))
+            .unwrap();
+        let buffer = project
+            .update(cx, |project, cx| project.open_buffer(file_path, cx))
+            .await
+            .unwrap();
+
+        // The user's edits alone don't count as edits in the turn.
+        cx.update(|cx| {
+            action_log.update(cx, |log, cx| {
+                log.start_turn();
+                log.buffer_read(buffer.clone(), cx);
+            });
+        });
+        buffer.update(cx, |buffer, cx| {
+            buffer.edit([(Point::new(1, 0)..Point::new(1, 3), "DEF")], None, cx)
+        });
+        cx.run_until_parked();
+        assert!(!action_log.read_with(cx, |log, cx| log.has_edits_in_turn(cx)));
+
+        cx.update(|cx| {
+            buffer.update(cx, |buffer, cx| {
+                buffer.edit([(Point::new(0, 0)..Point::new(0, 3), "ABC")], None, cx)
+            });
+            action_log.update(cx, |log, cx| log.buffer_edited(buffer.clone(), cx));
+        });
+        cx.run_until_parked();
+        buffer.update(cx, |buffer, cx| {
+            buffer.edit([(Point::new(2, 0)..Point::new(2, 3), "GHI")], None, cx)
+        });
+        cx.run_until_parked();
+        assert!(action_log.read_with(cx, |log, cx| log.has_edits_in_turn(cx)));
+
+        action_log
+            .update(cx, |log, cx| log.revert_turn(cx))
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        assert_eq!(
+            buffer.read_with(cx, |buffer, _| buffer.text()),
+            "abc\nDEF\nGHI"
+        );
+        assert_eq!(
+            fs.load(path!("/dir/file").as_ref()).await.unwrap(),
+            "abc\nDEF\nGHI"
+        );
+        assert!(!action_log.read_with(cx, |log, cx| log.has_edits_in_turn(cx)));
+    }
+
+    #[gpui::test]
+    async fn test_apply_plan(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/dir"), json!({"file": "abc\ndef\nghi"}))
+            .await;
+        let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+        let action_log = cx.new(|_| ActionLog::new(project.clone()));
+        let file_path = project
+            .read_with(cx, |project, cx| project.find_project_path("dir/file", cx))
+            .unwrap();
+        let buffer = project
+            .update(cx, |project, cx| project.open_buffer(file_path, cx))
+            .await
+            .unwrap();
+
+        let planned_buffer = cx.update(|cx| {
+            action_log.update(cx, |log, cx| {
+                log.set_plan_only(true);
+                let planned_buffer = log.buffer_to_edit(buffer.clone(), cx);
+                log.buffer_read(planned_buffer.clone(), cx);
+                planned_buffer
+            })
+        });
+        cx.update(|cx| {
+            planned_buffer.update(cx, |buffer, cx| {
+                buffer.edit([(Point::new(1, 0)..Point::new(1, 3), "DEF")], None, cx)
+            });
+            action_log.update(cx, |log, cx| log.buffer_edited(planned_buffer.clone(), cx));
+        });
+        cx.run_until_parked();
+
+        // Planning leaves the project's buffer alone.
+        assert_ne!(planned_buffer, buffer);
+        assert_eq!(
+            buffer.read_with(cx, |buffer, _| buffer.text()),
+            "abc\ndef\nghi"
+        );
+        assert_eq!(unreviewed_hunks(&action_log, cx), vec![]);
+        assert!(action_log.read_with(cx, |log, cx| log.has_planned_edits(cx)));
+
+        // Edits the user makes in the meantime are preserved when the plan is applied.
+        buffer.update(cx, |buffer, cx| {
+            buffer.edit([(Point::new(0, 0)..Point::new(0, 0), "// ")], None, cx)
+        });
+        action_log
+            .update(cx, |log, cx| log.apply_plan(cx))
+            .await
+            .unwrap();
+        cx.run_until_parked();
+
+        assert_eq!(
+            buffer.read_with(cx, |buffer, _| buffer.text()),
+            "// abc\nDEF\nghi"
+        );
+        assert_eq!(
+            fs.load(path!("/dir/file").as_ref()).await.unwrap(),
+            "// abc\nDEF\nghi"
+        );
+        assert!(!action_log.read_with(cx, |log, cx| log.has_planned_edits(cx)));
+        assert_eq!(
+            unreviewed_hunks(&action_log, cx),
+            vec![(
+                buffer.clone(),
+                vec![HunkStatus {
+                    range: Point::new(1, 0)..Point::new(2, 0),
+                    diff_status: DiffHunkStatusKind::Modified,
+                    old_text: "def\n".into(),
+                }],
+            )]
+        );
+    }
+
     #[gpui::test]
     async fn test_reject_created_file_with_user_edits(cx: &mut TestAppContext) {
         init_test(cx);
@@ -2126,6 +2997,94 @@ mod tests {
         assert_eq!(unreviewed_hunks(&action_log, cx), vec![]);
     }
 
+    #[gpui::test]
+    async fn test_review_state(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/dir"), json!({"file": "abc\ndef\nghi\njkl\nmno"}))
+            .await;
+        let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+        let action_log = cx.new(|_| ActionLog::new(project.clone()));
+        let file_path = project
+            .read_with(cx, |project, cx| project.find_project_path("dir/file", cx))
+            .unwrap();
+        let worktree_id = file_path.worktree_id.to_proto();
+        let buffer = project
+            .update(cx, |project, cx| project.open_buffer(file_path, cx))
+            .await
+            .unwrap();
+
+        cx.update(|cx| {
+            action_log.update(cx, |log, cx| log.buffer_read(buffer.clone(), cx));
+            buffer.update(cx, |buffer, cx| {
+                buffer.edit(
+                    [
+                        (Point::new(1, 0)..Point::new(1, 3), "DEF"),
+                        (Point::new(3, 0)..Point::new(4, 0), ""),
+                    ],
+                    None,
+                    cx,
+                )
+            });
+            action_log.update(cx, |log, cx| log.buffer_edited(buffer.clone(), cx));
+        });
+        cx.run_until_parked();
+
+        let pending_hunks = action_log.read_with(cx, |log, cx| log.pending_hunks(cx));
+        assert_eq!(
+            pending_hunks
+                .iter()
+                .map(|hunk| (hunk.status, hunk.old_text.as_str()))
+                .collect::<Vec<_>>(),
+            [
+                (DiffHunkStatusKind::Modified, "def\n"),
+                (DiffHunkStatusKind::Deleted, "jkl\n"),
+            ]
+        );
+        assert_eq!(
+            serde_json::to_value(action_log.read_with(cx, |log, cx| log.review_state(cx))).unwrap(),
+            json!({
+                "files": [{
+                    "worktree_id": worktree_id,
+                    "path": "file",
+                    "reviewed": false,
+                    "hunks": [
+                        {
+                            "rows": {"start": 1, "end": 2},
+                            "kind": "modified",
+                            "old_text": "def\n",
+                            "new_text": "DEF\n",
+                        },
+                        {
+                            "rows": {"start": 3, "end": 3},
+                            "kind": "deleted",
+                            "old_text": "jkl\n",
+                            "new_text": "",
+                        },
+                    ],
+                }],
+            })
+        );
+
+        // Keeping the first hunk by range leaves only the deletion pending.
+        let first_hunk_range = pending_hunks[0].range.clone();
+        action_log.update(cx, |log, cx| {
+            log.keep_edits_in_ranges(buffer.clone(), vec![first_hunk_range], cx)
+        });
+        cx.run_until_parked();
+        assert_eq!(
+            action_log
+                .read_with(cx, |log, cx| log.review_state(cx))
+                .files[0]
+                .hunks
+                .iter()
+                .map(|hunk| hunk.kind)
+                .collect::<Vec<_>>(),
+            [HunkReviewKind::Deleted]
+        );
+    }
+
     #[derive(Debug, Clone, PartialEq, Eq)]
     struct HunkStatus {
         range: Range<Point>,
@@ -68,6 +68,8 @@ impl ToolUseStatus {
 pub struct ToolResultOutput {
     pub content: ToolResultContent,
     pub output: Option<serde_json::Value>,
+    /// Whether the tool left out part of its result to keep it small enough for the model.
+    pub truncated: bool,
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -104,6 +106,7 @@ impl From<String> for ToolResultOutput {
         ToolResultOutput {
             content: ToolResultContent::Text(value),
             output: None,
+            truncated: false,
         }
     }
 }
@@ -221,6 +224,12 @@ pub trait Tool: 'static + Send + Sync {
     /// Returns true if the tool may perform edits.
     fn may_perform_edits(&self) -> bool;
 
+    /// Returns true if the tool can run in a plan-only turn. Tools that perform edits can only
+    /// do so if they make them to the buffers returned by [`ActionLog::buffer_to_edit`].
+    fn supports_plan_only(&self) -> bool {
+        !self.may_perform_edits()
+    }
+
     /// Returns the JSON schema that describes the tool's input.
     fn input_schema(&self, _: LanguageModelToolSchemaFormat) -> Result<serde_json::Value> {
         Ok(serde_json::Value::Object(serde_json::Map::default()))
@@ -6,12 +6,17 @@ mod edit_agent;
 mod edit_file_tool;
 mod fetch_tool;
 mod find_path_tool;
+mod find_replace_multi_tool;
+mod git_tool;
 mod grep_tool;
 mod list_directory_tool;
 mod move_path_tool;
 mod now_tool;
 mod open_tool;
+mod project_dependencies_tool;
+mod propose_command_tool;
 mod read_file_tool;
+mod run_task_tool;
 mod schema;
 mod templates;
 mod terminal_tool;
@@ -37,8 +42,13 @@ use crate::diagnostics_tool::DiagnosticsTool;
 use crate::edit_file_tool::EditFileTool;
 use crate::fetch_tool::FetchTool;
 use crate::find_path_tool::FindPathTool;
+use crate::find_replace_multi_tool::FindReplaceMultiTool;
+use crate::git_tool::GitTool;
 use crate::list_directory_tool::ListDirectoryTool;
 use crate::now_tool::NowTool;
+use crate::project_dependencies_tool::ProjectDependenciesTool;
+use crate::propose_command_tool::ProposeCommandTool;
+use crate::run_task_tool::RunTaskTool;
 use crate::thinking_tool::ThinkingTool;
 
 pub use edit_file_tool::{EditFileMode, EditFileToolInput};
@@ -53,6 +63,8 @@ pub fn init(http_client: Arc<HttpClientWithUrl>, cx: &mut App) {
 
     let registry = ToolRegistry::global(cx);
     registry.register_tool(TerminalTool::new(cx));
+    registry.register_tool(RunTaskTool::new(cx));
+    registry.register_tool(ProposeCommandTool);
     registry.register_tool(CreateDirectoryTool);
     registry.register_tool(CopyPathTool);
     registry.register_tool(DeletePathTool);
@@ -64,9 +76,12 @@ pub fn init(http_client: Arc<HttpClientWithUrl>, cx: &mut App) {
     registry.register_tool(FindPathTool);
     registry.register_tool(ReadFileTool);
     registry.register_tool(GrepTool);
+    registry.register_tool(GitTool);
+    registry.register_tool(ProjectDependenciesTool);
     registry.register_tool(ThinkingTool);
     registry.register_tool(FetchTool::new(http_client));
     registry.register_tool(EditFileTool);
+    registry.register_tool(FindReplaceMultiTool);
 
     register_web_search_tool(&LanguageModelRegistry::global(cx), cx);
     cx.subscribe(
@@ -45,6 +45,10 @@ impl Tool for CreateDirectoryTool {
         false
     }
 
+    fn supports_plan_only(&self) -> bool {
+        false
+    }
+
     fn icon(&self) -> IconName {
         IconName::Folder
     }
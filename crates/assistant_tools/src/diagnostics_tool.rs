@@ -28,6 +28,27 @@ pub struct DiagnosticsToolInput {
     /// </example>
     #[serde(deserialize_with = "deserialize_path")]
     pub path: Option<String>,
+    /// Only report diagnostics of this severity. If not provided, both errors and warnings are reported.
+    #[serde(default)]
+    pub severity: Option<DiagnosticsSeverityFilter>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum DiagnosticsSeverityFilter {
+    Error,
+    Warning,
+}
+
+impl DiagnosticsSeverityFilter {
+    fn includes(filter: Option<Self>, severity: DiagnosticSeverity) -> bool {
+        match (filter, severity) {
+            (None, DiagnosticSeverity::ERROR | DiagnosticSeverity::WARNING) => true,
+            (Some(Self::Error), DiagnosticSeverity::ERROR) => true,
+            (Some(Self::Warning), DiagnosticSeverity::WARNING) => true,
+            _ => false,
+        }
+    }
 }
 
 fn deserialize_path<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
@@ -90,10 +111,14 @@ impl Tool for DiagnosticsTool {
         _window: Option<AnyWindowHandle>,
         cx: &mut App,
     ) -> ToolResult {
-        match serde_json::from_value::<DiagnosticsToolInput>(input)
-            .ok()
-            .and_then(|input| input.path)
-        {
+        let input =
+            serde_json::from_value::<DiagnosticsToolInput>(input).unwrap_or(DiagnosticsToolInput {
+                path: None,
+                severity: None,
+            });
+        let severity_filter = input.severity;
+
+        match input.path {
             Some(path) if !path.is_empty() => {
                 let Some(project_path) = project.read(cx).find_project_path(&path, cx) else {
                     return Task::ready(Err(anyhow!("Could not find path {path} in project",)))
@@ -110,6 +135,13 @@ impl Tool for DiagnosticsTool {
 
                     for (_, group) in snapshot.diagnostic_groups(None) {
                         let entry = &group.entries[group.primary_ix];
+                        if !DiagnosticsSeverityFilter::includes(
+                            severity_filter,
+                            entry.diagnostic.severity,
+                        ) {
+                            continue;
+                        }
+
                         let range = entry.range.to_point(&snapshot);
                         let severity = match entry.diagnostic.severity {
                             DiagnosticSeverity::ERROR => "error",
@@ -127,7 +159,14 @@ impl Tool for DiagnosticsTool {
                     }
 
                     if output.is_empty() {
-                        Ok("File doesn't have errors or warnings!".to_string().into())
+                        let message = match severity_filter {
+                            Some(DiagnosticsSeverityFilter::Error) => "File doesn't have errors!",
+                            Some(DiagnosticsSeverityFilter::Warning) => {
+                                "File doesn't have warnings!"
+                            }
+                            None => "File doesn't have errors or warnings!",
+                        };
+                        Ok(message.to_string().into())
                     } else {
                         Ok(output.into())
                     }
@@ -140,21 +179,30 @@ impl Tool for DiagnosticsTool {
                 let mut has_diagnostics = false;
 
                 for (project_path, _, summary) in project.diagnostic_summaries(true, cx) {
-                    if summary.error_count > 0 || summary.warning_count > 0 {
+                    let (error_count, warning_count) = match severity_filter {
+                        Some(DiagnosticsSeverityFilter::Error) => (summary.error_count, 0),
+                        Some(DiagnosticsSeverityFilter::Warning) => (0, summary.warning_count),
+                        None => (summary.error_count, summary.warning_count),
+                    };
+
+                    if error_count > 0 || warning_count > 0 {
                         let Some(worktree) = project.worktree_for_id(project_path.worktree_id, cx)
                         else {
                             continue;
                         };
 
                         has_diagnostics = true;
-                        output.push_str(&format!(
-                            "{}: {} error(s), {} warning(s)\n",
-                            Path::new(worktree.read(cx).root_name())
-                                .join(project_path.path)
-                                .display(),
-                            summary.error_count,
-                            summary.warning_count
-                        ));
+                        let path = Path::new(worktree.read(cx).root_name()).join(project_path.path);
+                        let counts = match severity_filter {
+                            Some(DiagnosticsSeverityFilter::Error) => {
+                                format!("{error_count} error(s)")
+                            }
+                            Some(DiagnosticsSeverityFilter::Warning) => {
+                                format!("{warning_count} warning(s)")
+                            }
+                            None => format!("{error_count} error(s), {warning_count} warning(s)"),
+                        };
+                        output.push_str(&format!("{}: {counts}\n", path.display()));
                     }
                 }
 
@@ -165,10 +213,14 @@ impl Tool for DiagnosticsTool {
                 if has_diagnostics {
                     Task::ready(Ok(output.into())).into()
                 } else {
-                    Task::ready(Ok("No errors or warnings found in the project."
-                        .to_string()
-                        .into()))
-                    .into()
+                    let message = match severity_filter {
+                        Some(DiagnosticsSeverityFilter::Error) => "No errors found in the project.",
+                        Some(DiagnosticsSeverityFilter::Warning) => {
+                            "No warnings found in the project."
+                        }
+                        None => "No errors or warnings found in the project.",
+                    };
+                    Task::ready(Ok(message.to_string().into())).into()
                 }
             }
         }
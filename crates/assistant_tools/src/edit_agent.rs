@@ -5,6 +5,7 @@ mod evals;
 mod streaming_fuzzy_matcher;
 
 use crate::{Template, Templates};
+use agent_settings::{AgentSettings, EditMatchingMode};
 use anyhow::Result;
 use assistant_tool::ActionLog;
 use create_file_parser::{CreateFileParser, CreateFileParserEvent};
@@ -13,17 +14,17 @@ use futures::{
     Stream, StreamExt,
     channel::mpsc::{self, UnboundedReceiver},
     pin_mut,
-    stream::BoxStream,
 };
-use gpui::{AppContext, AsyncApp, Entity, Task};
+use gpui::{App, AppContext, AsyncApp, Entity, Task};
 use language::{Anchor, Buffer, BufferSnapshot, LineIndent, Point, TextBufferSnapshot};
 use language_model::{
     LanguageModel, LanguageModelCompletionError, LanguageModelRequest, LanguageModelRequestMessage,
-    LanguageModelToolChoice, MessageContent, Role,
+    LanguageModelTextStream, LanguageModelToolChoice, MessageContent, Role, TokenUsage,
 };
 use project::{AgentLocation, Project};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use settings::Settings as _;
 use std::{cmp, iter, mem, ops::Range, path::PathBuf, pin::Pin, sync::Arc, task::Poll};
 use streaming_diff::{CharOperation, StreamingDiff};
 use streaming_fuzzy_matcher::StreamingFuzzyMatcher;
@@ -62,6 +63,37 @@ pub enum EditAgentOutputEvent {
 pub struct EditAgentOutput {
     pub raw_edits: String,
     pub parser_metrics: EditParserMetrics,
+    #[serde(default)]
+    #[schemars(skip)]
+    pub token_usage: TokenUsage,
+}
+
+/// How the `<old_text>` of an edit is located in the buffer.
+#[derive(Clone, Copy, Debug)]
+pub struct EditMatching {
+    pub mode: EditMatchingMode,
+    /// The fraction of `<old_text>` lines that must match the buffer for the
+    /// edit to be applied.
+    pub min_confidence: f32,
+}
+
+impl EditMatching {
+    pub fn from_settings(cx: &App) -> Self {
+        let settings = AgentSettings::get_global(cx);
+        Self {
+            mode: settings.edit_matching_mode,
+            min_confidence: settings.edit_matching_min_confidence,
+        }
+    }
+}
+
+impl Default for EditMatching {
+    fn default() -> Self {
+        Self {
+            mode: EditMatchingMode::Fuzzy,
+            min_confidence: 0.8,
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -70,6 +102,7 @@ pub struct EditAgent {
     action_log: Entity<ActionLog>,
     project: Entity<Project>,
     templates: Arc<Templates>,
+    matching: EditMatching,
 }
 
 impl EditAgent {
@@ -84,9 +117,15 @@ impl EditAgent {
             project,
             action_log,
             templates,
+            matching: EditMatching::default(),
         }
     }
 
+    pub fn with_matching(mut self, matching: EditMatching) -> Self {
+        self.matching = matching;
+        self
+    }
+
     pub fn overwrite(
         &self,
         buffer: Entity<Buffer>,
@@ -108,15 +147,18 @@ impl EditAgent {
                 edit_description,
             }
             .render(&this.templates)?;
-            let new_chunks = this
+            let response = this
                 .request(conversation, CompletionIntent::CreateFile, prompt, cx)
                 .await?;
 
-            let (output, mut inner_events) = this.overwrite_with_chunks(buffer, new_chunks, cx);
+            let (output, mut inner_events) =
+                this.overwrite_with_chunks(buffer, response.stream, cx);
             while let Some(event) = inner_events.next().await {
                 events_tx.unbounded_send(event).ok();
             }
-            output.await
+            let mut output = output.await?;
+            output.token_usage = *response.last_token_usage.lock();
+            Ok(output)
         });
         (output, events_rx)
     }
@@ -217,11 +259,14 @@ impl EditAgent {
                 edit_description,
             }
             .render(&this.templates)?;
-            let edit_chunks = this
+            let response = this
                 .request(conversation, CompletionIntent::EditFile, prompt, cx)
                 .await?;
-            this.apply_edit_chunks(buffer, edit_chunks, events_tx, cx)
-                .await
+            let mut output = this
+                .apply_edit_chunks(buffer, response.stream, events_tx, cx)
+                .await?;
+            output.token_usage = *response.last_token_usage.lock();
+            Ok(output)
         });
         (output, events_rx)
     }
@@ -250,7 +295,7 @@ impl EditAgent {
             // Resolve the old text in the background, updating the agent
             // location as we keep refining which range it corresponds to.
             let (resolve_old_text, mut old_range) =
-                Self::resolve_old_text(snapshot.text.clone(), edit_events, cx);
+                Self::resolve_old_text(snapshot.text.clone(), edit_events, self.matching, cx);
             while let Ok(old_range) = old_range.recv().await {
                 if let Some(old_range) = old_range {
                     let old_range = snapshot.anchor_before(old_range.start)
@@ -295,6 +340,13 @@ impl EditAgent {
                 }
             };
 
+            // Edits whose old text only approximately matched the buffer are
+            // recorded, so that they get a closer look during review.
+            let fuzzy_range = (!resolved_old_text.is_exact).then(|| {
+                snapshot.anchor_before(resolved_old_text.range.start)
+                    ..snapshot.anchor_after(resolved_old_text.range.end)
+            });
+
             // Compute edits in the background and apply them as they become
             // available.
             let (compute_edits, edits) =
@@ -337,6 +389,12 @@ impl EditAgent {
             }
 
             edit_events = compute_edits.await?;
+
+            if let Some(fuzzy_range) = fuzzy_range {
+                self.action_log.update(cx, |log, cx| {
+                    log.record_fuzzy_edit(buffer.clone(), fuzzy_range, cx)
+                })?;
+            }
         }
 
         output.await
@@ -371,6 +429,7 @@ impl EditAgent {
             Ok(EditAgentOutput {
                 raw_edits,
                 parser_metrics: parser.finish(),
+                token_usage: TokenUsage::default(),
             })
         });
         (output, rx)
@@ -409,6 +468,7 @@ impl EditAgent {
             Ok(EditAgentOutput {
                 raw_edits,
                 parser_metrics: EditParserMetrics::default(),
+                token_usage: TokenUsage::default(),
             })
         });
         (output, rx)
@@ -417,6 +477,7 @@ impl EditAgent {
     fn resolve_old_text<T>(
         snapshot: TextBufferSnapshot,
         mut edit_events: T,
+        matching: EditMatching,
         cx: &mut AsyncApp,
     ) -> (
         Task<Result<(T, Vec<ResolvedOldText>)>>,
@@ -427,7 +488,7 @@ impl EditAgent {
     {
         let (old_range_tx, old_range_rx) = async_watch::channel(None);
         let task = cx.background_spawn(async move {
-            let mut matcher = StreamingFuzzyMatcher::new(snapshot);
+            let mut matcher = StreamingFuzzyMatcher::new(snapshot).with_matching(matching);
             while let Some(edit_event) = edit_events.next().await {
                 let EditParserEvent::OldTextChunk { chunk, done } = edit_event? else {
                     break;
@@ -458,7 +519,11 @@ impl EditAgent {
             );
             let resolved_old_texts = matches
                 .into_iter()
-                .map(|range| ResolvedOldText { range, indent })
+                .map(|range| ResolvedOldText {
+                    is_exact: matcher.is_exact_match(&range),
+                    range,
+                    indent,
+                })
                 .collect::<Vec<_>>();
 
             Ok((edit_events, resolved_old_texts))
@@ -619,7 +684,7 @@ impl EditAgent {
         intent: CompletionIntent,
         prompt: String,
         cx: &mut AsyncApp,
-    ) -> Result<BoxStream<'static, Result<String, LanguageModelCompletionError>>> {
+    ) -> Result<LanguageModelTextStream> {
         let mut messages_iter = conversation.messages.iter_mut();
         if let Some(last_message) = messages_iter.next_back() {
             if last_message.role == Role::Assistant {
@@ -681,15 +746,17 @@ impl EditAgent {
             tools,
             stop: Vec::new(),
             temperature: None,
+            response_schema: None,
         };
 
-        Ok(self.model.stream_completion_text(request, cx).await?.stream)
+        self.model.stream_completion_text(request, cx).await
     }
 }
 
 struct ResolvedOldText {
     range: Range<usize>,
     indent: LineIndent,
+    is_exact: bool,
 }
 
 #[derive(Copy, Clone, Debug)]
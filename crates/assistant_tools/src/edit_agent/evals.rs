@@ -6,6 +6,7 @@ use crate::{
     list_directory_tool::ListDirectoryToolInput,
 };
 use Role::*;
+use anyhow::{Context as _, anyhow};
 use assistant_tool::ToolRegistry;
 use client::{Client, UserStore};
 use collections::HashMap;
@@ -25,9 +26,10 @@ use serde_json::json;
 use std::{
     cmp::Reverse,
     fmt::{self, Display},
-    io::Write as _,
+    io::{BufRead as _, Write as _},
     str::FromStr,
     sync::mpsc,
+    time::{Duration, Instant},
 };
 use util::path;
 
@@ -80,6 +82,7 @@ fn eval_extract_handle_command_output() {
                             path: input_file_path.into(),
                             start_line: None,
                             end_line: None,
+                            symbol: None,
                         },
                     )],
                 ),
@@ -102,7 +105,13 @@ fn eval_extract_handle_command_output() {
             ],
             Some(input_file_content.into()),
             EvalAssertion::assert_diff_any(possible_diffs),
-        ),
+        )
+        // The file is about 4k tokens and appears twice in the conversation, so these only
+        // catch runaway responses.
+        .with_budget(EvalBudget {
+            max_tokens: Some(20_000),
+            max_duration: Some(Duration::from_secs(90)),
+        }),
     );
 }
 
@@ -134,6 +143,7 @@ fn eval_delete_run_git_blame() {
                             path: input_file_path.into(),
                             start_line: None,
                             end_line: None,
+                            symbol: None,
                         },
                     )],
                 ),
@@ -156,7 +166,11 @@ fn eval_delete_run_git_blame() {
             ],
             Some(input_file_content.into()),
             EvalAssertion::assert_eq(output_file_content),
-        ),
+        )
+        .with_budget(EvalBudget {
+            max_tokens: Some(20_000),
+            max_duration: Some(Duration::from_secs(90)),
+        }),
     );
 }
 
@@ -196,6 +210,7 @@ fn eval_translate_doc_comments() {
                             path: input_file_path.into(),
                             start_line: None,
                             end_line: None,
+                            symbol: None,
                         },
                     )],
                 ),
@@ -274,6 +289,7 @@ fn eval_use_wasi_sdk_in_compile_parser_to_wasm() {
                             path: input_file_path.into(),
                             start_line: Some(971),
                             end_line: Some(1050),
+                            symbol: None,
                         },
                     )],
                 ),
@@ -294,6 +310,7 @@ fn eval_use_wasi_sdk_in_compile_parser_to_wasm() {
                             path: input_file_path.into(),
                             start_line: Some(1050),
                             end_line: Some(1100),
+                            symbol: None,
                         },
                     )],
                 ),
@@ -314,6 +331,7 @@ fn eval_use_wasi_sdk_in_compile_parser_to_wasm() {
                             path: input_file_path.into(),
                             start_line: Some(1100),
                             end_line: Some(1150),
+                            symbol: None,
                         },
                     )],
                 ),
@@ -466,6 +484,7 @@ fn eval_from_pixels_constructor() {
                             path: input_file_path.into(),
                             start_line: None,
                             end_line: None,
+                            symbol: None,
                         },
                     )],
                 ),
@@ -661,6 +680,7 @@ fn eval_zode() {
                                 path: "root/eval/react.py".into(),
                                 start_line: None,
                                 end_line: None,
+                                symbol: None,
                             },
                         ),
                         tool_use(
@@ -670,6 +690,7 @@ fn eval_zode() {
                                 path: "root/eval/react_test.py".into(),
                                 start_line: None,
                                 end_line: None,
+                                symbol: None,
                             },
                         ),
                     ],
@@ -723,11 +744,13 @@ fn eval_zode() {
                 if message.is_empty() {
                     Ok(EvalAssertionOutcome {
                         score: 100,
+                        category_scores: Vec::new(),
                         message: None,
                     })
                 } else {
                     Ok(EvalAssertionOutcome {
                         score: 0,
+                        category_scores: Vec::new(),
                         message: Some(message),
                     })
                 }
@@ -773,6 +796,7 @@ fn eval_add_overwrite_test() {
                             path: input_file_path.into(),
                             start_line: None,
                             end_line: None,
+                            symbol: None,
                         },
                     )],
                 ),
@@ -872,6 +896,7 @@ fn eval_add_overwrite_test() {
                                 path: input_file_path.into(),
                                 start_line: Some(953),
                                 end_line: Some(1010),
+                                symbol: None,
                             },
                         ),
                     ],
@@ -897,6 +922,7 @@ fn eval_add_overwrite_test() {
                                 path: input_file_path.into(),
                                 start_line: Some(1012),
                                 end_line: Some(1120),
+                                symbol: None,
                             },
                         ),
                     ],
@@ -920,6 +946,7 @@ fn eval_add_overwrite_test() {
                                 path: input_file_path.into(),
                                 start_line: Some(271),
                                 end_line: Some(276),
+                                symbol: None,
                             },
                         ),
                     ],
@@ -1104,6 +1131,7 @@ struct EvalInput {
     edit_file_input: EditFileToolInput,
     input_content: Option<String>,
     assertion: EvalAssertion,
+    budget: EvalBudget,
 }
 
 impl EvalInput {
@@ -1137,11 +1165,61 @@ impl EvalInput {
             edit_file_input,
             input_content,
             assertion,
+            budget: EvalBudget::default(),
         }
     }
+
+    fn with_budget(mut self, budget: EvalBudget) -> Self {
+        self.budget = budget;
+        self
+    }
 }
 
-#[derive(Clone)]
+/// Limits on the resources a single iteration of an eval may consume. Iterations
+/// that exceed them fail regardless of their score.
+#[derive(Clone, Copy, Debug, Default)]
+struct EvalBudget {
+    max_tokens: Option<u32>,
+    max_duration: Option<Duration>,
+}
+
+impl EvalBudget {
+    /// Returns the budget with its limits replaced by the ones given in `ZED_EVAL_MAX_TOKENS`
+    /// and `ZED_EVAL_MAX_SECONDS`, for tightening or loosening budgets without editing evals.
+    fn with_env_overrides(self) -> Self {
+        Self {
+            max_tokens: std::env::var("ZED_EVAL_MAX_TOKENS")
+                .ok()
+                .and_then(|value| value.parse().ok())
+                .or(self.max_tokens),
+            max_duration: std::env::var("ZED_EVAL_MAX_SECONDS")
+                .ok()
+                .and_then(|value| value.parse().ok())
+                .map(Duration::from_secs_f64)
+                .or(self.max_duration),
+        }
+    }
+
+    fn violations(&self, token_usage: &TokenUsage, elapsed: Duration) -> Vec<String> {
+        let mut violations = Vec::new();
+        if let Some(max_tokens) = self.max_tokens {
+            let total_tokens = token_usage.total_tokens();
+            if total_tokens > max_tokens {
+                violations.push(format!(
+                    "used {total_tokens} tokens, budget is {max_tokens}"
+                ));
+            }
+        }
+        if let Some(max_duration) = self.max_duration {
+            if elapsed > max_duration {
+                violations.push(format!("took {elapsed:?}, budget is {max_duration:?}"));
+            }
+        }
+        violations
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
 struct EvalSample {
     text_before: String,
     text_after: String,
@@ -1206,6 +1284,7 @@ impl EvalAssertion {
                 } else {
                     0
                 },
+                category_scores: Vec::new(),
                 message: None,
             })
         })
@@ -1222,6 +1301,7 @@ impl EvalAssertion {
 
             Ok(EvalAssertionOutcome {
                 score: if matches { 100 } else { 0 },
+                category_scores: Vec::new(),
                 message: None,
             })
         })
@@ -1253,19 +1333,7 @@ impl EvalAssertion {
                 output.push_str(&chunk);
             }
 
-            // Parse the score from the response
-            let re = regex::Regex::new(r"<score>(\d+)</score>").unwrap();
-            if let Some(captures) = re.captures(&output) {
-                if let Some(score_match) = captures.get(1) {
-                    let score = score_match.as_str().parse().unwrap_or(0);
-                    return Ok(EvalAssertionOutcome {
-                        score,
-                        message: Some(output),
-                    });
-                }
-            }
-
-            anyhow::bail!("No score found in response. Raw output: {output}");
+            parse_judge_output(output)
         })
     }
 
@@ -1279,83 +1347,322 @@ impl EvalAssertion {
     }
 }
 
-fn eval(iterations: usize, expected_pass_ratio: f32, mut eval: EvalInput) {
-    let mut evaluated_count = 0;
-    let mut failed_count = 0;
-    report_progress(evaluated_count, failed_count, iterations);
+/// When an eval's pass ratio is too close to the expected one to tell apart from noise, it is
+/// rerun with the same number of iterations up to this many more times.
+const MAX_RERUN_ROUNDS: usize = 2;
+
+/// The z-score for a two-sided 95% confidence interval, Bonferroni-corrected for the
+/// `MAX_RERUN_ROUNDS + 1` times the results are looked at. Checking the same interval after
+/// each round would otherwise give a passing eval several chances to be called failing.
+const CONFIDENCE_Z: f64 = 2.394;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum EvalVerdict {
+    Passing,
+    /// The pass ratio is below the expected one, but not by a statistically significant margin.
+    Flaky,
+    /// The pass ratio is below the expected one even at the top of its confidence interval.
+    Failing,
+}
 
-    let (tx, rx) = mpsc::channel();
+impl EvalVerdict {
+    fn new(passed_count: usize, evaluated_count: usize, expected_pass_ratio: f64) -> Self {
+        let (_, upper_bound) = pass_ratio_interval(passed_count, evaluated_count);
+        if evaluated_count > 0
+            && passed_count as f64 / evaluated_count as f64 >= expected_pass_ratio
+        {
+            EvalVerdict::Passing
+        } else if upper_bound < expected_pass_ratio {
+            EvalVerdict::Failing
+        } else {
+            EvalVerdict::Flaky
+        }
+    }
+}
 
-    // Cache the last message in the conversation, and run one instance of the eval so that
-    // all the next ones are cached.
-    eval.conversation.last_mut().unwrap().cache = true;
-    run_eval(eval.clone(), tx.clone());
+/// Returns the Wilson score interval for the true pass ratio, which unlike the normal
+/// approximation stays within 0..=1 and behaves well for ratios close to 0 or 1.
+fn pass_ratio_interval(passed_count: usize, evaluated_count: usize) -> (f64, f64) {
+    if evaluated_count == 0 {
+        return (0., 1.);
+    }
 
-    let executor = gpui::background_executor();
-    for _ in 1..iterations {
-        let eval = eval.clone();
-        let tx = tx.clone();
-        executor.spawn(async move { run_eval(eval, tx) }).detach();
+    let n = evaluated_count as f64;
+    let ratio = passed_count as f64 / n;
+    let z_squared = CONFIDENCE_Z * CONFIDENCE_Z;
+    let denominator = 1. + z_squared / n;
+    let center = (ratio + z_squared / (2. * n)) / denominator;
+    let margin =
+        CONFIDENCE_Z * (ratio * (1. - ratio) / n + z_squared / (4. * n * n)).sqrt() / denominator;
+    ((center - margin).max(0.), (center + margin).min(1.))
+}
+
+#[derive(Default)]
+struct EvalResults {
+    evaluated_count: usize,
+    failed_count: usize,
+    failed_evals: HashMap<String, Vec<EvalOutput>>,
+    errored_evals: HashMap<String, usize>,
+    eval_outputs: Vec<EvalOutput>,
+    cumulative_parser_metrics: EditParserMetrics,
+}
+
+impl EvalResults {
+    fn passed_count(&self) -> usize {
+        self.evaluated_count - self.failed_count
     }
-    drop(tx);
 
-    let mut failed_evals = HashMap::default();
-    let mut errored_evals = HashMap::default();
-    let mut eval_outputs = Vec::new();
-    let mut cumulative_parser_metrics = EditParserMetrics::default();
-    while let Ok(output) = rx.recv() {
+    fn run(&mut self, eval: &EvalInput, iterations: usize, total_iterations: usize) {
+        let (tx, rx) = mpsc::channel();
+        match worker_count() {
+            Some(worker_count) => run_eval_workers(iterations, worker_count, tx),
+            None => run_evals(eval, iterations, tx),
+        }
+
+        while let Ok(output) = rx.recv() {
+            self.record(output);
+            report_progress(self.evaluated_count, self.failed_count, total_iterations);
+        }
+    }
+
+    fn record(&mut self, output: Result<EvalOutput>) {
         match output {
             Ok(output) => {
-                cumulative_parser_metrics += output.sample.edit_output.parser_metrics.clone();
-                eval_outputs.push(output.clone());
-                if output.assertion.score < 80 {
-                    failed_count += 1;
-                    failed_evals
+                self.cumulative_parser_metrics += output.sample.edit_output.parser_metrics.clone();
+                self.eval_outputs.push(output.clone());
+                if output.assertion.score < 80 || !output.budget_violations.is_empty() {
+                    self.failed_count += 1;
+                    self.failed_evals
                         .entry(output.sample.text_after.clone())
                         .or_insert(Vec::new())
                         .push(output);
                 }
             }
             Err(error) => {
-                failed_count += 1;
-                *errored_evals.entry(format!("{:?}", error)).or_insert(0) += 1;
+                self.failed_count += 1;
+                *self
+                    .errored_evals
+                    .entry(format!("{:?}", error))
+                    .or_insert(0) += 1;
             }
         }
-
-        evaluated_count += 1;
-        report_progress(evaluated_count, failed_count, iterations);
+        self.evaluated_count += 1;
     }
 
-    let actual_pass_ratio = (iterations - failed_count) as f32 / iterations as f32;
-    println!("Actual pass ratio: {}\n", actual_pass_ratio);
-    if actual_pass_ratio < expected_pass_ratio {
-        let mut errored_evals = errored_evals.into_iter().collect::<Vec<_>>();
-        errored_evals.sort_by_key(|(_, count)| Reverse(*count));
+    fn print_failures(&self) {
+        print_category_averages(&self.eval_outputs);
+
+        let mut errored_evals = self.errored_evals.iter().collect::<Vec<_>>();
+        errored_evals.sort_by_key(|(_, count)| Reverse(**count));
         for (error, count) in errored_evals {
             println!("Eval errored {} times. Error: {}", count, error);
         }
 
-        let mut failed_evals = failed_evals.into_iter().collect::<Vec<_>>();
-        failed_evals.sort_by_key(|(_, evals)| Reverse(evals.len()));
-        for (_buffer_output, failed_evals) in failed_evals {
+        let mut failed_evals = self.failed_evals.values().collect::<Vec<_>>();
+        failed_evals.sort_by_key(|evals| Reverse(evals.len()));
+        for failed_evals in failed_evals {
             let eval_output = failed_evals.first().unwrap();
             println!("Eval failed {} times", failed_evals.len());
             println!("{}", eval_output);
         }
+    }
+}
 
-        panic!(
-            "Actual pass ratio: {}\nExpected pass ratio: {}",
-            actual_pass_ratio, expected_pass_ratio
+fn eval(iterations: usize, expected_pass_ratio: f32, mut eval: EvalInput) {
+    if let Some(iterations) = std::env::var(WORKER_ITERATIONS_ENV)
+        .ok()
+        .and_then(|value| value.parse().ok())
+    {
+        eval.conversation.last_mut().unwrap().cache = true;
+        run_as_worker(&eval, iterations);
+        return;
+    }
+
+    let expected_pass_ratio = expected_pass_ratio as f64;
+    let mut results = EvalResults::default();
+    let mut total_iterations = iterations;
+    report_progress(0, 0, total_iterations);
+
+    // Cache the last message in the conversation, and run one instance of the eval so that
+    // all the next ones are cached.
+    eval.conversation.last_mut().unwrap().cache = true;
+    let (tx, rx) = mpsc::channel();
+    run_eval(eval.clone(), tx);
+    if let Ok(output) = rx.recv() {
+        results.record(output);
+        report_progress(
+            results.evaluated_count,
+            results.failed_count,
+            total_iterations,
+        );
+    }
+    results.run(&eval, iterations.saturating_sub(1), total_iterations);
+
+    let mut verdict = EvalVerdict::new(
+        results.passed_count(),
+        results.evaluated_count,
+        expected_pass_ratio,
+    );
+    for _ in 0..MAX_RERUN_ROUNDS {
+        if verdict != EvalVerdict::Flaky {
+            break;
+        }
+        println!(
+            "\nPass ratio is too close to {} to call, running {} more iterations",
+            expected_pass_ratio, iterations
+        );
+        total_iterations += iterations;
+        results.run(&eval, iterations, total_iterations);
+        verdict = EvalVerdict::new(
+            results.passed_count(),
+            results.evaluated_count,
+            expected_pass_ratio,
         );
     }
 
-    let mismatched_tag_ratio =
-        cumulative_parser_metrics.mismatched_tags as f32 / cumulative_parser_metrics.tags as f32;
+    let actual_pass_ratio = results.passed_count() as f64 / results.evaluated_count as f64;
+    let (lower_bound, upper_bound) =
+        pass_ratio_interval(results.passed_count(), results.evaluated_count);
+    println!(
+        "\nActual pass ratio: {:.3} (corrected 95% CI {:.3}..{:.3} over {} iterations)\n",
+        actual_pass_ratio, lower_bound, upper_bound, results.evaluated_count
+    );
+    match verdict {
+        EvalVerdict::Passing => {}
+        EvalVerdict::Flaky => {
+            results.print_failures();
+            panic!(
+                "FLAKY: actual pass ratio {:.3} is below the expected {} after {} iterations, \
+                 but the corrected 95% confidence interval {:.3}..{:.3} still includes it",
+                actual_pass_ratio,
+                expected_pass_ratio,
+                results.evaluated_count,
+                lower_bound,
+                upper_bound
+            );
+        }
+        EvalVerdict::Failing => {
+            results.print_failures();
+            panic!(
+                "FAILING: actual pass ratio: {:.3} (corrected 95% CI {:.3}..{:.3})\nExpected pass ratio: {}",
+                actual_pass_ratio, lower_bound, upper_bound, expected_pass_ratio
+            );
+        }
+    }
+
+    let mismatched_tag_ratio = results.cumulative_parser_metrics.mismatched_tags as f32
+        / results.cumulative_parser_metrics.tags as f32;
     if mismatched_tag_ratio > 0.10 {
-        for eval_output in eval_outputs {
+        for eval_output in results.eval_outputs {
             println!("{}", eval_output);
         }
-        panic!("Too many mismatched tags: {:?}", cumulative_parser_metrics);
+        panic!(
+            "Too many mismatched tags: {:?}",
+            results.cumulative_parser_metrics
+        );
+    }
+}
+
+fn run_evals(eval: &EvalInput, iterations: usize, tx: mpsc::Sender<Result<EvalOutput>>) {
+    let executor = gpui::background_executor();
+    for _ in 0..iterations {
+        let eval = eval.clone();
+        let tx = tx.clone();
+        executor.spawn(async move { run_eval(eval, tx) }).detach();
+    }
+}
+
+/// Set in worker processes to the number of iterations they should run.
+const WORKER_ITERATIONS_ENV: &str = "ZED_EVAL_WORKER_ITERATIONS";
+
+/// Worker processes print their results to stdout as JSON on lines starting with this prefix,
+/// so that they can be told apart from anything else the eval prints.
+const WORKER_OUTPUT_PREFIX: &str = "ZED_EVAL_OUTPUT:";
+
+/// The number of worker processes to shard iterations across, from `ZED_EVAL_WORKERS`.
+///
+/// Each worker is a copy of the test binary that only runs the current eval, which requires the
+/// test to be running on a thread named after it, as it is when the test harness runs tests in
+/// parallel.
+fn worker_count() -> Option<usize> {
+    let worker_count = std::env::var("ZED_EVAL_WORKERS")
+        .ok()?
+        .parse::<usize>()
+        .ok()
+        .filter(|count| *count > 1)?;
+    std::thread::current()
+        .name()
+        .is_some_and(|name| name != "main")
+        .then_some(worker_count)
+}
+
+fn run_eval_workers(iterations: usize, worker_count: usize, tx: mpsc::Sender<Result<EvalOutput>>) {
+    let test_name = std::thread::current()
+        .name()
+        .unwrap_or_default()
+        .to_string();
+    for worker_ix in 0..worker_count.min(iterations) {
+        let worker_iterations =
+            iterations / worker_count + usize::from(worker_ix < iterations % worker_count);
+        let tx = tx.clone();
+        let test_name = test_name.clone();
+        std::thread::spawn(move || {
+            let mut reported_count = 0;
+            if let Err(error) =
+                run_eval_worker(&test_name, worker_iterations, &tx, &mut reported_count)
+            {
+                tx.send(Err(error)).ok();
+                reported_count += 1;
+            }
+            for _ in reported_count..worker_iterations {
+                tx.send(Err(anyhow!("eval worker exited before reporting a result")))
+                    .ok();
+            }
+        });
+    }
+}
+
+/// Runs a worker process and forwards its results, counting them in `reported_count`.
+fn run_eval_worker(
+    test_name: &str,
+    iterations: usize,
+    tx: &mpsc::Sender<Result<EvalOutput>>,
+    reported_count: &mut usize,
+) -> Result<()> {
+    let mut child = std::process::Command::new(std::env::current_exe()?)
+        .args([test_name, "--exact", "--include-ignored", "--nocapture"])
+        .env(WORKER_ITERATIONS_ENV, iterations.to_string())
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::inherit())
+        .spawn()
+        .context("failed to spawn eval worker")?;
+    let stdout = child.stdout.take().context("eval worker has no stdout")?;
+
+    for line in std::io::BufReader::new(stdout).lines() {
+        let line = line?;
+        let Some((_, output)) = line.split_once(WORKER_OUTPUT_PREFIX) else {
+            continue;
+        };
+        let output = serde_json::from_str::<Result<EvalOutput, String>>(output)
+            .context("failed to parse eval worker output")?;
+        tx.send(output.map_err(|error| anyhow!(error))).ok();
+        *reported_count += 1;
+    }
+    child.wait()?;
+    Ok(())
+}
+
+fn run_as_worker(eval: &EvalInput, iterations: usize) {
+    let (tx, rx) = mpsc::channel();
+    run_evals(eval, iterations, tx);
+    // Write to stdout directly rather than with `println!`, which the test harness may capture.
+    let mut stdout = std::io::stdout().lock();
+    while let Ok(output) = rx.recv() {
+        let output = output.map_err(|error| format!("{:?}", error));
+        let output = serde_json::to_string(&output).unwrap();
+        writeln!(stdout, "{WORKER_OUTPUT_PREFIX}{output}").unwrap();
+        stdout.flush().unwrap();
     }
 }
 
@@ -1369,19 +1676,34 @@ fn run_eval(eval: EvalInput, tx: mpsc::Sender<Result<EvalOutput>>) {
     tx.send(output).unwrap();
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 struct EvalOutput {
     sample: EvalSample,
     assertion: EvalAssertionOutcome,
+    elapsed: Duration,
+    budget_violations: Vec<String>,
 }
 
 impl Display for EvalOutput {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         writeln!(f, "Score: {:?}", self.assertion.score)?;
+        for (category, score) in &self.assertion.category_scores {
+            writeln!(f, "  {}: {}", category, score)?;
+        }
         if let Some(message) = self.assertion.message.as_ref() {
             writeln!(f, "Message: {}", message)?;
         }
 
+        writeln!(
+            f,
+            "Tokens: {}",
+            self.sample.edit_output.token_usage.total_tokens()
+        )?;
+        writeln!(f, "Elapsed: {:?}", self.elapsed)?;
+        for violation in &self.budget_violations {
+            writeln!(f, "Budget exceeded: {}", violation)?;
+        }
+
         writeln!(f, "Diff:\n{}", self.sample.diff)?;
 
         writeln!(
@@ -1394,6 +1716,28 @@ impl Display for EvalOutput {
     }
 }
 
+fn print_category_averages(eval_outputs: &[EvalOutput]) {
+    let mut totals = HashMap::<JudgeCategory, (usize, usize)>::default();
+    for output in eval_outputs {
+        for (category, score) in &output.assertion.category_scores {
+            let (total, count) = totals.entry(*category).or_default();
+            *total += score;
+            *count += 1;
+        }
+    }
+    if totals.is_empty() {
+        return;
+    }
+
+    println!("Average category scores:");
+    for category in JudgeCategory::ALL {
+        if let Some((total, count)) = totals.get(&category) {
+            println!("  {}: {:.2}", category, *total as f32 / *count as f32);
+        }
+    }
+    println!();
+}
+
 fn report_progress(evaluated_count: usize, failed_count: usize, iterations: usize) {
     let passed_count = evaluated_count - failed_count;
     let passed_ratio = if evaluated_count == 0 {
@@ -1524,6 +1868,7 @@ impl EditAgentTest {
         let worktrees = vec![WorktreeContext {
             root_name: "root".to_string(),
             rules_file: None,
+            nested_rules_files: Vec::new(),
         }];
         let prompt_builder = PromptBuilder::new(None)?;
         let project_context = ProjectContext::new(worktrees, Vec::default());
@@ -1557,6 +1902,7 @@ impl EditAgentTest {
             ..Default::default()
         };
 
+        let start = Instant::now();
         let edit_output = if matches!(eval.edit_file_input.mode, EditFileMode::Edit) {
             if let Some(input_content) = eval.input_content.as_deref() {
                 buffer.update(cx, |buffer, cx| buffer.set_text(input_content, cx));
@@ -1577,6 +1923,11 @@ impl EditAgentTest {
             );
             edit_output.await?
         };
+        let elapsed = start.elapsed();
+        let budget_violations = eval
+            .budget
+            .with_env_overrides()
+            .violations(&edit_output.token_usage, elapsed);
 
         let buffer_text = buffer.read_with(cx, |buffer, _| buffer.text());
         let sample = EvalSample {
@@ -1593,16 +1944,99 @@ impl EditAgentTest {
             .run(&sample, self.judge_model.clone(), cx)
             .await?;
 
-        Ok(EvalOutput { assertion, sample })
+        Ok(EvalOutput {
+            assertion,
+            sample,
+            elapsed,
+            budget_violations,
+        })
+    }
+}
+
+/// Parses the judge's response, combining the per-category scores into a
+/// weighted overall score. Responses that only contain a single `<score>` tag
+/// are still accepted.
+fn parse_judge_output(output: String) -> Result<EvalAssertionOutcome> {
+    let mut category_scores = Vec::new();
+    for category in JudgeCategory::ALL {
+        let re = regex::Regex::new(&format!(r"<{0}>(\d+)</{0}>", category.tag()))?;
+        if let Some(score) = re
+            .captures(&output)
+            .and_then(|captures| captures[1].parse::<usize>().ok())
+        {
+            category_scores.push((category, score.min(100)));
+        }
     }
+
+    let score = if category_scores.is_empty() {
+        let re = regex::Regex::new(r"<score>(\d+)</score>")?;
+        let Some(captures) = re.captures(&output) else {
+            anyhow::bail!("No score found in response. Raw output: {output}");
+        };
+        captures[1].parse().unwrap_or(0)
+    } else {
+        let total_weight = category_scores
+            .iter()
+            .map(|(category, _)| category.weight())
+            .sum::<usize>();
+        let weighted_score = category_scores
+            .iter()
+            .map(|(category, score)| category.weight() * score)
+            .sum::<usize>();
+        weighted_score / total_weight
+    };
+
+    Ok(EvalAssertionOutcome {
+        score,
+        category_scores,
+        message: Some(output),
+    })
 }
 
-#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
 struct EvalAssertionOutcome {
     score: usize,
+    category_scores: Vec<(JudgeCategory, usize)>,
     message: Option<String>,
 }
 
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+enum JudgeCategory {
+    Correctness,
+    Minimality,
+    StylePreservation,
+}
+
+impl JudgeCategory {
+    const ALL: [Self; 3] = [Self::Correctness, Self::Minimality, Self::StylePreservation];
+
+    fn tag(&self) -> &'static str {
+        match self {
+            Self::Correctness => "correctness",
+            Self::Minimality => "minimality",
+            Self::StylePreservation => "style_preservation",
+        }
+    }
+
+    /// Correctness counts double, so that a minimal but wrong edit can't pass.
+    fn weight(&self) -> usize {
+        match self {
+            Self::Correctness => 2,
+            Self::Minimality | Self::StylePreservation => 1,
+        }
+    }
+}
+
+impl Display for JudgeCategory {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Correctness => write!(f, "Correctness"),
+            Self::Minimality => write!(f, "Minimality"),
+            Self::StylePreservation => write!(f, "Style Preservation"),
+        }
+    }
+}
+
 #[derive(Serialize)]
 pub struct DiffJudgeTemplate {
     diff: String,
@@ -1613,9 +2047,58 @@ impl Template for DiffJudgeTemplate {
     const TEMPLATE_NAME: &'static str = "diff_judge.hbs";
 }
 
+#[test]
+fn test_parse_judge_output() {
+    let outcome = parse_judge_output(
+        indoc! {"
+            <analysis>
+            - Assertion 1: passes
+            </analysis>
+            <correctness>100</correctness>
+            <minimality>60</minimality>
+            <style_preservation>80</style_preservation>
+        "}
+        .into(),
+    )
+    .unwrap();
+    assert_eq!(outcome.score, 85);
+    assert_eq!(
+        outcome.category_scores,
+        vec![
+            (JudgeCategory::Correctness, 100),
+            (JudgeCategory::Minimality, 60),
+            (JudgeCategory::StylePreservation, 80),
+        ]
+    );
+
+    let outcome = parse_judge_output("<score>75</score>".into()).unwrap();
+    assert_eq!(outcome.score, 75);
+    assert!(outcome.category_scores.is_empty());
+
+    assert!(parse_judge_output("no score".into()).is_err());
+}
+
 fn strip_empty_lines(text: &str) -> String {
     text.lines()
         .filter(|line| !line.trim().is_empty())
         .collect::<Vec<_>>()
         .join("\n")
 }
+
+#[test]
+fn test_eval_verdict() {
+    let (lower_bound, upper_bound) = pass_ratio_interval(93, 100);
+    assert!((lower_bound - 0.8429).abs() < 0.001, "{lower_bound}");
+    assert!((upper_bound - 0.9705).abs() < 0.001, "{upper_bound}");
+    assert_eq!(pass_ratio_interval(0, 0), (0., 1.));
+    assert_eq!(pass_ratio_interval(10, 10).1, 1.);
+
+    assert_eq!(EvalVerdict::new(95, 100, 0.95), EvalVerdict::Passing);
+    assert_eq!(EvalVerdict::new(93, 100, 0.95), EvalVerdict::Flaky);
+    // An uncorrected 95% interval would already call this failing.
+    assert_eq!(EvalVerdict::new(90, 100, 0.95), EvalVerdict::Flaky);
+    assert_eq!(EvalVerdict::new(85, 100, 0.95), EvalVerdict::Failing);
+    // The same observed ratio becomes significant with more iterations.
+    assert_eq!(EvalVerdict::new(930, 1000, 0.95), EvalVerdict::Failing);
+    assert_eq!(EvalVerdict::new(0, 0, 0.95), EvalVerdict::Flaky);
+}
@@ -1,5 +1,7 @@
+use super::EditMatching;
+use agent_settings::EditMatchingMode;
 use language::{Point, TextBufferSnapshot};
-use std::{cmp, ops::Range};
+use std::{cmp, mem, ops::Range};
 
 const REPLACEMENT_COST: u32 = 1;
 const INSERTION_COST: u32 = 3;
@@ -13,6 +15,7 @@ pub struct StreamingFuzzyMatcher {
     incomplete_line: String,
     best_matches: Vec<Range<usize>>,
     matrix: SearchMatrix,
+    matching: EditMatching,
 }
 
 impl StreamingFuzzyMatcher {
@@ -24,9 +27,15 @@ impl StreamingFuzzyMatcher {
             incomplete_line: String::new(),
             best_matches: Vec::new(),
             matrix: SearchMatrix::new(buffer_line_count + 1),
+            matching: EditMatching::default(),
         }
     }
 
+    pub fn with_matching(mut self, matching: EditMatching) -> Self {
+        self.matching = matching;
+        self
+    }
+
     /// Returns the query lines.
     pub fn query_lines(&self) -> &[String] {
         &self.query_lines
@@ -74,7 +83,8 @@ impl StreamingFuzzyMatcher {
     /// Finish processing and return the final best match(es).
     ///
     /// This processes any remaining incomplete line before returning the final
-    /// match result.
+    /// match result. When several locations match equally well, only the ones
+    /// whose lines agree best with the query's, indentation included, are kept.
     pub fn finish(&mut self) -> Vec<Range<usize>> {
         // Process any remaining incomplete line
         if !self.incomplete_line.is_empty() {
@@ -82,9 +92,69 @@ impl StreamingFuzzyMatcher {
             self.incomplete_line.clear();
             self.best_matches = self.resolve_location_fuzzy();
         }
+        self.best_matches = self.disambiguate(mem::take(&mut self.best_matches));
         self.best_matches.clone()
     }
 
+    fn disambiguate(&self, matches: Vec<Range<usize>>) -> Vec<Range<usize>> {
+        if matches.len() < 2 {
+            return matches;
+        }
+
+        let scores = matches
+            .iter()
+            .map(|range| self.context_score(range))
+            .collect::<Vec<_>>();
+        let best_score = scores.iter().copied().max().unwrap_or_default();
+        matches
+            .into_iter()
+            .zip(scores)
+            .filter(|(_, score)| *score == best_score)
+            .map(|(range, _)| range)
+            .collect()
+    }
+
+    /// Returns whether each query line is identical to the corresponding line
+    /// of `range`, apart from leading and trailing whitespace.
+    pub fn is_exact_match(&self, range: &Range<usize>) -> bool {
+        let start_row = self.snapshot.offset_to_point(range.start).row;
+        let end_row = self.snapshot.offset_to_point(range.end).row;
+        self.query_lines.len() == (end_row - start_row + 1) as usize
+            && self
+                .query_lines
+                .iter()
+                .zip(start_row..=end_row)
+                .all(|(query_line, row)| query_line.trim() == self.buffer_line(row).trim())
+    }
+
+    fn buffer_line(&self, row: u32) -> String {
+        self.snapshot
+            .text_for_range(Point::new(row, 0)..Point::new(row, self.snapshot.line_len(row)))
+            .collect()
+    }
+
+    /// Scores how closely the lines of a match agree with the query lines,
+    /// since matching trims whitespace and so can't tell apart the same code at
+    /// different nesting levels.
+    fn context_score(&self, range: &Range<usize>) -> usize {
+        let start_row = self.snapshot.offset_to_point(range.start).row;
+        let end_row = self.snapshot.offset_to_point(range.end).row;
+        self.query_lines
+            .iter()
+            .zip(start_row..=end_row)
+            .map(|(query_line, row)| {
+                let buffer_line = self.buffer_line(row);
+                if *query_line == buffer_line {
+                    2
+                } else if leading_whitespace(query_line) == leading_whitespace(&buffer_line) {
+                    1
+                } else {
+                    0
+                }
+            })
+            .sum()
+    }
+
     fn resolve_location_fuzzy(&mut self) -> Vec<Range<usize>> {
         let new_query_line_count = self.query_lines.len();
         let old_query_line_count = self.matrix.rows.saturating_sub(1);
@@ -126,7 +196,10 @@ impl StreamingFuzzyMatcher {
                 let diagonal = SearchState::new(
                     if query_line == buffer_line {
                         self.matrix.get(row, col).cost
-                    } else if fuzzy_eq(query_line, buffer_line) {
+                    } else if self.matching.mode == EditMatchingMode::Fuzzy
+                        && (eq_ignoring_formatting(query_line, buffer_line)
+                            || fuzzy_eq(query_line, buffer_line))
+                    {
                         self.matrix.get(row, col).cost + REPLACEMENT_COST
                     } else {
                         self.matrix
@@ -184,7 +257,7 @@ impl StreamingFuzzyMatcher {
             let matched_buffer_row_count = buffer_row_end - buffer_row_start;
             let matched_ratio = matched_lines as f32
                 / (matched_buffer_row_count as f32).max(new_query_line_count as f32);
-            if matched_ratio >= 0.8 {
+            if matched_ratio >= self.matching.min_confidence {
                 let buffer_start_ix = self
                     .snapshot
                     .point_to_offset(Point::new(buffer_row_start, 0));
@@ -200,6 +273,20 @@ impl StreamingFuzzyMatcher {
     }
 }
 
+fn leading_whitespace(line: &str) -> &str {
+    &line[..line.len() - line.trim_start().len()]
+}
+
+/// Compares lines ignoring whitespace and trailing commas, which models often
+/// get wrong when reproducing code.
+fn eq_ignoring_formatting(left: &str, right: &str) -> bool {
+    let left = left.trim_end_matches(',');
+    let right = right.trim_end_matches(',');
+    left.chars()
+        .filter(|c| !c.is_whitespace())
+        .eq(right.chars().filter(|c| !c.is_whitespace()))
+}
+
 fn fuzzy_eq(left: &str, right: &str) -> bool {
     const THRESHOLD: f64 = 0.8;
 
@@ -640,6 +727,78 @@ mod tests {
         );
     }
 
+    #[gpui::test(iterations = 100)]
+    fn test_resolve_location_by_indentation(mut rng: StdRng) {
+        assert_location_resolution(
+            indoc! {"
+                fn outer() {
+                    if ready {
+                        run();
+                    }
+                «        if ready {
+                            run();
+                        }»
+                }
+            "},
+            concat!("        if ready {\n", "            run();\n", "        }",),
+            &mut rng,
+        );
+    }
+
+    #[test]
+    fn test_matching_modes() {
+        let buffer = TextBuffer::new(
+            0,
+            BufferId::new(1).unwrap(),
+            "let values = vec![\n    first(a,b),\n    second(c),\n];",
+        );
+        let snapshot = buffer.snapshot();
+        let query = "let values = vec![\n    first(a, b),\n    second(c)\n];";
+
+        let mut matcher = StreamingFuzzyMatcher::new(snapshot.clone());
+        matcher.push(query);
+        let matches = matcher.finish();
+        assert_eq!(matches, vec![0..snapshot.len()]);
+        assert!(!matcher.is_exact_match(&matches[0]));
+
+        let mut matcher =
+            StreamingFuzzyMatcher::new(snapshot.clone()).with_matching(EditMatching {
+                mode: EditMatchingMode::Strict,
+                min_confidence: 0.8,
+            });
+        matcher.push(query);
+        assert_eq!(matcher.finish(), Vec::<Range<usize>>::new());
+
+        let mut matcher =
+            StreamingFuzzyMatcher::new(snapshot.clone()).with_matching(EditMatching {
+                mode: EditMatchingMode::Strict,
+                min_confidence: 0.8,
+            });
+        matcher.push(&snapshot.text());
+        let matches = matcher.finish();
+        assert_eq!(matches, vec![0..snapshot.len()]);
+        assert!(matcher.is_exact_match(&matches[0]));
+    }
+
+    #[test]
+    fn test_ambiguous_matches() {
+        let buffer = TextBuffer::new(
+            0,
+            BufferId::new(1).unwrap(),
+            "fn a() {\n    return 42;\n}\nfn b() {\n    return 42;\n}",
+        );
+        let snapshot = buffer.snapshot();
+
+        let mut matcher = StreamingFuzzyMatcher::new(snapshot.clone());
+        matcher.push("    return 42;\n");
+        let matches = matcher
+            .finish()
+            .into_iter()
+            .map(|range| snapshot.offset_to_point(range.start).row)
+            .collect::<Vec<_>>();
+        assert_eq!(matches, vec![1, 4]);
+    }
+
     #[track_caller]
     fn assert_location_resolution(text_with_expected_range: &str, query: &str, rng: &mut StdRng) {
         let (text, expected_ranges) = marked_text_ranges(text_with_expected_range, false);
@@ -1,6 +1,6 @@
 use crate::{
     Templates,
-    edit_agent::{EditAgent, EditAgentOutput, EditAgentOutputEvent},
+    edit_agent::{EditAgent, EditAgentOutput, EditAgentOutputEvent, EditMatching},
     schema::json_schema_for,
     ui::{COLLAPSED_LINES, ToolOutputPreview},
 };
@@ -13,8 +13,8 @@ use buffer_diff::{BufferDiff, BufferDiffSnapshot};
 use editor::{Editor, EditorMode, MinimapVisibility, MultiBuffer, PathKey};
 use futures::StreamExt;
 use gpui::{
-    Animation, AnimationExt, AnyWindowHandle, App, AppContext, AsyncApp, Entity, Task,
-    TextStyleRefinement, WeakEntity, pulsating_between, px,
+    Animation, AnimationExt, AnyWindowHandle, App, AppContext, AsyncApp, Entity, Subscription,
+    Task, TextStyleRefinement, WeakEntity, pulsating_between, px,
 };
 use indoc::formatdoc;
 use language::{
@@ -34,7 +34,7 @@ use settings::Settings;
 use std::{
     cmp::Reverse,
     collections::HashSet,
-    ops::Range,
+    ops::{Range, RangeInclusive},
     path::{Path, PathBuf},
     sync::Arc,
     time::Duration,
@@ -133,6 +133,10 @@ impl Tool for EditFileTool {
         true
     }
 
+    fn supports_plan_only(&self) -> bool {
+        true
+    }
+
     fn description(&self) -> String {
         include_str!("edit_file_tool/description.md").to_string()
     }
@@ -192,7 +196,13 @@ impl Tool for EditFileTool {
             window
                 .update(cx, |_, window, cx| {
                     cx.new(|cx| {
-                        EditFileToolCard::new(input.path.clone(), project.clone(), window, cx)
+                        EditFileToolCard::new(
+                            input.path.clone(),
+                            project.clone(),
+                            Some(action_log.clone()),
+                            window,
+                            cx,
+                        )
                     })
                 })
                 .ok()
@@ -200,15 +210,19 @@ impl Tool for EditFileTool {
 
         let card_clone = card.clone();
         let action_log_clone = action_log.clone();
+        let matching = EditMatching::from_settings(cx);
         let task = cx.spawn(async move |cx: &mut AsyncApp| {
             let edit_agent =
-                EditAgent::new(model, project.clone(), action_log_clone, Templates::new());
+                EditAgent::new(model, project.clone(), action_log_clone, Templates::new())
+                    .with_matching(matching);
 
             let buffer = project
                 .update(cx, |project, cx| {
                     project.open_buffer(project_path.clone(), cx)
                 })?
                 .await?;
+            let buffer = action_log.update(cx, |log, cx| log.buffer_to_edit(buffer.clone(), cx))?;
+            let is_planned = action_log.read_with(cx, |log, _| log.is_planned(&buffer))?;
 
             let old_snapshot = buffer.read_with(cx, |buffer, _cx| buffer.snapshot())?;
             let old_text = cx
@@ -239,7 +253,7 @@ impl Tool for EditFileTool {
             };
 
             let mut hallucinated_old_text = false;
-            let mut ambiguous_ranges = Vec::new();
+            let mut ambiguous_rows = Vec::new();
             while let Some(event) = events.next().await {
                 match event {
                     EditAgentOutputEvent::Edited => {
@@ -248,7 +262,17 @@ impl Tool for EditFileTool {
                         }
                     }
                     EditAgentOutputEvent::UnresolvedEditRange => hallucinated_old_text = true,
-                    EditAgentOutputEvent::AmbiguousEditRange(ranges) => ambiguous_ranges = ranges,
+                    EditAgentOutputEvent::AmbiguousEditRange(ranges) => {
+                        ambiguous_rows = buffer.read_with(cx, |buffer, _| {
+                            ranges
+                                .into_iter()
+                                .map(|range| {
+                                    buffer.offset_to_point(range.start).row
+                                        ..=buffer.offset_to_point(range.end).row
+                                })
+                                .collect::<Vec<_>>()
+                        })?;
+                    }
                     EditAgentOutputEvent::ResolvingEditRange(range) => {
                         if let Some(card) = card_clone.as_ref() {
                             card.update(cx, |card, cx| card.reveal_range(range, cx))?;
@@ -270,7 +294,7 @@ impl Tool for EditFileTool {
                 })
                 .unwrap_or(false);
 
-            if format_on_save_enabled {
+            if format_on_save_enabled && !is_planned {
                 let format_task = project.update(cx, |project, cx| {
                     project.format(
                         HashSet::from_iter([buffer.clone()]),
@@ -283,9 +307,11 @@ impl Tool for EditFileTool {
                 format_task.await.log_err();
             }
 
-            project
-                .update(cx, |project, cx| project.save_buffer(buffer.clone(), cx))?
-                .await?;
+            if !is_planned {
+                project
+                    .update(cx, |project, cx| project.save_buffer(buffer.clone(), cx))?
+                    .await?;
+            }
 
             // Notify the action log that we've edited the buffer (*after* formatting has completed).
             action_log.update(cx, |log, cx| {
@@ -332,27 +358,27 @@ impl Tool for EditFileTool {
                     "}
                 );
                 anyhow::ensure!(
-                    ambiguous_ranges.is_empty(),
-                    // TODO: Include ambiguous_ranges, converted to line numbers.
-                    //       This would work best if we add `line_hint` parameter
-                    //       to edit_file_tool
-                    formatdoc! {"
-                        <old_text> matches more than one position in the file. Read the
-                        relevant sections of {input_path} again and extend <old_text> so
-                        that I can perform the requested edits.
-                    "}
+                    ambiguous_rows.is_empty(),
+                    ambiguous_old_text_error(&input_path.to_string(), &ambiguous_rows)
                 );
                 Ok(ToolResultOutput {
                     content: ToolResultContent::Text("No edits were made.".into()),
                     output: serde_json::to_value(output).ok(),
+                    truncated: false,
                 })
             } else {
+                let verb = if is_planned {
+                    "Planned edits to"
+                } else {
+                    "Edited"
+                };
                 Ok(ToolResultOutput {
                     content: ToolResultContent::Text(format!(
-                        "Edited {}:\n\n```diff\n{}\n```",
+                        "{verb} {}:\n\n```diff\n{}\n```",
                         input_path, diff
                     )),
                     output: serde_json::to_value(output).ok(),
+                    truncated: false,
                 })
             }
         });
@@ -376,7 +402,13 @@ impl Tool for EditFileTool {
         };
 
         let card = cx.new(|cx| {
-            EditFileToolCard::new(output.original_path.clone(), project.clone(), window, cx)
+            EditFileToolCard::new(
+                output.original_path.clone(),
+                project.clone(),
+                None,
+                window,
+                cx,
+            )
         });
 
         cx.spawn({
@@ -487,6 +519,28 @@ fn resolve_path(
     }
 }
 
+/// Lists the rows `<old_text>` matched (zero-based, as they come from the
+/// buffer) as line numbers, so the model can tell which match it meant.
+fn ambiguous_old_text_error(input_path: &str, rows: &[RangeInclusive<u32>]) -> String {
+    let mut message = format!(
+        "<old_text> matches more than one position in {input_path}, \
+        even after comparing indentation:\n"
+    );
+    for rows in rows {
+        let (start, end) = (rows.start() + 1, rows.end() + 1);
+        if start == end {
+            message.push_str(&format!("- line {start}\n"));
+        } else {
+            message.push_str(&format!("- lines {start}-{end}\n"));
+        }
+    }
+    message.push_str(
+        "Read the relevant sections of the file again and extend <old_text> \
+        so that it only matches one of these positions.",
+    );
+    message
+}
+
 pub struct EditFileToolCard {
     path: PathBuf,
     editor: Entity<Editor>,
@@ -501,10 +555,20 @@ pub struct EditFileToolCard {
     error_expanded: Option<Entity<Markdown>>,
     full_height_expanded: bool,
     total_lines: Option<u32>,
+    action_log: Option<Entity<ActionLog>>,
+    /// The buffer that was edited and the ranges the edits touched, once the edit has finished.
+    edits: Option<(Entity<Buffer>, Vec<Range<Anchor>>)>,
+    _action_log_subscription: Option<Subscription>,
 }
 
 impl EditFileToolCard {
-    pub fn new(path: PathBuf, project: Entity<Project>, window: &mut Window, cx: &mut App) -> Self {
+    pub fn new(
+        path: PathBuf,
+        project: Entity<Project>,
+        action_log: Option<Entity<ActionLog>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
         let multibuffer = cx.new(|_| MultiBuffer::without_headers(Capability::ReadOnly));
         let editor = cx.new(|cx| {
             let mut editor = Editor::new(
@@ -534,6 +598,10 @@ impl EditFileToolCard {
             editor.set_expand_all_diff_hunks(cx);
             editor
         });
+        // Keep/Reject buttons depend on whether the edits are still unreviewed.
+        let _action_log_subscription = action_log
+            .as_ref()
+            .map(|action_log| cx.observe(action_log, |_, _, cx| cx.notify()));
         Self {
             path,
             project,
@@ -546,8 +614,11 @@ impl EditFileToolCard {
             diff_task: None,
             preview_expanded: true,
             error_expanded: None,
-            full_height_expanded: true,
+            full_height_expanded: false,
             total_lines: None,
+            action_log,
+            edits: None,
+            _action_log_subscription,
         }
     }
 
@@ -685,6 +756,8 @@ impl EditFileToolCard {
             .take()
             .context("card was already finalized")?;
         let language_registry = self.project.read(cx).languages().clone();
+        let edited_buffer = buffer.clone();
+        let edited_snapshot = buffer.read(cx).text_snapshot();
 
         // Replace the buffer in the multibuffer with the snapshot
         let buffer = cx.new(|cx| {
@@ -711,6 +784,20 @@ impl EditFileToolCard {
         cx.spawn(async move |this, cx| {
             let buffer_diff = buffer_diff.await?;
             this.update(cx, |this, cx| {
+                // The snapshot has the same contents as the edited buffer at this point,
+                // so its hunks can be anchored in the edited buffer.
+                let snapshot = buffer.read(cx).snapshot();
+                let edited_ranges = buffer_diff
+                    .read(cx)
+                    .hunks_intersecting_range(Anchor::MIN..Anchor::MAX, &snapshot, cx)
+                    .map(|diff_hunk| {
+                        let range = diff_hunk.buffer_range.to_point(&snapshot);
+                        edited_snapshot.anchor_before(range.start)
+                            ..edited_snapshot.anchor_after(range.end)
+                    })
+                    .collect();
+                this.edits = Some((edited_buffer, edited_ranges));
+
                 this.multibuffer.update(cx, |multibuffer, cx| {
                     let path_key = PathKey::for_buffer(&buffer, cx);
                     multibuffer.clear(cx);
@@ -730,6 +817,47 @@ impl EditFileToolCard {
         .detach_and_log_err(cx);
         Ok(())
     }
+
+    fn has_unreviewed_edits(&self, cx: &App) -> bool {
+        let (Some(action_log), Some((buffer, ranges))) = (&self.action_log, &self.edits) else {
+            return false;
+        };
+        let Some(diff) = action_log.read(cx).changed_buffers(cx).remove(buffer) else {
+            return false;
+        };
+
+        let snapshot = buffer.read(cx).snapshot();
+        let diff = diff.read(cx);
+        ranges.iter().any(|range| {
+            diff.hunks_intersecting_range(range.clone(), &snapshot, cx)
+                .next()
+                .is_some()
+        })
+    }
+
+    fn keep_edits(&mut self, cx: &mut Context<Self>) {
+        let (Some(action_log), Some((buffer, ranges))) = (&self.action_log, &self.edits) else {
+            return;
+        };
+
+        action_log.update(cx, |action_log, cx| {
+            for range in ranges {
+                action_log.keep_edits_in_range(buffer.clone(), range.clone(), cx);
+            }
+        });
+    }
+
+    fn reject_edits(&mut self, cx: &mut Context<Self>) {
+        let (Some(action_log), Some((buffer, ranges))) = (&self.action_log, &self.edits) else {
+            return;
+        };
+
+        action_log
+            .update(cx, |action_log, cx| {
+                action_log.reject_edits_in_ranges(buffer.clone(), ranges.clone(), cx)
+            })
+            .detach_and_log_err(cx);
+    }
 }
 
 impl ToolCard for EditFileToolCard {
@@ -867,6 +995,36 @@ impl ToolCard for EditFileToolCard {
                         ),
                 )
             })
+            .when(
+                error_message.is_none() && self.has_unreviewed_edits(cx),
+                |header| {
+                    header.child(
+                        h_flex()
+                            .gap_1()
+                            .child(
+                                Button::new(
+                                    ("edit-file-reject", self.editor.entity_id()),
+                                    "Reject",
+                                )
+                                .label_size(LabelSize::Small)
+                                .tooltip(Tooltip::text("Reject This Edit"))
+                                .on_click(cx.listener(
+                                    |this, _event, _window, cx| {
+                                        this.reject_edits(cx);
+                                    },
+                                )),
+                            )
+                            .child(
+                                Button::new(("edit-file-keep", self.editor.entity_id()), "Keep")
+                                    .label_size(LabelSize::Small)
+                                    .tooltip(Tooltip::text("Keep This Edit"))
+                                    .on_click(cx.listener(|this, _event, _window, cx| {
+                                        this.keep_edits(cx);
+                                    })),
+                            ),
+                    )
+                },
+            )
             .when(error_message.is_none() && !self.is_loading(), |header| {
                 header.child(
                     Disclosure::new(
@@ -1257,6 +1415,18 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn test_ambiguous_old_text_error() {
+        assert_eq!(
+            ambiguous_old_text_error("root/src/lib.rs", &[1..=1, 4..=6]),
+            indoc::indoc! {"
+                <old_text> matches more than one position in root/src/lib.rs, even after comparing indentation:
+                - line 2
+                - lines 5-7
+                Read the relevant sections of the file again and extend <old_text> so that it only matches one of these positions."}
+        );
+    }
+
     #[test]
     fn still_streaming_ui_text_with_path() {
         let input = json!({
@@ -1330,6 +1500,7 @@ mod tests {
             cx.set_global(settings_store);
             language::init(cx);
             TelemetrySettings::register(cx);
+            agent_settings::AgentSettings::register(cx);
             Project::init_settings(cx);
         });
     }
@@ -136,6 +136,7 @@ impl Tool for FindPathTool {
                 Ok(ToolResultOutput {
                     content: ToolResultContent::Text(message),
                     output: Some(serde_json::to_value(output)?),
+                    truncated: false,
                 })
             }
         });
@@ -0,0 +1,444 @@
+use crate::schema::json_schema_for;
+use anyhow::{Result, anyhow};
+use assistant_tool::{ActionLog, Tool, ToolResult};
+use gpui::{AnyWindowHandle, App, AsyncApp, Entity, Task};
+use language::BufferSnapshot;
+use language_model::{LanguageModel, LanguageModelRequest, LanguageModelToolSchemaFormat};
+use project::Project;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt::Write as _, ops::Range, sync::Arc};
+use ui::IconName;
+use util::markdown::MarkdownInlineCode;
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct FindReplaceMultiToolInput {
+    /// A one-line, user-friendly markdown description of the change.
+    ///
+    /// <example>Rename `Config::load` to `Config::read`</example>
+    pub display_description: String,
+
+    /// The edits to apply.
+    ///
+    /// Edits to the same file are all matched against its original contents,
+    /// so they must not overlap.
+    pub edits: Vec<FindReplaceEdit>,
+
+    /// When true, only report whether each edit matches, without changing any files.
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct FindReplaceEdit {
+    /// The relative path of the file to edit.
+    ///
+    /// This path should never be absolute, and the first component
+    /// of the path should always be a root directory in a project.
+    pub path: String,
+
+    /// The exact text to find. It must appear exactly once in the file.
+    pub old_text: String,
+
+    /// The text to replace `old_text` with.
+    pub new_text: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum EditStatus {
+    Matched { range: Range<usize>, line: u32 },
+    PathNotFound,
+    EmptyOldText,
+    NotFound,
+    Ambiguous { occurrences: usize },
+    Overlapping { other_edit: usize },
+}
+
+impl EditStatus {
+    fn is_matched(&self) -> bool {
+        matches!(self, EditStatus::Matched { .. })
+    }
+}
+
+pub struct FindReplaceMultiTool;
+
+impl Tool for FindReplaceMultiTool {
+    fn name(&self) -> String {
+        "find_replace_multi".into()
+    }
+
+    fn needs_confirmation(&self, _: &serde_json::Value, _: &App) -> bool {
+        false
+    }
+
+    fn may_perform_edits(&self) -> bool {
+        true
+    }
+
+    fn supports_plan_only(&self) -> bool {
+        true
+    }
+
+    fn description(&self) -> String {
+        include_str!("./find_replace_multi_tool/description.md").into()
+    }
+
+    fn icon(&self) -> IconName {
+        IconName::Replace
+    }
+
+    fn input_schema(&self, format: LanguageModelToolSchemaFormat) -> Result<serde_json::Value> {
+        json_schema_for::<FindReplaceMultiToolInput>(format)
+    }
+
+    fn ui_text(&self, input: &serde_json::Value) -> String {
+        match serde_json::from_value::<FindReplaceMultiToolInput>(input.clone()) {
+            Ok(input) if input.dry_run => format!("{} (dry run)", input.display_description),
+            Ok(input) => input.display_description,
+            Err(_) => "Find and replace".to_string(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: serde_json::Value,
+        _request: Arc<LanguageModelRequest>,
+        project: Entity<Project>,
+        action_log: Entity<ActionLog>,
+        _model: Arc<dyn LanguageModel>,
+        _window: Option<AnyWindowHandle>,
+        cx: &mut App,
+    ) -> ToolResult {
+        let input = match serde_json::from_value::<FindReplaceMultiToolInput>(input) {
+            Ok(input) => input,
+            Err(err) => return Task::ready(Err(anyhow!(err))).into(),
+        };
+        if input.edits.is_empty() {
+            return Task::ready(Err(anyhow!("No edits were provided."))).into();
+        }
+
+        cx.spawn(async move |cx: &mut AsyncApp| {
+            let mut edits_by_path = BTreeMap::<&str, Vec<usize>>::new();
+            for (ix, edit) in input.edits.iter().enumerate() {
+                edits_by_path.entry(&edit.path).or_default().push(ix);
+            }
+
+            let mut buffers = Vec::new();
+            for (path, edit_ixs) in edits_by_path {
+                let Some(project_path) =
+                    project.read_with(cx, |project, cx| project.find_project_path(path, cx))?
+                else {
+                    continue;
+                };
+                let open_buffer =
+                    project.update(cx, |project, cx| project.open_buffer(project_path, cx))?;
+                if let Ok(buffer) = open_buffer.await {
+                    let buffer =
+                        action_log.update(cx, |log, cx| log.buffer_to_edit(buffer, cx))?;
+                    buffers.push((buffer, edit_ixs));
+                }
+            }
+
+            // Match and apply the edits without yielding, so that no buffer can change
+            // between validating the edits and applying them.
+            let (statuses, edited_buffers) = cx.update(|cx| {
+                let mut statuses = vec![EditStatus::PathNotFound; input.edits.len()];
+                for (buffer, edit_ixs) in &buffers {
+                    let snapshot = buffer.read(cx).snapshot();
+                    for (ix, status) in match_edits(&snapshot, &input.edits, edit_ixs) {
+                        statuses[ix] = status;
+                    }
+                }
+
+                if input.dry_run || !statuses.iter().all(EditStatus::is_matched) {
+                    return (statuses, Vec::new());
+                }
+
+                let mut edited_buffers = Vec::new();
+                for (buffer, edit_ixs) in buffers {
+                    let mut edits = edit_ixs
+                        .iter()
+                        .filter_map(|ix| match &statuses[*ix] {
+                            EditStatus::Matched { range, .. } => {
+                                Some((range.clone(), input.edits[*ix].new_text.clone()))
+                            }
+                            _ => None,
+                        })
+                        .collect::<Vec<_>>();
+                    edits.sort_by_key(|(range, _)| range.start);
+
+                    action_log.update(cx, |log, cx| log.buffer_read(buffer.clone(), cx));
+                    buffer.update(cx, |buffer, cx| buffer.edit(edits, None, cx));
+                    action_log.update(cx, |log, cx| log.buffer_edited(buffer.clone(), cx));
+                    edited_buffers.push(buffer);
+                }
+                (statuses, edited_buffers)
+            })?;
+
+            let report = edit_report(&input.edits, &statuses);
+            let matched_count = statuses.iter().filter(|status| status.is_matched()).count();
+            let edit_count = input.edits.len();
+
+            if input.dry_run {
+                return Ok(format!(
+                    "Dry run: {matched_count} of {edit_count} edits matched. No files were changed.\n\n{report}"
+                )
+                .into());
+            }
+
+            if matched_count < edit_count {
+                return Err(anyhow!(
+                    "{} of {edit_count} edits did not match, so no files were changed.\n\n{report}",
+                    edit_count - matched_count
+                ));
+            }
+
+            let is_plan_only = action_log.read_with(cx, |log, _| log.is_plan_only())?;
+            if is_plan_only {
+                return Ok(format!(
+                    "Planned {edit_count} edits to {} files. No files were changed.\n\n{report}",
+                    edited_buffers.len()
+                )
+                .into());
+            }
+
+            for buffer in &edited_buffers {
+                project
+                    .update(cx, |project, cx| project.save_buffer(buffer.clone(), cx))?
+                    .await?;
+            }
+
+            Ok(format!(
+                "Applied {edit_count} edits to {} files.\n\n{report}",
+                edited_buffers.len()
+            )
+            .into())
+        })
+        .into()
+    }
+}
+
+/// Finds where each of the given edits applies in the buffer, rejecting edits
+/// that are ambiguous or that overlap an earlier edit to the same buffer.
+fn match_edits(
+    snapshot: &BufferSnapshot,
+    edits: &[FindReplaceEdit],
+    edit_ixs: &[usize],
+) -> Vec<(usize, EditStatus)> {
+    let text = snapshot.text();
+    let mut statuses = edit_ixs
+        .iter()
+        .map(|&ix| {
+            let old_text = edits[ix].old_text.as_str();
+            if old_text.is_empty() {
+                return (ix, EditStatus::EmptyOldText);
+            }
+
+            let mut matches = text.match_indices(old_text);
+            let status = match (matches.next(), matches.count()) {
+                (None, _) => EditStatus::NotFound,
+                (Some((start, _)), 0) => EditStatus::Matched {
+                    range: start..start + old_text.len(),
+                    line: snapshot.offset_to_point(start).row + 1,
+                },
+                (Some(_), remaining) => EditStatus::Ambiguous {
+                    occurrences: remaining + 1,
+                },
+            };
+            (ix, status)
+        })
+        .collect::<Vec<_>>();
+
+    let mut matched = statuses
+        .iter()
+        .filter_map(|(ix, status)| match status {
+            EditStatus::Matched { range, .. } => Some((*ix, range.clone())),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    matched.sort_by_key(|(_, range)| range.start);
+
+    let mut previous: Option<(usize, Range<usize>)> = None;
+    for (ix, range) in matched {
+        match &previous {
+            Some((previous_ix, previous_range)) if range.start < previous_range.end => {
+                if let Some((_, status)) = statuses.iter_mut().find(|(i, _)| *i == ix) {
+                    *status = EditStatus::Overlapping {
+                        other_edit: *previous_ix,
+                    };
+                }
+            }
+            _ => previous = Some((ix, range)),
+        }
+    }
+
+    statuses
+}
+
+fn edit_report(edits: &[FindReplaceEdit], statuses: &[EditStatus]) -> String {
+    let mut report = String::new();
+    for (ix, (edit, status)) in edits.iter().zip(statuses).enumerate() {
+        let path = MarkdownInlineCode(&edit.path);
+        let status = match status {
+            EditStatus::Matched { line, .. } => format!("matched at line {line}"),
+            EditStatus::PathNotFound => "file was not found in the project".to_string(),
+            EditStatus::EmptyOldText => "`old_text` is empty".to_string(),
+            EditStatus::NotFound => "`old_text` was not found in the file".to_string(),
+            EditStatus::Ambiguous { occurrences } => format!(
+                "`old_text` matched {occurrences} times; include more surrounding text to make it unique"
+            ),
+            EditStatus::Overlapping { other_edit } => {
+                format!("overlaps with edit {}", other_edit + 1)
+            }
+        };
+        writeln!(report, "{}. {path}: {status}", ix + 1).ok();
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fs::{FakeFs, Fs};
+    use gpui::{AppContext, TestAppContext};
+    use language_model::fake_provider::FakeLanguageModel;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use util::path;
+
+    #[gpui::test]
+    async fn test_find_replace_multi(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/root"),
+            json!({
+                "a.rs": "fn load() {}\nfn main() { load(); }\n",
+                "b.rs": "use crate::load;\n",
+            }),
+        )
+        .await;
+        let project = Project::test(fs.clone(), [path!("/root").as_ref()], cx).await;
+        let action_log = cx.new(|_| ActionLog::new(project.clone()));
+
+        let result = run_tool(
+            json!({
+                "display_description": "Rename `load` to `read`",
+                "edits": [
+                    { "path": "root/a.rs", "old_text": "fn load()", "new_text": "fn read()" },
+                    { "path": "root/a.rs", "old_text": "load();", "new_text": "read();" },
+                    { "path": "root/b.rs", "old_text": "load", "new_text": "read" },
+                ]
+            }),
+            project.clone(),
+            action_log.clone(),
+            cx,
+        )
+        .await
+        .unwrap();
+        assert!(result.starts_with("Applied 3 edits to 2 files."));
+        assert_eq!(
+            fs.load(path!("/root/a.rs").as_ref()).await.unwrap(),
+            "fn read() {}\nfn main() { read(); }\n"
+        );
+        assert_eq!(
+            fs.load(path!("/root/b.rs").as_ref()).await.unwrap(),
+            "use crate::read;\n"
+        );
+        cx.read(|cx| assert_eq!(action_log.read(cx).changed_buffers(cx).len(), 2));
+    }
+
+    #[gpui::test]
+    async fn test_find_replace_multi_mismatch(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/root"),
+            json!({
+                "a.rs": "fn load() {}\nfn main() { load(); }\n",
+            }),
+        )
+        .await;
+        let project = Project::test(fs.clone(), [path!("/root").as_ref()], cx).await;
+        let action_log = cx.new(|_| ActionLog::new(project.clone()));
+        let edits = json!([
+            { "path": "root/a.rs", "old_text": "fn load()", "new_text": "fn read()" },
+            { "path": "root/a.rs", "old_text": "load", "new_text": "read" },
+            { "path": "root/a.rs", "old_text": "fn unload()", "new_text": "fn unread()" },
+            { "path": "root/missing.rs", "old_text": "load", "new_text": "read" },
+        ]);
+
+        let dry_run = run_tool(
+            json!({
+                "display_description": "Rename `load` to `read`",
+                "edits": edits.clone(),
+                "dry_run": true,
+            }),
+            project.clone(),
+            action_log.clone(),
+            cx,
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            dry_run,
+            concat!(
+                "Dry run: 1 of 4 edits matched. No files were changed.\n\n",
+                "1. `root/a.rs`: matched at line 1\n",
+                "2. `root/a.rs`: `old_text` matched 2 times; include more surrounding text to make it unique\n",
+                "3. `root/a.rs`: `old_text` was not found in the file\n",
+                "4. `root/missing.rs`: file was not found in the project\n",
+            )
+        );
+
+        let error = run_tool(
+            json!({
+                "display_description": "Rename `load` to `read`",
+                "edits": edits,
+            }),
+            project.clone(),
+            action_log.clone(),
+            cx,
+        )
+        .await
+        .unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .starts_with("3 of 4 edits did not match, so no files were changed.")
+        );
+        assert_eq!(
+            fs.load(path!("/root/a.rs").as_ref()).await.unwrap(),
+            "fn load() {}\nfn main() { load(); }\n"
+        );
+        cx.read(|cx| assert!(action_log.read(cx).changed_buffers(cx).is_empty()));
+    }
+
+    async fn run_tool(
+        input: serde_json::Value,
+        project: Entity<Project>,
+        action_log: Entity<ActionLog>,
+        cx: &mut TestAppContext,
+    ) -> Result<String> {
+        let model = Arc::new(FakeLanguageModel::default());
+        let output = cx
+            .update(|cx| {
+                Arc::new(FindReplaceMultiTool)
+                    .run(input, Arc::default(), project, action_log, model, None, cx)
+                    .output
+            })
+            .await?;
+        Ok(output.content.as_str().unwrap_or_default().to_string())
+    }
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            language::init(cx);
+            Project::init_settings(cx);
+        });
+    }
+}
@@ -0,0 +1,608 @@
+use crate::schema::json_schema_for;
+use anyhow::{Context as _, Result, anyhow};
+use assistant_tool::{ActionLog, Tool, ToolResult, ToolResultContent, ToolResultOutput};
+use git::{
+    repository::{CommitDiff, RepoPath},
+    status::{FileStatus, StageStatus},
+};
+use gpui::{AnyWindowHandle, App, AppContext as _, AsyncApp, Entity, Task};
+use language_model::{LanguageModel, LanguageModelRequest, LanguageModelToolSchemaFormat};
+use project::{Project, ProjectPath, git_store::Repository};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::{ops::Range, sync::Arc};
+use ui::IconName;
+use util::markdown::MarkdownInlineCode;
+
+const MAX_STATUS_FILES: usize = 200;
+const DEFAULT_LOG_LIMIT: usize = 20;
+/// Patches are only included in `show` results while their combined size stays below this.
+const MAX_SHOW_PATCH_BYTES: usize = 16 * 1024;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum GitQuery {
+    /// Lists the files with uncommitted changes.
+    Status,
+    /// Lists the commits that changed a file.
+    Log,
+    /// Shows the commit that last changed each line of a file.
+    Blame,
+    /// Shows a commit's message and the files it changed.
+    Show,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct GitToolInput {
+    /// The query to run.
+    pub query: GitQuery,
+
+    /// The relative path of a file or directory in the project.
+    ///
+    /// Required for `log` and `blame`. For `status`, only changes within this path are listed.
+    ///
+    /// <example>
+    /// If the project has the following root directories:
+    ///
+    /// - directory1
+    /// - directory2
+    ///
+    /// You can query `directory1/src/main.rs` by providing a path of "directory1/src/main.rs".
+    /// </example>
+    #[serde(default)]
+    pub path: Option<String>,
+
+    /// The one-based line number to start blaming from. Defaults to the first line.
+    #[serde(default)]
+    pub start_line: Option<u32>,
+
+    /// The one-based, inclusive line number to stop blaming at. Defaults to the last line.
+    #[serde(default)]
+    pub end_line: Option<u32>,
+
+    /// The commit to show, as a SHA or any other revision git understands, like `HEAD~1`.
+    /// Required for `show`.
+    #[serde(default)]
+    pub commit: Option<String>,
+
+    /// The maximum number of commits returned by `log`. Defaults to 20.
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct StatusOutput {
+    repository: String,
+    branch: Option<String>,
+    files: Vec<StatusFile>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    truncated: bool,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct StatusFile {
+    path: String,
+    change: ChangeKind,
+    staged: Staged,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum ChangeKind {
+    Added,
+    Modified,
+    Deleted,
+    Untracked,
+    Conflicted,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Staged {
+    Yes,
+    No,
+    Partially,
+}
+
+#[derive(Debug, Serialize)]
+struct LogOutput {
+    commits: Vec<LogCommit>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    truncated: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct LogCommit {
+    commit: String,
+    date: Option<String>,
+    subject: String,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct BlameLines {
+    /// One-based and inclusive.
+    lines: [u32; 2],
+    commit: String,
+    author: Option<String>,
+    date: Option<String>,
+    summary: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct ShowOutput {
+    commit: String,
+    author: String,
+    date: Option<String>,
+    message: String,
+    files: Vec<ShowFile>,
+}
+
+#[derive(Debug, Serialize)]
+struct ShowFile {
+    path: String,
+    change: ChangeKind,
+    insertions: usize,
+    deletions: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    patch: Option<String>,
+}
+
+pub struct GitTool;
+
+impl Tool for GitTool {
+    fn name(&self) -> String {
+        "git".into()
+    }
+
+    fn needs_confirmation(&self, _: &serde_json::Value, _: &App) -> bool {
+        false
+    }
+
+    fn may_perform_edits(&self) -> bool {
+        false
+    }
+
+    fn description(&self) -> String {
+        include_str!("./git_tool/description.md").into()
+    }
+
+    fn icon(&self) -> IconName {
+        IconName::GitBranch
+    }
+
+    fn input_schema(&self, format: LanguageModelToolSchemaFormat) -> Result<serde_json::Value> {
+        json_schema_for::<GitToolInput>(format)
+    }
+
+    fn ui_text(&self, input: &serde_json::Value) -> String {
+        match serde_json::from_value::<GitToolInput>(input.clone()) {
+            Ok(input) => match (input.query, input.path, input.commit) {
+                (GitQuery::Status, None, _) => "Git status".to_string(),
+                (GitQuery::Status, Some(path), _) => {
+                    format!("Git status of {}", MarkdownInlineCode(&path))
+                }
+                (GitQuery::Log, Some(path), _) => {
+                    format!("Git log of {}", MarkdownInlineCode(&path))
+                }
+                (GitQuery::Blame, Some(path), _) => {
+                    format!("Git blame of {}", MarkdownInlineCode(&path))
+                }
+                (GitQuery::Show, _, Some(commit)) => {
+                    format!("Show commit {}", MarkdownInlineCode(&commit))
+                }
+                _ => "Query git repository".to_string(),
+            },
+            Err(_) => "Query git repository".to_string(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: serde_json::Value,
+        _request: Arc<LanguageModelRequest>,
+        project: Entity<Project>,
+        _action_log: Entity<ActionLog>,
+        _model: Arc<dyn LanguageModel>,
+        _window: Option<AnyWindowHandle>,
+        cx: &mut App,
+    ) -> ToolResult {
+        let input = match serde_json::from_value::<GitToolInput>(input) {
+            Ok(input) => input,
+            Err(err) => return Task::ready(Err(anyhow!(err))).into(),
+        };
+        let (repository, path) = match find_repository(&project, input.path.as_deref(), cx) {
+            Ok(repository_and_path) => repository_and_path,
+            Err(err) => return Task::ready(Err(err)).into(),
+        };
+
+        match input.query {
+            GitQuery::Status => {
+                let repo_path = path.map(|(_, repo_path)| repo_path);
+                Task::ready(status(
+                    repository.read(cx),
+                    repo_path.as_ref(),
+                    project.read(cx),
+                    cx,
+                ))
+                .into()
+            }
+            GitQuery::Log => {
+                let Some((_, repo_path)) = path else {
+                    return Task::ready(Err(anyhow!("`log` requires a `path`"))).into();
+                };
+                let limit = input.limit.unwrap_or(DEFAULT_LOG_LIMIT);
+                let history =
+                    repository.update(cx, |repository, _| repository.file_history(repo_path));
+                cx.background_spawn(async move {
+                    let commits = history.await??;
+                    let output = LogOutput {
+                        truncated: commits.len() > limit,
+                        commits: commits
+                            .into_iter()
+                            .take(limit)
+                            .map(|commit| LogCommit {
+                                commit: commit.sha.to_string(),
+                                date: format_date(commit.commit_timestamp),
+                                subject: commit.subject.to_string(),
+                            })
+                            .collect(),
+                    };
+                    Ok(serde_json::to_string(&output)?.into())
+                })
+                .into()
+            }
+            GitQuery::Blame => {
+                let Some((project_path, _)) = path else {
+                    return Task::ready(Err(anyhow!("`blame` requires a `path`"))).into();
+                };
+                let start_line = input.start_line.unwrap_or(1).max(1);
+                let end_line = input.end_line.unwrap_or(u32::MAX);
+                if end_line < start_line {
+                    return Task::ready(Err(anyhow!("`end_line` must not be before `start_line`")))
+                        .into();
+                }
+                cx.spawn(async move |cx| {
+                    let buffer = project
+                        .update(cx, |project, cx| project.open_buffer(project_path, cx))?
+                        .await?;
+                    let blame = project
+                        .update(cx, |project, cx| {
+                            project.git_store().update(cx, |git_store, cx| {
+                                git_store.blame_buffer(&buffer, None, cx)
+                            })
+                        })?
+                        .await?
+                        .context("no blame is available for this file")?;
+                    let output = blame_lines(&blame, start_line - 1..end_line);
+                    Ok(serde_json::to_string(&output)?.into())
+                })
+                .into()
+            }
+            GitQuery::Show => {
+                let Some(commit) = input.commit else {
+                    return Task::ready(Err(anyhow!("`show` requires a `commit`"))).into();
+                };
+                // The revision is passed to `git show` as a positional argument, so anything
+                // that looks like an option (e.g. `--output=<file>`) must never reach it.
+                let commit = commit.trim().to_string();
+                if commit.is_empty() || commit.starts_with('-') {
+                    return Task::ready(Err(anyhow!(
+                        "`commit` must be a revision, not an option: {commit:?}"
+                    )))
+                    .into();
+                }
+                let (details, diff) = repository.update(cx, |repository, _| {
+                    (
+                        repository.show(commit.clone()),
+                        repository.load_commit_diff(commit),
+                    )
+                });
+                cx.spawn(async move |cx: &mut AsyncApp| {
+                    let details = details.await??;
+                    let diff = diff.await??;
+                    let files = cx.background_spawn(async move { show_files(diff) }).await;
+                    let output = ShowOutput {
+                        commit: details.sha.to_string(),
+                        author: format!("{} <{}>", details.author_name, details.author_email),
+                        date: format_date(details.commit_timestamp),
+                        message: details.message.trim_end().to_string(),
+                        files,
+                    };
+                    Ok(serde_json::to_string(&output)?.into())
+                })
+                .into()
+            }
+        }
+    }
+}
+
+/// Finds the repository containing `path`, or the active repository when no path is given.
+fn find_repository(
+    project: &Entity<Project>,
+    path: Option<&str>,
+    cx: &App,
+) -> Result<(Entity<Repository>, Option<(ProjectPath, RepoPath)>)> {
+    let project = project.read(cx);
+    let git_store = project.git_store().read(cx);
+    match path {
+        Some(path) => {
+            let project_path = project
+                .find_project_path(path, cx)
+                .filter(|project_path| project.entry_for_path(project_path, cx).is_some())
+                .ok_or_else(|| anyhow!("Path {path} not found in project"))?;
+            let (repository, repo_path) = git_store
+                .repository_and_path_for_project_path(&project_path, cx)
+                .ok_or_else(|| anyhow!("{path} isn't in a git repository"))?;
+            Ok((repository, Some((project_path, repo_path))))
+        }
+        None => {
+            let repository = git_store
+                .active_repository()
+                .context("The project doesn't contain a git repository")?;
+            Ok((repository, None))
+        }
+    }
+}
+
+fn status(
+    repository: &Repository,
+    within: Option<&RepoPath>,
+    project: &Project,
+    cx: &App,
+) -> Result<ToolResultOutput> {
+    let mut files = repository
+        .status()
+        .filter(|entry| within.is_none_or(|within| entry.repo_path.starts_with(&within.0)))
+        .map(|entry| StatusFile {
+            path: display_path(repository, &entry.repo_path, project, cx),
+            change: change_kind(entry.status),
+            staged: match entry.status.staging() {
+                StageStatus::Staged => Staged::Yes,
+                StageStatus::Unstaged => Staged::No,
+                StageStatus::PartiallyStaged => Staged::Partially,
+            },
+        })
+        .collect::<Vec<_>>();
+    let truncated = files.len() > MAX_STATUS_FILES;
+    files.truncate(MAX_STATUS_FILES);
+    let output = StatusOutput {
+        repository: repository.display_name().to_string(),
+        branch: repository
+            .branch
+            .as_ref()
+            .map(|branch| branch.name().to_string()),
+        files,
+        truncated,
+    };
+    Ok(ToolResultOutput {
+        content: ToolResultContent::Text(serde_json::to_string(&output)?),
+        output: None,
+        truncated,
+    })
+}
+
+fn change_kind(status: FileStatus) -> ChangeKind {
+    if status.is_conflicted() {
+        ChangeKind::Conflicted
+    } else if status.is_untracked() {
+        ChangeKind::Untracked
+    } else if status.is_created() {
+        ChangeKind::Added
+    } else if status.is_deleted() {
+        ChangeKind::Deleted
+    } else {
+        ChangeKind::Modified
+    }
+}
+
+/// Formats a repository path the way the other tools expect project paths, starting with
+/// the name of the worktree that contains it.
+fn display_path(
+    repository: &Repository,
+    repo_path: &RepoPath,
+    project: &Project,
+    cx: &App,
+) -> String {
+    repository
+        .repo_path_to_project_path(repo_path, cx)
+        .and_then(|project_path| {
+            let worktree = project.worktree_for_id(project_path.worktree_id, cx)?;
+            let root_name = worktree.read(cx).root_name().to_string();
+            Some(
+                std::path::Path::new(&root_name)
+                    .join(&project_path.path)
+                    .to_string_lossy()
+                    .into_owned(),
+            )
+        })
+        .unwrap_or_else(|| repo_path.to_string_lossy().into_owned())
+}
+
+/// Returns the blame entries that overlap the given zero-based rows, clipped to them.
+fn blame_lines(blame: &git::blame::Blame, rows: Range<u32>) -> Vec<BlameLines> {
+    let mut entries = blame
+        .entries
+        .iter()
+        .filter(|entry| entry.range.start < rows.end && entry.range.end > rows.start)
+        .map(|entry| BlameLines {
+            lines: [
+                entry.range.start.max(rows.start) + 1,
+                entry.range.end.min(rows.end),
+            ],
+            commit: entry.sha.display_short(),
+            author: entry.author.clone(),
+            date: entry.author_time.and_then(format_date),
+            summary: blame
+                .messages
+                .get(&entry.sha)
+                .and_then(|message| message.lines().next())
+                .map(str::to_string),
+        })
+        .collect::<Vec<_>>();
+    entries.sort_by_key(|entry| entry.lines[0]);
+    entries
+}
+
+fn show_files(diff: CommitDiff) -> Vec<ShowFile> {
+    let mut patch_bytes = 0;
+    diff.files
+        .into_iter()
+        .map(|file| {
+            let old_text = file.old_text.as_deref().unwrap_or_default();
+            let new_text = file.new_text.as_deref().unwrap_or_default();
+            let (mut insertions, mut deletions) = (0, 0);
+            for (old_rows, new_rows) in language::line_diff(old_text, new_text) {
+                deletions += old_rows.len();
+                insertions += new_rows.len();
+            }
+            let change = match (&file.old_text, &file.new_text) {
+                (None, _) => ChangeKind::Added,
+                (_, None) => ChangeKind::Deleted,
+                _ => ChangeKind::Modified,
+            };
+
+            let patch = language::unified_diff(old_text, new_text);
+            patch_bytes += patch.len();
+            ShowFile {
+                path: file.path.to_string_lossy().into_owned(),
+                change,
+                insertions,
+                deletions,
+                patch: (patch_bytes <= MAX_SHOW_PATCH_BYTES).then_some(patch),
+            }
+        })
+        .collect()
+}
+
+fn format_date(timestamp: i64) -> Option<String> {
+    chrono::DateTime::from_timestamp(timestamp, 0).map(|date| date.to_rfc3339())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fs::{FakeFs, Fs as _};
+    use gpui::TestAppContext;
+    use language_model::fake_provider::FakeLanguageModel;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use util::path;
+
+    #[test]
+    fn test_blame_lines() {
+        let entry = |sha: &str, range: Range<u32>| git::blame::BlameEntry {
+            sha: sha.parse().unwrap(),
+            range,
+            author: Some("Ada".into()),
+            ..Default::default()
+        };
+        let blame = git::blame::Blame {
+            entries: vec![
+                entry("2222222222222222222222222222222222222222", 4..10),
+                entry("1111111111111111111111111111111111111111", 0..4),
+            ],
+            ..Default::default()
+        };
+
+        let lines = blame_lines(&blame, 2..6);
+        assert_eq!(
+            lines
+                .iter()
+                .map(|entry| (entry.lines, entry.commit.as_str()))
+                .collect::<Vec<_>>(),
+            [([3, 4], "1111111"), ([5, 6], "2222222")]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_git_status(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/root"),
+            json!({
+                ".git": {},
+                "src": {
+                    "main.rs": "fn main() {}",
+                    "new.rs": "",
+                },
+                "README.md": "# Root",
+            }),
+        )
+        .await;
+        fs.set_status_for_repo(
+            path!("/root/.git").as_ref(),
+            &[
+                ("src/new.rs".as_ref(), FileStatus::Untracked),
+                (
+                    "README.md".as_ref(),
+                    git::status::TrackedStatus {
+                        index_status: git::status::StatusCode::Unmodified,
+                        worktree_status: git::status::StatusCode::Modified,
+                    }
+                    .into(),
+                ),
+            ],
+        );
+        let project = Project::test(fs.clone(), [path!("/root").as_ref()], cx).await;
+        cx.run_until_parked();
+
+        let run = |input: serde_json::Value, cx: &mut TestAppContext| {
+            let project = project.clone();
+            cx.update(|cx| {
+                let action_log = cx.new(|_| ActionLog::new(project.clone()));
+                let model = Arc::new(FakeLanguageModel::default());
+                Arc::new(GitTool)
+                    .run(input, Arc::default(), project, action_log, model, None, cx)
+                    .output
+            })
+        };
+
+        let output = run(json!({ "query": "status" }), cx).await.unwrap();
+        let output: serde_json::Value =
+            serde_json::from_str(output.content.as_str().unwrap()).unwrap();
+        assert_eq!(
+            output["files"],
+            json!([
+                { "path": "root/README.md", "change": "modified", "staged": "no" },
+                { "path": "root/src/new.rs", "change": "untracked", "staged": "no" },
+            ])
+        );
+
+        let output = run(json!({ "query": "status", "path": "root/src" }), cx)
+            .await
+            .unwrap();
+        let output: serde_json::Value =
+            serde_json::from_str(output.content.as_str().unwrap()).unwrap();
+        assert_eq!(
+            output["files"],
+            json!([{ "path": "root/src/new.rs", "change": "untracked", "staged": "no" }])
+        );
+
+        let error = run(json!({ "query": "log" }), cx).await.unwrap_err();
+        assert_eq!(error.to_string(), "`log` requires a `path`");
+
+        let error = run(
+            json!({ "query": "show", "commit": "--output=/root/pwned" }),
+            cx,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "`commit` must be a revision, not an option: \"--output=/root/pwned\""
+        );
+        assert!(!fs.is_file(path!("/root/pwned").as_ref()).await);
+    }
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            language::init(cx);
+            Project::init_settings(cx);
+        });
+    }
+}
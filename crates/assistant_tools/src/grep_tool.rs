@@ -1,5 +1,5 @@
 use crate::schema::json_schema_for;
-use anyhow::{Result, anyhow};
+use anyhow::{Context as _, Result, anyhow};
 use assistant_tool::{ActionLog, Tool, ToolResult};
 use futures::StreamExt;
 use gpui::{AnyWindowHandle, App, Entity, Task};
@@ -12,7 +12,12 @@ use project::{
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use settings::Settings;
-use std::{cmp, fmt::Write, sync::Arc};
+use std::{
+    cmp,
+    fmt::{self, Write},
+    path::PathBuf,
+    sync::Arc,
+};
 use ui::IconName;
 use util::RangeExt;
 use util::markdown::MarkdownInlineCode;
@@ -39,16 +44,38 @@ pub struct GrepToolInput {
     /// Whether the regex is case-sensitive. Defaults to false (case-insensitive).
     #[serde(default)]
     pub case_sensitive: bool,
+
+    /// The maximum number of results per page, up to 100. Defaults to 20.
+    #[serde(default)]
+    pub max_results: Option<u32>,
+
+    /// Whether to list each matching file with its matching lines, instead of
+    /// showing each match with the code around it. Each file counts as one
+    /// result. Defaults to false.
+    #[serde(default)]
+    pub group_by_file: bool,
+
+    /// The `continuation_token` from the previous page of results, to get the
+    /// next page. Takes precedence over `offset`.
+    #[serde(default)]
+    pub continuation_token: Option<String>,
 }
 
 impl GrepToolInput {
     /// Which page of search results this is.
     pub fn page(&self) -> u32 {
-        1 + (self.offset / RESULTS_PER_PAGE)
+        1 + (self.offset / self.max_results())
+    }
+
+    fn max_results(&self) -> u32 {
+        self.max_results
+            .unwrap_or(RESULTS_PER_PAGE)
+            .clamp(1, MAX_RESULTS_PER_PAGE)
     }
 }
 
 const RESULTS_PER_PAGE: u32 = 20;
+const MAX_RESULTS_PER_PAGE: u32 = 100;
 
 pub struct GrepTool;
 
@@ -88,7 +115,9 @@ impl Tool for GrepTool {
                     ""
                 };
 
-                if page > 1 {
+                if input.continuation_token.is_some() {
+                    format!("Get next page of search results for regex {regex_str}{case_info}")
+                } else if page > 1 {
                     format!("Get page {page} of search results for regex {regex_str}{case_info}")
                 } else {
                     format!("Search files for regex {regex_str}{case_info}")
@@ -164,22 +193,33 @@ impl Tool for GrepTool {
         };
 
         let results = project.update(cx, |project, cx| project.search(query, cx));
+        let max_results = input.max_results();
 
         cx.spawn(async move |cx|  {
             futures::pin_mut!(results);
 
-            let mut output = String::new();
-            let mut skips_remaining = input.offset;
-            let mut matches_found = 0;
-            let mut has_more_matches = false;
-
-            'outer: while let Some(SearchResult::Buffer { buffer, ranges }) = results.next().await {
+            let start = input
+                .continuation_token
+                .as_deref()
+                .map(ContinuationToken::parse)
+                .transpose()?;
+
+            let mut files = Vec::new();
+            let mut search_limit_reached = false;
+            while let Some(result) = results.next().await {
+                let (buffer, ranges) = match result {
+                    SearchResult::Buffer { buffer, ranges } => (buffer, ranges),
+                    SearchResult::LimitReached => {
+                        search_limit_reached = true;
+                        break;
+                    }
+                };
                 if ranges.is_empty() {
                     continue;
                 }
 
-                let Ok((Some(path), mut parse_status)) = buffer.read_with(cx, |buffer, cx| {
-                    (buffer.file().map(|file| file.full_path(cx)), buffer.parse_status())
+                let Ok(Some(path)) = buffer.read_with(cx, |buffer, cx| {
+                    buffer.file().map(|file| file.full_path(cx))
                 }) else {
                     continue;
                 };
@@ -197,6 +237,64 @@ impl Tool for GrepTool {
                     }
                 }
 
+                files.push((path, buffer, ranges));
+            }
+            // The search yields files in whichever order they finish, so sort them
+            // for every page to be taken from the same sequence.
+            files.sort_by(|(a, _, _), (b, _, _)| a.cmp(b));
+
+            let mut output = String::new();
+            let mut skips_remaining = if start.is_some() { 0 } else { input.offset };
+            let mut results_found = 0;
+            let mut next_page = None;
+
+            'outer: for (path, buffer, ranges) in files {
+                let mut skips_in_file = 0;
+                if let Some(start) = &start {
+                    match path.cmp(&start.path) {
+                        cmp::Ordering::Less => continue,
+                        cmp::Ordering::Equal => skips_in_file = start.result_ix,
+                        cmp::Ordering::Greater => {}
+                    }
+                }
+
+                if input.group_by_file {
+                    if skips_remaining > 0 {
+                        skips_remaining -= 1;
+                        continue;
+                    }
+                    if results_found >= max_results {
+                        next_page = Some(ContinuationToken { path, result_ix: 0 });
+                        break;
+                    }
+
+                    let snapshot = buffer.read_with(cx, |buffer, _cx| buffer.snapshot())?;
+                    let mut rows = ranges
+                        .iter()
+                        .map(|range| range.to_point(&snapshot))
+                        .map(|range| range.start.row..range.end.row)
+                        .collect::<Vec<_>>();
+                    rows.dedup_by(|next, prev| next.start <= prev.end);
+
+                    let match_count = ranges.len();
+                    let plural = if match_count == 1 { "" } else { "es" };
+                    writeln!(output, "\n## {match_count} match{plural} in {}", path.display())?;
+                    for rows in rows {
+                        let line = snapshot
+                            .text_for_range(Point::new(rows.start, 0)..Point::new(rows.start, snapshot.line_len(rows.start)))
+                            .collect::<String>();
+                        if rows.start == rows.end {
+                            writeln!(output, "- L{}: `{}`", rows.start + 1, line.trim())?;
+                        } else {
+                            writeln!(output, "- L{}-{}: `{}`", rows.start + 1, rows.end + 1, line.trim())?;
+                        }
+                    }
+
+                    results_found += 1;
+                    continue;
+                }
+
+                let mut parse_status = buffer.read_with(cx, |buffer, _cx| buffer.parse_status())?;
                 while *parse_status.borrow() != ParseStatus::Idle {
                     parse_status.changed().await?;
                 }
@@ -237,19 +335,9 @@ impl Tool for GrepTool {
                     .peekable();
 
                 let mut file_header_written = false;
+                let mut result_ix = 0;
 
                 while let Some((mut range, ancestor_range, parent_symbols)) = ranges.next(){
-                    if skips_remaining > 0 {
-                        skips_remaining -= 1;
-                        continue;
-                    }
-
-                    // We'd already found a full page of matches, and we just found one more.
-                    if matches_found >= RESULTS_PER_PAGE {
-                        has_more_matches = true;
-                        break 'outer;
-                    }
-
                     while let Some((next_range, _, _)) = ranges.peek() {
                         if range.end.row >= next_range.start.row {
                             range.end = next_range.end;
@@ -259,6 +347,25 @@ impl Tool for GrepTool {
                         }
                     }
 
+                    result_ix += 1;
+                    if skips_in_file > 0 {
+                        skips_in_file -= 1;
+                        continue;
+                    }
+                    if skips_remaining > 0 {
+                        skips_remaining -= 1;
+                        continue;
+                    }
+
+                    // We'd already found a full page of matches, and we just found one more.
+                    if results_found >= max_results {
+                        next_page = Some(ContinuationToken {
+                            path,
+                            result_ix: result_ix - 1,
+                        });
+                        break 'outer;
+                    }
+
                     if !file_header_written {
                         writeln!(output, "\n## Matches in {}", path.display())?;
                         file_header_written = true;
@@ -290,26 +397,61 @@ impl Tool for GrepTool {
                         }
                     }
 
-                    matches_found += 1;
+                    results_found += 1;
                 }
             }
 
-            if matches_found == 0 {
-                Ok("No matches found".to_string().into())
-            } else if has_more_matches {
-                Ok(format!(
-                    "Showing matches {}-{} (there were more matches found; use offset: {} to see next page):\n{output}",
-                    input.offset + 1,
-                    input.offset + matches_found,
-                    input.offset + RESULTS_PER_PAGE,
-                ).into())
+            let unit = if input.group_by_file { "files with matches" } else { "matches" };
+            let mut result = if results_found == 0 {
+                "No matches found".to_string()
+            } else if let Some(next_page) = next_page {
+                let shown = if start.is_some() {
+                    format!("the next {results_found} {unit}")
+                } else {
+                    format!("{unit} {}-{}", input.offset + 1, input.offset + results_found)
+                };
+                format!(
+                    "Showing {shown} (there were more {unit} found; use continuation_token: \"{next_page}\" to see next page):\n{output}",
+                )
             } else {
-                Ok(format!("Found {matches_found} matches:\n{output}").into())
+                format!("Found {results_found} {unit}:\n{output}")
+            };
+            if search_limit_reached {
+                result.push_str("\nThe search stopped early because it matched too many files. Use a more specific regex or include_pattern to see all matches.\n");
             }
+            Ok(result.into())
         }).into()
     }
 }
 
+/// Where a page of search results starts: the `result_ix`th result in the file
+/// at `path`, with files ordered by path.
+#[derive(Debug, PartialEq, Eq)]
+struct ContinuationToken {
+    path: PathBuf,
+    result_ix: u32,
+}
+
+impl ContinuationToken {
+    fn parse(token: &str) -> Result<Self> {
+        let (result_ix, path) = token
+            .split_once(':')
+            .with_context(|| format!("invalid continuation token {token:?}"))?;
+        Ok(Self {
+            path: PathBuf::from(path),
+            result_ix: result_ix
+                .parse()
+                .with_context(|| format!("invalid continuation token {token:?}"))?,
+        })
+    }
+}
+
+impl fmt::Display for ContinuationToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.result_ix, self.path.display())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -320,6 +462,7 @@ mod tests {
     use project::{FakeFs, Project, WorktreeSettings};
     use serde_json::json;
     use settings::SettingsStore;
+    use std::path::Path;
     use unindent::Unindent;
     use util::path;
 
@@ -353,6 +496,9 @@ mod tests {
             include_pattern: Some("root/**/*.rs".to_string()),
             offset: 0,
             case_sensitive: false,
+            max_results: None,
+            group_by_file: false,
+            continuation_token: None,
         })
         .unwrap();
 
@@ -373,6 +519,9 @@ mod tests {
             include_pattern: Some("root/**/src/**".to_string()),
             offset: 0,
             case_sensitive: false,
+            max_results: None,
+            group_by_file: false,
+            continuation_token: None,
         })
         .unwrap();
 
@@ -396,6 +545,9 @@ mod tests {
             include_pattern: None,
             offset: 0,
             case_sensitive: false,
+            max_results: None,
+            group_by_file: false,
+            continuation_token: None,
         })
         .unwrap();
 
@@ -433,6 +585,9 @@ mod tests {
             include_pattern: Some("**/*.txt".to_string()),
             offset: 0,
             case_sensitive: false,
+            max_results: None,
+            group_by_file: false,
+            continuation_token: None,
         })
         .unwrap();
 
@@ -448,6 +603,9 @@ mod tests {
             include_pattern: Some("**/*.txt".to_string()),
             offset: 0,
             case_sensitive: true,
+            max_results: None,
+            group_by_file: false,
+            continuation_token: None,
         })
         .unwrap();
 
@@ -463,6 +621,9 @@ mod tests {
             include_pattern: Some("**/*.txt".to_string()),
             offset: 0,
             case_sensitive: true,
+            max_results: None,
+            group_by_file: false,
+            continuation_token: None,
         })
         .unwrap();
 
@@ -479,6 +640,9 @@ mod tests {
             include_pattern: Some("**/*.txt".to_string()),
             offset: 0,
             case_sensitive: true,
+            max_results: None,
+            group_by_file: false,
+            continuation_token: None,
         })
         .unwrap();
 
@@ -581,6 +745,9 @@ mod tests {
             include_pattern: Some("**/*.rs".to_string()),
             offset: 0,
             case_sensitive: false,
+            max_results: None,
+            group_by_file: false,
+            continuation_token: None,
         })
         .unwrap();
 
@@ -611,6 +778,9 @@ mod tests {
             include_pattern: Some("**/*.rs".to_string()),
             offset: 0,
             case_sensitive: false,
+            max_results: None,
+            group_by_file: false,
+            continuation_token: None,
         })
         .unwrap();
 
@@ -643,6 +813,9 @@ mod tests {
             include_pattern: Some("**/*.rs".to_string()),
             offset: 0,
             case_sensitive: false,
+            max_results: None,
+            group_by_file: false,
+            continuation_token: None,
         })
         .unwrap();
 
@@ -679,6 +852,9 @@ mod tests {
             include_pattern: Some("**/*.rs".to_string()),
             offset: 0,
             case_sensitive: false,
+            max_results: None,
+            group_by_file: false,
+            continuation_token: None,
         })
         .unwrap();
 
@@ -710,6 +886,9 @@ mod tests {
             include_pattern: Some("**/*.rs".to_string()),
             offset: 0,
             case_sensitive: false,
+            max_results: None,
+            group_by_file: false,
+            continuation_token: None,
         })
         .unwrap();
 
@@ -751,6 +930,9 @@ mod tests {
             include_pattern: Some("**/*.rs".to_string()),
             offset: 0,
             case_sensitive: false,
+            max_results: None,
+            group_by_file: false,
+            continuation_token: None,
         })
         .unwrap();
 
@@ -773,6 +955,128 @@ mod tests {
         assert_eq!(result, expected);
     }
 
+    #[gpui::test]
+    async fn test_grep_continuation_token(cx: &mut TestAppContext) {
+        init_test(cx);
+        cx.executor().allow_parking();
+
+        let project = setup_pagination_test(cx).await;
+        let input = |max_results, continuation_token: Option<ContinuationToken>| {
+            serde_json::to_value(GrepToolInput {
+                regex: "needle".to_string(),
+                include_pattern: None,
+                offset: 0,
+                case_sensitive: false,
+                max_results: Some(max_results),
+                group_by_file: false,
+                continuation_token: continuation_token.map(|token| token.to_string()),
+            })
+            .unwrap()
+        };
+        let token = |result_ix, file_name| ContinuationToken {
+            path: Path::new("root").join(file_name),
+            result_ix,
+        };
+
+        let result = run_grep_tool(input(3, None), project.clone(), cx).await;
+        assert!(result.starts_with("Showing matches 1-3 "), "{result}");
+        assert!(result.contains("## Matches in root/a.txt"), "{result}");
+        assert!(result.contains("## Matches in root/b.txt"), "{result}");
+        assert!(!result.contains("b needle 2"), "{result}");
+        assert!(
+            result.contains("continuation_token: \"1:root/b.txt\""),
+            "{result}"
+        );
+
+        let result = run_grep_tool(input(3, Some(token(1, "b.txt"))), project.clone(), cx).await;
+        assert!(result.starts_with("Found 3 matches:"), "{result}");
+        assert!(!result.contains("a needle"), "{result}");
+        assert!(!result.contains("b needle 1"), "{result}");
+        assert!(result.contains("b needle 2"), "{result}");
+        assert!(result.contains("c needle 1"), "{result}");
+        assert!(result.contains("c needle 2"), "{result}");
+
+        // Paging through the results yields the same matches as a single large page.
+        let mut paged = Vec::new();
+        let mut next_token = None;
+        loop {
+            let result = run_grep_tool(input(1, next_token.take()), project.clone(), cx).await;
+            paged.extend(
+                result
+                    .lines()
+                    .filter(|line| line.contains("needle"))
+                    .map(str::to_string),
+            );
+            match result.split_once("continuation_token: \"") {
+                Some((_, rest)) => {
+                    let token = rest
+                        .split_once('"')
+                        .unwrap()
+                        .0
+                        .replace('/', std::path::MAIN_SEPARATOR_STR);
+                    next_token = Some(ContinuationToken::parse(&token).unwrap());
+                }
+                None => break,
+            }
+        }
+        let result = run_grep_tool(input(100, None), project.clone(), cx).await;
+        let all = result
+            .lines()
+            .filter(|line| line.contains("needle"))
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        assert_eq!(paged, all);
+        assert_eq!(all.len(), 6);
+    }
+
+    #[gpui::test]
+    async fn test_grep_group_by_file(cx: &mut TestAppContext) {
+        init_test(cx);
+        cx.executor().allow_parking();
+
+        let project = setup_pagination_test(cx).await;
+        let input = serde_json::to_value(GrepToolInput {
+            regex: "needle".to_string(),
+            include_pattern: None,
+            offset: 0,
+            case_sensitive: false,
+            max_results: Some(2),
+            group_by_file: true,
+            continuation_token: None,
+        })
+        .unwrap();
+
+        let result = run_grep_tool(input, project.clone(), cx).await;
+        assert_eq!(
+            result,
+            "Showing files with matches 1-2 (there were more files with matches found; use continuation_token: \"0:root/c.txt\" to see next page):\n\
+            \n\
+            ## 2 matches in root/a.txt\n\
+            - L1: `a needle 1`\n\
+            - L8: `a needle 2`\n\
+            \n\
+            ## 2 matches in root/b.txt\n\
+            - L1: `b needle 1`\n\
+            - L8: `b needle 2`\n"
+        );
+    }
+
+    async fn setup_pagination_test(cx: &mut TestAppContext) -> Entity<Project> {
+        let file = |name| format!("{name} needle 1\nx\nx\nx\nx\nx\nx\n{name} needle 2\n");
+        let fs = FakeFs::new(cx.executor().clone());
+        // Inserted out of order, to check that results are ordered by path.
+        fs.insert_tree(
+            path!("/root"),
+            json!({
+                "c.txt": file("c"),
+                "a.txt": file("a"),
+                "b.txt": file("b"),
+            }),
+        )
+        .await;
+        Project::test(fs.clone(), [path!("/root").as_ref()], cx).await
+    }
+
     async fn run_grep_tool(
         input: serde_json::Value,
         project: Entity<Project>,
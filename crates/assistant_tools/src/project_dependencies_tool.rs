@@ -0,0 +1,815 @@
+use crate::schema::json_schema_for;
+use anyhow::{Result, anyhow};
+use assistant_tool::{ActionLog, Tool, ToolResult};
+use collections::HashMap;
+use gpui::{AnyWindowHandle, App, AsyncApp, Entity, Task};
+use language_model::{LanguageModel, LanguageModelRequest, LanguageModelToolSchemaFormat};
+use project::{Project, ProjectPath};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::{fmt::Write as _, path::Path, sync::Arc};
+use ui::IconName;
+use util::markdown::MarkdownInlineCode;
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ProjectDependenciesToolInput {
+    /// The relative path of a directory or manifest file to inspect.
+    ///
+    /// When omitted, the manifests at the root of every directory in the project are inspected.
+    ///
+    /// <example>
+    /// To inspect `directory1/crates/editor/Cargo.toml`, provide a path of
+    /// "directory1/crates/editor" or "directory1/crates/editor/Cargo.toml".
+    /// </example>
+    #[serde(default)]
+    pub path: Option<String>,
+
+    /// When provided, only dependencies whose name contains this text (ignoring case) are listed.
+    #[serde(default)]
+    pub filter: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Ecosystem {
+    Cargo,
+    Npm,
+    Python,
+}
+
+impl Ecosystem {
+    const ALL: [Self; 3] = [Self::Cargo, Self::Npm, Self::Python];
+
+    fn for_manifest(file_name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|ecosystem| ecosystem.manifest_name() == file_name)
+    }
+
+    fn manifest_name(self) -> &'static str {
+        match self {
+            Self::Cargo => "Cargo.toml",
+            Self::Npm => "package.json",
+            Self::Python => "pyproject.toml",
+        }
+    }
+
+    fn lockfile_names(self) -> &'static [&'static str] {
+        match self {
+            Self::Cargo => &["Cargo.lock"],
+            Self::Npm => &["package-lock.json"],
+            Self::Python => &["uv.lock", "poetry.lock"],
+        }
+    }
+
+    fn parse_manifest(self, text: &str) -> Result<Vec<DeclaredDependency>> {
+        match self {
+            Self::Cargo => parse_cargo_manifest(text),
+            Self::Npm => parse_package_json(text),
+            Self::Python => parse_pyproject(text),
+        }
+    }
+
+    fn parse_lockfile(self, text: &str) -> Result<HashMap<String, Vec<String>>> {
+        let versions = match self {
+            Self::Cargo | Self::Python => parse_toml_lockfile(text)?,
+            Self::Npm => parse_package_lock(text)?,
+        };
+        Ok(versions
+            .into_iter()
+            .map(|(name, versions)| (self.normalize_name(&name), versions))
+            .collect())
+    }
+
+    /// Python package names are case-insensitive, and treat `-`, `_`, and `.` as equivalent.
+    fn normalize_name(self, name: &str) -> String {
+        match self {
+            Self::Cargo | Self::Npm => name.to_string(),
+            Self::Python => name.to_lowercase().replace(['_', '.'], "-"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct DeclaredDependency {
+    section: String,
+    name: String,
+    /// The name the dependency is published and locked under, when it's renamed in the manifest.
+    package: Option<String>,
+    requirement: String,
+}
+
+struct ManifestFiles {
+    ecosystem: Ecosystem,
+    manifest: ProjectPath,
+    manifest_display_path: String,
+    lockfile: Option<(ProjectPath, String)>,
+}
+
+pub struct ProjectDependenciesTool;
+
+impl Tool for ProjectDependenciesTool {
+    fn name(&self) -> String {
+        "project_dependencies".into()
+    }
+
+    fn needs_confirmation(&self, _: &serde_json::Value, _: &App) -> bool {
+        false
+    }
+
+    fn may_perform_edits(&self) -> bool {
+        false
+    }
+
+    fn description(&self) -> String {
+        include_str!("./project_dependencies_tool/description.md").into()
+    }
+
+    fn icon(&self) -> IconName {
+        IconName::Library
+    }
+
+    fn input_schema(&self, format: LanguageModelToolSchemaFormat) -> Result<serde_json::Value> {
+        json_schema_for::<ProjectDependenciesToolInput>(format)
+    }
+
+    fn ui_text(&self, input: &serde_json::Value) -> String {
+        match serde_json::from_value::<ProjectDependenciesToolInput>(input.clone()) {
+            Ok(ProjectDependenciesToolInput {
+                path: Some(path), ..
+            }) => format!("List dependencies of {}", MarkdownInlineCode(&path)),
+            _ => "List project dependencies".to_string(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: serde_json::Value,
+        _request: Arc<LanguageModelRequest>,
+        project: Entity<Project>,
+        _action_log: Entity<ActionLog>,
+        _model: Arc<dyn LanguageModel>,
+        _window: Option<AnyWindowHandle>,
+        cx: &mut App,
+    ) -> ToolResult {
+        let input = match serde_json::from_value::<ProjectDependenciesToolInput>(input) {
+            Ok(input) => input,
+            Err(err) => return Task::ready(Err(anyhow!(err))).into(),
+        };
+        let manifests = match find_manifests(project.read(cx), input.path.as_deref(), cx) {
+            Ok(manifests) => manifests,
+            Err(err) => return Task::ready(Err(err)).into(),
+        };
+        if manifests.is_empty() {
+            return Task::ready(Ok(
+                "No `Cargo.toml`, `package.json`, or `pyproject.toml` manifest was found."
+                    .to_string()
+                    .into(),
+            ))
+            .into();
+        }
+
+        let filter = input.filter.map(|filter| filter.to_lowercase());
+        cx.spawn(async move |cx: &mut AsyncApp| {
+            let mut output = String::new();
+            for manifest in manifests {
+                let ecosystem = manifest.ecosystem;
+                writeln!(
+                    output,
+                    "## {}\n",
+                    MarkdownInlineCode(&manifest.manifest_display_path)
+                )?;
+
+                let dependencies = match load_text(&project, manifest.manifest, cx)
+                    .await
+                    .and_then(|text| ecosystem.parse_manifest(&text))
+                {
+                    Ok(dependencies) => dependencies,
+                    Err(err) => {
+                        writeln!(output, "Failed to read manifest: {err}\n")?;
+                        continue;
+                    }
+                };
+
+                let locked_versions = match manifest.lockfile {
+                    Some((lockfile, display_path)) => {
+                        match load_text(&project, lockfile, cx)
+                            .await
+                            .and_then(|text| ecosystem.parse_lockfile(&text))
+                        {
+                            Ok(versions) => {
+                                writeln!(
+                                    output,
+                                    "Lockfile: {}\n",
+                                    MarkdownInlineCode(&display_path)
+                                )?;
+                                Some(versions)
+                            }
+                            Err(err) => {
+                                writeln!(output, "Failed to read lockfile: {err}\n")?;
+                                None
+                            }
+                        }
+                    }
+                    None => {
+                        writeln!(output, "No lockfile was found.\n")?;
+                        None
+                    }
+                };
+
+                let mut current_section = None;
+                for dependency in &dependencies {
+                    if let Some(filter) = &filter {
+                        let matches_filter = dependency.name.to_lowercase().contains(filter)
+                            || dependency
+                                .package
+                                .as_ref()
+                                .is_some_and(|package| package.to_lowercase().contains(filter));
+                        if !matches_filter {
+                            continue;
+                        }
+                    }
+
+                    if current_section != Some(&dependency.section) {
+                        if current_section.is_some() {
+                            writeln!(output)?;
+                        }
+                        writeln!(output, "### {}\n", dependency.section)?;
+                        current_section = Some(&dependency.section);
+                    }
+
+                    write!(output, "- {}", MarkdownInlineCode(&dependency.name))?;
+                    if let Some(package) = &dependency.package {
+                        write!(output, " (package {})", MarkdownInlineCode(package))?;
+                    }
+                    write!(output, ": {}", MarkdownInlineCode(&dependency.requirement))?;
+                    if let Some(locked_versions) = &locked_versions {
+                        let package = dependency.package.as_ref().unwrap_or(&dependency.name);
+                        match locked_versions.get(&ecosystem.normalize_name(package)) {
+                            Some(versions) => write!(output, " (locked: {})", versions.join(", "))?,
+                            None => write!(output, " (not locked)")?,
+                        }
+                    }
+                    writeln!(output)?;
+                }
+
+                if current_section.is_none() {
+                    writeln!(output, "No matching dependencies are declared.")?;
+                }
+                writeln!(output)?;
+            }
+
+            Ok(output.trim_end().to_string().into())
+        })
+        .into()
+    }
+}
+
+fn find_manifests(project: &Project, path: Option<&str>, cx: &App) -> Result<Vec<ManifestFiles>> {
+    let mut directories = Vec::new();
+    let mut manifests = Vec::new();
+    match path {
+        Some(path) => {
+            let project_path = project
+                .find_project_path(path, cx)
+                .filter(|project_path| project.entry_for_path(project_path, cx).is_some())
+                .ok_or_else(|| anyhow!("Path {path} not found in project"))?;
+            if project
+                .entry_for_path(&project_path, cx)
+                .is_some_and(|entry| entry.is_dir())
+            {
+                directories.push(project_path);
+            } else {
+                let ecosystem = project_path
+                    .path
+                    .file_name()
+                    .and_then(|file_name| file_name.to_str())
+                    .and_then(Ecosystem::for_manifest)
+                    .ok_or_else(|| anyhow!("{path} is not a supported manifest file"))?;
+                manifests.push((ecosystem, project_path));
+            }
+        }
+        None => {
+            directories.extend(project.visible_worktrees(cx).map(|worktree| ProjectPath {
+                worktree_id: worktree.read(cx).id(),
+                path: Path::new("").into(),
+            }));
+        }
+    }
+
+    for directory in directories {
+        for ecosystem in Ecosystem::ALL {
+            let manifest = ProjectPath {
+                worktree_id: directory.worktree_id,
+                path: directory.path.join(ecosystem.manifest_name()).into(),
+            };
+            if project.entry_for_path(&manifest, cx).is_some() {
+                manifests.push((ecosystem, manifest));
+            }
+        }
+    }
+
+    Ok(manifests
+        .into_iter()
+        .map(|(ecosystem, manifest)| {
+            // Workspace members share the lockfile of the workspace they belong to.
+            let lockfile = manifest.path.ancestors().skip(1).find_map(|directory| {
+                ecosystem.lockfile_names().iter().find_map(|lockfile_name| {
+                    let lockfile = ProjectPath {
+                        worktree_id: manifest.worktree_id,
+                        path: directory.join(lockfile_name).into(),
+                    };
+                    project.entry_for_path(&lockfile, cx).map(|_| lockfile)
+                })
+            });
+            ManifestFiles {
+                ecosystem,
+                manifest_display_path: display_path(project, &manifest, cx),
+                lockfile: lockfile.map(|lockfile| {
+                    let display_path = display_path(project, &lockfile, cx);
+                    (lockfile, display_path)
+                }),
+                manifest,
+            }
+        })
+        .collect())
+}
+
+fn display_path(project: &Project, project_path: &ProjectPath, cx: &App) -> String {
+    match project.worktree_for_id(project_path.worktree_id, cx) {
+        Some(worktree) => Path::new(worktree.read(cx).root_name())
+            .join(&project_path.path)
+            .to_string_lossy()
+            .into_owned(),
+        None => project_path.path.to_string_lossy().into_owned(),
+    }
+}
+
+async fn load_text(
+    project: &Entity<Project>,
+    project_path: ProjectPath,
+    cx: &mut AsyncApp,
+) -> Result<String> {
+    let buffer = project
+        .update(cx, |project, cx| project.open_buffer(project_path, cx))?
+        .await?;
+    buffer.read_with(cx, |buffer, _| buffer.text())
+}
+
+fn parse_cargo_manifest(text: &str) -> Result<Vec<DeclaredDependency>> {
+    const SECTIONS: [&str; 3] = ["dependencies", "dev-dependencies", "build-dependencies"];
+
+    let manifest = text.parse::<toml::Table>()?;
+    let mut dependencies = Vec::new();
+    for section in SECTIONS {
+        push_cargo_dependencies(&manifest, section, section, &mut dependencies);
+    }
+    if let Some(workspace) = manifest.get("workspace").and_then(toml::Value::as_table) {
+        push_cargo_dependencies(
+            workspace,
+            "dependencies",
+            "workspace.dependencies",
+            &mut dependencies,
+        );
+    }
+    if let Some(targets) = manifest.get("target").and_then(toml::Value::as_table) {
+        for (target, table) in targets {
+            let Some(table) = table.as_table() else {
+                continue;
+            };
+            for section in SECTIONS {
+                let section_name = format!("target.{target}.{section}");
+                push_cargo_dependencies(table, section, &section_name, &mut dependencies);
+            }
+        }
+    }
+    Ok(dependencies)
+}
+
+fn push_cargo_dependencies(
+    table: &toml::Table,
+    key: &str,
+    section: &str,
+    dependencies: &mut Vec<DeclaredDependency>,
+) {
+    let Some(entries) = table.get(key).and_then(toml::Value::as_table) else {
+        return;
+    };
+    for (name, value) in entries {
+        let (requirement, package) = match value {
+            toml::Value::String(version) => (version.clone(), None),
+            toml::Value::Table(dependency) => {
+                let field = |key: &str| dependency.get(key).and_then(toml::Value::as_str);
+                let requirement = if let Some(version) = field("version") {
+                    version.to_string()
+                } else if dependency.get("workspace").and_then(toml::Value::as_bool) == Some(true) {
+                    "workspace".to_string()
+                } else if let Some(path) = field("path") {
+                    format!("path: {path}")
+                } else if let Some(git) = field("git") {
+                    format!("git: {git}")
+                } else {
+                    "*".to_string()
+                };
+                (requirement, field("package").map(ToString::to_string))
+            }
+            _ => continue,
+        };
+        dependencies.push(DeclaredDependency {
+            section: section.to_string(),
+            name: name.clone(),
+            package,
+            requirement,
+        });
+    }
+}
+
+fn parse_package_json(text: &str) -> Result<Vec<DeclaredDependency>> {
+    const SECTIONS: [&str; 4] = [
+        "dependencies",
+        "devDependencies",
+        "peerDependencies",
+        "optionalDependencies",
+    ];
+
+    let manifest = serde_json::from_str::<serde_json::Value>(text)?;
+    let mut dependencies = Vec::new();
+    for section in SECTIONS {
+        let Some(entries) = manifest.get(section).and_then(|value| value.as_object()) else {
+            continue;
+        };
+        for (name, requirement) in entries {
+            let Some(requirement) = requirement.as_str() else {
+                continue;
+            };
+            dependencies.push(DeclaredDependency {
+                section: section.to_string(),
+                name: name.clone(),
+                package: None,
+                requirement: requirement.to_string(),
+            });
+        }
+    }
+    Ok(dependencies)
+}
+
+fn parse_pyproject(text: &str) -> Result<Vec<DeclaredDependency>> {
+    let manifest = text.parse::<toml::Table>()?;
+    let mut dependencies = Vec::new();
+
+    if let Some(project) = manifest.get("project").and_then(toml::Value::as_table) {
+        push_pep508_dependencies(
+            project.get("dependencies"),
+            "project.dependencies",
+            &mut dependencies,
+        );
+        if let Some(groups) = project
+            .get("optional-dependencies")
+            .and_then(toml::Value::as_table)
+        {
+            for (group, specs) in groups {
+                let section = format!("project.optional-dependencies.{group}");
+                push_pep508_dependencies(Some(specs), &section, &mut dependencies);
+            }
+        }
+    }
+
+    if let Some(groups) = manifest
+        .get("dependency-groups")
+        .and_then(toml::Value::as_table)
+    {
+        for (group, specs) in groups {
+            let section = format!("dependency-groups.{group}");
+            push_pep508_dependencies(Some(specs), &section, &mut dependencies);
+        }
+    }
+
+    if let Some(poetry) = manifest
+        .get("tool")
+        .and_then(|tool| tool.get("poetry"))
+        .and_then(toml::Value::as_table)
+    {
+        for section in ["dependencies", "dev-dependencies"] {
+            push_poetry_dependencies(
+                poetry.get(section),
+                &format!("tool.poetry.{section}"),
+                &mut dependencies,
+            );
+        }
+        if let Some(groups) = poetry.get("group").and_then(toml::Value::as_table) {
+            for (group, table) in groups {
+                push_poetry_dependencies(
+                    table.get("dependencies"),
+                    &format!("tool.poetry.group.{group}.dependencies"),
+                    &mut dependencies,
+                );
+            }
+        }
+    }
+
+    Ok(dependencies)
+}
+
+fn push_pep508_dependencies(
+    specs: Option<&toml::Value>,
+    section: &str,
+    dependencies: &mut Vec<DeclaredDependency>,
+) {
+    for spec in specs
+        .and_then(toml::Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(toml::Value::as_str)
+    {
+        let spec = spec.trim();
+        let name_len = spec
+            .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')))
+            .unwrap_or(spec.len());
+        if name_len == 0 {
+            continue;
+        }
+        let requirement = spec[name_len..].trim();
+        dependencies.push(DeclaredDependency {
+            section: section.to_string(),
+            name: spec[..name_len].to_string(),
+            package: None,
+            requirement: if requirement.is_empty() {
+                "*".to_string()
+            } else {
+                requirement.to_string()
+            },
+        });
+    }
+}
+
+fn push_poetry_dependencies(
+    table: Option<&toml::Value>,
+    section: &str,
+    dependencies: &mut Vec<DeclaredDependency>,
+) {
+    let Some(entries) = table.and_then(toml::Value::as_table) else {
+        return;
+    };
+    for (name, value) in entries {
+        // Poetry declares the supported Python versions alongside the dependencies.
+        if name == "python" {
+            continue;
+        }
+        let requirement = match value {
+            toml::Value::String(version) => version.clone(),
+            toml::Value::Table(dependency) => {
+                let field = |key: &str| dependency.get(key).and_then(toml::Value::as_str);
+                if let Some(version) = field("version") {
+                    version.to_string()
+                } else if let Some(path) = field("path") {
+                    format!("path: {path}")
+                } else if let Some(git) = field("git") {
+                    format!("git: {git}")
+                } else {
+                    "*".to_string()
+                }
+            }
+            _ => continue,
+        };
+        dependencies.push(DeclaredDependency {
+            section: section.to_string(),
+            name: name.clone(),
+            package: None,
+            requirement,
+        });
+    }
+}
+
+/// Parses the `[[package]]` entries shared by `Cargo.lock`, `uv.lock`, and `poetry.lock`.
+fn parse_toml_lockfile(text: &str) -> Result<HashMap<String, Vec<String>>> {
+    let lockfile = text.parse::<toml::Table>()?;
+    let mut versions = HashMap::<String, Vec<String>>::default();
+    for package in lockfile
+        .get("package")
+        .and_then(toml::Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        let name = package.get("name").and_then(toml::Value::as_str);
+        let version = package.get("version").and_then(toml::Value::as_str);
+        if let Some((name, version)) = name.zip(version) {
+            versions
+                .entry(name.to_string())
+                .or_default()
+                .push(version.to_string());
+        }
+    }
+    Ok(versions)
+}
+
+fn parse_package_lock(text: &str) -> Result<HashMap<String, Vec<String>>> {
+    let lockfile = serde_json::from_str::<serde_json::Value>(text)?;
+    let mut versions = HashMap::<String, Vec<String>>::default();
+    let version = |package: &serde_json::Value| {
+        package
+            .get("version")
+            .and_then(|version| version.as_str())
+            .map(ToString::to_string)
+    };
+
+    if let Some(packages) = lockfile.get("packages").and_then(|value| value.as_object()) {
+        for (path, package) in packages {
+            // Skip copies of packages nested under other packages' `node_modules`.
+            let Some(name) = path
+                .strip_prefix("node_modules/")
+                .filter(|name| !name.contains("/node_modules/"))
+            else {
+                continue;
+            };
+            if let Some(version) = version(package) {
+                versions.entry(name.to_string()).or_default().push(version);
+            }
+        }
+    } else if let Some(packages) = lockfile
+        .get("dependencies")
+        .and_then(|value| value.as_object())
+    {
+        for (name, package) in packages {
+            if let Some(version) = version(package) {
+                versions.entry(name.clone()).or_default().push(version);
+            }
+        }
+    }
+    Ok(versions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fs::FakeFs;
+    use gpui::{AppContext, TestAppContext};
+    use indoc::indoc;
+    use language_model::fake_provider::FakeLanguageModel;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use util::path;
+
+    #[test]
+    fn test_parse_cargo_manifest() {
+        let dependencies = parse_cargo_manifest(indoc! {r#"
+            [dependencies]
+            anyhow = "1.0"
+            gpui = { workspace = true }
+            util = { path = "../util" }
+            json = { package = "serde_json", version = "1.0.140" }
+
+            [target.'cfg(windows)'.dependencies]
+            windows = "0.61"
+        "#})
+        .unwrap();
+        assert_eq!(
+            dependencies
+                .iter()
+                .map(|dependency| (
+                    dependency.section.as_str(),
+                    dependency.name.as_str(),
+                    dependency.package.as_deref(),
+                    dependency.requirement.as_str()
+                ))
+                .collect::<Vec<_>>(),
+            [
+                ("dependencies", "anyhow", None, "1.0"),
+                ("dependencies", "gpui", None, "workspace"),
+                ("dependencies", "json", Some("serde_json"), "1.0.140"),
+                ("dependencies", "util", None, "path: ../util"),
+                ("target.cfg(windows).dependencies", "windows", None, "0.61"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_pyproject() {
+        let dependencies = parse_pyproject(indoc! {r#"
+            [project]
+            dependencies = ["requests>=2.31", "Typing_Extensions"]
+
+            [project.optional-dependencies]
+            test = ["pytest[cov] ~= 8.0"]
+        "#})
+        .unwrap();
+        assert_eq!(
+            dependencies
+                .iter()
+                .map(|dependency| (
+                    dependency.section.as_str(),
+                    dependency.name.as_str(),
+                    dependency.requirement.as_str()
+                ))
+                .collect::<Vec<_>>(),
+            [
+                ("project.dependencies", "requests", ">=2.31"),
+                ("project.dependencies", "Typing_Extensions", "*"),
+                (
+                    "project.optional-dependencies.test",
+                    "pytest",
+                    "[cov] ~= 8.0"
+                ),
+            ]
+        );
+        assert_eq!(
+            Ecosystem::Python.normalize_name("Typing_Extensions"),
+            "typing-extensions"
+        );
+    }
+
+    #[test]
+    fn test_parse_package_lock() {
+        let versions = parse_package_lock(
+            &json!({
+                "lockfileVersion": 3,
+                "packages": {
+                    "": { "name": "app" },
+                    "node_modules/react": { "version": "18.3.1" },
+                    "node_modules/@types/node": { "version": "20.14.2" },
+                    "node_modules/react/node_modules/loose-envify": { "version": "1.4.0" }
+                }
+            })
+            .to_string(),
+        )
+        .unwrap();
+        assert_eq!(versions.len(), 2);
+        assert_eq!(versions["react"], ["18.3.1"]);
+        assert_eq!(versions["@types/node"], ["20.14.2"]);
+    }
+
+    #[gpui::test]
+    async fn test_project_dependencies(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/root"),
+            json!({
+                "Cargo.lock": indoc! {r#"
+                    version = 4
+
+                    [[package]]
+                    name = "anyhow"
+                    version = "1.0.98"
+
+                    [[package]]
+                    name = "editor"
+                    version = "0.1.0"
+                "#},
+                "crates": {
+                    "editor": {
+                        "Cargo.toml": indoc! {r#"
+                            [package]
+                            name = "editor"
+
+                            [dependencies]
+                            anyhow = "1.0"
+                            smol = "2.0"
+                        "#},
+                    }
+                }
+            }),
+        )
+        .await;
+        let project = Project::test(fs.clone(), [path!("/root").as_ref()], cx).await;
+        let action_log = cx.new(|_| ActionLog::new(project.clone()));
+        let model = Arc::new(FakeLanguageModel::default());
+
+        let output = cx
+            .update(|cx| {
+                Arc::new(ProjectDependenciesTool)
+                    .run(
+                        json!({ "path": "root/crates/editor" }),
+                        Arc::default(),
+                        project.clone(),
+                        action_log,
+                        model,
+                        None,
+                        cx,
+                    )
+                    .output
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            output.content.as_str(),
+            Some(indoc! {"
+                ## `root/crates/editor/Cargo.toml`
+
+                Lockfile: `root/Cargo.lock`
+
+                ### dependencies
+
+                - `anyhow`: `1.0` (locked: 1.0.98)
+                - `smol`: `2.0` (not locked)"})
+        );
+    }
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            language::init(cx);
+            Project::init_settings(cx);
+        });
+    }
+}
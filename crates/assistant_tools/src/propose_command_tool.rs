@@ -0,0 +1,368 @@
+use crate::{
+    schema::json_schema_for,
+    terminal_tool::{Redactor, markdown_style, process_content, redact, working_dir},
+};
+use anyhow::{Context as _, Result, anyhow};
+use assistant_tool::{ActionLog, Tool, ToolCard, ToolResult, ToolUseStatus};
+use futures::channel::oneshot;
+use gpui::{
+    AnyWindowHandle, App, AppContext, ClipboardItem, Empty, Entity, EntityId, Task, WeakEntity,
+    Window,
+};
+use language_model::{LanguageModel, LanguageModelRequest, LanguageModelToolSchemaFormat};
+use markdown::{Markdown, MarkdownElement};
+use project::{Project, terminals::TerminalKind};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::{path::PathBuf, process::ExitStatus, sync::Arc};
+use task::{RevealStrategy, SpawnInTerminal, TaskId};
+use terminal_view::terminal_panel::TerminalPanel;
+use ui::{Tooltip, prelude::*};
+use util::{get_system_shell, markdown::MarkdownInlineCode};
+use workspace::Workspace;
+
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ProposeCommandToolInput {
+    /// The one-liner command to propose to the user.
+    pub command: String,
+    /// Working directory for the command. This must be one of the root directories of the project.
+    pub cd: String,
+}
+
+/// Shows a shell command to the user, who decides whether to run it in the terminal panel or to
+/// copy it, instead of the agent running it on its own.
+pub struct ProposeCommandTool;
+
+impl Tool for ProposeCommandTool {
+    fn name(&self) -> String {
+        "propose_terminal_command".into()
+    }
+
+    fn needs_confirmation(&self, _: &serde_json::Value, _: &App) -> bool {
+        // The card's buttons are the confirmation.
+        false
+    }
+
+    fn may_perform_edits(&self) -> bool {
+        false
+    }
+
+    fn supports_plan_only(&self) -> bool {
+        // A proposed command can be run from the card, changing files behind the action log's back.
+        false
+    }
+
+    fn description(&self) -> String {
+        include_str!("./propose_command_tool/description.md").into()
+    }
+
+    fn icon(&self) -> IconName {
+        IconName::Terminal
+    }
+
+    fn input_schema(&self, format: LanguageModelToolSchemaFormat) -> Result<serde_json::Value> {
+        json_schema_for::<ProposeCommandToolInput>(format)
+    }
+
+    fn ui_text(&self, input: &serde_json::Value) -> String {
+        match serde_json::from_value::<ProposeCommandToolInput>(input.clone()) {
+            Ok(input) => format!("Propose {}", MarkdownInlineCode(&input.command)),
+            Err(_) => "Propose terminal command".to_string(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: serde_json::Value,
+        _request: Arc<LanguageModelRequest>,
+        project: Entity<Project>,
+        _action_log: Entity<ActionLog>,
+        _model: Arc<dyn LanguageModel>,
+        window: Option<AnyWindowHandle>,
+        cx: &mut App,
+    ) -> ToolResult {
+        let input: ProposeCommandToolInput = match serde_json::from_value(input) {
+            Ok(input) => input,
+            Err(err) => return Task::ready(Err(anyhow!(err))).into(),
+        };
+        if window.is_none() {
+            return Task::ready(Err(anyhow!(
+                "Proposed commands need a user to run them, use the terminal tool instead"
+            )))
+            .into();
+        }
+        let working_dir = match working_dir(&input.cd, &project, cx) {
+            Ok(dir) => dir,
+            Err(err) => return Task::ready(Err(err)).into(),
+        };
+
+        let (response_tx, response_rx) = oneshot::channel();
+        let command_markdown = cx.new(|cx| {
+            Markdown::new(
+                format!("```bash\n{}\n```", input.command).into(),
+                None,
+                None,
+                cx,
+            )
+        });
+        let card = cx.new(|cx| ProposeCommandCard {
+            command: input.command,
+            command_markdown,
+            working_dir,
+            entity_id: cx.entity_id(),
+            state: ProposalState::Pending,
+            response_tx: Some(response_tx),
+        });
+
+        let output = cx.background_spawn(async move {
+            let output = response_rx
+                .await
+                .context("The user didn't respond to the proposed command")??;
+            Ok(output.into())
+        });
+
+        ToolResult {
+            output,
+            card: Some(card.into()),
+        }
+    }
+}
+
+enum ProposalState {
+    Pending,
+    Running,
+    Finished(Option<ExitStatus>),
+    Copied,
+}
+
+struct ProposeCommandCard {
+    command: String,
+    command_markdown: Entity<Markdown>,
+    working_dir: Option<PathBuf>,
+    entity_id: EntityId,
+    state: ProposalState,
+    response_tx: Option<oneshot::Sender<Result<String>>>,
+}
+
+impl ProposeCommandCard {
+    fn run_in_terminal(
+        &mut self,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(response_tx) = self.response_tx.take() else {
+            return;
+        };
+        self.state = ProposalState::Running;
+
+        let spawn = SpawnInTerminal {
+            id: TaskId(format!("agent-proposed-command-{}", self.entity_id)),
+            full_label: self.command.clone(),
+            label: self.command.clone(),
+            command: get_system_shell(),
+            args: vec!["-c".into(), self.command.clone()],
+            command_label: self.command.clone(),
+            cwd: self.working_dir.clone(),
+            use_new_terminal: true,
+            allow_concurrent_runs: true,
+            reveal: RevealStrategy::NoFocus,
+            ..Default::default()
+        };
+        let terminal = workspace.update(cx, |workspace, cx| {
+            let terminal_panel = workspace
+                .panel::<TerminalPanel>(cx)
+                .context("The terminal panel isn't available")?;
+            anyhow::Ok(terminal_panel.update(cx, |terminal_panel, cx| {
+                terminal_panel.add_terminal(
+                    TerminalKind::Task(spawn),
+                    RevealStrategy::NoFocus,
+                    window,
+                    cx,
+                )
+            }))
+        });
+
+        let command = self.command.clone();
+        let redactor = Redactor::from_settings(cx);
+        cx.spawn(async move |this, cx| {
+            let result = async {
+                let terminal = terminal??
+                    .await?
+                    .upgrade()
+                    .context("The terminal was closed")?;
+                let exit_status = terminal
+                    .update(cx, |terminal, cx| terminal.wait_for_completed_task(cx))?
+                    .await;
+                let content = terminal.read_with(cx, |terminal, _| terminal.get_content())?;
+                this.update(cx, |card, cx| {
+                    card.state = ProposalState::Finished(exit_status);
+                    cx.notify();
+                })?;
+                let content = redact(&content, redactor.as_ref());
+                let (output, _) = process_content(
+                    &content,
+                    &command,
+                    exit_status.map(portable_pty::ExitStatus::from),
+                );
+                anyhow::Ok(format!(
+                    "The user ran the command in the terminal.\n\n{output}"
+                ))
+            }
+            .await;
+            if result.is_err() {
+                this.update(cx, |card, cx| {
+                    card.state = ProposalState::Finished(None);
+                    cx.notify();
+                })
+                .ok();
+            }
+            response_tx.send(result).ok();
+        })
+        .detach();
+        cx.notify();
+    }
+
+    fn copy(&mut self, cx: &mut Context<Self>) {
+        let Some(response_tx) = self.response_tx.take() else {
+            return;
+        };
+        cx.write_to_clipboard(ClipboardItem::new_string(self.command.clone()));
+        self.state = ProposalState::Copied;
+        response_tx
+            .send(Ok(
+                "The user copied the command to run it themselves, so its output isn't available."
+                    .into(),
+            ))
+            .ok();
+        cx.notify();
+    }
+}
+
+impl ToolCard for ProposeCommandCard {
+    fn render(
+        &mut self,
+        status: &ToolUseStatus,
+        window: &mut Window,
+        workspace: WeakEntity<Workspace>,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let tool_failed = matches!(status, ToolUseStatus::Error(_));
+        let header_bg = cx
+            .theme()
+            .colors()
+            .element_background
+            .blend(cx.theme().colors().editor_foreground.opacity(0.025));
+        let border_color = cx.theme().colors().border.opacity(0.6);
+
+        let path = self
+            .working_dir
+            .as_ref()
+            .map(|path| path.display().to_string())
+            .unwrap_or_else(|| "current directory".to_string());
+
+        let footer = match &self.state {
+            ProposalState::Pending if !tool_failed => h_flex()
+                .gap_1()
+                .justify_end()
+                .child(
+                    Button::new(("copy-proposed-command", self.entity_id), "Copy")
+                        .icon(IconName::Copy)
+                        .icon_position(IconPosition::Start)
+                        .icon_size(IconSize::XSmall)
+                        .label_size(LabelSize::Small)
+                        .tooltip(Tooltip::text("Copy the command to run it yourself"))
+                        .on_click(cx.listener(|this, _, _, cx| this.copy(cx))),
+                )
+                .child(
+                    Button::new(("run-proposed-command", self.entity_id), "Run in Terminal")
+                        .icon(IconName::Play)
+                        .icon_position(IconPosition::Start)
+                        .icon_size(IconSize::XSmall)
+                        .label_size(LabelSize::Small)
+                        .style(ButtonStyle::Filled)
+                        .on_click(cx.listener(move |this, _, window, cx| {
+                            this.run_in_terminal(workspace.clone(), window, cx)
+                        })),
+                )
+                .into_any_element(),
+            ProposalState::Pending => Empty.into_any_element(),
+            ProposalState::Running => Label::new("Running in the terminal…")
+                .size(LabelSize::Small)
+                .color(Color::Muted)
+                .into_any_element(),
+            ProposalState::Finished(exit_status) => {
+                let succeeded = exit_status.is_some_and(|status| status.success());
+                h_flex()
+                    .gap_1()
+                    .child(
+                        Icon::new(if succeeded {
+                            IconName::Check
+                        } else {
+                            IconName::Close
+                        })
+                        .size(IconSize::Small)
+                        .color(if succeeded {
+                            Color::Success
+                        } else {
+                            Color::Error
+                        }),
+                    )
+                    .child(
+                        Label::new(match exit_status.and_then(|status| status.code()) {
+                            Some(code) => format!("Ran in the terminal, exited with code {code}"),
+                            None => "Ran in the terminal".to_string(),
+                        })
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                    )
+                    .into_any_element()
+            }
+            ProposalState::Copied => Label::new("Copied to the clipboard")
+                .size(LabelSize::Small)
+                .color(Color::Muted)
+                .into_any_element(),
+        };
+
+        v_flex()
+            .mb_2()
+            .border_1()
+            .when(tool_failed, |card| card.border_dashed())
+            .border_color(border_color)
+            .rounded_lg()
+            .overflow_hidden()
+            .child(
+                v_flex()
+                    .p_2()
+                    .gap_0p5()
+                    .bg(header_bg)
+                    .text_xs()
+                    .child(
+                        Label::new(path)
+                            .buffer_font(cx)
+                            .size(LabelSize::XSmall)
+                            .color(Color::Muted),
+                    )
+                    .child(
+                        MarkdownElement::new(
+                            self.command_markdown.clone(),
+                            markdown_style(window, cx),
+                        )
+                        .code_block_renderer(
+                            markdown::CodeBlockRenderer::Default {
+                                copy_button: false,
+                                copy_button_on_hover: false,
+                                border: false,
+                            },
+                        ),
+                    ),
+            )
+            .child(
+                div()
+                    .p_1p5()
+                    .border_t_1()
+                    .border_color(border_color)
+                    .child(footer),
+            )
+    }
+}
@@ -8,7 +8,7 @@ use project::{ImageItem, image_store};
 use assistant_tool::ToolResultOutput;
 use indoc::formatdoc;
 use itertools::Itertools;
-use language::{Anchor, Point};
+use language::{Anchor, OutlineItem, ParseStatus, Point};
 use language_model::{
     LanguageModel, LanguageModelImage, LanguageModelRequest, LanguageModelToolSchemaFormat,
 };
@@ -46,8 +46,18 @@ pub struct ReadFileToolInput {
     /// Optional line number to end reading on (1-based index, inclusive)
     #[serde(default)]
     pub end_line: Option<u32>,
+
+    /// Optional path of a symbol to read, such as `MyStruct::my_method`, instead of a line range.
+    ///
+    /// Only the definition of that symbol is returned, along with a few lines around it.
+    /// The path is made of the names in the file's outline, separated by `::`.
+    #[serde(default)]
+    pub symbol: Option<String>,
 }
 
+/// Lines of context to include around a definition read by its symbol path.
+const SYMBOL_CONTEXT_LINES: u32 = 2;
+
 pub struct ReadFileTool;
 
 impl Tool for ReadFileTool {
@@ -79,6 +89,9 @@ impl Tool for ReadFileTool {
         match serde_json::from_value::<ReadFileToolInput>(input.clone()) {
             Ok(input) => {
                 let path = MarkdownInlineCode(&input.path);
+                if let Some(symbol) = &input.symbol {
+                    return format!("Read {} in file {path}", MarkdownInlineCode(symbol));
+                }
                 match (input.start_line, input.end_line) {
                     (Some(start), None) => format!("Read file {path} (from line {start})"),
                     (Some(start), Some(end)) => format!("Read file {path} (lines {start}-{end})"),
@@ -175,6 +188,7 @@ impl Tool for ReadFileTool {
                 Ok(ToolResultOutput {
                     content: ToolResultContent::Image(language_model_image),
                     output: None,
+                    truncated: false,
                 })
             });
 
@@ -206,6 +220,68 @@ impl Tool for ReadFileTool {
                 );
             })?;
 
+            if let Some(symbol) = &input.symbol {
+                let mut parse_status = buffer.read_with(cx, |buffer, _| buffer.parse_status())?;
+                while *parse_status.borrow() != ParseStatus::Idle {
+                    parse_status.changed().await?;
+                }
+
+                let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot())?;
+                let outline = snapshot
+                    .outline(None)
+                    .with_context(|| format!("No outline information available for {file_path}, so symbols can't be resolved. Read a line range instead."))?;
+                let items = outline
+                    .items
+                    .iter()
+                    .map(|item| item.to_point(&snapshot))
+                    .collect::<Vec<_>>();
+                let matches = resolve_symbol(&items, symbol);
+                let Some(first_match) = matches.first() else {
+                    anyhow::bail!(
+                        "Symbol {} not found in {file_path}. Read the file without a symbol to see its outline.",
+                        MarkdownInlineCode(symbol)
+                    );
+                };
+                let anchor = snapshot.anchor_before(first_match.range.start);
+
+                let mut result = String::new();
+                for item in &matches {
+                    let start_row = item.range.start.row.saturating_sub(SYMBOL_CONTEXT_LINES);
+                    let end_row = (item.range.end.row + SYMBOL_CONTEXT_LINES).min(snapshot.max_point().row);
+                    let text = snapshot
+                        .text_for_range(Point::new(start_row, 0)..Point::new(end_row, snapshot.line_len(end_row)))
+                        .collect::<String>();
+                    if !result.is_empty() {
+                        result.push_str("\n\n");
+                    }
+                    result.push_str(&formatdoc! {"
+                        {} is defined at L{}-{}. Showing L{}-{}:
+
+                        {text}",
+                        MarkdownInlineCode(&item.text),
+                        item.range.start.row + 1,
+                        item.range.end.row + 1,
+                        start_row + 1,
+                        end_row + 1,
+                    });
+                }
+
+                action_log.update(cx, |log, cx| {
+                    log.buffer_read(buffer.clone(), cx);
+                })?;
+                project.update(cx, |project, cx| {
+                    project.set_agent_location(
+                        Some(AgentLocation {
+                            buffer: buffer.downgrade(),
+                            position: anchor,
+                        }),
+                        cx,
+                    );
+                })?;
+
+                return Ok(result.into());
+            }
+
             // Check if specific line ranges are provided
             if input.start_line.is_some() || input.end_line.is_some() {
                 let mut anchor = None;
@@ -267,7 +343,7 @@ impl Tool for ReadFileTool {
                     // and a suggestion to read again with line numbers.
                     let outline =
                         outline::file_outline(project, file_path, action_log, None, cx).await?;
-                    Ok(formatdoc! {"
+                    let content = formatdoc! {"
                         This file was too big to read all at once.
 
                         Here is an outline of its symbols:
@@ -276,9 +352,15 @@ impl Tool for ReadFileTool {
 
                         Using the line numbers in this outline, you can call this tool again
                         while specifying the start_line and end_line fields to see the
-                        implementations of symbols in the outline."
-                    }
-                    .into())
+                        implementations of symbols in the outline. You can also specify the
+                        symbol field with a path like `MyStruct::my_method` to read just that
+                        definition."
+                    };
+                    Ok(ToolResultOutput {
+                        content: ToolResultContent::Text(content),
+                        output: None,
+                        truncated: true,
+                    })
                 }
             }
         })
@@ -286,6 +368,51 @@ impl Tool for ReadFileTool {
     }
 }
 
+/// Finds the outline items named by the last segment of `symbol`, whose enclosing
+/// items are named by the preceding segments. Enclosing items that aren't named in
+/// `symbol` are skipped, so `MyStruct::new` matches `new` within `mod a { impl MyStruct }`.
+fn resolve_symbol<'a>(
+    items: &'a [OutlineItem<Point>],
+    symbol: &str,
+) -> Vec<&'a OutlineItem<Point>> {
+    let segments = symbol
+        .split("::")
+        .map(str::trim)
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<_>>();
+    let Some((name, parents)) = segments.split_last() else {
+        return Vec::new();
+    };
+
+    let mut matches = Vec::new();
+    let mut ancestors = Vec::<&OutlineItem<Point>>::new();
+    for item in items {
+        ancestors.truncate(item.depth);
+        if has_name(item, name) {
+            let mut remaining_ancestors = ancestors.iter().rev();
+            if parents
+                .iter()
+                .rev()
+                .all(|parent| remaining_ancestors.any(|ancestor| has_name(ancestor, parent)))
+            {
+                matches.push(item);
+            }
+        }
+        ancestors.push(item);
+    }
+    matches
+}
+
+fn has_name(item: &OutlineItem<Point>, name: &str) -> bool {
+    item.name_ranges.iter().any(|range| {
+        item.text.get(range.clone()).is_some_and(|item_name| {
+            // Match `MyStruct` against `impl<T> MyStruct<T>`.
+            let item_name = item_name.split('<').next().unwrap_or(item_name);
+            item_name.trim() == name
+        })
+    })
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -501,6 +628,103 @@ mod test {
         );
     }
 
+    #[gpui::test]
+    async fn test_read_file_by_symbol(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/root"),
+            json!({
+                "lib.rs": indoc::indoc! {"
+                    mod a {
+                        struct MyStruct;
+
+                        impl MyStruct {
+                            fn new() -> Self {
+                                MyStruct
+                            }
+
+                            fn other() {}
+                        }
+                    }
+
+                    fn new() {}
+                "}
+            }),
+        )
+        .await;
+        let project = Project::test(fs.clone(), [path!("/root").as_ref()], cx).await;
+        let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+        language_registry.add(Arc::new(rust_lang()));
+        let action_log = cx.new(|_| ActionLog::new(project.clone()));
+        let model = Arc::new(FakeLanguageModel::default());
+
+        let read_symbol = |symbol: &str, cx: &mut TestAppContext| {
+            let input = json!({
+                "path": "root/lib.rs",
+                "symbol": symbol,
+            });
+            cx.update(|cx| {
+                Arc::new(ReadFileTool)
+                    .run(
+                        input,
+                        Arc::default(),
+                        project.clone(),
+                        action_log.clone(),
+                        model.clone(),
+                        None,
+                        cx,
+                    )
+                    .output
+            })
+        };
+
+        let result = read_symbol("MyStruct::new", cx).await.unwrap();
+        assert_eq!(
+            result.content.as_str(),
+            Some(
+                [
+                    "`fn new` is defined at L5-7. Showing L3-9:",
+                    "",
+                    "",
+                    "    impl MyStruct {",
+                    "        fn new() -> Self {",
+                    "            MyStruct",
+                    "        }",
+                    "",
+                    "        fn other() {}",
+                ]
+                .join("\n")
+                .as_str()
+            )
+        );
+
+        let result = read_symbol("new", cx).await.unwrap();
+        let content = result.content.as_str().unwrap();
+        assert!(
+            content.contains("`fn new` is defined at L5-7."),
+            "{content}"
+        );
+        assert!(
+            content.contains("`fn new` is defined at L13-13."),
+            "{content}"
+        );
+
+        let result = read_symbol("a::other", cx).await.unwrap();
+        let content = result.content.as_str().unwrap();
+        assert!(
+            content.starts_with("`fn other` is defined at L9-9."),
+            "{content}"
+        );
+
+        let result = read_symbol("Other::new", cx).await;
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Symbol `Other::new` not found in root/lib.rs. Read the file without a symbol to see its outline."
+        );
+    }
+
     #[gpui::test]
     async fn test_read_file_line_range_edge_cases(cx: &mut TestAppContext) {
         init_test(cx);
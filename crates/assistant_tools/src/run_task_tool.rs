@@ -0,0 +1,359 @@
+use crate::{
+    schema::json_schema_for,
+    terminal_tool::{TerminalTool, TerminalToolInput, working_dir},
+};
+use anyhow::{Context as _, Result, anyhow};
+use assistant_tool::{ActionLog, Tool, ToolResult};
+use collections::HashSet;
+use gpui::{AnyWindowHandle, App, Entity, Task};
+use language_model::{LanguageModel, LanguageModelRequest, LanguageModelToolSchemaFormat};
+use project::{Inventory, Project, TaskSourceKind, WorktreeId};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::{path::Path, sync::Arc};
+use task::{ResolvedTask, SpawnInTerminal, TaskContext, TaskVariables, VariableName};
+use ui::IconName;
+use util::{ResultExt as _, markdown::MarkdownInlineCode};
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct RunTaskToolInput {
+    /// The label of the task to run, exactly as it appears when listing tasks.
+    ///
+    /// If not provided, the tasks available in the project are listed instead of running one.
+    #[serde(default)]
+    pub label: Option<String>,
+    /// The root directory of the project whose tasks should be listed or run.
+    pub cd: String,
+}
+
+pub struct RunTaskTool {
+    terminal_tool: Arc<TerminalTool>,
+}
+
+impl RunTaskTool {
+    pub(crate) fn new(cx: &mut App) -> Self {
+        Self {
+            terminal_tool: Arc::new(TerminalTool::new(cx)),
+        }
+    }
+}
+
+impl Tool for RunTaskTool {
+    fn name(&self) -> String {
+        "run_task".into()
+    }
+
+    fn needs_confirmation(&self, input: &serde_json::Value, _: &App) -> bool {
+        serde_json::from_value::<RunTaskToolInput>(input.clone())
+            .map_or(true, |input| input.label.is_some())
+    }
+
+    fn may_perform_edits(&self) -> bool {
+        false
+    }
+
+    fn supports_plan_only(&self) -> bool {
+        // Tasks can change files behind the action log's back.
+        false
+    }
+
+    fn description(&self) -> String {
+        include_str!("./run_task_tool/description.md").into()
+    }
+
+    fn icon(&self) -> IconName {
+        IconName::Play
+    }
+
+    fn input_schema(&self, format: LanguageModelToolSchemaFormat) -> Result<serde_json::Value> {
+        json_schema_for::<RunTaskToolInput>(format)
+    }
+
+    fn ui_text(&self, input: &serde_json::Value) -> String {
+        match serde_json::from_value::<RunTaskToolInput>(input.clone()) {
+            Ok(RunTaskToolInput {
+                label: Some(label), ..
+            }) => format!("Run task {}", MarkdownInlineCode(&label)),
+            _ => "List project tasks".to_string(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: serde_json::Value,
+        _request: Arc<LanguageModelRequest>,
+        project: Entity<Project>,
+        _action_log: Entity<ActionLog>,
+        _model: Arc<dyn LanguageModel>,
+        window: Option<AnyWindowHandle>,
+        cx: &mut App,
+    ) -> ToolResult {
+        let input: RunTaskToolInput = match serde_json::from_value(input) {
+            Ok(input) => input,
+            Err(err) => return Task::ready(Err(anyhow!(err))).into(),
+        };
+
+        let root = match working_dir(&input.cd, &project, cx) {
+            Ok(Some(root)) => root,
+            Ok(None) => return Task::ready(Err(anyhow!("The project has no worktrees"))).into(),
+            Err(err) => return Task::ready(Err(err)).into(),
+        };
+        let Some((worktree_id, worktree_root)) = project
+            .read(cx)
+            .worktrees(cx)
+            .find(|worktree| root.starts_with(worktree.read(cx).abs_path()))
+            .map(|worktree| (worktree.read(cx).id(), worktree.read(cx).abs_path()))
+        else {
+            return Task::ready(Err(anyhow!("{root:?} is not a worktree of the project"))).into();
+        };
+        let Some(inventory) = project
+            .read(cx)
+            .task_store()
+            .read(cx)
+            .task_inventory()
+            .cloned()
+        else {
+            return Task::ready(Err(anyhow!("Tasks are not available in this project"))).into();
+        };
+
+        let tasks = runnable_tasks(
+            inventory.read(cx),
+            &project,
+            worktree_id,
+            &worktree_root,
+            cx,
+        );
+
+        let Some(label) = input.label else {
+            if tasks.is_empty() {
+                return Task::ready(Ok("No tasks are configured for this project."
+                    .to_string()
+                    .into()))
+                .into();
+            }
+
+            let mut output = String::new();
+            for task in &tasks {
+                let Some(command) = task_command(&task.resolved).log_err() else {
+                    continue;
+                };
+                output.push_str(&format!(
+                    "- {}: {}\n",
+                    MarkdownInlineCode(&task.resolved_label),
+                    MarkdownInlineCode(&command)
+                ));
+            }
+            return Task::ready(Ok(output.into())).into();
+        };
+
+        let Some(task) = tasks.into_iter().find(|task| task.resolved_label == label) else {
+            return Task::ready(Err(anyhow!(
+                "No task labeled {label:?}. List the available tasks by omitting the label."
+            )))
+            .into();
+        };
+
+        let command = match task_command(&task.resolved) {
+            Ok(command) => command,
+            Err(err) => return Task::ready(Err(err)).into(),
+        };
+        let spawn = task.resolved;
+        let terminal_input = TerminalToolInput {
+            command,
+            cd: spawn.cwd.unwrap_or(root).to_string_lossy().into_owned(),
+        };
+
+        self.terminal_tool
+            .clone()
+            .run_command(terminal_input, spawn.env, project, window, cx)
+    }
+}
+
+/// Resolves the tasks that can be run in the worktree. The agent has no active editor, so
+/// language tasks are taken from the worktree's open buffers and resolved against each of them.
+/// Tasks that need more context than that, like the symbol under the cursor, are left out.
+fn runnable_tasks(
+    inventory: &Inventory,
+    project: &Entity<Project>,
+    worktree_id: WorktreeId,
+    worktree_root: &Path,
+    cx: &App,
+) -> Vec<ResolvedTask> {
+    let worktree_context = TaskContext {
+        cwd: Some(worktree_root.to_path_buf()),
+        task_variables: TaskVariables::from_iter([(
+            VariableName::WorktreeRoot,
+            worktree_root.to_string_lossy().into_owned(),
+        )]),
+        project_env: Default::default(),
+    };
+    let mut tasks = inventory
+        .list_tasks(None, None, Some(worktree_id), cx)
+        .into_iter()
+        .filter_map(|(kind, template)| template.resolve_task(&kind.to_id_base(), &worktree_context))
+        .collect::<Vec<_>>();
+
+    for buffer in project.read(cx).buffer_store().read(cx).buffers() {
+        let buffer = buffer.read(cx);
+        let (Some(file), Some(language)) = (buffer.file(), buffer.language()) else {
+            continue;
+        };
+        if file.worktree_id(cx) != worktree_id {
+            continue;
+        }
+
+        let abs_path = worktree_root.join(file.path());
+        let mut task_context = worktree_context.clone();
+        let variables = &mut task_context.task_variables;
+        variables.insert(VariableName::File, abs_path.to_string_lossy().into_owned());
+        variables.insert(
+            VariableName::RelativeFile,
+            file.path().to_string_lossy().into_owned(),
+        );
+        if let Some(dirname) = abs_path.parent() {
+            variables.insert(
+                VariableName::Dirname,
+                dirname.to_string_lossy().into_owned(),
+            );
+        }
+        if let Some(filename) = abs_path.file_name() {
+            variables.insert(
+                VariableName::Filename,
+                filename.to_string_lossy().into_owned(),
+            );
+        }
+        if let Some(stem) = abs_path.file_stem() {
+            variables.insert(VariableName::Stem, stem.to_string_lossy().into_owned());
+        }
+
+        tasks.extend(
+            inventory
+                .list_tasks(
+                    Some(file.clone()),
+                    Some(language.clone()),
+                    Some(worktree_id),
+                    cx,
+                )
+                .into_iter()
+                .filter(|(kind, _)| matches!(kind, TaskSourceKind::Language { .. }))
+                .filter_map(|(kind, template)| {
+                    template.resolve_task(&kind.to_id_base(), &task_context)
+                }),
+        );
+    }
+
+    // The same language task is resolved once per open file, and the label tells them apart
+    // when it mentions the file.
+    let mut labels = HashSet::default();
+    tasks.retain(|task| labels.insert(task.resolved_label.clone()));
+    tasks
+}
+
+/// The shell command that runs the task. The task's command is a shell command in its own
+/// right, but its arguments are quoted so they're passed on verbatim.
+fn task_command(spawn: &SpawnInTerminal) -> Result<String> {
+    let mut command = spawn.command.clone();
+    for arg in &spawn.args {
+        let arg = shlex::try_quote(arg)
+            .with_context(|| format!("task argument {arg:?} can't be passed to a shell"))?;
+        command.push(' ');
+        command.push_str(&arg);
+    }
+    Ok(command)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fs::FakeFs;
+    use gpui::{AppContext as _, TestAppContext};
+    use language::{Language, LanguageConfig, LanguageMatcher};
+    use language_model::fake_provider::FakeLanguageModel;
+    use pretty_assertions::assert_eq;
+    use project::ContextProviderWithTasks;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use task::{TaskTemplate, TaskTemplates};
+    use util::path;
+
+    #[gpui::test]
+    async fn test_list_language_tasks(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/root"), json!({ "src": { "lib.rs": "" } }))
+            .await;
+        let project = Project::test(fs, [path!("/root").as_ref()], cx).await;
+        project.read_with(cx, |project, _| {
+            project.languages().add(Arc::new(
+                Language::new(
+                    LanguageConfig {
+                        name: "Rust".into(),
+                        matcher: LanguageMatcher {
+                            path_suffixes: vec!["rs".to_string()],
+                            ..LanguageMatcher::default()
+                        },
+                        ..LanguageConfig::default()
+                    },
+                    None,
+                )
+                .with_context_provider(Some(Arc::new(
+                    ContextProviderWithTasks::new(TaskTemplates(vec![
+                        TaskTemplate {
+                            label: "Test $ZED_STEM".to_string(),
+                            command: "cargo test".to_string(),
+                            args: vec!["--".to_string(), "tests in $ZED_STEM".to_string()],
+                            ..TaskTemplate::default()
+                        },
+                        TaskTemplate {
+                            label: "Test symbol".to_string(),
+                            command: "cargo test".to_string(),
+                            args: vec!["$ZED_SYMBOL".to_string()],
+                            ..TaskTemplate::default()
+                        },
+                    ])),
+                ))),
+            ));
+        });
+        let _buffer = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer(path!("/root/src/lib.rs"), cx)
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+
+        let output = cx
+            .update(|cx| {
+                let action_log = cx.new(|_| ActionLog::new(project.clone()));
+                let model = Arc::new(FakeLanguageModel::default());
+                Arc::new(RunTaskTool::new(cx))
+                    .run(
+                        json!({ "cd": "root" }),
+                        Arc::default(),
+                        project.clone(),
+                        action_log,
+                        model,
+                        None,
+                        cx,
+                    )
+                    .output
+            })
+            .await
+            .unwrap();
+        // The task that needs the symbol under the cursor can't be resolved without an editor.
+        assert_eq!(
+            output.content.as_str().unwrap(),
+            "- `Test lib`: `cargo test -- 'tests in lib'`\n"
+        );
+    }
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            language::init(cx);
+            Project::init_settings(cx);
+        });
+    }
+}
@@ -1,9 +1,14 @@
+mod redaction;
+
 use crate::{
     schema::json_schema_for,
     ui::{COLLAPSED_LINES, ToolOutputPreview},
 };
 use anyhow::{Context as _, Result, anyhow};
-use assistant_tool::{ActionLog, Tool, ToolCard, ToolResult, ToolUseStatus};
+use assistant_tool::{
+    ActionLog, Tool, ToolCard, ToolResult, ToolResultContent, ToolResultOutput, ToolUseStatus,
+};
+use collections::HashMap;
 use futures::{FutureExt as _, future::Shared};
 use gpui::{
     AnyWindowHandle, App, AppContext, Empty, Entity, EntityId, Task, TextStyleRefinement,
@@ -18,6 +23,7 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use settings::Settings;
 use std::{
+    borrow::Cow,
     env,
     path::{Path, PathBuf},
     process::ExitStatus,
@@ -33,14 +39,16 @@ use util::{
 };
 use workspace::Workspace;
 
+pub(crate) use redaction::Redactor;
+
 const COMMAND_OUTPUT_LIMIT: usize = 16 * 1024;
 
 #[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct TerminalToolInput {
     /// The one-liner command to execute.
-    command: String,
+    pub(crate) command: String,
     /// Working directory for the command. This must be one of the root directories of the project.
-    cd: String,
+    pub(crate) cd: String,
 }
 
 pub struct TerminalTool {
@@ -84,6 +92,11 @@ impl Tool for TerminalTool {
         false
     }
 
+    fn supports_plan_only(&self) -> bool {
+        // Commands can change files behind the action log's back.
+        false
+    }
+
     fn description(&self) -> String {
         include_str!("./terminal_tool/description.md").to_string()
     }
@@ -131,8 +144,22 @@ impl Tool for TerminalTool {
             Ok(input) => input,
             Err(err) => return Task::ready(Err(anyhow!(err))).into(),
         };
+        self.run_command(input, HashMap::default(), project, window, cx)
+    }
+}
 
-        let working_dir = match working_dir(&input, &project, cx) {
+impl TerminalTool {
+    /// Runs `input.command` with `extra_env` added to the project's environment. The extra
+    /// environment isn't part of the input, so that the model can't set it.
+    pub(crate) fn run_command(
+        self: Arc<Self>,
+        input: TerminalToolInput,
+        extra_env: HashMap<String, String>,
+        project: Entity<Project>,
+        window: Option<AnyWindowHandle>,
+        cx: &mut App,
+    ) -> ToolResult {
+        let working_dir = match working_dir(&input.cd, &project, cx) {
             Ok(dir) => dir,
             Err(err) => return Task::ready(Err(err)).into(),
         };
@@ -158,19 +185,26 @@ impl Tool for TerminalTool {
             None => Task::ready(None).shared(),
         };
 
+        let redactor = Redactor::from_settings(cx);
         let env = cx.spawn(async move |_| {
             let mut env = env.await.unwrap_or_default();
+            env.extend(extra_env);
             if cfg!(unix) {
                 env.insert("PAGER".into(), "cat".into());
             }
-            env
+            // The project's environment can contain secrets that the process environment doesn't.
+            let redactor = redactor.map(|mut redactor| {
+                redactor.add_environment(&env);
+                redactor
+            });
+            (env, redactor)
         });
 
         let Some(window) = window else {
             // Headless setup, a test or eval. Our terminal subsystem requires a workspace,
             // so bypass it and provide a convincing imitation using a pty.
             let task = cx.background_spawn(async move {
-                let env = env.await;
+                let (env, redactor) = env.await;
                 let pty_system = native_pty_system();
                 let program = program.await;
                 let mut cmd = CommandBuilder::new(program);
@@ -198,10 +232,15 @@ impl Tool for TerminalTool {
                     .filter(|c| c.is_ascii_whitespace() || !c.is_ascii_control())
                     .collect();
                 let content = content.trim_start().trim_start_matches("^D");
+                let content = redact(content, redactor.as_ref());
                 let exit_status = child.wait()?;
                 let (processed_content, _) =
-                    process_content(content, &input.command, Some(exit_status));
-                Ok(processed_content.into())
+                    process_content(&content, &input.command, Some(exit_status));
+                Ok(ToolResultOutput {
+                    content: ToolResultContent::Text(processed_content),
+                    output: None,
+                    truncated: content.len() > COMMAND_OUTPUT_LIMIT,
+                })
             });
             return ToolResult {
                 output: task,
@@ -213,7 +252,7 @@ impl Tool for TerminalTool {
             let project = project.downgrade();
             async move |cx| {
                 let program = program.await;
-                let env = env.await;
+                let (env, redactor) = env.await;
                 let terminal = project
                     .update(cx, |project, cx| {
                         project.create_terminal(
@@ -228,8 +267,8 @@ impl Tool for TerminalTool {
                             cx,
                         )
                     })?
-                    .await;
-                terminal
+                    .await?;
+                anyhow::Ok((terminal, redactor))
             }
         });
 
@@ -253,7 +292,7 @@ impl Tool for TerminalTool {
         let output = cx.spawn({
             let card = card.clone();
             async move |cx| {
-                let terminal = terminal.await?;
+                let (terminal, redactor) = terminal.await?;
                 let workspace = window
                     .downcast::<Workspace>()
                     .and_then(|handle| handle.entity(cx).ok())
@@ -286,6 +325,7 @@ impl Tool for TerminalTool {
                 let (content, content_line_count) = terminal.read_with(cx, |terminal, _| {
                     (terminal.get_content(), terminal.total_lines())
                 })?;
+                let content = redact(&content, redactor.as_ref());
 
                 let previous_len = content.len();
                 let (processed_content, finished_with_empty_output) = process_content(
@@ -294,10 +334,11 @@ impl Tool for TerminalTool {
                     exit_status.map(portable_pty::ExitStatus::from),
                 );
 
+                let was_content_truncated = processed_content.len() < previous_len;
                 card.update(cx, |card, _| {
                     card.command_finished = true;
                     card.exit_status = exit_status;
-                    card.was_content_truncated = processed_content.len() < previous_len;
+                    card.was_content_truncated = was_content_truncated;
                     card.original_content_len = previous_len;
                     card.content_line_count = content_line_count;
                     card.finished_with_empty_output = finished_with_empty_output;
@@ -305,7 +346,11 @@ impl Tool for TerminalTool {
                 })
                 .log_err();
 
-                Ok(processed_content.into())
+                Ok(ToolResultOutput {
+                    content: ToolResultContent::Text(processed_content),
+                    output: None,
+                    truncated: was_content_truncated,
+                })
             }
         });
 
@@ -316,7 +361,14 @@ impl Tool for TerminalTool {
     }
 }
 
-fn process_content(
+pub(crate) fn redact<'a>(content: &'a str, redactor: Option<&Redactor>) -> Cow<'a, str> {
+    match redactor {
+        Some(redactor) => redactor.redact(content),
+        None => Cow::Borrowed(content),
+    }
+}
+
+pub(crate) fn process_content(
     content: &str,
     command: &str,
     exit_status: Option<portable_pty::ExitStatus>,
@@ -377,13 +429,12 @@ fn process_content(
     (content, is_empty)
 }
 
-fn working_dir(
-    input: &TerminalToolInput,
+pub(crate) fn working_dir(
+    cd: &str,
     project: &Entity<Project>,
     cx: &mut App,
 ) -> Result<Option<PathBuf>> {
     let project = project.read(cx);
-    let cd = &input.cd;
 
     if cd == "." || cd == "" {
         // Accept "." or "" as meaning "the one worktree" if we only have one worktree.
@@ -668,7 +719,7 @@ impl ToolCard for TerminalToolCard {
     }
 }
 
-fn markdown_style(window: &Window, cx: &App) -> MarkdownStyle {
+pub(crate) fn markdown_style(window: &Window, cx: &App) -> MarkdownStyle {
     let theme_settings = ThemeSettings::get_global(cx);
     let buffer_font_size = TextSize::Default.rems(cx);
     let mut text_style = window.text_style();
@@ -691,6 +742,7 @@ fn markdown_style(window: &Window, cx: &App) -> MarkdownStyle {
 
 #[cfg(test)]
 mod tests {
+    use agent_settings::AgentSettings;
     use editor::EditorSettings;
     use fs::RealFs;
     use gpui::{BackgroundExecutor, TestAppContext};
@@ -717,6 +769,7 @@ mod tests {
             ThemeSettings::register(cx);
             TerminalSettings::register(cx);
             EditorSettings::register(cx);
+            AgentSettings::register(cx);
         });
     }
 
@@ -85,6 +85,7 @@ impl Tool for WebSearchTool {
                             .context("Failed to serialize search results")?,
                     ),
                     output: Some(serde_json::to_value(response)?),
+                    truncated: false,
                 })
             }
         });
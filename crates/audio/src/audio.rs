@@ -1,16 +1,20 @@
 use assets::SoundRegistry;
 use derive_more::{Deref, DerefMut};
 use gpui::{App, AssetSource, BorrowAppContext, Global};
-use rodio::{OutputStream, OutputStreamHandle};
+use rodio::{OutputStream, OutputStreamHandle, Sink, Source as _};
 use util::ResultExt;
 
 mod assets;
+mod microphone;
+
+pub use microphone::{MicrophoneCapture, RecordedAudio};
 
 pub fn init(source: impl AssetSource, cx: &mut App) {
     SoundRegistry::set_global(source, cx);
     cx.set_global(GlobalAudio(Audio::new()));
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Sound {
     Joined,
     Leave,
@@ -35,6 +39,11 @@ impl Sound {
     }
 }
 
+/// A sound that keeps playing until this handle is dropped.
+pub struct PlayingSound {
+    _sink: Sink,
+}
+
 #[derive(Default)]
 pub struct Audio {
     _output_stream: Option<OutputStream>,
@@ -62,6 +71,11 @@ impl Audio {
     }
 
     pub fn play_sound(sound: Sound, cx: &mut App) {
+        Self::play_sound_at_volume(sound, 1., cx);
+    }
+
+    /// Plays the given sound once, with its volume scaled by `volume` (between 0 and 1).
+    pub fn play_sound_at_volume(sound: Sound, volume: f32, cx: &mut App) {
         if !cx.has_global::<GlobalAudio>() {
             return;
         }
@@ -69,11 +83,29 @@ impl Audio {
         cx.update_global::<GlobalAudio, _>(|this, cx| {
             let output_handle = this.ensure_output_exists()?;
             let source = SoundRegistry::global(cx).get(sound.file()).log_err()?;
-            output_handle.play_raw(source).log_err()?;
+            output_handle
+                .play_raw(source.amplify(volume.clamp(0., 1.)))
+                .log_err()?;
             Some(())
         });
     }
 
+    /// Plays the given sound repeatedly until the returned handle is dropped.
+    pub fn play_looping(sound: Sound, volume: f32, cx: &mut App) -> Option<PlayingSound> {
+        if !cx.has_global::<GlobalAudio>() {
+            return None;
+        }
+
+        cx.update_global::<GlobalAudio, _>(|this, cx| {
+            let output_handle = this.ensure_output_exists()?;
+            let source = SoundRegistry::global(cx).get(sound.file()).log_err()?;
+            let sink = Sink::try_new(output_handle).log_err()?;
+            sink.set_volume(volume.clamp(0., 1.));
+            sink.append(source.repeat_infinite());
+            Some(PlayingSound { _sink: sink })
+        })
+    }
+
     pub fn end_call(cx: &mut App) {
         if !cx.has_global::<GlobalAudio>() {
             return;
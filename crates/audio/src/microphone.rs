@@ -0,0 +1,164 @@
+use std::sync::{Arc, mpsc};
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{Context as _, Result, anyhow};
+use parking_lot::Mutex;
+use rodio::cpal::{
+    self, FromSample, Sample, SampleFormat, SizedSample,
+    traits::{DeviceTrait, HostTrait, StreamTrait},
+};
+
+/// Records mono audio from the default input device until dropped.
+pub struct MicrophoneCapture {
+    recording: RecordedAudio,
+    // The input stream can't be sent between threads on every platform, so it lives on its own
+    // thread, which stops recording when this sender is dropped.
+    _stop: mpsc::Sender<()>,
+}
+
+/// A handle to the audio recorded by a [`MicrophoneCapture`], which can be encoded on another
+/// thread while recording continues.
+#[derive(Clone)]
+pub struct RecordedAudio {
+    samples: Arc<Mutex<Vec<f32>>>,
+    sample_rate: u32,
+}
+
+impl MicrophoneCapture {
+    /// Opens the default input device and starts recording. This blocks while the device is
+    /// opened, so it shouldn't be called on the main thread.
+    pub fn start() -> Result<Self> {
+        let samples = Arc::new(Mutex::new(Vec::new()));
+        let (started_tx, started_rx) = mpsc::channel();
+        let (stop_tx, stop_rx) = mpsc::channel::<()>();
+
+        thread::Builder::new()
+            .name("microphone-capture".into())
+            .spawn({
+                let samples = samples.clone();
+                move || match open_input_stream(samples) {
+                    Ok((stream, sample_rate)) => {
+                        started_tx.send(Ok(sample_rate)).ok();
+                        // Blocks until the capture is dropped.
+                        stop_rx.recv().ok();
+                        drop(stream);
+                    }
+                    Err(error) => {
+                        started_tx.send(Err(error)).ok();
+                    }
+                }
+            })
+            .context("failed to spawn audio input thread")?;
+
+        let sample_rate = started_rx
+            .recv()
+            .context("audio input thread exited unexpectedly")??;
+        Ok(Self {
+            recording: RecordedAudio {
+                samples,
+                sample_rate,
+            },
+            _stop: stop_tx,
+        })
+    }
+
+    /// How much audio has been recorded so far.
+    pub fn duration(&self) -> Duration {
+        self.recording.duration()
+    }
+
+    pub fn recording(&self) -> RecordedAudio {
+        self.recording.clone()
+    }
+}
+
+impl RecordedAudio {
+    pub fn duration(&self) -> Duration {
+        let sample_count = self.samples.lock().len();
+        Duration::from_secs_f64(sample_count as f64 / self.sample_rate as f64)
+    }
+
+    /// Encodes everything recorded so far as a 16-bit PCM WAV file.
+    pub fn to_wav(&self) -> Vec<u8> {
+        let samples = self.samples.lock().clone();
+        encode_wav(&samples, self.sample_rate)
+    }
+}
+
+fn open_input_stream(samples: Arc<Mutex<Vec<f32>>>) -> Result<(cpal::Stream, u32)> {
+    let device = cpal::default_host()
+        .default_input_device()
+        .context("no audio input device available")?;
+    let config = device
+        .default_input_config()
+        .context("failed to get default input config")?;
+    let sample_rate = config.sample_rate().0;
+    let channels = config.channels() as usize;
+
+    let stream = match config.sample_format() {
+        SampleFormat::F32 => build_input_stream::<f32>(&device, &config.into(), channels, samples),
+        SampleFormat::I16 => build_input_stream::<i16>(&device, &config.into(), channels, samples),
+        SampleFormat::U16 => build_input_stream::<u16>(&device, &config.into(), channels, samples),
+        format => Err(anyhow!("unsupported input sample format {format:?}")),
+    }?;
+    stream.play().context("failed to start recording")?;
+    Ok((stream, sample_rate))
+}
+
+fn build_input_stream<T>(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    channels: usize,
+    samples: Arc<Mutex<Vec<f32>>>,
+) -> Result<cpal::Stream>
+where
+    T: SizedSample,
+    f32: FromSample<T>,
+{
+    let stream = device.build_input_stream(
+        config,
+        move |data: &[T], _: &cpal::InputCallbackInfo| {
+            let mut samples = samples.lock();
+            samples.extend(data.chunks(channels.max(1)).map(|frame| {
+                frame
+                    .iter()
+                    .map(|sample| sample.to_sample::<f32>())
+                    .sum::<f32>()
+                    / frame.len() as f32
+            }));
+        },
+        |error| log::error!("error recording audio: {error}"),
+        None,
+    )?;
+    Ok(stream)
+}
+
+fn encode_wav(samples: &[f32], sample_rate: u32) -> Vec<u8> {
+    const BITS_PER_SAMPLE: u16 = 16;
+    const CHANNELS: u16 = 1;
+
+    let block_align = CHANNELS * BITS_PER_SAMPLE / 8;
+    let byte_rate = sample_rate * block_align as u32;
+    let data_len = samples.len() as u32 * block_align as u32;
+
+    let mut wav = Vec::with_capacity(44 + data_len as usize);
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+    wav.extend_from_slice(b"WAVE");
+    wav.extend_from_slice(b"fmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes());
+    wav.extend_from_slice(&CHANNELS.to_le_bytes());
+    wav.extend_from_slice(&sample_rate.to_le_bytes());
+    wav.extend_from_slice(&byte_rate.to_le_bytes());
+    wav.extend_from_slice(&block_align.to_le_bytes());
+    wav.extend_from_slice(&BITS_PER_SAMPLE.to_le_bytes());
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_len.to_le_bytes());
+    for sample in samples {
+        let sample = (sample.clamp(-1., 1.) * i16::MAX as f32) as i16;
+        wav.extend_from_slice(&sample.to_le_bytes());
+    }
+    wav
+}
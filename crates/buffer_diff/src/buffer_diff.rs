@@ -947,6 +947,84 @@ impl BufferDiff {
         new_index_text
     }
 
+    /// Stages only the given lines of the buffer, rather than the whole hunks that contain them.
+    ///
+    /// Only lines that are additions relative to the index are staged. This is what allows
+    /// a file that isn't in HEAD, and therefore forms a single hunk, to be staged piecemeal
+    /// once it has an entry in the index (e.g. after `git add --intent-to-add`).
+    pub fn stage_lines(
+        &mut self,
+        ranges: &[Range<Anchor>],
+        buffer: &text::BufferSnapshot,
+        cx: &mut Context<Self>,
+    ) -> Option<Rope> {
+        let unstaged_diff = self.secondary_diff.clone()?;
+        let new_index_text = {
+            let unstaged_diff = unstaged_diff.read(cx);
+            if !unstaged_diff.base_text_exists() {
+                return None;
+            }
+
+            // Pairs of an offset in the index and the buffer range to insert there.
+            let mut insertions = Vec::<(usize, Range<usize>)>::new();
+            for range in ranges {
+                let range = range.to_point(buffer);
+                let start = Point::new(range.start.row, 0);
+                let end = if range.end.column == 0 && range.end.row > range.start.row {
+                    range.end
+                } else {
+                    Point::new(range.end.row + 1, 0).min(buffer.max_point())
+                };
+                let line_range = start.to_offset(buffer)..end.to_offset(buffer);
+                for hunk in unstaged_diff.hunks_intersecting_range(
+                    buffer.anchor_before(start)..buffer.anchor_after(end),
+                    buffer,
+                    cx,
+                ) {
+                    if !hunk.diff_base_byte_range.is_empty() {
+                        continue;
+                    }
+                    let hunk_range = hunk.buffer_range.to_offset(buffer);
+                    let start = hunk_range.start.max(line_range.start);
+                    let end = hunk_range.end.min(line_range.end);
+                    if start < end {
+                        insertions.push((hunk.diff_base_byte_range.start, start..end));
+                    }
+                }
+            }
+            if insertions.is_empty() {
+                return None;
+            }
+            insertions.sort_by_key(|(_, range)| range.start);
+            insertions.dedup_by(|(next_offset, next_range), (offset, range)| {
+                if next_offset == offset && next_range.start <= range.end {
+                    range.end = range.end.max(next_range.end);
+                    true
+                } else {
+                    false
+                }
+            });
+
+            let index_text = unstaged_diff.base_text().as_rope().clone();
+            let mut new_index_text = Rope::new();
+            let mut index_cursor = index_text.cursor(0);
+            for (index_offset, buffer_range) in insertions {
+                new_index_text.append(index_cursor.slice(index_offset));
+                new_index_text.push(&buffer.text_for_range(buffer_range).collect::<String>());
+            }
+            new_index_text.append(index_cursor.suffix());
+            new_index_text
+        };
+
+        cx.emit(BufferDiffEvent::HunksStagedOrUnstaged(Some(
+            new_index_text.clone(),
+        )));
+        cx.emit(BufferDiffEvent::DiffChanged {
+            changed_range: Some(Anchor::MIN..Anchor::MAX),
+        });
+        Some(new_index_text)
+    }
+
     pub fn range_to_hunk_range(
         &self,
         range: Range<Anchor>,
@@ -1889,6 +1967,50 @@ mod tests {
         });
     }
 
+    #[gpui::test]
+    async fn test_stage_lines_of_new_file(cx: &mut TestAppContext) {
+        let buffer_text = "
+            one
+            two
+            three
+            four
+        "
+        .unindent();
+
+        // The file isn't in HEAD, and was added to the index with intent-to-add.
+        let buffer = Buffer::new(0, BufferId::new(1).unwrap(), buffer_text.clone());
+        let unstaged_diff = cx.new(|cx| {
+            let mut diff = BufferDiff::new(&buffer, cx);
+            let unstaged = BufferDiffSnapshot::new_sync(buffer.clone(), String::new(), cx);
+            diff.set_snapshot(unstaged, &buffer, cx);
+            diff
+        });
+        let uncommitted_diff = cx.new(|cx| {
+            let mut diff = BufferDiff::new(&buffer, cx);
+            diff.set_secondary_diff(unstaged_diff.clone());
+            diff
+        });
+
+        let new_index_text = uncommitted_diff.update(cx, |diff, cx| {
+            let range =
+                buffer.anchor_before(Point::new(1, 1))..buffer.anchor_after(Point::new(1, 2));
+            diff.stage_lines(&[range], &buffer, cx).unwrap().to_string()
+        });
+        assert_eq!(new_index_text, "two\n");
+
+        let unstaged = BufferDiffSnapshot::new_sync(buffer.clone(), new_index_text, cx);
+        unstaged_diff.update(cx, |diff, cx| diff.set_snapshot(unstaged, &buffer, cx));
+        let new_index_text = uncommitted_diff.update(cx, |diff, cx| {
+            let ranges = [
+                buffer.anchor_before(Point::new(0, 0))..buffer.anchor_after(Point::new(0, 0)),
+                buffer.anchor_before(Point::new(1, 0))..buffer.anchor_after(Point::new(1, 3)),
+                buffer.anchor_before(Point::new(3, 2))..buffer.anchor_after(Point::new(4, 0)),
+            ];
+            diff.stage_lines(&ranges, &buffer, cx).unwrap().to_string()
+        });
+        assert_eq!(new_index_text, "one\ntwo\nfour\n");
+    }
+
     #[gpui::test]
     async fn test_buffer_diff_compare(cx: &mut TestAppContext) {
         let base_text = "
@@ -1127,6 +1127,36 @@ impl Room {
         })
     }
 
+    /// Whether the given participant is still sharing the project in this room and it hasn't
+    /// been joined yet.
+    pub fn is_project_awaiting_join(&self, owner_id: u64, project_id: u64, cx: &App) -> bool {
+        let is_shared = self
+            .remote_participants
+            .get(&owner_id)
+            .is_some_and(|participant| {
+                participant
+                    .projects
+                    .iter()
+                    .any(|project| project.id == project_id)
+            });
+        let is_joined = self.joined_projects.iter().any(|project| {
+            project
+                .upgrade()
+                .is_some_and(|project| project.read(cx).remote_id() == Some(project_id))
+        });
+        is_shared && !is_joined
+    }
+
+    /// Fetches the top-level entries of a project shared in this room, without joining it.
+    pub fn project_preview(
+        &self,
+        project_id: u64,
+        cx: &App,
+    ) -> Task<Result<Vec<proto::ProjectPreviewWorktree>>> {
+        let request = self.client.request(proto::GetProjectPreview { project_id });
+        cx.background_spawn(async move { Ok(request.await?.worktrees) })
+    }
+
     pub fn join_project(
         &mut self,
         id: u64,
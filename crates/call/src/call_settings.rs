@@ -1,4 +1,5 @@
 use anyhow::Result;
+use audio::Sound;
 use gpui::App;
 use schemars::JsonSchema;
 use serde_derive::{Deserialize, Serialize};
@@ -8,6 +9,45 @@ use settings::{Settings, SettingsSources};
 pub struct CallSettings {
     pub mute_on_join: bool,
     pub share_on_join: bool,
+    pub notification_sounds: NotificationSoundSettings,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct NotificationSoundSettings {
+    pub incoming_call: NotificationSound,
+    pub project_shared: NotificationSound,
+    pub volume: f32,
+    pub mute: bool,
+    pub mute_during_do_not_disturb: bool,
+}
+
+/// A built-in sound that can be played for call notifications.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationSound {
+    None,
+    Joined,
+    Leave,
+    Mute,
+    Unmute,
+    StartScreenshare,
+    StopScreenshare,
+    AgentDone,
+}
+
+impl NotificationSound {
+    pub fn sound(self) -> Option<Sound> {
+        match self {
+            Self::None => None,
+            Self::Joined => Some(Sound::Joined),
+            Self::Leave => Some(Sound::Leave),
+            Self::Mute => Some(Sound::Mute),
+            Self::Unmute => Some(Sound::Unmute),
+            Self::StartScreenshare => Some(Sound::StartScreenshare),
+            Self::StopScreenshare => Some(Sound::StopScreenshare),
+            Self::AgentDone => Some(Sound::AgentDone),
+        }
+    }
 }
 
 /// Configuration of voice calls in Zed.
@@ -23,6 +63,39 @@ pub struct CallSettingsContent {
     ///
     /// Default: false
     pub share_on_join: Option<bool>,
+
+    /// Sounds played when receiving call notifications.
+    pub notification_sounds: Option<NotificationSoundSettingsContent>,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, Debug)]
+#[schemars(deny_unknown_fields)]
+pub struct NotificationSoundSettingsContent {
+    /// The sound that rings while someone is calling you.
+    ///
+    /// Default: joined
+    pub incoming_call: Option<NotificationSound>,
+
+    /// The sound played when a collaborator shares a project in the current call.
+    ///
+    /// Default: start_screenshare
+    pub project_shared: Option<NotificationSound>,
+
+    /// The volume of notification sounds, between 0 and 1.
+    ///
+    /// Default: 0.8
+    pub volume: Option<f32>,
+
+    /// Whether notification sounds are muted.
+    ///
+    /// Default: false
+    pub mute: Option<bool>,
+
+    /// Whether notification sounds are muted while GNOME's do not disturb mode is on.
+    /// This setting has no effect on other platforms.
+    ///
+    /// Default: true
+    pub mute_during_do_not_disturb: Option<bool>,
 }
 
 impl Settings for CallSettings {
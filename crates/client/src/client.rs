@@ -1,7 +1,6 @@
 #[cfg(any(test, feature = "test-support"))]
 pub mod test;
 
-mod proxy;
 pub mod telemetry;
 pub mod user;
 pub mod zed_urls;
@@ -9,22 +8,20 @@ pub mod zed_urls;
 use anyhow::{Context as _, Result, anyhow, bail};
 use async_recursion::async_recursion;
 use async_tungstenite::tungstenite::{
-    client::IntoClientRequest,
     error::Error as WebsocketError,
-    http::{HeaderValue, Request, StatusCode},
+    http::{HeaderMap, HeaderValue, Request, StatusCode},
 };
 use chrono::{DateTime, Utc};
 use clock::SystemClock;
 use credentials_provider::CredentialsProvider;
 use futures::{
-    AsyncReadExt, FutureExt, SinkExt, Stream, StreamExt, TryFutureExt as _, TryStreamExt,
-    channel::oneshot, future::BoxFuture,
+    AsyncReadExt, FutureExt, Stream, StreamExt, TryFutureExt as _, channel::oneshot,
+    future::BoxFuture,
 };
 use gpui::{App, AsyncApp, Entity, Global, Task, WeakEntity, actions};
 use http_client::{AsyncBody, HttpClient, HttpClientWithUrl};
 use parking_lot::RwLock;
 use postage::watch;
-use proxy::connect_proxy_stream;
 use rand::prelude::*;
 use release_channel::{AppVersion, ReleaseChannel};
 use rpc::proto::{AnyTypedEnvelope, EnvelopedMessage, PeerId, RequestMessage};
@@ -47,7 +44,6 @@ use std::{
 };
 use telemetry::Telemetry;
 use thiserror::Error;
-use tokio::net::TcpStream;
 use url::Url;
 use util::{ConnectionResult, ResultExt};
 
@@ -1112,12 +1108,11 @@ impl Client {
             .unwrap_or_default();
 
         let http = self.http.clone();
-        let proxy = http.proxy().cloned();
         let credentials = credentials.clone();
-        let rpc_url = self.rpc_url(http, release_channel);
+        let rpc_url = self.rpc_url(http.clone(), release_channel);
         let system_id = self.telemetry.system_id();
         let metrics_id = self.telemetry.metrics_id();
-        cx.spawn(async move |cx| {
+        cx.spawn(async move |_| {
             use HttpOrHttps::*;
 
             #[derive(Debug)]
@@ -1132,37 +1127,14 @@ impl Client {
                 "http" => Http,
                 _ => Err(anyhow!("invalid rpc url: {}", rpc_url))?,
             };
-            let rpc_host = rpc_url
-                .host_str()
-                .zip(rpc_url.port_or_known_default())
-                .context("missing host in rpc url")?;
-
-            let stream = {
-                let handle = cx.update(|cx| gpui_tokio::Tokio::handle(cx)).ok().unwrap();
-                let _guard = handle.enter();
-                match proxy {
-                    Some(proxy) => connect_proxy_stream(&proxy, rpc_host).await?,
-                    None => Box::new(TcpStream::connect(rpc_host).await?),
-                }
-            };
-
-            log::info!("connected to rpc endpoint {}", rpc_url);
-
             rpc_url
                 .set_scheme(match url_scheme {
                     Https => "wss",
                     Http => "ws",
                 })
-                .unwrap();
+                .map_err(|()| anyhow!("invalid rpc url: {}", rpc_url))?;
 
-            // We call `into_client_request` to let `tungstenite` construct the WebSocket request
-            // for us from the RPC URL.
-            //
-            // Among other things, it will generate and set a `Sec-WebSocket-Key` header for us.
-            let mut request = IntoClientRequest::into_client_request(rpc_url.as_str())?;
-
-            // We then modify the request to add our desired headers.
-            let request_headers = request.headers_mut();
+            let mut request_headers = HeaderMap::new();
             request_headers.insert(
                 "Authorization",
                 HeaderValue::from_str(&credentials.authorization_header())?,
@@ -1183,19 +1155,16 @@ impl Client {
                 request_headers.insert("x-zed-metrics-id", HeaderValue::from_str(&metrics_id)?);
             }
 
-            let (stream, _) = async_tungstenite::tokio::client_async_tls_with_connector_and_config(
-                request,
-                stream,
-                Some(Arc::new(http_client_tls::tls_config()).into()),
-                None,
-            )
-            .await?;
+            let stream = http
+                .connect_websocket(&rpc_url, request_headers)
+                .await
+                .map_err(|error| match error.downcast::<WebsocketError>() {
+                    Ok(error) => EstablishConnectionError::from(error),
+                    Err(error) => EstablishConnectionError::Other(error),
+                })?;
+            log::info!("connected to rpc endpoint {}", rpc_url);
 
-            Ok(Connection::new(
-                stream
-                    .map_err(|error| anyhow!(error))
-                    .sink_map_err(|error| anyhow!(error)),
-            ))
+            Ok(Connection::new(stream))
         })
     }
 
@@ -1333,6 +1333,61 @@ impl Database {
         Ok(guest_connection_ids)
     }
 
+    /// Returns the top-level entries of each visible worktree in the given project, for
+    /// participants of the project's room who are deciding whether to join it.
+    pub async fn project_preview(
+        &self,
+        project_id: ProjectId,
+        connection_id: ConnectionId,
+        max_entries_per_worktree: usize,
+    ) -> Result<Vec<proto::ProjectPreviewWorktree>> {
+        self.transaction(|tx| async move {
+            let (project, _) = self
+                .access_project(project_id, connection_id, Capability::ReadOnly, &tx)
+                .await?;
+
+            let worktrees = project
+                .find_related(worktree::Entity)
+                .filter(worktree::Column::Visible.eq(true))
+                .order_by_asc(worktree::Column::Id)
+                .all(&*tx)
+                .await?;
+            let mut preview = Vec::with_capacity(worktrees.len());
+            for worktree in worktrees {
+                let mut entries = worktree_entry::Entity::find()
+                    .filter(
+                        Condition::all()
+                            .add(worktree_entry::Column::ProjectId.eq(project_id))
+                            .add(worktree_entry::Column::WorktreeId.eq(worktree.id))
+                            .add(worktree_entry::Column::IsDeleted.eq(false))
+                            .add(worktree_entry::Column::IsIgnored.eq(false))
+                            .add(worktree_entry::Column::Path.ne(""))
+                            .add(worktree_entry::Column::Path.not_like("%/%")),
+                    )
+                    .all(&*tx)
+                    .await?;
+                entries.sort_by(|a, b| {
+                    b.is_dir
+                        .cmp(&a.is_dir)
+                        .then_with(|| a.path.to_lowercase().cmp(&b.path.to_lowercase()))
+                });
+                preview.push(proto::ProjectPreviewWorktree {
+                    root_name: worktree.root_name,
+                    entries: entries
+                        .into_iter()
+                        .take(max_entries_per_worktree)
+                        .map(|entry| proto::ProjectPreviewEntry {
+                            path: entry.path,
+                            is_dir: entry.is_dir,
+                        })
+                        .collect(),
+                });
+            }
+            Ok(preview)
+        })
+        .await
+    }
+
     /// Returns the [`RoomId`] for the given project.
     pub async fn room_id_for_project(&self, project_id: ProjectId) -> Result<Option<RoomId>> {
         self.transaction(|tx| async move {
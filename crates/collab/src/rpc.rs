@@ -86,6 +86,7 @@ pub const CLEANUP_TIMEOUT: Duration = Duration::from_secs(15);
 const MESSAGE_COUNT_PER_PAGE: usize = 100;
 const MAX_MESSAGE_LEN: usize = 1024;
 const NOTIFICATION_COUNT_PER_PAGE: usize = 50;
+const PROJECT_PREVIEW_ENTRIES_PER_WORKTREE: usize = 20;
 
 type MessageHandler =
     Box<dyn Send + Sync + Fn(Box<dyn AnyTypedEnvelope>, Session) -> BoxFuture<'static, ()>>;
@@ -272,6 +273,7 @@ impl Server {
             .add_request_handler(share_project)
             .add_message_handler(unshare_project)
             .add_request_handler(join_project)
+            .add_request_handler(get_project_preview)
             .add_message_handler(leave_project)
             .add_request_handler(update_project)
             .add_request_handler(update_worktree)
@@ -402,10 +404,18 @@ impl Server {
             .add_request_handler(forward_mutating_project_request::<proto::Commit>)
             .add_request_handler(forward_mutating_project_request::<proto::GitInit>)
             .add_request_handler(forward_read_only_project_request::<proto::GetRemotes>)
+            .add_request_handler(forward_read_only_project_request::<proto::GitGetSubmodules>)
+            .add_request_handler(forward_mutating_project_request::<proto::GitUpdateSubmodules>)
             .add_request_handler(forward_read_only_project_request::<proto::GitShow>)
             .add_request_handler(forward_read_only_project_request::<proto::LoadCommitDiff>)
+            .add_request_handler(forward_read_only_project_request::<proto::GitFileHistory>)
+            .add_request_handler(forward_mutating_project_request::<proto::GitDiffRefs>)
+            .add_request_handler(forward_read_only_project_request::<proto::GitRenamedPaths>)
             .add_request_handler(forward_read_only_project_request::<proto::GitReset>)
+            .add_request_handler(forward_mutating_project_request::<proto::GitApplyCommit>)
             .add_request_handler(forward_read_only_project_request::<proto::GitCheckoutFiles>)
+            .add_request_handler(forward_mutating_project_request::<proto::GitStash>)
+            .add_request_handler(forward_mutating_project_request::<proto::GitStashPop>)
             .add_request_handler(forward_mutating_project_request::<proto::SetIndexText>)
             .add_request_handler(forward_mutating_project_request::<proto::ToggleBreakpoint>)
             .add_message_handler(broadcast_project_message_from_host::<proto::BreakpointsForFile>)
@@ -1847,6 +1857,27 @@ async fn join_project(
     join_project_internal(response, session, project, replica_id)
 }
 
+/// Returns the top-level contents of a shared project, so that room participants can
+/// see what's in it before joining.
+async fn get_project_preview(
+    request: proto::GetProjectPreview,
+    response: Response<proto::GetProjectPreview>,
+    session: Session,
+) -> Result<()> {
+    let project_id = ProjectId::from_proto(request.project_id);
+    let worktrees = session
+        .db()
+        .await
+        .project_preview(
+            project_id,
+            session.connection_id,
+            PROJECT_PREVIEW_ENTRIES_PER_WORKTREE,
+        )
+        .await?;
+    response.send(proto::GetProjectPreviewResponse { worktrees })?;
+    Ok(())
+}
+
 trait JoinProjectInternalResponse {
     fn send(self, result: proto::JoinProjectResponse) -> Result<()>;
 }
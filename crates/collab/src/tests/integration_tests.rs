@@ -37,6 +37,7 @@ use project::{
 };
 use prompt_store::PromptBuilder;
 use rand::prelude::*;
+use rpc::proto;
 use serde_json::json;
 use settings::SettingsStore;
 use std::{
@@ -1370,6 +1371,75 @@ async fn test_calls_on_multiple_connections(
     active_call_a.read_with(cx_a, |call, _| assert!(call.room().is_none()));
 }
 
+#[gpui::test(iterations = 10)]
+async fn test_project_preview(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+    cx_c: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    let client_c = server.create_client(cx_c, "user_c").await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+
+    let active_call_a = cx_a.read(ActiveCall::global);
+    let active_call_b = cx_b.read(ActiveCall::global);
+
+    client_a
+        .fs()
+        .insert_tree(
+            path!("/a"),
+            json!({
+                ".gitignore": "target",
+                "README.md": "",
+                "src": {
+                    "main.rs": "",
+                },
+                "target": {
+                    "debug": {},
+                },
+            }),
+        )
+        .await;
+    let (project_a, _) = client_a.build_local_project(path!("/a"), cx_a).await;
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    let preview = active_call_b
+        .read_with(cx_b, |call, cx| {
+            call.room()
+                .unwrap()
+                .read(cx)
+                .project_preview(project_id, cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(preview.len(), 1);
+    assert_eq!(preview[0].root_name, "a");
+    assert_eq!(
+        preview[0]
+            .entries
+            .iter()
+            .map(|entry| (entry.path.as_str(), entry.is_dir))
+            .collect::<Vec<_>>(),
+        [("src", true), (".gitignore", false), ("README.md", false)]
+    );
+
+    // Users outside of the room can't preview the project.
+    let result = client_c
+        .client()
+        .request(proto::GetProjectPreview { project_id })
+        .await;
+    assert!(result.is_err());
+}
+
 #[gpui::test(iterations = 10)]
 async fn test_unshare_project(
     executor: BackgroundExecutor,
@@ -9,8 +9,8 @@ use std::{rc::Rc, sync::Arc};
 
 pub use collab_panel::CollabPanel;
 use gpui::{
-    App, Pixels, PlatformDisplay, Size, WindowBackgroundAppearance, WindowBounds,
-    WindowDecorations, WindowKind, WindowOptions, point,
+    App, Pixels, PlatformDisplay, Size, WindowBounds, WindowDecorations, WindowKind, WindowOptions,
+    point,
 };
 use panel_settings::MessageEditorSettings;
 pub use panel_settings::{
@@ -62,7 +62,7 @@ fn notification_window_options(
         kind: WindowKind::PopUp,
         is_movable: false,
         display_id: Some(screen.id()),
-        window_background: WindowBackgroundAppearance::Transparent,
+        window_background: ui::popup_window_background(cx),
         app_id: Some(app_id.to_owned()),
         window_min_size: None,
         window_decorations: Some(WindowDecorations::Client),
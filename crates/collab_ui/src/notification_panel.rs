@@ -1,25 +1,33 @@
 use crate::{NotificationPanelSettings, chat_panel::ChatPanel};
 use anyhow::Result;
+use call::{ActiveCall, room};
 use channel::ChannelStore;
 use client::{ChannelId, Client, Notification, User, UserStore};
 use collections::HashMap;
 use db::kvp::KEY_VALUE_STORE;
+use editor::Editor;
 use futures::StreamExt;
 use gpui::{
-    AnyElement, App, AsyncWindowContext, ClickEvent, Context, CursorStyle, DismissEvent, Element,
-    Entity, EventEmitter, FocusHandle, Focusable, InteractiveElement, IntoElement, ListAlignment,
-    ListScrollEvent, ListState, ParentElement, Render, StatefulInteractiveElement, Styled, Task,
-    WeakEntity, Window, actions, div, img, list, px,
+    AnyElement, App, AsyncWindowContext, ClickEvent, Context, Corner, CursorStyle, DismissEvent,
+    Element, Entity, EntityId, EventEmitter, FocusHandle, Focusable, InteractiveElement,
+    IntoElement, ListAlignment, ListScrollEvent, ListState, ParentElement, Render,
+    StatefulInteractiveElement, Styled, Subscription, Task, WeakEntity, Window, actions, div, img,
+    list, px,
 };
-use notifications::{NotificationEntry, NotificationEvent, NotificationStore};
+use language::{Buffer, BufferEvent, ReplicaId};
+use notifications::{NotificationEntry, NotificationEvent, NotificationStore, SnoozeDuration};
 use project::Fs;
 use rpc::proto;
 use serde::{Deserialize, Serialize};
 use settings::{Settings, SettingsStore};
-use std::{sync::Arc, time::Duration};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use time::{OffsetDateTime, UtcOffset};
 use ui::{
-    Avatar, Button, Icon, IconButton, IconName, Label, Tab, Tooltip, h_flex, prelude::*, v_flex,
+    Avatar, Button, ContextMenu, Icon, IconButton, IconName, Label, PopoverMenu, Tab, Tooltip,
+    h_flex, prelude::*, v_flex,
 };
 use util::{ResultExt, TryFutureExt};
 use workspace::notifications::{
@@ -33,6 +41,9 @@ use workspace::{
 const LOADING_THRESHOLD: usize = 30;
 const MARK_AS_READ_DELAY: Duration = Duration::from_secs(1);
 const TOAST_DURATION: Duration = Duration::from_secs(5);
+/// How long a collaborator has to stop editing a buffer before their next edit to it is
+/// reported as them starting to edit it again.
+const COLLABORATOR_EDIT_IDLE_DURATION: Duration = Duration::from_secs(120);
 const NOTIFICATION_PANEL_KEY: &str = "NotificationPanel";
 
 pub struct NotificationPanel {
@@ -52,6 +63,8 @@ pub struct NotificationPanel {
     focus_handle: FocusHandle,
     mark_as_read_tasks: HashMap<u64, Task<Result<()>>>,
     unseen_notifications: Vec<NotificationEntry>,
+    active_buffer_subscription: Option<Subscription>,
+    last_collaborator_edits: HashMap<(EntityId, proto::PeerId), Instant>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -95,6 +108,8 @@ impl NotificationPanel {
         let client = workspace.app_state().client.clone();
         let user_store = workspace.app_state().user_store.clone();
         let workspace_handle = workspace.weak_handle();
+        let workspace_entity = cx.entity();
+        let active_editor = workspace.active_item_as::<Editor>(cx);
 
         cx.new(|cx| {
             let mut status = client.status();
@@ -153,8 +168,12 @@ impl NotificationPanel {
                 mark_as_read_tasks: HashMap::default(),
                 width: None,
                 unseen_notifications: Vec::new(),
+                active_buffer_subscription: None,
+                last_collaborator_edits: HashMap::default(),
             };
+            this.watch_active_editor(active_editor, window, cx);
 
+            let active_call = ActiveCall::global(cx);
             let mut old_dock_position = this.position(window, cx);
             this.subscriptions.extend([
                 cx.observe(&this.notification_store, |_, _, cx| cx.notify()),
@@ -174,6 +193,12 @@ impl NotificationPanel {
                         cx.notify();
                     },
                 ),
+                cx.subscribe_in(&workspace_entity, window, Self::on_workspace_event),
+                cx.subscribe(&active_call, |this, _, event: &room::Event, _| {
+                    if let room::Event::RoomLeft { .. } = event {
+                        this.last_collaborator_edits.clear();
+                    }
+                }),
             ]);
             this
         })
@@ -224,6 +249,120 @@ impl NotificationPanel {
         );
     }
 
+    fn on_workspace_event(
+        &mut self,
+        workspace: &Entity<Workspace>,
+        event: &workspace::Event,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let workspace::Event::ActiveItemChanged = event {
+            let active_editor = workspace.read(cx).active_item_as::<Editor>(cx);
+            self.watch_active_editor(active_editor, window, cx);
+        }
+    }
+
+    fn watch_active_editor(
+        &mut self,
+        editor: Option<Entity<Editor>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.active_buffer_subscription = None;
+        let Some(editor) = editor else {
+            return;
+        };
+        let Some(buffer) = editor.read(cx).buffer().read(cx).as_singleton() else {
+            return;
+        };
+        let editor = editor.downgrade();
+        self.active_buffer_subscription = Some(cx.subscribe_in(
+            &buffer,
+            window,
+            move |this, buffer, event: &BufferEvent, window, cx| {
+                if let BufferEvent::Operation {
+                    operation: language::Operation::Buffer(operation),
+                    is_local: false,
+                } = event
+                {
+                    this.on_remote_edit(
+                        &editor,
+                        buffer,
+                        operation.timestamp().replica_id,
+                        window,
+                        cx,
+                    );
+                }
+            },
+        ));
+    }
+
+    fn on_remote_edit(
+        &mut self,
+        editor: &WeakEntity<Editor>,
+        buffer: &Entity<Buffer>,
+        replica_id: ReplicaId,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(editor) = editor.upgrade() else {
+            return;
+        };
+        if !editor.focus_handle(cx).contains_focused(window, cx) {
+            return;
+        }
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        let workspace = workspace.read(cx);
+        let project = workspace.project().read(cx);
+        let Some(project_id) = project.remote_id() else {
+            return;
+        };
+        let Some((peer_id, user_id)) = project
+            .collaborators()
+            .values()
+            .find(|collaborator| collaborator.replica_id == replica_id)
+            .map(|collaborator| (collaborator.peer_id, collaborator.user_id))
+        else {
+            return;
+        };
+        let is_in_call = ActiveCall::global(cx).read(cx).room().is_some_and(|room| {
+            room.read(cx)
+                .remote_participant_for_peer_id(peer_id)
+                .is_some()
+        });
+        if !is_in_call || workspace.is_being_followed(peer_id) {
+            return;
+        }
+
+        let now = Instant::now();
+        let last_edit = self
+            .last_collaborator_edits
+            .insert((buffer.entity_id(), peer_id), now);
+        if last_edit.is_some_and(|last_edit| now - last_edit < COLLABORATOR_EDIT_IDLE_DURATION) {
+            return;
+        }
+        let Some(path) = buffer
+            .read(cx)
+            .file()
+            .map(|file| file.path().to_string_lossy().into_owned())
+        else {
+            return;
+        };
+
+        self.notification_store.update(cx, |store, cx| {
+            store.add_local_notification(
+                Notification::CollaboratorEditing {
+                    project_id,
+                    collaborator_id: user_id,
+                    path,
+                },
+                cx,
+            )
+        });
+    }
+
     fn render_notification(
         &mut self,
         ix: usize,
@@ -349,15 +488,83 @@ impl NotificationPanel {
                                                 }
                                             })),
                                     )
+                                } else if let Notification::CollaboratorEditing {
+                                    collaborator_id,
+                                    ..
+                                } = notification
+                                {
+                                    Some(h_flex().flex_grow().justify_end().child(
+                                        Button::new("follow", "Follow").on_click({
+                                            let workspace = self.workspace.clone();
+                                            move |_, window, cx| {
+                                                cx.stop_propagation();
+                                                follow_collaborator(
+                                                    workspace.clone(),
+                                                    collaborator_id,
+                                                    window,
+                                                    cx,
+                                                );
+                                            }
+                                        }),
+                                    ))
                                 } else {
                                     None
                                 }),
                         ),
                 )
+                .child(
+                    PopoverMenu::new(("snooze", notification_id))
+                        .trigger_with_tooltip(
+                            IconButton::new(("snooze-trigger", notification_id), IconName::BellOff)
+                                .icon_size(IconSize::Small)
+                                .icon_color(Color::Muted),
+                            Tooltip::text("Snooze"),
+                        )
+                        .anchor(Corner::TopRight)
+                        .menu({
+                            let this = cx.entity();
+                            move |window, cx| {
+                                Some(Self::render_snooze_menu(&this, notification_id, window, cx))
+                            }
+                        }),
+                )
                 .into_any(),
         )
     }
 
+    fn render_snooze_menu(
+        this: &Entity<Self>,
+        notification_id: u64,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Entity<ContextMenu> {
+        ContextMenu::build(window, cx, move |mut menu, window, _| {
+            menu = menu.header("Snooze for");
+            for duration in SnoozeDuration::ALL {
+                menu = menu.entry(
+                    duration.label(),
+                    None,
+                    window.handler_for(this, move |this, _, cx| {
+                        this.snooze_notification(notification_id, duration, cx)
+                    }),
+                );
+            }
+            menu
+        })
+    }
+
+    fn snooze_notification(
+        &mut self,
+        notification_id: u64,
+        duration: SnoozeDuration,
+        cx: &mut Context<Self>,
+    ) {
+        self.mark_as_read_tasks.remove(&notification_id);
+        self.notification_store.update(cx, |store, cx| {
+            store.snooze_notification(notification_id, duration, cx)
+        });
+    }
+
     fn present_notification(
         &self,
         entry: &NotificationEntry,
@@ -425,6 +632,31 @@ impl NotificationPanel {
                     can_navigate: true,
                 })
             }
+            Notification::CollaboratorEditing {
+                collaborator_id,
+                ref path,
+                ..
+            } => {
+                let collaborator = user_store.get_cached_user(collaborator_id)?;
+                Some(NotificationPresenter {
+                    icon: "icons/pencil.svg",
+                    text: format!("{} started editing {path}", collaborator.github_login),
+                    needs_response: false,
+                    actor: Some(collaborator),
+                    can_navigate: true,
+                })
+            }
+            Notification::AgentTaskFinished {
+                ref task_name,
+                ref summary,
+                ..
+            } => Some(NotificationPresenter {
+                icon: "icons/zed_assistant.svg",
+                text: format!("{task_name} finished:\n{summary}"),
+                needs_response: false,
+                actor: None,
+                can_navigate: true,
+            }),
         }
     }
 
@@ -436,7 +668,9 @@ impl NotificationPanel {
         cx: &mut Context<Self>,
     ) {
         let should_mark_as_read = match notification {
-            Notification::ContactRequestAccepted { .. } => true,
+            Notification::ContactRequestAccepted { .. }
+            | Notification::CollaboratorEditing { .. }
+            | Notification::AgentTaskFinished { .. } => true,
             Notification::ContactRequest { .. }
             | Notification::ChannelInvitation { .. }
             | Notification::ChannelMessageMention { .. } => false,
@@ -447,11 +681,23 @@ impl NotificationPanel {
                 .entry(notification_id)
                 .or_insert_with(|| {
                     let client = self.client.clone();
+                    let is_local = self
+                        .notification_store
+                        .read(cx)
+                        .is_local_notification(notification_id);
                     cx.spawn_in(window, async move |this, cx| {
                         cx.background_executor().timer(MARK_AS_READ_DELAY).await;
-                        client
-                            .request(proto::MarkNotificationRead { notification_id })
-                            .await?;
+                        if is_local {
+                            this.update(cx, |this, cx| {
+                                this.notification_store.update(cx, |store, cx| {
+                                    store.mark_local_notification_read(notification_id, cx)
+                                });
+                            })?;
+                        } else {
+                            client
+                                .request(proto::MarkNotificationRead { notification_id })
+                                .await?;
+                        }
                         this.update(cx, |this, _| {
                             this.mark_as_read_tasks.remove(&notification_id);
                         })?;
@@ -467,25 +713,37 @@ impl NotificationPanel {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        if let Notification::ChannelMessageMention {
-            message_id,
-            channel_id,
-            ..
-        } = notification.clone()
-        {
-            if let Some(workspace) = self.workspace.upgrade() {
-                window.defer(cx, move |window, cx| {
-                    workspace.update(cx, |workspace, cx| {
-                        if let Some(panel) = workspace.focus_panel::<ChatPanel>(window, cx) {
-                            panel.update(cx, |panel, cx| {
-                                panel
-                                    .select_channel(ChannelId(channel_id), Some(message_id), cx)
-                                    .detach_and_log_err(cx);
-                            });
-                        }
+        match notification.clone() {
+            Notification::ChannelMessageMention {
+                message_id,
+                channel_id,
+                ..
+            } => {
+                if let Some(workspace) = self.workspace.upgrade() {
+                    window.defer(cx, move |window, cx| {
+                        workspace.update(cx, |workspace, cx| {
+                            if let Some(panel) = workspace.focus_panel::<ChatPanel>(window, cx) {
+                                panel.update(cx, |panel, cx| {
+                                    panel
+                                        .select_channel(ChannelId(channel_id), Some(message_id), cx)
+                                        .detach_and_log_err(cx);
+                                });
+                            }
+                        });
                     });
-                });
+                }
+            }
+            Notification::CollaboratorEditing {
+                collaborator_id, ..
+            } => {
+                follow_collaborator(self.workspace.clone(), collaborator_id, window, cx);
+            }
+            Notification::AgentTaskFinished { thread_id, .. } => {
+                window.dispatch_action(Box::new(zed_actions::agent::OpenThread { thread_id }), cx);
             }
+            Notification::ContactRequest { .. }
+            | Notification::ContactRequestAccepted { .. }
+            | Notification::ChannelInvitation { .. } => {}
         }
     }
 
@@ -550,6 +808,25 @@ impl NotificationPanel {
             return;
         }
 
+        // The notification store is shared by all windows, so only pop up collaborator edits in
+        // the workspace for the project they happened in.
+        let follow_collaborator_id = match entry.notification {
+            Notification::CollaboratorEditing {
+                project_id,
+                collaborator_id,
+                ..
+            } => {
+                let is_workspace_project = self.workspace.upgrade().is_some_and(|workspace| {
+                    workspace.read(cx).project().read(cx).remote_id() == Some(project_id)
+                });
+                if !is_workspace_project {
+                    return;
+                }
+                Some(collaborator_id)
+            }
+            _ => None,
+        };
+
         let Some(NotificationPresenter { actor, text, .. }) = self.present_notification(entry, cx)
         else {
             return;
@@ -576,6 +853,7 @@ impl NotificationPanel {
                         notification_id,
                         actor,
                         text,
+                        follow_collaborator_id,
                         workspace,
                         focus_handle: cx.focus_handle(),
                     })
@@ -779,10 +1057,34 @@ impl Panel for NotificationPanel {
     }
 }
 
+/// Follows the call participant with the given user id, joining the project they're in if
+/// it isn't the workspace's.
+fn follow_collaborator(
+    workspace: WeakEntity<Workspace>,
+    user_id: u64,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let Some(peer_id) = ActiveCall::global(cx)
+        .read(cx)
+        .room()
+        .and_then(|room| room.read(cx).remote_participants().get(&user_id))
+        .map(|participant| participant.peer_id)
+    else {
+        return;
+    };
+    window.defer(cx, move |window, cx| {
+        workspace
+            .update(cx, |workspace, cx| workspace.follow(peer_id, window, cx))
+            .ok();
+    });
+}
+
 pub struct NotificationToast {
     notification_id: u64,
     actor: Option<Arc<User>>,
     text: String,
+    follow_collaborator_id: Option<u64>,
     workspace: WeakEntity<Workspace>,
     focus_handle: FocusHandle,
 }
@@ -838,6 +1140,15 @@ impl Render for NotificationToast {
             .justify_between()
             .children(user.map(|user| Avatar::new(user.avatar_uri.clone())))
             .child(Label::new(self.text.clone()))
+            .children(self.follow_collaborator_id.map(|collaborator_id| {
+                Button::new("follow", "Follow").on_click(cx.listener(
+                    move |this, _: &ClickEvent, window, cx| {
+                        cx.stop_propagation();
+                        follow_collaborator(this.workspace.clone(), collaborator_id, window, cx);
+                        cx.emit(DismissEvent);
+                    },
+                ))
+            }))
             .on_modifiers_changed(cx.listener(|_, _, _, cx| cx.notify()))
             .child(
                 IconButton::new(close_id, close_icon)
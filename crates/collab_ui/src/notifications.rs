@@ -5,14 +5,140 @@ pub mod project_shared_notification;
 #[cfg(feature = "stories")]
 mod stories;
 
-use gpui::App;
+use audio::Audio;
+use call::call_settings::{CallSettings, NotificationSound, NotificationSoundSettings};
+use gpui::{AnyWindowHandle, App, FocusHandle, Global, Task, Window, actions};
+use settings::Settings as _;
 use std::sync::Arc;
 use workspace::AppState;
 
+actions!(
+    collab,
+    [
+        /// Moves keyboard focus to the most recently shown call or project share notification.
+        FocusNotification
+    ]
+);
+
 #[cfg(feature = "stories")]
 pub use stories::*;
 
 pub fn init(app_state: &Arc<AppState>, cx: &mut App) {
     incoming_call_notification::init(app_state, cx);
     project_shared_notification::init(app_state, cx);
+
+    cx.set_global(NotificationWindows::default());
+    cx.on_action(|_: &FocusNotification, cx| {
+        let latest = NotificationWindows::open_windows(cx).last().cloned();
+        if let Some((window, focus_handle)) = latest {
+            focus_notification_window(window, focus_handle, cx);
+        }
+    });
+}
+
+/// The pop-up notification windows that are currently open, oldest first.
+///
+/// These windows are opened without focus so that they don't steal keystrokes, so they're tracked
+/// here to let the keyboard reach them on request.
+#[derive(Default)]
+struct NotificationWindows(Vec<(AnyWindowHandle, FocusHandle)>);
+
+impl Global for NotificationWindows {}
+
+impl NotificationWindows {
+    fn open_windows(cx: &mut App) -> &[(AnyWindowHandle, FocusHandle)] {
+        let open_windows = cx.windows();
+        let this = cx.default_global::<Self>();
+        this.0.retain(|(window, _)| open_windows.contains(window));
+        &this.0
+    }
+}
+
+/// Records a newly opened notification window, whose root view tracks `focus_handle`.
+fn register_notification_window(window: &Window, focus_handle: FocusHandle, cx: &mut App) {
+    cx.default_global::<NotificationWindows>()
+        .0
+        .push((window.window_handle(), focus_handle));
+}
+
+/// Moves focus from the notification in `window` to the one shown before (`forward == false`) or
+/// after it, wrapping around at either end.
+fn focus_adjacent_notification(window: &Window, forward: bool, cx: &mut App) {
+    let current = window.window_handle();
+    // Defer so that the other window isn't updated while this one's action is being dispatched.
+    cx.defer(move |cx| {
+        let windows = NotificationWindows::open_windows(cx);
+        let Some(ix) = windows.iter().position(|(window, _)| *window == current) else {
+            return;
+        };
+        let target_ix = if forward {
+            (ix + 1) % windows.len()
+        } else {
+            (ix + windows.len() - 1) % windows.len()
+        };
+        let (target, focus_handle) = windows[target_ix].clone();
+        focus_notification_window(target, focus_handle, cx);
+    });
+}
+
+fn focus_notification_window(window: AnyWindowHandle, focus_handle: FocusHandle, cx: &mut App) {
+    window
+        .update(cx, |_, window, _| {
+            window.activate_window();
+            window.focus(&focus_handle);
+        })
+        .ok();
+}
+
+/// Plays the notification sound chosen by `select_sound`, unless notification sounds are muted.
+///
+/// When `looping` is true, the sound repeats until the returned task is dropped.
+fn play_notification_sound(
+    select_sound: fn(&NotificationSoundSettings) -> NotificationSound,
+    looping: bool,
+    cx: &mut App,
+) -> Task<()> {
+    let settings = CallSettings::get_global(cx).notification_sounds.clone();
+    let Some(sound) = select_sound(&settings).sound().filter(|_| !settings.mute) else {
+        return Task::ready(());
+    };
+    let do_not_disturb = if settings.mute_during_do_not_disturb {
+        cx.background_spawn(do_not_disturb_enabled())
+    } else {
+        Task::ready(false)
+    };
+    cx.spawn(async move |cx| {
+        if do_not_disturb.await {
+            return;
+        }
+        if !looping {
+            cx.update(|cx| Audio::play_sound_at_volume(sound, settings.volume, cx))
+                .ok();
+            return;
+        }
+        let Ok(Some(_playing)) = cx.update(|cx| Audio::play_looping(sound, settings.volume, cx))
+        else {
+            return;
+        };
+        futures::future::pending::<()>().await;
+    })
+}
+
+#[cfg(any(target_os = "linux", target_os = "freebsd"))]
+async fn do_not_disturb_enabled() -> bool {
+    let Ok(output) = util::command::new_smol_command("gsettings")
+        .args(["get", "org.gnome.desktop.notifications", "show-banners"])
+        .output()
+        .await
+    else {
+        return false;
+    };
+    output.status.success() && String::from_utf8_lossy(&output.stdout).trim() == "false"
+}
+
+// macOS exposes no public API for reading the current Focus state, so sounds are only
+// suppressed there through the `mute` setting.
+#[cfg(not(any(target_os = "linux", target_os = "freebsd")))]
+async fn do_not_disturb_enabled() -> bool {
+    false
 }
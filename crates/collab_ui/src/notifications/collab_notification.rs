@@ -7,6 +7,7 @@ pub struct CollabNotification {
     avatar_uri: SharedUri,
     accept_button: Button,
     dismiss_button: Button,
+    secondary_actions: Option<AnyElement>,
     children: SmallVec<[AnyElement; 2]>,
 }
 
@@ -20,9 +21,16 @@ impl CollabNotification {
             avatar_uri: avatar_uri.into(),
             accept_button,
             dismiss_button,
+            secondary_actions: None,
             children: SmallVec::new(),
         }
     }
+
+    /// Shows less common actions, such as snoozing, below the accept and dismiss buttons.
+    pub fn secondary_actions(mut self, actions: impl IntoElement) -> Self {
+        self.secondary_actions = Some(actions.into_any_element());
+        self
+    }
 }
 
 impl ParentElement for CollabNotification {
@@ -38,7 +46,7 @@ impl RenderOnce for CollabNotification {
             .justify_between()
             .size_full()
             .overflow_hidden()
-            .elevation_3(cx)
+            .popup_window_surface(cx)
             .p_2()
             .gap_2()
             .child(img(self.avatar_uri).w_12().h_12().rounded_full())
@@ -46,7 +54,8 @@ impl RenderOnce for CollabNotification {
             .child(
                 v_flex()
                     .child(self.accept_button)
-                    .child(self.dismiss_button),
+                    .child(self.dismiss_button)
+                    .children(self.secondary_actions),
             )
     }
 }
@@ -1,8 +1,11 @@
 use crate::notification_window_options;
 use crate::notifications::collab_notification::CollabNotification;
+use crate::notifications::{
+    focus_adjacent_notification, play_notification_sound, register_notification_window,
+};
 use call::{ActiveCall, IncomingCall};
 use futures::StreamExt;
-use gpui::{App, WindowHandle, prelude::*};
+use gpui::{App, FocusHandle, Focusable, WindowHandle, prelude::*};
 
 use std::sync::{Arc, Weak};
 use ui::{Button, Label, prelude::*};
@@ -14,7 +17,9 @@ pub fn init(app_state: &Arc<AppState>, cx: &mut App) {
     let mut incoming_call = ActiveCall::global(cx).read(cx).incoming();
     cx.spawn(async move |cx| {
         let mut notification_windows: Vec<WindowHandle<IncomingCallNotification>> = Vec::new();
+        let mut ringtone = None;
         while let Some(incoming_call) = incoming_call.next().await {
+            drop(ringtone.take());
             for window in notification_windows.drain(..) {
                 window
                     .update(cx, |_, window, _| {
@@ -24,6 +29,11 @@ pub fn init(app_state: &Arc<AppState>, cx: &mut App) {
             }
 
             if let Some(incoming_call) = incoming_call {
+                ringtone = cx
+                    .update(|cx| {
+                        play_notification_sound(|settings| settings.incoming_call, true, cx)
+                    })
+                    .ok();
                 let unique_screens = cx.update(|cx| cx.displays()).unwrap();
                 let window_size = gpui::Size {
                     width: px(400.),
@@ -36,18 +46,28 @@ pub fn init(app_state: &Arc<AppState>, cx: &mut App) {
                         .log_err()
                     {
                         let window = cx
-                            .open_window(options, |_, cx| {
-                                cx.new(|_| {
+                            .open_window(options, |window, cx| {
+                                cx.new(|cx| {
                                     IncomingCallNotification::new(
                                         incoming_call.clone(),
                                         app_state.clone(),
+                                        window,
+                                        cx,
                                     )
                                 })
                             })
-                            .unwrap();
-                        notification_windows.push(window);
+                            .log_err();
+                        notification_windows.extend(window);
                     }
                 }
+
+                // Fall back to a system notification so the call isn't silently missed when
+                // there's nowhere to show the pop-up.
+                if notification_windows.is_empty() {
+                    let state =
+                        IncomingCallNotificationState::new(incoming_call, app_state.clone());
+                    cx.update(|cx| state.post_platform_notification(cx)).ok();
+                }
             }
         }
     })
@@ -61,6 +81,7 @@ struct IncomingCallNotificationState {
 
 pub struct IncomingCallNotification {
     state: Arc<IncomingCallNotificationState>,
+    focus_handle: FocusHandle,
 }
 impl IncomingCallNotificationState {
     pub fn new(call: IncomingCall, app_state: Weak<AppState>) -> Self {
@@ -100,36 +121,93 @@ impl IncomingCallNotificationState {
             });
         }
     }
+
+    /// Accepts the call when the notification is clicked.
+    fn post_platform_notification(self, cx: &mut App) {
+        let title = format!("Incoming call from {}", self.call.calling_user.github_login);
+        let body = self
+            .call
+            .initial_project
+            .as_ref()
+            .map(|project| project.worktree_root_names.join(", "))
+            .unwrap_or_default();
+        cx.post_notification_with_action(&title, &body, move |cx| self.respond(true, cx))
+            .detach_and_log_err(cx);
+    }
 }
 
 impl IncomingCallNotification {
-    pub fn new(call: IncomingCall, app_state: Weak<AppState>) -> Self {
+    pub fn new(
+        call: IncomingCall,
+        app_state: Weak<AppState>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let focus_handle = cx.focus_handle();
+        register_notification_window(window, focus_handle.clone(), cx);
         Self {
             state: Arc::new(IncomingCallNotificationState::new(call, app_state)),
+            focus_handle,
         }
     }
+
+    fn confirm(&mut self, _: &menu::Confirm, _: &mut Window, cx: &mut Context<Self>) {
+        self.state.respond(true, cx);
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        self.state.respond(false, cx);
+    }
+
+    fn select_next(&mut self, _: &menu::SelectNext, window: &mut Window, cx: &mut Context<Self>) {
+        focus_adjacent_notification(window, true, cx);
+    }
+
+    fn select_previous(
+        &mut self,
+        _: &menu::SelectPrevious,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        focus_adjacent_notification(window, false, cx);
+    }
+}
+
+impl Focusable for IncomingCallNotification {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
 }
 
 impl Render for IncomingCallNotification {
     fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let ui_font = theme::setup_ui_font(window, cx);
 
-        div().size_full().font(ui_font).child(
-            CollabNotification::new(
-                self.state.call.calling_user.avatar_uri.clone(),
-                Button::new("accept", "Accept").on_click({
-                    let state = self.state.clone();
-                    move |_, _, cx| state.respond(true, cx)
-                }),
-                Button::new("decline", "Decline").on_click({
-                    let state = self.state.clone();
-                    move |_, _, cx| state.respond(false, cx)
-                }),
+        div()
+            .key_context("CollabNotification")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_previous))
+            .size_full()
+            .font(ui_font)
+            .child(
+                CollabNotification::new(
+                    self.state.call.calling_user.avatar_uri.clone(),
+                    Button::new("accept", "Accept").on_click({
+                        let state = self.state.clone();
+                        move |_, _, cx| state.respond(true, cx)
+                    }),
+                    Button::new("decline", "Decline").on_click({
+                        let state = self.state.clone();
+                        move |_, _, cx| state.respond(false, cx)
+                    }),
+                )
+                .child(v_flex().overflow_hidden().child(Label::new(format!(
+                    "{} is sharing a project in Zed",
+                    self.state.call.calling_user.github_login
+                )))),
             )
-            .child(v_flex().overflow_hidden().child(Label::new(format!(
-                "{} is sharing a project in Zed",
-                self.state.call.calling_user.github_login
-            )))),
-        )
     }
 }
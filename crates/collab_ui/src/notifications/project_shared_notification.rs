@@ -1,12 +1,17 @@
 use crate::notification_window_options;
 use crate::notifications::collab_notification::CollabNotification;
+use crate::notifications::{
+    focus_adjacent_notification, play_notification_sound, register_notification_window,
+};
 use call::{ActiveCall, room};
 use client::User;
 use collections::HashMap;
-use gpui::{App, Size};
+use gpui::{App, FocusHandle, Focusable, Size};
+use notifications::SnoozeDuration;
+use rpc::proto;
 use std::sync::{Arc, Weak};
 
-use ui::{Button, Label, prelude::*};
+use ui::{Button, Label, Tooltip, prelude::*};
 use util::ResultExt;
 use workspace::AppState;
 
@@ -14,27 +19,31 @@ pub fn init(app_state: &Arc<AppState>, cx: &mut App) {
     let app_state = Arc::downgrade(app_state);
     let active_call = ActiveCall::global(cx);
     let mut notification_windows = HashMap::default();
-    cx.subscribe(&active_call, move |_, event, cx| match event {
+    cx.subscribe(&active_call, move |active_call, event, cx| match event {
         room::Event::RemoteProjectShared {
             owner,
             project_id,
             worktree_root_names,
         } => {
+            play_notification_sound(|settings| settings.project_shared, false, cx).detach();
+
             let window_size = Size {
                 width: px(400.),
-                height: px(72.),
+                height: px(112.),
             };
 
             for screen in cx.displays() {
                 let options = notification_window_options(screen, window_size, cx);
                 let Some(window) = cx
-                    .open_window(options, |_, cx| {
-                        cx.new(|_| {
+                    .open_window(options, |window, cx| {
+                        cx.new(|cx| {
                             ProjectSharedNotification::new(
                                 owner.clone(),
                                 *project_id,
                                 worktree_root_names.clone(),
                                 app_state.clone(),
+                                window,
+                                cx,
                             )
                         })
                     })
@@ -47,6 +56,41 @@ pub fn init(app_state: &Arc<AppState>, cx: &mut App) {
                     .or_insert(Vec::new())
                     .push(window);
             }
+
+            if !notification_windows.contains_key(project_id) {
+                post_platform_notification(
+                    owner.clone(),
+                    *project_id,
+                    worktree_root_names,
+                    app_state.clone(),
+                    cx,
+                );
+                return;
+            }
+
+            let Some(preview) = active_call
+                .read(cx)
+                .room()
+                .map(|room| room.read(cx).project_preview(*project_id, cx))
+            else {
+                return;
+            };
+            let windows = notification_windows
+                .get(project_id)
+                .cloned()
+                .unwrap_or_default();
+            cx.spawn(async move |cx| {
+                let worktrees = preview.await?;
+                for window in windows {
+                    window
+                        .update(cx, |notification, _, cx| {
+                            notification.set_preview(worktrees.clone(), cx)
+                        })
+                        .ok();
+                }
+                anyhow::Ok(())
+            })
+            .detach_and_log_err(cx);
         }
 
         room::Event::RemoteProjectUnshared { project_id }
@@ -79,11 +123,33 @@ pub fn init(app_state: &Arc<AppState>, cx: &mut App) {
     .detach();
 }
 
+/// Used when no pop-up could be shown, such as when there are no displays, so that the share
+/// isn't silently lost.
+fn post_platform_notification(
+    owner: Arc<User>,
+    project_id: u64,
+    worktree_root_names: &[String],
+    app_state: Weak<AppState>,
+    cx: &mut App,
+) {
+    let title = format!("{} is sharing a project in Zed", owner.github_login);
+    let body = worktree_root_names.join(", ");
+    cx.post_notification_with_action(&title, &body, move |cx| {
+        if let Some(app_state) = app_state.upgrade() {
+            workspace::join_in_room_project(project_id, owner.id, app_state, cx)
+                .detach_and_log_err(cx);
+        }
+    })
+    .detach_and_log_err(cx);
+}
+
 pub struct ProjectSharedNotification {
     project_id: u64,
     worktree_root_names: Vec<String>,
     owner: Arc<User>,
     app_state: Weak<AppState>,
+    preview: Vec<proto::ProjectPreviewWorktree>,
+    focus_handle: FocusHandle,
 }
 
 impl ProjectSharedNotification {
@@ -92,15 +158,48 @@ impl ProjectSharedNotification {
         project_id: u64,
         worktree_root_names: Vec<String>,
         app_state: Weak<AppState>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
     ) -> Self {
+        let focus_handle = cx.focus_handle();
+        register_notification_window(window, focus_handle.clone(), cx);
         Self {
             project_id,
             worktree_root_names,
             owner,
             app_state,
+            preview: Vec::new(),
+            focus_handle,
         }
     }
 
+    fn set_preview(&mut self, preview: Vec<proto::ProjectPreviewWorktree>, cx: &mut Context<Self>) {
+        self.preview = preview;
+        cx.notify();
+    }
+
+    fn preview_text(&self) -> Option<String> {
+        let show_root_names = self.preview.len() > 1;
+        let entries = self
+            .preview
+            .iter()
+            .flat_map(|worktree| {
+                worktree.entries.iter().map(move |entry| {
+                    let mut text = if show_root_names {
+                        format!("{}/{}", worktree.root_name, entry.path)
+                    } else {
+                        entry.path.clone()
+                    };
+                    if entry.is_dir {
+                        text.push('/');
+                    }
+                    text
+                })
+            })
+            .collect::<Vec<_>>();
+        (!entries.is_empty()).then(|| entries.join(", "))
+    }
+
     fn join(&mut self, cx: &mut Context<Self>) {
         if let Some(app_state) = self.app_state.upgrade() {
             workspace::join_in_room_project(self.project_id, self.owner.id, app_state, cx)
@@ -108,6 +207,55 @@ impl ProjectSharedNotification {
         }
     }
 
+    /// Closes the notification and shows it again after `duration`, as long as the project is
+    /// still shared and hasn't been joined in the meantime.
+    fn snooze(&mut self, duration: SnoozeDuration, cx: &mut Context<Self>) {
+        let Some(room) = ActiveCall::global(cx).read(cx).room().cloned() else {
+            return;
+        };
+        self.dismiss(cx);
+
+        let owner = self.owner.clone();
+        let project_id = self.project_id;
+        let worktree_root_names = self.worktree_root_names.clone();
+        let room = room.downgrade();
+        cx.spawn(async move |_, cx| {
+            cx.background_executor().timer(duration.duration()).await;
+            room.update(cx, |room, cx| {
+                if room.is_project_awaiting_join(owner.id, project_id, cx) {
+                    cx.emit(room::Event::RemoteProjectShared {
+                        owner,
+                        project_id,
+                        worktree_root_names,
+                    });
+                }
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, _: &mut Window, cx: &mut Context<Self>) {
+        self.join(cx);
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        self.dismiss(cx);
+    }
+
+    fn select_next(&mut self, _: &menu::SelectNext, window: &mut Window, cx: &mut Context<Self>) {
+        focus_adjacent_notification(window, true, cx);
+    }
+
+    fn select_previous(
+        &mut self,
+        _: &menu::SelectPrevious,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        focus_adjacent_notification(window, false, cx);
+    }
+
     fn dismiss(&mut self, cx: &mut Context<Self>) {
         if let Some(active_room) = ActiveCall::global(cx).read(cx).room().cloned() {
             active_room.update(cx, |_, cx| {
@@ -119,36 +267,68 @@ impl ProjectSharedNotification {
     }
 }
 
+impl Focusable for ProjectSharedNotification {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
 impl Render for ProjectSharedNotification {
     fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let ui_font = theme::setup_ui_font(window, cx);
 
-        div().size_full().font(ui_font).child(
-            CollabNotification::new(
-                self.owner.avatar_uri.clone(),
-                Button::new("open", "Open").on_click(cx.listener(move |this, _event, _, cx| {
-                    this.join(cx);
+        div()
+            .key_context("CollabNotification")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_previous))
+            .size_full()
+            .font(ui_font)
+            .child(
+                CollabNotification::new(
+                    self.owner.avatar_uri.clone(),
+                    Button::new("open", "Open").on_click(cx.listener(
+                        move |this, _event, _, cx| {
+                            this.join(cx);
+                        },
+                    )),
+                    Button::new("dismiss", "Dismiss").on_click(cx.listener(
+                        move |this, _event, _, cx| {
+                            this.dismiss(cx);
+                        },
+                    )),
+                )
+                .secondary_actions(h_flex().children(SnoozeDuration::ALL.map(|duration| {
+                    Button::new(duration.short_label(), duration.short_label())
+                        .label_size(LabelSize::Small)
+                        .color(Color::Muted)
+                        .tooltip(Tooltip::text(format!("Snooze for {}", duration.label())))
+                        .on_click(cx.listener(move |this, _event, _, cx| {
+                            this.snooze(duration, cx);
+                        }))
+                })))
+                .child(Label::new(self.owner.github_login.clone()))
+                .child(Label::new(format!(
+                    "is sharing a project in Zed{}",
+                    if self.worktree_root_names.is_empty() {
+                        ""
+                    } else {
+                        ":"
+                    }
+                )))
+                .children(if self.worktree_root_names.is_empty() {
+                    None
+                } else {
+                    Some(Label::new(self.worktree_root_names.join(", ")))
+                })
+                .children(self.preview_text().map(|preview| {
+                    Label::new(preview)
+                        .size(LabelSize::Small)
+                        .color(Color::Muted)
+                        .truncate()
                 })),
-                Button::new("dismiss", "Dismiss").on_click(cx.listener(
-                    move |this, _event, _, cx| {
-                        this.dismiss(cx);
-                    },
-                )),
             )
-            .child(Label::new(self.owner.github_login.clone()))
-            .child(Label::new(format!(
-                "is sharing a project in Zed{}",
-                if self.worktree_root_names.is_empty() {
-                    ""
-                } else {
-                    ":"
-                }
-            )))
-            .children(if self.worktree_root_names.is_empty() {
-                None
-            } else {
-                Some(Label::new(self.worktree_root_names.join(", ")))
-            }),
-        )
     }
 }
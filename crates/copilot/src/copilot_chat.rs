@@ -9,7 +9,9 @@ use collections::HashSet;
 use fs::Fs;
 use futures::{AsyncBufReadExt, AsyncReadExt, StreamExt, io::BufReader, stream::BoxStream};
 use gpui::{App, AsyncApp, Global, prelude::*};
-use http_client::{AsyncBody, HttpClient, Method, Request as HttpRequest};
+use http_client::{
+    AsyncBody, HttpClient, HttpRequestExt as _, Method, Request as HttpRequest, RequestPriority,
+};
 use itertools::Itertools;
 use paths::home_dir;
 use serde::{Deserialize, Serialize};
@@ -593,6 +595,7 @@ async fn stream_completion(
     let request_builder = HttpRequest::builder()
         .method(Method::POST)
         .uri(COPILOT_CHAT_COMPLETION_URL)
+        .priority(RequestPriority::Interactive)
         .header(
             "Editor-Version",
             format!(
@@ -4,7 +4,9 @@ use futures::{
     io::BufReader,
     stream::{BoxStream, StreamExt},
 };
-use http_client::{AsyncBody, HttpClient, Method, Request as HttpRequest};
+use http_client::{
+    AsyncBody, HttpClient, HttpRequestExt as _, Method, Request as HttpRequest, RequestPriority,
+};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::convert::TryFrom;
@@ -266,6 +268,7 @@ pub async fn stream_completion(
     let request_builder = HttpRequest::builder()
         .method(Method::POST)
         .uri(uri)
+        .priority(RequestPriority::Interactive)
         .header("Content-Type", "application/json")
         .header("Authorization", format!("Bearer {}", api_key));
 
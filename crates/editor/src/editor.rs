@@ -16646,6 +16646,9 @@ impl Editor {
                     .diff_hunks_in_ranges(&ranges, &snapshot)
                     .chunk_by(|hunk| hunk.buffer_id);
                 for (buffer_id, hunks) in &chunk_by {
+                    if stage && this.stage_selected_lines_of_new_file(buffer_id, &ranges, cx) {
+                        continue;
+                    }
                     this.do_stage_or_unstage(stage, buffer_id, hunks, cx);
                 }
             })
@@ -16729,6 +16732,46 @@ impl Editor {
         }
     }
 
+    /// A file that isn't in HEAD forms a single hunk, so once it's in the index, only the
+    /// selected lines are staged, allowing it to be committed piecemeal.
+    fn stage_selected_lines_of_new_file(
+        &self,
+        buffer_id: BufferId,
+        ranges: &[Range<Anchor>],
+        cx: &mut App,
+    ) -> bool {
+        let multibuffer = self.buffer.read(cx);
+        let Some(diff) = multibuffer.diff_for(buffer_id) else {
+            return false;
+        };
+        let Some(buffer) = multibuffer.buffer(buffer_id) else {
+            return false;
+        };
+        if diff.read(cx).base_text_exists() {
+            return false;
+        }
+
+        let snapshot = multibuffer.snapshot(cx);
+        let buffer_ranges = ranges
+            .iter()
+            .filter(|range| range.start.to_offset(&snapshot) != range.end.to_offset(&snapshot))
+            .flat_map(|range| snapshot.range_to_buffer_ranges(range.clone()))
+            .filter(|(buffer, _, _)| buffer.remote_id() == buffer_id)
+            .map(|(buffer, range, _)| {
+                buffer.anchor_before(range.start)..buffer.anchor_after(range.end)
+            })
+            .collect::<Vec<_>>();
+        if buffer_ranges.is_empty() {
+            return false;
+        }
+
+        let buffer_snapshot = buffer.read(cx).text_snapshot();
+        diff.update(cx, |diff, cx| {
+            diff.stage_lines(&buffer_ranges, &buffer_snapshot, cx)
+        })
+        .is_some()
+    }
+
     fn do_stage_or_unstage(
         &self,
         stage: bool,
@@ -153,6 +153,7 @@ pub struct Gutter {
     pub runnables: bool,
     pub breakpoints: bool,
     pub folds: bool,
+    pub stage_buttons: bool,
 }
 
 /// When to show the scrollbar in the editor.
@@ -615,6 +616,11 @@ pub struct GutterContent {
     ///
     /// Default: true
     pub folds: Option<bool>,
+    /// Whether to show stage, unstage and restore buttons for the git hunk under
+    /// the cursor or mouse when the hunk is collapsed.
+    ///
+    /// Default: true
+    pub stage_buttons: Option<bool>,
 }
 
 impl EditorSettings {
@@ -3394,13 +3394,13 @@ impl EditorElement {
                                             file_status,
                                             |el, status| {
                                                 el.color(if status.is_conflicted() {
-                                                    Color::Conflict
+                                                    Color::VersionControlConflict
                                                 } else if status.is_modified() {
-                                                    Color::Modified
+                                                    Color::VersionControlModified
                                                 } else if status.is_deleted() {
                                                     Color::Disabled
                                                 } else {
-                                                    Color::Created
+                                                    Color::VersionControlAdded
                                                 })
                                                 .when(status.is_deleted(), |el| el.strikethrough())
                                             },
@@ -4633,6 +4633,7 @@ impl EditorElement {
     ) -> Vec<AnyElement> {
         let render_diff_hunk_controls = editor.read(cx).render_diff_hunk_controls.clone();
         let point_for_position = position_map.point_for_position(window.mouse_position());
+        let show_for_collapsed_hunks = EditorSettings::get_global(cx).gutter.stage_buttons;
 
         let mut controls = vec![];
 
@@ -4672,7 +4673,9 @@ impl EditorElement {
                     continue;
                 }
                 let row_ix = (display_row_range.start - row_range.start).0 as usize;
-                if row_infos[row_ix].diff_status.is_none() {
+                let is_expanded = row_infos[row_ix].diff_status.is_some();
+                // A new file is a single hunk, so its controls would follow the cursor everywhere.
+                if !is_expanded && (!show_for_collapsed_hunks || *is_created_file) {
                     continue;
                 }
                 if row_infos[row_ix]
@@ -4682,9 +4685,12 @@ impl EditorElement {
                 {
                     continue;
                 }
+                // A collapsed deletion occupies no rows, so treat it as spanning the row below it.
+                let active_rows =
+                    display_row_range.start..display_row_range.end.max(display_row_range.start + 1);
                 if active_positions
                     .iter()
-                    .any(|p| p.map_or(false, |p| display_row_range.contains(&p.row())))
+                    .any(|p| p.map_or(false, |p| active_rows.contains(&p.row())))
                 {
                     let y = display_row_range.start.as_f32() * line_height
                         + text_hitbox.bounds.top()
@@ -1829,13 +1829,13 @@ pub fn entry_diagnostic_aware_icon_decoration_and_color(
 pub fn entry_git_aware_label_color(git_status: GitSummary, ignored: bool, selected: bool) -> Color {
     let tracked = git_status.index + git_status.worktree;
     if ignored {
-        Color::Ignored
+        Color::VersionControlIgnored
     } else if git_status.conflict > 0 {
-        Color::Conflict
+        Color::VersionControlConflict
     } else if tracked.modified > 0 {
-        Color::Modified
+        Color::VersionControlModified
     } else if tracked.added > 0 || git_status.untracked > 0 {
-        Color::Created
+        Color::VersionControlAdded
     } else {
         entry_label_color(selected)
     }
@@ -107,6 +107,8 @@ fn main() {
 
     app.run(move |cx| {
         let app_state = init(cx);
+        cx.on_app_quit(|_| async { ReqwestClient::shutdown_shared_runtime() })
+            .detach();
 
         let telemetry = app_state.client.telemetry();
         telemetry.start(system_id, installation_id, session_id, cx);
@@ -586,6 +586,7 @@ impl ExampleInstance {
                 tools: Vec::new(),
                 tool_choice: None,
                 stop: Vec::new(),
+                response_schema: None,
             };
 
             let model = model.clone();
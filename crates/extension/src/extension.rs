@@ -1,4 +1,5 @@
 pub mod extension_builder;
+mod extension_event_log;
 mod extension_events;
 mod extension_host_proxy;
 mod extension_manifest;
@@ -15,6 +16,7 @@ use gpui::{App, Task};
 use language::LanguageName;
 use semantic_version::SemanticVersion;
 
+pub use crate::extension_event_log::*;
 pub use crate::extension_events::*;
 pub use crate::extension_host_proxy::*;
 pub use crate::extension_manifest::*;
@@ -127,6 +129,26 @@ pub trait Extension: Send + Sync + 'static {
         project: Arc<dyn ProjectDelegate>,
     ) -> Result<Option<ContextServerConfiguration>>;
 
+    async fn context_picker_entries(
+        &self,
+        source_id: Arc<str>,
+        query: String,
+    ) -> Result<Vec<ContextPickerEntry>>;
+
+    async fn context_picker_entry_content(
+        &self,
+        source_id: Arc<str>,
+        entry_id: String,
+    ) -> Result<String>;
+
+    async fn git_hosting_build_status(
+        &self,
+        provider_id: Arc<str>,
+        owner: String,
+        repo: String,
+        branch: String,
+    ) -> Result<Option<GitBuildStatus>>;
+
     async fn suggest_docs_packages(&self, provider: Arc<str>) -> Result<Vec<String>>;
 
     async fn index_docs(
@@ -6,7 +6,7 @@ use async_compression::futures::bufread::GzipDecoder;
 use async_tar::Archive;
 use futures::io::BufReader;
 use heck::ToSnakeCase;
-use http_client::{self, AsyncBody, HttpClient};
+use http_client::{self, DownloadManager, DownloadRequest, HttpClient};
 use serde::Deserialize;
 use std::{
     env, fs, mem,
@@ -393,19 +393,25 @@ impl ExtensionBuilder {
 
         let mut tar_out_dir = wasi_sdk_dir.clone();
         tar_out_dir.set_extension("archive");
+        // Kept across failed attempts, so that an interrupted download resumes.
+        let archive_path = wasi_sdk_dir.with_extension("tar.gz");
 
         fs::remove_dir_all(&wasi_sdk_dir).ok();
         fs::remove_dir_all(&tar_out_dir).ok();
 
         log::info!("downloading wasi-sdk to {}", wasi_sdk_dir.display());
-        let mut response = self.http.get(&url, AsyncBody::default(), true).await?;
-        let body = BufReader::new(response.body_mut());
-        let body = GzipDecoder::new(body);
+        DownloadManager::global(self.http.clone())
+            .download(DownloadRequest::new(url, &archive_path))
+            .await
+            .context("failed to download wasi-sdk")?;
+        let archive = smol::fs::File::open(&archive_path).await?;
+        let body = GzipDecoder::new(BufReader::new(archive));
         let tar = Archive::new(body);
 
         tar.unpack(&tar_out_dir)
             .await
             .context("failed to unpack wasi-sdk archive")?;
+        fs::remove_file(&archive_path).ok();
 
         let inner_dir = fs::read_dir(&tar_out_dir)?
             .next()
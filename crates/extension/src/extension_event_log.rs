@@ -0,0 +1,218 @@
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+
+use anyhow::Result;
+use futures::channel::mpsc;
+use parking_lot::Mutex;
+
+/// The maximum number of entries retained by an [`ExtensionEventLog`].
+const MAX_ENTRIES: usize = 1000;
+
+/// The kind of listener an extension lifecycle event was dispatched to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExtensionEventCategory {
+    Theme,
+    IconTheme,
+    Grammar,
+    Language,
+    LanguageServer,
+    Snippet,
+    SlashCommand,
+    ContextPickerSource,
+    GitHostingProvider,
+    ContextServer,
+    IndexedDocsProvider,
+    DebugAdapter,
+}
+
+impl fmt::Display for ExtensionEventCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            Self::Theme => "Theme",
+            Self::IconTheme => "Icon Theme",
+            Self::Grammar => "Grammar",
+            Self::Language => "Language",
+            Self::LanguageServer => "Language Server",
+            Self::Snippet => "Snippet",
+            Self::SlashCommand => "Slash Command",
+            Self::ContextPickerSource => "Context Picker Source",
+            Self::GitHostingProvider => "Git Hosting Provider",
+            Self::ContextServer => "Context Server",
+            Self::IndexedDocsProvider => "Indexed Docs Provider",
+            Self::DebugAdapter => "Debug Adapter",
+        };
+        f.write_str(label)
+    }
+}
+
+/// A single dispatch of an extension lifecycle event to a listener.
+#[derive(Debug, Clone)]
+pub struct ExtensionEventLogEntry {
+    pub timestamp: SystemTime,
+    pub category: ExtensionEventCategory,
+    /// The extension the event originated from, if it concerns a single extension.
+    pub extension_id: Option<Arc<str>>,
+    pub action: String,
+    pub duration: Duration,
+    pub error: Option<String>,
+}
+
+/// An in-memory log of the events dispatched to extension listeners, used to
+/// debug why an extension's contributions didn't get registered.
+#[derive(Default)]
+pub struct ExtensionEventLog {
+    entries: Mutex<VecDeque<ExtensionEventLogEntry>>,
+    subscribers: Mutex<Vec<mpsc::UnboundedSender<()>>>,
+}
+
+impl ExtensionEventLog {
+    /// Runs `f`, recording how long the listener took to handle the event.
+    pub fn record<T>(
+        &self,
+        category: ExtensionEventCategory,
+        extension_id: Option<Arc<str>>,
+        action: impl Into<String>,
+        f: impl FnOnce() -> T,
+    ) -> T {
+        let start = Instant::now();
+        let result = f();
+        self.push(category, extension_id, action.into(), start.elapsed(), None);
+        result
+    }
+
+    /// Runs `f`, recording how long the listener took and the error it returned, if any.
+    pub fn record_result<T>(
+        &self,
+        category: ExtensionEventCategory,
+        extension_id: Option<Arc<str>>,
+        action: impl Into<String>,
+        f: impl FnOnce() -> Result<T>,
+    ) -> Result<T> {
+        let start = Instant::now();
+        let result = f();
+        let error = result.as_ref().err().map(|error| format!("{error:#}"));
+        self.push(
+            category,
+            extension_id,
+            action.into(),
+            start.elapsed(),
+            error,
+        );
+        result
+    }
+
+    /// Awaits `future`, recording how long the listener took and the error it returned, if any.
+    pub async fn record_async<T>(
+        &self,
+        category: ExtensionEventCategory,
+        extension_id: Option<Arc<str>>,
+        action: impl Into<String>,
+        future: impl Future<Output = Result<T>>,
+    ) -> Result<T> {
+        let action = action.into();
+        let start = Instant::now();
+        let result = future.await;
+        let error = result.as_ref().err().map(|error| format!("{error:#}"));
+        self.push(category, extension_id, action, start.elapsed(), error);
+        result
+    }
+
+    /// Returns the recorded entries, oldest first.
+    pub fn entries(&self) -> Vec<ExtensionEventLogEntry> {
+        self.entries.lock().iter().cloned().collect()
+    }
+
+    pub fn clear(&self) {
+        self.entries.lock().clear();
+        self.notify_subscribers();
+    }
+
+    /// Returns a receiver that yields whenever the log changes.
+    pub fn subscribe(&self) -> mpsc::UnboundedReceiver<()> {
+        let (tx, rx) = mpsc::unbounded();
+        self.subscribers.lock().push(tx);
+        rx
+    }
+
+    fn push(
+        &self,
+        category: ExtensionEventCategory,
+        extension_id: Option<Arc<str>>,
+        action: String,
+        duration: Duration,
+        error: Option<String>,
+    ) {
+        if let Some(error) = &error {
+            log::error!(
+                "{category} listener failed to handle {action:?} for extension {}: {error}",
+                extension_id.as_deref().unwrap_or("<none>")
+            );
+        }
+
+        let mut entries = self.entries.lock();
+        if entries.len() == MAX_ENTRIES {
+            entries.pop_front();
+        }
+        entries.push_back(ExtensionEventLogEntry {
+            timestamp: SystemTime::now(),
+            category,
+            extension_id,
+            action,
+            duration,
+            error,
+        });
+        drop(entries);
+        self.notify_subscribers();
+    }
+
+    fn notify_subscribers(&self) {
+        self.subscribers
+            .lock()
+            .retain(|subscriber| subscriber.unbounded_send(()).is_ok());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+
+    #[test]
+    fn test_records_errors_and_evicts_oldest_entries() {
+        let log = ExtensionEventLog::default();
+        let extension_id: Arc<str> = "zed-theme".into();
+
+        log.record(
+            ExtensionEventCategory::Grammar,
+            None,
+            "register grammars",
+            || (),
+        );
+        log.record_result(
+            ExtensionEventCategory::Theme,
+            Some(extension_id.clone()),
+            "load theme",
+            || Err::<(), _>(anyhow!("invalid theme")),
+        )
+        .ok();
+
+        let entries = log.entries();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].error, None);
+        assert_eq!(entries[1].extension_id, Some(extension_id));
+        assert_eq!(entries[1].error.as_deref(), Some("invalid theme"));
+
+        for _ in 0..MAX_ENTRIES {
+            log.record(ExtensionEventCategory::Snippet, None, "register", || ());
+        }
+        let entries = log.entries();
+        assert_eq!(entries.len(), MAX_ENTRIES);
+        assert!(
+            entries
+                .iter()
+                .all(|entry| entry.category == ExtensionEventCategory::Snippet)
+        );
+    }
+}
@@ -38,6 +38,8 @@ pub enum Event {
     ExtensionInstalled(Arc<ExtensionManifest>),
     ExtensionsInstalledChanged,
     ConfigureExtensionRequested(Arc<ExtensionManifest>),
+    /// An upgraded extension declares different capabilities than the version it replaced.
+    ExtensionCapabilitiesChanged(Arc<ExtensionManifest>),
 }
 
 impl EventEmitter<Event> for ExtensionEvents {}
@@ -8,7 +8,7 @@ use language::{BinaryStatus, LanguageMatcher, LanguageName, LoadedLanguage};
 use lsp::LanguageServerName;
 use parking_lot::RwLock;
 
-use crate::{Extension, SlashCommand};
+use crate::{Extension, ExtensionEventLog, GitHostingProviderManifestEntry, SlashCommand};
 
 #[derive(Default)]
 struct GlobalExtensionHostProxy(Arc<ExtensionHostProxy>);
@@ -22,14 +22,18 @@ impl Global for GlobalExtensionHostProxy {}
 #[derive(Default)]
 pub struct ExtensionHostProxy {
     theme_proxy: RwLock<Option<Arc<dyn ExtensionThemeProxy>>>,
+    icon_theme_proxy: RwLock<Option<Arc<dyn ExtensionIconThemeProxy>>>,
     grammar_proxy: RwLock<Option<Arc<dyn ExtensionGrammarProxy>>>,
     language_proxy: RwLock<Option<Arc<dyn ExtensionLanguageProxy>>>,
     language_server_proxy: RwLock<Option<Arc<dyn ExtensionLanguageServerProxy>>>,
     snippet_proxy: RwLock<Option<Arc<dyn ExtensionSnippetProxy>>>,
     slash_command_proxy: RwLock<Option<Arc<dyn ExtensionSlashCommandProxy>>>,
+    context_picker_source_proxy: RwLock<Option<Arc<dyn ExtensionContextPickerSourceProxy>>>,
+    git_hosting_provider_proxy: RwLock<Option<Arc<dyn ExtensionGitHostingProviderProxy>>>,
     context_server_proxy: RwLock<Option<Arc<dyn ExtensionContextServerProxy>>>,
     indexed_docs_provider_proxy: RwLock<Option<Arc<dyn ExtensionIndexedDocsProviderProxy>>>,
     debug_adapter_provider_proxy: RwLock<Option<Arc<dyn ExtensionDebugAdapterProviderProxy>>>,
+    event_log: ExtensionEventLog,
 }
 
 impl ExtensionHostProxy {
@@ -48,21 +52,34 @@ impl ExtensionHostProxy {
     pub fn new() -> Self {
         Self {
             theme_proxy: RwLock::default(),
+            icon_theme_proxy: RwLock::default(),
             grammar_proxy: RwLock::default(),
             language_proxy: RwLock::default(),
             language_server_proxy: RwLock::default(),
             snippet_proxy: RwLock::default(),
             slash_command_proxy: RwLock::default(),
+            context_picker_source_proxy: RwLock::default(),
+            git_hosting_provider_proxy: RwLock::default(),
             context_server_proxy: RwLock::default(),
             indexed_docs_provider_proxy: RwLock::default(),
             debug_adapter_provider_proxy: RwLock::default(),
+            event_log: ExtensionEventLog::default(),
         }
     }
 
+    /// Returns the log of events dispatched through this proxy.
+    pub fn event_log(&self) -> &ExtensionEventLog {
+        &self.event_log
+    }
+
     pub fn register_theme_proxy(&self, proxy: impl ExtensionThemeProxy) {
         self.theme_proxy.write().replace(Arc::new(proxy));
     }
 
+    pub fn register_icon_theme_proxy(&self, proxy: impl ExtensionIconThemeProxy) {
+        self.icon_theme_proxy.write().replace(Arc::new(proxy));
+    }
+
     pub fn register_grammar_proxy(&self, proxy: impl ExtensionGrammarProxy) {
         self.grammar_proxy.write().replace(Arc::new(proxy));
     }
@@ -83,6 +100,24 @@ impl ExtensionHostProxy {
         self.slash_command_proxy.write().replace(Arc::new(proxy));
     }
 
+    pub fn register_context_picker_source_proxy(
+        &self,
+        proxy: impl ExtensionContextPickerSourceProxy,
+    ) {
+        self.context_picker_source_proxy
+            .write()
+            .replace(Arc::new(proxy));
+    }
+
+    pub fn register_git_hosting_provider_proxy(
+        &self,
+        proxy: impl ExtensionGitHostingProviderProxy,
+    ) {
+        self.git_hosting_provider_proxy
+            .write()
+            .replace(Arc::new(proxy));
+    }
+
     pub fn register_context_server_proxy(&self, proxy: impl ExtensionContextServerProxy) {
         self.context_server_proxy.write().replace(Arc::new(proxy));
     }
@@ -112,7 +147,9 @@ pub trait ExtensionThemeProxy: Send + Sync + 'static {
     fn load_user_theme(&self, theme_path: PathBuf, fs: Arc<dyn Fs>) -> Task<Result<()>>;
 
     fn reload_current_theme(&self, cx: &mut App);
+}
 
+pub trait ExtensionIconThemeProxy: Send + Sync + 'static {
     fn list_icon_theme_names(
         &self,
         icon_theme_path: PathBuf,
@@ -171,13 +208,15 @@ impl ExtensionThemeProxy for ExtensionHostProxy {
 
         proxy.reload_current_theme(cx)
     }
+}
 
+impl ExtensionIconThemeProxy for ExtensionHostProxy {
     fn list_icon_theme_names(
         &self,
         icon_theme_path: PathBuf,
         fs: Arc<dyn Fs>,
     ) -> Task<Result<Vec<String>>> {
-        let Some(proxy) = self.theme_proxy.read().clone() else {
+        let Some(proxy) = self.icon_theme_proxy.read().clone() else {
             return Task::ready(Ok(Vec::new()));
         };
 
@@ -185,7 +224,7 @@ impl ExtensionThemeProxy for ExtensionHostProxy {
     }
 
     fn remove_icon_themes(&self, icon_themes: Vec<SharedString>) {
-        let Some(proxy) = self.theme_proxy.read().clone() else {
+        let Some(proxy) = self.icon_theme_proxy.read().clone() else {
             return;
         };
 
@@ -198,7 +237,7 @@ impl ExtensionThemeProxy for ExtensionHostProxy {
         icons_root_dir: PathBuf,
         fs: Arc<dyn Fs>,
     ) -> Task<Result<()>> {
-        let Some(proxy) = self.theme_proxy.read().clone() else {
+        let Some(proxy) = self.icon_theme_proxy.read().clone() else {
             return Task::ready(Ok(()));
         };
 
@@ -206,7 +245,7 @@ impl ExtensionThemeProxy for ExtensionHostProxy {
     }
 
     fn reload_current_icon_theme(&self, cx: &mut App) {
-        let Some(proxy) = self.theme_proxy.read().clone() else {
+        let Some(proxy) = self.icon_theme_proxy.read().clone() else {
             return;
         };
 
@@ -362,6 +401,74 @@ impl ExtensionSlashCommandProxy for ExtensionHostProxy {
     }
 }
 
+pub trait ExtensionContextPickerSourceProxy: Send + Sync + 'static {
+    fn register_context_picker_source(
+        &self,
+        extension: Arc<dyn Extension>,
+        source_id: Arc<str>,
+        name: SharedString,
+    );
+
+    fn unregister_context_picker_source(&self, source_id: Arc<str>);
+}
+
+impl ExtensionContextPickerSourceProxy for ExtensionHostProxy {
+    fn register_context_picker_source(
+        &self,
+        extension: Arc<dyn Extension>,
+        source_id: Arc<str>,
+        name: SharedString,
+    ) {
+        let Some(proxy) = self.context_picker_source_proxy.read().clone() else {
+            return;
+        };
+
+        proxy.register_context_picker_source(extension, source_id, name)
+    }
+
+    fn unregister_context_picker_source(&self, source_id: Arc<str>) {
+        let Some(proxy) = self.context_picker_source_proxy.read().clone() else {
+            return;
+        };
+
+        proxy.unregister_context_picker_source(source_id)
+    }
+}
+
+pub trait ExtensionGitHostingProviderProxy: Send + Sync + 'static {
+    fn register_git_hosting_provider(
+        &self,
+        extension: Arc<dyn Extension>,
+        provider_id: Arc<str>,
+        provider: GitHostingProviderManifestEntry,
+    );
+
+    fn unregister_git_hosting_provider(&self, extension_id: Arc<str>, provider_id: Arc<str>);
+}
+
+impl ExtensionGitHostingProviderProxy for ExtensionHostProxy {
+    fn register_git_hosting_provider(
+        &self,
+        extension: Arc<dyn Extension>,
+        provider_id: Arc<str>,
+        provider: GitHostingProviderManifestEntry,
+    ) {
+        let Some(proxy) = self.git_hosting_provider_proxy.read().clone() else {
+            return;
+        };
+
+        proxy.register_git_hosting_provider(extension, provider_id, provider)
+    }
+
+    fn unregister_git_hosting_provider(&self, extension_id: Arc<str>, provider_id: Arc<str>) {
+        let Some(proxy) = self.git_hosting_provider_proxy.read().clone() else {
+            return;
+        };
+
+        proxy.unregister_git_hosting_provider(extension_id, provider_id)
+    }
+}
+
 pub trait ExtensionContextServerProxy: Send + Sync + 'static {
     fn register_context_server(
         &self,
@@ -8,7 +8,7 @@ use serde::{Deserialize, Serialize};
 use std::{
     ffi::OsStr,
     fmt,
-    path::{Path, PathBuf},
+    path::{Component, Path, PathBuf},
     sync::Arc,
 };
 
@@ -82,6 +82,10 @@ pub struct ExtensionManifest {
     #[serde(default)]
     pub slash_commands: BTreeMap<Arc<str>, SlashCommandManifestEntry>,
     #[serde(default)]
+    pub context_picker_sources: BTreeMap<Arc<str>, ContextPickerSourceManifestEntry>,
+    #[serde(default)]
+    pub git_hosting_providers: BTreeMap<Arc<str>, GitHostingProviderManifestEntry>,
+    #[serde(default)]
     pub indexed_docs_providers: BTreeMap<Arc<str>, IndexedDocsProviderEntry>,
     #[serde(default)]
     pub snippets: Option<PathBuf>,
@@ -89,6 +93,9 @@ pub struct ExtensionManifest {
     pub capabilities: Vec<ExtensionCapability>,
     #[serde(default)]
     pub debug_adapters: Vec<Arc<str>>,
+    /// The IDs of other extensions that must be installed for this extension to work.
+    #[serde(default)]
+    pub dependencies: Vec<Arc<str>>,
 }
 
 impl ExtensionManifest {
@@ -128,6 +135,85 @@ impl ExtensionManifest {
 
         Ok(())
     }
+
+    pub fn allow_network_request(&self, url: &str) -> Result<()> {
+        let uri = url
+            .parse::<http_client::Uri>()
+            .with_context(|| format!("invalid URL {url:?}"))?;
+        let desired_host = uri
+            .host()
+            .with_context(|| format!("URL {url:?} has no host"))?
+            .to_ascii_lowercase();
+
+        let is_allowed = self.capabilities.iter().any(|capability| match capability {
+            ExtensionCapability::NetworkRequest { host } => {
+                let host = host.to_ascii_lowercase();
+                if host == "*" {
+                    return true;
+                }
+                match host.strip_prefix("*.") {
+                    Some(domain) => desired_host
+                        .strip_suffix(domain)
+                        .is_some_and(|subdomain| subdomain.ends_with('.')),
+                    None => host == desired_host,
+                }
+            }
+            _ => false,
+        });
+
+        if !is_allowed {
+            bail!(
+                "capability for network:request {desired_host} was not listed in the extension manifest",
+            );
+        }
+
+        Ok(())
+    }
+
+    pub fn allow_file_read(&self, desired_path: &str) -> Result<()> {
+        // Paths are matched segment by segment, so anything that could step outside of the
+        // matched directory has to be rejected up front.
+        let escapes_directory = Path::new(desired_path)
+            .components()
+            .any(|component| !matches!(component, Component::Normal(_) | Component::CurDir));
+        if escapes_directory || desired_path.contains('\\') {
+            bail!("fs:read {desired_path} must be a relative path without `..`");
+        }
+        let desired_segments = desired_path
+            .split('/')
+            .filter(|segment| !segment.is_empty() && *segment != ".")
+            .collect::<Vec<_>>();
+
+        let is_allowed = self.capabilities.iter().any(|capability| match capability {
+            ExtensionCapability::FileRead { path } => {
+                let segments = path
+                    .split('/')
+                    .filter(|segment| !segment.is_empty())
+                    .collect::<Vec<_>>();
+                for (ix, segment) in segments.iter().enumerate() {
+                    if *segment == "**" {
+                        return true;
+                    }
+
+                    if ix >= desired_segments.len() {
+                        return false;
+                    }
+
+                    if *segment != "*" && *segment != desired_segments[ix] {
+                        return false;
+                    }
+                }
+                segments.len() == desired_segments.len()
+            }
+            _ => false,
+        });
+
+        if !is_allowed {
+            bail!("capability for fs:read {desired_path} was not listed in the extension manifest",);
+        }
+
+        Ok(())
+    }
 }
 
 /// A capability for an extension.
@@ -142,6 +228,39 @@ pub enum ExtensionCapability {
         /// If the last element is `**`, then any trailing arguments are allowed.
         args: Vec<String>,
     },
+    #[serde(rename = "network:request")]
+    NetworkRequest {
+        /// The host that may be contacted. Use `*.example.com` to allow any subdomain of
+        /// `example.com`, or `*` to allow any host.
+        host: String,
+    },
+    #[serde(rename = "fs:read")]
+    FileRead {
+        /// The path of the files that may be read, relative to the worktree root. Use `*` for a
+        /// single wildcard path segment. If the last segment is `**`, then any trailing segments
+        /// are allowed.
+        path: String,
+    },
+}
+
+impl ExtensionCapability {
+    /// Returns a description of what this capability allows, to be shown to the user.
+    pub fn description(&self) -> String {
+        match self {
+            Self::ProcessExec { command, args } => {
+                if args.is_empty() {
+                    format!("Run `{command}`")
+                } else {
+                    format!("Run `{command} {}`", args.join(" "))
+                }
+            }
+            Self::NetworkRequest { host } if host == "*" => {
+                "Make network requests to any host".to_string()
+            }
+            Self::NetworkRequest { host } => format!("Make network requests to {host}"),
+            Self::FileRead { path } => format!("Read `{path}` in your projects"),
+        }
+    }
 }
 
 #[derive(Clone, Default, PartialEq, Eq, Debug, Deserialize, Serialize)]
@@ -205,6 +324,61 @@ pub struct SlashCommandManifestEntry {
     pub requires_argument: bool,
 }
 
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct ContextPickerSourceManifestEntry {
+    /// The name shown for this source in the agent's context picker.
+    pub name: String,
+}
+
+/// A Git hosting provider contributed by an extension.
+///
+/// URL templates may reference `{base_url}`, `{owner}`, and `{repo}`, along with the
+/// placeholders listed for each template.
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct GitHostingProviderManifestEntry {
+    /// The name of the provider (e.g., "Forgejo").
+    pub name: String,
+    /// The base URL of the provider (e.g., "https://git.example.com").
+    pub base_url: String,
+    /// The URL of a commit, which may reference `{sha}`.
+    pub commit_url: String,
+    /// The URL of a file at a commit, which may reference `{sha}` and `{path}`.
+    pub file_url: String,
+    /// The fragment appended to a file URL to select a single line, which may reference `{line}`.
+    #[serde(default = "default_line_fragment")]
+    pub line_fragment: String,
+    /// The fragment appended to a file URL to select a range of lines, which may reference
+    /// `{start_line}` and `{end_line}`.
+    #[serde(default = "default_line_range_fragment")]
+    pub line_range_fragment: String,
+    /// A regex matched against the first line of a commit message, whose first capture group
+    /// is the number of the pull request the commit came from.
+    #[serde(default)]
+    pub pull_request_pattern: Option<String>,
+    /// The URL of a pull request, which may reference `{number}`.
+    #[serde(default)]
+    pub pull_request_url: Option<String>,
+    /// The URL for opening a new pull request from a branch, which may reference `{branch}`.
+    #[serde(default)]
+    pub new_pull_request_url: Option<String>,
+    /// The ref that the remote exposes the head of a pull request under, which may reference
+    /// `{number}` (e.g., "refs/pull/{number}/head"). Pull requests can only be checked out when
+    /// this is set.
+    #[serde(default)]
+    pub pull_request_ref: Option<String>,
+    /// Whether the extension reports CI build statuses for branches on this provider.
+    #[serde(default)]
+    pub build_status: bool,
+}
+
+fn default_line_fragment() -> String {
+    "L{line}".to_string()
+}
+
+fn default_line_range_fragment() -> String {
+    "L{start_line}-L{end_line}".to_string()
+}
+
 #[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
 pub struct IndexedDocsProviderEntry {}
 
@@ -273,10 +447,13 @@ fn manifest_from_old_manifest(
         language_servers: Default::default(),
         context_servers: BTreeMap::default(),
         slash_commands: BTreeMap::default(),
+        context_picker_sources: BTreeMap::default(),
+        git_hosting_providers: BTreeMap::default(),
         indexed_docs_providers: BTreeMap::default(),
         snippets: None,
         capabilities: Vec::new(),
         debug_adapters: vec![],
+        dependencies: vec![],
     }
 }
 
@@ -301,10 +478,13 @@ mod tests {
             language_servers: BTreeMap::default(),
             context_servers: BTreeMap::default(),
             slash_commands: BTreeMap::default(),
+            context_picker_sources: BTreeMap::default(),
+            git_hosting_providers: BTreeMap::default(),
             indexed_docs_providers: BTreeMap::default(),
             snippets: None,
             capabilities: vec![],
             debug_adapters: Default::default(),
+            dependencies: Default::default(),
         }
     }
 
@@ -383,4 +563,75 @@ mod tests {
         );
         assert!(manifest.allow_exec("docker", &["ps"]).is_err()); // wrong first arg
     }
+
+    #[test]
+    fn test_allow_network_request() {
+        let manifest = ExtensionManifest {
+            capabilities: vec![
+                ExtensionCapability::NetworkRequest {
+                    host: "api.example.com".to_string(),
+                },
+                ExtensionCapability::NetworkRequest {
+                    host: "*.cdn.example.com".to_string(),
+                },
+            ],
+            ..extension_manifest()
+        };
+
+        assert!(
+            manifest
+                .allow_network_request("https://api.example.com/v1/releases")
+                .is_ok()
+        );
+        assert!(
+            manifest
+                .allow_network_request("https://eu.cdn.example.com/file.tar.gz")
+                .is_ok()
+        );
+        assert!(
+            manifest
+                .allow_network_request("https://cdn.example.com/file.tar.gz")
+                .is_err()
+        ); // wildcard requires a subdomain
+        assert!(
+            manifest
+                .allow_network_request("https://evilcdn.example.com/file.tar.gz")
+                .is_err()
+        );
+        assert!(
+            manifest
+                .allow_network_request("https://example.com/")
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_allow_file_read() {
+        let manifest = ExtensionManifest {
+            capabilities: vec![
+                ExtensionCapability::FileRead {
+                    path: "Cargo.toml".to_string(),
+                },
+                ExtensionCapability::FileRead {
+                    path: "config/*/settings.json".to_string(),
+                },
+                ExtensionCapability::FileRead {
+                    path: ".tools/**".to_string(),
+                },
+            ],
+            ..extension_manifest()
+        };
+
+        assert!(manifest.allow_file_read("Cargo.toml").is_ok());
+        assert!(manifest.allow_file_read("crates/Cargo.toml").is_err());
+        assert!(manifest.allow_file_read("config/dev/settings.json").is_ok());
+        assert!(manifest.allow_file_read("config/settings.json").is_err());
+        assert!(manifest.allow_file_read(".tools/bin/tool").is_ok());
+        assert!(manifest.allow_file_read("./.tools/bin/tool").is_ok());
+        assert!(manifest.allow_file_read("src/main.rs").is_err());
+        assert!(manifest.allow_file_read(".tools/../../etc/passwd").is_err());
+        assert!(manifest.allow_file_read("config/../settings.json").is_err());
+        assert!(manifest.allow_file_read("/Cargo.toml").is_err());
+        assert!(manifest.allow_file_read(".tools\\..\\secret").is_err());
+    }
 }
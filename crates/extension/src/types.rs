@@ -1,12 +1,16 @@
+mod context_picker;
 mod context_server;
 mod dap;
+mod git_hosting;
 mod lsp;
 mod slash_command;
 
 use std::ops::Range;
 
+pub use context_picker::*;
 pub use context_server::*;
 pub use dap::*;
+pub use git_hosting::*;
 pub use lsp::*;
 pub use slash_command::*;
 
@@ -0,0 +1,10 @@
+/// An entry contributed by an extension to the agent's context picker.
+#[derive(Debug, Clone)]
+pub struct ContextPickerEntry {
+    /// The identifier used to retrieve the entry's content.
+    pub id: String,
+    /// The label to display in the context picker.
+    pub label: String,
+    /// An optional description to display alongside the label.
+    pub description: Option<String>,
+}
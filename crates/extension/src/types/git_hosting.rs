@@ -0,0 +1,16 @@
+/// The state of a CI build reported by an extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GitBuildState {
+    Pending,
+    Success,
+    Failure,
+}
+
+/// The status of the most recent CI build for a branch, as reported by an extension.
+#[derive(Debug, Clone)]
+pub struct GitBuildStatus {
+    /// The state of the build.
+    pub state: GitBuildState,
+    /// A link to the build's details, if there is one.
+    pub url: Option<String>,
+}
@@ -18,12 +18,14 @@ pub use wit::{
     CodeLabel, CodeLabelSpan, CodeLabelSpanLiteral, Command, DownloadedFileType, EnvVars,
     KeyValueStore, LanguageServerInstallationStatus, Project, Range, Worktree, download_file,
     make_file_executable,
+    zed::extension::context_picker::ContextPickerEntry,
     zed::extension::context_server::ContextServerConfiguration,
     zed::extension::dap::{
         DebugAdapterBinary, DebugTaskDefinition, StartDebuggingRequestArguments,
         StartDebuggingRequestArgumentsRequest, TcpArguments, TcpArgumentsTemplate,
         resolve_tcp_template,
     },
+    zed::extension::git_hosting::{BuildState, BuildStatus},
     zed::extension::github::{
         GithubRelease, GithubReleaseAsset, GithubReleaseOptions, github_release_by_tag_name,
         latest_github_release,
@@ -156,6 +158,36 @@ pub trait Extension: Send + Sync {
         Err("`run_slash_command` not implemented".to_string())
     }
 
+    /// Returns the entries of the given context picker source that match the query.
+    fn context_picker_entries(
+        &self,
+        _source_id: String,
+        _query: String,
+    ) -> Result<Vec<ContextPickerEntry>, String> {
+        Ok(Vec::new())
+    }
+
+    /// Returns the content of the given context picker entry, to be attached as context.
+    fn context_picker_entry_content(
+        &self,
+        _source_id: String,
+        _entry_id: String,
+    ) -> Result<String, String> {
+        Err("`context_picker_entry_content` not implemented".to_string())
+    }
+
+    /// Returns the status of the most recent CI build for the given branch of a repository on
+    /// one of the extension's Git hosting providers.
+    fn git_hosting_build_status(
+        &self,
+        _provider_id: String,
+        _owner: String,
+        _repo: String,
+        _branch: String,
+    ) -> Result<Option<BuildStatus>, String> {
+        Ok(None)
+    }
+
     /// Returns the command used to start a context server.
     fn context_server_command(
         &mut self,
@@ -364,6 +396,26 @@ impl wit::Guest for Component {
         extension().run_slash_command(command, args, worktree)
     }
 
+    fn context_picker_entries(
+        source_id: String,
+        query: String,
+    ) -> Result<Vec<ContextPickerEntry>, String> {
+        extension().context_picker_entries(source_id, query)
+    }
+
+    fn context_picker_entry_content(source_id: String, entry_id: String) -> Result<String, String> {
+        extension().context_picker_entry_content(source_id, entry_id)
+    }
+
+    fn git_hosting_build_status(
+        provider_id: String,
+        owner: String,
+        repo: String,
+        branch: String,
+    ) -> Result<Option<BuildStatus>, String> {
+        extension().git_hosting_build_status(provider_id, owner, repo, branch)
+    }
+
     fn context_server_command(
         context_server_id: String,
         project: &Project,
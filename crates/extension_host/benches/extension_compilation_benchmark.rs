@@ -132,6 +132,8 @@ fn manifest() -> ExtensionManifest {
             .collect(),
         context_servers: BTreeMap::default(),
         slash_commands: BTreeMap::default(),
+        context_picker_sources: BTreeMap::default(),
+        git_hosting_providers: BTreeMap::default(),
         indexed_docs_providers: BTreeMap::default(),
         snippets: None,
         capabilities: vec![ExtensionCapability::ProcessExec {
@@ -139,6 +141,7 @@ fn manifest() -> ExtensionManifest {
             args: vec!["hello!".into()],
         }],
         debug_adapters: Default::default(),
+        dependencies: Default::default(),
     }
 }
 
@@ -14,10 +14,12 @@ use collections::{BTreeMap, BTreeSet, HashMap, HashSet, btree_map};
 pub use extension::ExtensionManifest;
 use extension::extension_builder::{CompileExtensionOptions, ExtensionBuilder};
 use extension::{
-    ExtensionContextServerProxy, ExtensionDebugAdapterProviderProxy, ExtensionEvents,
-    ExtensionGrammarProxy, ExtensionHostProxy, ExtensionIndexedDocsProviderProxy,
-    ExtensionLanguageProxy, ExtensionLanguageServerProxy, ExtensionSlashCommandProxy,
-    ExtensionSnippetProxy, ExtensionThemeProxy,
+    ExtensionCapability, ExtensionContextPickerSourceProxy, ExtensionContextServerProxy,
+    ExtensionDebugAdapterProviderProxy, ExtensionEventCategory, ExtensionEvents,
+    ExtensionGitHostingProviderProxy, ExtensionGrammarProxy, ExtensionHostProxy,
+    ExtensionIconThemeProxy, ExtensionIndexedDocsProviderProxy, ExtensionLanguageProxy,
+    ExtensionLanguageServerProxy, ExtensionSlashCommandProxy, ExtensionSnippetProxy,
+    ExtensionThemeProxy,
 };
 use fs::{Fs, RemoveOptions};
 use futures::{
@@ -33,7 +35,7 @@ use gpui::{
     App, AppContext as _, AsyncApp, Context, Entity, EventEmitter, Global, Task, WeakEntity,
     actions,
 };
-use http_client::{AsyncBody, HttpClient, HttpClientWithUrl};
+use http_client::{AsyncBody, DownloadManager, DownloadRequest, HttpClient, HttpClientWithUrl};
 use language::{
     LanguageConfig, LanguageMatcher, LanguageName, LanguageQueries, LoadedLanguage,
     QUERY_FILENAME_PREFIXES, Rope,
@@ -56,7 +58,7 @@ use std::{
 use url::Url;
 use util::ResultExt;
 use wasm_host::{
-    WasmExtension, WasmHost,
+    ExtensionCrash, WasmExtension, WasmHost,
     wit::{is_supported_wasm_api_version, wasm_api_version_range},
 };
 
@@ -113,6 +115,9 @@ pub struct ExtensionStore {
     pub outstanding_operations: BTreeMap<Arc<str>, ExtensionOperation>,
     pub index_path: PathBuf,
     pub modified_extensions: HashSet<Arc<str>>,
+    /// Extensions that crashed or exceeded their resource limits, which stay unloaded until
+    /// their files change.
+    pub crashed_extensions: HashSet<Arc<str>>,
     pub wasm_host: Arc<WasmHost>,
     pub wasm_extensions: Vec<(Arc<ExtensionManifest>, WasmExtension)>,
     pub tasks: Vec<Task<()>>,
@@ -133,6 +138,26 @@ pub enum Event {
     StartedReloading,
     ExtensionInstalled(Arc<str>),
     ExtensionFailedToLoad(Arc<str>),
+    ExtensionCrashed(Arc<str>),
+    CapabilityApprovalRequested(CapabilityApprovalRequest),
+}
+
+/// A request for the user to grant the capabilities declared by an extension that is being
+/// installed or upgraded.
+#[derive(Clone)]
+pub struct CapabilityApprovalRequest {
+    pub extension_id: Arc<str>,
+    pub extension_name: String,
+    pub operation: ExtensionOperation,
+    /// The capabilities that weren't already granted to the installed version of the extension.
+    pub capabilities: Vec<ExtensionCapability>,
+    response_tx: UnboundedSender<bool>,
+}
+
+impl CapabilityApprovalRequest {
+    pub fn respond(&self, approved: bool) {
+        self.response_tx.unbounded_send(approved).ok();
+    }
 }
 
 impl EventEmitter<Event> for ExtensionStore {}
@@ -246,6 +271,7 @@ impl ExtensionStore {
             builder: Arc::new(ExtensionBuilder::new(builder_client, build_dir)),
             outstanding_operations: Default::default(),
             modified_extensions: Default::default(),
+            crashed_extensions: Default::default(),
             reload_complete_senders: Vec::new(),
             wasm_host: WasmHost::new(
                 fs.clone(),
@@ -366,6 +392,18 @@ impl ExtensionStore {
             .await;
         }));
 
+        let mut crashed_extensions = this.wasm_host.crashed_extensions();
+        this.tasks.push(cx.spawn(async move |this, cx| {
+            while let Some(crash) = crashed_extensions.next().await {
+                if this
+                    .update(cx, |this, cx| this.extension_crashed(crash, cx))
+                    .is_err()
+                {
+                    break;
+                }
+            }
+        }));
+
         // Watch the installed extensions directory for changes. Whenever changes are
         // detected, rebuild the extension index, and load/unload any extensions that
         // have been added, removed, or modified.
@@ -413,6 +451,16 @@ impl ExtensionStore {
         }
     }
 
+    fn extension_crashed(&mut self, crash: ExtensionCrash, cx: &mut Context<Self>) {
+        if !self.crashed_extensions.insert(crash.extension_id.clone()) {
+            return;
+        }
+        cx.emit(Event::ExtensionCrashed(crash.extension_id));
+        // Rebuilding the index leaves the crashed extension out, which unloads it and
+        // unregisters everything it provided.
+        drop(self.reload(None, cx));
+    }
+
     fn extensions_dir(&self) -> PathBuf {
         self.installed_dir.clone()
     }
@@ -714,10 +762,45 @@ impl ExtensionStore {
                 }
             });
 
-            let mut response = http_client
-                .get(url.as_ref(), Default::default(), true)
+            // The download manager checks the archive's size, and resumes the download if
+            // an earlier attempt at it was interrupted.
+            let archive_path = paths::temp_dir()
+                .join("extensions")
+                .join(format!("{extension_id}.tar.gz"));
+            DownloadManager::global(http_client)
+                .download(DownloadRequest::new(url.as_str(), &archive_path))
                 .await
                 .context("downloading extension")?;
+            let tar_gz_bytes = smol::fs::read(&archive_path).await?;
+            smol::fs::remove_file(&archive_path).await.log_err();
+
+            // Ask before touching the installed version, so that declining an upgrade leaves it
+            // in place.
+            let previous_capabilities = this.update(cx, |this, _| {
+                this.extension_manifest_for_id(&extension_id)
+                    .map(|manifest| manifest.capabilities.clone())
+            })?;
+            let new_capabilities = manifest_from_archive(&tar_gz_bytes)
+                .await?
+                .map(|manifest| (manifest.name, manifest.capabilities));
+            if let Some((extension_name, capabilities)) = &new_capabilities {
+                let approval = this.update(cx, |this, cx| {
+                    this.request_capability_approval(
+                        extension_id.clone(),
+                        extension_name.clone(),
+                        operation,
+                        capabilities,
+                        previous_capabilities.as_deref().unwrap_or_default(),
+                        cx,
+                    )
+                })?;
+                if let Some(approval) = approval {
+                    if !approval.await {
+                        log::info!("not installing {extension_id}: capabilities were not granted");
+                        return Ok(());
+                    }
+                }
+            }
 
             fs.remove_dir(
                 &extension_dir,
@@ -728,31 +811,53 @@ impl ExtensionStore {
             )
             .await?;
 
-            let content_length = response
-                .headers()
-                .get(http_client::http::header::CONTENT_LENGTH)
-                .and_then(|value| value.to_str().ok()?.parse::<usize>().ok());
+            let decompressed_bytes = GzipDecoder::new(BufReader::new(tar_gz_bytes.as_slice()));
+            let archive = Archive::new(decompressed_bytes);
+            archive.unpack(&extension_dir).await?;
 
-            let mut body = BufReader::new(response.body_mut());
-            let mut tar_gz_bytes = Vec::new();
-            body.read_to_end(&mut tar_gz_bytes).await?;
+            // Install any missing dependencies before loading the extension, so
+            // that they are registered by the time the extension is.
+            let manifest = ExtensionManifest::load(fs.clone(), &extension_dir).await?;
+            let dependency_installs = this.update(cx, |this, cx| {
+                this.missing_dependencies(&manifest)
+                    .into_iter()
+                    .map(|dependency_id| {
+                        log::info!("installing {dependency_id}, required by {extension_id}");
+                        this.install_latest_extension_version(dependency_id, cx)
+                    })
+                    .collect::<Vec<_>>()
+            })?;
+            for result in futures::future::join_all(dependency_installs).await {
+                result.context("installing extension dependency")?;
+            }
 
-            if let Some(content_length) = content_length {
-                let actual_len = tar_gz_bytes.len();
-                if content_length != actual_len {
-                    bail!("downloaded extension size {actual_len} does not match content length {content_length}");
-                }
+            this.update(cx, |this, cx| this.reload(Some(extension_id.clone()), cx))?
+                .await;
+
+            let capabilities_changed = match (&previous_capabilities, &new_capabilities) {
+                (Some(previous), Some((_, new))) => previous != new,
+                _ => false,
+            };
+            if capabilities_changed {
+                this.update(cx, |this, cx| {
+                    if let Some(events) = ExtensionEvents::try_global(cx) {
+                        if let Some(manifest) = this.extension_manifest_for_id(&extension_id) {
+                            events.update(cx, |this, cx| {
+                                this.emit(
+                                    extension::Event::ExtensionCapabilitiesChanged(
+                                        manifest.clone(),
+                                    ),
+                                    cx,
+                                )
+                            });
+                        }
+                    }
+                })
+                .ok();
             }
-            let decompressed_bytes = GzipDecoder::new(BufReader::new(tar_gz_bytes.as_slice()));
-            let archive = Archive::new(decompressed_bytes);
-            archive.unpack(extension_dir).await?;
-            this.update( cx, |this, cx| {
-                this.reload(Some(extension_id.clone()), cx)
-            })?
-            .await;
 
             if let ExtensionOperation::Install = operation {
-                this.update( cx, |this, cx| {
+                this.update(cx, |this, cx| {
                     cx.emit(Event::ExtensionInstalled(extension_id.clone()));
                     if let Some(events) = ExtensionEvents::try_global(cx) {
                         if let Some(manifest) = this.extension_manifest_for_id(&extension_id) {
@@ -772,7 +877,51 @@ impl ExtensionStore {
         })
     }
 
+    /// Asks the user to grant the capabilities in `capabilities` that aren't in `granted`.
+    ///
+    /// Returns `None` when there's nothing new to grant. The request is declined if nobody
+    /// responds to it.
+    fn request_capability_approval(
+        &mut self,
+        extension_id: Arc<str>,
+        extension_name: String,
+        operation: ExtensionOperation,
+        capabilities: &[ExtensionCapability],
+        granted: &[ExtensionCapability],
+        cx: &mut Context<Self>,
+    ) -> Option<impl Future<Output = bool> + use<>> {
+        let capabilities = capabilities
+            .iter()
+            .filter(|capability| !granted.contains(capability))
+            .cloned()
+            .collect::<Vec<_>>();
+        if capabilities.is_empty() {
+            return None;
+        }
+
+        let (response_tx, mut response_rx) = unbounded();
+        cx.emit(Event::CapabilityApprovalRequested(
+            CapabilityApprovalRequest {
+                extension_id,
+                extension_name,
+                operation,
+                capabilities,
+                response_tx,
+            },
+        ));
+        Some(async move { response_rx.next().await.unwrap_or(false) })
+    }
+
     pub fn install_latest_extension(&mut self, extension_id: Arc<str>, cx: &mut Context<Self>) {
+        self.install_latest_extension_version(extension_id, cx)
+            .detach_and_log_err(cx);
+    }
+
+    fn install_latest_extension_version(
+        &mut self,
+        extension_id: Arc<str>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
         log::info!("installing extension {extension_id} latest version");
 
         let schema_versions = schema_version_range();
@@ -794,7 +943,7 @@ impl ExtensionStore {
             )
             .log_err()
         else {
-            return;
+            return Task::ready(Ok(()));
         };
 
         self.install_or_upgrade_extension_at_endpoint(
@@ -803,7 +952,36 @@ impl ExtensionStore {
             ExtensionOperation::Install,
             cx,
         )
-        .detach_and_log_err(cx);
+    }
+
+    /// Returns the dependencies of the given extension that are neither installed
+    /// nor currently being installed.
+    fn missing_dependencies(&self, manifest: &ExtensionManifest) -> Vec<Arc<str>> {
+        manifest
+            .dependencies
+            .iter()
+            .filter(|dependency_id| {
+                !self.extension_index.extensions.contains_key(*dependency_id)
+                    && !self.outstanding_operations.contains_key(*dependency_id)
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the IDs of the installed extensions that depend on the given extension.
+    pub fn dependents_of(&self, extension_id: &str) -> Vec<Arc<str>> {
+        self.extension_index
+            .extensions
+            .iter()
+            .filter(|(_, entry)| {
+                entry
+                    .manifest
+                    .dependencies
+                    .iter()
+                    .any(|dependency_id| dependency_id.as_ref() == extension_id)
+            })
+            .map(|(id, _)| id.clone())
+            .collect()
     }
 
     pub fn upgrade_extension(
@@ -842,6 +1020,15 @@ impl ExtensionStore {
         let work_dir = self.wasm_host.work_dir.join(extension_id.as_ref());
         let fs = self.fs.clone();
 
+        let dependents = self.dependents_of(&extension_id);
+        if !dependents.is_empty() {
+            log::error!(
+                "cannot uninstall extension {extension_id}: required by {}",
+                dependents.join(", ")
+            );
+            return;
+        }
+
         match self.outstanding_operations.entry(extension_id.clone()) {
             btree_map::Entry::Occupied(_) => return,
             btree_map::Entry::Vacant(e) => e.insert(ExtensionOperation::Remove),
@@ -1019,6 +1206,23 @@ impl ExtensionStore {
         mut new_index: ExtensionIndex,
         cx: &mut Context<Self>,
     ) -> Task<()> {
+        for extension_id in &self.modified_extensions {
+            self.crashed_extensions.remove(extension_id);
+        }
+        if !self.crashed_extensions.is_empty() {
+            let crashed = &self.crashed_extensions;
+            new_index.extensions.retain(|id, _| !crashed.contains(id));
+            new_index
+                .themes
+                .retain(|_, entry| !crashed.contains(&entry.extension));
+            new_index
+                .icon_themes
+                .retain(|_, entry| !crashed.contains(&entry.extension));
+            new_index
+                .languages
+                .retain(|_, entry| !crashed.contains(&entry.extension));
+        }
+
         let old_index = &self.extension_index;
 
         // Determine which extensions need to be loaded and unloaded, based
@@ -1060,6 +1264,11 @@ impl ExtensionStore {
             self.modified_extensions.clear();
         }
 
+        // Load dependencies before their dependents, and unload them after.
+        sort_by_dependencies(&mut extensions_to_load, &new_index);
+        sort_by_dependencies(&mut extensions_to_unload, old_index);
+        extensions_to_unload.reverse();
+
         if extensions_to_load.is_empty() && extensions_to_unload.is_empty() {
             return Task::ready(());
         }
@@ -1125,24 +1334,85 @@ impl ExtensionStore {
                 continue;
             };
             grammars_to_remove.extend(extension.manifest.grammars.keys().cloned());
+            let event_log = self.proxy.event_log();
             for (language_server_name, config) in extension.manifest.language_servers.iter() {
                 for language in config.languages() {
-                    self.proxy
-                        .remove_language_server(&language, language_server_name);
+                    event_log.record(
+                        ExtensionEventCategory::LanguageServer,
+                        Some(extension_id.clone()),
+                        format!("remove language server {language_server_name} for {language}"),
+                        || {
+                            self.proxy
+                                .remove_language_server(&language, language_server_name)
+                        },
+                    );
                 }
             }
 
+            for (source_id, _) in extension.manifest.context_picker_sources.iter() {
+                event_log.record(
+                    ExtensionEventCategory::ContextPickerSource,
+                    Some(extension_id.clone()),
+                    format!("unregister context picker source {source_id}"),
+                    || {
+                        self.proxy
+                            .unregister_context_picker_source(source_id.clone())
+                    },
+                );
+            }
+
+            for (provider_id, _) in extension.manifest.git_hosting_providers.iter() {
+                event_log.record(
+                    ExtensionEventCategory::GitHostingProvider,
+                    Some(extension_id.clone()),
+                    format!("unregister git hosting provider {provider_id}"),
+                    || {
+                        self.proxy.unregister_git_hosting_provider(
+                            extension_id.clone(),
+                            provider_id.clone(),
+                        )
+                    },
+                );
+            }
+
             for (server_id, _) in extension.manifest.context_servers.iter() {
-                self.proxy.unregister_context_server(server_id.clone(), cx);
+                event_log.record(
+                    ExtensionEventCategory::ContextServer,
+                    Some(extension_id.clone()),
+                    format!("unregister context server {server_id}"),
+                    || self.proxy.unregister_context_server(server_id.clone(), cx),
+                );
             }
         }
 
         self.wasm_extensions
             .retain(|(extension, _)| !extensions_to_unload.contains(&extension.id));
-        self.proxy.remove_user_themes(themes_to_remove);
-        self.proxy.remove_icon_themes(icon_themes_to_remove);
-        self.proxy
-            .remove_languages(&languages_to_remove, &grammars_to_remove);
+        let event_log = self.proxy.event_log();
+        event_log.record(
+            ExtensionEventCategory::Theme,
+            None,
+            format!("remove {} themes", themes_to_remove.len()),
+            || self.proxy.remove_user_themes(themes_to_remove),
+        );
+        event_log.record(
+            ExtensionEventCategory::IconTheme,
+            None,
+            format!("remove {} icon themes", icon_themes_to_remove.len()),
+            || self.proxy.remove_icon_themes(icon_themes_to_remove),
+        );
+        event_log.record(
+            ExtensionEventCategory::Language,
+            None,
+            format!(
+                "remove {} languages and {} grammars",
+                languages_to_remove.len(),
+                grammars_to_remove.len()
+            ),
+            || {
+                self.proxy
+                    .remove_languages(&languages_to_remove, &grammars_to_remove)
+            },
+        );
 
         let mut grammars_to_add = Vec::new();
         let mut themes_to_add = Vec::new();
@@ -1163,7 +1433,7 @@ impl ExtensionStore {
             themes_to_add.extend(extension.manifest.themes.iter().map(|theme_path| {
                 let mut path = self.installed_dir.clone();
                 path.extend([Path::new(extension_id.as_ref()), theme_path.as_path()]);
-                path
+                (extension_id.clone(), path)
             }));
             icon_themes_to_add.extend(extension.manifest.icon_themes.iter().map(
                 |icon_theme_path| {
@@ -1173,17 +1443,22 @@ impl ExtensionStore {
                     let mut icons_root_path = self.installed_dir.clone();
                     icons_root_path.extend([Path::new(extension_id.as_ref())]);
 
-                    (path, icons_root_path)
+                    (extension_id.clone(), path, icons_root_path)
                 },
             ));
             snippets_to_add.extend(extension.manifest.snippets.iter().map(|snippets_path| {
                 let mut path = self.installed_dir.clone();
                 path.extend([Path::new(extension_id.as_ref()), snippets_path.as_path()]);
-                path
+                (extension_id.clone(), path)
             }));
         }
 
-        self.proxy.register_grammars(grammars_to_add);
+        self.proxy.event_log().record(
+            ExtensionEventCategory::Grammar,
+            None,
+            format!("register {} grammars", grammars_to_add.len()),
+            || self.proxy.register_grammars(grammars_to_add),
+        );
         let languages_to_add = new_index
             .languages
             .iter_mut()
@@ -1195,31 +1470,40 @@ impl ExtensionStore {
                 Path::new(language.extension.as_ref()),
                 language.path.as_path(),
             ]);
-            self.proxy.register_language(
-                language_name.clone(),
-                language.grammar.clone(),
-                language.matcher.clone(),
-                language.hidden,
-                Arc::new(move || {
-                    let config = std::fs::read_to_string(language_path.join("config.toml"))?;
-                    let config: LanguageConfig = ::toml::from_str(&config)?;
-                    let queries = load_plugin_queries(&language_path);
-                    let context_provider =
-                        std::fs::read_to_string(language_path.join("tasks.json"))
-                            .ok()
-                            .and_then(|contents| {
-                                let definitions =
-                                    serde_json_lenient::from_str(&contents).log_err()?;
-                                Some(Arc::new(ContextProviderWithTasks::new(definitions)) as Arc<_>)
-                            });
-
-                    Ok(LoadedLanguage {
-                        config,
-                        queries,
-                        context_provider,
-                        toolchain_provider: None,
-                    })
-                }),
+            let register_language = || {
+                self.proxy.register_language(
+                    language_name.clone(),
+                    language.grammar.clone(),
+                    language.matcher.clone(),
+                    language.hidden,
+                    Arc::new(move || {
+                        let config = std::fs::read_to_string(language_path.join("config.toml"))?;
+                        let config: LanguageConfig = ::toml::from_str(&config)?;
+                        let queries = load_plugin_queries(&language_path);
+                        let context_provider =
+                            std::fs::read_to_string(language_path.join("tasks.json"))
+                                .ok()
+                                .and_then(|contents| {
+                                    let definitions =
+                                        serde_json_lenient::from_str(&contents).log_err()?;
+                                    Some(Arc::new(ContextProviderWithTasks::new(definitions))
+                                        as Arc<_>)
+                                });
+
+                        Ok(LoadedLanguage {
+                            config,
+                            queries,
+                            context_provider,
+                            toolchain_provider: None,
+                        })
+                    }),
+                )
+            };
+            self.proxy.event_log().record(
+                ExtensionEventCategory::Language,
+                Some(language.extension.clone()),
+                format!("register language {language_name}"),
+                register_language,
             );
         }
 
@@ -1239,25 +1523,63 @@ impl ExtensionStore {
             cx.background_spawn({
                 let fs = fs.clone();
                 async move {
-                    for theme_path in themes_to_add.into_iter() {
-                        proxy
-                            .load_user_theme(theme_path, fs.clone())
+                    let event_log = proxy.event_log();
+                    for (extension_id, theme_path) in themes_to_add.into_iter() {
+                        event_log
+                            .record_async(
+                                ExtensionEventCategory::Theme,
+                                Some(extension_id),
+                                format!("load theme {}", theme_path.display()),
+                                proxy.load_user_theme(theme_path.clone(), fs.clone()),
+                            )
                             .await
-                            .log_err();
+                            .ok();
                     }
 
-                    for (icon_theme_path, icons_root_path) in icon_themes_to_add.into_iter() {
-                        proxy
-                            .load_icon_theme(icon_theme_path, icons_root_path, fs.clone())
+                    for (extension_id, icon_theme_path, icons_root_path) in
+                        icon_themes_to_add.into_iter()
+                    {
+                        event_log
+                            .record_async(
+                                ExtensionEventCategory::IconTheme,
+                                Some(extension_id),
+                                format!("load icon theme {}", icon_theme_path.display()),
+                                proxy.load_icon_theme(
+                                    icon_theme_path.clone(),
+                                    icons_root_path,
+                                    fs.clone(),
+                                ),
+                            )
                             .await
-                            .log_err();
+                            .ok();
                     }
 
-                    for snippets_path in &snippets_to_add {
-                        if let Some(snippets_contents) = fs.load(snippets_path).await.log_err() {
-                            proxy
-                                .register_snippet(snippets_path, &snippets_contents)
-                                .log_err();
+                    for (extension_id, snippets_path) in &snippets_to_add {
+                        let action = format!("register snippets {}", snippets_path.display());
+                        match fs.load(snippets_path).await {
+                            Ok(snippets_contents) => {
+                                event_log
+                                    .record_result(
+                                        ExtensionEventCategory::Snippet,
+                                        Some(extension_id.clone()),
+                                        action,
+                                        || {
+                                            proxy
+                                                .register_snippet(snippets_path, &snippets_contents)
+                                        },
+                                    )
+                                    .ok();
+                            }
+                            Err(error) => {
+                                event_log
+                                    .record_result(
+                                        ExtensionEventCategory::Snippet,
+                                        Some(extension_id.clone()),
+                                        action,
+                                        || Err::<(), _>(error),
+                                    )
+                                    .ok();
+                            }
                         }
                     }
                 }
@@ -1292,47 +1614,118 @@ impl ExtensionStore {
             this.update(cx, |this, cx| {
                 this.reload_complete_senders.clear();
 
+                let proxy = this.proxy.clone();
+                let event_log = proxy.event_log();
                 for (manifest, wasm_extension) in &wasm_extensions {
                     let extension = Arc::new(wasm_extension.clone());
+                    let extension_id = Some(manifest.id.clone());
 
                     for (language_server_id, language_server_config) in &manifest.language_servers {
                         for language in language_server_config.languages() {
-                            this.proxy.register_language_server(
-                                extension.clone(),
-                                language_server_id.clone(),
-                                language.clone(),
+                            event_log.record(
+                                ExtensionEventCategory::LanguageServer,
+                                extension_id.clone(),
+                                format!(
+                                    "register language server {language_server_id} for {language}"
+                                ),
+                                || {
+                                    proxy.register_language_server(
+                                        extension.clone(),
+                                        language_server_id.clone(),
+                                        language.clone(),
+                                    )
+                                },
                             );
                         }
                     }
 
                     for (slash_command_name, slash_command) in &manifest.slash_commands {
-                        this.proxy.register_slash_command(
-                            extension.clone(),
-                            extension::SlashCommand {
-                                name: slash_command_name.to_string(),
-                                description: slash_command.description.to_string(),
-                                // We don't currently expose this as a configurable option, as it currently drives
-                                // the `menu_text` on the `SlashCommand` trait, which is not used for slash commands
-                                // defined in extensions, as they are not able to be added to the menu.
-                                tooltip_text: String::new(),
-                                requires_argument: slash_command.requires_argument,
+                        event_log.record(
+                            ExtensionEventCategory::SlashCommand,
+                            extension_id.clone(),
+                            format!("register slash command /{slash_command_name}"),
+                            || {
+                                proxy.register_slash_command(
+                                    extension.clone(),
+                                    extension::SlashCommand {
+                                        name: slash_command_name.to_string(),
+                                        description: slash_command.description.to_string(),
+                                        // We don't currently expose this as a configurable option, as it currently drives
+                                        // the `menu_text` on the `SlashCommand` trait, which is not used for slash commands
+                                        // defined in extensions, as they are not able to be added to the menu.
+                                        tooltip_text: String::new(),
+                                        requires_argument: slash_command.requires_argument,
+                                    },
+                                )
+                            },
+                        );
+                    }
+
+                    for (source_id, source) in &manifest.context_picker_sources {
+                        event_log.record(
+                            ExtensionEventCategory::ContextPickerSource,
+                            extension_id.clone(),
+                            format!("register context picker source {source_id}"),
+                            || {
+                                proxy.register_context_picker_source(
+                                    extension.clone(),
+                                    source_id.clone(),
+                                    source.name.clone().into(),
+                                )
+                            },
+                        );
+                    }
+
+                    for (provider_id, provider) in &manifest.git_hosting_providers {
+                        event_log.record(
+                            ExtensionEventCategory::GitHostingProvider,
+                            extension_id.clone(),
+                            format!("register git hosting provider {provider_id}"),
+                            || {
+                                proxy.register_git_hosting_provider(
+                                    extension.clone(),
+                                    provider_id.clone(),
+                                    provider.clone(),
+                                )
                             },
                         );
                     }
 
                     for (id, _context_server_entry) in &manifest.context_servers {
-                        this.proxy
-                            .register_context_server(extension.clone(), id.clone(), cx);
+                        event_log.record(
+                            ExtensionEventCategory::ContextServer,
+                            extension_id.clone(),
+                            format!("register context server {id}"),
+                            || proxy.register_context_server(extension.clone(), id.clone(), cx),
+                        );
                     }
 
                     for (provider_id, _provider) in &manifest.indexed_docs_providers {
-                        this.proxy
-                            .register_indexed_docs_provider(extension.clone(), provider_id.clone());
+                        event_log.record(
+                            ExtensionEventCategory::IndexedDocsProvider,
+                            extension_id.clone(),
+                            format!("register indexed docs provider {provider_id}"),
+                            || {
+                                proxy.register_indexed_docs_provider(
+                                    extension.clone(),
+                                    provider_id.clone(),
+                                )
+                            },
+                        );
                     }
 
                     for debug_adapter in &manifest.debug_adapters {
-                        this.proxy
-                            .register_debug_adapter(extension.clone(), debug_adapter.clone());
+                        event_log.record(
+                            ExtensionEventCategory::DebugAdapter,
+                            extension_id.clone(),
+                            format!("register debug adapter {debug_adapter}"),
+                            || {
+                                proxy.register_debug_adapter(
+                                    extension.clone(),
+                                    debug_adapter.clone(),
+                                )
+                            },
+                        );
                     }
                 }
 
@@ -1720,3 +2113,57 @@ fn load_plugin_queries(root_path: &Path) -> LanguageQueries {
     }
     result
 }
+
+/// Orders the given extensions so that each one comes after the extensions it
+/// depends on. Dependency cycles are broken arbitrarily.
+fn sort_by_dependencies(extension_ids: &mut Vec<Arc<str>>, index: &ExtensionIndex) {
+    fn visit(
+        extension_id: &Arc<str>,
+        extension_ids: &HashSet<Arc<str>>,
+        index: &ExtensionIndex,
+        visited: &mut HashSet<Arc<str>>,
+        sorted: &mut Vec<Arc<str>>,
+    ) {
+        if !visited.insert(extension_id.clone()) {
+            return;
+        }
+        if let Some(entry) = index.extensions.get(extension_id) {
+            for dependency_id in &entry.manifest.dependencies {
+                if extension_ids.contains(dependency_id) {
+                    visit(dependency_id, extension_ids, index, visited, sorted);
+                }
+            }
+        }
+        sorted.push(extension_id.clone());
+    }
+
+    let ids = extension_ids.iter().cloned().collect::<HashSet<_>>();
+    let mut visited = HashSet::default();
+    let mut sorted = Vec::with_capacity(extension_ids.len());
+    for extension_id in extension_ids.iter() {
+        visit(extension_id, &ids, index, &mut visited, &mut sorted);
+    }
+    *extension_ids = sorted;
+}
+
+/// Reads the manifest out of a packaged extension without unpacking it.
+///
+/// Returns `None` for extensions packaged with a legacy `extension.json` manifest, which predates
+/// capabilities.
+async fn manifest_from_archive(tar_gz_bytes: &[u8]) -> Result<Option<ExtensionManifest>> {
+    let archive = Archive::new(GzipDecoder::new(BufReader::new(tar_gz_bytes)));
+    let mut entries = archive.entries()?;
+    while let Some(entry) = entries.next().await {
+        let mut entry = entry?;
+        let path = entry.path()?;
+        if path.strip_prefix(".").unwrap_or(&path) != Path::new("extension.toml") {
+            continue;
+        }
+
+        let mut contents = String::new();
+        entry.read_to_string(&mut contents).await?;
+        let manifest = toml::from_str(&contents).context("invalid extension.toml")?;
+        return Ok(Some(manifest));
+    }
+    Ok(None)
+}
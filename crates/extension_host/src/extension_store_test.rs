@@ -1,11 +1,13 @@
+use crate::wasm_host::ExtensionCrash;
 use crate::{
     Event, ExtensionIndex, ExtensionIndexEntry, ExtensionIndexLanguageEntry,
-    ExtensionIndexThemeEntry, ExtensionManifest, ExtensionSettings, ExtensionStore,
-    GrammarManifestEntry, RELOAD_DEBOUNCE_DURATION, SchemaVersion,
+    ExtensionIndexThemeEntry, ExtensionManifest, ExtensionOperation, ExtensionSettings,
+    ExtensionStore, GrammarManifestEntry, RELOAD_DEBOUNCE_DURATION, SchemaVersion,
+    sort_by_dependencies,
 };
 use async_compression::futures::bufread::GzipEncoder;
 use collections::BTreeMap;
-use extension::ExtensionHostProxy;
+use extension::{ExtensionCapability, ExtensionHostProxy};
 use fs::{FakeFs, Fs, RealFs};
 use futures::{AsyncReadExt, StreamExt, io::BufReader};
 use gpui::{AppContext as _, SemanticVersion, SharedString, TestAppContext};
@@ -159,10 +161,13 @@ async fn test_extension_store(cx: &mut TestAppContext) {
                         language_servers: BTreeMap::default(),
                         context_servers: BTreeMap::default(),
                         slash_commands: BTreeMap::default(),
+                        context_picker_sources: BTreeMap::default(),
+                        git_hosting_providers: BTreeMap::default(),
                         indexed_docs_providers: BTreeMap::default(),
                         snippets: None,
                         capabilities: Vec::new(),
                         debug_adapters: Default::default(),
+                        dependencies: Default::default(),
                     }),
                     dev: false,
                 },
@@ -189,10 +194,13 @@ async fn test_extension_store(cx: &mut TestAppContext) {
                         language_servers: BTreeMap::default(),
                         context_servers: BTreeMap::default(),
                         slash_commands: BTreeMap::default(),
+                        context_picker_sources: BTreeMap::default(),
+                        git_hosting_providers: BTreeMap::default(),
                         indexed_docs_providers: BTreeMap::default(),
                         snippets: None,
                         capabilities: Vec::new(),
                         debug_adapters: Default::default(),
+                        dependencies: Default::default(),
                     }),
                     dev: false,
                 },
@@ -364,10 +372,13 @@ async fn test_extension_store(cx: &mut TestAppContext) {
                 language_servers: BTreeMap::default(),
                 context_servers: BTreeMap::default(),
                 slash_commands: BTreeMap::default(),
+                context_picker_sources: BTreeMap::default(),
+                git_hosting_providers: BTreeMap::default(),
                 indexed_docs_providers: BTreeMap::default(),
                 snippets: None,
                 capabilities: Vec::new(),
                 debug_adapters: Default::default(),
+                dependencies: Default::default(),
             }),
             dev: false,
         },
@@ -510,6 +521,36 @@ async fn test_extension_store(cx: &mut TestAppContext) {
         assert_eq!(language_registry.language_names(), ["Plain Text"]);
         assert_eq!(language_registry.grammar_names(), []);
     });
+
+    // A crashed extension is unloaded along with everything it provides.
+    store.update(cx, |store, cx| {
+        store.extension_crashed(
+            ExtensionCrash {
+                extension_id: "zed-gruvbox".into(),
+                reason: "wasm trap: unreachable".into(),
+            },
+            cx,
+        )
+    });
+
+    cx.executor().advance_clock(RELOAD_DEBOUNCE_DURATION);
+    expected_index.extensions.remove("zed-gruvbox");
+    expected_index.themes.remove("Gruvbox");
+
+    store.read_with(cx, |store, _| {
+        assert_eq!(store.extension_index.extensions, expected_index.extensions);
+        assert_eq!(store.extension_index.themes, expected_index.themes);
+        assert_eq!(
+            theme_registry.list_names(),
+            [
+                "Monokai Dark",
+                "Monokai Light",
+                "Monokai Pro Dark",
+                "Monokai Pro Light",
+                "One Dark",
+            ]
+        );
+    });
 }
 
 // todo(windows)
@@ -821,6 +862,121 @@ async fn test_extension_store_with_test_extension(cx: &mut TestAppContext) {
     assert!(fs.metadata(&expected_server_path).await.unwrap().is_none());
 }
 
+#[test]
+fn test_sort_by_dependencies() {
+    let mut index = ExtensionIndex::default();
+    for (id, dependencies) in [
+        ("icon-pack", vec![]),
+        ("theme-pack", vec!["icon-pack", "zed-base"]),
+        ("zed-base", vec!["theme-pack"]),
+        ("unrelated", vec![]),
+    ] {
+        let manifest: ExtensionManifest = toml::from_str(&format!(
+            "id = \"{id}\"\nname = \"{id}\"\nversion = \"1.0.0\"\nschema_version = 1\ndependencies = {dependencies:?}"
+        ))
+        .unwrap();
+        index.extensions.insert(
+            id.into(),
+            ExtensionIndexEntry {
+                manifest: Arc::new(manifest),
+                dev: false,
+            },
+        );
+    }
+
+    let mut extension_ids: Vec<Arc<str>> =
+        vec!["theme-pack".into(), "unrelated".into(), "icon-pack".into()];
+    sort_by_dependencies(&mut extension_ids, &index);
+    assert_eq!(
+        extension_ids,
+        ["icon-pack", "theme-pack", "unrelated"].map(Arc::<str>::from)
+    );
+
+    // Cycles don't prevent the extensions from being loaded.
+    let mut extension_ids: Vec<Arc<str>> = vec!["theme-pack".into(), "zed-base".into()];
+    sort_by_dependencies(&mut extension_ids, &index);
+    assert_eq!(extension_ids.len(), 2);
+}
+
+#[gpui::test]
+async fn test_capability_approval(cx: &mut TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/the-extension-dir", json!({ "installed": {} }))
+        .await;
+    let http_client = FakeHttpClient::with_200_response();
+    let store = cx.new(|cx| {
+        ExtensionStore::new(
+            PathBuf::from("/the-extension-dir"),
+            None,
+            Arc::new(ExtensionHostProxy::new()),
+            fs.clone(),
+            http_client.clone(),
+            http_client.clone(),
+            None,
+            NodeRuntime::unavailable(),
+            cx,
+        )
+    });
+
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    cx.update(|cx| {
+        cx.subscribe(&store, {
+            let requests = requests.clone();
+            move |_, event, _| {
+                if let Event::CapabilityApprovalRequested(request) = event {
+                    requests.lock().push(request.clone());
+                }
+            }
+        })
+        .detach();
+    });
+
+    let exec = ExtensionCapability::ProcessExec {
+        command: "cargo".into(),
+        args: vec!["**".into()],
+    };
+    let network = ExtensionCapability::NetworkRequest {
+        host: "example.com".into(),
+    };
+    let request_approval = |capabilities: Vec<ExtensionCapability>,
+                            granted: Vec<ExtensionCapability>,
+                            cx: &mut TestAppContext| {
+        store.update(cx, |store, cx| {
+            store.request_capability_approval(
+                "the-extension".into(),
+                "The Extension".into(),
+                ExtensionOperation::Upgrade,
+                &capabilities,
+                &granted,
+                cx,
+            )
+        })
+    };
+
+    // Capabilities that were already granted don't need to be approved again.
+    assert!(request_approval(vec![exec.clone()], vec![exec.clone()], cx).is_none());
+    assert!(requests.lock().is_empty());
+
+    // Only the new capabilities are presented to the user.
+    let approval =
+        request_approval(vec![exec.clone(), network.clone()], vec![exec.clone()], cx).unwrap();
+    let request = requests.lock().pop().unwrap();
+    assert_eq!(request.capabilities, vec![network.clone()]);
+    request.respond(true);
+    assert!(approval.await);
+
+    let approval = request_approval(vec![network.clone()], Vec::new(), cx).unwrap();
+    requests.lock().pop().unwrap().respond(false);
+    assert!(!approval.await);
+
+    // A request that nobody answers is declined.
+    let approval = request_approval(vec![network.clone()], Vec::new(), cx).unwrap();
+    drop(requests.lock().pop());
+    assert!(!approval.await);
+}
+
 fn init_test(cx: &mut TestAppContext) {
     cx.update(|cx| {
         let store = SettingsStore::test(cx);
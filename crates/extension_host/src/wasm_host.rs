@@ -4,9 +4,10 @@ use crate::ExtensionManifest;
 use anyhow::{Context as _, Result, anyhow, bail};
 use async_trait::async_trait;
 use extension::{
-    CodeLabel, Command, Completion, ContextServerConfiguration, DebugAdapterBinary,
-    DebugTaskDefinition, ExtensionHostProxy, KeyValueStoreDelegate, ProjectDelegate, SlashCommand,
-    SlashCommandArgumentCompletion, SlashCommandOutput, Symbol, WorktreeDelegate,
+    CodeLabel, Command, Completion, ContextPickerEntry, ContextServerConfiguration,
+    DebugAdapterBinary, DebugTaskDefinition, ExtensionHostProxy, GitBuildStatus,
+    KeyValueStoreDelegate, ProjectDelegate, SlashCommand, SlashCommandArgumentCompletion,
+    SlashCommandOutput, Symbol, WorktreeDelegate,
 };
 use fs::{Fs, normalize_path};
 use futures::future::LocalBoxFuture;
@@ -24,21 +25,33 @@ use language::LanguageName;
 use lsp::LanguageServerName;
 use moka::sync::Cache;
 use node_runtime::NodeRuntime;
+use parking_lot::Mutex;
 use release_channel::ReleaseChannel;
 use semantic_version::SemanticVersion;
 use std::borrow::Cow;
 use std::sync::LazyLock;
+use std::time::Duration;
 use std::{
+    fmt,
     path::{Path, PathBuf},
     sync::Arc,
 };
+use util::ResultExt as _;
 use wasmtime::{
-    CacheStore, Engine, Store,
+    CacheStore, Engine, ResourceLimiter, Store, Trap, UpdateDeadline,
     component::{Component, ResourceTable},
 };
 use wasmtime_wasi::{self as wasi, WasiView};
 use wit::Extension;
 
+/// The most linear memory a single extension may allocate.
+const MAX_EXTENSION_MEMORY: usize = 512 * 1024 * 1024;
+/// How often the engine's epoch advances, which is how often running extension code is
+/// interrupted to check its CPU time and let other extensions run.
+const EPOCH_INTERVAL: Duration = Duration::from_millis(100);
+/// How many epochs a single call into an extension may spend running before it's stopped.
+const MAX_CALL_EPOCHS: u64 = 600;
+
 pub struct WasmHost {
     engine: Engine,
     release_channel: ReleaseChannel,
@@ -49,8 +62,42 @@ pub struct WasmHost {
     pub work_dir: PathBuf,
     _main_thread_message_task: Task<()>,
     main_thread_message_tx: mpsc::UnboundedSender<MainThreadCall>,
+    crash_listeners: Mutex<Vec<UnboundedSender<ExtensionCrash>>>,
+}
+
+/// Reported when an extension traps or exceeds its resource limits, after which its instance
+/// can no longer be called.
+#[derive(Clone, Debug)]
+pub struct ExtensionCrash {
+    pub extension_id: Arc<str>,
+    pub reason: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ResourceLimitExceeded {
+    Memory,
+    CpuTime,
+}
+
+impl fmt::Display for ResourceLimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Memory => write!(
+                f,
+                "extension exceeded its memory limit of {} MiB",
+                MAX_EXTENSION_MEMORY / 1024 / 1024
+            ),
+            Self::CpuTime => write!(
+                f,
+                "extension ran for longer than {:?} in a single call",
+                EPOCH_INTERVAL * MAX_CALL_EPOCHS as u32
+            ),
+        }
+    }
 }
 
+impl std::error::Error for ResourceLimitExceeded {}
+
 #[derive(Clone)]
 pub struct WasmExtension {
     tx: UnboundedSender<ExtensionCall>,
@@ -337,6 +384,65 @@ impl extension::Extension for WasmExtension {
         .await
     }
 
+    async fn context_picker_entries(
+        &self,
+        source_id: Arc<str>,
+        query: String,
+    ) -> Result<Vec<ContextPickerEntry>> {
+        self.call(|extension, store| {
+            async move {
+                let entries = extension
+                    .call_context_picker_entries(store, &source_id, &query)
+                    .await?
+                    .map_err(|err| anyhow!("{err:?}"))?;
+
+                Ok(entries.into_iter().map(Into::into).collect())
+            }
+            .boxed()
+        })
+        .await
+    }
+
+    async fn context_picker_entry_content(
+        &self,
+        source_id: Arc<str>,
+        entry_id: String,
+    ) -> Result<String> {
+        self.call(|extension, store| {
+            async move {
+                let content = extension
+                    .call_context_picker_entry_content(store, &source_id, &entry_id)
+                    .await?
+                    .map_err(|err| anyhow!("{err:?}"))?;
+
+                Ok(content)
+            }
+            .boxed()
+        })
+        .await
+    }
+
+    async fn git_hosting_build_status(
+        &self,
+        provider_id: Arc<str>,
+        owner: String,
+        repo: String,
+        branch: String,
+    ) -> Result<Option<GitBuildStatus>> {
+        self.call(|extension, store| {
+            async move {
+                let status = extension
+                    .call_git_hosting_build_status(store, &provider_id, &owner, &repo, &branch)
+                    .await?
+                    .map_err(|err| anyhow!("{err:?}"))?;
+
+                Ok(status.map(Into::into))
+            }
+            .boxed()
+        })
+        .await
+    }
+
     async fn suggest_docs_packages(&self, provider: Arc<str>) -> Result<Vec<String>> {
         self.call(|extension, store| {
             async move {
@@ -416,9 +522,12 @@ impl extension::Extension for WasmExtension {
 
 pub struct WasmState {
     manifest: Arc<ExtensionManifest>,
+    zed_api_version: SemanticVersion,
     pub table: ResourceTable,
     ctx: wasi::WasiCtx,
     pub host: Arc<WasmHost>,
+    call_epochs: u64,
+    crashed: bool,
 }
 
 type MainThreadCall = Box<dyn Send + for<'a> FnOnce(&'a mut AsyncApp) -> LocalBoxFuture<'a, ()>>;
@@ -432,10 +541,24 @@ fn wasm_engine() -> wasmtime::Engine {
         let mut config = wasmtime::Config::new();
         config.wasm_component_model(true);
         config.async_support(true);
+        config.epoch_interruption(true);
         config
             .enable_incremental_compilation(cache_store())
             .unwrap();
-        wasmtime::Engine::new(&config).unwrap()
+        let engine = wasmtime::Engine::new(&config).unwrap();
+
+        let ticking_engine = engine.clone();
+        std::thread::Builder::new()
+            .name("ExtensionEpochTicker".into())
+            .spawn(move || {
+                loop {
+                    std::thread::sleep(EPOCH_INTERVAL);
+                    ticking_engine.increment_epoch();
+                }
+            })
+            .log_err();
+
+        engine
     });
 
     WASM_ENGINE.clone()
@@ -472,9 +595,28 @@ impl WasmHost {
             release_channel: ReleaseChannel::global(cx),
             _main_thread_message_task: task,
             main_thread_message_tx: tx,
+            crash_listeners: Mutex::default(),
         })
     }
 
+    /// Returns a stream of the extensions that have crashed and been stopped.
+    pub fn crashed_extensions(&self) -> mpsc::UnboundedReceiver<ExtensionCrash> {
+        let (tx, rx) = mpsc::unbounded();
+        self.crash_listeners.lock().push(tx);
+        rx
+    }
+
+    fn report_crash(&self, crash: ExtensionCrash) {
+        log::error!(
+            "extension {} crashed and was stopped: {}",
+            crash.extension_id,
+            crash.reason
+        );
+        self.crash_listeners
+            .lock()
+            .retain(|tx| tx.unbounded_send(crash.clone()).is_ok());
+    }
+
     pub fn load_extension(
         self: &Arc<Self>,
         wasm_bytes: Vec<u8>,
@@ -494,10 +636,23 @@ impl WasmHost {
                 WasmState {
                     ctx: this.build_wasi_ctx(&manifest).await?,
                     manifest: manifest.clone(),
+                    zed_api_version,
                     table: ResourceTable::new(),
                     host: this.clone(),
+                    call_epochs: 0,
+                    crashed: false,
                 },
             );
+            store.limiter(|state| state);
+            store.epoch_deadline_callback(|mut store| {
+                let state = store.data_mut();
+                state.call_epochs += 1;
+                if state.call_epochs > MAX_CALL_EPOCHS {
+                    return Err(ResourceLimitExceeded::CpuTime.into());
+                }
+                Ok(UpdateDeadline::Yield(1))
+            });
+            start_call(&mut store);
 
             let mut extension = Extension::instantiate_async(
                 &mut store,
@@ -634,19 +789,42 @@ impl WasmExtension {
             .with_context(|| format!("failed to load wasm extension {}", manifest.id))
     }
 
-    pub async fn call<T, Fn>(&self, f: Fn) -> T
+    pub async fn call<T, Fn>(&self, f: Fn) -> Result<T>
     where
         T: 'static + Send,
         Fn: 'static
             + Send
-            + for<'a> FnOnce(&'a mut Extension, &'a mut Store<WasmState>) -> BoxFuture<'a, T>,
+            + for<'a> FnOnce(&'a mut Extension, &'a mut Store<WasmState>) -> BoxFuture<'a, Result<T>>,
     {
         let (return_tx, return_rx) = oneshot::channel();
         self.tx
             .clone()
             .unbounded_send(Box::new(move |extension, store| {
                 async {
+                    // A trapped instance is left in an undefined state, so it must not be
+                    // entered again.
+                    if store.data().crashed {
+                        return_tx
+                            .send(Err(anyhow!(
+                                "extension {} has crashed",
+                                store.data().manifest.id
+                            )))
+                            .ok();
+                        return;
+                    }
+
+                    start_call(store);
                     let result = f(extension, store).await;
+                    if let Err(error) = &result {
+                        if is_crash(error) {
+                            let state = store.data_mut();
+                            state.crashed = true;
+                            state.host.report_crash(ExtensionCrash {
+                                extension_id: state.manifest.id.clone(),
+                                reason: format!("{error:#}"),
+                            });
+                        }
+                    }
                     return_tx.send(result).ok();
                 }
                 .boxed()
@@ -656,6 +834,17 @@ impl WasmExtension {
     }
 }
 
+fn start_call(store: &mut Store<WasmState>) {
+    store.data_mut().call_epochs = 0;
+    store.set_epoch_deadline(1);
+}
+
+fn is_crash(error: &anyhow::Error) -> bool {
+    error
+        .chain()
+        .any(|cause| cause.is::<Trap>() || cause.is::<ResourceLimitExceeded>())
+}
+
 impl WasmState {
     fn on_main_thread<T, Fn>(&self, f: Fn) -> impl 'static + Future<Output = T>
     where
@@ -682,6 +871,29 @@ impl WasmState {
     }
 }
 
+impl ResourceLimiter for WasmState {
+    fn memory_growing(
+        &mut self,
+        _current: usize,
+        desired: usize,
+        _maximum: Option<usize>,
+    ) -> Result<bool> {
+        if desired > MAX_EXTENSION_MEMORY {
+            return Err(ResourceLimitExceeded::Memory.into());
+        }
+        Ok(true)
+    }
+
+    fn table_growing(
+        &mut self,
+        _current: usize,
+        _desired: usize,
+        _maximum: Option<usize>,
+    ) -> Result<bool> {
+        Ok(true)
+    }
+}
+
 impl wasi::WasiView for WasmState {
     fn table(&mut self) -> &mut ResourceTable {
         &mut self.table
@@ -26,7 +26,9 @@ use wasmtime::{
 pub use latest::CodeLabelSpanLiteral;
 pub use latest::{
     CodeLabel, CodeLabelSpan, Command, DebugAdapterBinary, ExtensionProject, Range, SlashCommand,
+    zed::extension::context_picker::ContextPickerEntry,
     zed::extension::context_server::ContextServerConfiguration,
+    zed::extension::git_hosting::BuildStatus,
     zed::extension::lsp::{
         Completion, CompletionKind, CompletionLabelDetails, InsertTextFormat, Symbol, SymbolKind,
     },
@@ -841,6 +843,53 @@ impl Extension {
         }
     }
 
+    pub async fn call_context_picker_entries(
+        &self,
+        store: &mut Store<WasmState>,
+        source_id: &str,
+        query: &str,
+    ) -> Result<Result<Vec<ContextPickerEntry>, String>> {
+        match self {
+            Extension::V0_6_0(ext) => {
+                ext.call_context_picker_entries(store, source_id, query)
+                    .await
+            }
+            _ => anyhow::bail!("`context_picker_entries` not available prior to v0.6.0"),
+        }
+    }
+
+    pub async fn call_context_picker_entry_content(
+        &self,
+        store: &mut Store<WasmState>,
+        source_id: &str,
+        entry_id: &str,
+    ) -> Result<Result<String, String>> {
+        match self {
+            Extension::V0_6_0(ext) => {
+                ext.call_context_picker_entry_content(store, source_id, entry_id)
+                    .await
+            }
+            _ => anyhow::bail!("`context_picker_entry_content` not available prior to v0.6.0"),
+        }
+    }
+
+    pub async fn call_git_hosting_build_status(
+        &self,
+        store: &mut Store<WasmState>,
+        provider_id: &str,
+        owner: &str,
+        repo: &str,
+        branch: &str,
+    ) -> Result<Result<Option<BuildStatus>, String>> {
+        match self {
+            Extension::V0_6_0(ext) => {
+                ext.call_git_hosting_build_status(store, provider_id, owner, repo, branch)
+                    .await
+            }
+            _ => anyhow::bail!("`git_hosting_build_status` not available prior to v0.6.0"),
+        }
+    }
+
     pub async fn call_suggest_docs_packages(
         &self,
         store: &mut Store<WasmState>,
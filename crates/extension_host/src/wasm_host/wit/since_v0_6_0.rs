@@ -319,6 +319,29 @@ impl From<SlashCommandArgumentCompletion> for extension::SlashCommandArgumentCom
     }
 }
 
+impl From<ContextPickerEntry> for extension::ContextPickerEntry {
+    fn from(value: ContextPickerEntry) -> Self {
+        Self {
+            id: value.id,
+            label: value.label,
+            description: value.description,
+        }
+    }
+}
+
+impl From<BuildStatus> for extension::GitBuildStatus {
+    fn from(value: BuildStatus) -> Self {
+        Self {
+            state: match value.state {
+                BuildState::Pending => extension::GitBuildState::Pending,
+                BuildState::Success => extension::GitBuildState::Success,
+                BuildState::Failure => extension::GitBuildState::Failure,
+            },
+            url: value.url,
+        }
+    }
+}
+
 impl TryFrom<ContextServerConfiguration> for extension::ContextServerConfiguration {
     type Error = anyhow::Error;
 
@@ -334,6 +357,28 @@ impl TryFrom<ContextServerConfiguration> for extension::ContextServerConfigurati
     }
 }
 
+impl WasmState {
+    /// Extensions built against an API older than this one couldn't declare network or file
+    /// capabilities, so they reach these imports through the older shims unchecked.
+    fn enforces_resource_capabilities(&self) -> bool {
+        self.zed_api_version >= MIN_VERSION
+    }
+
+    fn allow_file_read(&self, path: &str) -> Result<()> {
+        if self.enforces_resource_capabilities() {
+            self.manifest.allow_file_read(path)?;
+        }
+        Ok(())
+    }
+
+    fn allow_network_request(&self, url: &str) -> Result<()> {
+        if self.enforces_resource_capabilities() {
+            self.manifest.allow_network_request(url)?;
+        }
+        Ok(())
+    }
+}
+
 impl HostKeyValueStore for WasmState {
     async fn insert(
         &mut self,
@@ -385,6 +430,9 @@ impl HostWorktree for WasmState {
         delegate: Resource<Arc<dyn WorktreeDelegate>>,
         path: String,
     ) -> wasmtime::Result<Result<String, String>> {
+        if let Err(error) = self.allow_file_read(&path) {
+            return Ok(Err(error.to_string()));
+        }
         let delegate = self.table.get(&delegate)?;
         Ok(delegate
             .read_text_file(path.into())
@@ -424,6 +472,7 @@ impl http_client::Host for WasmState {
     ) -> wasmtime::Result<Result<http_client::HttpResponse, String>> {
         maybe!(async {
             let url = &request.url;
+            self.allow_network_request(url)?;
             let request = convert_request(&request)?;
             let mut response = self.host.http_client.send(request).await?;
 
@@ -440,6 +489,9 @@ impl http_client::Host for WasmState {
         &mut self,
         request: http_client::HttpRequest,
     ) -> wasmtime::Result<Result<Resource<ExtensionHttpResponseStream>, String>> {
+        if let Err(error) = self.allow_network_request(&request.url) {
+            return Ok(Err(error.to_string()));
+        }
         let request = convert_request(&request)?;
         let response = self.host.http_client.send(request);
         maybe!(async {
@@ -697,9 +749,15 @@ impl process::Host for WasmState {
 #[async_trait]
 impl slash_command::Host for WasmState {}
 
+#[async_trait]
+impl context_picker::Host for WasmState {}
+
 #[async_trait]
 impl context_server::Host for WasmState {}
 
+#[async_trait]
+impl git_hosting::Host for WasmState {}
+
 impl dap::Host for WasmState {
     async fn resolve_tcp_template(
         &mut self,
@@ -828,6 +886,7 @@ impl ExtensionImports for WasmState {
         file_type: DownloadedFileType,
     ) -> wasmtime::Result<Result<(), String>> {
         maybe!(async {
+            self.allow_network_request(&url)?;
             let path = PathBuf::from(path);
             let extension_work_dir = self.host.work_dir.join(self.manifest.id.as_ref());
 
@@ -0,0 +1,223 @@
+use std::sync::Arc;
+
+use extension::{ExtensionEventLogEntry, ExtensionHostProxy};
+use futures::StreamExt as _;
+use gpui::{
+    App, EventEmitter, FocusHandle, Focusable, ListSizingBehavior, Task, UniformListScrollHandle,
+    actions, uniform_list,
+};
+use time::OffsetDateTime;
+use time_format::TimestampFormat;
+use ui::{Tooltip, prelude::*};
+use workspace::{Item, Workspace};
+
+actions!(dev, [OpenExtensionEvents]);
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace.register_action(|workspace, _: &OpenExtensionEvents, window, cx| {
+            let existing = workspace
+                .active_pane()
+                .read(cx)
+                .items()
+                .find_map(|item| item.downcast::<ExtensionEventsView>());
+            if let Some(existing) = existing {
+                workspace.activate_item(&existing, true, true, window, cx);
+            } else {
+                let view = cx.new(ExtensionEventsView::new);
+                workspace.add_item_to_active_pane(Box::new(view), None, true, window, cx);
+            }
+        });
+    })
+    .detach();
+}
+
+/// Shows every event the extension host dispatched to its listeners, newest first.
+pub struct ExtensionEventsView {
+    proxy: Arc<ExtensionHostProxy>,
+    entries: Vec<ExtensionEventLogEntry>,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    _log_updates: Task<()>,
+}
+
+impl ExtensionEventsView {
+    fn new(cx: &mut Context<Self>) -> Self {
+        let proxy = ExtensionHostProxy::global(cx);
+        let mut updates = proxy.event_log().subscribe();
+        let _log_updates = cx.spawn(async move |this, cx| {
+            while updates.next().await.is_some() {
+                if this.update(cx, |this, cx| this.refresh(cx)).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let mut this = Self {
+            proxy,
+            entries: Vec::new(),
+            focus_handle: cx.focus_handle(),
+            scroll_handle: UniformListScrollHandle::new(),
+            _log_updates,
+        };
+        this.refresh(cx);
+        this
+    }
+
+    fn refresh(&mut self, cx: &mut Context<Self>) {
+        self.entries = self.proxy.event_log().entries();
+        self.entries.reverse();
+        cx.notify();
+    }
+
+    fn render_entry(
+        &self,
+        entry: &ExtensionEventLogEntry,
+        now: OffsetDateTime,
+    ) -> impl IntoElement {
+        let timestamp = time_format::format_local_timestamp(
+            OffsetDateTime::from(entry.timestamp),
+            now,
+            TimestampFormat::Relative,
+        );
+        let (icon, color) = if entry.error.is_some() {
+            (IconName::XCircle, Color::Error)
+        } else {
+            (IconName::Check, Color::Success)
+        };
+
+        h_flex()
+            .w_full()
+            .gap_2()
+            .px_2()
+            .child(Icon::new(icon).size(IconSize::Small).color(color))
+            .child(
+                div()
+                    .w_32()
+                    .flex_none()
+                    .child(Label::new(entry.category.to_string()).size(LabelSize::Small)),
+            )
+            .child(
+                div().w_40().flex_none().child(
+                    Label::new(
+                        entry
+                            .extension_id
+                            .as_deref()
+                            .map_or_else(|| "—".to_string(), ToString::to_string),
+                    )
+                    .size(LabelSize::Small)
+                    .color(Color::Muted)
+                    .truncate(),
+                ),
+            )
+            .child(
+                div().flex_1().min_w_0().child(
+                    Label::new(match &entry.error {
+                        Some(error) => format!("{}: {error}", entry.action),
+                        None => entry.action.clone(),
+                    })
+                    .size(LabelSize::Small)
+                    .color(if entry.error.is_some() {
+                        Color::Error
+                    } else {
+                        Color::Default
+                    })
+                    .truncate(),
+                ),
+            )
+            .child(
+                Label::new(format!("{:.1?}", entry.duration))
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(
+                Label::new(timestamp)
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+    }
+}
+
+impl Render for ExtensionEventsView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let error_count = self
+            .entries
+            .iter()
+            .filter(|entry| entry.error.is_some())
+            .count();
+
+        v_flex()
+            .size_full()
+            .track_focus(&self.focus_handle)
+            .bg(cx.theme().colors().editor_background)
+            .child(
+                h_flex()
+                    .p_2()
+                    .justify_between()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(Label::new(format!(
+                        "{} events, {error_count} failed",
+                        self.entries.len()
+                    )))
+                    .child(
+                        IconButton::new("clear-extension-events", IconName::Trash)
+                            .icon_size(IconSize::Small)
+                            .tooltip(Tooltip::text("Clear Events"))
+                            .on_click(cx.listener(|this, _, _, _| {
+                                this.proxy.event_log().clear();
+                            })),
+                    ),
+            )
+            .child(if self.entries.is_empty() {
+                h_flex()
+                    .size_full()
+                    .justify_center()
+                    .child(Label::new("No extension events recorded yet.").color(Color::Muted))
+                    .into_any_element()
+            } else {
+                uniform_list(
+                    cx.entity(),
+                    "extension-events",
+                    self.entries.len(),
+                    |this, range, _window, _cx| {
+                        let now = OffsetDateTime::now_utc();
+                        this.entries[range]
+                            .iter()
+                            .map(|entry| this.render_entry(entry, now))
+                            .collect()
+                    },
+                )
+                .with_sizing_behavior(ListSizingBehavior::Infer)
+                .track_scroll(self.scroll_handle.clone())
+                .size_full()
+                .into_any_element()
+            })
+    }
+}
+
+impl EventEmitter<()> for ExtensionEventsView {}
+
+impl Focusable for ExtensionEventsView {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for ExtensionEventsView {
+    type Event = ();
+
+    fn to_item_events(_: &Self::Event, _: impl FnMut(workspace::item::ItemEvent)) {}
+
+    fn tab_content_text(&self, _detail: usize, _cx: &App) -> SharedString {
+        "Extension Events".into()
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        None
+    }
+
+    fn show_toolbar(&self) -> bool {
+        false
+    }
+}
@@ -1,4 +1,5 @@
 mod components;
+mod extension_events_view;
 mod extension_suggest;
 mod extension_version_selector;
 
@@ -9,11 +10,13 @@ use std::{ops::Range, sync::Arc};
 use client::{ExtensionMetadata, ExtensionProvides};
 use collections::{BTreeMap, BTreeSet};
 use editor::{Editor, EditorElement, EditorStyle};
-use extension_host::{ExtensionManifest, ExtensionOperation, ExtensionStore};
+use extension_host::{
+    CapabilityApprovalRequest, ExtensionManifest, ExtensionOperation, ExtensionStore,
+};
 use fuzzy::{StringMatchCandidate, match_strings};
 use gpui::{
-    Action, App, ClipboardItem, Context, Entity, EventEmitter, Flatten, Focusable,
-    InteractiveElement, KeyContext, ParentElement, Render, Styled, Task, TextStyle,
+    Action, AnyWindowHandle, App, ClipboardItem, Context, Entity, EventEmitter, Flatten, Focusable,
+    InteractiveElement, KeyContext, ParentElement, PromptLevel, Render, Styled, Task, TextStyle,
     UniformListScrollHandle, WeakEntity, Window, actions, point, uniform_list,
 };
 use num_format::{Locale, ToFormattedString};
@@ -41,6 +44,8 @@ use crate::extension_version_selector::{
 actions!(zed, [InstallDevExtension]);
 
 pub fn init(cx: &mut App) {
+    extension_events_view::init(cx);
+
     cx.observe_new(move |workspace: &mut Workspace, window, cx| {
         let Some(window) = window else {
             return;
@@ -154,6 +159,20 @@ pub fn init(cx: &mut App) {
                     .detach();
             });
 
+        cx.subscribe_in(
+            &ExtensionStore::global(cx),
+            window,
+            |_, _, event, window, cx| {
+                if let extension_host::Event::CapabilityApprovalRequested(request) = event {
+                    // Every workspace sees the request, but only one of them should ask.
+                    if capability_prompt_window(cx) == Some(window.window_handle()) {
+                        prompt_for_capabilities(request.clone(), window, cx);
+                    }
+                }
+            },
+        )
+        .detach();
+
         cx.subscribe_in(workspace.project(), window, |_, _, event, window, cx| {
             if let project::Event::LanguageNotFound(buffer) = event {
                 extension_suggest::suggest(buffer.clone(), window, cx);
@@ -164,6 +183,40 @@ pub fn init(cx: &mut App) {
     .detach();
 }
 
+/// The workspace window the user is looking at, or any workspace window when Zed isn't focused,
+/// so that requests made in the background still get an answer.
+fn capability_prompt_window(cx: &App) -> Option<AnyWindowHandle> {
+    let is_workspace = |window: &AnyWindowHandle| window.downcast::<Workspace>().is_some();
+    cx.active_window()
+        .filter(is_workspace)
+        .or_else(|| cx.windows().into_iter().find(is_workspace))
+}
+
+fn prompt_for_capabilities(request: CapabilityApprovalRequest, window: &mut Window, cx: &mut App) {
+    let message = format!("Allow {} to:", request.extension_name);
+    let detail = request
+        .capabilities
+        .iter()
+        .map(|capability| format!("• {}", capability.description()))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let confirm = match request.operation {
+        ExtensionOperation::Upgrade => "Update",
+        ExtensionOperation::Install | ExtensionOperation::Remove => "Install",
+    };
+    let answer = window.prompt(
+        PromptLevel::Info,
+        &message,
+        Some(&detail),
+        &[confirm, "Cancel"],
+        cx,
+    );
+    cx.spawn(async move |_| {
+        request.respond(answer.await == Ok(0));
+    })
+    .detach();
+}
+
 fn extension_provides_label(provides: ExtensionProvides) -> &'static str {
     match provides {
         ExtensionProvides::Themes => "Themes",
@@ -985,6 +1038,19 @@ impl ExtensionsPage {
                             store.uninstall_extension(extension_id.clone(), cx)
                         });
                     }
+                })
+                .map(|button| {
+                    let dependents = ExtensionStore::global(cx)
+                        .read(cx)
+                        .dependents_of(&extension.id);
+                    if dependents.is_empty() {
+                        button
+                    } else {
+                        button.disabled(true).tooltip(Tooltip::text(format!(
+                            "Required by {}",
+                            dependents.join(", ")
+                        )))
+                    }
                 }),
                 configure: is_configurable.then(|| {
                     Button::new(
@@ -1,14 +1,17 @@
-use crate::FakeFs;
+use crate::{FakeFs, Fs as _};
 use anyhow::{Context as _, Result};
 use collections::{HashMap, HashSet};
 use futures::future::{self, BoxFuture};
 use git::{
     blame::Blame,
     repository::{
-        AskPassDelegate, Branch, CommitDetails, CommitOptions, GitRepository,
-        GitRepositoryCheckpoint, PushOptions, Remote, RepoPath, ResetMode,
+        ApplyCommitMode, ApplyCommitOutcome, AskPassDelegate, Branch, CommitDetails, CommitOptions,
+        GitRepository, GitRepositoryCheckpoint, PushOptions, Remote, RepoPath, ResetMode,
+        Submodule,
+    },
+    status::{
+        FileStatus, GitStatus, StatusCode, TrackedStatus, UnmergedStatus, UnmergedStatusCode,
     },
-    status::{FileStatus, GitStatus, StatusCode, TrackedStatus, UnmergedStatus},
 };
 use gpui::{AsyncApp, BackgroundExecutor};
 use ignore::gitignore::GitignoreBuilder;
@@ -36,6 +39,11 @@ pub struct FakeGitRepositoryState {
     pub branches: HashSet<String>,
     pub simulated_index_write_error_message: Option<String>,
     pub refs: HashMap<String, String>,
+    /// File contents at refs other than `HEAD`, whose contents are `head_contents`.
+    pub ref_contents: HashMap<String, HashMap<RepoPath, String>>,
+    pub submodules: Vec<Submodule>,
+    /// The worktree contents of the stashed paths, most recent stash last.
+    pub stashes: Vec<HashMap<RepoPath, Option<String>>>,
 }
 
 impl FakeGitRepositoryState {
@@ -50,6 +58,19 @@ impl FakeGitRepositoryState {
             branches: Default::default(),
             simulated_index_write_error_message: Default::default(),
             refs: HashMap::from_iter([("HEAD".into(), "abc".into())]),
+            ref_contents: Default::default(),
+            submodules: Default::default(),
+            stashes: Default::default(),
+        }
+    }
+}
+
+impl FakeGitRepositoryState {
+    fn contents_at_ref(&self, git_ref: &str) -> Option<&HashMap<RepoPath, String>> {
+        if git_ref == "HEAD" {
+            Some(&self.head_contents)
+        } else {
+            self.ref_contents.get(git_ref)
         }
     }
 }
@@ -112,6 +133,56 @@ impl GitRepository for FakeGitRepository {
         unimplemented!()
     }
 
+    fn diff_refs(
+        &self,
+        base: String,
+        head: String,
+        _cx: AsyncApp,
+    ) -> BoxFuture<Result<git::repository::CommitDiff>> {
+        self.with_state_async(false, move |state| {
+            git::repository::validate_ref(&base)?;
+            git::repository::validate_ref(&head)?;
+            let old_contents = state
+                .contents_at_ref(&base)
+                .with_context(|| format!("unknown ref {base}"))?;
+            let new_contents = state
+                .contents_at_ref(&head)
+                .with_context(|| format!("unknown ref {head}"))?;
+
+            let mut paths = old_contents
+                .keys()
+                .chain(new_contents.keys())
+                .cloned()
+                .collect::<Vec<_>>();
+            paths.sort();
+            paths.dedup();
+            let files = paths
+                .into_iter()
+                .filter_map(|path| {
+                    let old_text = old_contents.get(&path).cloned();
+                    let new_text = new_contents.get(&path).cloned();
+                    (old_text != new_text).then_some(git::repository::CommitFile {
+                        path,
+                        old_text,
+                        new_text,
+                    })
+                })
+                .collect();
+            Ok(git::repository::CommitDiff { files })
+        })
+    }
+
+    fn renamed_paths(&self) -> BoxFuture<Result<Vec<git::repository::RenamedPath>>> {
+        async { Ok(Vec::new()) }.boxed()
+    }
+
+    fn file_history(
+        &self,
+        _path: RepoPath,
+    ) -> BoxFuture<Result<Vec<git::repository::CommitSummary>>> {
+        unimplemented!()
+    }
+
     fn set_index_text(
         &self,
         path: RepoPath,
@@ -134,6 +205,10 @@ impl GitRepository for FakeGitRepository {
         None
     }
 
+    fn excludes_file(&self) -> Option<PathBuf> {
+        None
+    }
+
     fn revparse_batch(&self, revs: Vec<String>) -> BoxFuture<Result<Vec<Option<String>>>> {
         self.with_state_async(false, |state| {
             Ok(revs
@@ -171,6 +246,148 @@ impl GitRepository for FakeGitRepository {
         unimplemented!()
     }
 
+    fn stash_paths(
+        &self,
+        paths: Vec<RepoPath>,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        let fs = self.fs.clone();
+        let workdir_path = self.dot_git_path.parent().unwrap().to_path_buf();
+        let this = self.clone();
+        async move {
+            let mut stash = HashMap::default();
+            for path in paths {
+                let content = fs.load(&workdir_path.join(&path)).await.ok();
+                stash.insert(path, content);
+            }
+            let restored = this
+                .with_state_async(true, move |state| {
+                    let restored = stash
+                        .keys()
+                        .map(|path| (path.clone(), state.head_contents.get(path).cloned()))
+                        .collect::<Vec<_>>();
+                    for (path, content) in &restored {
+                        match content {
+                            Some(content) => {
+                                state.index_contents.insert(path.clone(), content.clone())
+                            }
+                            None => state.index_contents.remove(path),
+                        };
+                    }
+                    state.stashes.push(stash);
+                    Ok(restored)
+                })
+                .await?;
+            for (path, content) in restored {
+                let abs_path = workdir_path.join(&path);
+                if let Some(content) = content {
+                    fs.insert_file(abs_path, content.into_bytes()).await;
+                } else {
+                    fs.remove_file(&abs_path, Default::default()).await?;
+                }
+            }
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn stash_pop(&self, _env: Arc<HashMap<String, String>>) -> BoxFuture<Result<()>> {
+        let fs = self.fs.clone();
+        let workdir_path = self.dot_git_path.parent().unwrap().to_path_buf();
+        let pop = self.with_state_async(true, |state| state.stashes.pop().context("no stash"));
+        async move {
+            for (path, content) in pop.await? {
+                let abs_path = workdir_path.join(&path);
+                if let Some(content) = content {
+                    fs.insert_file(abs_path, content.into_bytes()).await;
+                } else {
+                    fs.remove_file(&abs_path, Default::default()).await?;
+                }
+            }
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn apply_commit(
+        &self,
+        commit: String,
+        mode: ApplyCommitMode,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<ApplyCommitOutcome>> {
+        let fs = self.fs.clone();
+        let workdir_path = self.dot_git_path.parent().unwrap().to_path_buf();
+        let apply = self.with_state_async(true, move |state| {
+            let commit_contents = state
+                .ref_contents
+                .get(&commit)
+                .with_context(|| format!("unknown commit {commit}"))?;
+            let parent_contents = state
+                .ref_contents
+                .get(&format!("{commit}~1"))
+                .cloned()
+                .unwrap_or_default();
+            let (base, theirs) = match mode {
+                ApplyCommitMode::CherryPick => (&parent_contents, commit_contents),
+                ApplyCommitMode::Revert => (commit_contents, &parent_contents),
+            };
+
+            let mut applied = Vec::new();
+            let mut conflicted = Vec::new();
+            for path in base.keys().chain(theirs.keys()).collect::<HashSet<_>>() {
+                let base = base.get(path);
+                let theirs = theirs.get(path);
+                let ours = state.head_contents.get(path);
+                if base == theirs || ours == theirs {
+                    continue;
+                } else if ours == base {
+                    applied.push((path.clone(), theirs.cloned()));
+                } else {
+                    conflicted.push(path.clone());
+                }
+            }
+
+            if !conflicted.is_empty() {
+                for path in conflicted {
+                    state.unmerged_paths.insert(
+                        path,
+                        UnmergedStatus {
+                            first_head: UnmergedStatusCode::Updated,
+                            second_head: UnmergedStatusCode::Updated,
+                        },
+                    );
+                }
+                return Ok((ApplyCommitOutcome::Conflicted, Vec::new()));
+            }
+            if applied.is_empty() {
+                return Ok((ApplyCommitOutcome::Empty, Vec::new()));
+            }
+            for (path, content) in &applied {
+                if let Some(content) = content {
+                    state.head_contents.insert(path.clone(), content.clone());
+                    state.index_contents.insert(path.clone(), content.clone());
+                } else {
+                    state.head_contents.remove(path);
+                    state.index_contents.remove(path);
+                }
+            }
+            Ok((ApplyCommitOutcome::Applied, applied))
+        });
+        async move {
+            let (outcome, applied) = apply.await?;
+            for (path, content) in applied {
+                let abs_path = workdir_path.join(&path);
+                if let Some(content) = content {
+                    fs.insert_file(abs_path, content.into_bytes()).await;
+                } else {
+                    fs.remove_file(&abs_path, Default::default()).await?;
+                }
+            }
+            Ok(outcome)
+        }
+        .boxed()
+    }
+
     fn path(&self) -> PathBuf {
         self.repository_dir_path.clone()
     }
@@ -362,6 +579,19 @@ impl GitRepository for FakeGitRepository {
         unimplemented!()
     }
 
+    fn intent_to_add_paths(
+        &self,
+        paths: Vec<RepoPath>,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        self.with_state_async(true, move |state| {
+            for path in paths {
+                state.index_contents.entry(path).or_default();
+            }
+            Ok(())
+        })
+    }
+
     fn unstage_paths(
         &self,
         _paths: Vec<RepoPath>,
@@ -412,10 +642,34 @@ impl GitRepository for FakeGitRepository {
         unimplemented!()
     }
 
+    fn fetch_refspec(
+        &self,
+        _remote_name: String,
+        _refspec: String,
+        _askpass: AskPassDelegate,
+        _env: Arc<HashMap<String, String>>,
+        _cx: AsyncApp,
+    ) -> BoxFuture<Result<git::repository::RemoteCommandOutput>> {
+        unimplemented!()
+    }
+
     fn get_remotes(&self, _branch: Option<String>) -> BoxFuture<Result<Vec<Remote>>> {
         unimplemented!()
     }
 
+    fn submodules(&self) -> BoxFuture<Result<Vec<Submodule>>> {
+        self.with_state_async(false, |state| Ok(state.submodules.clone()))
+    }
+
+    fn update_submodules(
+        &self,
+        _askpass: AskPassDelegate,
+        _env: Arc<HashMap<String, String>>,
+        _cx: AsyncApp,
+    ) -> BoxFuture<Result<git::repository::RemoteCommandOutput>> {
+        unimplemented!()
+    }
+
     fn check_for_pushed_commit(&self) -> BoxFuture<Result<Vec<gpui::SharedString>>> {
         future::ready(Ok(Vec::new())).boxed()
     }
@@ -1515,6 +1515,20 @@ impl FakeFs {
         .unwrap();
     }
 
+    pub fn set_ref_contents_for_repo(
+        &self,
+        dot_git: &Path,
+        git_ref: impl Into<String>,
+        contents_by_path: &[(RepoPath, String)],
+    ) {
+        self.with_git_state(dot_git, true, |state| {
+            state
+                .ref_contents
+                .insert(git_ref.into(), contents_by_path.iter().cloned().collect());
+        })
+        .unwrap();
+    }
+
     pub fn set_blame_for_repo(&self, dot_git: &Path, blames: Vec<(RepoPath, git::blame::Blame)>) {
         self.with_git_state(dot_git, true, |state| {
             state.blames.clear();
@@ -2938,6 +2952,62 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_fake_git_diff_refs(cx: &mut gpui::TestAppContext) {
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/repo"), json!({ ".git": {} })).await;
+        let dot_git = Path::new(path!("/repo/.git"));
+        fs.set_ref_contents_for_repo(
+            dot_git,
+            "main",
+            &[
+                ("a.txt".into(), "one".into()),
+                ("b.txt".into(), "same".into()),
+                ("c.txt".into(), "removed".into()),
+            ],
+        );
+        fs.set_head_for_repo(
+            dot_git,
+            &[
+                ("a.txt".into(), "two".into()),
+                ("b.txt".into(), "same".into()),
+                ("d.txt".into(), "added".into()),
+            ],
+            "abc",
+        );
+
+        let repo = fs.open_repo(dot_git).unwrap();
+        let diff = repo
+            .diff_refs("main".into(), "HEAD".into(), cx.to_async())
+            .await
+            .unwrap();
+        let files = diff
+            .files
+            .iter()
+            .map(|file| {
+                (
+                    file.path.to_string_lossy().into_owned(),
+                    file.old_text.as_deref(),
+                    file.new_text.as_deref(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            files,
+            [
+                ("a.txt".to_string(), Some("one"), Some("two")),
+                ("c.txt".to_string(), Some("removed"), None),
+                ("d.txt".to_string(), None, Some("added")),
+            ]
+        );
+
+        assert!(
+            repo.diff_refs("--output=/tmp/out".into(), "HEAD".into(), cx.to_async())
+                .await
+                .is_err()
+        );
+    }
+
     #[gpui::test]
     async fn test_realfs_atomic_write(executor: BackgroundExecutor) {
         // With the file handle still open, the file should be replaced
@@ -17,6 +17,7 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::ffi::OsStr;
 use std::fmt;
+use std::path::Path;
 use std::str::FromStr;
 use std::sync::LazyLock;
 
@@ -28,6 +29,8 @@ pub static LFS_DIR: LazyLock<&'static OsStr> = LazyLock::new(|| OsStr::new("lfs"
 pub static COMMIT_MESSAGE: LazyLock<&'static OsStr> =
     LazyLock::new(|| OsStr::new("COMMIT_EDITMSG"));
 pub static INDEX_LOCK: LazyLock<&'static OsStr> = LazyLock::new(|| OsStr::new("index.lock"));
+/// The path of the repository's own ignore rules, relative to its common directory.
+pub static REPO_EXCLUDE: LazyLock<&'static Path> = LazyLock::new(|| Path::new("info/exclude"));
 
 actions!(
     git,
@@ -39,6 +42,9 @@ actions!(
         // per-file
         StageFile,
         UnstageFile,
+        StagePartially,
+        StashFile,
+        FileHistory,
         // repo-wide
         StageAll,
         UnstageAll,
@@ -49,11 +55,14 @@ actions!(
         ForcePush,
         Pull,
         Fetch,
+        UpdateSubmodules,
+        StashPop,
         Commit,
         Amend,
         Cancel,
         ExpandCommitEditor,
         GenerateCommitMessage,
+        GeneratePullRequestDescription,
         Init,
     ]
 );
@@ -14,6 +14,21 @@ pub struct PullRequest {
     pub url: Url,
 }
 
+/// The state of the most recent CI build for a branch.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum BuildState {
+    Pending,
+    Success,
+    Failure,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct BuildStatus {
+    pub state: BuildState,
+    /// A link to the build's details on the hosting provider, if there is one.
+    pub url: Option<Url>,
+}
+
 #[derive(Clone)]
 pub struct GitRemote {
     pub host: Arc<dyn GitHostingProvider + Send + Sync + 'static>,
@@ -47,6 +62,32 @@ impl GitRemote {
             .ok()
             .flatten()
     }
+
+    pub async fn build_status(
+        &self,
+        branch: &str,
+        client: Arc<dyn HttpClient>,
+    ) -> Result<Option<BuildStatus>> {
+        let remote = ParsedGitRemote {
+            owner: self.owner.as_str().into(),
+            repo: self.repo.as_str().into(),
+        };
+        self.host.build_status(&remote, branch, client).await
+    }
+
+    /// Returns the ref that the remote exposes the head of the given pull request under.
+    pub fn pull_request_ref(&self, number: u32) -> Option<String> {
+        self.host.pull_request_ref(number)
+    }
+
+    /// Returns the URL for opening a pull request from the given branch on the remote.
+    pub fn new_pull_request_url(&self, branch: &str) -> Option<Url> {
+        let remote = ParsedGitRemote {
+            owner: self.owner.as_str().into(),
+            repo: self.repo.as_str().into(),
+        };
+        self.host.build_new_pull_request_url(&remote, branch)
+    }
 }
 
 pub struct BuildCommitPermalinkParams<'a> {
@@ -111,6 +152,18 @@ pub trait GitHostingProvider {
         None
     }
 
+    /// Returns the URL for opening a pull request from the given branch, if the provider
+    /// supports it.
+    fn build_new_pull_request_url(&self, _remote: &ParsedGitRemote, _branch: &str) -> Option<Url> {
+        None
+    }
+
+    /// Returns the ref that the remote exposes the head of the given pull request under, if the
+    /// provider supports checking out pull requests.
+    fn pull_request_ref(&self, _number: u32) -> Option<String> {
+        None
+    }
+
     async fn commit_author_avatar_url(
         &self,
         _repo_owner: &str,
@@ -120,6 +173,16 @@ pub trait GitHostingProvider {
     ) -> Result<Option<Url>> {
         Ok(None)
     }
+
+    /// Returns the status of the most recent CI build for the given branch on the remote.
+    async fn build_status(
+        &self,
+        _remote: &ParsedGitRemote,
+        _branch: &str,
+        _http_client: Arc<dyn HttpClient>,
+    ) -> Result<Option<BuildStatus>> {
+        Ok(None)
+    }
 }
 
 #[derive(Default, Deref, DerefMut)]
@@ -131,6 +194,12 @@ impl Global for GlobalGitHostingProviderRegistry {}
 struct GitHostingProviderRegistryState {
     default_providers: Vec<Arc<dyn GitHostingProvider + Send + Sync + 'static>>,
     setting_providers: Vec<Arc<dyn GitHostingProvider + Send + Sync + 'static>>,
+    /// Providers contributed by extensions, keyed by the extension's ID and the provider's ID,
+    /// since different extensions may use the same provider ID.
+    extension_providers: Vec<(
+        (Arc<str>, Arc<str>),
+        Arc<dyn GitHostingProvider + Send + Sync + 'static>,
+    )>,
 }
 
 #[derive(Default)]
@@ -171,6 +240,7 @@ impl GitHostingProviderRegistry {
             state: RwLock::new(GitHostingProviderRegistryState {
                 setting_providers: Vec::default(),
                 default_providers: Vec::default(),
+                extension_providers: Vec::default(),
             }),
         }
     }
@@ -185,6 +255,12 @@ impl GitHostingProviderRegistry {
             .iter()
             .cloned()
             .chain(state.setting_providers.iter().cloned())
+            .chain(
+                state
+                    .extension_providers
+                    .iter()
+                    .map(|(_, provider)| provider.clone()),
+            )
             .collect()
     }
 
@@ -204,6 +280,32 @@ impl GitHostingProviderRegistry {
     ) {
         self.state.write().default_providers.push(provider);
     }
+
+    /// Adds a [`GitHostingProvider`] contributed by an extension, replacing any existing one
+    /// that the same extension registered with the same ID.
+    pub fn register_extension_provider(
+        &self,
+        extension_id: Arc<str>,
+        provider_id: Arc<str>,
+        provider: Arc<dyn GitHostingProvider + Send + Sync + 'static>,
+    ) {
+        let key = (extension_id, provider_id);
+        let mut state = self.state.write();
+        state
+            .extension_providers
+            .retain(|(existing_key, _)| *existing_key != key);
+        state.extension_providers.push((key, provider));
+    }
+
+    /// Removes the [`GitHostingProvider`] that the given extension registered with the given ID.
+    pub fn unregister_extension_provider(&self, extension_id: &str, provider_id: &str) {
+        self.state.write().extension_providers.retain(
+            |((existing_extension_id, existing_provider_id), _)| {
+                existing_extension_id.as_ref() != extension_id
+                    || existing_provider_id.as_ref() != provider_id
+            },
+        );
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -96,6 +96,8 @@ impl Upstream {
 #[derive(Clone, Copy, Default)]
 pub struct CommitOptions {
     pub amend: bool,
+    /// Whether to add a `Signed-off-by` trailer for the committer.
+    pub signoff: bool,
 }
 
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
@@ -166,6 +168,23 @@ pub struct CommitDiff {
     pub files: Vec<CommitFile>,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RenameKind {
+    Renamed,
+    Copied,
+}
+
+/// A changed file that git matched up with an existing file, either because it
+/// was moved or because it was copied from it.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RenamedPath {
+    pub old_path: RepoPath,
+    pub new_path: RepoPath,
+    pub kind: RenameKind,
+    /// How similar the new file is to the old one, as a percentage.
+    pub similarity: u8,
+}
+
 #[derive(Debug)]
 pub struct CommitFile {
     pub path: RepoPath,
@@ -184,6 +203,32 @@ pub struct Remote {
     pub name: SharedString,
 }
 
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct Submodule {
+    pub path: RepoPath,
+    /// The commit checked out in the submodule, or the commit recorded in the
+    /// superproject if the submodule is not initialized.
+    pub sha: SharedString,
+    pub status: SubmoduleStatus,
+}
+
+impl Submodule {
+    pub fn short_sha(&self) -> SharedString {
+        self.sha[..SHORT_SHA_LENGTH.min(self.sha.len())]
+            .to_string()
+            .into()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum SubmoduleStatus {
+    UpToDate,
+    Uninitialized,
+    /// The checked out commit does not match the one recorded in the superproject.
+    OutOfSync,
+    Conflicted,
+}
+
 pub enum ResetMode {
     /// Reset the branch pointer, leave index and worktree unchanged (this will make it look like things that were
     /// committed are now staged).
@@ -193,6 +238,30 @@ pub enum ResetMode {
     Mixed,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApplyCommitMode {
+    CherryPick,
+    Revert,
+}
+
+impl ApplyCommitMode {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ApplyCommitMode::CherryPick => "cherry-pick",
+            ApplyCommitMode::Revert => "revert",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApplyCommitOutcome {
+    Applied,
+    /// The commit could not be applied cleanly, and the repository was left with conflicts to resolve.
+    Conflicted,
+    /// The commit's changes are already present on HEAD, so there was nothing to apply.
+    Empty,
+}
+
 /// Modifies .git/info/exclude temporarily
 pub struct GitExcludeOverride {
     git_exclude_path: PathBuf,
@@ -282,6 +351,10 @@ pub trait GitRepository: Send + Sync {
     /// Returns the URL of the remote with the given name.
     fn remote_url(&self, name: &str) -> Option<String>;
 
+    /// The global ignore file that applies to this repository, whether it's configured with
+    /// `core.excludesFile` or is git's default location.
+    fn excludes_file(&self) -> Option<PathBuf>;
+
     /// Resolve a list of refs to SHAs.
     fn revparse_batch(&self, revs: Vec<String>) -> BoxFuture<Result<Vec<Option<String>>>>;
 
@@ -320,9 +393,39 @@ pub trait GitRepository: Send + Sync {
         env: Arc<HashMap<String, String>>,
     ) -> BoxFuture<Result<()>>;
 
+    /// Stashes the changes to the given paths, including untracked ones, and
+    /// leaves the rest of the working tree and index alone.
+    fn stash_paths(
+        &self,
+        paths: Vec<RepoPath>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>>;
+
+    /// Applies the most recent stash and drops it.
+    fn stash_pop(&self, env: Arc<HashMap<String, String>>) -> BoxFuture<Result<()>>;
+
+    /// Cherry-picks or reverts the given commit on top of HEAD.
+    fn apply_commit(
+        &self,
+        commit: String,
+        mode: ApplyCommitMode,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<ApplyCommitOutcome>>;
+
     fn show(&self, commit: String) -> BoxFuture<Result<CommitDetails>>;
 
     fn load_commit(&self, commit: String, cx: AsyncApp) -> BoxFuture<Result<CommitDiff>>;
+
+    /// Loads the changes between two revisions, as they'd be shown by `git diff <base> <head>`.
+    fn diff_refs(&self, base: String, head: String, cx: AsyncApp) -> BoxFuture<Result<CommitDiff>>;
+
+    /// Returns the renamed and copied files among the staged changes and the unstaged
+    /// changes to files that have been added with intent-to-add.
+    fn renamed_paths(&self) -> BoxFuture<Result<Vec<RenamedPath>>>;
+
+    /// Returns the commits reachable from HEAD that touched the given path, newest first.
+    fn file_history(&self, path: RepoPath) -> BoxFuture<Result<Vec<CommitSummary>>>;
+
     fn blame(&self, path: RepoPath, content: Rope) -> BoxFuture<Result<crate::blame::Blame>>;
 
     /// Returns the absolute path to the repository. For worktrees, this will be the path to the
@@ -339,6 +442,15 @@ pub trait GitRepository: Send + Sync {
         paths: Vec<RepoPath>,
         env: Arc<HashMap<String, String>>,
     ) -> BoxFuture<Result<()>>;
+    /// Records that the given untracked paths will be added later, without staging their contents.
+    ///
+    /// The paths are added to the index as empty intent-to-add entries, so that their
+    /// contents can subsequently be staged piecemeal.
+    fn intent_to_add_paths(
+        &self,
+        paths: Vec<RepoPath>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>>;
     /// Updates the index to match HEAD at the given paths.
     ///
     /// If any of the paths were previously staged but do not exist in HEAD, they will be removed from the index.
@@ -388,8 +500,33 @@ pub trait GitRepository: Send + Sync {
         cx: AsyncApp,
     ) -> BoxFuture<Result<RemoteCommandOutput>>;
 
+    /// Fetches a single refspec from the given remote.
+    fn fetch_refspec(
+        &self,
+        remote_name: String,
+        refspec: String,
+        askpass: AskPassDelegate,
+        env: Arc<HashMap<String, String>>,
+        // This method takes an AsyncApp to ensure it's invoked on the main thread,
+        // otherwise git-credentials-manager won't work.
+        cx: AsyncApp,
+    ) -> BoxFuture<Result<RemoteCommandOutput>>;
+
     fn get_remotes(&self, branch_name: Option<String>) -> BoxFuture<Result<Vec<Remote>>>;
 
+    /// Lists the submodules registered in the repository.
+    fn submodules(&self) -> BoxFuture<Result<Vec<Submodule>>>;
+
+    /// Initializes and updates all submodules, recursively.
+    fn update_submodules(
+        &self,
+        askpass: AskPassDelegate,
+        env: Arc<HashMap<String, String>>,
+        // This method takes an AsyncApp to ensure it's invoked on the main thread,
+        // otherwise git-credentials-manager won't work.
+        cx: AsyncApp,
+    ) -> BoxFuture<Result<RemoteCommandOutput>>;
+
     /// returns a list of remote branches that contain HEAD
     fn check_for_pushed_commit(&self) -> BoxFuture<Result<Vec<SharedString>>>;
 
@@ -420,6 +557,11 @@ pub trait GitRepository: Send + Sync {
 pub enum DiffType {
     HeadToIndex,
     HeadToWorktree,
+    /// The changes committed on HEAD since it diverged from `base_ref`, as shown by
+    /// `git diff <base_ref>...HEAD`.
+    MergeBase {
+        base_ref: String,
+    },
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, JsonSchema)]
@@ -522,6 +664,69 @@ impl GitRepository for RealGitRepository {
             .boxed()
     }
 
+    fn renamed_paths(&self) -> BoxFuture<Result<Vec<RenamedPath>>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        self.executor
+            .spawn(async move {
+                let working_directory = working_directory?;
+                let mut renamed_paths = Vec::new();
+                for cached in [true, false] {
+                    let mut command = new_smol_command(&git_binary_path);
+                    command.current_dir(&working_directory).args([
+                        "--no-optional-locks",
+                        "diff",
+                        "-z",
+                        "--name-status",
+                        "--find-renames",
+                        "--find-copies",
+                    ]);
+                    if cached {
+                        command.arg("--cached");
+                    }
+                    let output = command.output().await?;
+                    anyhow::ensure!(
+                        output.status.success(),
+                        "Failed to detect renames:\n{}",
+                        String::from_utf8_lossy(&output.stderr)
+                    );
+                    renamed_paths.extend(parse_renamed_paths(&String::from_utf8_lossy(
+                        &output.stdout,
+                    ))?);
+                }
+                Ok(renamed_paths)
+            })
+            .boxed()
+    }
+
+    fn file_history(&self, path: RepoPath) -> BoxFuture<Result<Vec<CommitSummary>>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        self.executor
+            .spawn(async move {
+                let working_directory = working_directory?;
+                let output = new_smol_command(&git_binary_path)
+                    .current_dir(&working_directory)
+                    .args([
+                        "--no-optional-locks",
+                        "log",
+                        "--format=%H%x00%P%x00%ct%x00%s",
+                        "--",
+                    ])
+                    .arg(path.as_os_str())
+                    .output()
+                    .await?;
+
+                anyhow::ensure!(
+                    output.status.success(),
+                    "Failed to get file history:\n{}",
+                    String::from_utf8_lossy(&output.stderr)
+                );
+                parse_file_history(&String::from_utf8_lossy(&output.stdout))
+            })
+            .boxed()
+    }
+
     fn load_commit(&self, commit: String, cx: AsyncApp) -> BoxFuture<Result<CommitDiff>> {
         let Some(working_directory) = self.repository.lock().workdir().map(ToOwned::to_owned)
         else {
@@ -550,76 +755,46 @@ impl GitRepository for RealGitRepository {
             let parent_sha = lines.next().unwrap().trim().trim_end_matches('\0');
             let changes = parse_git_diff_name_status(lines.next().unwrap_or(""));
 
-            let mut cat_file_process = util::command::new_std_command("git")
+            load_changed_files(&working_directory, parent_sha, &commit, changes)
+        })
+        .boxed()
+    }
+
+    fn diff_refs(&self, base: String, head: String, cx: AsyncApp) -> BoxFuture<Result<CommitDiff>> {
+        let Some(working_directory) = self.repository.lock().workdir().map(ToOwned::to_owned)
+        else {
+            return future::ready(Err(anyhow!("no working directory"))).boxed();
+        };
+        cx.background_spawn(async move {
+            validate_ref(&base)?;
+            validate_ref(&head)?;
+            let diff_output = util::command::new_std_command("git")
                 .current_dir(&working_directory)
-                .args(["--no-optional-locks", "cat-file", "--batch=%(objectsize)"])
-                .stdin(Stdio::piped())
+                .args([
+                    "--no-optional-locks",
+                    "diff",
+                    "-z",
+                    "--no-renames",
+                    "--name-status",
+                    "--end-of-options",
+                ])
+                .arg(&base)
+                .arg(&head)
+                .arg("--")
+                .stdin(Stdio::null())
                 .stdout(Stdio::piped())
                 .stderr(Stdio::piped())
-                .spawn()
-                .context("starting git cat-file process")?;
-
-            use std::io::Write as _;
-            let mut files = Vec::<CommitFile>::new();
-            let mut stdin = BufWriter::with_capacity(512, cat_file_process.stdin.take().unwrap());
-            let mut stdout = BufReader::new(cat_file_process.stdout.take().unwrap());
-            let mut info_line = String::new();
-            let mut newline = [b'\0'];
-            for (path, status_code) in changes {
-                match status_code {
-                    StatusCode::Modified => {
-                        writeln!(&mut stdin, "{commit}:{}", path.display())?;
-                        writeln!(&mut stdin, "{parent_sha}:{}", path.display())?;
-                    }
-                    StatusCode::Added => {
-                        writeln!(&mut stdin, "{commit}:{}", path.display())?;
-                    }
-                    StatusCode::Deleted => {
-                        writeln!(&mut stdin, "{parent_sha}:{}", path.display())?;
-                    }
-                    _ => continue,
-                }
-                stdin.flush()?;
-
-                info_line.clear();
-                stdout.read_line(&mut info_line)?;
-
-                let len = info_line.trim_end().parse().with_context(|| {
-                    format!("invalid object size output from cat-file {info_line}")
-                })?;
-                let mut text = vec![0; len];
-                stdout.read_exact(&mut text)?;
-                stdout.read_exact(&mut newline)?;
-                let text = String::from_utf8_lossy(&text).to_string();
-
-                let mut old_text = None;
-                let mut new_text = None;
-                match status_code {
-                    StatusCode::Modified => {
-                        info_line.clear();
-                        stdout.read_line(&mut info_line)?;
-                        let len = info_line.trim_end().parse().with_context(|| {
-                            format!("invalid object size output from cat-file {}", info_line)
-                        })?;
-                        let mut parent_text = vec![0; len];
-                        stdout.read_exact(&mut parent_text)?;
-                        stdout.read_exact(&mut newline)?;
-                        old_text = Some(String::from_utf8_lossy(&parent_text).to_string());
-                        new_text = Some(text);
-                    }
-                    StatusCode::Added => new_text = Some(text),
-                    StatusCode::Deleted => old_text = Some(text),
-                    _ => continue,
-                }
-
-                files.push(CommitFile {
-                    path: path.into(),
-                    old_text,
-                    new_text,
-                })
-            }
+                .output()
+                .context("starting git diff process")?;
+            anyhow::ensure!(
+                diff_output.status.success(),
+                "Failed to diff {base} and {head}:\n{}",
+                String::from_utf8_lossy(&diff_output.stderr)
+            );
 
-            Ok(CommitDiff { files })
+            let diff_stdout = String::from_utf8_lossy(&diff_output.stdout);
+            let changes = parse_git_diff_name_status(&diff_stdout);
+            load_changed_files(&working_directory, &base, &head, changes)
         })
         .boxed()
     }
@@ -654,6 +829,71 @@ impl GitRepository for RealGitRepository {
         .boxed()
     }
 
+    fn apply_commit(
+        &self,
+        commit: String,
+        mode: ApplyCommitMode,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<ApplyCommitOutcome>> {
+        async move {
+            let working_directory = self.working_directory()?;
+
+            let (args, sequencer_head): (&[&str], _) = match mode {
+                ApplyCommitMode::CherryPick => (&["cherry-pick"], "CHERRY_PICK_HEAD"),
+                ApplyCommitMode::Revert => (&["revert", "--no-edit"], "REVERT_HEAD"),
+            };
+
+            let output = new_smol_command(&self.git_binary_path)
+                .envs(env.iter())
+                .current_dir(&working_directory)
+                .args(args)
+                .arg(&commit)
+                .output()
+                .await?;
+            if output.status.success() {
+                return Ok(ApplyCommitOutcome::Applied);
+            }
+
+            // Git leaves the sequencer head behind when it stops to let the user resolve conflicts,
+            // but also when the commit turns out to be empty on top of HEAD. Only the former leaves
+            // unmerged paths, and the latter has to be aborted so the repository isn't left mid-operation.
+            if smol::fs::metadata(self.path().join(sequencer_head))
+                .await
+                .is_ok()
+            {
+                let unmerged = new_smol_command(&self.git_binary_path)
+                    .envs(env.iter())
+                    .current_dir(&working_directory)
+                    .args(["diff", "--name-only", "--diff-filter=U"])
+                    .output()
+                    .await?;
+                if !unmerged.status.success() || !unmerged.stdout.trim_ascii().is_empty() {
+                    return Ok(ApplyCommitOutcome::Conflicted);
+                }
+
+                let abort = new_smol_command(&self.git_binary_path)
+                    .envs(env.iter())
+                    .current_dir(&working_directory)
+                    .args([mode.name(), "--abort"])
+                    .output()
+                    .await?;
+                anyhow::ensure!(
+                    abort.status.success(),
+                    "Failed to abort empty {}:\n{}",
+                    mode.name(),
+                    String::from_utf8_lossy(&abort.stderr)
+                );
+                return Ok(ApplyCommitOutcome::Empty);
+            }
+            bail!(
+                "Failed to {}:\n{}",
+                mode.name(),
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+        .boxed()
+    }
+
     fn checkout_files(
         &self,
         commit: String,
@@ -684,6 +924,55 @@ impl GitRepository for RealGitRepository {
         .boxed()
     }
 
+    fn stash_paths(
+        &self,
+        paths: Vec<RepoPath>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        async move {
+            if paths.is_empty() {
+                return Ok(());
+            }
+
+            let output = new_smol_command(&git_binary_path)
+                .current_dir(&working_directory?)
+                .envs(env.iter())
+                .args(["stash", "push", "--include-untracked", "--"])
+                .args(paths.iter().map(|path| path.as_ref()))
+                .output()
+                .await?;
+            anyhow::ensure!(
+                output.status.success(),
+                "Failed to stash files:\n{}",
+                String::from_utf8_lossy(&output.stderr),
+            );
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn stash_pop(&self, env: Arc<HashMap<String, String>>) -> BoxFuture<Result<()>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        async move {
+            let output = new_smol_command(&git_binary_path)
+                .current_dir(&working_directory?)
+                .envs(env.iter())
+                .args(["stash", "pop"])
+                .output()
+                .await?;
+            anyhow::ensure!(
+                output.status.success(),
+                "Failed to pop stash:\n{}",
+                String::from_utf8_lossy(&output.stderr),
+            );
+            Ok(())
+        }
+        .boxed()
+    }
+
     fn load_index_text(&self, path: RepoPath) -> BoxFuture<Option<String>> {
         // https://git-scm.com/book/en/v2/Git-Internals-Git-Objects
         const GIT_MODE_SYMLINK: u32 = 0o120000;
@@ -802,6 +1091,18 @@ impl GitRepository for RealGitRepository {
         remote.url().map(|url| url.to_string())
     }
 
+    fn excludes_file(&self) -> Option<PathBuf> {
+        let repo = self.repository.lock();
+        if let Ok(path) = repo.config().ok()?.get_path("core.excludesFile") {
+            return Some(path);
+        }
+        let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| Some(PathBuf::from(std::env::var_os("HOME")?).join(".config")))?;
+        Some(config_dir.join("git").join("ignore"))
+    }
+
     fn revparse_batch(&self, revs: Vec<String>) -> BoxFuture<Result<Vec<Option<String>>>> {
         let working_directory = self.working_directory();
         self.executor
@@ -1019,8 +1320,12 @@ impl GitRepository for RealGitRepository {
         self.executor
             .spawn(async move {
                 let args = match diff {
-                    DiffType::HeadToIndex => Some("--staged"),
-                    DiffType::HeadToWorktree => None,
+                    DiffType::HeadToIndex => vec!["--staged".to_string()],
+                    DiffType::HeadToWorktree => Vec::new(),
+                    DiffType::MergeBase { base_ref } => {
+                        validate_ref(&base_ref)?;
+                        vec!["--end-of-options".to_string(), format!("{base_ref}...HEAD")]
+                    }
                 };
 
                 let output = new_smol_command(&git_binary_path)
@@ -1068,6 +1373,34 @@ impl GitRepository for RealGitRepository {
             .boxed()
     }
 
+    fn intent_to_add_paths(
+        &self,
+        paths: Vec<RepoPath>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        self.executor
+            .spawn(async move {
+                if !paths.is_empty() {
+                    let output = new_smol_command(&git_binary_path)
+                        .current_dir(&working_directory?)
+                        .envs(env.iter())
+                        .args(["add", "--intent-to-add", "--"])
+                        .args(paths.iter().map(|p| p.to_unix_style()))
+                        .output()
+                        .await?;
+                    anyhow::ensure!(
+                        output.status.success(),
+                        "Failed to add paths with intent to add:\n{}",
+                        String::from_utf8_lossy(&output.stderr),
+                    );
+                }
+                Ok(())
+            })
+            .boxed()
+    }
+
     fn unstage_paths(
         &self,
         paths: Vec<RepoPath>,
@@ -1119,6 +1452,10 @@ impl GitRepository for RealGitRepository {
                     cmd.arg("--amend");
                 }
 
+                if options.signoff {
+                    cmd.arg("--signoff");
+                }
+
                 if let Some((name, email)) = name_and_email {
                     cmd.arg("--author").arg(&format!("{name} <{email}>"));
                 }
@@ -1216,6 +1553,32 @@ impl GitRepository for RealGitRepository {
         .boxed()
     }
 
+    fn fetch_refspec(
+        &self,
+        remote_name: String,
+        refspec: String,
+        ask_pass: AskPassDelegate,
+        env: Arc<HashMap<String, String>>,
+        cx: AsyncApp,
+    ) -> BoxFuture<Result<RemoteCommandOutput>> {
+        let working_directory = self.working_directory();
+        let executor = cx.background_executor().clone();
+        async move {
+            let mut command = new_smol_command("git");
+            command
+                .envs(env.iter())
+                .current_dir(&working_directory?)
+                .args(["fetch", "--"])
+                .arg(remote_name)
+                .arg(refspec)
+                .stdout(smol::process::Stdio::piped())
+                .stderr(smol::process::Stdio::piped());
+
+            run_git_command(env, ask_pass, command, &executor).await
+        }
+        .boxed()
+    }
+
     fn get_remotes(&self, branch_name: Option<String>) -> BoxFuture<Result<Vec<Remote>>> {
         let working_directory = self.working_directory();
         let git_binary_path = self.git_binary_path.clone();
@@ -1262,6 +1625,50 @@ impl GitRepository for RealGitRepository {
             .boxed()
     }
 
+    fn submodules(&self) -> BoxFuture<Result<Vec<Submodule>>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        self.executor
+            .spawn(async move {
+                let working_directory = working_directory?;
+                let output = new_smol_command(&git_binary_path)
+                    .current_dir(&working_directory)
+                    .args(["submodule", "status"])
+                    .output()
+                    .await?;
+
+                anyhow::ensure!(
+                    output.status.success(),
+                    "Failed to get submodules:\n{}",
+                    String::from_utf8_lossy(&output.stderr)
+                );
+                parse_submodule_status(&String::from_utf8_lossy(&output.stdout))
+            })
+            .boxed()
+    }
+
+    fn update_submodules(
+        &self,
+        ask_pass: AskPassDelegate,
+        env: Arc<HashMap<String, String>>,
+        cx: AsyncApp,
+    ) -> BoxFuture<Result<RemoteCommandOutput>> {
+        let working_directory = self.working_directory();
+        let executor = cx.background_executor().clone();
+        async move {
+            let mut command = new_smol_command("git");
+            command
+                .envs(env.iter())
+                .current_dir(&working_directory?)
+                .args(["submodule", "update", "--init", "--recursive"])
+                .stdout(smol::process::Stdio::piped())
+                .stderr(smol::process::Stdio::piped());
+
+            run_git_command(env, ask_pass, command, &executor).await
+        }
+        .boxed()
+    }
+
     fn check_for_pushed_commit(&self) -> BoxFuture<Result<Vec<SharedString>>> {
         let working_directory = self.working_directory();
         let git_binary_path = self.git_binary_path.clone();
@@ -1864,6 +2271,171 @@ fn parse_branch_input(input: &str) -> Result<Vec<Branch>> {
     Ok(branches)
 }
 
+/// Loads the old and new contents of the given changed paths, reading them at
+/// `old_rev` and `new_rev` respectively.
+fn load_changed_files<'a>(
+    working_directory: &Path,
+    old_rev: &str,
+    new_rev: &str,
+    changes: impl Iterator<Item = (&'a Path, StatusCode)>,
+) -> Result<CommitDiff> {
+    let mut cat_file_process = util::command::new_std_command("git")
+        .current_dir(working_directory)
+        .args(["--no-optional-locks", "cat-file", "--batch=%(objectsize)"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("starting git cat-file process")?;
+
+    use std::io::Write as _;
+    let mut files = Vec::<CommitFile>::new();
+    let mut stdin = BufWriter::with_capacity(512, cat_file_process.stdin.take().unwrap());
+    let mut stdout = BufReader::new(cat_file_process.stdout.take().unwrap());
+    let mut info_line = String::new();
+    let mut newline = [b'\0'];
+    for (path, status_code) in changes {
+        match status_code {
+            StatusCode::Modified => {
+                writeln!(&mut stdin, "{new_rev}:{}", path.display())?;
+                writeln!(&mut stdin, "{old_rev}:{}", path.display())?;
+            }
+            StatusCode::Added => {
+                writeln!(&mut stdin, "{new_rev}:{}", path.display())?;
+            }
+            StatusCode::Deleted => {
+                writeln!(&mut stdin, "{old_rev}:{}", path.display())?;
+            }
+            _ => continue,
+        }
+        stdin.flush()?;
+
+        info_line.clear();
+        stdout.read_line(&mut info_line)?;
+
+        let len = info_line
+            .trim_end()
+            .parse()
+            .with_context(|| format!("invalid object size output from cat-file {info_line}"))?;
+        let mut text = vec![0; len];
+        stdout.read_exact(&mut text)?;
+        stdout.read_exact(&mut newline)?;
+        let text = String::from_utf8_lossy(&text).to_string();
+
+        let mut old_text = None;
+        let mut new_text = None;
+        match status_code {
+            StatusCode::Modified => {
+                info_line.clear();
+                stdout.read_line(&mut info_line)?;
+                let len = info_line.trim_end().parse().with_context(|| {
+                    format!("invalid object size output from cat-file {}", info_line)
+                })?;
+                let mut parent_text = vec![0; len];
+                stdout.read_exact(&mut parent_text)?;
+                stdout.read_exact(&mut newline)?;
+                old_text = Some(String::from_utf8_lossy(&parent_text).to_string());
+                new_text = Some(text);
+            }
+            StatusCode::Added => new_text = Some(text),
+            StatusCode::Deleted => old_text = Some(text),
+            _ => continue,
+        }
+
+        files.push(CommitFile {
+            path: path.into(),
+            old_text,
+            new_text,
+        })
+    }
+
+    Ok(CommitDiff { files })
+}
+
+fn parse_file_history(input: &str) -> Result<Vec<CommitSummary>> {
+    let mut commits = Vec::new();
+    for line in input.lines() {
+        if line.is_empty() {
+            continue;
+        }
+        let mut fields = line.splitn(4, '\0');
+        let sha = fields.next().context("no commit sha")?;
+        let parents = fields.next().context("no parents")?;
+        let commit_timestamp = fields.next().context("no commit timestamp")?.parse()?;
+        let subject = fields.next().context("no subject")?;
+        commits.push(CommitSummary {
+            sha: sha.to_string().into(),
+            subject: subject.to_string().into(),
+            commit_timestamp,
+            has_parent: !parents.is_empty(),
+        });
+    }
+    Ok(commits)
+}
+
+/// Parses the renames and copies out of `git diff -z --name-status` output, in
+/// which they're listed as `R<score>\0<old path>\0<new path>`.
+fn parse_renamed_paths(input: &str) -> Result<Vec<RenamedPath>> {
+    let mut renamed_paths = Vec::new();
+    let mut fields = input.split('\0');
+    while let Some(status) = fields.next() {
+        if status.is_empty() {
+            continue;
+        }
+        let kind = match status.as_bytes()[0] {
+            b'R' => RenameKind::Renamed,
+            b'C' => RenameKind::Copied,
+            _ => {
+                fields.next();
+                continue;
+            }
+        };
+        let similarity = status[1..]
+            .parse()
+            .with_context(|| format!("invalid similarity score in {status:?}"))?;
+        let old_path = fields.next().context("no source path")?;
+        let new_path = fields.next().context("no destination path")?;
+        renamed_paths.push(RenamedPath {
+            old_path: RepoPath::from(old_path),
+            new_path: RepoPath::from(new_path),
+            kind,
+            similarity,
+        });
+    }
+    Ok(renamed_paths)
+}
+
+fn parse_submodule_status(input: &str) -> Result<Vec<Submodule>> {
+    let mut submodules = Vec::new();
+    for line in input.lines() {
+        if line.is_empty() {
+            continue;
+        }
+        let mut chars = line.chars();
+        let status = match chars.next().context("no submodule status")? {
+            ' ' => SubmoduleStatus::UpToDate,
+            '-' => SubmoduleStatus::Uninitialized,
+            '+' => SubmoduleStatus::OutOfSync,
+            'U' => SubmoduleStatus::Conflicted,
+            other => bail!("unexpected submodule status {other:?}"),
+        };
+        let mut fields = chars.as_str().splitn(2, ' ');
+        let sha = fields.next().context("no submodule sha")?;
+        let rest = fields.next().context("no submodule path")?;
+        // The path is followed by the output of `git describe` in parentheses, when available.
+        let path = match rest.rsplit_once(" (") {
+            Some((path, describe)) if describe.ends_with(')') => path,
+            _ => rest,
+        };
+        submodules.push(Submodule {
+            path: RepoPath::new(PathBuf::from(path)),
+            sha: sha.to_string().into(),
+            status,
+        });
+    }
+    Ok(submodules)
+}
+
 fn parse_upstream_track(upstream_track: &str) -> Result<UpstreamTracking> {
     if upstream_track == "" {
         return Ok(UpstreamTracking::Tracked(UpstreamTrackingStatus {
@@ -1893,6 +2465,13 @@ fn parse_upstream_track(upstream_track: &str) -> Result<UpstreamTracking> {
     }))
 }
 
+/// Rejects refs that git would parse as options, since refs can come from collaborators.
+pub fn validate_ref(git_ref: &str) -> Result<()> {
+    anyhow::ensure!(!git_ref.is_empty(), "empty git ref");
+    anyhow::ensure!(!git_ref.starts_with('-'), "invalid git ref: {git_ref}");
+    Ok(())
+}
+
 fn check_path_to_repo_path_errors(relative_file_path: &Path) -> Result<()> {
     match relative_file_path.components().next() {
         None => anyhow::bail!("repo path should not be empty"),
@@ -2061,6 +2640,54 @@ mod tests {
         // );
     }
 
+    #[gpui::test]
+    async fn test_diff_refs(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo =
+            RealGitRepository::new(&repo_dir.path().join(".git"), None, cx.executor()).unwrap();
+
+        for content in ["one", "two"] {
+            smol::fs::write(repo_dir.path().join("file"), content)
+                .await
+                .unwrap();
+            repo.stage_paths(
+                vec![RepoPath::from_str("file")],
+                Arc::new(HashMap::default()),
+            )
+            .await
+            .unwrap();
+            repo.commit(
+                content.into(),
+                None,
+                CommitOptions::default(),
+                Arc::new(checkpoint_author_envs()),
+            )
+            .await
+            .unwrap();
+        }
+
+        let diff = repo
+            .diff_refs("HEAD~1".into(), "HEAD".into(), cx.to_async())
+            .await
+            .unwrap();
+        assert_eq!(diff.files.len(), 1);
+        assert_eq!(diff.files[0].path, RepoPath::from_str("file"));
+        assert_eq!(diff.files[0].old_text.as_deref(), Some("one"));
+        assert_eq!(diff.files[0].new_text.as_deref(), Some("two"));
+
+        let output_path = repo_dir.path().join("injected");
+        let injected = format!("--output={}", output_path.display());
+        assert!(
+            repo.diff_refs(injected, "HEAD".into(), cx.to_async())
+                .await
+                .is_err()
+        );
+        assert!(!output_path.exists());
+    }
+
     #[gpui::test]
     async fn test_compare_checkpoints(cx: &mut TestAppContext) {
         cx.executor().allow_parking();
@@ -2181,6 +2808,80 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_file_history_parsing() {
+        #[allow(clippy::octal_escapes)]
+        let input = "1b2c3d\0a0a0a0\01733187470\0Rename the thing: again\n\
+                     a0a0a0\0\01733180000\0Initial commit\n";
+        assert_eq!(
+            parse_file_history(input).unwrap(),
+            vec![
+                CommitSummary {
+                    sha: "1b2c3d".into(),
+                    subject: "Rename the thing: again".into(),
+                    commit_timestamp: 1733187470,
+                    has_parent: true,
+                },
+                CommitSummary {
+                    sha: "a0a0a0".into(),
+                    subject: "Initial commit".into(),
+                    commit_timestamp: 1733180000,
+                    has_parent: false,
+                },
+            ]
+        )
+    }
+
+    #[test]
+    fn test_renamed_paths_parsing() {
+        let input = "M\0src/lib.rs\0R087\0src/old.rs\0src/new.rs\0\
+                     C100\0a.txt\0b.txt\0A\0c.txt\0";
+        assert_eq!(
+            parse_renamed_paths(input).unwrap(),
+            vec![
+                RenamedPath {
+                    old_path: "src/old.rs".into(),
+                    new_path: "src/new.rs".into(),
+                    kind: RenameKind::Renamed,
+                    similarity: 87,
+                },
+                RenamedPath {
+                    old_path: "a.txt".into(),
+                    new_path: "b.txt".into(),
+                    kind: RenameKind::Copied,
+                    similarity: 100,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_submodule_status_parsing() {
+        let input = " 4b825dc642cb6eb9a060e54bf8d69288fbee4904 crates/vendored (v1.0.0)\n\
+                     -c3bd9f0a1b2c3d4e5f60718293a4b5c6d7e8f901 docs/theme\n\
+                     +0123456789abcdef0123456789abcdef01234567 extensions/my ext (heads/main)\n";
+        assert_eq!(
+            parse_submodule_status(input).unwrap(),
+            vec![
+                Submodule {
+                    path: RepoPath::new(PathBuf::from("crates/vendored")),
+                    sha: "4b825dc642cb6eb9a060e54bf8d69288fbee4904".into(),
+                    status: SubmoduleStatus::UpToDate,
+                },
+                Submodule {
+                    path: RepoPath::new(PathBuf::from("docs/theme")),
+                    sha: "c3bd9f0a1b2c3d4e5f60718293a4b5c6d7e8f901".into(),
+                    status: SubmoduleStatus::Uninitialized,
+                },
+                Submodule {
+                    path: RepoPath::new(PathBuf::from("extensions/my ext")),
+                    sha: "0123456789abcdef0123456789abcdef01234567".into(),
+                    status: SubmoduleStatus::OutOfSync,
+                },
+            ]
+        )
+    }
+
     impl RealGitRepository {
         /// Force a Git garbage collection on the repository.
         fn gc(&self) -> BoxFuture<Result<()>> {
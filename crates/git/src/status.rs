@@ -385,6 +385,25 @@ impl GitSummary {
         untracked: 0,
         count: 0,
     };
+
+    /// A single-letter abbreviation of the most significant change in this summary,
+    /// as shown next to changed files.
+    pub fn status_letter(&self) -> Option<char> {
+        let tracked = self.index + self.worktree;
+        if self.conflict > 0 {
+            Some('C')
+        } else if tracked.modified > 0 {
+            Some('M')
+        } else if tracked.added > 0 {
+            Some('A')
+        } else if self.untracked > 0 {
+            Some('U')
+        } else if tracked.deleted > 0 {
+            Some('D')
+        } else {
+            None
+        }
+    }
 }
 
 impl From<FileStatus> for GitSummary {
@@ -0,0 +1,380 @@
+use std::str::FromStr;
+use std::sync::Arc;
+
+use anyhow::{Context as _, Result};
+use async_trait::async_trait;
+use extension::{
+    Extension, ExtensionGitHostingProviderProxy, ExtensionHostProxy, GitBuildState,
+    GitHostingProviderManifestEntry,
+};
+use git::{
+    BuildCommitPermalinkParams, BuildPermalinkParams, BuildState, BuildStatus, GitHostingProvider,
+    GitHostingProviderRegistry, ParsedGitRemote, PullRequest, RemoteUrl,
+};
+use gpui::App;
+use http_client::HttpClient;
+use regex::Regex;
+use url::Url;
+use util::ResultExt as _;
+
+pub(crate) fn init(cx: &mut App) {
+    let proxy = ExtensionHostProxy::default_global(cx);
+    proxy.register_git_hosting_provider_proxy(GitHostingProviderRegistryProxy {
+        provider_registry: GitHostingProviderRegistry::global(cx),
+    });
+}
+
+struct GitHostingProviderRegistryProxy {
+    provider_registry: Arc<GitHostingProviderRegistry>,
+}
+
+impl ExtensionGitHostingProviderProxy for GitHostingProviderRegistryProxy {
+    fn register_git_hosting_provider(
+        &self,
+        extension: Arc<dyn Extension>,
+        provider_id: Arc<str>,
+        provider: GitHostingProviderManifestEntry,
+    ) {
+        let extension_id = extension.manifest().id.clone();
+        let build_status_source = provider.build_status.then_some(extension);
+        let Some(provider) =
+            ExtensionGitHostingProvider::new(provider_id.clone(), provider, build_status_source)
+                .with_context(|| format!("invalid git hosting provider {provider_id}"))
+                .log_err()
+        else {
+            return;
+        };
+
+        self.provider_registry.register_extension_provider(
+            extension_id,
+            provider_id,
+            Arc::new(provider),
+        );
+    }
+
+    fn unregister_git_hosting_provider(&self, extension_id: Arc<str>, provider_id: Arc<str>) {
+        self.provider_registry
+            .unregister_extension_provider(&extension_id, &provider_id);
+    }
+}
+
+/// A Git hosting provider whose URLs are described by templates in an extension's manifest.
+pub struct ExtensionGitHostingProvider {
+    id: Arc<str>,
+    name: String,
+    base_url: Url,
+    manifest: GitHostingProviderManifestEntry,
+    pull_request_regex: Option<Regex>,
+    /// The extension to ask for build statuses, if it reports them.
+    build_status_source: Option<Arc<dyn Extension>>,
+}
+
+impl ExtensionGitHostingProvider {
+    pub fn new(
+        id: Arc<str>,
+        manifest: GitHostingProviderManifestEntry,
+        build_status_source: Option<Arc<dyn Extension>>,
+    ) -> Result<Self> {
+        let base_url = Url::parse(&manifest.base_url).context("parsing base_url")?;
+        let pull_request_regex = manifest
+            .pull_request_pattern
+            .as_deref()
+            .map(Regex::new)
+            .transpose()
+            .context("parsing pull_request_pattern")?;
+
+        Ok(Self {
+            id,
+            name: manifest.name.clone(),
+            base_url,
+            manifest,
+            pull_request_regex,
+            build_status_source,
+        })
+    }
+
+    fn expand_url(&self, template: &str, remote: &ParsedGitRemote, vars: &[(&str, &str)]) -> Url {
+        let url = expand_template(
+            template,
+            [
+                ("base_url", self.base_url.as_str().trim_end_matches('/')),
+                ("owner", remote.owner.as_ref()),
+                ("repo", remote.repo.as_ref()),
+            ]
+            .iter()
+            .chain(vars),
+        );
+        Url::parse(&url)
+            .with_context(|| format!("invalid URL {url:?} from {} templates", self.id))
+            .log_err()
+            .unwrap_or_else(|| self.base_url.clone())
+    }
+}
+
+fn expand_template<'a>(
+    template: &str,
+    vars: impl Iterator<Item = &'a (&'a str, &'a str)>,
+) -> String {
+    vars.fold(template.to_string(), |text, (name, value)| {
+        text.replace(&format!("{{{name}}}"), value)
+    })
+}
+
+#[async_trait]
+impl GitHostingProvider for ExtensionGitHostingProvider {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn base_url(&self) -> Url {
+        self.base_url.clone()
+    }
+
+    fn supports_avatars(&self) -> bool {
+        false
+    }
+
+    fn format_line_number(&self, line: u32) -> String {
+        expand_template(
+            &self.manifest.line_fragment,
+            [("line", line.to_string().as_str())].iter(),
+        )
+    }
+
+    fn format_line_numbers(&self, start_line: u32, end_line: u32) -> String {
+        expand_template(
+            &self.manifest.line_range_fragment,
+            [
+                ("start_line", start_line.to_string().as_str()),
+                ("end_line", end_line.to_string().as_str()),
+            ]
+            .iter(),
+        )
+    }
+
+    fn parse_remote_url(&self, url: &str) -> Option<ParsedGitRemote> {
+        let url = RemoteUrl::from_str(url).ok()?;
+
+        if url.host_str()? != self.base_url.host_str()? {
+            return None;
+        }
+
+        let base_path_len = self
+            .base_url
+            .path_segments()
+            .map_or(0, |segments| segments.filter(|s| !s.is_empty()).count());
+        let mut path_segments = url.path_segments()?.skip(base_path_len);
+        let owner = path_segments.next()?;
+        let repo = path_segments.next()?.trim_end_matches(".git");
+
+        Some(ParsedGitRemote {
+            owner: owner.into(),
+            repo: repo.into(),
+        })
+    }
+
+    fn build_commit_permalink(
+        &self,
+        remote: &ParsedGitRemote,
+        params: BuildCommitPermalinkParams,
+    ) -> Url {
+        self.expand_url(&self.manifest.commit_url, remote, &[("sha", params.sha)])
+    }
+
+    fn build_permalink(&self, remote: ParsedGitRemote, params: BuildPermalinkParams) -> Url {
+        let BuildPermalinkParams {
+            sha,
+            path,
+            selection,
+        } = params;
+
+        let mut permalink = self.expand_url(
+            &self.manifest.file_url,
+            &remote,
+            &[("sha", sha), ("path", path)],
+        );
+        permalink.set_fragment(
+            selection
+                .map(|selection| self.line_fragment(&selection))
+                .as_deref(),
+        );
+        permalink
+    }
+
+    fn extract_pull_request(&self, remote: &ParsedGitRemote, message: &str) -> Option<PullRequest> {
+        let template = self.manifest.pull_request_url.as_ref()?;
+        let line = message.lines().next()?;
+        let capture = self.pull_request_regex.as_ref()?.captures(line)?;
+        let number = capture.get(1)?.as_str().parse::<u32>().ok()?;
+        let url = self.expand_url(template, remote, &[("number", number.to_string().as_str())]);
+
+        Some(PullRequest { number, url })
+    }
+
+    fn build_new_pull_request_url(&self, remote: &ParsedGitRemote, branch: &str) -> Option<Url> {
+        let template = self.manifest.new_pull_request_url.as_ref()?;
+        Some(self.expand_url(template, remote, &[("branch", branch)]))
+    }
+
+    fn pull_request_ref(&self, number: u32) -> Option<String> {
+        let template = self.manifest.pull_request_ref.as_ref()?;
+        Some(expand_template(
+            template,
+            [("number", number.to_string().as_str())].iter(),
+        ))
+    }
+
+    async fn build_status(
+        &self,
+        remote: &ParsedGitRemote,
+        branch: &str,
+        _http_client: Arc<dyn HttpClient>,
+    ) -> Result<Option<BuildStatus>> {
+        let Some(extension) = self.build_status_source.as_ref() else {
+            return Ok(None);
+        };
+
+        let status = extension
+            .git_hosting_build_status(
+                self.id.clone(),
+                remote.owner.to_string(),
+                remote.repo.to_string(),
+                branch.to_string(),
+            )
+            .await?;
+
+        Ok(status.map(|status| BuildStatus {
+            state: match status.state {
+                GitBuildState::Pending => BuildState::Pending,
+                GitBuildState::Success => BuildState::Success,
+                GitBuildState::Failure => BuildState::Failure,
+            },
+            url: status.url.and_then(|url| Url::parse(&url).log_err()),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn forgejo() -> ExtensionGitHostingProvider {
+        ExtensionGitHostingProvider::new(
+            "forgejo".into(),
+            GitHostingProviderManifestEntry {
+                name: "Forgejo".into(),
+                base_url: "https://git.example.com/forge".into(),
+                commit_url: "{base_url}/{owner}/{repo}/commit/{sha}".into(),
+                file_url: "{base_url}/{owner}/{repo}/src/commit/{sha}/{path}".into(),
+                line_fragment: "L{line}".into(),
+                line_range_fragment: "L{start_line}-L{end_line}".into(),
+                pull_request_pattern: Some(r"\(#(\d+)\)$".into()),
+                pull_request_url: Some("{base_url}/{owner}/{repo}/pulls/{number}".into()),
+                new_pull_request_url: Some(
+                    "{base_url}/{owner}/{repo}/compare/main...{branch}".into(),
+                ),
+                pull_request_ref: Some("refs/pull/{number}/head".into()),
+                build_status: false,
+            },
+            None,
+        )
+        .unwrap()
+    }
+
+    fn remote() -> ParsedGitRemote {
+        ParsedGitRemote {
+            owner: "zed-industries".into(),
+            repo: "zed".into(),
+        }
+    }
+
+    #[test]
+    fn test_parse_remote_url() {
+        let provider = forgejo();
+
+        assert_eq!(
+            provider.parse_remote_url("https://git.example.com/forge/zed-industries/zed.git"),
+            Some(remote())
+        );
+        assert_eq!(
+            provider.parse_remote_url("git@git.example.com:forge/zed-industries/zed.git"),
+            Some(remote())
+        );
+        assert_eq!(
+            provider.parse_remote_url("https://github.com/zed-industries/zed.git"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_build_permalink_with_multi_line_selection() {
+        let permalink = forgejo().build_permalink(
+            remote(),
+            BuildPermalinkParams {
+                sha: "faa6f979be417239b2e070dbbf6392b909224e0b",
+                path: "crates/editor/src/git/permalink.rs",
+                selection: Some(23..47),
+            },
+        );
+
+        let expected_url = "https://git.example.com/forge/zed-industries/zed/src/commit/faa6f979be417239b2e070dbbf6392b909224e0b/crates/editor/src/git/permalink.rs#L24-L48";
+        assert_eq!(permalink.to_string(), expected_url.to_string())
+    }
+
+    #[test]
+    fn test_extract_pull_request() {
+        let pull_request = forgejo()
+            .extract_pull_request(&remote(), "Fix the frobnicator (#1234)\n\nDetails")
+            .unwrap();
+
+        assert_eq!(pull_request.number, 1234);
+        assert_eq!(
+            pull_request.url.to_string(),
+            "https://git.example.com/forge/zed-industries/zed/pulls/1234"
+        );
+    }
+
+    #[test]
+    fn test_build_new_pull_request_url() {
+        let url = forgejo()
+            .build_new_pull_request_url(&remote(), "feature")
+            .unwrap();
+
+        assert_eq!(
+            url.to_string(),
+            "https://git.example.com/forge/zed-industries/zed/compare/main...feature"
+        );
+    }
+
+    #[test]
+    fn test_pull_request_ref() {
+        assert_eq!(
+            forgejo().pull_request_ref(1234).as_deref(),
+            Some("refs/pull/1234/head")
+        );
+    }
+
+    #[test]
+    fn test_extension_providers_are_keyed_by_extension() {
+        let registry = GitHostingProviderRegistry::new();
+        registry.register_extension_provider("first".into(), "forgejo".into(), Arc::new(forgejo()));
+        registry.register_extension_provider(
+            "second".into(),
+            "forgejo".into(),
+            Arc::new(forgejo()),
+        );
+        registry.register_extension_provider(
+            "second".into(),
+            "forgejo".into(),
+            Arc::new(forgejo()),
+        );
+        assert_eq!(registry.list_hosting_providers().len(), 2);
+
+        registry.unregister_extension_provider("first", "forgejo");
+        assert_eq!(registry.list_hosting_providers().len(), 1);
+        registry.unregister_extension_provider("second", "forgejo");
+        assert_eq!(registry.list_hosting_providers().len(), 0);
+    }
+}
@@ -1,3 +1,4 @@
+mod extension_git_hosting_provider;
 mod providers;
 mod settings;
 
@@ -11,12 +12,14 @@ use gpui::App;
 use url::Url;
 use util::maybe;
 
+pub use crate::extension_git_hosting_provider::ExtensionGitHostingProvider;
 pub use crate::providers::*;
 pub use crate::settings::*;
 
 /// Initializes the Git hosting providers.
 pub fn init(cx: &mut App) {
     crate::settings::init(cx);
+    crate::extension_git_hosting_provider::init(cx);
 
     let provider_registry = GitHostingProviderRegistry::global(cx);
     provider_registry.register_hosting_provider(Arc::new(Bitbucket::public_instance()));
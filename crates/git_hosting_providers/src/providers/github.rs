@@ -216,6 +216,24 @@ impl GitHostingProvider for Github {
         Some(PullRequest { number, url })
     }
 
+    fn build_new_pull_request_url(&self, remote: &ParsedGitRemote, branch: &str) -> Option<Url> {
+        let ParsedGitRemote { owner, repo } = remote;
+        let mut url = self
+            .base_url()
+            .join(&format!("{owner}/{repo}/compare/"))
+            .ok()?;
+        url.path_segments_mut()
+            .ok()?
+            .pop_if_empty()
+            .extend(branch.split('/'));
+        url.set_query(Some("expand=1"));
+        Some(url)
+    }
+
+    fn pull_request_ref(&self, number: u32) -> Option<String> {
+        Some(format!("refs/pull/{number}/head"))
+    }
+
     async fn commit_author_avatar_url(
         &self,
         repo_owner: &str,
@@ -429,6 +447,22 @@ mod tests {
         assert_eq!(permalink.to_string(), expected_url.to_string())
     }
 
+    #[test]
+    fn test_build_github_new_pull_request_url() {
+        let url = Github::public_instance().build_new_pull_request_url(
+            &ParsedGitRemote {
+                owner: "zed-industries".into(),
+                repo: "zed".into(),
+            },
+            "feature/pr-#1",
+        );
+
+        assert_eq!(
+            url.unwrap().as_str(),
+            "https://github.com/zed-industries/zed/compare/feature/pr-%231?expand=1"
+        );
+    }
+
     #[test]
     fn test_github_pull_requests() {
         let remote = ParsedGitRemote {
@@ -122,6 +122,21 @@ impl GitHostingProvider for Gitlab {
         );
         permalink
     }
+
+    fn build_new_pull_request_url(&self, remote: &ParsedGitRemote, branch: &str) -> Option<Url> {
+        let ParsedGitRemote { owner, repo } = remote;
+        let mut url = self
+            .base_url()
+            .join(&format!("{owner}/{repo}/-/merge_requests/new"))
+            .ok()?;
+        url.query_pairs_mut()
+            .append_pair("merge_request[source_branch]", branch);
+        Some(url)
+    }
+
+    fn pull_request_ref(&self, number: u32) -> Option<String> {
+        Some(format!("refs/merge-requests/{number}/head"))
+    }
 }
 
 #[cfg(test)]
@@ -297,4 +312,20 @@ mod tests {
         let expected_url = "https://gitlab-instance.big-co.com/zed-industries/zed/-/blob/b2efec9824c45fcc90c9a7eb107a50d1772a60aa/crates/zed/src/main.rs";
         assert_eq!(permalink.to_string(), expected_url.to_string())
     }
+
+    #[test]
+    fn test_build_gitlab_new_pull_request_url() {
+        let url = Gitlab::public_instance().build_new_pull_request_url(
+            &ParsedGitRemote {
+                owner: "zed-industries".into(),
+                repo: "zed".into(),
+            },
+            "feature/widgets",
+        );
+
+        assert_eq!(
+            url.unwrap().as_str(),
+            "https://gitlab.com/zed-industries/zed/-/merge_requests/new?merge_request%5Bsource_branch%5D=feature%2Fwidgets"
+        );
+    }
 }
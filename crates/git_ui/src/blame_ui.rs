@@ -1,11 +1,12 @@
 use crate::{
     commit_tooltip::{CommitAvatar, CommitDetails, CommitTooltip},
     commit_view::CommitView,
+    git_panel::GitPanel,
 };
 use editor::{BlameRenderer, Editor, hover_markdown_style};
 use git::{
     blame::{BlameEntry, ParsedCommitMessage},
-    repository::CommitSummary,
+    repository::{ApplyCommitMode, CommitSummary},
 };
 use gpui::{
     ClipboardItem, Entity, Hsla, MouseButton, ScrollHandle, Subscription, TextStyle, WeakEntity,
@@ -69,10 +70,14 @@ impl BlameRenderer for GitBlameRenderer {
                 .on_mouse_down(MouseButton::Right, {
                     let blame_entry = blame_entry.clone();
                     let details = details.clone();
+                    let repository = repository.clone();
+                    let workspace = workspace.clone();
                     move |event, window, cx| {
                         deploy_blame_entry_context_menu(
                             &blame_entry,
                             details.as_ref(),
+                            repository.clone(),
+                            workspace.clone(),
                             editor.clone(),
                             event.position,
                             window,
@@ -387,11 +392,37 @@ impl BlameRenderer for GitBlameRenderer {
 fn deploy_blame_entry_context_menu(
     blame_entry: &BlameEntry,
     details: Option<&ParsedCommitMessage>,
+    repository: Entity<Repository>,
+    workspace: WeakEntity<Workspace>,
     editor: Entity<Editor>,
     position: gpui::Point<Pixels>,
     window: &mut Window,
     cx: &mut App,
 ) {
+    let commit = CommitSummary {
+        sha: blame_entry.sha.to_string().into(),
+        subject: blame_entry.summary.clone().unwrap_or_default().into(),
+        commit_timestamp: blame_entry.committer_time.unwrap_or_default(),
+        has_parent: true,
+    };
+    let apply_commit = move |mode: ApplyCommitMode| {
+        let repository = repository.clone();
+        let workspace = workspace.clone();
+        let commit = commit.clone();
+        move |window: &mut Window, cx: &mut App| {
+            workspace
+                .update(cx, |workspace, cx| {
+                    let Some(panel) = workspace.panel::<GitPanel>(cx) else {
+                        return;
+                    };
+                    panel.update(cx, |panel, cx| {
+                        panel.apply_commit(repository.clone(), commit.clone(), mode, window, cx)
+                    });
+                })
+                .ok();
+        }
+    };
+
     let context_menu = ContextMenu::build(window, cx, move |menu, _, _| {
         let sha = format!("{}", blame_entry.sha);
         menu.on_blur_subscription(Subscription::new(|| {}))
@@ -406,6 +437,13 @@ fn deploy_blame_entry_context_menu(
                     })
                 },
             )
+            .separator()
+            .entry(
+                "Cherry-pick commit",
+                None,
+                apply_commit(ApplyCommitMode::CherryPick),
+            )
+            .entry("Revert commit", None, apply_commit(ApplyCommitMode::Revert))
     });
 
     editor.update(cx, move |editor, cx| {
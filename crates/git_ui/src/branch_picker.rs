@@ -1,8 +1,10 @@
 use anyhow::Context as _;
+use askpass::AskPassDelegate;
 use fuzzy::StringMatchCandidate;
 
-use collections::HashSet;
+use collections::{HashMap, HashSet};
 use git::repository::Branch;
+use git::{BuildState, BuildStatus};
 use gpui::{
     App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, InteractiveElement,
     IntoElement, Modifiers, ModifiersChangedEvent, ParentElement, Render, SharedString, Styled,
@@ -13,11 +15,13 @@ use project::git_store::Repository;
 use std::sync::Arc;
 use time::OffsetDateTime;
 use time_format::format_local_timestamp;
-use ui::{HighlightedLabel, ListItem, ListItemSpacing, prelude::*};
+use ui::{HighlightedLabel, ListItem, ListItemSpacing, Tooltip, prelude::*};
 use util::ResultExt;
 use workspace::notifications::DetachAndPromptErr;
 use workspace::{ModalView, Workspace};
 
+use crate::askpass_modal::AskPassModal;
+
 pub fn register(workspace: &mut Workspace) {
     workspace.register_action(open);
     workspace.register_action(switch);
@@ -91,6 +95,7 @@ impl BranchList {
             .clone()
             .map(|repository| repository.update(cx, |repository, _| repository.branches()));
 
+        let status_repository = repository.clone();
         cx.spawn_in(window, async move |this, cx| {
             let mut all_branches = all_branches_request
                 .context("No active repository")?
@@ -122,6 +127,19 @@ impl BranchList {
                 })
                 .await;
 
+            let branches_with_upstream = all_branches
+                .iter()
+                .filter(|branch| {
+                    !branch.is_remote()
+                        && branch
+                            .upstream
+                            .as_ref()
+                            .is_some_and(|upstream| upstream.is_remote())
+                })
+                .take(RECENT_BRANCHES_COUNT)
+                .cloned()
+                .collect::<Vec<_>>();
+
             this.update_in(cx, |this, window, cx| {
                 this.picker.update(cx, |picker, cx| {
                     picker.delegate.all_branches = Some(all_branches);
@@ -129,6 +147,36 @@ impl BranchList {
                 })
             })?;
 
+            let Some(repository) = status_repository else {
+                return anyhow::Ok(());
+            };
+            let http_client = cx.update(|_, cx| cx.http_client())?;
+            for branch in branches_with_upstream {
+                let remote_request = repository.update(cx, |repository, _| {
+                    repository.upstream_hosted_remote(&branch)
+                })?;
+                let Some((remote, remote_branch)) = remote_request.await?? else {
+                    continue;
+                };
+                let Some(status) = remote
+                    .build_status(&remote_branch, http_client.clone())
+                    .await
+                    .log_err()
+                    .flatten()
+                else {
+                    continue;
+                };
+                this.update(cx, |this, cx| {
+                    this.picker.update(cx, |picker, cx| {
+                        picker
+                            .delegate
+                            .build_statuses
+                            .insert(branch.ref_name.clone(), status);
+                        cx.notify();
+                    })
+                })?;
+            }
+
             anyhow::Ok(())
         })
         .detach_and_log_err(cx);
@@ -182,11 +230,15 @@ impl Render for BranchList {
     }
 }
 
+const RECENT_BRANCHES_COUNT: usize = 10;
+
 #[derive(Debug, Clone)]
 struct BranchEntry {
     branch: Branch,
     positions: Vec<usize>,
     is_new: bool,
+    /// The number of the pull request to check out, for entries offering to do so.
+    pull_request: Option<u32>,
 }
 
 pub struct BranchListDelegate {
@@ -197,6 +249,8 @@ pub struct BranchListDelegate {
     selected_index: usize,
     last_query: String,
     modifiers: Modifiers,
+    /// The latest CI build statuses of branches' upstreams, keyed by ref name.
+    build_statuses: HashMap<SharedString, BuildStatus>,
 }
 
 impl BranchListDelegate {
@@ -209,6 +263,7 @@ impl BranchListDelegate {
             selected_index: 0,
             last_query: Default::default(),
             modifiers: Default::default(),
+            build_statuses: HashMap::default(),
         }
     }
 
@@ -239,6 +294,59 @@ impl BranchListDelegate {
         });
         cx.emit(DismissEvent);
     }
+
+    fn checkout_pull_request(
+        &self,
+        number: u32,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) {
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        let askpass = askpass_delegate(format!("git fetch pull request #{number}"), window, cx);
+        cx.spawn(async move |_, cx| {
+            repo.update(cx, |repo, _| repo.checkout_pull_request(number, askpass))?
+                .await??;
+            Ok(())
+        })
+        .detach_and_prompt_err(
+            "Failed to check out pull request",
+            window,
+            cx,
+            |e, _, _| Some(e.to_string()),
+        );
+        cx.emit(DismissEvent);
+    }
+}
+
+/// Returns a delegate that prompts for credentials in a modal on the window's workspace.
+fn askpass_delegate(
+    operation: impl Into<SharedString>,
+    window: &mut Window,
+    cx: &mut App,
+) -> AskPassDelegate {
+    let operation = operation.into();
+    let window = window.window_handle();
+    AskPassDelegate::new(&mut cx.to_async(), move |prompt, tx, cx| {
+        window
+            .update(cx, |_, window, cx| {
+                let Some(workspace) = window.root::<Workspace>().flatten() else {
+                    return;
+                };
+                workspace.update(cx, |workspace, cx| {
+                    workspace.toggle_modal(window, cx, |window, cx| {
+                        AskPassModal::new(operation.clone(), prompt.into(), tx, window, cx)
+                    });
+                })
+            })
+            .ok();
+    })
+}
+
+/// Parses a query like `#123` into the number of the pull request it refers to.
+fn parse_pull_request_number(query: &str) -> Option<u32> {
+    query.trim().strip_prefix('#')?.parse().ok()
 }
 
 impl PickerDelegate for BranchListDelegate {
@@ -282,7 +390,6 @@ impl PickerDelegate for BranchListDelegate {
             return Task::ready(());
         };
 
-        const RECENT_BRANCHES_COUNT: usize = 10;
         cx.spawn_in(window, async move |picker, cx| {
             let mut matches: Vec<BranchEntry> = if query.is_empty() {
                 all_branches
@@ -293,6 +400,7 @@ impl PickerDelegate for BranchListDelegate {
                         branch,
                         positions: Vec::new(),
                         is_new: false,
+                        pull_request: None,
                     })
                     .collect()
             } else {
@@ -315,13 +423,32 @@ impl PickerDelegate for BranchListDelegate {
                     branch: all_branches[candidate.candidate_id].clone(),
                     positions: candidate.positions,
                     is_new: false,
+                    pull_request: None,
                 })
                 .collect()
             };
             picker
                 .update(cx, |picker, _| {
+                    let pull_request = parse_pull_request_number(&query);
+                    if let Some(number) = pull_request {
+                        matches.insert(
+                            0,
+                            BranchEntry {
+                                branch: Branch {
+                                    ref_name: format!("refs/heads/pr-{number}").into(),
+                                    is_head: false,
+                                    upstream: None,
+                                    most_recent_commit: None,
+                                },
+                                positions: Vec::new(),
+                                is_new: false,
+                                pull_request: Some(number),
+                            },
+                        );
+                    }
                     #[allow(clippy::nonminimal_bool)]
-                    if !query.is_empty()
+                    if pull_request.is_none()
+                        && !query.is_empty()
                         && !matches
                             .first()
                             .is_some_and(|entry| entry.branch.name() == query)
@@ -336,6 +463,7 @@ impl PickerDelegate for BranchListDelegate {
                             },
                             positions: Vec::new(),
                             is_new: true,
+                            pull_request: None,
                         })
                     }
                     let delegate = &mut picker.delegate;
@@ -356,6 +484,10 @@ impl PickerDelegate for BranchListDelegate {
         let Some(entry) = self.matches.get(self.selected_index()) else {
             return;
         };
+        if let Some(number) = entry.pull_request {
+            self.checkout_pull_request(number, window, cx);
+            return;
+        }
         if entry.is_new {
             self.create_branch(entry.branch.name().to_owned().into(), window, cx);
             return;
@@ -462,7 +594,11 @@ impl PickerDelegate for BranchListDelegate {
                                 .gap_2()
                                 .justify_between()
                                 .child(div().flex_shrink().overflow_x_hidden().child(
-                                    if entry.is_new {
+                                    if let Some(number) = entry.pull_request {
+                                        Label::new(format!("Check out pull request #{number}…"))
+                                            .single_line()
+                                            .into_any_element()
+                                    } else if entry.is_new {
                                         Label::new(format!(
                                             "Create branch \"{}\"…",
                                             entry.branch.name()
@@ -478,6 +614,10 @@ impl PickerDelegate for BranchListDelegate {
                                         .into_any_element()
                                     },
                                 ))
+                                .when_some(
+                                    self.build_statuses.get(&entry.branch.ref_name),
+                                    |el, status| el.child(render_build_status(ix, status)),
+                                )
                                 .when_some(commit_time, |el, commit_time| {
                                     el.child(
                                         Label::new(commit_time)
@@ -489,7 +629,9 @@ impl PickerDelegate for BranchListDelegate {
                         )
                         .when(self.style == BranchListStyle::Modal, |el| {
                             el.child(div().max_w_96().child({
-                                let message = if entry.is_new {
+                                let message = if entry.pull_request.is_some() {
+                                    format!("into {}", entry.branch.name())
+                                } else if entry.is_new {
                                     if let Some(current_branch) =
                                         self.repo.as_ref().and_then(|repo| {
                                             repo.read(cx).branch.as_ref().map(|b| b.name())
@@ -516,3 +658,15 @@ impl PickerDelegate for BranchListDelegate {
         None
     }
 }
+
+fn render_build_status(ix: usize, status: &BuildStatus) -> impl IntoElement {
+    let (icon, color, description) = match status.state {
+        BuildState::Pending => (IconName::CountdownTimer, Color::Muted, "Build in progress"),
+        BuildState::Success => (IconName::Check, Color::Success, "Build succeeded"),
+        BuildState::Failure => (IconName::XCircle, Color::Error, "Build failed"),
+    };
+    div()
+        .id(("build-status", ix))
+        .child(Icon::new(icon).size(IconSize::Small).color(color))
+        .tooltip(Tooltip::text(description))
+}
@@ -0,0 +1,262 @@
+use crate::git_panel_settings::CommitMessageLintSettings;
+
+/// The types defined by the Conventional Commits specification and the
+/// Angular convention it grew out of.
+const CONVENTIONAL_COMMIT_TYPES: &[&str] = &[
+    "build", "chore", "ci", "docs", "feat", "fix", "perf", "refactor", "revert", "style", "test",
+];
+
+/// Verbs that commit subjects commonly start with. Only these are checked for
+/// the imperative mood, since guessing at arbitrary words flags too many nouns.
+const COMMON_VERBS: &[&str] = &[
+    "add",
+    "allow",
+    "avoid",
+    "bump",
+    "change",
+    "clean",
+    "correct",
+    "create",
+    "delete",
+    "disable",
+    "document",
+    "enable",
+    "ensure",
+    "extract",
+    "fix",
+    "handle",
+    "hide",
+    "implement",
+    "improve",
+    "introduce",
+    "make",
+    "merge",
+    "move",
+    "optimize",
+    "prevent",
+    "refactor",
+    "release",
+    "remove",
+    "rename",
+    "replace",
+    "revert",
+    "show",
+    "simplify",
+    "support",
+    "update",
+    "upgrade",
+    "use",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommitMessageWarning {
+    SubjectTooLong { length: usize, max_length: usize },
+    NotImperative { word: String, imperative: String },
+    MissingConventionalType,
+    UnknownConventionalType { commit_type: String },
+}
+
+impl CommitMessageWarning {
+    pub fn message(&self) -> String {
+        match self {
+            CommitMessageWarning::SubjectTooLong { length, max_length } => {
+                format!("Subject is {length} characters long; keep it to {max_length}")
+            }
+            CommitMessageWarning::NotImperative { word, imperative } => {
+                format!("Use the imperative mood: \"{imperative}\" rather than \"{word}\"")
+            }
+            CommitMessageWarning::MissingConventionalType => {
+                "Start the subject with a type, such as \"feat:\" or \"fix:\"".to_string()
+            }
+            CommitMessageWarning::UnknownConventionalType { commit_type } => format!(
+                "\"{commit_type}\" isn't a conventional commit type; use one of {}",
+                CONVENTIONAL_COMMIT_TYPES.join(", ")
+            ),
+        }
+    }
+}
+
+pub fn lint_commit_message(
+    message: &str,
+    settings: &CommitMessageLintSettings,
+) -> Vec<CommitMessageWarning> {
+    let Some(subject) = message.lines().next().map(str::trim_end) else {
+        return Vec::new();
+    };
+    if subject.trim().is_empty() {
+        return Vec::new();
+    }
+
+    let mut warnings = Vec::new();
+    let length = subject.chars().count();
+    if length > settings.max_subject_length {
+        warnings.push(CommitMessageWarning::SubjectTooLong {
+            length,
+            max_length: settings.max_subject_length,
+        });
+    }
+
+    let description = if settings.conventional_commits {
+        match split_conventional_type(subject) {
+            Some((commit_type, description)) => {
+                if !CONVENTIONAL_COMMIT_TYPES.contains(&commit_type.to_lowercase().as_str()) {
+                    warnings.push(CommitMessageWarning::UnknownConventionalType {
+                        commit_type: commit_type.to_string(),
+                    });
+                }
+                description
+            }
+            None => {
+                warnings.push(CommitMessageWarning::MissingConventionalType);
+                subject
+            }
+        }
+    } else {
+        subject
+    };
+
+    if let Some(word) = description.split_whitespace().next() {
+        if let Some(imperative) = imperative_form(word) {
+            warnings.push(CommitMessageWarning::NotImperative {
+                word: word.to_string(),
+                imperative,
+            });
+        }
+    }
+
+    warnings
+}
+
+/// Splits `type(scope)!: description` into the type and the description.
+fn split_conventional_type(subject: &str) -> Option<(&str, &str)> {
+    let (prefix, description) = subject.split_once(':')?;
+    let prefix = prefix.strip_suffix('!').unwrap_or(prefix);
+    let commit_type = match prefix.split_once('(') {
+        Some((commit_type, scope)) => {
+            scope.strip_suffix(')')?;
+            commit_type
+        }
+        None => prefix,
+    };
+    if commit_type.is_empty() || !commit_type.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    Some((commit_type, description.trim_start()))
+}
+
+/// Returns the imperative form of `word` if it's a past tense, gerund, or third
+/// person form of a common verb, capitalized like `word`.
+fn imperative_form(word: &str) -> Option<String> {
+    let lowercase = word.to_lowercase();
+    let verb = COMMON_VERBS.iter().find(|verb| {
+        let stem = verb.strip_suffix('e').unwrap_or(verb);
+        let last = verb.chars().last();
+        [
+            format!("{verb}s"),
+            format!("{verb}es"),
+            format!("{verb}d"),
+            format!("{verb}ed"),
+            format!("{stem}ing"),
+            // Doubled final consonant, as in "bumped" or "fixed" vs. "setting".
+            last.map(|c| format!("{verb}{c}ed")).unwrap_or_default(),
+            last.map(|c| format!("{verb}{c}ing")).unwrap_or_default(),
+        ]
+        .contains(&lowercase)
+    })?;
+
+    let mut chars = verb.chars();
+    let first = chars.next()?;
+    let imperative = if word.starts_with(|c: char| c.is_uppercase()) {
+        first.to_uppercase().chain(chars).collect()
+    } else {
+        verb.to_string()
+    };
+    Some(imperative)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(conventional_commits: bool) -> CommitMessageLintSettings {
+        CommitMessageLintSettings {
+            conventional_commits,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_subject_length() {
+        let subject = "a".repeat(73);
+        assert_eq!(
+            lint_commit_message(&format!("{subject}\n\nbody"), &settings(false)),
+            vec![CommitMessageWarning::SubjectTooLong {
+                length: 73,
+                max_length: 72
+            }]
+        );
+        assert_eq!(
+            lint_commit_message(&format!("Add a\n\n{}", "b".repeat(100)), &settings(false)),
+            vec![]
+        );
+        assert_eq!(lint_commit_message("", &settings(true)), vec![]);
+    }
+
+    #[test]
+    fn test_imperative_mood() {
+        let warning = |word: &str, imperative: &str| CommitMessageWarning::NotImperative {
+            word: word.into(),
+            imperative: imperative.into(),
+        };
+        assert_eq!(
+            lint_commit_message("Fixed the crash", &settings(false)),
+            vec![warning("Fixed", "Fix")]
+        );
+        assert_eq!(
+            lint_commit_message("Adds support for Lua", &settings(false)),
+            vec![warning("Adds", "Add")]
+        );
+        assert_eq!(
+            lint_commit_message("Making it faster", &settings(false)),
+            vec![warning("Making", "Make")]
+        );
+        assert_eq!(
+            lint_commit_message("fix: bumped the version", &settings(true)),
+            vec![warning("bumped", "bump")]
+        );
+        assert_eq!(
+            lint_commit_message("Fix the crash", &settings(false)),
+            vec![]
+        );
+        assert_eq!(
+            lint_commit_message("Settings overhaul", &settings(false)),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn test_conventional_commits() {
+        assert_eq!(
+            lint_commit_message("feat(git): add commit linting", &settings(true)),
+            vec![]
+        );
+        assert_eq!(
+            lint_commit_message("fix!: drop the old API", &settings(true)),
+            vec![]
+        );
+        assert_eq!(
+            lint_commit_message("Add commit linting", &settings(true)),
+            vec![CommitMessageWarning::MissingConventionalType]
+        );
+        assert_eq!(
+            lint_commit_message("feature: add commit linting", &settings(true)),
+            vec![CommitMessageWarning::UnknownConventionalType {
+                commit_type: "feature".into()
+            }]
+        );
+        assert_eq!(
+            lint_commit_message("Add commit linting", &settings(false)),
+            vec![]
+        );
+    }
+}
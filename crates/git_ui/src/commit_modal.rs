@@ -291,7 +291,9 @@ impl CommitModal {
             tooltip,
             commit_label,
             co_authors,
+            signoff,
             generate_commit_message,
+            commit_message_warnings,
             active_repo,
             is_amend_pending,
             has_previous_commit,
@@ -299,7 +301,9 @@ impl CommitModal {
             let (can_commit, tooltip) = git_panel.configure_commit_button(cx);
             let title = git_panel.commit_button_title();
             let co_authors = git_panel.render_co_authors(cx);
+            let signoff = git_panel.render_signoff_button(cx);
             let generate_commit_message = git_panel.render_generate_commit_message_button(cx);
+            let commit_message_warnings = git_panel.render_commit_message_warnings(cx);
             let active_repo = git_panel.active_repository.clone();
             let is_amend_pending = git_panel.amend_pending();
             let has_previous_commit = active_repo
@@ -311,7 +315,9 @@ impl CommitModal {
                 tooltip,
                 title,
                 co_authors,
+                signoff,
                 generate_commit_message,
+                commit_message_warnings,
                 active_repo,
                 is_amend_pending,
                 has_previous_commit,
@@ -384,7 +390,9 @@ impl CommitModal {
                             .child(branch_picker),
                     )
                     .children(generate_commit_message)
-                    .children(co_authors),
+                    .children(signoff)
+                    .children(co_authors)
+                    .children(commit_message_warnings),
             )
             .child(div().flex_1())
             .child(
@@ -418,7 +426,10 @@ impl CommitModal {
                                     this.git_panel.update(cx, |git_panel, cx| {
                                         git_panel.set_amend_pending(false, cx);
                                         git_panel.commit_changes(
-                                            CommitOptions { amend: true },
+                                            CommitOptions {
+                                                amend: true,
+                                                ..Default::default()
+                                            },
                                             window,
                                             cx,
                                         );
@@ -444,7 +455,7 @@ impl CommitModal {
                                     telemetry::event!("Git Committed", source = "Git Modal");
                                     this.git_panel.update(cx, |git_panel, cx| {
                                         git_panel.commit_changes(
-                                            CommitOptions { amend: false },
+                                            CommitOptions::default(),
                                             window,
                                             cx,
                                         )
@@ -504,7 +515,7 @@ impl CommitModal {
                                             );
                                             this.git_panel.update(cx, |git_panel, cx| {
                                                 git_panel.commit_changes(
-                                                    CommitOptions { amend: false },
+                                                    CommitOptions::default(),
                                                     window,
                                                     cx,
                                                 )
@@ -533,7 +544,7 @@ impl CommitModal {
         }
         telemetry::event!("Git Committed", source = "Git Modal");
         self.git_panel.update(cx, |git_panel, cx| {
-            git_panel.commit_changes(CommitOptions { amend: false }, window, cx)
+            git_panel.commit_changes(CommitOptions::default(), window, cx)
         });
         cx.emit(DismissEvent);
     }
@@ -558,7 +569,14 @@ impl CommitModal {
             telemetry::event!("Git Amended", source = "Git Modal");
             self.git_panel.update(cx, |git_panel, cx| {
                 git_panel.set_amend_pending(false, cx);
-                git_panel.commit_changes(CommitOptions { amend: true }, window, cx);
+                git_panel.commit_changes(
+                    CommitOptions {
+                        amend: true,
+                        ..Default::default()
+                    },
+                    window,
+                    cx,
+                );
             });
             cx.emit(DismissEvent);
         }
@@ -1,17 +1,19 @@
+use crate::{git_panel_settings::GitPanelSettings, project_diff::diff_hunk_ranges};
 use anyhow::{Context as _, Result};
 use buffer_diff::{BufferDiff, BufferDiffSnapshot};
 use editor::{Editor, EditorEvent, MultiBuffer};
-use git::repository::{CommitDetails, CommitDiff, CommitSummary, RepoPath};
+use git::repository::{CommitDetails, CommitDiff, CommitFile, CommitSummary, RepoPath};
 use gpui::{
     AnyElement, AnyView, App, AppContext as _, AsyncApp, Context, Entity, EventEmitter,
-    FocusHandle, Focusable, IntoElement, Render, WeakEntity, Window,
+    FocusHandle, Focusable, IntoElement, Render, Task, WeakEntity, Window,
 };
 use language::{
-    Anchor, Buffer, Capability, DiskState, File, LanguageRegistry, LineEnding, OffsetRangeExt as _,
-    Point, Rope, TextBuffer,
+    Buffer, Capability, DiskState, File, LanguageRegistry, LineEnding, OffsetRangeExt as _, Point,
+    Rope, TextBuffer,
 };
 use multi_buffer::PathKey;
 use project::{Project, WorktreeId, git_store::Repository};
+use settings::Settings as _;
 use std::{
     any::{Any, TypeId},
     ffi::OsStr,
@@ -29,6 +31,7 @@ use workspace::{
 
 pub struct CommitView {
     commit: CommitDetails,
+    path: Option<RepoPath>,
     editor: Entity<Editor>,
     multibuffer: Entity<MultiBuffer>,
 }
@@ -44,8 +47,15 @@ struct CommitMetadataFile {
     worktree_id: WorktreeId,
 }
 
+struct FileStats {
+    path: RepoPath,
+    insertions: usize,
+    deletions: usize,
+}
+
 const COMMIT_METADATA_NAMESPACE: u32 = 0;
 const FILE_NAMESPACE: u32 = 1;
+const MAX_STAT_BAR_WIDTH: usize = 40;
 
 impl CommitView {
     pub fn open(
@@ -54,6 +64,47 @@ impl CommitView {
         workspace: WeakEntity<Workspace>,
         window: &mut Window,
         cx: &mut App,
+    ) {
+        Self::open_for_path(commit, None, repo, workspace, window, cx)
+    }
+
+    /// Opens a commit of the workspace's active repository by its SHA, as
+    /// requested by a `zed://git/commit/<sha>` link.
+    pub fn open_sha(
+        sha: String,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Result<()> {
+        let repo = workspace
+            .project()
+            .read(cx)
+            .active_repository(cx)
+            .context("no git repository is open in this workspace")?;
+        let commit = CommitSummary {
+            sha: sha.into(),
+            subject: SharedString::default(),
+            commit_timestamp: 0,
+            has_parent: true,
+        };
+        Self::open(
+            commit,
+            repo.downgrade(),
+            cx.entity().downgrade(),
+            window,
+            cx,
+        );
+        Ok(())
+    }
+
+    /// Opens the changes a commit made, limited to a single file when `path` is provided.
+    pub fn open_for_path(
+        commit: CommitSummary,
+        path: Option<RepoPath>,
+        repo: WeakEntity<Repository>,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut App,
     ) {
         let commit_diff = repo
             .update(cx, |repo, _| repo.load_commit_diff(commit.sha.to_string()))
@@ -61,6 +112,7 @@ impl CommitView {
         let commit_details = repo
             .update(cx, |repo, _| repo.show(commit.sha.to_string()))
             .ok();
+        let ignore_whitespace = GitPanelSettings::get_global(cx).diff.ignore_whitespace;
 
         window
             .spawn(cx, async move |cx| {
@@ -68,6 +120,15 @@ impl CommitView {
                 let commit_diff = commit_diff.log_err()?.log_err()?;
                 let commit_details = commit_details.log_err()?.log_err()?;
                 let repo = repo.upgrade()?;
+                let (commit_diff, stats) = cx
+                    .background_spawn({
+                        let path = path.clone();
+                        async move {
+                            let stats = diff_stats(&commit_diff, path.as_ref(), ignore_whitespace);
+                            (commit_diff, stats)
+                        }
+                    })
+                    .await;
 
                 workspace
                     .update_in(cx, |workspace, window, cx| {
@@ -76,6 +137,8 @@ impl CommitView {
                             CommitView::new(
                                 commit_details,
                                 commit_diff,
+                                stats,
+                                path.clone(),
                                 repo,
                                 project.clone(),
                                 window,
@@ -87,8 +150,11 @@ impl CommitView {
                         pane.update(cx, |pane, cx| {
                             let ix = pane.items().position(|item| {
                                 let commit_view = item.downcast::<CommitView>();
-                                commit_view
-                                    .map_or(false, |view| view.read(cx).commit.sha == commit.sha)
+                                commit_view.map_or(false, |view| {
+                                    let view = view.read(cx);
+                                    view.commit.sha.starts_with(commit.sha.as_ref())
+                                        && view.path == path
+                                })
                             });
                             if let Some(ix) = ix {
                                 pane.activate_item(ix, true, true, window, cx);
@@ -106,12 +172,13 @@ impl CommitView {
     fn new(
         commit: CommitDetails,
         commit_diff: CommitDiff,
+        stats: Vec<FileStats>,
+        path: Option<RepoPath>,
         repository: Entity<Repository>,
         project: Entity<Project>,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) -> Self {
-        let language_registry = project.read(cx).languages().clone();
         let multibuffer = cx.new(|_| MultiBuffer::new(Capability::ReadOnly));
         let editor = cx.new(|cx| {
             let mut editor =
@@ -138,7 +205,7 @@ impl CommitView {
                     0,
                     cx.entity_id().as_non_zero_u64().into(),
                     LineEnding::default(),
-                    format_commit(&commit).into(),
+                    format_commit(&commit, &stats).into(),
                 );
                 metadata_buffer_id = Some(buffer.remote_id());
                 Buffer::build(buffer, Some(file.clone()), Capability::ReadWrite)
@@ -160,61 +227,84 @@ impl CommitView {
             });
         }
 
-        cx.spawn(async move |this, mut cx| {
-            for file in commit_diff.files {
-                let is_deleted = file.new_text.is_none();
-                let new_text = file.new_text.unwrap_or_default();
-                let old_text = file.old_text;
-                let worktree_id = repository
-                    .update(cx, |repository, cx| {
-                        repository
-                            .repo_path_to_project_path(&file.path, cx)
-                            .map(|path| path.worktree_id)
-                            .or(first_worktree_id)
-                    })?
-                    .context("project has no worktrees")?;
-                let file = Arc::new(GitBlob {
-                    path: file.path.clone(),
-                    is_deleted,
-                    worktree_id,
-                }) as Arc<dyn language::File>;
-
-                let buffer = build_buffer(new_text, file, &language_registry, &mut cx).await?;
-                let buffer_diff =
-                    build_buffer_diff(old_text, &buffer, &language_registry, &mut cx).await?;
-
-                this.update(cx, |this, cx| {
-                    this.multibuffer.update(cx, |multibuffer, cx| {
-                        let snapshot = buffer.read(cx).snapshot();
-                        let diff = buffer_diff.read(cx);
-                        let diff_hunk_ranges = diff
-                            .hunks_intersecting_range(Anchor::MIN..Anchor::MAX, &snapshot, cx)
-                            .map(|diff_hunk| diff_hunk.buffer_range.to_point(&snapshot))
-                            .collect::<Vec<_>>();
-                        let path = snapshot.file().unwrap().path().clone();
-                        let _is_newly_added = multibuffer.set_excerpts_for_path(
-                            PathKey::namespaced(FILE_NAMESPACE, path),
-                            buffer,
-                            diff_hunk_ranges,
-                            editor::DEFAULT_MULTIBUFFER_CONTEXT,
-                            cx,
-                        );
-                        multibuffer.add_diff(buffer_diff, cx);
-                    });
-                })?;
-            }
-            anyhow::Ok(())
-        })
-        .detach();
+        let files = commit_diff
+            .files
+            .into_iter()
+            .filter(|file| path.as_ref().is_none_or(|path| *path == file.path))
+            .collect();
+        load_diff_excerpts(files, repository, &project, &multibuffer, cx).detach_and_log_err(cx);
 
         Self {
             commit,
+            path,
             editor,
             multibuffer,
         }
     }
 }
 
+/// Adds an excerpt to `multibuffer` for each of the given files, showing the
+/// hunks that changed between its old and new text.
+pub(crate) fn load_diff_excerpts(
+    files: Vec<CommitFile>,
+    repository: Entity<Repository>,
+    project: &Entity<Project>,
+    multibuffer: &Entity<MultiBuffer>,
+    cx: &mut App,
+) -> Task<Result<()>> {
+    let language_registry = project.read(cx).languages().clone();
+    let first_worktree_id = project
+        .read(cx)
+        .worktrees(cx)
+        .next()
+        .map(|worktree| worktree.read(cx).id());
+    let multibuffer = multibuffer.downgrade();
+    cx.spawn(async move |cx| {
+        for file in files {
+            let is_deleted = file.new_text.is_none();
+            let new_text = file.new_text.unwrap_or_default();
+            let old_text = file.old_text;
+            let worktree_id = repository
+                .update(cx, |repository, cx| {
+                    repository
+                        .repo_path_to_project_path(&file.path, cx)
+                        .map(|path| path.worktree_id)
+                        .or(first_worktree_id)
+                })?
+                .context("project has no worktrees")?;
+            let file = Arc::new(GitBlob {
+                path: file.path.clone(),
+                is_deleted,
+                worktree_id,
+            }) as Arc<dyn language::File>;
+
+            let buffer = build_buffer(new_text, file, &language_registry, cx).await?;
+            let buffer_diff = build_buffer_diff(old_text, &buffer, &language_registry, cx).await?;
+
+            multibuffer.update(cx, |multibuffer, cx| {
+                let diff_settings = GitPanelSettings::get_global(cx).diff;
+                let snapshot = buffer.read(cx).snapshot();
+                let diff = buffer_diff.read(cx);
+                let diff_hunk_ranges =
+                    diff_hunk_ranges(diff, &snapshot, diff_settings.ignore_whitespace, cx)
+                        .into_iter()
+                        .map(|range| range.to_point(&snapshot))
+                        .collect::<Vec<_>>();
+                let path = snapshot.file().unwrap().path().clone();
+                let _is_newly_added = multibuffer.set_excerpts_for_path(
+                    PathKey::namespaced(FILE_NAMESPACE, path),
+                    buffer,
+                    diff_hunk_ranges,
+                    diff_settings.context_lines,
+                    cx,
+                );
+                multibuffer.add_diff(buffer_diff, cx);
+            })?;
+        }
+        anyhow::Ok(())
+    })
+}
+
 impl language::File for GitBlob {
     fn as_local(&self) -> Option<&dyn language::LocalFile> {
         None
@@ -361,7 +451,90 @@ async fn build_buffer_diff(
     })
 }
 
-fn format_commit(commit: &CommitDetails) -> String {
+fn diff_stats(
+    commit_diff: &CommitDiff,
+    path_filter: Option<&RepoPath>,
+    ignore_whitespace: bool,
+) -> Vec<FileStats> {
+    commit_diff
+        .files
+        .iter()
+        .filter(|file| path_filter.is_none_or(|path_filter| *path_filter == file.path))
+        .map(|file| {
+            let old_text = file.old_text.as_deref().unwrap_or_default();
+            let new_text = file.new_text.as_deref().unwrap_or_default();
+            let (mut insertions, mut deletions) = (0, 0);
+            let line_diff = if ignore_whitespace {
+                language::line_diff_ignoring_whitespace(old_text, new_text)
+            } else {
+                language::line_diff(old_text, new_text)
+            };
+            for (old_rows, new_rows) in line_diff {
+                deletions += old_rows.len();
+                insertions += new_rows.len();
+            }
+            FileStats {
+                path: file.path.clone(),
+                insertions,
+                deletions,
+            }
+        })
+        .collect()
+}
+
+/// Formats the stats like `git show --stat`, scaling the bars down when a file
+/// has too many changes to fit.
+fn format_stats(stats: &[FileStats]) -> String {
+    let mut result = String::new();
+    if stats.is_empty() {
+        return result;
+    }
+
+    let paths = stats
+        .iter()
+        .map(|stat| stat.path.to_string_lossy())
+        .collect::<Vec<_>>();
+    let path_width = paths.iter().map(|path| path.chars().count()).max();
+    let path_width = path_width.unwrap_or_default();
+    let max_changes = stats
+        .iter()
+        .map(|stat| stat.insertions + stat.deletions)
+        .max()
+        .unwrap_or_default();
+    let changes_width = max_changes.to_string().len();
+
+    for (stat, path) in stats.iter().zip(&paths) {
+        let changes = stat.insertions + stat.deletions;
+        let (mut plus, mut minus) = (stat.insertions, stat.deletions);
+        if max_changes > MAX_STAT_BAR_WIDTH {
+            plus = (plus * MAX_STAT_BAR_WIDTH).div_ceil(max_changes);
+            minus = (minus * MAX_STAT_BAR_WIDTH).div_ceil(max_changes);
+        }
+        writeln!(
+            &mut result,
+            " {path:<path_width$} | {changes:>changes_width$} {}{}",
+            "+".repeat(plus),
+            "-".repeat(minus),
+        )
+        .unwrap();
+    }
+
+    let insertions = stats.iter().map(|stat| stat.insertions).sum::<usize>();
+    let deletions = stats.iter().map(|stat| stat.deletions).sum::<usize>();
+    let plural = |count: usize| if count == 1 { "" } else { "s" };
+    write!(
+        &mut result,
+        " {} file{} changed, {insertions} insertion{}(+), {deletions} deletion{}(-)",
+        stats.len(),
+        plural(stats.len()),
+        plural(insertions),
+        plural(deletions),
+    )
+    .unwrap();
+    result
+}
+
+fn format_commit(commit: &CommitDetails, stats: &[FileStats]) -> String {
     let mut result = String::new();
     writeln!(&mut result, "commit {}", commit.sha).unwrap();
     writeln!(
@@ -391,6 +564,10 @@ fn format_commit(commit: &CommitDetails) -> String {
     if result.ends_with("\n\n") {
         result.pop();
     }
+    if !stats.is_empty() {
+        result.push('\n');
+        result.push_str(&format_stats(stats));
+    }
     result
 }
 
@@ -421,6 +598,9 @@ impl Item for CommitView {
 
     fn tab_content_text(&self, _detail: usize, _cx: &App) -> SharedString {
         let short_sha = self.commit.sha.get(0..7).unwrap_or(&*self.commit.sha);
+        if let Some(file_name) = self.path.as_ref().and_then(|path| path.file_name()) {
+            return format!("{short_sha} - {}", file_name.to_string_lossy()).into();
+        }
         let subject = truncate_and_trailoff(self.commit.message.split('\n').next().unwrap(), 20);
         format!("{short_sha} - {subject}").into()
     }
@@ -521,3 +701,50 @@ impl Render for CommitView {
         self.editor.clone()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_diff_stats_ignoring_whitespace() {
+        let commit_diff = CommitDiff {
+            files: vec![CommitFile {
+                path: RepoPath::from("src/main.rs"),
+                old_text: Some("fn main() {\n  run();\n}\n".into()),
+                new_text: Some("fn main() {\n    run();\n    exit();\n}\n".into()),
+            }],
+        };
+
+        let stats = diff_stats(&commit_diff, None, false);
+        assert_eq!((stats[0].insertions, stats[0].deletions), (2, 1));
+
+        let stats = diff_stats(&commit_diff, None, true);
+        assert_eq!((stats[0].insertions, stats[0].deletions), (1, 0));
+    }
+
+    #[test]
+    fn test_format_stats() {
+        let stats = vec![
+            FileStats {
+                path: RepoPath::from("src/main.rs"),
+                insertions: 3,
+                deletions: 1,
+            },
+            FileStats {
+                path: RepoPath::from("README.md"),
+                insertions: 0,
+                deletions: 120,
+            },
+        ];
+        assert_eq!(
+            format_stats(&stats),
+            concat!(
+                " src/main.rs |   4 +-\n",
+                " README.md   | 120 ----------------------------------------\n",
+                " 2 files changed, 3 insertions(+), 121 deletions(-)",
+            )
+        );
+    }
+}
@@ -1,8 +1,10 @@
 use crate::askpass_modal::AskPassModal;
+use crate::commit_message_lint::{CommitMessageWarning, lint_commit_message};
 use crate::commit_modal::CommitModal;
 use crate::commit_tooltip::CommitTooltip;
 use crate::commit_view::CommitView;
-use crate::git_panel_settings::StatusStyle;
+use crate::git_panel_settings::{CommitMessageLintMode, DiffSettingsContent, StatusStyle};
+use crate::inline_diff::{InlineDiff, InlineDiffLineKind};
 use crate::project_diff::{self, Diff, ProjectDiff};
 use crate::remote_output::{self, RemoteAction, SuccessMessage};
 use crate::{branch_picker, picker_prompt, render_remote_button};
@@ -12,29 +14,35 @@ use crate::{
 use agent_settings::AgentSettings;
 use anyhow::Context as _;
 use askpass::AskPassDelegate;
+use buffer_diff::{BufferDiffEvent, DiffHunk};
 use db::kvp::KEY_VALUE_STORE;
 use editor::{
     Editor, EditorElement, EditorMode, EditorSettings, MultiBuffer, ShowScrollbar,
     scroll::ScrollbarAutoHide,
 };
-use futures::StreamExt as _;
+use file_icons::FileIcons;
 use git::blame::ParsedCommitMessage;
 use git::repository::{
-    Branch, CommitDetails, CommitOptions, CommitSummary, DiffType, PushOptions, Remote,
-    RemoteCommandOutput, ResetMode, Upstream, UpstreamTracking, UpstreamTrackingStatus,
+    ApplyCommitMode, ApplyCommitOutcome, Branch, CommitDetails, CommitOptions, CommitSummary,
+    DiffType, PushOptions, Remote, RemoteCommandOutput, RenameKind, RenamedPath, ResetMode,
+    Submodule, SubmoduleStatus, Upstream, UpstreamTracking, UpstreamTrackingStatus,
 };
 use git::status::StageStatus;
 use git::{Amend, ToggleStaged, repository::RepoPath, status::FileStatus};
-use git::{ExpandCommitEditor, RestoreTrackedFiles, StageAll, TrashUntrackedFiles, UnstageAll};
+use git::{
+    ExpandCommitEditor, GITIGNORE, RestoreTrackedFiles, StageAll, TrashUntrackedFiles, UnstageAll,
+    UpdateSubmodules,
+};
 use gpui::{
-    Action, Animation, AnimationExt as _, Axis, ClickEvent, Corner, DismissEvent, Entity,
-    EventEmitter, FocusHandle, Focusable, KeyContext, ListHorizontalSizingBehavior,
-    ListSizingBehavior, Modifiers, ModifiersChangedEvent, MouseButton, MouseDownEvent, Point,
-    PromptLevel, ScrollStrategy, Subscription, Task, Transformation, UniformListScrollHandle,
-    WeakEntity, actions, anchored, deferred, percentage, uniform_list,
+    Action, Animation, AnimationExt as _, AsyncWindowContext, Axis, ClickEvent, Corner,
+    DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, KeyContext,
+    ListHorizontalSizingBehavior, ListSizingBehavior, Modifiers, ModifiersChangedEvent,
+    MouseButton, MouseDownEvent, Point, PromptLevel, ScrollStrategy, Subscription, Task, TextStyle,
+    Transformation, UniformListScrollHandle, WeakEntity, actions, anchored, deferred, percentage,
+    point, uniform_list,
 };
 use itertools::Itertools;
-use language::{Buffer, File};
+use language::{Buffer, BufferEvent, File};
 use language_model::{
     ConfiguredModel, LanguageModel, LanguageModelRegistry, LanguageModelRequest,
     LanguageModelRequestMessage, Role,
@@ -51,16 +59,25 @@ use project::{
     Fs, Project, ProjectPath,
     git_store::{GitStoreEvent, Repository},
 };
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use settings::{Settings as _, SettingsStore};
 use std::future::Future;
 use std::path::{Path, PathBuf};
-use std::{collections::HashSet, sync::Arc, time::Duration, usize};
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    rc::Rc,
+    sync::Arc,
+    time::Duration,
+    usize,
+};
 use strum::{IntoEnumIterator, VariantNames};
+use theme::ThemeSettings;
 use time::OffsetDateTime;
 use ui::{
-    Checkbox, ContextMenu, ElevationIndex, PopoverMenu, Scrollbar, ScrollbarState, SplitButton,
-    Tooltip, prelude::*,
+    Checkbox, ContextMenu, Disclosure, ElevationIndex, PopoverMenu, Scrollbar, ScrollbarState,
+    SplitButton, Tooltip, prelude::*,
 };
 use util::{ResultExt, TryFutureExt, maybe};
 use workspace::AppState;
@@ -80,7 +97,14 @@ actions!(
         FocusEditor,
         FocusChanges,
         ToggleFillCoAuthors,
-        GenerateCommitMessage
+        ToggleSignoff,
+        GenerateCommitMessage,
+        ExpandSelectedDiff,
+        CollapseSelectedDiff,
+        StashFileAndSwitchBranch,
+        ToggleIgnoreWhitespace,
+        IncreaseDiffContext,
+        DecreaseDiffContext
     ]
 );
 
@@ -109,6 +133,8 @@ struct GitMenuState {
     has_staged_changes: bool,
     has_unstaged_changes: bool,
     has_new_changes: bool,
+    has_submodules: bool,
+    ignore_whitespace: bool,
 }
 
 fn git_panel_context_menu(
@@ -119,7 +145,7 @@ fn git_panel_context_menu(
 ) -> Entity<ContextMenu> {
     ContextMenu::build(window, cx, move |context_menu, _, _| {
         context_menu
-            .context(focus_handle)
+            .context(focus_handle.clone())
             .map(|menu| {
                 if state.has_unstaged_changes {
                     menu.action("Stage All", StageAll.boxed_clone())
@@ -136,6 +162,15 @@ fn git_panel_context_menu(
             })
             .separator()
             .action("Open Diff", project_diff::Diff.boxed_clone())
+            .toggleable_entry(
+                "Ignore Whitespace",
+                state.ignore_whitespace,
+                IconPosition::Start,
+                Some(ToggleIgnoreWhitespace.boxed_clone()),
+                move |window, cx| focus_handle.dispatch_action(&ToggleIgnoreWhitespace, window, cx),
+            )
+            .action("More Diff Context", IncreaseDiffContext.boxed_clone())
+            .action("Less Diff Context", DecreaseDiffContext.boxed_clone())
             .separator()
             .map(|menu| {
                 if state.has_tracked_changes {
@@ -154,12 +189,122 @@ fn git_panel_context_menu(
                     menu.disabled_action("Trash Untracked Files", TrashUntrackedFiles.boxed_clone())
                 }
             })
+            .separator()
+            .action("Pop Stash", git::StashPop.boxed_clone())
+            .action(
+                "Generate Pull Request Description",
+                git::GeneratePullRequestDescription.boxed_clone(),
+            )
+            .when(state.has_submodules, |menu| {
+                menu.separator()
+                    .action("Update Submodules", UpdateSubmodules.boxed_clone())
+            })
     })
 }
 
+/// A file that untracked files can be added to, so that git ignores them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum IgnoreFile {
+    /// The `.gitignore` at the root of the repository.
+    Repository,
+    /// The `.gitignore` in the directory containing the file.
+    Directory,
+    /// The repository's `info/exclude`, which isn't committed.
+    Exclude,
+}
+
+impl IgnoreFile {
+    /// Returns the absolute path of the ignore file, along with the pattern in it
+    /// that matches `repo_path`.
+    ///
+    /// The exclude file lives in the repository's common directory rather than in
+    /// `<work directory>/.git`, which isn't a directory in linked worktrees and
+    /// submodules, so its path is passed in.
+    ///
+    /// Returns `None` for [`IgnoreFile::Directory`] when the file is at the root
+    /// of the repository, as that's the repository's own `.gitignore`, and for
+    /// [`IgnoreFile::Exclude`] when the exclude file isn't known.
+    fn location(
+        self,
+        repo_path: &RepoPath,
+        work_directory: &Path,
+        exclude_file: Option<&Path>,
+    ) -> Option<(PathBuf, String)> {
+        let pattern = ignore_pattern(&repo_path.to_unix_style().to_string_lossy());
+        match self {
+            IgnoreFile::Repository => Some((work_directory.join(*GITIGNORE), pattern)),
+            IgnoreFile::Directory => {
+                let parent = repo_path
+                    .parent()
+                    .filter(|parent| !parent.as_os_str().is_empty())?;
+                let file_name = repo_path.file_name()?.to_string_lossy();
+                Some((
+                    work_directory.join(parent).join(*GITIGNORE),
+                    ignore_pattern(&file_name),
+                ))
+            }
+            IgnoreFile::Exclude => Some((exclude_file?.to_path_buf(), pattern)),
+        }
+    }
+}
+
+/// Returns a pattern that matches exactly the file at `path`, relative to the
+/// ignore file's directory.
+///
+/// The leading slash anchors the pattern, and the characters that would otherwise
+/// be read as globs, a comment, a negation, or ignorable trailing whitespace are
+/// escaped.
+fn ignore_pattern(path: &str) -> String {
+    let trimmed = path.trim_end_matches(' ');
+    let mut pattern = String::with_capacity(path.len() + 1);
+    pattern.push('/');
+    for (ix, ch) in trimmed.char_indices() {
+        let special = match ch {
+            '\\' | '*' | '?' | '[' => true,
+            '#' | '!' => ix == 0,
+            _ => false,
+        };
+        if special {
+            pattern.push('\\');
+        }
+        pattern.push(ch);
+    }
+    for _ in 0..path.len() - trimmed.len() {
+        pattern.push_str("\\ ");
+    }
+    pattern
+}
+
+/// Appends `pattern` on its own line, unless the ignore file already has it.
+///
+/// Returns whether the buffer was edited.
+fn append_ignore_pattern(buffer: &mut Buffer, pattern: &str, cx: &mut Context<Buffer>) -> bool {
+    let text = buffer.text();
+    if text.lines().any(|line| line.trim_end() == pattern) {
+        return false;
+    }
+    let mut new_text = String::new();
+    if !text.is_empty() && !text.ends_with('\n') {
+        new_text.push('\n');
+    }
+    new_text.push_str(pattern);
+    new_text.push('\n');
+    let end = buffer.len();
+    buffer.edit([(end..end, new_text)], None, cx);
+    true
+}
+
 const GIT_PANEL_KEY: &str = "GitPanel";
 
+fn signoff_key(work_directory_abs_path: &Path) -> String {
+    format!(
+        "{GIT_PANEL_KEY}-signoff-{}",
+        work_directory_abs_path.display()
+    )
+}
+
 const UPDATE_DEBOUNCE: Duration = Duration::from_millis(50);
+const SERIALIZATION_DEBOUNCE: Duration = Duration::from_millis(500);
 
 pub fn register(workspace: &mut Workspace) {
     workspace.register_action(|workspace, _: &ToggleFocus, window, cx| {
@@ -168,6 +313,29 @@ pub fn register(workspace: &mut Workspace) {
     workspace.register_action(|workspace, _: &ExpandCommitEditor, window, cx| {
         CommitModal::toggle(workspace, None, window, cx)
     });
+    workspace.register_action(|workspace, _: &git::FileHistory, window, cx| {
+        let Some(project_path) = workspace
+            .active_item_as::<Editor>(cx)
+            .and_then(|editor| editor.read(cx).project_path(cx))
+        else {
+            return;
+        };
+        let Some((repository, repo_path)) = workspace
+            .project()
+            .read(cx)
+            .git_store()
+            .read(cx)
+            .repository_and_path_for_project_path(&project_path, cx)
+        else {
+            return;
+        };
+        let Some(panel) = workspace.focus_panel::<GitPanel>(window, cx) else {
+            return;
+        };
+        panel.update(cx, |panel, cx| {
+            panel.show_file_history(repository, repo_path, window, cx);
+        });
+    });
 }
 
 #[derive(Debug, Clone)]
@@ -178,6 +346,27 @@ pub enum Event {
 #[derive(Serialize, Deserialize)]
 struct SerializedGitPanel {
     width: Option<Pixels>,
+    #[serde(flatten)]
+    state: SerializedGitPanelState,
+}
+
+/// The user's place in the list of changes, restored when the workspace is reopened.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+struct SerializedGitPanelState {
+    selected_path: Option<PathBuf>,
+    scroll_offset: Pixels,
+    expanded_diffs: Vec<PathBuf>,
+}
+
+/// The structured response requested when generating a commit message.
+#[derive(Deserialize, JsonSchema)]
+struct GeneratedCommitMessage {
+    /// The commit's subject line.
+    subject: String,
+    /// The commit's body, omitted when the subject line says everything.
+    #[serde(default)]
+    body: Option<String>,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
@@ -195,13 +384,12 @@ struct GitHeaderEntry {
 impl GitHeaderEntry {
     pub fn contains(&self, status_entry: &GitStatusEntry, repo: &Repository) -> bool {
         let this = &self.header;
-        let status = status_entry.status;
         match this {
             Section::Conflict => {
                 repo.had_conflict_on_last_merge_head_change(&status_entry.repo_path)
             }
-            Section::Tracked => !status.is_created(),
-            Section::New => status.is_created(),
+            Section::Tracked => !status_entry.is_new(),
+            Section::New => status_entry.is_new(),
         }
     }
     pub fn title(&self) -> &'static str {
@@ -217,6 +405,14 @@ impl GitHeaderEntry {
 enum GitListEntry {
     GitStatusEntry(GitStatusEntry),
     Header(GitHeaderEntry),
+    DiffLine(GitDiffLineEntry),
+}
+
+/// A line of an expanded file's diff, indexing into its [`InlineDiff`].
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct GitDiffLineEntry {
+    repo_path: RepoPath,
+    line_ix: usize,
 }
 
 impl GitListEntry {
@@ -228,15 +424,42 @@ impl GitListEntry {
     }
 }
 
+struct FileHistory {
+    repository: Entity<Repository>,
+    path: RepoPath,
+    /// `None` while the history is loading.
+    commits: Option<Vec<CommitSummary>>,
+    _load_commits: Task<()>,
+}
+
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct GitStatusEntry {
     pub(crate) repo_path: RepoPath,
     pub(crate) abs_path: PathBuf,
     pub(crate) status: FileStatus,
     pub(crate) staging: StageStatus,
+    /// Set when git detected that this file was renamed or copied from another one.
+    pub(crate) rename: Option<RenamedPath>,
 }
 
 impl GitStatusEntry {
+    /// Whether the file is new to the repository, rather than renamed or copied from
+    /// a file that's already tracked.
+    fn is_new(&self) -> bool {
+        self.status.is_created() && self.rename.is_none()
+    }
+
+    /// The paths to stage or unstage along with this entry. A rename is staged
+    /// together with the deletion of its old path, so that it's never half-staged.
+    fn paths_to_stage(&self) -> impl Iterator<Item = RepoPath> + '_ {
+        let old_path = self
+            .rename
+            .as_ref()
+            .filter(|rename| rename.kind == RenameKind::Renamed)
+            .map(|rename| rename.old_path.clone());
+        std::iter::once(self.repo_path.clone()).chain(old_path)
+    }
+
     fn display_name(&self) -> String {
         self.repo_path
             .file_name()
@@ -251,6 +474,39 @@ impl GitStatusEntry {
     }
 }
 
+/// The value of a drag from the list, which is filled in with the dragged
+/// entries once the drag starts. They're staged or unstaged when dropped on
+/// the corresponding target.
+#[derive(Clone, Default)]
+struct GitEntriesDrag(Rc<RefCell<Vec<GitStatusEntry>>>);
+
+/// The preview shown while dragging entries.
+struct DraggedGitEntries {
+    entries: Vec<GitStatusEntry>,
+    label: SharedString,
+}
+
+impl Render for DraggedGitEntries {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let ui_font = ThemeSettings::get_global(cx).ui_font.family.clone();
+        h_flex()
+            .font_family(ui_font)
+            .bg(cx.theme().colors().background)
+            .border_1()
+            .border_color(cx.theme().colors().border)
+            .rounded_sm()
+            .px_1p5()
+            .py_0p5()
+            .gap_1()
+            .child(
+                Icon::new(IconName::File)
+                    .size(IconSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(Label::new(self.label.clone()).size(LabelSize::Small))
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 enum TargetStatus {
     Staged,
@@ -328,8 +584,17 @@ pub struct GitPanel {
     conflicted_staged_count: usize,
     current_modifiers: Modifiers,
     add_coauthors: bool,
+    /// Emails of the room participants that were deselected in the co-author picker.
+    excluded_co_authors: HashSet<String>,
+    /// Whether commits to the active repository get a `Signed-off-by` trailer.
+    signoff: bool,
     generate_commit_message_task: Option<Task<Option<()>>>,
     entries: Vec<GitListEntry>,
+    submodules: Vec<Submodule>,
+    refresh_submodules_task: Task<()>,
+    renamed_paths: Vec<RenamedPath>,
+    inline_diffs: HashMap<RepoPath, InlineDiff>,
+    file_history: Option<FileHistory>,
     single_staged_entry: Option<GitStatusEntry>,
     single_tracked_entry: Option<GitStatusEntry>,
     focus_handle: FocusHandle,
@@ -343,11 +608,17 @@ pub struct GitPanel {
     pending_commit: Option<Task<()>>,
     amend_pending: bool,
     pending_serialization: Task<Option<()>>,
+    serialized_state: Option<SerializedGitPanelState>,
+    state_to_restore: Option<SerializedGitPanelState>,
     pub(crate) project: Entity<Project>,
     scroll_handle: UniformListScrollHandle,
     max_width_item_index: Option<usize>,
     selected_entry: Option<usize>,
     marked_entries: Vec<usize>,
+    /// The paths stashed from the panel, so that they can be selected again once the
+    /// stash is popped.
+    stashed_paths: Vec<RepoPath>,
+    pending_selection: Option<RepoPath>,
     tracked_count: usize,
     tracked_staged_count: usize,
     update_visible_entries_task: Task<()>,
@@ -410,12 +681,24 @@ impl GitPanel {
         .detach();
 
         let mut was_sort_by_path = GitPanelSettings::get_global(cx).sort_by_path;
+        let mut had_file_icons = GitPanelSettings::get_global(cx).file_icons;
+        let mut icon_theme = ThemeSettings::get_global(cx).active_icon_theme.clone();
         cx.observe_global::<SettingsStore>(move |this, cx| {
             let is_sort_by_path = GitPanelSettings::get_global(cx).sort_by_path;
             if is_sort_by_path != was_sort_by_path {
                 this.update_visible_entries(cx);
             }
-            was_sort_by_path = is_sort_by_path
+            was_sort_by_path = is_sort_by_path;
+
+            // Installing or reloading an icon theme extension swaps the active icon theme
+            // in the theme settings.
+            let has_file_icons = GitPanelSettings::get_global(cx).file_icons;
+            let new_icon_theme = &ThemeSettings::get_global(cx).active_icon_theme;
+            if has_file_icons != had_file_icons || !Arc::ptr_eq(new_icon_theme, &icon_theme) {
+                had_file_icons = has_file_icons;
+                icon_theme = new_icon_theme.clone();
+                cx.notify();
+            }
         })
         .detach();
 
@@ -438,6 +721,9 @@ impl GitPanel {
             move |this, git_store, event, window, cx| match event {
                 GitStoreEvent::ActiveRepositoryChanged(_) => {
                     this.active_repository = git_store.read(cx).active_repository();
+                    this.file_history.take();
+                    this.load_signoff(cx);
+                    this.refresh_submodules(cx);
                     this.schedule_update(true, window, cx);
                 }
                 GitStoreEvent::RepositoryUpdated(
@@ -445,8 +731,16 @@ impl GitPanel {
                     RepositoryEvent::Updated { full_scan },
                     true,
                 ) => {
+                    this.refresh_submodules(cx);
                     this.schedule_update(*full_scan, window, cx);
                 }
+                GitStoreEvent::RepositoryUpdated(
+                    _,
+                    RepositoryEvent::StatusesChanged { .. },
+                    true,
+                ) => {
+                    this.schedule_update(false, window, cx);
+                }
 
                 GitStoreEvent::RepositoryAdded(_) | GitStoreEvent::RepositoryRemoved(_) => {
                     this.schedule_update(false, window, cx);
@@ -482,6 +776,11 @@ impl GitPanel {
             hide_task: None,
         };
 
+        // Selecting, scrolling, and expanding diffs all notify the panel, so that's when the
+        // persisted state may have changed.
+        cx.observe(&cx.entity(), |this, _, cx| this.serialize_if_changed(cx))
+            .detach();
+
         let mut assistant_enabled = AgentSettings::get_global(cx).enabled;
         let _settings_subscription = cx.observe_global::<SettingsStore>(move |_, cx| {
             if assistant_enabled != AgentSettings::get_global(cx).enabled {
@@ -497,8 +796,15 @@ impl GitPanel {
             conflicted_staged_count: 0,
             current_modifiers: window.modifiers(),
             add_coauthors: true,
+            excluded_co_authors: HashSet::default(),
+            signoff: false,
             generate_commit_message_task: None,
             entries: Vec::new(),
+            submodules: Vec::new(),
+            refresh_submodules_task: Task::ready(()),
+            renamed_paths: Vec::new(),
+            inline_diffs: HashMap::default(),
+            file_history: None,
             focus_handle: cx.focus_handle(),
             fs,
             new_count: 0,
@@ -507,6 +813,8 @@ impl GitPanel {
             pending_commit: None,
             amend_pending: false,
             pending_serialization: Task::ready(None),
+            serialized_state: None,
+            state_to_restore: None,
             single_staged_entry: None,
             single_tracked_entry: None,
             project,
@@ -514,6 +822,8 @@ impl GitPanel {
             max_width_item_index: None,
             selected_entry: None,
             marked_entries: Vec::new(),
+            stashed_paths: Vec::new(),
+            pending_selection: None,
             tracked_count: 0,
             tracked_staged_count: 0,
             update_visible_entries_task: Task::ready(()),
@@ -527,6 +837,8 @@ impl GitPanel {
             vertical_scrollbar,
             _settings_subscription,
         };
+        git_panel.load_signoff(cx);
+        git_panel.refresh_submodules(cx);
         git_panel.schedule_update(false, window, cx);
         git_panel
     }
@@ -677,20 +989,138 @@ impl GitPanel {
         cx.notify();
     }
 
+    pub async fn load(
+        workspace: WeakEntity<Workspace>,
+        mut cx: AsyncWindowContext,
+    ) -> anyhow::Result<Entity<Self>> {
+        let serialized_panel = match workspace
+            .read_with(&cx, |workspace, _| GitPanel::serialization_key(workspace))
+            .ok()
+            .flatten()
+        {
+            Some(serialization_key) => cx
+                .background_spawn(async move { KEY_VALUE_STORE.read_kvp(&serialization_key) })
+                .await
+                .context("loading git panel")
+                .log_err()
+                .flatten()
+                .map(|panel| serde_json::from_str::<SerializedGitPanel>(&panel))
+                .transpose()
+                .log_err()
+                .flatten(),
+            None => None,
+        };
+
+        workspace.update_in(&mut cx, |workspace, window, cx| {
+            let workspace_entity = cx.entity();
+            let project = workspace.project().clone();
+            let app_state = workspace.app_state().clone();
+            cx.new(|cx| {
+                let mut panel = GitPanel::new(workspace_entity, project, app_state, window, cx);
+                if let Some(serialized_panel) = serialized_panel {
+                    panel.width = serialized_panel.width.map(|px| px.round());
+                    panel.serialized_state = Some(serialized_panel.state.clone());
+                    panel.state_to_restore = Some(serialized_panel.state);
+                }
+                panel
+            })
+        })
+    }
+
+    fn serialization_key(workspace: &Workspace) -> Option<String> {
+        workspace
+            .database_id()
+            .map(|id| i64::from(id).to_string())
+            .or(workspace.session_id())
+            .map(|id| format!("{}-{:?}", GIT_PANEL_KEY, id))
+    }
+
     fn serialize(&mut self, cx: &mut Context<Self>) {
+        let Some(serialization_key) = self
+            .workspace
+            .read_with(cx, |workspace, _| GitPanel::serialization_key(workspace))
+            .ok()
+            .flatten()
+        else {
+            return;
+        };
         let width = self.width;
-        self.pending_serialization = cx.background_spawn(
-            async move {
-                KEY_VALUE_STORE
-                    .write_kvp(
-                        GIT_PANEL_KEY.into(),
-                        serde_json::to_string(&SerializedGitPanel { width })?,
-                    )
-                    .await?;
-                anyhow::Ok(())
+        let state = self.current_state();
+        self.serialized_state = Some(state.clone());
+        self.pending_serialization = cx.spawn(async move |_, cx| {
+            // Scrolling changes the state continuously, so only persist it once it settles.
+            cx.background_executor().timer(SERIALIZATION_DEBOUNCE).await;
+            let serialized_panel =
+                serde_json::to_string(&SerializedGitPanel { width, state }).log_err()?;
+            KEY_VALUE_STORE
+                .write_kvp(serialization_key, serialized_panel)
+                .await
+                .log_err()
+        });
+    }
+
+    fn serialize_if_changed(&mut self, cx: &mut Context<Self>) {
+        if self.serialized_state.as_ref() != Some(&self.current_state()) {
+            self.serialize(cx);
+        }
+    }
+
+    fn current_state(&self) -> SerializedGitPanelState {
+        // Until the saved state has been applied, the panel's own state is just the initial one.
+        if let Some(state) = &self.state_to_restore {
+            return state.clone();
+        }
+
+        let selected_path = self
+            .get_selected_entry()
+            .and_then(|entry| match entry {
+                GitListEntry::GitStatusEntry(entry) => Some(&entry.repo_path),
+                GitListEntry::DiffLine(line) => Some(&line.repo_path),
+                GitListEntry::Header(_) => None,
+            })
+            .map(|repo_path| repo_path.0.to_path_buf());
+        let expanded_diffs = self
+            .inline_diffs
+            .keys()
+            .map(|repo_path| repo_path.0.to_path_buf())
+            .sorted()
+            .collect();
+        SerializedGitPanelState {
+            selected_path,
+            scroll_offset: self.scroll_handle.0.borrow().base_handle.offset().y,
+            expanded_diffs,
+        }
+    }
+
+    /// Applies the state loaded from the database once the active repository's entries are
+    /// known, since the saved selection and expanded diffs refer to its paths.
+    fn restore_serialized_state(&mut self, cx: &mut Context<Self>) {
+        if self.entries.is_empty() {
+            return;
+        }
+        let Some(state) = self.state_to_restore.take() else {
+            return;
+        };
+
+        if let Some(ix) = state
+            .selected_path
+            .and_then(|path| self.entry_by_path(&RepoPath(path.into()), cx))
+        {
+            self.selected_entry = Some(ix);
+        }
+        for path in state.expanded_diffs {
+            let repo_path = RepoPath(path.into());
+            if !self.inline_diffs.contains_key(&repo_path)
+                && self.entry_by_path(&repo_path, cx).is_some()
+            {
+                self.toggle_inline_diff(repo_path, cx);
             }
-            .log_err(),
-        );
+        }
+        self.scroll_handle
+            .0
+            .borrow()
+            .base_handle
+            .set_offset(point(px(0.), state.scroll_offset));
     }
 
     pub(crate) fn set_modal_open(&mut self, open: bool, cx: &mut Context<Self>) {
@@ -765,20 +1195,10 @@ impl GitPanel {
         }
 
         if let Some(selected_entry) = self.selected_entry {
-            let new_selected_entry = if selected_entry > 0 {
-                selected_entry - 1
-            } else {
-                selected_entry
-            };
-
-            if matches!(
-                self.entries.get(new_selected_entry),
-                Some(GitListEntry::Header(..))
-            ) {
-                if new_selected_entry > 0 {
-                    self.selected_entry = Some(new_selected_entry - 1)
-                }
-            } else {
+            if let Some(new_selected_entry) = (0..selected_entry)
+                .rev()
+                .find(|ix| self.entries[*ix].status_entry().is_some())
+            {
                 self.selected_entry = Some(new_selected_entry);
             }
 
@@ -795,17 +1215,9 @@ impl GitPanel {
         }
 
         if let Some(selected_entry) = self.selected_entry {
-            let new_selected_entry = if selected_entry < item_count - 1 {
-                selected_entry + 1
-            } else {
-                selected_entry
-            };
-            if matches!(
-                self.entries.get(new_selected_entry),
-                Some(GitListEntry::Header(..))
-            ) {
-                self.selected_entry = Some(new_selected_entry + 1);
-            } else {
+            if let Some(new_selected_entry) = (selected_entry + 1..item_count)
+                .find(|ix| self.entries[*ix].status_entry().is_some())
+            {
                 self.selected_entry = Some(new_selected_entry);
             }
 
@@ -816,8 +1228,12 @@ impl GitPanel {
     }
 
     fn select_last(&mut self, _: &SelectLast, _window: &mut Window, cx: &mut Context<Self>) {
-        if self.entries.last().is_some() {
-            self.selected_entry = Some(self.entries.len() - 1);
+        if let Some(last_entry) = self
+            .entries
+            .iter()
+            .rposition(|entry| entry.status_entry().is_some())
+        {
+            self.selected_entry = Some(last_entry);
             self.scroll_to_selected_entry(cx);
         }
     }
@@ -855,6 +1271,155 @@ impl GitPanel {
         cx.notify();
     }
 
+    fn expand_selected_diff(
+        &mut self,
+        _: &ExpandSelectedDiff,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(entry) = self
+            .get_selected_entry()
+            .and_then(GitListEntry::status_entry)
+        else {
+            return;
+        };
+        if !self.inline_diffs.contains_key(&entry.repo_path) {
+            self.toggle_inline_diff(entry.repo_path.clone(), cx);
+        }
+    }
+
+    fn collapse_selected_diff(
+        &mut self,
+        _: &CollapseSelectedDiff,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(entry) = self
+            .get_selected_entry()
+            .and_then(GitListEntry::status_entry)
+        else {
+            return;
+        };
+        if self.inline_diffs.contains_key(&entry.repo_path) {
+            self.toggle_inline_diff(entry.repo_path.clone(), cx);
+        }
+    }
+
+    fn toggle_inline_diff(&mut self, repo_path: RepoPath, cx: &mut Context<Self>) {
+        if self.inline_diffs.remove(&repo_path).is_some() {
+            self.update_inline_diff_entries(cx);
+            return;
+        }
+        let Some(project_path) = self
+            .active_repository
+            .as_ref()
+            .and_then(|repo| repo.read(cx).repo_path_to_project_path(&repo_path, cx))
+        else {
+            return;
+        };
+        let project = self.project.clone();
+        cx.spawn(async move |this, cx| {
+            let buffer = project
+                .update(cx, |project, cx| project.open_buffer(project_path, cx))?
+                .await?;
+            let diff = project
+                .update(cx, |project, cx| {
+                    project.open_uncommitted_diff(buffer.clone(), cx)
+                })?
+                .await?;
+            this.update(cx, |this, cx| {
+                let _subscriptions = [
+                    cx.subscribe(&diff, {
+                        let repo_path = repo_path.clone();
+                        move |this, _, _: &BufferDiffEvent, cx| {
+                            this.refresh_inline_diff(&repo_path, cx)
+                        }
+                    }),
+                    cx.subscribe(&buffer, {
+                        let repo_path = repo_path.clone();
+                        move |this, _, event: &BufferEvent, cx| {
+                            if matches!(event, BufferEvent::Reparsed) {
+                                this.refresh_inline_diff(&repo_path, cx)
+                            }
+                        }
+                    }),
+                ];
+                this.inline_diffs.insert(
+                    repo_path.clone(),
+                    InlineDiff {
+                        buffer,
+                        diff,
+                        lines: Vec::new(),
+                        _subscriptions,
+                    },
+                );
+                this.refresh_inline_diff(&repo_path, cx);
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn refresh_inline_diff(&mut self, repo_path: &RepoPath, cx: &mut Context<Self>) {
+        let Some(inline_diff) = self.inline_diffs.get_mut(repo_path) else {
+            return;
+        };
+        inline_diff.refresh(cx.theme().syntax(), cx);
+        self.update_inline_diff_entries(cx);
+    }
+
+    /// Rebuilds the entries after diff rows were added or removed, keeping the
+    /// selection on the same file even though its index may have moved.
+    fn update_inline_diff_entries(&mut self, cx: &mut Context<Self>) {
+        let selected_path = self
+            .get_selected_entry()
+            .and_then(GitListEntry::status_entry)
+            .map(|entry| entry.repo_path.clone());
+        self.update_visible_entries(cx);
+        if let Some(selected_path) = selected_path {
+            if let Some(ix) = self.entries.iter().position(|entry| {
+                entry
+                    .status_entry()
+                    .is_some_and(|entry| entry.repo_path == selected_path)
+            }) {
+                self.selected_entry = Some(ix);
+            }
+        }
+    }
+
+    fn stage_inline_diff_hunk(
+        &mut self,
+        repo_path: &RepoPath,
+        hunk: DiffHunk,
+        stage: bool,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(inline_diff) = self.inline_diffs.get(repo_path) else {
+            return;
+        };
+        let buffer = inline_diff.buffer.clone();
+        let diff = inline_diff.diff.clone();
+        let file_exists = buffer
+            .read(cx)
+            .file()
+            .is_some_and(|file| file.disk_state().exists());
+        // Hunks are staged from the buffer's contents, so unsaved edits must
+        // reach the disk first, as when staging from an editor.
+        let save = if file_exists && buffer.read(cx).is_dirty() {
+            self.project
+                .update(cx, |project, cx| project.save_buffer(buffer.clone(), cx))
+        } else {
+            Task::ready(Ok(()))
+        };
+        cx.spawn(async move |_, cx| {
+            save.await?;
+            diff.update(cx, |diff, cx| {
+                let buffer = buffer.read(cx).text_snapshot();
+                diff.stage_or_unstage_hunks(stage, &[hunk], &buffer, file_exists, cx);
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
     fn get_selected_entry(&self) -> Option<&GitListEntry> {
         self.selected_entry.and_then(|i| self.entries.get(i))
     }
@@ -1280,6 +1845,7 @@ impl GitPanel {
 
                 (goal_staged_state, entries)
             }
+            GitListEntry::DiffLine(_) => return,
         };
         self.change_file_stage(stage, repo_paths, cx);
     }
@@ -1316,7 +1882,7 @@ impl GitPanel {
                             active_repository.update(cx, |repo, cx| {
                                 let repo_paths = entries
                                     .iter()
-                                    .map(|entry| entry.repo_path.clone())
+                                    .flat_map(|entry| entry.paths_to_stage())
                                     .collect();
                                 repo.stage_entries(repo_paths, cx)
                             })
@@ -1324,7 +1890,7 @@ impl GitPanel {
                             active_repository.update(cx, |repo, cx| {
                                 let repo_paths = entries
                                     .iter()
-                                    .map(|entry| entry.repo_path.clone())
+                                    .flat_map(|entry| entry.paths_to_stage())
                                     .collect();
                                 repo.unstage_entries(repo_paths, cx)
                             })
@@ -1379,49 +1945,265 @@ impl GitPanel {
         let Some(selected_entry) = self.get_selected_entry() else {
             return;
         };
-        let Some(status_entry) = selected_entry.status_entry() else {
-            return;
-        };
-        if status_entry.staging != StageStatus::Staged {
-            self.change_file_stage(true, vec![status_entry.clone()], cx);
+        let entries = self
+            .status_entries_for(selected_entry, cx)
+            .into_iter()
+            .filter(|status_entry| status_entry.staging != StageStatus::Staged)
+            .collect::<Vec<_>>();
+        if !entries.is_empty() {
+            self.change_file_stage(true, entries, cx);
         }
     }
 
-    fn unstage_selected(
+    /// Adds the selected untracked file to the index with intent-to-add, then opens its diff
+    /// so that its hunks can be staged one at a time rather than all at once.
+    fn stage_partially(
         &mut self,
-        _: &git::UnstageFile,
-        _window: &mut Window,
+        _: &git::StagePartially,
+        window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        let Some(selected_entry) = self.get_selected_entry() else {
+        let Some(entry) = self
+            .get_selected_entry()
+            .and_then(|entry| entry.status_entry())
+            .filter(|entry| entry.status.is_untracked())
+            .cloned()
+        else {
             return;
         };
-        let Some(status_entry) = selected_entry.status_entry() else {
+        let Some(active_repository) = self.active_repository.clone() else {
             return;
         };
-        if status_entry.staging != StageStatus::Unstaged {
-            self.change_file_stage(false, vec![status_entry.clone()], cx);
-        }
+        let task = active_repository.update(cx, |repository, cx| {
+            repository.intent_to_add_entries(vec![entry.repo_path.clone()], cx)
+        });
+        cx.spawn_in(window, async move |this, cx| {
+            let result = task.await;
+            this.update_in(cx, |this, window, cx| match result {
+                Ok(()) => {
+                    this.workspace
+                        .update(cx, |workspace, cx| {
+                            ProjectDiff::deploy_at(workspace, Some(entry), window, cx);
+                        })
+                        .ok();
+                }
+                Err(e) => this.show_error_toast("add --intent-to-add", e, cx),
+            })
+        })
+        .detach_and_log_err(cx);
     }
 
-    fn commit(&mut self, _: &git::Commit, window: &mut Window, cx: &mut Context<Self>) {
-        if self.amend_pending {
+    fn stash_selected(&mut self, _: &git::StashFile, window: &mut Window, cx: &mut Context<Self>) {
+        self.stash_selected_entries(false, window, cx);
+    }
+
+    fn stash_selected_and_switch_branch(
+        &mut self,
+        _: &StashFileAndSwitchBranch,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.stash_selected_entries(true, window, cx);
+    }
+
+    /// Stashes only the selected file, or every file under the selected header, along with
+    /// the marked ones if the selection is marked. The rest of the working tree is left
+    /// alone so that a branch can be switched to safely.
+    fn stash_selected_entries(
+        &mut self,
+        switch_branch: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(active_repository) = self.active_repository.clone() else {
+            return;
+        };
+        let Some(selected_entry) = self.selected_entry else {
+            return;
+        };
+        let paths = self
+            .status_entries_at(selected_entry, cx)
+            .into_iter()
+            .map(|status_entry| status_entry.repo_path)
+            .collect::<Vec<_>>();
+        if paths.is_empty() {
             return;
         }
-        if self
-            .commit_editor
-            .focus_handle(cx)
-            .contains_focused(window, cx)
-        {
-            telemetry::event!("Git Committed", source = "Git Panel");
-            self.commit_changes(CommitOptions { amend: false }, window, cx)
-        } else {
-            cx.propagate();
-        }
-    }
 
-    fn amend(&mut self, _: &git::Amend, window: &mut Window, cx: &mut Context<Self>) {
-        if self
+        let stash = active_repository.update(cx, |repo, cx| repo.stash_paths(paths.clone(), cx));
+        cx.spawn_in(window, async move |this, cx| {
+            let result = stash.await.map_err(anyhow::Error::from).and_then(|r| r);
+            this.update_in(cx, |this, window, cx| match result {
+                Ok(()) => {
+                    this.stashed_paths = paths;
+                    this.marked_entries.clear();
+                    if switch_branch {
+                        window.dispatch_action(zed_actions::git::Switch.boxed_clone(), cx);
+                    }
+                }
+                Err(e) => this.show_error_toast("stash", e, cx),
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn stash_pop(&mut self, _: &git::StashPop, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(active_repository) = self.active_repository.clone() else {
+            return;
+        };
+        let pop = active_repository.update(cx, |repo, cx| repo.stash_pop(cx));
+        cx.spawn_in(window, async move |this, cx| {
+            let result = pop.await.map_err(anyhow::Error::from).and_then(|r| r);
+            this.update(cx, |this, cx| match result {
+                Ok(()) => {
+                    let stashed_paths = std::mem::take(&mut this.stashed_paths);
+                    this.pending_selection = stashed_paths.into_iter().next();
+                }
+                Err(e) => this.show_error_toast("stash pop", e, cx),
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn unstage_selected(
+        &mut self,
+        _: &git::UnstageFile,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(selected_entry) = self.get_selected_entry() else {
+            return;
+        };
+        let entries = self
+            .status_entries_for(selected_entry, cx)
+            .into_iter()
+            .filter(|status_entry| status_entry.staging != StageStatus::Unstaged)
+            .collect::<Vec<_>>();
+        if !entries.is_empty() {
+            self.change_file_stage(false, entries, cx);
+        }
+    }
+
+    /// The status entries that staging `entry` applies to. A header applies to
+    /// every entry in its section.
+    fn status_entries_for(&self, entry: &GitListEntry, cx: &App) -> Vec<GitStatusEntry> {
+        match entry {
+            GitListEntry::GitStatusEntry(status_entry) => vec![status_entry.clone()],
+            GitListEntry::Header(section) => {
+                let Some(repository) = self.active_repository.as_ref() else {
+                    return Vec::new();
+                };
+                let repository = repository.read(cx);
+                self.entries
+                    .iter()
+                    .filter_map(|entry| entry.status_entry())
+                    .filter(|status_entry| section.contains(status_entry, repository))
+                    .cloned()
+                    .collect()
+            }
+            GitListEntry::DiffLine(_) => Vec::new(),
+        }
+    }
+
+    /// The status entries that an action on the row at `ix` applies to. Acting on
+    /// a marked row acts on all of the marked rows with it.
+    fn status_entries_at(&self, ix: usize, cx: &App) -> Vec<GitStatusEntry> {
+        let indices = if self.marked_entries.contains(&ix) {
+            self.marked_entries.clone()
+        } else {
+            vec![ix]
+        };
+        let mut entries = Vec::new();
+        for ix in indices {
+            if let Some(entry) = self.entries.get(ix) {
+                entries.extend(self.status_entries_for(entry, cx));
+            }
+        }
+        let mut seen = HashSet::default();
+        entries.retain(|entry| seen.insert(entry.repo_path.clone()));
+        entries
+    }
+
+    /// Marks or unmarks the row at `ix`, so that actions on it also apply to the
+    /// other marked rows.
+    fn toggle_marked(&mut self, ix: usize) {
+        if let Some(position) = self.marked_entries.iter().position(|marked| *marked == ix) {
+            self.marked_entries.remove(position);
+        } else {
+            self.marked_entries.push(ix);
+        }
+    }
+
+    /// The entries dragged from the row at `ix`.
+    fn dragged_entries(&self, ix: usize, cx: &App) -> Option<DraggedGitEntries> {
+        let entries = self.status_entries_at(ix, cx);
+        let label = match entries.as_slice() {
+            [] => return None,
+            [entry] => entry.display_name().into(),
+            entries => format!("{} files", entries.len()).into(),
+        };
+        Some(DraggedGitEntries { entries, label })
+    }
+
+    /// Lets the row at `ix` be dragged. The dragged entries are only gathered
+    /// when the drag starts, rather than for every row whenever the list renders.
+    fn draggable_entries<E: StatefulInteractiveElement>(
+        &self,
+        element: E,
+        ix: usize,
+        cx: &Context<Self>,
+    ) -> E {
+        let panel = cx.weak_entity();
+        element.on_drag(GitEntriesDrag::default(), move |drag, _, _, cx| {
+            let dragged = panel
+                .upgrade()
+                .and_then(|panel| panel.read(cx).dragged_entries(ix, cx))
+                .unwrap_or_else(|| DraggedGitEntries {
+                    entries: Vec::new(),
+                    label: "No files".into(),
+                });
+            *drag.0.borrow_mut() = dragged.entries.clone();
+            cx.new(|_| dragged)
+        })
+    }
+
+    fn drop_entries(&mut self, dragged: &GitEntriesDrag, stage: bool, cx: &mut Context<Self>) {
+        let entries = dragged
+            .0
+            .borrow()
+            .iter()
+            .filter(|entry| {
+                if stage {
+                    entry.staging.has_unstaged()
+                } else {
+                    entry.staging.has_staged()
+                }
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        if !entries.is_empty() {
+            self.change_file_stage(stage, entries, cx);
+        }
+    }
+
+    fn commit(&mut self, _: &git::Commit, window: &mut Window, cx: &mut Context<Self>) {
+        if self.amend_pending {
+            return;
+        }
+        if self
+            .commit_editor
+            .focus_handle(cx)
+            .contains_focused(window, cx)
+        {
+            telemetry::event!("Git Committed", source = "Git Panel");
+            self.commit_changes(CommitOptions::default(), window, cx)
+        } else {
+            cx.propagate();
+        }
+    }
+
+    fn amend(&mut self, _: &git::Amend, window: &mut Window, cx: &mut Context<Self>) {
+        if self
             .commit_editor
             .focus_handle(cx)
             .contains_focused(window, cx)
@@ -1438,7 +2220,14 @@ impl GitPanel {
                 } else {
                     telemetry::event!("Git Amended", source = "Git Panel");
                     self.set_amend_pending(false, cx);
-                    self.commit_changes(CommitOptions { amend: true }, window, cx);
+                    self.commit_changes(
+                        CommitOptions {
+                            amend: true,
+                            ..Default::default()
+                        },
+                        window,
+                        cx,
+                    );
                 }
             }
         } else {
@@ -1496,14 +2285,14 @@ impl GitPanel {
 
     pub(crate) fn commit_changes(
         &mut self,
-        options: CommitOptions,
+        mut options: CommitOptions,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
         let Some(active_repository) = self.active_repository.clone() else {
             return;
         };
-        let error_spawn = |message, window: &mut Window, cx: &mut App| {
+        let error_spawn = |message: &str, window: &mut Window, cx: &mut App| {
             let prompt = window.prompt(PromptLevel::Warning, message, None, &["Ok"], cx);
             cx.spawn(async move |_| {
                 prompt.await.ok();
@@ -1527,9 +2316,15 @@ impl GitPanel {
             return;
         };
 
+        if let Some(warning) = self.enforced_commit_message_warnings(cx).first() {
+            error_spawn(&warning.message(), window, cx);
+            return;
+        }
+
         if self.add_coauthors {
             self.fill_co_authors(&mut message, cx);
         }
+        options.signoff = self.signoff;
 
         let task = if self.has_staged_changes() {
             // Repository serializes all git operations, so we can just send a commit immediately
@@ -1777,31 +2572,30 @@ impl GitPanel {
                     tool_choice: None,
                     stop: Vec::new(),
                     temperature,
+                    response_schema: None,
                 };
 
-                let stream = model.stream_completion_text(request, &cx);
-                let mut messages = stream.await?;
+                let message = model
+                    .request_structured::<GeneratedCommitMessage>(request, &cx)
+                    .await?
+                    .value;
 
-                if !text_empty {
-                    this.update(cx, |this, cx| {
-                        this.commit_message_buffer(cx).update(cx, |buffer, cx| {
-                            let insert_position = buffer.anchor_before(buffer.len());
-                            buffer.edit([(insert_position..insert_position, "\n")], None, cx)
-                        });
-                    })?;
+                let mut text = String::new();
+                if text_empty {
+                    text.push_str(message.subject.trim());
                 }
-
-                while let Some(message) = messages.stream.next().await {
-                    let text = message?;
-
-                    this.update(cx, |this, cx| {
-                        this.commit_message_buffer(cx).update(cx, |buffer, cx| {
-                            let insert_position = buffer.anchor_before(buffer.len());
-                            buffer.edit([(insert_position..insert_position, text)], None, cx);
-                        });
-                    })?;
+                if let Some(body) = message.body.as_deref().map(str::trim).filter(|body| !body.is_empty()) {
+                    text.push_str("\n\n");
+                    text.push_str(body);
                 }
 
+                this.update(cx, |this, cx| {
+                    this.commit_message_buffer(cx).update(cx, |buffer, cx| {
+                        let insert_position = buffer.anchor_before(buffer.len());
+                        buffer.edit([(insert_position..insert_position, text)], None, cx);
+                    });
+                })?;
+
                 anyhow::Ok(())
             }
             .log_err().await
@@ -1842,6 +2636,229 @@ impl GitPanel {
             .detach_and_log_err(cx);
     }
 
+    pub(crate) fn update_submodules(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if !self.can_push_and_pull(cx) {
+            return;
+        }
+
+        let Some(repo) = self.active_repository.clone() else {
+            return;
+        };
+        let askpass = self.askpass_delegate("git submodule update", window, cx);
+        let this = cx.weak_entity();
+        window
+            .spawn(cx, async move |cx| {
+                let update = repo.update(cx, |repo, cx| repo.update_submodules(askpass, cx))?;
+
+                let remote_message = update.await?;
+                this.update(cx, |this, cx| {
+                    let action = RemoteAction::UpdateSubmodules;
+                    match remote_message {
+                        Ok(remote_message) => this.show_remote_output(action, remote_message, cx),
+                        Err(e) => {
+                            log::error!("Error while updating submodules {:?}", e);
+                            this.show_error_toast(action.name(), e, cx)
+                        }
+                    }
+
+                    anyhow::Ok(())
+                })
+                .ok();
+                anyhow::Ok(())
+            })
+            .detach_and_log_err(cx);
+    }
+
+    /// Returns the repository for a submodule of the active repository, if it has been
+    /// initialized and discovered by the project.
+    fn submodule_repository(&self, submodule: &Submodule, cx: &App) -> Option<Entity<Repository>> {
+        let active_repository = self.active_repository.as_ref()?;
+        let abs_path = active_repository
+            .read(cx)
+            .work_directory_abs_path
+            .join(&submodule.path);
+        let git_store = self.project.read(cx).git_store().read(cx);
+        git_store
+            .repositories()
+            .values()
+            .find(|repo| repo.read(cx).work_directory_abs_path.as_ref() == abs_path)
+            .cloned()
+    }
+
+    fn open_submodule(&mut self, submodule: &Submodule, cx: &mut Context<Self>) {
+        let Some(repository) = self.submodule_repository(submodule, cx) else {
+            return;
+        };
+        repository.update(cx, |repository, cx| repository.set_as_active_repository(cx));
+    }
+
+    /// Lists the commits that touched `path` in place of the panel's changes.
+    pub(crate) fn show_file_history(
+        &mut self,
+        repository: Entity<Repository>,
+        path: RepoPath,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let commits = repository.update(cx, |repository, _| repository.file_history(path.clone()));
+        let load_commits = cx.spawn_in(window, async move |this, cx| {
+            let commits = commits
+                .await
+                .map_err(anyhow::Error::from)
+                .and_then(|commits| commits);
+            this.update(cx, |this, cx| {
+                match commits {
+                    Ok(commits) => {
+                        if let Some(file_history) = this.file_history.as_mut() {
+                            file_history.commits = Some(commits);
+                        }
+                    }
+                    Err(e) => {
+                        this.file_history.take();
+                        this.show_error_toast("log", e, cx);
+                    }
+                }
+                cx.notify();
+            })
+            .ok();
+        });
+
+        self.file_history = Some(FileHistory {
+            repository,
+            path,
+            commits: None,
+            _load_commits: load_commits,
+        });
+        cx.notify();
+    }
+
+    fn file_history_for_selected(
+        &mut self,
+        _: &git::FileHistory,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(repo_path) = self
+            .get_selected_entry()
+            .and_then(|entry| entry.status_entry())
+            .map(|entry| entry.repo_path.clone())
+        else {
+            return;
+        };
+        let Some(repository) = self.active_repository.clone() else {
+            return;
+        };
+        self.show_file_history(repository, repo_path, window, cx);
+    }
+
+    fn close_file_history(&mut self, cx: &mut Context<Self>) {
+        self.file_history.take();
+        cx.notify();
+    }
+
+    /// Cherry-picks or reverts a commit onto the current branch of the given repository.
+    ///
+    /// If the commit doesn't apply cleanly, the repository is made active and the panel is
+    /// focused, so that the conflicted files can be resolved and committed from there.
+    pub(crate) fn apply_commit(
+        &mut self,
+        repo: Entity<Repository>,
+        commit: CommitSummary,
+        mode: ApplyCommitMode,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let short_sha = commit.sha[..git::SHORT_SHA_LENGTH.min(commit.sha.len())].to_owned();
+        let branch = repo
+            .read(cx)
+            .branch
+            .as_ref()
+            .map(|branch| branch.name().to_owned())
+            .unwrap_or_else(|| "HEAD".to_owned());
+        let (message, confirm_label) = match mode {
+            ApplyCommitMode::CherryPick => (
+                format!("Cherry-pick {short_sha} onto {branch}?"),
+                "Cherry-pick",
+            ),
+            ApplyCommitMode::Revert => (format!("Revert {short_sha} on {branch}?"), "Revert"),
+        };
+        let confirmation = window.prompt(
+            PromptLevel::Info,
+            &message,
+            Some(&commit.subject),
+            &[confirm_label, "Cancel"],
+            cx,
+        );
+
+        cx.spawn_in(window, async move |this, cx| {
+            if confirmation.await? != 0 {
+                return anyhow::Ok(());
+            }
+
+            let result = repo
+                .update(cx, |repo, cx| {
+                    repo.apply_commit(commit.sha.to_string(), mode, cx)
+                })?
+                .await?;
+
+            this.update_in(cx, |this, window, cx| match result {
+                Ok(ApplyCommitOutcome::Applied) => {}
+                Ok(ApplyCommitOutcome::Conflicted) => {
+                    repo.update(cx, |repo, cx| repo.set_as_active_repository(cx));
+                    this.show_conflicts(mode, window, cx)
+                }
+                Ok(ApplyCommitOutcome::Empty) => {
+                    this.show_empty_apply_commit(mode, &short_sha, &branch, cx)
+                }
+                Err(e) => this.show_error_toast(mode.name(), e, cx),
+            })?;
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn show_conflicts(&self, mode: ApplyCommitMode, window: &mut Window, cx: &mut App) {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+
+        workspace.update(cx, |workspace, cx| {
+            workspace.focus_panel::<GitPanel>(window, cx);
+            let message = format!("git {} stopped with conflicts", mode.name());
+            let toast = StatusToast::new(message, cx, |this, _cx| {
+                this.icon(ToastIcon::new(IconName::Warning).color(Color::Warning))
+            });
+            workspace.toggle_status_toast(toast, cx)
+        });
+    }
+
+    fn show_empty_apply_commit(
+        &self,
+        mode: ApplyCommitMode,
+        short_sha: &str,
+        branch: &str,
+        cx: &mut App,
+    ) {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+
+        let message = match mode {
+            ApplyCommitMode::CherryPick => {
+                format!("Nothing to cherry-pick: {short_sha} is already on {branch}")
+            }
+            ApplyCommitMode::Revert => {
+                format!("Nothing to revert: {short_sha} is already undone on {branch}")
+            }
+        };
+        workspace.update(cx, |workspace, cx| {
+            let toast = StatusToast::new(message, cx, |this, _cx| {
+                this.icon(ToastIcon::new(IconName::Info).color(Color::Muted))
+            });
+            workspace.toggle_status_toast(toast, cx)
+        });
+    }
+
     pub(crate) fn git_init(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         let worktrees = self
             .project
@@ -2140,26 +3157,28 @@ impl GitPanel {
 
         let room = room.read(cx);
 
-        for (peer_id, collaborator) in project.collaborators() {
-            if collaborator.is_host {
+        // The host authors commits made in a shared project, so they're never a co-author.
+        let host_peer_id = project
+            .collaborators()
+            .values()
+            .find(|collaborator| collaborator.is_host)
+            .map(|collaborator| collaborator.peer_id);
+        for participant in room.remote_participants().values() {
+            if Some(participant.peer_id) == host_peer_id || !participant.can_write() {
                 continue;
             }
-
-            let Some(participant) = room.remote_participant_for_peer_id(*peer_id) else {
+            let Some(email) = participant.user.email.clone() else {
                 continue;
             };
-            if participant.can_write() && participant.user.email.is_some() {
-                let email = participant.user.email.clone().unwrap();
-
-                new_co_authors.push((
-                    participant
-                        .user
-                        .name
-                        .clone()
-                        .unwrap_or_else(|| participant.user.github_login.clone()),
-                    email,
-                ))
-            }
+
+            new_co_authors.push((
+                participant
+                    .user
+                    .name
+                    .clone()
+                    .unwrap_or_else(|| participant.user.github_login.clone()),
+                email,
+            ))
         }
         if !project.is_local() && !project.is_read_only(cx) {
             if let Some(user) = room.local_participant_user(cx) {
@@ -2176,6 +3195,37 @@ impl GitPanel {
         new_co_authors
     }
 
+    fn selected_co_authors(&self, cx: &App) -> Vec<(String, String)> {
+        if !self.add_coauthors {
+            return Vec::new();
+        }
+        self.potential_co_authors(cx)
+            .into_iter()
+            .filter(|(_, email)| !self.excluded_co_authors.contains(email))
+            .collect()
+    }
+
+    fn toggle_co_author(&mut self, email: String, cx: &mut Context<Self>) {
+        if !self.add_coauthors {
+            self.add_coauthors = true;
+            self.excluded_co_authors = self
+                .potential_co_authors(cx)
+                .into_iter()
+                .map(|(_, email)| email)
+                .collect();
+        }
+        if !self.excluded_co_authors.remove(&email) {
+            self.excluded_co_authors.insert(email);
+        }
+        cx.notify();
+    }
+
+    fn set_all_co_authors(&mut self, selected: bool, cx: &mut Context<Self>) {
+        self.add_coauthors = selected;
+        self.excluded_co_authors.clear();
+        cx.notify();
+    }
+
     fn toggle_fill_co_authors(
         &mut self,
         _: &ToggleFillCoAuthors,
@@ -2186,6 +3236,82 @@ impl GitPanel {
         cx.notify();
     }
 
+    fn toggle_ignore_whitespace(
+        &mut self,
+        _: &ToggleIgnoreWhitespace,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let ignore_whitespace = !GitPanelSettings::get_global(cx).diff.ignore_whitespace;
+        self.update_diff_settings(cx, move |diff| {
+            diff.ignore_whitespace = Some(ignore_whitespace)
+        });
+    }
+
+    fn increase_diff_context(
+        &mut self,
+        _: &IncreaseDiffContext,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let context_lines = GitPanelSettings::get_global(cx).diff.context_lines + 1;
+        self.update_diff_settings(cx, move |diff| diff.context_lines = Some(context_lines));
+    }
+
+    fn decrease_diff_context(
+        &mut self,
+        _: &DecreaseDiffContext,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let context_lines = GitPanelSettings::get_global(cx)
+            .diff
+            .context_lines
+            .saturating_sub(1);
+        self.update_diff_settings(cx, move |diff| diff.context_lines = Some(context_lines));
+    }
+
+    fn update_diff_settings(
+        &self,
+        cx: &App,
+        update: impl 'static + Send + FnOnce(&mut DiffSettingsContent),
+    ) {
+        settings::update_settings_file::<GitPanelSettings>(
+            self.fs.clone(),
+            cx,
+            move |settings, _| update(settings.diff.get_or_insert_default()),
+        );
+    }
+
+    /// Loads whether the active repository is set to sign off commits, which
+    /// is remembered for each repository.
+    fn load_signoff(&mut self, cx: &App) {
+        self.signoff = self.active_repository.as_ref().is_some_and(|repo| {
+            KEY_VALUE_STORE
+                .read_kvp(&signoff_key(&repo.read(cx).work_directory_abs_path))
+                .log_err()
+                .flatten()
+                .is_some()
+        });
+    }
+
+    fn toggle_signoff(&mut self, _: &ToggleSignoff, _: &mut Window, cx: &mut Context<Self>) {
+        let Some(repo) = self.active_repository.as_ref() else {
+            return;
+        };
+        self.signoff = !self.signoff;
+        let key = signoff_key(&repo.read(cx).work_directory_abs_path);
+        let signoff = self.signoff;
+        db::write_and_log(cx, move || async move {
+            if signoff {
+                KEY_VALUE_STORE.write_kvp(key, "1".into()).await
+            } else {
+                KEY_VALUE_STORE.delete_kvp(key).await
+            }
+        });
+        cx.notify();
+    }
+
     fn fill_co_authors(&mut self, message: &mut String, cx: &mut Context<Self>) {
         const CO_AUTHOR_PREFIX: &str = "Co-authored-by: ";
 
@@ -2207,7 +3333,7 @@ impl GitPanel {
             .collect::<HashSet<_>>();
 
         let new_co_authors = self
-            .potential_co_authors(cx)
+            .selected_co_authors(cx)
             .into_iter()
             .filter(|(_, email)| {
                 !existing_co_authors
@@ -2245,19 +3371,67 @@ impl GitPanel {
         self.update_visible_entries_task = cx.spawn_in(window, async move |_, cx| {
             cx.background_executor().timer(UPDATE_DEBOUNCE).await;
             if let Some(git_panel) = handle.upgrade() {
-                git_panel
+                let renamed_paths = git_panel
                     .update_in(cx, |git_panel, window, cx| {
                         if clear_pending {
                             git_panel.clear_pending();
                         }
                         git_panel.update_visible_entries(cx);
                         git_panel.update_scrollbar_properties(window, cx);
+                        git_panel
+                            .active_repository
+                            .as_ref()
+                            .map(|repo| repo.update(cx, |repo, _| repo.renamed_paths()))
+                    })
+                    .ok()
+                    .flatten();
+
+                let renamed_paths = match renamed_paths {
+                    Some(renamed_paths) => renamed_paths
+                        .await
+                        .ok()
+                        .and_then(|renamed_paths| renamed_paths.log_err())
+                        .unwrap_or_default(),
+                    None => Vec::new(),
+                };
+                git_panel
+                    .update(cx, |git_panel, cx| {
+                        if git_panel.renamed_paths != renamed_paths {
+                            git_panel.renamed_paths = renamed_paths;
+                            git_panel.update_visible_entries(cx);
+                        }
+                        cx.notify();
                     })
                     .ok();
             }
         });
     }
 
+    /// Reloads the active repository's submodules. This happens whenever the repository is
+    /// rescanned, since checkouts, pulls and submodule updates can all change them, but not on the
+    /// incremental status updates made while files are edited.
+    fn refresh_submodules(&mut self, cx: &mut Context<Self>) {
+        let Some(repository) = self.active_repository.clone() else {
+            self.submodules.clear();
+            self.refresh_submodules_task = Task::ready(());
+            return;
+        };
+        let submodules = repository.update(cx, |repository, _| repository.submodules());
+        self.refresh_submodules_task = cx.spawn(async move |git_panel, cx| {
+            let submodules = submodules
+                .await
+                .ok()
+                .and_then(|submodules| submodules.log_err())
+                .unwrap_or_default();
+            git_panel
+                .update(cx, |git_panel, cx| {
+                    git_panel.submodules = submodules;
+                    cx.notify();
+                })
+                .ok();
+        });
+    }
+
     fn reopen_commit_buffer(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         let Some(active_repo) = self.active_repository.as_ref() else {
             return;
@@ -2304,6 +3478,11 @@ impl GitPanel {
     }
 
     fn update_visible_entries(&mut self, cx: &mut Context<Self>) {
+        // Marks are row indices, so they're restored by path once the rows are rebuilt.
+        let marked_paths = std::mem::take(&mut self.marked_entries)
+            .into_iter()
+            .filter_map(|ix| Some(self.entries.get(ix)?.status_entry()?.repo_path.clone()))
+            .collect::<Vec<_>>();
         self.entries.clear();
         self.single_staged_entry.take();
         self.single_tracked_entry.take();
@@ -2326,15 +3505,33 @@ impl GitPanel {
 
         let Some(repo) = self.active_repository.as_ref() else {
             // Just clear entries if no repository is active.
+            self.inline_diffs.clear();
             cx.notify();
             return;
         };
 
         let repo = repo.read(cx);
+        self.inline_diffs
+            .retain(|repo_path, _| repo.status_for_path(repo_path).is_some());
+
+        let renames = self
+            .renamed_paths
+            .iter()
+            .map(|rename| (rename.new_path.clone(), rename.clone()))
+            .collect::<HashMap<_, _>>();
+        let moved_paths = self
+            .renamed_paths
+            .iter()
+            .filter(|rename| rename.kind == RenameKind::Renamed)
+            .map(|rename| rename.old_path.clone())
+            .collect::<HashSet<_>>();
 
         for entry in repo.cached_status() {
+            // The deletion of a renamed file is shown as part of its rename.
+            if entry.status.is_deleted() && moved_paths.contains(&entry.repo_path) {
+                continue;
+            }
             let is_conflict = repo.had_conflict_on_last_merge_head_change(&entry.repo_path);
-            let is_new = entry.status.is_created();
             let staging = entry.status.staging();
 
             if self.pending.iter().any(|pending| {
@@ -2354,7 +3551,9 @@ impl GitPanel {
                 abs_path,
                 status: entry.status,
                 staging,
+                rename: renames.get(&entry.repo_path).cloned(),
             };
+            let is_new = entry.is_new();
 
             if staging.has_staged() {
                 staged_count += 1;
@@ -2425,11 +3624,7 @@ impl GitPanel {
             self.entries.push(GitListEntry::Header(GitHeaderEntry {
                 header: Section::Conflict,
             }));
-            self.entries.extend(
-                conflict_entries
-                    .into_iter()
-                    .map(GitListEntry::GitStatusEntry),
-            );
+            self.push_status_entries(conflict_entries);
         }
 
         if changed_entries.len() > 0 {
@@ -2438,18 +3633,13 @@ impl GitPanel {
                     header: Section::Tracked,
                 }));
             }
-            self.entries.extend(
-                changed_entries
-                    .into_iter()
-                    .map(GitListEntry::GitStatusEntry),
-            );
+            self.push_status_entries(changed_entries);
         }
         if new_entries.len() > 0 {
             self.entries.push(GitListEntry::Header(GitHeaderEntry {
                 header: Section::New,
             }));
-            self.entries
-                .extend(new_entries.into_iter().map(GitListEntry::GitStatusEntry));
+            self.push_status_entries(new_entries);
         }
 
         if let Some((repo_path, _)) = max_width_item {
@@ -2457,12 +3647,26 @@ impl GitPanel {
                 GitListEntry::GitStatusEntry(git_status_entry) => {
                     git_status_entry.repo_path == repo_path
                 }
-                GitListEntry::Header(_) => false,
+                GitListEntry::Header(_) | GitListEntry::DiffLine(_) => false,
             });
         }
 
         self.update_counts(repo);
 
+        self.restore_serialized_state(cx);
+        if let Some(ix) = self
+            .pending_selection
+            .as_ref()
+            .and_then(|path| self.entry_by_path(path, cx))
+        {
+            self.pending_selection = None;
+            self.selected_entry = Some(ix);
+            self.scroll_to_selected_entry(cx);
+        }
+        self.marked_entries = marked_paths
+            .iter()
+            .filter_map(|path| self.entry_by_path(path, cx))
+            .collect();
         self.select_first_entry_if_none(cx);
 
         let suggested_commit_message = self.suggest_commit_message(cx);
@@ -2475,6 +3679,24 @@ impl GitPanel {
         cx.notify();
     }
 
+    fn push_status_entries(&mut self, status_entries: Vec<GitStatusEntry>) {
+        for status_entry in status_entries {
+            let line_count = self
+                .inline_diffs
+                .get(&status_entry.repo_path)
+                .map_or(0, |inline_diff| inline_diff.lines.len());
+            let repo_path = status_entry.repo_path.clone();
+            self.entries
+                .push(GitListEntry::GitStatusEntry(status_entry));
+            self.entries.extend((0..line_count).map(|line_ix| {
+                GitListEntry::DiffLine(GitDiffLineEntry {
+                    repo_path: repo_path.clone(),
+                    line_ix,
+                })
+            }));
+        }
+    }
+
     fn header_state(&self, header_type: Section) -> ToggleState {
         let (staged_count, count) = match header_type {
             Section::New => (self.new_staged_count, self.new_count),
@@ -2509,7 +3731,7 @@ impl GitPanel {
                 if self.entry_staging(status_entry).has_staged() {
                     self.conflicted_staged_count += 1;
                 }
-            } else if status_entry.status.is_created() {
+            } else if status_entry.is_new() {
                 self.new_count += 1;
                 if self.entry_staging(status_entry).has_staged() {
                     self.new_staged_count += 1;
@@ -2677,6 +3899,7 @@ impl GitPanel {
         let has_staged_changes = self.has_staged_changes();
         let has_unstaged_changes = self.has_unstaged_changes();
         let has_new_changes = self.new_count > 0;
+        let has_submodules = !self.submodules.is_empty();
 
         PopoverMenu::new(id.into())
             .trigger(
@@ -2692,6 +3915,8 @@ impl GitPanel {
                         has_staged_changes,
                         has_unstaged_changes,
                         has_new_changes,
+                        has_submodules,
+                        ignore_whitespace: GitPanelSettings::get_global(cx).diff.ignore_whitespace,
                     },
                     window,
                     cx,
@@ -2756,45 +3981,128 @@ impl GitPanel {
 
     pub(crate) fn render_co_authors(&self, cx: &Context<Self>) -> Option<AnyElement> {
         let potential_co_authors = self.potential_co_authors(cx);
+        if potential_co_authors.is_empty() {
+            return None;
+        }
 
-        let (tooltip_label, icon) = if self.add_coauthors {
-            ("Remove co-authored-by", IconName::Person)
-        } else {
+        let selected_co_authors = self.selected_co_authors(cx);
+        let (tooltip_label, icon) = if selected_co_authors.is_empty() {
             ("Add co-authored-by", IconName::UserCheck)
+        } else {
+            ("Co-authored-by", IconName::Person)
         };
-
-        if potential_co_authors.is_empty() {
-            None
+        let tooltip = if selected_co_authors.is_empty() {
+            tooltip_label.to_string()
         } else {
-            Some(
-                IconButton::new("co-authors", icon)
-                    .shape(ui::IconButtonShape::Square)
-                    .icon_color(Color::Disabled)
-                    .selected_icon_color(Color::Selected)
-                    .toggle_state(self.add_coauthors)
-                    .tooltip(move |_, cx| {
-                        let title = format!(
-                            "{}:{}{}",
-                            tooltip_label,
-                            if potential_co_authors.len() == 1 {
-                                ""
-                            } else {
-                                "\n"
-                            },
-                            potential_co_authors
+            format!(
+                "{}:{}{}",
+                tooltip_label,
+                if selected_co_authors.len() == 1 {
+                    ""
+                } else {
+                    "\n"
+                },
+                selected_co_authors
+                    .iter()
+                    .map(|(name, email)| format!(" {} <{}>", name, email))
+                    .join("\n")
+            )
+        };
+
+        let git_panel = cx.weak_entity();
+        Some(
+            PopoverMenu::new("co-authors")
+                .trigger_with_tooltip(
+                    IconButton::new("co-authors-button", icon)
+                        .shape(ui::IconButtonShape::Square)
+                        .icon_color(if selected_co_authors.is_empty() {
+                            Color::Disabled
+                        } else {
+                            Color::Selected
+                        }),
+                    Tooltip::text(tooltip),
+                )
+                .menu(move |window, cx| {
+                    let git_panel = git_panel.clone();
+                    let potential_co_authors = potential_co_authors.clone();
+                    let selected_co_authors = selected_co_authors.clone();
+                    Some(ContextMenu::build(window, cx, move |mut menu, _, _| {
+                        menu = menu.header("Co-authored-by");
+                        for (name, email) in potential_co_authors {
+                            let selected = selected_co_authors
                                 .iter()
-                                .map(|(name, email)| format!(" {} <{}>", name, email))
-                                .join("\n")
-                        );
-                        Tooltip::simple(title, cx)
-                    })
-                    .on_click(cx.listener(|this, _, _, cx| {
-                        this.add_coauthors = !this.add_coauthors;
-                        cx.notify();
+                                .any(|(_, selected_email)| *selected_email == email);
+                            let git_panel = git_panel.clone();
+                            menu = menu.toggleable_entry(
+                                format!("{name} <{email}>"),
+                                selected,
+                                IconPosition::Start,
+                                None,
+                                move |_, cx| {
+                                    git_panel
+                                        .update(cx, |git_panel, cx| {
+                                            git_panel.toggle_co_author(email.clone(), cx)
+                                        })
+                                        .ok();
+                                },
+                            );
+                        }
+                        let select_all = git_panel.clone();
+                        let select_none = git_panel.clone();
+                        menu.separator()
+                            .entry("Select All", None, move |_, cx| {
+                                select_all
+                                    .update(cx, |git_panel, cx| {
+                                        git_panel.set_all_co_authors(true, cx)
+                                    })
+                                    .ok();
+                            })
+                            .entry("Select None", None, move |_, cx| {
+                                select_none
+                                    .update(cx, |git_panel, cx| {
+                                        git_panel.set_all_co_authors(false, cx)
+                                    })
+                                    .ok();
+                            })
                     }))
-                    .into_any_element(),
-            )
-        }
+                })
+                .anchor(Corner::BottomRight)
+                .into_any_element(),
+        )
+    }
+
+    pub(crate) fn render_signoff_button(&self, cx: &Context<Self>) -> Option<AnyElement> {
+        self.active_repository.as_ref()?;
+        let focus_handle = self.commit_editor.focus_handle(cx);
+        let tooltip = if self.signoff {
+            "Signing off commits"
+        } else {
+            "Sign off commits"
+        };
+        Some(
+            IconButton::new("signoff-button", IconName::UserRoundPen)
+                .shape(ui::IconButtonShape::Square)
+                .toggle_state(self.signoff)
+                .icon_color(if self.signoff {
+                    Color::Selected
+                } else {
+                    Color::Disabled
+                })
+                .tooltip(move |window, cx| {
+                    Tooltip::with_meta_in(
+                        tooltip,
+                        Some(&ToggleSignoff),
+                        "Adds a Signed-off-by trailer with your git user name and email",
+                        &focus_handle,
+                        window,
+                        cx,
+                    )
+                })
+                .on_click(cx.listener(|this, _, window, cx| {
+                    this.toggle_signoff(&ToggleSignoff, window, cx)
+                }))
+                .into_any_element(),
+        )
     }
 
     fn render_git_commit_menu(
@@ -2834,6 +4142,11 @@ impl GitPanel {
             (false, "Commit in progress")
         } else if self.custom_or_suggested_commit_message(cx).is_none() {
             (false, "No commit message")
+        } else if !self.enforced_commit_message_warnings(cx).is_empty() {
+            (
+                false,
+                "Address the commit message warnings before committing",
+            )
         } else if !self.has_write_access(cx) {
             (false, "You do not have write access to this project")
         } else {
@@ -2841,6 +4154,66 @@ impl GitPanel {
         }
     }
 
+    fn commit_message_warnings(&self, cx: &App) -> Vec<CommitMessageWarning> {
+        let settings = GitPanelSettings::get_global(cx).commit_message_lint;
+        if settings.mode == CommitMessageLintMode::Off {
+            return Vec::new();
+        }
+        lint_commit_message(&self.commit_editor.read(cx).text(cx), &settings)
+    }
+
+    fn enforced_commit_message_warnings(&self, cx: &App) -> Vec<CommitMessageWarning> {
+        if GitPanelSettings::get_global(cx).commit_message_lint.mode
+            == CommitMessageLintMode::Enforce
+        {
+            self.commit_message_warnings(cx)
+        } else {
+            Vec::new()
+        }
+    }
+
+    pub(crate) fn render_commit_message_warnings(&self, cx: &Context<Self>) -> Option<AnyElement> {
+        let warnings = self.commit_message_warnings(cx);
+        let first_warning = warnings.first()?;
+        let color = match GitPanelSettings::get_global(cx).commit_message_lint.mode {
+            CommitMessageLintMode::Enforce => Color::Error,
+            CommitMessageLintMode::Hint | CommitMessageLintMode::Off => Color::Warning,
+        };
+        let tooltip = warnings
+            .iter()
+            .map(CommitMessageWarning::message)
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        Some(
+            h_flex()
+                .id("commit-message-warnings")
+                .min_w_0()
+                .gap_1()
+                .overflow_x_hidden()
+                .child(
+                    Icon::new(IconName::Warning)
+                        .size(IconSize::Small)
+                        .color(color),
+                )
+                .child(
+                    Label::new(first_warning.message())
+                        .size(LabelSize::Small)
+                        .color(color)
+                        .truncate(),
+                )
+                .when(warnings.len() > 1, |this| {
+                    this.child(
+                        Label::new(format!("+{}", warnings.len() - 1))
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                })
+                .tooltip(Tooltip::text(tooltip))
+                .into_any_element(),
+        )
+    }
+
     pub fn commit_button_title(&self) -> &'static str {
         if self.amend_pending {
             if self.has_staged_changes() {
@@ -2969,6 +4342,7 @@ impl GitPanel {
         let panel_editor_style = panel_editor_style(true, window, cx);
 
         let enable_coauthors = self.render_co_authors(cx);
+        let signoff = self.render_signoff_button(cx);
 
         let editor_focus_handle = self.commit_editor.focus_handle(cx);
         let expand_tooltip_focus_handle = editor_focus_handle.clone();
@@ -3031,12 +4405,16 @@ impl GitPanel {
                             .flex_none()
                             .justify_between()
                             .child(
-                                self.render_generate_commit_message_button(cx)
-                                    .unwrap_or_else(|| div().into_any_element()),
+                                h_flex()
+                                    .min_w_0()
+                                    .gap_1()
+                                    .children(self.render_generate_commit_message_button(cx))
+                                    .children(self.render_commit_message_warnings(cx)),
                             )
                             .child(
                                 h_flex()
                                     .gap_0p5()
+                                    .children(signoff)
                                     .children(enable_coauthors)
                                     .child(self.render_commit_button(has_previous_commit, cx)),
                             ),
@@ -3147,7 +4525,10 @@ impl GitPanel {
                                             .update(cx, |git_panel, cx| {
                                                 git_panel.set_amend_pending(false, cx);
                                                 git_panel.commit_changes(
-                                                    CommitOptions { amend: true },
+                                                    CommitOptions {
+                                                        amend: true,
+                                                        ..Default::default()
+                                                    },
                                                     window,
                                                     cx,
                                                 );
@@ -3178,7 +4559,7 @@ impl GitPanel {
                                 git_panel
                                     .update(cx, |git_panel, cx| {
                                         git_panel.commit_changes(
-                                            CommitOptions { amend: false },
+                                            CommitOptions::default(),
                                             window,
                                             cx,
                                         );
@@ -3236,7 +4617,7 @@ impl GitPanel {
                                     git_panel
                                         .update(cx, |git_panel, cx| {
                                             git_panel.commit_changes(
-                                                CommitOptions { amend: false },
+                                                CommitOptions::default(),
                                                 window,
                                                 cx,
                                             );
@@ -3560,6 +4941,9 @@ impl GitPanel {
                     .border_b_1()
                     .border_color(cx.theme().colors().border)
             })
+            .when(has_write_access && cx.has_active_drag(), |this| {
+                this.child(self.render_drop_targets(cx))
+            })
             .child(
                 h_flex()
                     .flex_1()
@@ -3591,6 +4975,14 @@ impl GitPanel {
                                                 cx,
                                             ));
                                         }
+                                        Some(GitListEntry::DiffLine(line)) => {
+                                            items.push(this.render_inline_diff_line(
+                                                ix,
+                                                line,
+                                                has_write_access,
+                                                cx,
+                                            ));
+                                        }
                                         None => {}
                                     }
                                 }
@@ -3690,39 +5082,435 @@ impl GitPanel {
             })
     }
 
-    fn entry_label(&self, label: impl Into<SharedString>, color: Color) -> Label {
-        Label::new(label.into()).color(color).single_line()
+    fn entry_label(&self, label: impl Into<SharedString>, color: Color) -> Label {
+        Label::new(label.into()).color(color).single_line()
+    }
+
+    fn list_item_height(&self) -> Rems {
+        rems(1.75)
+    }
+
+    fn render_drop_targets(&self, cx: &Context<Self>) -> impl IntoElement {
+        let drop_target = |id: &'static str, label: &'static str, stage: bool| {
+            h_flex()
+                .id(id)
+                .flex_1()
+                .h(self.list_item_height())
+                .justify_center()
+                .border_1()
+                .border_dashed()
+                .border_color(cx.theme().colors().border)
+                .rounded_sm()
+                .child(Label::new(label).size(LabelSize::Small).color(Color::Muted))
+                .drag_over::<GitEntriesDrag>(|style, _, _, cx| {
+                    style.bg(cx.theme().colors().drop_target_background)
+                })
+                .on_drop(cx.listener(move |this, dragged: &GitEntriesDrag, _, cx| {
+                    this.drop_entries(dragged, stage, cx);
+                }))
+        };
+
+        h_flex()
+            .p_1()
+            .gap_1()
+            .child(drop_target("stage-drop-target", "Drop to Stage", true))
+            .child(drop_target("unstage-drop-target", "Drop to Unstage", false))
+    }
+
+    fn render_list_header(
+        &self,
+        ix: usize,
+        header: &GitHeaderEntry,
+        has_write_access: bool,
+        _: &Window,
+        cx: &Context<Self>,
+    ) -> AnyElement {
+        let id: ElementId = ElementId::Name(format!("header_{}", ix).into());
+
+        h_flex()
+            .id(id)
+            .h(self.list_item_height())
+            .w_full()
+            .items_end()
+            .px(rems(0.75)) // ~12px
+            .pb(rems(0.3125)) // ~ 5px
+            .when(has_write_access, |this| {
+                self.draggable_entries(this, ix, cx)
+            })
+            .child(
+                Label::new(header.title())
+                    .color(Color::Muted)
+                    .size(LabelSize::Small)
+                    .line_height_style(LineHeightStyle::UiLabel)
+                    .single_line(),
+            )
+            .into_any_element()
+    }
+
+    fn render_inline_diff_line(
+        &self,
+        ix: usize,
+        line: &GitDiffLineEntry,
+        has_write_access: bool,
+        cx: &Context<Self>,
+    ) -> AnyElement {
+        let Some(diff_line) = self
+            .inline_diffs
+            .get(&line.repo_path)
+            .and_then(|inline_diff| inline_diff.lines.get(line.line_ix))
+        else {
+            return div().h(self.list_item_height()).into_any_element();
+        };
+
+        let settings = ThemeSettings::get_global(cx);
+        let text_style = TextStyle {
+            color: cx.theme().colors().text,
+            font_family: settings.buffer_font.family.clone(),
+            font_fallbacks: settings.buffer_font.fallbacks.clone(),
+            font_size: settings.buffer_font_size(cx).into(),
+            font_weight: settings.buffer_font.weight,
+            ..Default::default()
+        };
+        let text = diff_line.text.to_styled_text(&text_style);
+
+        let row = h_flex()
+            .id(ElementId::Name(format!("diff_line_{ix}").into()))
+            .h(self.list_item_height())
+            .w_full()
+            .flex_none()
+            .pl(rems(2.5))
+            .pr(rems(0.75))
+            .gap_1p5();
+
+        match &diff_line.kind {
+            InlineDiffLineKind::HunkHeader { hunk, is_staged } => {
+                let (label, stage) = if *is_staged {
+                    ("Unstage", false)
+                } else {
+                    ("Stage", true)
+                };
+                let repo_path = line.repo_path.clone();
+                let hunk = hunk.clone();
+                row.justify_between()
+                    .child(
+                        Label::new(diff_line.text.text.clone())
+                            .size(LabelSize::Small)
+                            .color(Color::Muted)
+                            .buffer_font(cx),
+                    )
+                    .child(
+                        Button::new(ElementId::Name(format!("stage_hunk_{ix}").into()), label)
+                            .label_size(LabelSize::Small)
+                            .disabled(!has_write_access)
+                            .on_click(cx.listener(move |this, _, _, cx| {
+                                this.stage_inline_diff_hunk(&repo_path, hunk.clone(), stage, cx);
+                                cx.stop_propagation();
+                            })),
+                    )
+                    .into_any_element()
+            }
+            InlineDiffLineKind::Deleted => row
+                .bg(cx.theme().colors().version_control_deleted.alpha(0.12))
+                .child(text)
+                .into_any_element(),
+            InlineDiffLineKind::Added => row
+                .bg(cx.theme().colors().version_control_added.alpha(0.12))
+                .child(text)
+                .into_any_element(),
+        }
+    }
+
+    fn render_file_history(&self, cx: &mut Context<Self>) -> Option<AnyElement> {
+        let file_history = self.file_history.as_ref()?;
+
+        let header = h_flex()
+            .h(self.list_item_height())
+            .w_full()
+            .px(rems(0.5))
+            .gap_1()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .child(
+                IconButton::new("close-file-history", IconName::ArrowLeft)
+                    .icon_size(IconSize::Small)
+                    .icon_color(Color::Muted)
+                    .tooltip(Tooltip::text("Back to Changes"))
+                    .on_click(cx.listener(|this, _, _, cx| this.close_file_history(cx))),
+            )
+            .child(
+                Label::new("History of")
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(
+                self.entry_label(
+                    file_history.path.to_string_lossy().into_owned(),
+                    Color::Default,
+                )
+                .size(LabelSize::Small)
+                .truncate(),
+            );
+
+        let body = match &file_history.commits {
+            None => self.render_file_history_message("Loading history…"),
+            Some(commits) if commits.is_empty() => {
+                self.render_file_history_message("No commits have touched this file")
+            }
+            Some(commits) => uniform_list(cx.entity(), "file-history", commits.len(), {
+                move |this, range, _, cx| {
+                    let Some(file_history) = this.file_history.as_ref() else {
+                        return Vec::new();
+                    };
+                    let Some(commits) = file_history.commits.as_ref() else {
+                        return Vec::new();
+                    };
+                    range
+                        .filter_map(|ix| {
+                            let commit = commits.get(ix)?;
+                            Some(this.render_file_history_entry(ix, commit, file_history, cx))
+                        })
+                        .collect()
+                }
+            })
+            .size_full()
+            .flex_grow()
+            .into_any_element(),
+        };
+
+        Some(
+            v_flex()
+                .flex_1()
+                .size_full()
+                .overflow_hidden()
+                .child(header)
+                .child(body)
+                .into_any_element(),
+        )
+    }
+
+    fn render_file_history_message(&self, message: &'static str) -> AnyElement {
+        h_flex()
+            .flex_grow()
+            .justify_center()
+            .items_center()
+            .child(
+                Label::new(message)
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .into_any_element()
+    }
+
+    fn render_file_history_entry(
+        &self,
+        ix: usize,
+        commit: &CommitSummary,
+        file_history: &FileHistory,
+        cx: &Context<Self>,
+    ) -> AnyElement {
+        let short_sha = commit.sha[..git::SHORT_SHA_LENGTH.min(commit.sha.len())].to_string();
+        let commit_time = OffsetDateTime::from_unix_timestamp(commit.commit_timestamp)
+            .unwrap_or_else(|_| OffsetDateTime::now_utc());
+        let relative_time = time_format::format_local_timestamp(
+            commit_time,
+            OffsetDateTime::now_utc(),
+            time_format::TimestampFormat::Relative,
+        );
+
+        h_flex()
+            .id(("file-history-entry", ix))
+            .h(self.list_item_height())
+            .w_full()
+            .px(rems(0.75))
+            .gap_1p5()
+            .cursor_pointer()
+            .hover(|style| style.bg(cx.theme().colors().ghost_element_hover))
+            .child(
+                Label::new(short_sha)
+                    .size(LabelSize::Small)
+                    .color(Color::Muted)
+                    .buffer_font(cx),
+            )
+            .child(
+                div().flex_1().overflow_hidden().child(
+                    Label::new(commit.subject.clone())
+                        .size(LabelSize::Small)
+                        .truncate(),
+                ),
+            )
+            .child(
+                Label::new(relative_time)
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .on_click({
+                let commit = commit.clone();
+                let path = file_history.path.clone();
+                let repository = file_history.repository.downgrade();
+                let workspace = self.workspace.clone();
+                move |_, window, cx| {
+                    CommitView::open_for_path(
+                        commit.clone(),
+                        Some(path.clone()),
+                        repository.clone(),
+                        workspace.clone(),
+                        window,
+                        cx,
+                    );
+                }
+            })
+            .on_mouse_down(MouseButton::Right, {
+                let commit = commit.clone();
+                let repository = file_history.repository.clone();
+                cx.listener(move |this, event: &MouseDownEvent, window, cx| {
+                    this.deploy_file_history_context_menu(
+                        event.position,
+                        repository.clone(),
+                        commit.clone(),
+                        window,
+                        cx,
+                    )
+                })
+            })
+            .into_any_element()
+    }
+
+    fn deploy_file_history_context_menu(
+        &mut self,
+        position: Point<Pixels>,
+        repository: Entity<Repository>,
+        commit: CommitSummary,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let this = cx.weak_entity();
+        let apply_commit = move |mode: ApplyCommitMode| {
+            let this = this.clone();
+            let repository = repository.clone();
+            let commit = commit.clone();
+            move |window: &mut Window, cx: &mut App| {
+                this.update(cx, |this, cx| {
+                    this.apply_commit(repository.clone(), commit.clone(), mode, window, cx)
+                })
+                .ok();
+            }
+        };
+        let context_menu = ContextMenu::build(window, cx, |context_menu, _, _| {
+            context_menu
+                .entry(
+                    "Cherry-pick Commit",
+                    None,
+                    apply_commit(ApplyCommitMode::CherryPick),
+                )
+                .entry("Revert Commit", None, apply_commit(ApplyCommitMode::Revert))
+        });
+        self.set_context_menu(context_menu, position, window, cx);
     }
 
-    fn list_item_height(&self) -> Rems {
-        rems(1.75)
-    }
+    fn render_submodules(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        if self.submodules.is_empty() {
+            return None;
+        }
 
-    fn render_list_header(
-        &self,
-        ix: usize,
-        header: &GitHeaderEntry,
-        _: bool,
-        _: &Window,
-        _: &Context<Self>,
-    ) -> AnyElement {
-        let id: ElementId = ElementId::Name(format!("header_{}", ix).into());
+        let can_update = self.can_push_and_pull(cx) && self.has_write_access(cx);
+        let repo = self.active_repository.as_ref()?.read(cx);
 
-        h_flex()
-            .id(id)
+        let header = h_flex()
             .h(self.list_item_height())
             .w_full()
-            .items_end()
-            .px(rems(0.75)) // ~12px
-            .pb(rems(0.3125)) // ~ 5px
+            .justify_between()
+            .px(rems(0.75))
             .child(
-                Label::new(header.title())
+                Label::new("Submodules")
                     .color(Color::Muted)
                     .size(LabelSize::Small)
                     .line_height_style(LineHeightStyle::UiLabel)
                     .single_line(),
             )
-            .into_any_element()
+            .when(can_update, |this| {
+                this.child(
+                    IconButton::new("update-submodules", IconName::ArrowCircle)
+                        .icon_size(IconSize::Small)
+                        .icon_color(Color::Muted)
+                        .tooltip(Tooltip::for_action_title(
+                            "Update Submodules",
+                            &UpdateSubmodules,
+                        ))
+                        .on_click(|_, window, cx| {
+                            window.dispatch_action(UpdateSubmodules.boxed_clone(), cx)
+                        }),
+                )
+            });
+
+        let rows = self.submodules.iter().enumerate().map(|(ix, submodule)| {
+            let is_dirty = repo.status_for_path(&submodule.path).is_some();
+            let (status, status_color) = match submodule.status {
+                SubmoduleStatus::Uninitialized => (Some("not initialized"), Color::Muted),
+                SubmoduleStatus::OutOfSync => (Some("out of sync"), Color::Warning),
+                SubmoduleStatus::Conflicted => (Some("conflicted"), Color::VersionControlConflict),
+                SubmoduleStatus::UpToDate if is_dirty => {
+                    (Some("modified"), Color::VersionControlModified)
+                }
+                SubmoduleStatus::UpToDate => (None, Color::Muted),
+            };
+            let can_open = self.submodule_repository(submodule, cx).is_some();
+            let submodule = submodule.clone();
+
+            h_flex()
+                .id(("submodule", ix))
+                .h(self.list_item_height())
+                .w_full()
+                .px(rems(0.75))
+                .gap_1p5()
+                .child(
+                    Icon::new(IconName::FileGit)
+                        .size(IconSize::Small)
+                        .color(Color::Muted),
+                )
+                .child(
+                    self.entry_label(
+                        submodule.path.to_string_lossy().into_owned(),
+                        Color::Default,
+                    )
+                    .truncate(),
+                )
+                .child(
+                    Label::new(submodule.short_sha())
+                        .size(LabelSize::Small)
+                        .color(Color::Muted)
+                        .buffer_font(cx),
+                )
+                .children(status.map(|status| {
+                    Label::new(status)
+                        .size(LabelSize::Small)
+                        .color(status_color)
+                }))
+                .child(div().flex_grow())
+                .child(
+                    IconButton::new(("open-submodule", ix), IconName::ArrowUpRight)
+                        .icon_size(IconSize::Small)
+                        .icon_color(Color::Muted)
+                        .disabled(!can_open)
+                        .tooltip(Tooltip::text(if can_open {
+                            "Open Submodule Repository"
+                        } else {
+                            "Submodule Is Not Initialized"
+                        }))
+                        .on_click(cx.listener(move |this, _, _, cx| {
+                            this.open_submodule(&submodule, cx);
+                        })),
+                )
+        });
+
+        Some(
+            v_flex()
+                .w_full()
+                .pb_1()
+                .border_t_1()
+                .border_color(cx.theme().colors().border)
+                .child(header)
+                .children(rows),
+        )
     }
 
     pub fn load_commit_details(
@@ -3759,19 +5547,129 @@ impl GitPanel {
         } else {
             "Restore File"
         };
+        let is_untracked = entry.status.is_untracked();
+        let repo_path = entry.repo_path.clone();
+        let ignore_files = self
+            .active_repository
+            .as_ref()
+            .filter(|_| is_untracked)
+            .map(|repo| {
+                let repo = repo.read(cx);
+                let work_directory = &repo.work_directory_abs_path;
+                let exclude_file = repo.exclude_file_abs_path();
+                [
+                    IgnoreFile::Repository,
+                    IgnoreFile::Directory,
+                    IgnoreFile::Exclude,
+                ]
+                .into_iter()
+                .filter_map(|ignore_file| {
+                    let (path, _) = ignore_file.location(
+                        &repo_path,
+                        work_directory,
+                        exclude_file.as_deref(),
+                    )?;
+                    let label = path.strip_prefix(work_directory).unwrap_or(&path);
+                    Some((ignore_file, format!("Add to {}", label.display())))
+                })
+                .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        let git_panel = cx.weak_entity();
         let context_menu = ContextMenu::build(window, cx, |context_menu, _, _| {
             context_menu
                 .context(self.focus_handle.clone())
                 .action(stage_title, ToggleStaged.boxed_clone())
+                .when(is_untracked, |menu| {
+                    menu.action("Stage Partially", git::StagePartially.boxed_clone())
+                })
                 .action(restore_title, git::RestoreFile::default().boxed_clone())
                 .separator()
+                .action("Stash File", git::StashFile.boxed_clone())
+                .action(
+                    "Stash File and Switch Branch…",
+                    StashFileAndSwitchBranch.boxed_clone(),
+                )
+                .separator()
                 .action("Open Diff", Confirm.boxed_clone())
                 .action("Open File", SecondaryConfirm.boxed_clone())
+                .action("File History", git::FileHistory.boxed_clone())
+                .when(!ignore_files.is_empty(), |mut menu| {
+                    menu = menu.separator();
+                    for (ignore_file, label) in ignore_files {
+                        let git_panel = git_panel.clone();
+                        let repo_path = repo_path.clone();
+                        menu = menu.entry(label, None, move |window, cx| {
+                            git_panel
+                                .update(cx, |git_panel, cx| {
+                                    git_panel.add_to_ignore_file(
+                                        repo_path.clone(),
+                                        ignore_file,
+                                        window,
+                                        cx,
+                                    )
+                                })
+                                .ok();
+                        });
+                    }
+                    menu
+                })
         });
         self.selected_entry = Some(ix);
         self.set_context_menu(context_menu, position, window, cx);
     }
 
+    /// Adds a pattern matching the file at `repo_path` to the given ignore file,
+    /// creating the file if needed.
+    ///
+    /// The file is edited through its buffer, so that the change can be undone
+    /// like any other edit.
+    fn add_to_ignore_file(
+        &mut self,
+        repo_path: RepoPath,
+        ignore_file: IgnoreFile,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(repo) = self.active_repository.as_ref() else {
+            return;
+        };
+        let repo = repo.read(cx);
+        let Some((abs_path, pattern)) = ignore_file.location(
+            &repo_path,
+            &repo.work_directory_abs_path,
+            repo.exclude_file_abs_path().as_deref(),
+        ) else {
+            return;
+        };
+        let project = self.project.clone();
+        cx.spawn_in(window, async move |_, cx| {
+            let (worktree, relative_path) = project
+                .update(cx, |project, cx| {
+                    project.find_or_create_worktree(&abs_path, false, cx)
+                })?
+                .await?;
+            let project_path = worktree.read_with(cx, |worktree, _| ProjectPath {
+                worktree_id: worktree.id(),
+                path: relative_path.into(),
+            })?;
+            let buffer = project
+                .update(cx, |project, cx| project.open_buffer(project_path, cx))?
+                .await?;
+            let edited =
+                buffer.update(cx, |buffer, cx| append_ignore_pattern(buffer, &pattern, cx))?;
+            if edited {
+                project
+                    .update(cx, |project, cx| project.save_buffer(buffer, cx))?
+                    .await?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_prompt_err("Failed to update ignore file", window, cx, |e, _, _| {
+            Some(format!("{e}"))
+        });
+    }
+
     fn deploy_panel_context_menu(
         &mut self,
         position: Point<Pixels>,
@@ -3785,6 +5683,8 @@ impl GitPanel {
                 has_staged_changes: self.has_staged_changes(),
                 has_unstaged_changes: self.has_unstaged_changes(),
                 has_new_changes: self.new_count > 0,
+                has_submodules: !self.submodules.is_empty(),
+                ignore_whitespace: GitPanelSettings::get_global(cx).diff.ignore_whitespace,
             },
             window,
             cx,
@@ -3828,7 +5728,13 @@ impl GitPanel {
 
         let selected = self.selected_entry == Some(ix);
         let marked = self.marked_entries.contains(&ix);
-        let status_style = GitPanelSettings::get_global(cx).status_style;
+        let settings = GitPanelSettings::get_global(cx);
+        let status_style = settings.status_style;
+        let file_icon = if settings.file_icons {
+            FileIcons::get_icon(&entry.repo_path, cx)
+        } else {
+            None
+        };
         let status = entry.status;
         let modifiers = self.current_modifiers;
         let shift_held = modifiers.shift;
@@ -3921,8 +5827,18 @@ impl GitPanel {
             .bg(base_bg)
             .hover(|this| this.bg(hover_bg))
             .active(|this| this.bg(active_bg))
+            .when(has_write_access, |this| {
+                self.draggable_entries(this, ix, cx)
+            })
             .on_click({
                 cx.listener(move |this, event: &ClickEvent, window, cx| {
+                    if event.modifiers().shift {
+                        this.toggle_marked(ix);
+                        this.selected_entry = Some(ix);
+                        cx.notify();
+                        return;
+                    }
+                    this.marked_entries.clear();
                     this.selected_entry = Some(ix);
                     cx.notify();
                     if event.modifiers().secondary() {
@@ -3956,6 +5872,20 @@ impl GitPanel {
             //         cx.stop_propagation();
             //     },
             // ))
+            .child(
+                Disclosure::new(
+                    ElementId::Name(format!("entry_{}_{}_disclosure", display_name, ix).into()),
+                    self.inline_diffs.contains_key(&entry.repo_path),
+                )
+                .on_click({
+                    let repo_path = entry.repo_path.clone();
+                    cx.listener(move |this, _, _, cx| {
+                        this.selected_entry = Some(ix);
+                        this.toggle_inline_diff(repo_path.clone(), cx);
+                        cx.stop_propagation();
+                    })
+                }),
+            )
             .child(
                 div()
                     .id(checkbox_wrapper_id)
@@ -4011,11 +5941,26 @@ impl GitPanel {
                     ),
             )
             .child(git_status_icon(status))
+            .when_some(file_icon, |this, icon| {
+                this.child(
+                    Icon::from_path(icon)
+                        .size(IconSize::Small)
+                        .color(Color::Muted),
+                )
+            })
             .child(
                 h_flex()
                     .items_center()
                     .flex_1()
                     // .overflow_hidden()
+                    .when_some(entry.rename.as_ref(), |this, rename| {
+                        this.child(
+                            self.entry_label(
+                                format!("{} → ", rename.old_path.display()),
+                                path_color,
+                            ),
+                        )
+                    })
                     .when_some(entry.parent_dir(), |this, parent| {
                         if !parent.is_empty() {
                             this.child(
@@ -4029,7 +5974,31 @@ impl GitPanel {
                     .child(
                         self.entry_label(display_name.clone(), label_color)
                             .when(status.is_deleted(), |this| this.strikethrough()),
-                    ),
+                    )
+                    .when_some(entry.rename.as_ref(), |this, rename| {
+                        let verb = match rename.kind {
+                            RenameKind::Renamed => "Renamed",
+                            RenameKind::Copied => "Copied",
+                        };
+                        let tooltip = format!(
+                            "{verb} from {} ({}% similar)",
+                            rename.old_path.display(),
+                            rename.similarity
+                        );
+                        this.child(
+                            div()
+                                .id(ElementId::Name(
+                                    format!("entry_{display_name}_{ix}_rename").into(),
+                                ))
+                                .ml_1()
+                                .child(
+                                    Label::new(format!("{}%", rename.similarity))
+                                        .size(LabelSize::Small)
+                                        .color(Color::Muted),
+                                )
+                                .tooltip(Tooltip::text(tooltip)),
+                        )
+                    }),
             )
             .into_any_element()
     }
@@ -4048,7 +6017,7 @@ impl GitPanel {
     }
 }
 
-fn current_language_model(cx: &Context<'_, GitPanel>) -> Option<Arc<dyn LanguageModel>> {
+pub(crate) fn current_language_model(cx: &App) -> Option<Arc<dyn LanguageModel>> {
     agent_settings::AgentSettings::get_global(cx)
         .enabled
         .then(|| {
@@ -4092,6 +6061,10 @@ impl Render for GitPanel {
                     .on_action(cx.listener(Self::unstage_all))
                     .on_action(cx.listener(Self::stage_selected))
                     .on_action(cx.listener(Self::unstage_selected))
+                    .on_action(cx.listener(Self::stage_partially))
+                    .on_action(cx.listener(Self::stash_selected))
+                    .on_action(cx.listener(Self::stash_selected_and_switch_branch))
+                    .on_action(cx.listener(Self::stash_pop))
                     .on_action(cx.listener(Self::restore_tracked_files))
                     .on_action(cx.listener(Self::revert_selected))
                     .on_action(cx.listener(Self::clean_all))
@@ -4104,12 +6077,21 @@ impl Render for GitPanel {
             .on_action(cx.listener(Self::close_panel))
             .on_action(cx.listener(Self::open_diff))
             .on_action(cx.listener(Self::open_file))
+            .on_action(cx.listener(Self::expand_selected_diff))
+            .on_action(cx.listener(Self::collapse_selected_diff))
+            .on_action(cx.listener(Self::file_history_for_selected))
             .on_action(cx.listener(Self::focus_changes_list))
             .on_action(cx.listener(Self::focus_editor))
             .on_action(cx.listener(Self::expand_commit_editor))
+            .on_action(cx.listener(Self::toggle_ignore_whitespace))
+            .on_action(cx.listener(Self::increase_diff_context))
+            .on_action(cx.listener(Self::decrease_diff_context))
             .when(has_write_access && has_co_authors, |git_panel| {
                 git_panel.on_action(cx.listener(Self::toggle_fill_co_authors))
             })
+            .when(has_write_access, |git_panel| {
+                git_panel.on_action(cx.listener(Self::toggle_signoff))
+            })
             .on_hover(cx.listener(move |this, hovered, window, cx| {
                 if *hovered {
                     this.horizontal_scrollbar.show(cx);
@@ -4127,12 +6109,15 @@ impl Render for GitPanel {
                     .size_full()
                     .children(self.render_panel_header(window, cx))
                     .map(|this| {
-                        if has_entries {
+                        if let Some(file_history) = self.render_file_history(cx) {
+                            this.child(file_history)
+                        } else if has_entries {
                             this.child(self.render_entries(has_write_access, window, cx))
                         } else {
                             this.child(self.render_empty_state(cx).into_any_element())
                         }
                     })
+                    .children(self.render_submodules(cx))
                     .children(self.render_footer(window, cx))
                     .when(self.amend_pending, |this| {
                         this.child(self.render_pending_amend(cx))
@@ -4757,7 +6742,7 @@ impl Component for PanelRepoFooter {
 #[cfg(test)]
 mod tests {
     use git::status::StatusCode;
-    use gpui::TestAppContext;
+    use gpui::{TestAppContext, VisualTestContext};
     use project::{FakeFs, WorktreeSettings};
     use serde_json::json;
     use settings::SettingsStore;
@@ -4783,6 +6768,75 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_ignore_file_location() {
+        let work_directory = Path::new(path!("/repo"));
+        let exclude_file = Path::new(path!("/repo/.git/info/exclude"));
+        let nested = RepoPath::from_str("crates/gpui/build.log");
+        assert_eq!(
+            IgnoreFile::Repository.location(&nested, work_directory, Some(exclude_file)),
+            Some((
+                PathBuf::from(path!("/repo/.gitignore")),
+                "/crates/gpui/build.log".into()
+            ))
+        );
+        assert_eq!(
+            IgnoreFile::Directory.location(&nested, work_directory, Some(exclude_file)),
+            Some((
+                PathBuf::from(path!("/repo/crates/gpui/.gitignore")),
+                "/build.log".into()
+            ))
+        );
+        assert_eq!(
+            IgnoreFile::Exclude.location(&nested, work_directory, Some(exclude_file)),
+            Some((exclude_file.to_path_buf(), "/crates/gpui/build.log".into()))
+        );
+
+        let top_level = RepoPath::from_str("build.log");
+        assert_eq!(
+            IgnoreFile::Directory.location(&top_level, work_directory, Some(exclude_file)),
+            None
+        );
+
+        // In a linked worktree, the exclude file is in the main repository's `.git`.
+        let linked_work_directory = Path::new(path!("/linked"));
+        assert_eq!(
+            IgnoreFile::Exclude.location(&nested, linked_work_directory, Some(exclude_file)),
+            Some((exclude_file.to_path_buf(), "/crates/gpui/build.log".into()))
+        );
+        assert_eq!(
+            IgnoreFile::Exclude.location(&nested, linked_work_directory, None),
+            None
+        );
+    }
+
+    #[test]
+    fn test_ignore_pattern_escaping() {
+        assert_eq!(ignore_pattern("src/main.rs"), "/src/main.rs");
+        assert_eq!(ignore_pattern("#notes.md"), "/\\#notes.md");
+        assert_eq!(ignore_pattern("!important"), "/\\!important");
+        assert_eq!(ignore_pattern("docs/#draft!"), "/docs/#draft!");
+        assert_eq!(ignore_pattern("logs/*[1]?.txt"), "/logs/\\*\\[1]\\?.txt");
+        assert_eq!(ignore_pattern("back\\slash"), "/back\\\\slash");
+        assert_eq!(ignore_pattern("spaced  "), "/spaced\\ \\ ");
+        assert_eq!(ignore_pattern("in between"), "/in between");
+    }
+
+    #[gpui::test]
+    fn test_append_ignore_pattern(cx: &mut TestAppContext) {
+        let buffer = cx.new(|cx| Buffer::local("target", cx));
+        buffer.update(cx, |buffer, cx| {
+            assert!(append_ignore_pattern(buffer, "/build.log", cx));
+            assert_eq!(buffer.text(), "target\n/build.log\n");
+
+            assert!(!append_ignore_pattern(buffer, "/build.log", cx));
+            assert_eq!(buffer.text(), "target\n/build.log\n");
+
+            buffer.undo(cx);
+            assert_eq!(buffer.text(), "target");
+        });
+    }
+
     #[gpui::test]
     async fn test_entry_worktree_paths(cx: &mut TestAppContext) {
         init_test(cx);
@@ -4862,12 +6916,14 @@ mod tests {
                     repo_path: "crates/gpui/gpui.rs".into(),
                     status: StatusCode::Modified.worktree(),
                     staging: StageStatus::Unstaged,
+                    rename: None,
                 }),
                 GitListEntry::GitStatusEntry(GitStatusEntry {
                     abs_path: path!("/root/zed/crates/util/util.rs").into(),
                     repo_path: "crates/util/util.rs".into(),
                     status: StatusCode::Modified.worktree(),
                     staging: StageStatus::Unstaged,
+                    rename: None,
                 },),
             ],
         );
@@ -4937,14 +6993,203 @@ mod tests {
                     repo_path: "crates/gpui/gpui.rs".into(),
                     status: StatusCode::Modified.worktree(),
                     staging: StageStatus::Unstaged,
+                    rename: None,
                 }),
                 GitListEntry::GitStatusEntry(GitStatusEntry {
                     abs_path: path!("/root/zed/crates/util/util.rs").into(),
                     repo_path: "crates/util/util.rs".into(),
                     status: StatusCode::Modified.worktree(),
                     staging: StageStatus::Unstaged,
+                    rename: None,
                 },),
             ],
         );
     }
+    #[gpui::test]
+    async fn test_inline_diff_entries(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.background_executor.clone());
+        fs.insert_tree(
+            path!("/root"),
+            json!({
+                "project": {
+                    ".git": {},
+                    "a.txt": "one\nTWO\nthree\n",
+                    "b.txt": "bee\n",
+                },
+            }),
+        )
+        .await;
+        fs.set_head_and_index_for_repo(
+            Path::new(path!("/root/project/.git")),
+            &[
+                ("a.txt".into(), "one\ntwo\nthree\n".into()),
+                ("b.txt".into(), "b\n".into()),
+            ],
+        );
+
+        let project = Project::test(fs.clone(), [path!("/root/project").as_ref()], cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        cx.executor().run_until_parked();
+
+        let app_state = workspace.read_with(cx, |workspace, _| workspace.app_state().clone());
+        let panel = cx.new_window_entity(|window, cx| {
+            GitPanel::new(workspace.clone(), project.clone(), app_state, window, cx)
+        });
+        let handle = cx.update_window_entity(&panel, |panel, _, _| {
+            std::mem::replace(&mut panel.update_visible_entries_task, Task::ready(()))
+        });
+        cx.executor().advance_clock(2 * UPDATE_DEBOUNCE);
+        handle.await;
+
+        let entries = |cx: &mut VisualTestContext| {
+            panel.read_with(cx, |panel, _| {
+                panel
+                    .entries
+                    .iter()
+                    .map(|entry| match entry {
+                        GitListEntry::Header(header) => format!("{:?}", header.header),
+                        GitListEntry::GitStatusEntry(entry) => entry.display_name(),
+                        GitListEntry::DiffLine(line) => {
+                            let inline_diff = &panel.inline_diffs[&line.repo_path];
+                            let text = &inline_diff.lines[line.line_ix].text.text;
+                            format!("  {text}")
+                        }
+                    })
+                    .collect::<Vec<_>>()
+            })
+        };
+        assert_eq!(entries(cx), ["Tracked", "a.txt", "b.txt"]);
+
+        cx.update_window_entity(&panel, |panel, window, cx| {
+            panel.selected_entry = Some(1);
+            panel.expand_selected_diff(&ExpandSelectedDiff, window, cx);
+        });
+        cx.run_until_parked();
+        assert_eq!(
+            entries(cx),
+            [
+                "Tracked",
+                "a.txt",
+                "  @@ -2,1 +2,1 @@",
+                "  two",
+                "  TWO",
+                "b.txt"
+            ]
+        );
+
+        // Moving the selection skips over the diff's rows.
+        cx.update_window_entity(&panel, |panel, window, cx| {
+            panel.select_next(&SelectNext, window, cx);
+            assert_eq!(panel.selected_entry, Some(5));
+            panel.select_previous(&SelectPrevious, window, cx);
+            assert_eq!(panel.selected_entry, Some(1));
+        });
+
+        // Edits to the file are reflected in the expanded diff.
+        fs.insert_file(path!("/root/project/a.txt"), b"one\nthree\n".to_vec())
+            .await;
+        cx.run_until_parked();
+        assert_eq!(
+            entries(cx),
+            ["Tracked", "a.txt", "  @@ -2,1 +2,0 @@", "  two", "b.txt"]
+        );
+
+        cx.update_window_entity(&panel, |panel, window, cx| {
+            panel.collapse_selected_diff(&CollapseSelectedDiff, window, cx);
+        });
+        assert_eq!(entries(cx), ["Tracked", "a.txt", "b.txt"]);
+    }
+
+    #[gpui::test]
+    async fn test_stash_marked_entries(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.background_executor.clone());
+        fs.insert_tree(
+            path!("/root"),
+            json!({
+                "project": {
+                    ".git": {},
+                    "a.txt": "A\n",
+                    "b.txt": "B\n",
+                    "c.txt": "C\n",
+                },
+            }),
+        )
+        .await;
+        fs.set_head_and_index_for_repo(
+            Path::new(path!("/root/project/.git")),
+            &[
+                ("a.txt".into(), "a\n".into()),
+                ("b.txt".into(), "b\n".into()),
+                ("c.txt".into(), "c\n".into()),
+            ],
+        );
+
+        let project = Project::test(fs.clone(), [path!("/root/project").as_ref()], cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        cx.executor().run_until_parked();
+
+        let app_state = workspace.read_with(cx, |workspace, _| workspace.app_state().clone());
+        let panel = cx.new_window_entity(|window, cx| {
+            GitPanel::new(workspace.clone(), project.clone(), app_state, window, cx)
+        });
+        let handle = cx.update_window_entity(&panel, |panel, _, _| {
+            std::mem::replace(&mut panel.update_visible_entries_task, Task::ready(()))
+        });
+        cx.executor().advance_clock(2 * UPDATE_DEBOUNCE);
+        handle.await;
+
+        let entries = |cx: &mut VisualTestContext| {
+            panel.read_with(cx, |panel, _| {
+                panel
+                    .entries
+                    .iter()
+                    .filter_map(|entry| Some(entry.status_entry()?.display_name()))
+                    .collect::<Vec<_>>()
+            })
+        };
+        assert_eq!(entries(cx), ["a.txt", "b.txt", "c.txt"]);
+
+        // Stashing a marked entry stashes every marked entry.
+        cx.update_window_entity(&panel, |panel, window, cx| {
+            panel.toggle_marked(1);
+            panel.toggle_marked(3);
+            panel.selected_entry = Some(1);
+            panel.stash_selected(&git::StashFile, window, cx);
+        });
+        cx.run_until_parked();
+        assert_eq!(entries(cx), ["b.txt"]);
+        assert_eq!(
+            fs.load(path!("/root/project/a.txt").as_ref())
+                .await
+                .unwrap(),
+            "a\n"
+        );
+        assert_eq!(
+            fs.load(path!("/root/project/c.txt").as_ref())
+                .await
+                .unwrap(),
+            "c\n"
+        );
+        panel.read_with(cx, |panel, _| assert!(panel.marked_entries.is_empty()));
+
+        cx.update_window_entity(&panel, |panel, window, cx| {
+            panel.stash_pop(&git::StashPop, window, cx);
+        });
+        cx.run_until_parked();
+        assert_eq!(entries(cx), ["a.txt", "b.txt", "c.txt"]);
+
+        // Stashing an unmarked entry ignores the marks.
+        cx.update_window_entity(&panel, |panel, window, cx| {
+            panel.toggle_marked(1);
+            panel.toggle_marked(3);
+            panel.selected_entry = Some(2);
+            panel.stash_selected(&git::StashFile, window, cx);
+        });
+        cx.run_until_parked();
+        assert_eq!(entries(cx), ["a.txt", "c.txt"]);
+    }
 }
@@ -36,6 +36,80 @@ pub enum StatusStyle {
     LabelColor,
 }
 
+#[derive(Default, Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CommitMessageLintMode {
+    /// Don't check commit messages.
+    #[default]
+    Off,
+    /// Show warnings about the commit message as you type it.
+    Hint,
+    /// Show warnings, and don't commit until they're addressed.
+    Enforce,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, Debug)]
+pub struct CommitMessageLintSettingsContent {
+    /// Whether to check commit messages, and whether to prevent committing
+    /// when the check fails.
+    ///
+    /// Default: off
+    pub mode: Option<CommitMessageLintMode>,
+    /// The maximum number of characters in the first line of a commit message.
+    ///
+    /// Default: 72
+    pub max_subject_length: Option<usize>,
+    /// Whether commit messages must start with a Conventional Commits type,
+    /// such as `feat:` or `fix(scope):`.
+    ///
+    /// Default: false
+    pub conventional_commits: Option<bool>,
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct CommitMessageLintSettings {
+    pub mode: CommitMessageLintMode,
+    pub max_subject_length: usize,
+    pub conventional_commits: bool,
+}
+
+impl Default for CommitMessageLintSettings {
+    fn default() -> Self {
+        Self {
+            mode: CommitMessageLintMode::Off,
+            max_subject_length: 72,
+            conventional_commits: false,
+        }
+    }
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, Debug)]
+pub struct DiffSettingsContent {
+    /// Whether to hide changes that only add, remove, or modify whitespace.
+    ///
+    /// Default: false
+    pub ignore_whitespace: Option<bool>,
+    /// The number of unchanged lines to show around each change.
+    ///
+    /// Default: 2
+    pub context_lines: Option<u32>,
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct DiffSettings {
+    pub ignore_whitespace: bool,
+    pub context_lines: u32,
+}
+
+impl Default for DiffSettings {
+    fn default() -> Self {
+        Self {
+            ignore_whitespace: false,
+            context_lines: 2,
+        }
+    }
+}
+
 #[derive(Clone, Default, Serialize, Deserialize, JsonSchema, Debug)]
 pub struct GitPanelSettingsContent {
     /// Whether to show the panel button in the status bar.
@@ -54,6 +128,10 @@ pub struct GitPanelSettingsContent {
     ///
     /// Default: icon
     pub status_style: Option<StatusStyle>,
+    /// Whether to show file icons, from the active icon theme, next to entries.
+    ///
+    /// Default: true
+    pub file_icons: Option<bool>,
     /// How and when the scrollbar should be displayed.
     ///
     /// Default: inherits editor scrollbar settings
@@ -70,6 +148,12 @@ pub struct GitPanelSettingsContent {
     ///
     /// Default: false
     pub sort_by_path: Option<bool>,
+
+    /// How to check commit messages as they're typed.
+    pub commit_message_lint: Option<CommitMessageLintSettingsContent>,
+
+    /// How to compute the diffs opened from the panel.
+    pub diff: Option<DiffSettingsContent>,
 }
 
 #[derive(Deserialize, Debug, Clone, PartialEq)]
@@ -78,9 +162,12 @@ pub struct GitPanelSettings {
     pub dock: DockPosition,
     pub default_width: Pixels,
     pub status_style: StatusStyle,
+    pub file_icons: bool,
     pub scrollbar: ScrollbarSettings,
     pub fallback_branch_name: String,
     pub sort_by_path: bool,
+    pub commit_message_lint: CommitMessageLintSettings,
+    pub diff: DiffSettings,
 }
 
 impl Settings for GitPanelSettings {
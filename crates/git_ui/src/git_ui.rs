@@ -18,15 +18,19 @@ use workspace::Workspace;
 
 mod askpass_modal;
 pub mod branch_picker;
+mod commit_message_lint;
 mod commit_modal;
 pub mod commit_tooltip;
-mod commit_view;
+pub mod commit_view;
 mod conflict_view;
 pub mod git_panel;
 mod git_panel_settings;
+mod inline_diff;
 pub mod onboarding;
 pub mod picker_prompt;
 pub mod project_diff;
+mod pull_request_description;
+pub mod ref_diff;
 pub(crate) mod remote_output;
 pub mod repository_selector;
 
@@ -48,6 +52,8 @@ pub fn init(cx: &mut App) {
         git_panel::register(workspace);
         repository_selector::register(workspace);
         branch_picker::register(workspace);
+        ref_diff::RefDiff::register(workspace);
+        pull_request_description::register(workspace);
 
         let project = workspace.project().read(cx);
         if project.is_read_only(cx) {
@@ -62,6 +68,14 @@ pub fn init(cx: &mut App) {
                     panel.fetch(window, cx);
                 });
             });
+            workspace.register_action(|workspace, _: &git::UpdateSubmodules, window, cx| {
+                let Some(panel) = workspace.panel::<git_panel::GitPanel>(cx) else {
+                    return;
+                };
+                panel.update(cx, |panel, cx| {
+                    panel.update_submodules(window, cx);
+                });
+            });
             workspace.register_action(|workspace, _: &git::Push, window, cx| {
                 let Some(panel) = workspace.panel::<git_panel::GitPanel>(cx) else {
                     return;
@@ -0,0 +1,179 @@
+use buffer_diff::{BufferDiff, DiffHunk, DiffHunkSecondaryStatus};
+use gpui::{App, Entity, Subscription};
+use language::{Buffer, BufferSnapshot, HighlightedText, Point};
+use std::ops::Range;
+use theme::SyntaxTheme;
+
+/// A file's diff hunks, shown as rows beneath its entry in the git panel.
+pub(crate) struct InlineDiff {
+    pub(crate) buffer: Entity<Buffer>,
+    pub(crate) diff: Entity<BufferDiff>,
+    pub(crate) lines: Vec<InlineDiffLine>,
+    pub(crate) _subscriptions: [Subscription; 2],
+}
+
+pub(crate) struct InlineDiffLine {
+    pub(crate) kind: InlineDiffLineKind,
+    pub(crate) text: HighlightedText,
+}
+
+pub(crate) enum InlineDiffLineKind {
+    HunkHeader { hunk: DiffHunk, is_staged: bool },
+    Deleted,
+    Added,
+}
+
+/// Files whose diffs have more lines than this are truncated, since the panel
+/// isn't the place to review large changes.
+const MAX_INLINE_DIFF_LINES: usize = 500;
+
+impl InlineDiff {
+    pub(crate) fn refresh(&mut self, syntax_theme: &SyntaxTheme, cx: &App) {
+        let buffer = self.buffer.read(cx).snapshot();
+        self.lines = diff_lines(&buffer, self.diff.read(cx), syntax_theme, cx);
+    }
+}
+
+fn diff_lines(
+    buffer: &BufferSnapshot,
+    diff: &BufferDiff,
+    syntax_theme: &SyntaxTheme,
+    cx: &App,
+) -> Vec<InlineDiffLine> {
+    let base_text = diff.base_text();
+    let mut lines = Vec::new();
+    for hunk in
+        diff.hunks_intersecting_range(language::Anchor::MIN..language::Anchor::MAX, buffer, cx)
+    {
+        if lines.len() >= MAX_INLINE_DIFF_LINES {
+            break;
+        }
+
+        let base_range = base_text.offset_to_point(hunk.diff_base_byte_range.start)
+            ..base_text.offset_to_point(hunk.diff_base_byte_range.end);
+        let deleted_rows = rows(&base_range);
+        let added_rows = rows(&hunk.range);
+        let header = format!(
+            "@@ -{},{} +{},{} @@",
+            base_range.start.row + 1,
+            deleted_rows.len(),
+            hunk.range.start.row + 1,
+            added_rows.len(),
+        );
+        let is_staged = matches!(
+            hunk.secondary_status,
+            DiffHunkSecondaryStatus::NoSecondaryHunk
+                | DiffHunkSecondaryStatus::SecondaryHunkRemovalPending
+        );
+        lines.push(InlineDiffLine {
+            kind: InlineDiffLineKind::HunkHeader { hunk, is_staged },
+            text: HighlightedText {
+                text: header.into(),
+                highlights: Vec::new(),
+            },
+        });
+
+        for row in deleted_rows {
+            lines.push(InlineDiffLine {
+                kind: InlineDiffLineKind::Deleted,
+                text: line_text(base_text, row, syntax_theme),
+            });
+        }
+        for row in added_rows {
+            lines.push(InlineDiffLine {
+                kind: InlineDiffLineKind::Added,
+                text: line_text(buffer, row, syntax_theme),
+            });
+        }
+    }
+    lines.truncate(MAX_INLINE_DIFF_LINES);
+    lines
+}
+
+/// Returns the rows spanned by a range of whole lines, which ends either at the
+/// start of the row after its last line or at the end of the text.
+fn rows(range: &Range<Point>) -> Range<u32> {
+    if range.end.column > 0 {
+        range.start.row..range.end.row + 1
+    } else {
+        range.start.row..range.end.row
+    }
+}
+
+fn line_text(snapshot: &BufferSnapshot, row: u32, syntax_theme: &SyntaxTheme) -> HighlightedText {
+    let range = Point::new(row, 0)..Point::new(row, snapshot.line_len(row));
+    snapshot.highlighted_text_for_range(range, None, syntax_theme)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{AppContext as _, TestAppContext};
+
+    fn summarize(lines: &[InlineDiffLine]) -> Vec<String> {
+        lines
+            .iter()
+            .map(|line| {
+                let prefix = match line.kind {
+                    InlineDiffLineKind::HunkHeader { .. } => "",
+                    InlineDiffLineKind::Deleted => "-",
+                    InlineDiffLineKind::Added => "+",
+                };
+                format!("{prefix}{}", line.text.text)
+            })
+            .collect()
+    }
+
+    fn diff_lines_for(base_text: &str, text: &str, cx: &mut TestAppContext) -> Vec<String> {
+        let buffer = cx.new(|cx| Buffer::local(text, cx));
+        let diff = cx.new(|cx| BufferDiff::new_with_base_text(base_text, &buffer, cx));
+        cx.update(|cx| {
+            let snapshot = buffer.read(cx).snapshot();
+            summarize(&diff_lines(
+                &snapshot,
+                diff.read(cx),
+                &SyntaxTheme::default(),
+                cx,
+            ))
+        })
+    }
+
+    #[test]
+    fn test_rows() {
+        assert_eq!(rows(&(Point::new(2, 0)..Point::new(4, 0))), 2..4);
+        assert_eq!(rows(&(Point::new(2, 0)..Point::new(4, 3))), 2..5);
+        assert_eq!(rows(&(Point::new(2, 0)..Point::new(2, 0))), 2..2);
+    }
+
+    #[gpui::test]
+    fn test_diff_lines(cx: &mut TestAppContext) {
+        assert_eq!(
+            diff_lines_for("one\ntwo\nthree\n", "one\nTWO\nthree\nfour\n", cx),
+            [
+                "@@ -2,1 +2,1 @@",
+                "-two",
+                "+TWO",
+                "@@ -4,0 +4,1 @@",
+                "+four",
+            ]
+        );
+        assert_eq!(
+            diff_lines_for("one\ntwo\nthree\n", "one\nthree\n", cx),
+            ["@@ -2,1 +2,0 @@", "-two"]
+        );
+        assert_eq!(diff_lines_for("one\n", "one\n", cx), Vec::<String>::new());
+    }
+
+    #[gpui::test]
+    fn test_diff_lines_are_truncated(cx: &mut TestAppContext) {
+        let text = (0..MAX_INLINE_DIFF_LINES * 2)
+            .map(|ix| format!("{ix}\n"))
+            .collect::<String>();
+        let lines = diff_lines_for("", &text, cx);
+        assert_eq!(lines.len(), MAX_INLINE_DIFF_LINES);
+        assert_eq!(
+            lines[0],
+            format!("@@ -1,0 +1,{} @@", MAX_INLINE_DIFF_LINES * 2)
+        );
+    }
+}
@@ -169,6 +169,11 @@ impl ProjectDiff {
             move |this, _git_store, event, _window, _cx| match event {
                 GitStoreEvent::ActiveRepositoryChanged(_)
                 | GitStoreEvent::RepositoryUpdated(_, RepositoryEvent::Updated { .. }, true)
+                | GitStoreEvent::RepositoryUpdated(
+                    _,
+                    RepositoryEvent::StatusesChanged { .. },
+                    true,
+                )
                 | GitStoreEvent::ConflictsUpdated => {
                     *this.update_needed.borrow_mut() = ();
                 }
@@ -176,13 +181,18 @@ impl ProjectDiff {
             },
         );
 
-        let mut was_sort_by_path = GitPanelSettings::get_global(cx).sort_by_path;
+        let settings = GitPanelSettings::get_global(cx);
+        let mut was_sort_by_path = settings.sort_by_path;
+        let mut old_diff_settings = settings.diff;
         cx.observe_global::<SettingsStore>(move |this, cx| {
-            let is_sort_by_path = GitPanelSettings::get_global(cx).sort_by_path;
-            if is_sort_by_path != was_sort_by_path {
+            let settings = GitPanelSettings::get_global(cx);
+            let is_sort_by_path = settings.sort_by_path;
+            let diff_settings = settings.diff;
+            if is_sort_by_path != was_sort_by_path || diff_settings != old_diff_settings {
                 *this.update_needed.borrow_mut() = ();
             }
-            was_sort_by_path = is_sort_by_path
+            was_sort_by_path = is_sort_by_path;
+            old_diff_settings = diff_settings;
         })
         .detach();
 
@@ -427,11 +437,11 @@ impl ProjectDiff {
             .addon::<ConflictAddon>()
             .expect("project diff editor should have a conflict addon");
 
+        let diff_settings = GitPanelSettings::get_global(cx).diff;
         let snapshot = buffer.read(cx).snapshot();
         let diff = diff.read(cx);
-        let diff_hunk_ranges = diff
-            .hunks_intersecting_range(Anchor::MIN..Anchor::MAX, &snapshot, cx)
-            .map(|diff_hunk| diff_hunk.buffer_range.clone());
+        let diff_hunk_ranges =
+            diff_hunk_ranges(diff, &snapshot, diff_settings.ignore_whitespace, cx).into_iter();
         let conflicts = conflict_addon
             .conflict_set(snapshot.remote_id())
             .map(|conflict_set| conflict_set.read(cx).snapshot().conflicts.clone())
@@ -448,7 +458,7 @@ impl ProjectDiff {
                 path_key.clone(),
                 buffer,
                 excerpt_ranges,
-                editor::DEFAULT_MULTIBUFFER_CONTEXT,
+                diff_settings.context_lines,
                 cx,
             );
             (was_empty, is_newly_added)
@@ -1282,6 +1292,33 @@ mod preview {
     }
 }
 
+/// Returns the buffer ranges of the hunks in `diff`, leaving out the ones that only
+/// change whitespace when `ignore_whitespace` is set. Lines are compared the same way
+/// as for the commit stats, so a hunk is hidden exactly when it adds nothing to them.
+pub(crate) fn diff_hunk_ranges(
+    diff: &BufferDiff,
+    snapshot: &language::BufferSnapshot,
+    ignore_whitespace: bool,
+    cx: &App,
+) -> Vec<Range<Anchor>> {
+    let base_text = diff.base_text();
+    diff.hunks_intersecting_range(Anchor::MIN..Anchor::MAX, snapshot, cx)
+        .filter(|hunk| {
+            if !ignore_whitespace {
+                return true;
+            }
+            let old_text = base_text
+                .text_for_range(hunk.diff_base_byte_range.clone())
+                .collect::<String>();
+            let new_text = snapshot
+                .text_for_range(hunk.buffer_range.clone())
+                .collect::<String>();
+            !language::line_diff_ignoring_whitespace(&old_text, &new_text).is_empty()
+        })
+        .map(|hunk| hunk.buffer_range)
+        .collect()
+}
+
 fn merge_anchor_ranges<'a>(
     left: impl 'a + Iterator<Item = Range<Anchor>>,
     right: impl 'a + Iterator<Item = Range<Anchor>>,
@@ -1363,6 +1400,25 @@ mod tests {
         });
     }
 
+    #[gpui::test]
+    fn test_diff_hunk_ranges_ignoring_whitespace(cx: &mut TestAppContext) {
+        let buffer = cx.new(|cx| Buffer::local("a\n  b\nc\nde\nf\n", cx));
+        let diff = cx.new(|cx| BufferDiff::new_with_base_text("a\nb\nc\nd\ne\nf\n", &buffer, cx));
+        cx.update(|cx| {
+            let snapshot = buffer.read(cx).snapshot();
+            let rows = |ignore_whitespace| {
+                diff_hunk_ranges(diff.read(cx), &snapshot, ignore_whitespace, cx)
+                    .into_iter()
+                    .map(|range| range.to_point(&snapshot).start.row)
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(rows(false), [1, 3]);
+            // Joining two lines only removes whitespace, but still changes the lines, as
+            // in the commit stats.
+            assert_eq!(rows(true), [3]);
+        });
+    }
+
     #[gpui::test]
     async fn test_save_after_restore(cx: &mut TestAppContext) {
         init_test(cx);
@@ -0,0 +1,189 @@
+use crate::git_panel::current_language_model;
+use agent_settings::AgentSettings;
+use anyhow::Context as _;
+use editor::{Editor, MultiBuffer};
+use futures::StreamExt as _;
+use git::{
+    GeneratePullRequestDescription,
+    repository::{Branch, DiffType},
+};
+use gpui::{AppContext as _, ClipboardItem, Context, Window};
+use language_model::{LanguageModelRequest, LanguageModelRequestMessage, Role};
+use notifications::status_toast::{StatusToast, ToastIcon};
+use ui::{Color, IconName};
+use util::ResultExt as _;
+use workspace::{Workspace, notifications::DetachAndPromptErr};
+
+const PROMPT: &str = include_str!("pull_request_description_prompt.txt");
+
+pub(crate) fn register(workspace: &mut Workspace) {
+    workspace.register_action(generate_pull_request_description);
+}
+
+/// Generates a description of the changes on the current branch using an LLM, and opens it
+/// in a new buffer. When the branch's remote is hosted by a provider that knows how to open
+/// pull requests, offers to open one with the description.
+fn generate_pull_request_description(
+    workspace: &mut Workspace,
+    _: &GeneratePullRequestDescription,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let project = workspace.project().clone();
+    let Some(repository) = project.read(cx).active_repository(cx) else {
+        return;
+    };
+    let model = current_language_model(cx);
+    let branch = repository.read(cx).branch.clone();
+    let base_ref = pull_request_base_ref(branch.as_ref());
+    let branch_name = branch
+        .as_ref()
+        .map(|branch| branch.name().to_string())
+        .unwrap_or_else(|| "HEAD".to_string());
+    let diff = repository.update(cx, |repository, cx| {
+        repository.diff(
+            DiffType::MergeBase {
+                base_ref: base_ref.clone(),
+            },
+            cx,
+        )
+    });
+    let markdown = workspace
+        .app_state()
+        .languages
+        .language_for_name("Markdown");
+
+    telemetry::event!("Git Pull Request Description Generated");
+
+    cx.spawn_in(window, async move |workspace, cx| {
+        let model = model.context("No language model is configured for generating text")?;
+        let temperature = cx.update(|_, cx| AgentSettings::temperature_for_model(&model, cx))?;
+
+        let mut diff_text = diff.await??;
+        anyhow::ensure!(
+            !diff_text.trim().is_empty(),
+            "{branch_name} has no changes since it diverged from {base_ref}"
+        );
+        const ONE_MB: usize = 1_000_000;
+        if diff_text.len() > ONE_MB {
+            diff_text = diff_text.chars().take(ONE_MB).collect()
+        }
+
+        let content = format!(
+            "{PROMPT}\nHere is the name of the branch:\n{branch_name}\nHere are the changes on the branch:\n{diff_text}"
+        );
+        let request = LanguageModelRequest {
+            thread_id: None,
+            prompt_id: None,
+            intent: None,
+            mode: None,
+            messages: vec![LanguageModelRequestMessage {
+                role: Role::User,
+                content: vec![content.into()],
+                cache: false,
+            }],
+            tools: Vec::new(),
+            tool_choice: None,
+            stop: Vec::new(),
+            temperature,
+            response_schema: None,
+        };
+
+        let markdown = markdown.await.log_err();
+        let buffer = project
+            .update(cx, |project, cx| project.create_buffer(cx))?
+            .await?;
+        buffer.update(cx, |buffer, cx| buffer.set_language(markdown, cx))?;
+        workspace.update_in(cx, |workspace, window, cx| {
+            let title = format!("Pull Request: {branch_name}");
+            let multibuffer =
+                cx.new(|cx| MultiBuffer::singleton(buffer.clone(), cx).with_title(title));
+            let editor = cx.new(|cx| {
+                Editor::for_multibuffer(multibuffer, Some(project.clone()), window, cx)
+            });
+            workspace.add_item_to_active_pane(Box::new(editor), None, true, window, cx);
+        })?;
+
+        // Stream the description into the buffer so there's something to read right away.
+        let mut chunks = model.stream_completion_text(request, &cx).await?.stream;
+        while let Some(chunk) = chunks.next().await {
+            let chunk = chunk?;
+            buffer.update(cx, |buffer, cx| {
+                let end = buffer.len();
+                buffer.edit([(end..end, chunk)], None, cx);
+            })?;
+        }
+
+        let Some(branch) = branch else {
+            return anyhow::Ok(());
+        };
+        let hosted_remote = repository
+            .update(cx, |repository, _| repository.upstream_hosted_remote(&branch))?
+            .await??;
+        let Some(url) = hosted_remote
+            .and_then(|(remote, remote_branch)| remote.new_pull_request_url(&remote_branch))
+        else {
+            return anyhow::Ok(());
+        };
+        workspace.update(cx, |workspace, cx| {
+            let toast = StatusToast::new("Pull request description ready", cx, move |this, _| {
+                this.icon(ToastIcon::new(IconName::GitBranchSmall).color(Color::Muted))
+                    .action("Copy and Open Pull Request", move |_, cx| {
+                        // Hosting providers can't take a long description in the URL, so it's
+                        // left on the clipboard to be pasted into the form.
+                        cx.write_to_clipboard(ClipboardItem::new_string(buffer.read(cx).text()));
+                        cx.open_url(url.as_str());
+                    })
+            });
+            workspace.toggle_status_toast(toast, cx);
+        })?;
+
+        anyhow::Ok(())
+    })
+    .detach_and_prompt_err(
+        "Failed to generate pull request description",
+        window,
+        cx,
+        |error, _, _| Some(error.to_string()),
+    );
+}
+
+/// Returns the ref that a pull request for `branch` most likely targets: the default branch
+/// of the remote that it tracks, or of `origin` when it doesn't track a remote branch.
+fn pull_request_base_ref(branch: Option<&Branch>) -> String {
+    let remote = branch
+        .and_then(|branch| branch.upstream.as_ref())
+        .and_then(|upstream| upstream.remote_name())
+        .unwrap_or("origin");
+    format!("{remote}/HEAD")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git::repository::{Upstream, UpstreamTracking};
+
+    #[test]
+    fn test_pull_request_base_ref() {
+        let branch = |upstream: Option<&str>| Branch {
+            is_head: true,
+            ref_name: "refs/heads/feature".into(),
+            upstream: upstream.map(|ref_name| Upstream {
+                ref_name: ref_name.to_string().into(),
+                tracking: UpstreamTracking::Gone,
+            }),
+            most_recent_commit: None,
+        };
+
+        assert_eq!(pull_request_base_ref(None), "origin/HEAD");
+        assert_eq!(pull_request_base_ref(Some(&branch(None))), "origin/HEAD");
+        assert_eq!(
+            pull_request_base_ref(Some(&branch(Some("refs/remotes/fork/feature")))),
+            "fork/HEAD"
+        );
+        assert_eq!(
+            pull_request_base_ref(Some(&branch(Some("refs/heads/main")))),
+            "origin/HEAD"
+        );
+    }
+}
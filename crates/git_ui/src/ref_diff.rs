@@ -0,0 +1,545 @@
+use crate::commit_view::load_diff_excerpts;
+use editor::{Editor, EditorEvent, MultiBuffer};
+use fuzzy::StringMatchCandidate;
+use git::repository::CommitDiff;
+use gpui::{
+    AnyElement, AnyView, App, AppContext as _, Context, DismissEvent, Entity, EventEmitter,
+    FocusHandle, Focusable, IntoElement, ParentElement, Render, SharedString, Styled, Subscription,
+    Task, WeakEntity, Window, actions, rems,
+};
+use language::Capability;
+use picker::{Picker, PickerDelegate};
+use project::{Project, git_store::Repository};
+use std::{
+    any::{Any, TypeId},
+    sync::Arc,
+};
+use ui::{HighlightedLabel, ListItem, ListItemSpacing, prelude::*};
+use util::ResultExt as _;
+use workspace::{
+    Item, ItemNavHistory, ModalView, ToolbarItemLocation, Workspace,
+    item::{BreadcrumbText, ItemEvent, TabContentParams},
+    notifications::DetachAndPromptErr,
+    searchable::SearchableItemHandle,
+};
+
+actions!(git, [DiffRefs]);
+
+/// A read-only view of the changes between two refs of a repository.
+pub struct RefDiff {
+    base: SharedString,
+    head: SharedString,
+    editor: Entity<Editor>,
+}
+
+impl RefDiff {
+    pub(crate) fn register(workspace: &mut Workspace) {
+        workspace.register_action(Self::pick_refs);
+    }
+
+    fn pick_refs(
+        workspace: &mut Workspace,
+        _: &DiffRefs,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let Some(repository) = workspace.project().read(cx).active_repository(cx) else {
+            return;
+        };
+        let workspace_handle = cx.entity().downgrade();
+        workspace.toggle_modal(window, cx, |window, cx| {
+            RefPicker::new(repository, workspace_handle, window, cx)
+        });
+    }
+
+    pub fn open(
+        base: String,
+        head: String,
+        repository: Entity<Repository>,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let diff = repository.update(cx, |repository, _| {
+            repository.diff_refs(base.clone(), head.clone())
+        });
+
+        window
+            .spawn(cx, async move |cx| {
+                let diff = diff.await??;
+                workspace.update_in(cx, |workspace, window, cx| {
+                    let existing = workspace.items_of_type::<Self>(cx).find(|ref_diff| {
+                        let ref_diff = ref_diff.read(cx);
+                        ref_diff.base == base && ref_diff.head == head
+                    });
+                    if let Some(existing) = existing {
+                        workspace.activate_item(&existing, true, true, window, cx);
+                        return;
+                    }
+
+                    let project = workspace.project().clone();
+                    let ref_diff = cx.new(|cx| {
+                        Self::new(
+                            base.into(),
+                            head.into(),
+                            diff,
+                            repository,
+                            project,
+                            window,
+                            cx,
+                        )
+                    });
+                    workspace.add_item_to_active_pane(Box::new(ref_diff), None, true, window, cx);
+                })
+            })
+            .detach_and_prompt_err("Failed to compare refs", window, cx, |error, _, _| {
+                Some(error.to_string())
+            });
+    }
+
+    fn new(
+        base: SharedString,
+        head: SharedString,
+        diff: CommitDiff,
+        repository: Entity<Repository>,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        // The diff can be navigated and copied from, but there's nothing to stage
+        // or edit, so both the excerpts and the hunk controls are read-only.
+        let multibuffer = cx.new(|_| MultiBuffer::new(Capability::ReadOnly));
+        let editor = cx.new(|cx| {
+            let mut editor =
+                Editor::for_multibuffer(multibuffer.clone(), Some(project.clone()), window, cx);
+            editor.disable_inline_diagnostics();
+            editor.set_expand_all_diff_hunks(cx);
+            editor
+        });
+        load_diff_excerpts(diff.files, repository, &project, &multibuffer, cx)
+            .detach_and_log_err(cx);
+
+        Self { base, head, editor }
+    }
+}
+
+impl EventEmitter<EditorEvent> for RefDiff {}
+
+impl Focusable for RefDiff {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.editor.focus_handle(cx)
+    }
+}
+
+impl Item for RefDiff {
+    type Event = EditorEvent;
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::GitBranch).color(Color::Muted))
+    }
+
+    fn tab_content(&self, params: TabContentParams, _window: &Window, cx: &App) -> AnyElement {
+        Label::new(self.tab_content_text(params.detail.unwrap_or_default(), cx))
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn tab_content_text(&self, _detail: usize, _cx: &App) -> SharedString {
+        format!("{}..{}", self.base, self.head).into()
+    }
+
+    fn tab_tooltip_text(&self, _: &App) -> Option<SharedString> {
+        Some(format!("Changes from {} to {}", self.base, self.head).into())
+    }
+
+    fn to_item_events(event: &EditorEvent, f: impl FnMut(ItemEvent)) {
+        Editor::to_item_events(event, f)
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("Ref Diff Opened")
+    }
+
+    fn deactivated(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.editor
+            .update(cx, |editor, cx| editor.deactivated(window, cx));
+    }
+
+    fn is_singleton(&self, _: &App) -> bool {
+        false
+    }
+
+    fn act_as_type<'a>(
+        &'a self,
+        type_id: TypeId,
+        self_handle: &'a Entity<Self>,
+        _: &'a App,
+    ) -> Option<AnyView> {
+        if type_id == TypeId::of::<Self>() {
+            Some(self_handle.to_any())
+        } else if type_id == TypeId::of::<Editor>() {
+            Some(self.editor.to_any())
+        } else {
+            None
+        }
+    }
+
+    fn as_searchable(&self, _: &Entity<Self>) -> Option<Box<dyn SearchableItemHandle>> {
+        Some(Box::new(self.editor.clone()))
+    }
+
+    fn for_each_project_item(
+        &self,
+        cx: &App,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::ProjectItem),
+    ) {
+        self.editor.for_each_project_item(cx, f)
+    }
+
+    fn set_nav_history(
+        &mut self,
+        nav_history: ItemNavHistory,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.editor.update(cx, |editor, _| {
+            editor.set_nav_history(Some(nav_history));
+        });
+    }
+
+    fn navigate(
+        &mut self,
+        data: Box<dyn Any>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        self.editor
+            .update(cx, |editor, cx| editor.navigate(data, window, cx))
+    }
+
+    fn breadcrumb_location(&self, _: &App) -> ToolbarItemLocation {
+        ToolbarItemLocation::PrimaryLeft
+    }
+
+    fn breadcrumbs(&self, theme: &theme::Theme, cx: &App) -> Option<Vec<BreadcrumbText>> {
+        self.editor.breadcrumbs(theme, cx)
+    }
+
+    fn added_to_workspace(
+        &mut self,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.editor.update(cx, |editor, cx| {
+            editor.added_to_workspace(workspace, window, cx)
+        });
+    }
+}
+
+impl Render for RefDiff {
+    fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+        self.editor.clone()
+    }
+}
+
+/// Picks the base ref and then the head ref to compare.
+pub struct RefPicker {
+    picker: Entity<Picker<RefPickerDelegate>>,
+    _subscription: Subscription,
+}
+
+impl RefPicker {
+    fn new(
+        repository: Entity<Repository>,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let branches_request = repository.update(cx, |repository, _| repository.branches());
+        cx.spawn_in(window, async move |this, cx| {
+            let mut branches = branches_request.await??;
+            branches.sort_by_key(|branch| {
+                branch
+                    .most_recent_commit
+                    .as_ref()
+                    .map(|commit| 0 - commit.commit_timestamp)
+            });
+            let refs = std::iter::once(SharedString::from("HEAD"))
+                .chain(
+                    branches
+                        .iter()
+                        .map(|branch| SharedString::from(branch.name().to_string())),
+                )
+                .collect();
+
+            this.update_in(cx, |this, window, cx| {
+                this.picker.update(cx, |picker, cx| {
+                    picker.delegate.refs = refs;
+                    picker.refresh(window, cx);
+                })
+            })?;
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+
+        let delegate = RefPickerDelegate {
+            repository,
+            workspace,
+            refs: Vec::new(),
+            matches: Vec::new(),
+            selected_index: 0,
+            base: None,
+        };
+        let picker = cx.new(|cx| Picker::uniform_list(delegate, window, cx));
+        let _subscription = cx.subscribe(&picker, |_, _, _, cx| cx.emit(DismissEvent));
+        Self {
+            picker,
+            _subscription,
+        }
+    }
+}
+
+impl ModalView for RefPicker {}
+impl EventEmitter<DismissEvent> for RefPicker {}
+
+impl Focusable for RefPicker {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for RefPicker {
+    fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+struct RefMatch {
+    name: SharedString,
+    positions: Vec<usize>,
+    is_typed: bool,
+}
+
+pub struct RefPickerDelegate {
+    repository: Entity<Repository>,
+    workspace: WeakEntity<Workspace>,
+    refs: Vec<SharedString>,
+    matches: Vec<RefMatch>,
+    selected_index: usize,
+    base: Option<SharedString>,
+}
+
+impl PickerDelegate for RefPickerDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        match &self.base {
+            Some(base) => format!("Compare {base} to…").into(),
+            None => "Select a branch, tag, or commit to compare from…".into(),
+        }
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let refs = self.refs.clone();
+        cx.spawn_in(window, async move |picker, cx| {
+            let mut matches = if query.is_empty() {
+                refs.into_iter()
+                    .map(|name| RefMatch {
+                        name,
+                        positions: Vec::new(),
+                        is_typed: false,
+                    })
+                    .collect::<Vec<_>>()
+            } else {
+                let candidates = refs
+                    .iter()
+                    .enumerate()
+                    .map(|(ix, name)| StringMatchCandidate::new(ix, name))
+                    .collect::<Vec<_>>();
+                fuzzy::match_strings(
+                    &candidates,
+                    &query,
+                    true,
+                    10000,
+                    &Default::default(),
+                    cx.background_executor().clone(),
+                )
+                .await
+                .into_iter()
+                .map(|candidate| RefMatch {
+                    name: refs[candidate.candidate_id].clone(),
+                    positions: candidate.positions,
+                    is_typed: false,
+                })
+                .collect()
+            };
+
+            // Tags and commits aren't listed, so any typed ref can be compared too.
+            let query = query.trim();
+            if !query.is_empty() && !matches.iter().any(|entry| entry.name.as_ref() == query) {
+                matches.push(RefMatch {
+                    name: query.to_string().into(),
+                    positions: Vec::new(),
+                    is_typed: true,
+                });
+            }
+
+            picker
+                .update(cx, |picker, _| {
+                    let delegate = &mut picker.delegate;
+                    delegate.matches = matches;
+                    delegate.selected_index = delegate
+                        .selected_index
+                        .min(delegate.matches.len().saturating_sub(1));
+                })
+                .log_err();
+        })
+    }
+
+    fn confirm(&mut self, _secondary: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(entry) = self.matches.get(self.selected_index) else {
+            return;
+        };
+        let name = entry.name.clone();
+
+        let Some(base) = self.base.clone() else {
+            self.base = Some(name);
+            self.selected_index = 0;
+            cx.defer_in(window, |picker, window, cx| {
+                picker.set_query("", window, cx);
+                picker.refresh_placeholder(window, cx);
+                picker.refresh(window, cx);
+            });
+            return;
+        };
+
+        RefDiff::open(
+            base.to_string(),
+            name.to_string(),
+            self.repository.clone(),
+            self.workspace.clone(),
+            window,
+            cx,
+        );
+        cx.emit(DismissEvent);
+    }
+
+    fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _window: &mut Window,
+        _cx: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let entry = self.matches.get(ix)?;
+        let label = if entry.is_typed {
+            Label::new(format!("Use \"{}\"", entry.name))
+                .single_line()
+                .into_any_element()
+        } else {
+            HighlightedLabel::new(entry.name.clone(), entry.positions.clone())
+                .truncate()
+                .into_any_element()
+        };
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .child(label),
+        )
+    }
+
+    fn no_matches_text(&self, _window: &mut Window, _cx: &mut App) -> Option<SharedString> {
+        Some("No refs found".into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use project::FakeFs;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use util::path;
+
+    #[gpui::test]
+    async fn test_ref_picker_offers_typed_refs(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/project"), json!({ ".git": {}, "a.txt": "a" }))
+            .await;
+        let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+        cx.run_until_parked();
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        let repository = project
+            .read_with(cx, |project, cx| project.active_repository(cx))
+            .unwrap();
+
+        let picker = cx.new_window_entity(|window, cx| {
+            RefPicker::new(repository, workspace.downgrade(), window, cx)
+        });
+        picker.update_in(cx, |picker, window, cx| {
+            picker.picker.update(cx, |picker, cx| {
+                picker.delegate.refs = vec!["HEAD".into(), "main".into()];
+                picker.set_query("v1.0", window, cx);
+            })
+        });
+        cx.run_until_parked();
+
+        picker.update(cx, |picker, cx| {
+            let delegate = &picker.picker.read(cx).delegate;
+            let names = delegate
+                .matches
+                .iter()
+                .map(|entry| (entry.name.as_ref(), entry.is_typed))
+                .collect::<Vec<_>>();
+            assert_eq!(names, vec![("v1.0", true)]);
+        });
+    }
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            language::init(cx);
+            Project::init_settings(cx);
+            workspace::init_settings(cx);
+            editor::init(cx);
+        });
+    }
+}
@@ -7,6 +7,7 @@ use util::ResultExt as _;
 #[derive(Clone)]
 pub enum RemoteAction {
     Fetch,
+    UpdateSubmodules,
     Pull(Remote),
     Push(SharedString, Remote),
 }
@@ -15,6 +16,7 @@ impl RemoteAction {
     pub fn name(&self) -> &'static str {
         match self {
             RemoteAction::Fetch => "fetch",
+            RemoteAction::UpdateSubmodules => "submodule update",
             RemoteAction::Pull(_) => "pull",
             RemoteAction::Push(_, _) => "push",
         }
@@ -47,6 +49,19 @@ pub fn format_output(action: &RemoteAction, output: RemoteCommandOutput) -> Succ
                 }
             }
         }
+        RemoteAction::UpdateSubmodules => {
+            if output.is_empty() {
+                SuccessMessage {
+                    message: "Submodules are up to date".into(),
+                    style: SuccessStyle::Toast,
+                }
+            } else {
+                SuccessMessage {
+                    message: "Updated submodules".into(),
+                    style: SuccessStyle::ToastWithLog { output },
+                }
+            }
+        }
         RemoteAction::Pull(remote_ref) => {
             let get_changes = |output: &RemoteCommandOutput| -> anyhow::Result<u32> {
                 let last_line = output
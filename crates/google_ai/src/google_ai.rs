@@ -2,7 +2,9 @@ use std::mem;
 
 use anyhow::{Result, anyhow, bail};
 use futures::{AsyncBufReadExt, AsyncReadExt, StreamExt, io::BufReader, stream::BoxStream};
-use http_client::{AsyncBody, HttpClient, Method, Request as HttpRequest};
+use http_client::{
+    AsyncBody, HttpClient, HttpRequestExt as _, Method, Request as HttpRequest, RequestPriority,
+};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 pub const API_URL: &str = "https://generativelanguage.googleapis.com";
@@ -24,6 +26,7 @@ pub async fn stream_generate_content(
     let request_builder = HttpRequest::builder()
         .method(Method::POST)
         .uri(uri)
+        .priority(RequestPriority::Interactive)
         .header("Content-Type", "application/json");
 
     let request = request_builder.body(AsyncBody::from(serde_json::to_string(&request)?))?;
@@ -322,6 +325,10 @@ pub struct GenerationConfig {
     pub top_k: Option<usize>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub thinking_config: Option<ThinkingConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_mime_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_schema: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -36,11 +36,12 @@ use crate::{
     Action, ActionBuildError, ActionRegistry, Any, AnyView, AnyWindowHandle, AppContext, Asset,
     AssetSource, BackgroundExecutor, Bounds, ClipboardItem, CursorStyle, DispatchPhase, DisplayId,
     EventEmitter, FocusHandle, FocusMap, ForegroundExecutor, Global, KeyBinding, KeyContext,
-    Keymap, Keystroke, LayoutId, Menu, MenuItem, OwnedMenu, PathPromptOptions, Pixels, Platform,
-    PlatformDisplay, PlatformKeyboardLayout, Point, PromptBuilder, PromptButton, PromptHandle,
-    PromptLevel, Render, RenderImage, RenderablePromptHandle, Reservation, ScreenCaptureSource,
-    SharedString, SubscriberSet, Subscription, SvgRenderer, Task, TextSystem, Window,
-    WindowAppearance, WindowHandle, WindowId, WindowInvalidator,
+    Keymap, Keystroke, LayoutId, Menu, MenuItem, NotificationPermission, OwnedMenu,
+    PathPromptOptions, Pixels, Platform, PlatformDisplay, PlatformKeyboardLayout, Point,
+    PromptBuilder, PromptButton, PromptHandle, PromptLevel, Render, RenderImage,
+    RenderablePromptHandle, Reservation, ScreenCaptureSource, SharedString, SubscriberSet,
+    Subscription, SvgRenderer, Task, TextSystem, Window, WindowAppearance, WindowHandle, WindowId,
+    WindowInvalidator,
     colors::{Colors, GlobalColors},
     current_platform, hash, init_app_menus,
 };
@@ -237,6 +238,24 @@ type WindowClosedHandler = Box<dyn FnMut(&mut App)>;
 type ReleaseListener = Box<dyn FnOnce(&mut dyn Any, &mut App) + 'static>;
 type NewEntityListener = Box<dyn FnMut(AnyEntity, &mut Option<&mut Window>, &mut App) + 'static>;
 
+/// How many notifications' click handlers are kept, see [`App::post_notification_with_action`].
+const MAX_NOTIFICATION_ACTIVATION_HANDLERS: usize = 32;
+
+/// Whether the app has asked the user for permission to show notifications.
+enum NotificationPermissionRequest {
+    NotRequested,
+    /// The user hasn't answered yet. Holds the notifications posted in the meantime, oldest first.
+    Pending(Vec<QueuedNotification>),
+    Answered,
+}
+
+struct QueuedNotification {
+    identifier: String,
+    title: String,
+    body: String,
+    posted: oneshot::Sender<Result<()>>,
+}
+
 /// Contains the state of the full application, and passed as a reference to a variety of callbacks.
 /// Other [Context] derefs to this type.
 /// You need a reference to an `App` to access the state of a [Entity].
@@ -277,6 +296,11 @@ pub struct App {
     pub(crate) global_observers: SubscriberSet<TypeId, Handler>,
     pub(crate) quit_observers: SubscriberSet<(), QuitHandler>,
     pub(crate) window_closed_observers: SubscriberSet<(), WindowClosedHandler>,
+    /// Handlers for clicks on the most recent notifications, oldest first.
+    notification_activation_handlers: VecDeque<(String, Box<dyn FnOnce(&mut App)>)>,
+    notification_activation_supported: bool,
+    next_notification_id: usize,
+    notification_permission: NotificationPermissionRequest,
     pub(crate) layout_id_buffer: Vec<LayoutId>, // We recycle this memory across layout requests.
     pub(crate) propagate_event: bool,
     pub(crate) prompt_builder: Option<PromptBuilder>,
@@ -348,6 +372,10 @@ impl App {
                 global_observers: SubscriberSet::new(),
                 quit_observers: SubscriberSet::new(),
                 window_closed_observers: SubscriberSet::new(),
+                notification_activation_handlers: VecDeque::new(),
+                notification_activation_supported: false,
+                next_notification_id: 0,
+                notification_permission: NotificationPermissionRequest::NotRequested,
                 layout_id_buffer: Default::default(),
                 propagate_event: true,
                 prompt_builder: Some(PromptBuilder::Default),
@@ -377,6 +405,19 @@ impl App {
             }
         }));
 
+        let notification_activation_supported = platform.on_notification_activated(Box::new({
+            let app = Rc::downgrade(&app);
+            move |identifier| {
+                if let Some(app) = app.upgrade() {
+                    let cx = &mut app.borrow_mut();
+                    if let Some(handler) = cx.take_notification_activation_handler(&identifier) {
+                        cx.update(handler);
+                    }
+                }
+            }
+        }));
+        app.borrow_mut().notification_activation_supported = notification_activation_supported;
+
         platform.on_quit(Box::new({
             let cx = app.clone();
             move || {
@@ -703,6 +744,17 @@ impl App {
         self.platform.window_appearance()
     }
 
+    /// Returns whether the user asked the system to reduce transparency,
+    /// in which case windows should be drawn with an opaque background.
+    pub fn should_reduce_transparency(&self) -> bool {
+        self.platform.should_reduce_transparency()
+    }
+
+    /// Returns whether the user asked the system to increase contrast.
+    pub fn should_increase_contrast(&self) -> bool {
+        self.platform.should_increase_contrast()
+    }
+
     /// Writes data to the primary selection buffer.
     /// Only available on Linux.
     #[cfg(any(target_os = "linux", target_os = "freebsd"))]
@@ -762,6 +814,158 @@ impl App {
         self.platform.register_url_scheme(scheme)
     }
 
+    /// Returns whether the user currently allows the app to show system notifications.
+    ///
+    /// Platforms without a notification permission model always report
+    /// [`NotificationPermission::Authorized`].
+    pub fn notification_permission(&self) -> Task<Result<NotificationPermission>> {
+        self.platform.notification_permission()
+    }
+
+    /// Asks the user for permission to show system notifications.
+    ///
+    /// The system only prompts the first time; afterwards this resolves to the
+    /// permission the user already chose.
+    pub fn request_notification_permission(&self) -> Task<Result<NotificationPermission>> {
+        self.platform.request_notification_permission()
+    }
+
+    /// Shows a system notification with the given title and body.
+    ///
+    /// The system decides whether to display it, for example only while the
+    /// app is in the background. Platforms without system notifications ignore
+    /// this.
+    ///
+    /// The first notification the app posts asks the user for permission to show
+    /// notifications, if they haven't been asked before. Notifications posted
+    /// before the user answers are shown once they allow them.
+    pub fn post_notification(&mut self, title: &str, body: &str) -> Task<Result<()>> {
+        let identifier = self.next_notification_identifier();
+        self.post_notification_with_identifier(identifier, title, body)
+    }
+
+    /// Shows a system notification like [`App::post_notification`], invoking
+    /// `on_activate` if the user clicks it.
+    ///
+    /// Clicks are only handled for the most recently posted notifications, as
+    /// older ones have most likely been dismissed, and only on platforms that
+    /// report them.
+    pub fn post_notification_with_action(
+        &mut self,
+        title: &str,
+        body: &str,
+        on_activate: impl FnOnce(&mut App) + 'static,
+    ) -> Task<Result<()>> {
+        let identifier = self.next_notification_identifier();
+        if !self.notification_activation_supported {
+            return self.post_notification_with_identifier(identifier, title, body);
+        }
+        // The system doesn't report notifications that are dismissed or expire, so only
+        // the handlers of the most recent ones are kept.
+        if self.notification_activation_handlers.len() == MAX_NOTIFICATION_ACTIVATION_HANDLERS {
+            self.notification_activation_handlers.pop_front();
+        }
+        self.notification_activation_handlers
+            .push_back((identifier.clone(), Box::new(on_activate)));
+        let post = self.post_notification_with_identifier(identifier.clone(), title, body);
+        self.spawn(async move |cx| {
+            let result = post.await;
+            if result.is_err() {
+                cx.update(|cx| cx.take_notification_activation_handler(&identifier))
+                    .ok();
+            }
+            result
+        })
+    }
+
+    fn take_notification_activation_handler(
+        &mut self,
+        identifier: &str,
+    ) -> Option<Box<dyn FnOnce(&mut App)>> {
+        let ix = self
+            .notification_activation_handlers
+            .iter()
+            .position(|(handler_identifier, _)| handler_identifier == identifier)?;
+        let (_, handler) = self.notification_activation_handlers.remove(ix)?;
+        Some(handler)
+    }
+
+    fn post_notification_with_identifier(
+        &mut self,
+        identifier: String,
+        title: &str,
+        body: &str,
+    ) -> Task<Result<()>> {
+        let (posted_tx, posted_rx) = oneshot::channel();
+        let notification = QueuedNotification {
+            identifier,
+            title: title.to_string(),
+            body: body.to_string(),
+            posted: posted_tx,
+        };
+        match &mut self.notification_permission {
+            NotificationPermissionRequest::Answered => {
+                return self.platform.post_notification(
+                    &notification.identifier,
+                    &notification.title,
+                    &notification.body,
+                );
+            }
+            NotificationPermissionRequest::Pending(queue) => queue.push(notification),
+            NotificationPermissionRequest::NotRequested => {
+                // The system only prompts the first time it's asked, and ignores notifications
+                // posted before the user answers, so hold them until there's an answer.
+                self.notification_permission =
+                    NotificationPermissionRequest::Pending(vec![notification]);
+                let permission = self.platform.request_notification_permission();
+                let platform = self.platform.clone();
+                self.spawn(async move |cx| {
+                    let permission = permission.await;
+                    let queue = cx.update(|cx| {
+                        match mem::replace(
+                            &mut cx.notification_permission,
+                            NotificationPermissionRequest::Answered,
+                        ) {
+                            NotificationPermissionRequest::Pending(queue) => queue,
+                            _ => Vec::new(),
+                        }
+                    })?;
+                    for notification in queue {
+                        let result = match &permission {
+                            Ok(NotificationPermission::Denied) => Ok(()),
+                            Ok(_) => {
+                                platform
+                                    .post_notification(
+                                        &notification.identifier,
+                                        &notification.title,
+                                        &notification.body,
+                                    )
+                                    .await
+                            }
+                            Err(error) => Err(anyhow!(
+                                "failed to request notification permission: {error:#}"
+                            )),
+                        };
+                        notification.posted.send(result).ok();
+                    }
+                    anyhow::Ok(())
+                })
+                .detach_and_log_err(self);
+            }
+        }
+        self.spawn(async move |_| posted_rx.await?)
+    }
+
+    fn next_notification_identifier(&mut self) -> String {
+        self.next_notification_id += 1;
+        // The system can still deliver clicks on notifications from earlier runs of the app.
+        format!(
+            "gpui-notification-{}-{}",
+            std::process::id(),
+            self.next_notification_id
+        )
+    }
+
     /// Returns the full pathname of the current app bundle.
     ///
     /// Returns an error if the app is not being run from a bundle.
@@ -1976,3 +2180,79 @@ impl HttpClient for NullHttpClient {
         type_name::<Self>()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::MAX_NOTIFICATION_ACTIVATION_HANDLERS;
+    use crate::{self as gpui, NotificationPermission, TestAppContext};
+    use std::{cell::RefCell, rc::Rc};
+
+    #[gpui::test]
+    async fn test_notifications_wait_for_permission(cx: &mut TestAppContext) {
+        let first = cx.update(|cx| cx.post_notification("First", ""));
+        let second = cx.update(|cx| cx.post_notification("Second", ""));
+        cx.run_until_parked();
+        assert!(cx.posted_notification_titles().is_empty());
+
+        cx.simulate_notification_permission_answer(NotificationPermission::Authorized);
+        first.await.unwrap();
+        second.await.unwrap();
+        assert_eq!(cx.posted_notification_titles(), ["First", "Second"]);
+
+        // The permission is only requested once.
+        cx.update(|cx| cx.post_notification("Third", ""))
+            .await
+            .unwrap();
+        assert_eq!(
+            cx.posted_notification_titles(),
+            ["First", "Second", "Third"]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_notifications_dropped_when_permission_denied(cx: &mut TestAppContext) {
+        let first = cx.update(|cx| cx.post_notification("First", ""));
+        let second = cx.update(|cx| cx.post_notification("Second", ""));
+        cx.simulate_notification_permission_answer(NotificationPermission::Denied);
+        first.await.unwrap();
+        second.await.unwrap();
+        assert!(cx.posted_notification_titles().is_empty());
+    }
+
+    #[gpui::test]
+    async fn test_notification_activation_handlers_are_capped(cx: &mut TestAppContext) {
+        let clicked = Rc::new(RefCell::new(Vec::new()));
+        let posts = (0..=MAX_NOTIFICATION_ACTIVATION_HANDLERS)
+            .map(|ix| {
+                let clicked = clicked.clone();
+                cx.update(|cx| {
+                    cx.post_notification_with_action(&format!("Notification {ix}"), "", move |_| {
+                        clicked.borrow_mut().push(ix)
+                    })
+                })
+            })
+            .collect::<Vec<_>>();
+        cx.simulate_notification_permission_answer(NotificationPermission::Authorized);
+        for post in posts {
+            post.await.unwrap();
+        }
+        cx.update(|cx| {
+            assert_eq!(
+                cx.notification_activation_handlers.len(),
+                MAX_NOTIFICATION_ACTIVATION_HANDLERS
+            )
+        });
+
+        // The oldest notification's handler was dropped to make room.
+        cx.simulate_notification_click("Notification 0");
+        cx.simulate_notification_click(&format!(
+            "Notification {MAX_NOTIFICATION_ACTIVATION_HANDLERS}"
+        ));
+        assert_eq!(*clicked.borrow(), [MAX_NOTIFICATION_ACTIVATION_HANDLERS]);
+
+        // Each handler runs at most once.
+        cx.simulate_notification_click("Notification 1");
+        cx.simulate_notification_click("Notification 1");
+        assert_eq!(*clicked.borrow(), [MAX_NOTIFICATION_ACTIVATION_HANDLERS, 1]);
+    }
+}
@@ -2,10 +2,10 @@ use crate::{
     Action, AnyView, AnyWindowHandle, App, AppCell, AppContext, AsyncApp, AvailableSpace,
     BackgroundExecutor, BorrowAppContext, Bounds, ClipboardItem, DrawPhase, Drawable, Element,
     Empty, EventEmitter, ForegroundExecutor, Global, InputEvent, Keystroke, Modifiers,
-    ModifiersChangedEvent, MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent, Pixels,
-    Platform, Point, Render, Result, Size, Task, TestDispatcher, TestPlatform,
-    TestScreenCaptureSource, TestWindow, TextSystem, VisualContext, Window, WindowBounds,
-    WindowHandle, WindowOptions,
+    ModifiersChangedEvent, MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent,
+    NotificationPermission, Pixels, Platform, Point, Render, Result, Size, Task, TestDispatcher,
+    TestPlatform, TestScreenCaptureSource, TestWindow, TextSystem, VisualContext, Window,
+    WindowBounds, WindowHandle, WindowOptions,
 };
 use anyhow::{anyhow, bail};
 use futures::{Stream, StreamExt, channel::oneshot};
@@ -282,6 +282,22 @@ impl TestAppContext {
         self.test_platform.read_from_clipboard()
     }
 
+    /// Answers the oldest pending request for permission to show notifications.
+    pub fn simulate_notification_permission_answer(&self, permission: NotificationPermission) {
+        self.test_platform
+            .simulate_notification_permission_answer(permission)
+    }
+
+    /// Returns the titles of the system notifications posted so far, oldest first.
+    pub fn posted_notification_titles(&self) -> Vec<String> {
+        self.test_platform.posted_notification_titles()
+    }
+
+    /// Simulates clicking the most recent system notification with the given title.
+    pub fn simulate_notification_click(&self, title: &str) {
+        self.test_platform.simulate_notification_click(title)
+    }
+
     /// Simulates choosing a File in the platform's "Open" dialog.
     pub fn simulate_new_path_selection(
         &self,
@@ -179,10 +179,34 @@ pub(crate) trait Platform: 'static {
     /// Returns the appearance of the application's windows.
     fn window_appearance(&self) -> WindowAppearance;
 
+    /// Returns whether the user asked the system to reduce transparency.
+    fn should_reduce_transparency(&self) -> bool {
+        false
+    }
+
+    /// Returns whether the user asked the system to increase contrast.
+    fn should_increase_contrast(&self) -> bool {
+        false
+    }
+
     fn open_url(&self, url: &str);
     fn on_open_urls(&self, callback: Box<dyn FnMut(Vec<String>)>);
     fn register_url_scheme(&self, url: &str) -> Task<Result<()>>;
 
+    fn notification_permission(&self) -> Task<Result<NotificationPermission>> {
+        Task::ready(Ok(NotificationPermission::Authorized))
+    }
+    fn request_notification_permission(&self) -> Task<Result<NotificationPermission>> {
+        Task::ready(Ok(NotificationPermission::Authorized))
+    }
+    fn post_notification(&self, _identifier: &str, _title: &str, _body: &str) -> Task<Result<()>> {
+        Task::ready(Ok(()))
+    }
+    /// Returns whether the platform reports clicks on posted notifications.
+    fn on_notification_activated(&self, _callback: Box<dyn FnMut(String)>) -> bool {
+        false
+    }
+
     fn prompt_for_paths(
         &self,
         options: PathPromptOptions,
@@ -1245,6 +1269,19 @@ pub enum PromptLevel {
     Critical,
 }
 
+/// Whether the user allows the application to show system notifications.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum NotificationPermission {
+    /// The user hasn't been asked yet.
+    NotDetermined,
+
+    /// The user allowed notifications, possibly only provisionally.
+    Authorized,
+
+    /// The user turned notifications off in the system settings.
+    Denied,
+}
+
 /// Prompt Button
 #[derive(Clone, Debug, PartialEq)]
 pub enum PromptButton {
@@ -7,9 +7,9 @@ use super::{
 use crate::{
     Action, AnyWindowHandle, BackgroundExecutor, ClipboardEntry, ClipboardItem, ClipboardString,
     CursorStyle, ForegroundExecutor, Image, ImageFormat, KeyContext, Keymap, MacDispatcher,
-    MacDisplay, MacWindow, Menu, MenuItem, PathPromptOptions, Platform, PlatformDisplay,
-    PlatformKeyboardLayout, PlatformTextSystem, PlatformWindow, Result, ScreenCaptureSource,
-    SemanticVersion, Task, WindowAppearance, WindowParams, hash,
+    MacDisplay, MacWindow, Menu, MenuItem, NotificationPermission, PathPromptOptions, Platform,
+    PlatformDisplay, PlatformKeyboardLayout, PlatformTextSystem, PlatformWindow, Result,
+    ScreenCaptureSource, SemanticVersion, Task, WindowAppearance, WindowParams, hash,
 };
 use anyhow::{Context as _, anyhow};
 use block::ConcreteBlock;
@@ -138,6 +138,10 @@ unsafe fn build_classes() {
                 sel!(application:openURLs:),
                 open_urls as extern "C" fn(&mut Object, Sel, id, id),
             );
+            decl.add_method(
+                sel!(userNotificationCenter:didReceiveNotificationResponse:withCompletionHandler:),
+                did_receive_notification_response as extern "C" fn(&mut Object, Sel, id, id, id),
+            );
 
             decl.add_method(
                 sel!(onKeyboardLayoutChange:),
@@ -168,6 +172,7 @@ pub(crate) struct MacPlatformState {
     will_open_menu: Option<Box<dyn FnMut()>>,
     menu_actions: Vec<Box<dyn Action>>,
     open_urls: Option<Box<dyn FnMut(Vec<String>)>>,
+    notification_activated: Option<Box<dyn FnMut(String)>>,
     finish_launching: Option<Box<dyn FnOnce()>>,
     dock_menu: Option<id>,
 }
@@ -204,6 +209,7 @@ impl MacPlatform {
             will_open_menu: None,
             menu_actions: Default::default(),
             open_urls: None,
+            notification_activated: None,
             finish_launching: None,
             dock_menu: None,
             on_keyboard_layout_change: None,
@@ -612,6 +618,22 @@ impl Platform for MacPlatform {
         }
     }
 
+    fn should_reduce_transparency(&self) -> bool {
+        unsafe {
+            let workspace: id = msg_send![class!(NSWorkspace), sharedWorkspace];
+            let reduce: BOOL = msg_send![workspace, accessibilityDisplayShouldReduceTransparency];
+            reduce == YES
+        }
+    }
+
+    fn should_increase_contrast(&self) -> bool {
+        unsafe {
+            let workspace: id = msg_send![class!(NSWorkspace), sharedWorkspace];
+            let increase: BOOL = msg_send![workspace, accessibilityDisplayShouldIncreaseContrast];
+            increase == YES
+        }
+    }
+
     fn open_url(&self, url: &str) {
         unsafe {
             let url = NSURL::alloc(nil)
@@ -671,10 +693,128 @@ impl Platform for MacPlatform {
             .spawn(async { crate::Flatten::flatten(done_rx.await.map_err(|e| anyhow!(e))) })
     }
 
+    fn notification_permission(&self) -> Task<anyhow::Result<NotificationPermission>> {
+        let center = match user_notification_center() {
+            Ok(center) => center,
+            Err(error) => return Task::ready(Err(error)),
+        };
+
+        let (done_tx, done_rx) = oneshot::channel();
+        unsafe {
+            let done_tx = Cell::new(Some(done_tx));
+            let block = ConcreteBlock::new(move |settings: id| {
+                let status: NSInteger = msg_send![settings, authorizationStatus];
+                if let Some(done_tx) = done_tx.take() {
+                    done_tx
+                        .send(notification_permission_from_status(status))
+                        .ok();
+                }
+            });
+            let block = block.copy();
+            let _: () = msg_send![center, getNotificationSettingsWithCompletionHandler: block];
+        }
+
+        self.background_executor()
+            .spawn(async { done_rx.await.map_err(|e| anyhow!(e)) })
+    }
+
+    fn request_notification_permission(&self) -> Task<anyhow::Result<NotificationPermission>> {
+        let center = match user_notification_center() {
+            Ok(center) => center,
+            Err(error) => return Task::ready(Err(error)),
+        };
+
+        let (done_tx, done_rx) = oneshot::channel();
+        unsafe {
+            let done_tx = Cell::new(Some(done_tx));
+            let block = ConcreteBlock::new(move |granted: BOOL, error: id| {
+                let result = if error != nil {
+                    let msg: id = msg_send![error, localizedDescription];
+                    Err(anyhow!(
+                        "Failed to request notification permission: {msg:?}"
+                    ))
+                } else if granted == YES {
+                    Ok(NotificationPermission::Authorized)
+                } else {
+                    Ok(NotificationPermission::Denied)
+                };
+
+                if let Some(done_tx) = done_tx.take() {
+                    done_tx.send(result).ok();
+                }
+            });
+            let block = block.copy();
+            let options = UN_AUTHORIZATION_OPTION_BADGE
+                | UN_AUTHORIZATION_OPTION_SOUND
+                | UN_AUTHORIZATION_OPTION_ALERT;
+            let _: () = msg_send![
+                center,
+                requestAuthorizationWithOptions: options
+                completionHandler: block
+            ];
+        }
+
+        self.background_executor()
+            .spawn(async { crate::Flatten::flatten(done_rx.await.map_err(|e| anyhow!(e))) })
+    }
+
+    fn post_notification(
+        &self,
+        identifier: &str,
+        title: &str,
+        body: &str,
+    ) -> Task<anyhow::Result<()>> {
+        let center = match user_notification_center() {
+            Ok(center) => center,
+            Err(error) => return Task::ready(Err(error)),
+        };
+
+        let (done_tx, done_rx) = oneshot::channel();
+        unsafe {
+            let content: id = msg_send![class!(UNMutableNotificationContent), new];
+            let _: () = msg_send![content, setTitle: ns_string(title)];
+            let _: () = msg_send![content, setBody: ns_string(body)];
+            let request: id = msg_send![
+                class!(UNNotificationRequest),
+                requestWithIdentifier: ns_string(identifier)
+                content: content
+                trigger: nil
+            ];
+            let _: () = msg_send![content, release];
+
+            let done_tx = Cell::new(Some(done_tx));
+            let block = ConcreteBlock::new(move |error: id| {
+                let result = if error == nil {
+                    Ok(())
+                } else {
+                    let msg: id = msg_send![error, localizedDescription];
+                    Err(anyhow!("Failed to post notification: {msg:?}"))
+                };
+                if let Some(done_tx) = done_tx.take() {
+                    done_tx.send(result).ok();
+                }
+            });
+            let block = block.copy();
+            let _: () = msg_send![
+                center,
+                addNotificationRequest: request
+                withCompletionHandler: block
+            ];
+        }
+
+        self.background_executor()
+            .spawn(async { crate::Flatten::flatten(done_rx.await.map_err(|e| anyhow!(e))) })
+    }
+
     fn on_open_urls(&self, callback: Box<dyn FnMut(Vec<String>)>) {
         self.0.lock().open_urls = Some(callback);
     }
 
+    fn on_notification_activated(&self, callback: Box<dyn FnMut(String)>) -> bool {
+        self.0.lock().notification_activated = Some(callback);
+        true
+    }
+
     fn prompt_for_paths(
         &self,
         options: PathPromptOptions,
@@ -1320,6 +1460,11 @@ extern "C" fn did_finish_launching(this: &mut Object, _: Sel, _: id) {
             object: nil
         ];
 
+        // Without a delegate, clicking one of our notifications only brings the app forward.
+        if let Ok(center) = user_notification_center() {
+            let _: () = msg_send![center, setDelegate: this as id];
+        }
+
         let platform = get_mac_platform(this);
         let callback = platform.0.lock().finish_launching.take();
         if let Some(callback) = callback {
@@ -1388,6 +1533,38 @@ extern "C" fn open_urls(this: &mut Object, _: Sel, _: id, urls: id) {
     }
 }
 
+extern "C" fn did_receive_notification_response(
+    this: &mut Object,
+    _: Sel,
+    _center: id,
+    response: id,
+    completion_handler: id,
+) {
+    let identifier = unsafe {
+        let notification: id = msg_send![response, notification];
+        let request: id = msg_send![notification, request];
+        let identifier: id = msg_send![request, identifier];
+        CStr::from_ptr(identifier.UTF8String() as *mut c_char)
+            .to_string_lossy()
+            .into_owned()
+    };
+    let platform = unsafe { get_mac_platform(this) };
+    let mut lock = platform.0.lock();
+    if let Some(mut callback) = lock.notification_activated.take() {
+        drop(lock);
+        callback(identifier);
+        platform
+            .0
+            .lock()
+            .notification_activated
+            .get_or_insert(callback);
+    }
+    unsafe {
+        let completion_handler = completion_handler as *mut block::Block<(), ()>;
+        (*completion_handler).call(());
+    }
+}
+
 extern "C" fn handle_menu_item(this: &mut Object, _: Sel, item: id) {
     unsafe {
         let platform = get_mac_platform(this);
@@ -1466,6 +1643,40 @@ unsafe fn ns_url_to_path(url: id) -> Result<PathBuf> {
     })))
 }
 
+const UN_AUTHORIZATION_OPTION_BADGE: NSUInteger = 1 << 0;
+const UN_AUTHORIZATION_OPTION_SOUND: NSUInteger = 1 << 1;
+const UN_AUTHORIZATION_OPTION_ALERT: NSUInteger = 1 << 2;
+
+/// Returns the shared `UNUserNotificationCenter`, which raises an Objective-C
+/// exception when accessed from a process that isn't running from an app bundle.
+fn user_notification_center() -> anyhow::Result<id> {
+    unsafe {
+        let bundle: id = msg_send![class!(NSBundle), mainBundle];
+        let bundle_id: id = msg_send![bundle, bundleIdentifier];
+        anyhow::ensure!(
+            bundle_id != nil,
+            "Notifications are only available in bundled apps"
+        );
+        Ok(msg_send![
+            class!(UNUserNotificationCenter),
+            currentNotificationCenter
+        ])
+    }
+}
+
+fn notification_permission_from_status(status: NSInteger) -> NotificationPermission {
+    // UNAuthorizationStatus: 1 is denied, while 2 (authorized), 3 (provisional)
+    // and 4 (ephemeral) all allow notifications to be delivered.
+    match status {
+        0 => NotificationPermission::NotDetermined,
+        1 => NotificationPermission::Denied,
+        _ => NotificationPermission::Authorized,
+    }
+}
+
+#[link(name = "UserNotifications", kind = "framework")]
+unsafe extern "C" {}
+
 #[link(name = "Carbon", kind = "framework")]
 unsafe extern "C" {
     pub(super) fn TISCopyCurrentKeyboardLayoutInputSource() -> *mut Object;
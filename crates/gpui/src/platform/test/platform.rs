@@ -1,8 +1,9 @@
 use crate::{
     AnyWindowHandle, BackgroundExecutor, ClipboardItem, CursorStyle, DevicePixels,
-    ForegroundExecutor, Keymap, NoopTextSystem, Platform, PlatformDisplay, PlatformKeyboardLayout,
-    PlatformTextSystem, PromptButton, ScreenCaptureFrame, ScreenCaptureSource, ScreenCaptureStream,
-    Size, Task, TestDisplay, TestWindow, WindowAppearance, WindowParams, size,
+    ForegroundExecutor, Keymap, NoopTextSystem, NotificationPermission, Platform, PlatformDisplay,
+    PlatformKeyboardLayout, PlatformTextSystem, PromptButton, ScreenCaptureFrame,
+    ScreenCaptureSource, ScreenCaptureStream, Size, Task, TestDisplay, TestWindow,
+    WindowAppearance, WindowParams, size,
 };
 use anyhow::Result;
 use collections::VecDeque;
@@ -32,6 +33,7 @@ pub(crate) struct TestPlatform {
     #[cfg(any(target_os = "linux", target_os = "freebsd"))]
     current_primary_item: Mutex<Option<ClipboardItem>>,
     pub(crate) prompts: RefCell<TestPrompts>,
+    notifications: RefCell<TestNotifications>,
     screen_capture_sources: RefCell<Vec<TestScreenCaptureSource>>,
     pub opened_url: RefCell<Option<String>>,
     pub text_system: Arc<dyn PlatformTextSystem>,
@@ -73,6 +75,14 @@ struct TestPrompt {
     tx: oneshot::Sender<usize>,
 }
 
+#[derive(Default)]
+struct TestNotifications {
+    permission_requests: VecDeque<oneshot::Sender<NotificationPermission>>,
+    posted_titles: Vec<String>,
+    posted_identifiers: Vec<String>,
+    activated: Option<Box<dyn FnMut(String)>>,
+}
+
 #[derive(Default)]
 pub(crate) struct TestPrompts {
     multiple_choice: VecDeque<TestPrompt>,
@@ -97,6 +107,7 @@ impl TestPlatform {
             background_executor: executor,
             foreground_executor,
             prompts: Default::default(),
+            notifications: Default::default(),
             screen_capture_sources: Default::default(),
             active_cursor: Default::default(),
             active_display: Rc::new(TestDisplay::new()),
@@ -144,6 +155,42 @@ impl TestPlatform {
         prompt.tx.send(ix).ok();
     }
 
+    #[track_caller]
+    pub(crate) fn simulate_notification_permission_answer(
+        &self,
+        permission: NotificationPermission,
+    ) {
+        let tx = self
+            .notifications
+            .borrow_mut()
+            .permission_requests
+            .pop_front()
+            .expect("no pending notification permission request");
+        tx.send(permission).ok();
+    }
+
+    pub(crate) fn posted_notification_titles(&self) -> Vec<String> {
+        self.notifications.borrow().posted_titles.clone()
+    }
+
+    #[track_caller]
+    pub(crate) fn simulate_notification_click(&self, title: &str) {
+        let mut notifications = self.notifications.borrow_mut();
+        let ix = notifications
+            .posted_titles
+            .iter()
+            .rposition(|posted_title| posted_title == title)
+            .unwrap_or_else(|| panic!("no notification titled {title:?} was posted"));
+        let identifier = notifications.posted_identifiers[ix].clone();
+        let mut callback = notifications
+            .activated
+            .take()
+            .expect("no notification activation callback");
+        drop(notifications);
+        callback(identifier);
+        self.notifications.borrow_mut().activated = Some(callback);
+    }
+
     pub(crate) fn has_pending_prompt(&self) -> bool {
         !self.prompts.borrow().multiple_choice.is_empty()
     }
@@ -406,6 +453,29 @@ impl Platform for TestPlatform {
         Task::ready(Ok(()))
     }
 
+    fn request_notification_permission(&self) -> Task<Result<NotificationPermission>> {
+        let (tx, rx) = oneshot::channel();
+        self.notifications
+            .borrow_mut()
+            .permission_requests
+            .push_back(tx);
+        self.foreground_executor.spawn(async move { Ok(rx.await?) })
+    }
+
+    fn post_notification(&self, identifier: &str, title: &str, _body: &str) -> Task<Result<()>> {
+        let mut notifications = self.notifications.borrow_mut();
+        notifications.posted_titles.push(title.to_string());
+        notifications
+            .posted_identifiers
+            .push(identifier.to_string());
+        Task::ready(Ok(()))
+    }
+
+    fn on_notification_activated(&self, callback: Box<dyn FnMut(String)>) -> bool {
+        self.notifications.borrow_mut().activated = Some(callback);
+        true
+    }
+
     fn register_url_scheme(&self, _: &str) -> Task<anyhow::Result<()>> {
         unimplemented!()
     }
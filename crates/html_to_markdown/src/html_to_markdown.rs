@@ -3,6 +3,7 @@
 mod html_element;
 pub mod markdown;
 mod markdown_writer;
+mod readability;
 pub mod structure;
 
 use std::io::Read;
@@ -16,6 +17,7 @@ use markup5ever_rcdom::RcDom;
 
 pub use crate::html_element::*;
 pub use crate::markdown_writer::*;
+pub use crate::readability::*;
 
 /// Converts the provided HTML to Markdown.
 pub fn convert_html_to_markdown(html: impl Read, handlers: &mut [TagHandler]) -> Result<String> {
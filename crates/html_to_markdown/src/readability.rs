@@ -0,0 +1,349 @@
+//! Extracts the main content of a web page, in the spirit of Mozilla's Readability.
+//!
+//! Paragraph-like elements are scored by how much prose they contain, and their scores are
+//! propagated to their parent and grandparent. The ancestor with the best score, discounted by
+//! how much of its text is links, is taken to be the main content.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::io::Read;
+use std::rc::Rc;
+use std::sync::LazyLock;
+
+use anyhow::{Context as _, Result};
+use markup5ever_rcdom::{Handle, Node, NodeData};
+use regex::Regex;
+
+use crate::{HandleTag, HtmlElement, MarkdownWriter, StartTagOutcome, TagHandler, parse_html};
+
+/// Paragraphs with less text than this are ignored when scoring, as they're usually captions
+/// or chrome.
+const MIN_PARAGRAPH_LEN: usize = 25;
+
+/// The main content of a web page, converted to Markdown.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReadableContent {
+    pub title: Option<String>,
+    pub markdown: String,
+}
+
+/// Converts the main content of the provided HTML to Markdown, leaving out navigation,
+/// sidebars, comments, and other page chrome.
+pub fn convert_html_to_readable_markdown(
+    html: impl Read,
+    handlers: &mut [TagHandler],
+) -> Result<ReadableContent> {
+    let dom = parse_html(html).context("failed to parse HTML")?;
+    let title = document_title(&dom.document);
+    let root = main_content(&dom.document).unwrap_or_else(|| dom.document.clone());
+
+    let unlikely_content_remover: TagHandler = Rc::new(RefCell::new(UnlikelyContentRemover));
+    let mut handlers = std::iter::once(unlikely_content_remover)
+        .chain(handlers.iter().cloned())
+        .collect::<Vec<_>>();
+    let markdown = MarkdownWriter::new()
+        .run(&root, &mut handlers)
+        .context("failed to convert HTML to Markdown")?;
+
+    Ok(ReadableContent { title, markdown })
+}
+
+fn unlikely_candidate_regex() -> &'static Regex {
+    static REGEX: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(
+            r"(?i)-ad-|ad-break|advert|banner|breadcrumb|combx|comment|community|cookie|disqus|footer|gdpr|header|menu|modal|newsletter|pager|pagination|popup|promo|related|remark|replies|share|shoutbox|sidebar|skyscraper|social|sponsor|subscribe|toolbar|widget",
+        )
+        .expect("Failed to create unlikely_candidate_regex")
+    });
+    &REGEX
+}
+
+fn maybe_candidate_regex() -> &'static Regex {
+    static REGEX: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r"(?i)and|article|body|column|content|main|shadow")
+            .expect("Failed to create maybe_candidate_regex")
+    });
+    &REGEX
+}
+
+fn positive_regex() -> &'static Regex {
+    static REGEX: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r"(?i)article|body|content|entry|main|page|post|text|blog|story")
+            .expect("Failed to create positive_regex")
+    });
+    &REGEX
+}
+
+fn negative_regex() -> &'static Regex {
+    static REGEX: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(
+            r"(?i)hidden|banner|combx|comment|footer|footnote|masthead|media|meta|promo|related|scroll|share|shoutbox|sidebar|skyscraper|sponsor|shopping|tags|widget",
+        )
+        .expect("Failed to create negative_regex")
+    });
+    &REGEX
+}
+
+/// Skips elements that are almost never part of a page's main content.
+struct UnlikelyContentRemover;
+
+impl HandleTag for UnlikelyContentRemover {
+    fn should_handle(&self, _tag: &str) -> bool {
+        true
+    }
+
+    fn handle_tag_start(
+        &mut self,
+        tag: &HtmlElement,
+        _writer: &mut MarkdownWriter,
+    ) -> StartTagOutcome {
+        if is_unlikely(tag) {
+            StartTagOutcome::Skip
+        } else {
+            StartTagOutcome::Continue
+        }
+    }
+}
+
+fn is_unlikely(element: &HtmlElement) -> bool {
+    match element.tag() {
+        "html" | "body" | "article" | "main" => return false,
+        "aside" | "button" | "dialog" | "footer" | "form" | "iframe" | "nav" | "noscript"
+        | "script" | "style" | "head" => return true,
+        _ => {}
+    }
+
+    if element.attr("hidden").is_some()
+        || element.attr("aria-hidden").as_deref() == Some("true")
+        || element.attr("role").is_some_and(|role| {
+            matches!(
+                role.as_str(),
+                "alert" | "banner" | "complementary" | "contentinfo" | "dialog" | "navigation"
+            )
+        })
+    {
+        return true;
+    }
+
+    let class_and_id = class_and_id(element);
+    unlikely_candidate_regex().is_match(&class_and_id)
+        && !maybe_candidate_regex().is_match(&class_and_id)
+}
+
+fn class_and_id(element: &HtmlElement) -> String {
+    format!(
+        "{} {}",
+        element.attr("class").unwrap_or_default(),
+        element.attr("id").unwrap_or_default()
+    )
+}
+
+fn element(node: &Handle) -> Option<HtmlElement> {
+    match &node.data {
+        NodeData::Element { name, attrs, .. } => {
+            Some(HtmlElement::new(name.local.to_string(), attrs.clone()))
+        }
+        _ => None,
+    }
+}
+
+fn document_title(document: &Handle) -> Option<String> {
+    let title = find_element(document, "title")
+        .or_else(|| find_element(document, "h1"))
+        .map(|node| collapse_whitespace(&inner_text(&node)))?;
+    (!title.is_empty()).then_some(title)
+}
+
+fn find_element(node: &Handle, tag: &str) -> Option<Handle> {
+    if element(node).is_some_and(|element| element.tag() == tag) {
+        return Some(node.clone());
+    }
+    node.children
+        .borrow()
+        .iter()
+        .find_map(|child| find_element(child, tag))
+}
+
+fn inner_text(node: &Handle) -> String {
+    let mut text = String::new();
+    push_inner_text(node, &mut text);
+    text
+}
+
+fn push_inner_text(node: &Handle, text: &mut String) {
+    if let NodeData::Text { contents } = &node.data {
+        text.push_str(&contents.borrow());
+    }
+    for child in node.children.borrow().iter() {
+        push_inner_text(child, text);
+    }
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Returns the element that most likely contains the page's main content.
+fn main_content(document: &Handle) -> Option<Handle> {
+    let mut candidates = HashMap::<*const Node, (Handle, f64)>::default();
+    score_paragraphs(document, &mut Vec::new(), &mut candidates);
+
+    candidates
+        .into_values()
+        .map(|(node, score)| {
+            let score = score * (1. - link_density(&node));
+            (node, score)
+        })
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(node, _)| node)
+}
+
+fn score_paragraphs(
+    node: &Handle,
+    ancestors: &mut Vec<Handle>,
+    candidates: &mut HashMap<*const Node, (Handle, f64)>,
+) {
+    let Some(element) = element(node) else {
+        for child in node.children.borrow().iter() {
+            score_paragraphs(child, ancestors, candidates);
+        }
+        return;
+    };
+    if is_unlikely(&element) {
+        return;
+    }
+
+    if matches!(element.tag(), "p" | "pre" | "td" | "blockquote") {
+        let text = collapse_whitespace(&inner_text(node));
+        if text.len() >= MIN_PARAGRAPH_LEN {
+            let score = 1. + text.matches(',').count() as f64 + (text.len() / 100).min(3) as f64;
+            for (ancestor, weight) in ancestors.iter().rev().zip([1., 0.5]) {
+                let (_, candidate_score) = candidates
+                    .entry(Rc::as_ptr(ancestor))
+                    .or_insert_with(|| (ancestor.clone(), initial_score(ancestor)));
+                *candidate_score += score * weight;
+            }
+        }
+    }
+
+    ancestors.push(node.clone());
+    for child in node.children.borrow().iter() {
+        score_paragraphs(child, ancestors, candidates);
+    }
+    ancestors.pop();
+}
+
+fn initial_score(node: &Handle) -> f64 {
+    let Some(element) = element(node) else {
+        return 0.;
+    };
+    let tag_score = match element.tag() {
+        "article" | "main" => 10.,
+        "div" => 5.,
+        "pre" | "td" | "blockquote" => 3.,
+        "address" | "ol" | "ul" | "dl" | "dd" | "dt" | "li" | "form" => -3.,
+        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "th" => -5.,
+        _ => 0.,
+    };
+
+    let class_and_id = class_and_id(&element);
+    let mut class_score = 0.;
+    if positive_regex().is_match(&class_and_id) {
+        class_score += 25.;
+    }
+    if negative_regex().is_match(&class_and_id) {
+        class_score -= 25.;
+    }
+
+    tag_score + class_score
+}
+
+/// Returns the fraction of the node's text that's inside links.
+fn link_density(node: &Handle) -> f64 {
+    let text_len = collapse_whitespace(&inner_text(node)).len();
+    if text_len == 0 {
+        return 0.;
+    }
+    let link_text_len = link_text_len(node);
+    link_text_len as f64 / text_len as f64
+}
+
+fn link_text_len(node: &Handle) -> usize {
+    if element(node).is_some_and(|element| element.tag() == "a") {
+        return collapse_whitespace(&inner_text(node)).len();
+    }
+    node.children.borrow().iter().map(link_text_len).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::markdown;
+
+    use super::*;
+
+    fn handlers() -> Vec<TagHandler> {
+        vec![
+            Rc::new(RefCell::new(markdown::ParagraphHandler)),
+            Rc::new(RefCell::new(markdown::HeadingHandler)),
+            Rc::new(RefCell::new(markdown::ListHandler)),
+            Rc::new(RefCell::new(markdown::StyledTextHandler)),
+        ]
+    }
+
+    #[test]
+    fn test_main_content_is_extracted() {
+        let html = indoc! {r#"
+            <html>
+            <head><title>Ownership in Rust | The Blog</title></head>
+            <body>
+            <nav><a href="/">Home</a> <a href="/posts">Posts</a></nav>
+            <div class="sidebar">
+            <p>Subscribe to our newsletter, for the latest posts, delivered weekly.</p>
+            </div>
+            <div class="post-content">
+            <h1>Ownership in Rust</h1>
+            <p>Each value in Rust has an owner, and there can only be one owner at a time.</p>
+            <p>When the owner goes out of scope, the value will be dropped, freeing its memory.</p>
+            <div class="share-buttons"><p>Share this post on all of your favorite networks!</p></div>
+            </div>
+            <div id="comments">
+            <p>Great post, thanks for writing it, it really helped me understand ownership.</p>
+            </div>
+            <footer><p>Copyright 2025, The Blog, all rights reserved, forever and ever.</p></footer>
+            </body>
+            </html>
+        "#};
+
+        assert_eq!(
+            convert_html_to_readable_markdown(html.as_bytes(), &mut handlers()).unwrap(),
+            ReadableContent {
+                title: Some("Ownership in Rust | The Blog".to_string()),
+                markdown: indoc! {"
+                    # Ownership in Rust
+
+                    Each value in Rust has an owner, and there can only be one owner at a time.
+
+                    When the owner goes out of scope, the value will be dropped, freeing its memory."
+                }
+                .to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_whole_document_is_used_without_paragraphs() {
+        let html =
+            "<html><body><h1>Status</h1><ul><li>All systems operational</li></ul></body></html>";
+
+        assert_eq!(
+            convert_html_to_readable_markdown(html.as_bytes(), &mut handlers()).unwrap(),
+            ReadableContent {
+                title: Some("Status".to_string()),
+                markdown: "# Status\n\n- All systems operational".to_string(),
+            }
+        );
+    }
+}
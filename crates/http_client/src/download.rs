@@ -0,0 +1,548 @@
+use crate::{
+    AsyncBody, HttpClient, HttpRequestExt, RedirectPolicy, RequestPriority, StatusCode, http,
+};
+use anyhow::{Context as _, Result, bail};
+use futures::{AsyncReadExt as _, AsyncWriteExt as _, channel::mpsc};
+use sha2::{Digest as _, Sha256};
+use smol::{fs, lock::Semaphore};
+use std::{
+    ffi::OsString,
+    path::{Path, PathBuf},
+    sync::{Arc, LazyLock},
+};
+
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// How many downloads the [global](DownloadManager::global) manager runs at once.
+const MAX_CONCURRENT_DOWNLOADS: usize = 3;
+
+static GLOBAL_PERMITS: LazyLock<Arc<Semaphore>> =
+    LazyLock::new(|| Arc::new(Semaphore::new(MAX_CONCURRENT_DOWNLOADS)));
+
+/// How far along a download is, in bytes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DownloadProgress {
+    pub downloaded: u64,
+    /// The size of the file, when the server reports it.
+    pub total: Option<u64>,
+}
+
+/// A file to download with a [`DownloadManager`].
+pub struct DownloadRequest {
+    pub url: String,
+    pub destination: PathBuf,
+    /// The expected SHA-256 of the file, as a hex string.
+    pub sha256: Option<String>,
+    pub progress: Option<mpsc::UnboundedSender<DownloadProgress>>,
+}
+
+impl DownloadRequest {
+    pub fn new(url: impl Into<String>, destination: impl Into<PathBuf>) -> Self {
+        Self {
+            url: url.into(),
+            destination: destination.into(),
+            sha256: None,
+            progress: None,
+        }
+    }
+
+    pub fn sha256(mut self, sha256: impl Into<String>) -> Self {
+        self.sha256 = Some(sha256.into());
+        self
+    }
+
+    pub fn progress(mut self, progress: mpsc::UnboundedSender<DownloadProgress>) -> Self {
+        self.progress = Some(progress);
+        self
+    }
+}
+
+/// Downloads files to disk with at most a fixed number of downloads in flight.
+///
+/// Bytes are written to a `.partial` file next to the destination, which is
+/// only moved into place once the download completes and its checksum matches.
+/// When a download is interrupted, the next attempt for the same destination
+/// asks the server for the remaining bytes instead of starting over, as long as
+/// the server still serves the same version of the file.
+#[derive(Clone)]
+pub struct DownloadManager {
+    http: Arc<dyn HttpClient>,
+    permits: Arc<Semaphore>,
+}
+
+impl DownloadManager {
+    pub fn new(http: Arc<dyn HttpClient>, max_concurrent_downloads: usize) -> Self {
+        Self {
+            http,
+            permits: Arc::new(Semaphore::new(max_concurrent_downloads.max(1))),
+        }
+    }
+
+    /// Returns a manager that shares its limit on concurrent downloads with every other
+    /// manager returned by this function, so that downloads from different parts of the
+    /// app don't all compete for bandwidth at once.
+    pub fn global(http: Arc<dyn HttpClient>) -> Self {
+        Self {
+            http,
+            permits: GLOBAL_PERMITS.clone(),
+        }
+    }
+
+    pub async fn download(&self, request: DownloadRequest) -> Result<()> {
+        let _permit = self.permits.acquire().await;
+        let partial_path = partial_download_path(&request.destination);
+        let validator_path = validator_path(&partial_path);
+        let result = self
+            .download_to(&request, &partial_path, &validator_path)
+            .await;
+        match result {
+            Ok(()) => {
+                fs::remove_file(&validator_path).await.ok();
+                fs::rename(&partial_path, &request.destination)
+                    .await
+                    .with_context(|| {
+                        format!("moving download to {}", request.destination.display())
+                    })
+            }
+            Err(error) => {
+                // A checksum mismatch means the bytes we kept are bad, so resuming
+                // from them would never succeed.
+                if error.is::<ChecksumMismatch>() {
+                    fs::remove_file(&partial_path).await.ok();
+                    fs::remove_file(&validator_path).await.ok();
+                }
+                Err(error)
+            }
+        }
+    }
+
+    async fn download_to(
+        &self,
+        request: &DownloadRequest,
+        partial_path: &Path,
+        validator_path: &Path,
+    ) -> Result<()> {
+        // Without a validator, there's no telling whether the partial file is of the
+        // version of the file that the server serves now, so it can't be resumed.
+        let validator = fs::read_to_string(validator_path).await.ok();
+        let mut offset = match validator {
+            Some(_) => fs::metadata(partial_path)
+                .await
+                .map_or(0, |metadata| metadata.len()),
+            None => 0,
+        };
+        let mut response = self
+            .send(&request.url, offset, validator.as_deref())
+            .await?;
+        if response.status() == StatusCode::RANGE_NOT_SATISFIABLE {
+            // The partial file doesn't belong to the file being served anymore.
+            offset = 0;
+            response = self.send(&request.url, offset, None).await?;
+        }
+
+        let status = response.status();
+        if !status.is_success() {
+            bail!("downloading {} failed with status {status}", request.url);
+        }
+        // Servers send the whole file when they ignore range requests, or when the file
+        // changed since the partial download started.
+        if status != StatusCode::PARTIAL_CONTENT {
+            offset = 0;
+        }
+        if offset == 0 {
+            if let Some(parent) = partial_path.parent() {
+                fs::create_dir_all(parent).await?;
+            }
+            match response_validator(response.headers()) {
+                Some(validator) => fs::write(validator_path, validator).await?,
+                None => {
+                    fs::remove_file(validator_path).await.ok();
+                }
+            }
+        }
+        let total = total_size(response.headers(), offset);
+
+        let mut hasher = Sha256::new();
+        let mut file = if offset > 0 {
+            hash_file(partial_path, &mut hasher).await?;
+            fs::OpenOptions::new()
+                .append(true)
+                .open(partial_path)
+                .await?
+        } else {
+            fs::File::create(partial_path).await?
+        };
+
+        let mut downloaded = offset;
+        let mut buffer = vec![0; CHUNK_SIZE];
+        let body = response.body_mut();
+        loop {
+            let len = body
+                .read(&mut buffer)
+                .await
+                .with_context(|| format!("reading response of {}", request.url))?;
+            if len == 0 {
+                break;
+            }
+            file.write_all(&buffer[..len]).await?;
+            hasher.update(&buffer[..len]);
+            downloaded += len as u64;
+            if let Some(progress) = &request.progress {
+                progress
+                    .unbounded_send(DownloadProgress { downloaded, total })
+                    .ok();
+            }
+        }
+        file.flush().await?;
+        file.sync_all().await?;
+
+        if let Some(total) = total {
+            if downloaded != total {
+                bail!(
+                    "downloading {} ended after {downloaded} of {total} bytes",
+                    request.url
+                );
+            }
+        }
+        if let Some(expected) = &request.sha256 {
+            let actual = format!("{:x}", hasher.finalize());
+            if !actual.eq_ignore_ascii_case(expected) {
+                return Err(ChecksumMismatch {
+                    expected: expected.clone(),
+                    actual,
+                }
+                .into());
+            }
+        }
+        Ok(())
+    }
+
+    async fn send(
+        &self,
+        url: &str,
+        offset: u64,
+        validator: Option<&str>,
+    ) -> Result<http::Response<AsyncBody>> {
+        let mut request = http::Request::get(url)
+            .follow_redirects(RedirectPolicy::FollowAll)
+            .priority(RequestPriority::Background);
+        if let Some(validator) = validator.filter(|_| offset > 0) {
+            request = request
+                .header(http::header::RANGE, format!("bytes={offset}-"))
+                .header(http::header::IF_RANGE, validator);
+        }
+        self.http
+            .send(request.body(AsyncBody::default())?)
+            .await
+            .with_context(|| format!("downloading {url}"))
+    }
+}
+
+#[derive(Debug)]
+struct ChecksumMismatch {
+    expected: String,
+    actual: String,
+}
+
+impl std::fmt::Display for ChecksumMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "checksum mismatch: expected sha256 {}, got {}",
+            self.expected, self.actual
+        )
+    }
+}
+
+impl std::error::Error for ChecksumMismatch {}
+
+/// The path that the download to `destination` is written to until it completes.
+fn partial_download_path(destination: &Path) -> PathBuf {
+    with_suffix(destination, ".partial")
+}
+
+/// The path where the validator of a partial download is kept, to resume it with `If-Range`.
+fn validator_path(partial_path: &Path) -> PathBuf {
+    with_suffix(partial_path, ".validator")
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut file_name = path.file_name().map(OsString::from).unwrap_or_default();
+    file_name.push(suffix);
+    path.with_file_name(file_name)
+}
+
+/// Returns the value to send as `If-Range` when resuming a download of the response's file.
+/// `If-Range` only accepts strong entity tags, so weak ones fall back to `Last-Modified`.
+fn response_validator(headers: &http::HeaderMap) -> Option<String> {
+    let header = |name: http::HeaderName| headers.get(name)?.to_str().ok();
+    header(http::header::ETAG)
+        .filter(|etag| !etag.starts_with("W/"))
+        .or_else(|| header(http::header::LAST_MODIFIED))
+        .map(ToOwned::to_owned)
+}
+
+async fn hash_file(path: &Path, hasher: &mut Sha256) -> Result<()> {
+    let mut file = fs::File::open(path).await?;
+    let mut buffer = vec![0; CHUNK_SIZE];
+    loop {
+        let len = file.read(&mut buffer).await?;
+        if len == 0 {
+            return Ok(());
+        }
+        hasher.update(&buffer[..len]);
+    }
+}
+
+/// Returns the size of the whole file, given the headers of a response that
+/// starts `offset` bytes into it.
+fn total_size(headers: &http::HeaderMap, offset: u64) -> Option<u64> {
+    if let Some(content_range) = headers.get(http::header::CONTENT_RANGE) {
+        // Formatted as `bytes <start>-<end>/<total>`, where the total may be `*`.
+        let (_, total) = content_range.to_str().ok()?.rsplit_once('/')?;
+        return total.parse().ok();
+    }
+    let content_length = headers
+        .get(http::header::CONTENT_LENGTH)?
+        .to_str()
+        .ok()?
+        .parse::<u64>()
+        .ok()?;
+    Some(offset + content_length)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Request, Response, Url};
+    use futures::{StreamExt as _, future::BoxFuture};
+    use std::any::type_name;
+
+    /// Serves `content`, honoring range requests, but stops after `limit` bytes.
+    struct FileServer {
+        content: Vec<u8>,
+        etag: &'static str,
+        limit: usize,
+    }
+
+    impl HttpClient for FileServer {
+        fn type_name(&self) -> &'static str {
+            type_name::<Self>()
+        }
+
+        fn send(
+            &self,
+            request: Request<AsyncBody>,
+        ) -> BoxFuture<'static, Result<Response<AsyncBody>>> {
+            // Like real servers, ignore the range when the file changed.
+            let if_range = request.headers().get(http::header::IF_RANGE);
+            let start = request
+                .headers()
+                .get(http::header::RANGE)
+                .filter(|_| if_range.is_none_or(|if_range| if_range == self.etag))
+                .and_then(|range| range.to_str().ok())
+                .and_then(|range| range.strip_prefix("bytes="))
+                .and_then(|range| range.strip_suffix('-'))
+                .and_then(|start| start.parse::<usize>().ok());
+            let (status, body_start) = match start {
+                Some(start) if start >= self.content.len() => {
+                    (StatusCode::RANGE_NOT_SATISFIABLE, self.content.len())
+                }
+                Some(start) => (StatusCode::PARTIAL_CONTENT, start),
+                None => (StatusCode::OK, 0),
+            };
+            let mut response = Response::builder()
+                .status(status)
+                .header(http::header::ETAG, self.etag);
+            if status == StatusCode::PARTIAL_CONTENT {
+                response = response.header(
+                    http::header::CONTENT_RANGE,
+                    format!(
+                        "bytes {body_start}-{}/{}",
+                        self.content.len() - 1,
+                        self.content.len()
+                    ),
+                );
+            } else {
+                response =
+                    response.header(http::header::CONTENT_LENGTH, self.content.len().to_string());
+            }
+            let body_end = self.content.len().min(body_start + self.limit);
+            let body = self.content[body_start..body_end].to_vec();
+            Box::pin(async move { Ok(response.body(body.into())?) })
+        }
+
+        fn proxy(&self) -> Option<&Url> {
+            None
+        }
+    }
+
+    #[test]
+    fn test_resumed_download() {
+        let dir = tempfile::tempdir().unwrap();
+        let destination = dir.path().join("archive.tar.gz");
+        let content = (0..200u8).collect::<Vec<_>>();
+        let sha256 = format!("{:x}", Sha256::digest(&content));
+
+        smol::block_on(async {
+            let interrupted = DownloadManager::new(
+                Arc::new(FileServer {
+                    content: content.clone(),
+                    etag: "\"v1\"",
+                    limit: 120,
+                }),
+                1,
+            );
+            let error = interrupted
+                .download(DownloadRequest::new(
+                    "https://example.com/archive",
+                    &destination,
+                ))
+                .await
+                .unwrap_err();
+            assert!(error.to_string().contains("120 of 200"), "{error}");
+            assert!(!destination.exists());
+            assert_eq!(
+                fs::read(partial_download_path(&destination))
+                    .await
+                    .unwrap()
+                    .len(),
+                120
+            );
+
+            let (progress_tx, progress_rx) = mpsc::unbounded();
+            let manager = DownloadManager::new(
+                Arc::new(FileServer {
+                    content: content.clone(),
+                    etag: "\"v1\"",
+                    limit: usize::MAX,
+                }),
+                1,
+            );
+            manager
+                .download(
+                    DownloadRequest::new("https://example.com/archive", &destination)
+                        .sha256(sha256)
+                        .progress(progress_tx),
+                )
+                .await
+                .unwrap();
+            assert_eq!(fs::read(&destination).await.unwrap(), content);
+            assert!(!partial_download_path(&destination).exists());
+            assert_eq!(
+                progress_rx.collect::<Vec<_>>().await,
+                vec![DownloadProgress {
+                    downloaded: 200,
+                    total: Some(200)
+                }]
+            );
+        });
+    }
+
+    #[test]
+    fn test_download_restarts_when_file_changed() {
+        let dir = tempfile::tempdir().unwrap();
+        let destination = dir.path().join("archive.tar.gz");
+        let old_content = vec![1; 200];
+        let new_content = vec![2; 150];
+
+        smol::block_on(async {
+            DownloadManager::new(
+                Arc::new(FileServer {
+                    content: old_content,
+                    etag: "\"v1\"",
+                    limit: 120,
+                }),
+                1,
+            )
+            .download(DownloadRequest::new(
+                "https://example.com/archive",
+                &destination,
+            ))
+            .await
+            .unwrap_err();
+
+            DownloadManager::new(
+                Arc::new(FileServer {
+                    content: new_content.clone(),
+                    etag: "\"v2\"",
+                    limit: usize::MAX,
+                }),
+                1,
+            )
+            .download(
+                DownloadRequest::new("https://example.com/archive", &destination)
+                    .sha256(format!("{:x}", Sha256::digest(&new_content))),
+            )
+            .await
+            .unwrap();
+            assert_eq!(fs::read(&destination).await.unwrap(), new_content);
+        });
+        assert!(!partial_download_path(&destination).exists());
+        assert!(!validator_path(&partial_download_path(&destination)).exists());
+    }
+
+    #[test]
+    fn test_response_validator() {
+        let mut headers = http::HeaderMap::new();
+        assert_eq!(response_validator(&headers), None);
+        headers.insert(
+            http::header::LAST_MODIFIED,
+            "Wed, 21 Oct 2015 07:28:00 GMT".parse().unwrap(),
+        );
+        headers.insert(http::header::ETAG, "W/\"weak\"".parse().unwrap());
+        assert_eq!(
+            response_validator(&headers).as_deref(),
+            Some("Wed, 21 Oct 2015 07:28:00 GMT")
+        );
+        headers.insert(http::header::ETAG, "\"strong\"".parse().unwrap());
+        assert_eq!(response_validator(&headers).as_deref(), Some("\"strong\""));
+    }
+
+    #[test]
+    fn test_checksum_mismatch() {
+        let dir = tempfile::tempdir().unwrap();
+        let destination = dir.path().join("server");
+        let manager = DownloadManager::new(
+            Arc::new(FileServer {
+                content: b"binary".to_vec(),
+                etag: "\"v1\"",
+                limit: usize::MAX,
+            }),
+            1,
+        );
+
+        smol::block_on(async {
+            let error = manager
+                .download(
+                    DownloadRequest::new("https://example.com/server", &destination)
+                        .sha256(format!("{:x}", Sha256::digest(b"other binary"))),
+                )
+                .await
+                .unwrap_err();
+            assert!(error.is::<ChecksumMismatch>(), "{error}");
+        });
+        assert!(!destination.exists());
+        assert!(!partial_download_path(&destination).exists());
+    }
+
+    #[test]
+    fn test_total_size() {
+        let mut headers = http::HeaderMap::new();
+        headers.insert(http::header::CONTENT_LENGTH, "100".parse().unwrap());
+        assert_eq!(total_size(&headers, 0), Some(100));
+        assert_eq!(total_size(&headers, 20), Some(120));
+
+        headers.insert(
+            http::header::CONTENT_RANGE,
+            "bytes 20-119/120".parse().unwrap(),
+        );
+        assert_eq!(total_size(&headers, 20), Some(120));
+
+        headers.insert(
+            http::header::CONTENT_RANGE,
+            "bytes 20-119/*".parse().unwrap(),
+        );
+        assert_eq!(total_size(&headers, 20), None);
+    }
+}
@@ -1,12 +1,15 @@
 mod async_body;
+pub mod download;
 pub mod github;
 
 pub use anyhow::{Result, anyhow};
 pub use async_body::{AsyncBody, Inner};
+pub use async_tungstenite::tungstenite::{Error as WebSocketError, Message as WebSocketMessage};
 use derive_more::Deref;
+pub use download::{DownloadManager, DownloadProgress, DownloadRequest};
 pub use http::{self, Method, Request, Response, StatusCode, Uri};
 
-use futures::future::BoxFuture;
+use futures::{Sink, Stream, future::BoxFuture};
 use http::request::Builder;
 #[cfg(feature = "test-support")]
 use std::fmt;
@@ -25,17 +28,58 @@ pub enum RedirectPolicy {
 }
 pub struct FollowRedirects(pub bool);
 
+/// How urgently the response to a request is needed. Clients use it to keep large transfers
+/// that nobody is waiting on from slowing down the requests that someone is.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RequestPriority {
+    /// The user is waiting for the response, as with completions and edit predictions.
+    Interactive,
+    #[default]
+    Normal,
+    /// The response can take as long as it needs, as with extension and model downloads.
+    Background,
+}
+
 pub trait HttpRequestExt {
     /// Whether or not to follow redirects
     fn follow_redirects(self, follow: RedirectPolicy) -> Self;
+    /// How urgently the response is needed
+    fn priority(self, priority: RequestPriority) -> Self;
 }
 
 impl HttpRequestExt for http::request::Builder {
     fn follow_redirects(self, follow: RedirectPolicy) -> Self {
         self.extension(follow)
     }
+
+    fn priority(self, priority: RequestPriority) -> Self {
+        self.extension(priority)
+    }
+}
+
+/// An open WebSocket connection. Errors from the underlying connection are
+/// [`WebSocketError`]s, so callers can downcast them to inspect the handshake
+/// response.
+pub trait WebSocketStream:
+    Stream<Item = anyhow::Result<WebSocketMessage>>
+    + Sink<WebSocketMessage, Error = anyhow::Error>
+    + Send
+    + Unpin
+    + 'static
+{
+}
+
+impl<T> WebSocketStream for T where
+    T: Stream<Item = anyhow::Result<WebSocketMessage>>
+        + Sink<WebSocketMessage, Error = anyhow::Error>
+        + Send
+        + Unpin
+        + 'static
+{
 }
 
+pub type WebSocket = Box<dyn WebSocketStream>;
+
 pub trait HttpClient: 'static + Send + Sync {
     fn type_name(&self) -> &'static str;
 
@@ -44,6 +88,30 @@ pub trait HttpClient: 'static + Send + Sync {
         req: http::Request<AsyncBody>,
     ) -> BoxFuture<'static, anyhow::Result<Response<AsyncBody>>>;
 
+    /// Opens a WebSocket connection to a `ws://` or `wss://` URL, sending
+    /// `headers` with the handshake request.
+    fn connect_websocket(
+        &self,
+        _url: &Url,
+        _headers: http::HeaderMap,
+    ) -> BoxFuture<'static, anyhow::Result<WebSocket>> {
+        let type_name = self.type_name();
+        Box::pin(async move {
+            Err(anyhow!(
+                "{type_name} does not support WebSocket connections"
+            ))
+        })
+    }
+
+    /// Connects to `url`'s host ahead of time, so that the next [`RequestPriority::Interactive`]
+    /// request to it can reuse the connection instead of waiting for DNS resolution and the TCP
+    /// and TLS handshakes.
+    ///
+    /// Clients that don't pool connections treat this as a no-op.
+    fn warm_up(&self, _url: &Url) -> BoxFuture<'static, anyhow::Result<()>> {
+        Box::pin(async { Ok(()) })
+    }
+
     fn get<'a>(
         &'a self,
         uri: &str,
@@ -118,6 +186,18 @@ impl HttpClient for HttpClientWithProxy {
         self.client.send(req)
     }
 
+    fn connect_websocket(
+        &self,
+        url: &Url,
+        headers: http::HeaderMap,
+    ) -> BoxFuture<'static, anyhow::Result<WebSocket>> {
+        self.client.connect_websocket(url, headers)
+    }
+
+    fn warm_up(&self, url: &Url) -> BoxFuture<'static, anyhow::Result<()>> {
+        self.client.warm_up(url)
+    }
+
     fn proxy(&self) -> Option<&Url> {
         self.proxy.as_ref()
     }
@@ -135,6 +215,18 @@ impl HttpClient for Arc<HttpClientWithProxy> {
         self.client.send(req)
     }
 
+    fn connect_websocket(
+        &self,
+        url: &Url,
+        headers: http::HeaderMap,
+    ) -> BoxFuture<'static, anyhow::Result<WebSocket>> {
+        self.client.connect_websocket(url, headers)
+    }
+
+    fn warm_up(&self, url: &Url) -> BoxFuture<'static, anyhow::Result<()>> {
+        self.client.warm_up(url)
+    }
+
     fn proxy(&self) -> Option<&Url> {
         self.proxy.as_ref()
     }
@@ -250,6 +342,18 @@ impl HttpClient for Arc<HttpClientWithUrl> {
         self.client.send(req)
     }
 
+    fn connect_websocket(
+        &self,
+        url: &Url,
+        headers: http::HeaderMap,
+    ) -> BoxFuture<'static, anyhow::Result<WebSocket>> {
+        self.client.connect_websocket(url, headers)
+    }
+
+    fn warm_up(&self, url: &Url) -> BoxFuture<'static, anyhow::Result<()>> {
+        self.client.warm_up(url)
+    }
+
     fn proxy(&self) -> Option<&Url> {
         self.client.proxy.as_ref()
     }
@@ -267,6 +371,18 @@ impl HttpClient for HttpClientWithUrl {
         self.client.send(req)
     }
 
+    fn connect_websocket(
+        &self,
+        url: &Url,
+        headers: http::HeaderMap,
+    ) -> BoxFuture<'static, anyhow::Result<WebSocket>> {
+        self.client.connect_websocket(url, headers)
+    }
+
+    fn warm_up(&self, url: &Url) -> BoxFuture<'static, anyhow::Result<()>> {
+        self.client.warm_up(url)
+    }
+
     fn proxy(&self) -> Option<&Url> {
         self.client.proxy.as_ref()
     }
@@ -17,7 +17,9 @@ use async_trait::async_trait;
 use collections::{HashSet, VecDeque};
 use fs::Fs;
 use futures::{AsyncReadExt, FutureExt};
-use http_client::{AsyncBody, HttpClient, HttpClientWithUrl};
+use http_client::{
+    AsyncBody, HttpClient, HttpClientWithUrl, HttpRequestExt as _, RedirectPolicy, RequestPriority,
+};
 
 use crate::{IndexedDocsDatabase, IndexedDocsProvider, PackageName, ProviderId};
 
@@ -174,13 +176,11 @@ impl IndexedDocsProvider for DocsDotRsProvider {
                             .unwrap_or_default()
                     );
 
-                    let mut response = http_client
-                        .get(
-                            &format!("https://docs.rs/{path}"),
-                            AsyncBody::default(),
-                            true,
-                        )
-                        .await?;
+                    let request = http_client::Request::get(format!("https://docs.rs/{path}"))
+                        .follow_redirects(RedirectPolicy::FollowAll)
+                        .priority(RequestPriority::Background)
+                        .body(AsyncBody::default())?;
+                    let mut response = http_client.send(request).await?;
 
                     let mut body = Vec::new();
                     response
@@ -34,6 +34,30 @@ pub fn line_diff(old_text: &str, new_text: &str) -> Vec<(Range<u32>, Range<u32>)
     edits
 }
 
+/// Like [`line_diff`], but lines that only differ in whitespace are considered
+/// equal, as with `git diff --ignore-all-space`.
+pub fn line_diff_ignoring_whitespace(
+    old_text: &str,
+    new_text: &str,
+) -> Vec<(Range<u32>, Range<u32>)> {
+    let mut edits = Vec::new();
+    let mut input = InternedInput::default();
+    input.update_before(lines_without_whitespace(old_text));
+    input.update_after(lines_without_whitespace(new_text));
+    diff(
+        Algorithm::Histogram,
+        &input,
+        |old_rows: Range<u32>, new_rows: Range<u32>| {
+            edits.push((old_rows, new_rows));
+        },
+    );
+    edits
+}
+
+fn lines_without_whitespace(text: &str) -> impl Iterator<Item = String> + '_ {
+    lines_with_terminator(text).map(|line| line.split_whitespace().collect())
+}
+
 /// Computes a diff between two strings, returning a vector of edits.
 ///
 /// The edits are represented as tuples of byte ranges and replacement strings.
@@ -278,6 +302,17 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_line_diff_ignoring_whitespace() {
+        let old_text = "one\n  two\nthree\nfour\n";
+        let new_text = "one\ntwo  \nTHREE\n\tfour\n";
+        assert_eq!(line_diff(old_text, new_text).len(), 1);
+        assert_eq!(
+            line_diff_ignoring_whitespace(old_text, new_text),
+            [(2..3, 2..3)]
+        );
+    }
+
     #[test]
     fn test_apply_diff_patch() {
         let old_text = "one two\nthree four five\nsix seven eight nine\nten\n";
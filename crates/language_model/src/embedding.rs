@@ -0,0 +1,151 @@
+use std::fmt;
+
+use anyhow::Result;
+use futures::{FutureExt as _, future::BoxFuture};
+use serde::{Deserialize, Serialize};
+
+use crate::{LanguageModelId, LanguageModelName, LanguageModelProviderId};
+
+/// A model that converts text into vectors whose similarity reflects the similarity
+/// of the texts' meaning.
+pub trait EmbeddingModel: Send + Sync {
+    fn id(&self) -> LanguageModelId;
+    fn name(&self) -> LanguageModelName;
+    fn provider_id(&self) -> LanguageModelProviderId;
+
+    /// The maximum number of texts that can be embedded in a single request.
+    fn max_batch_size(&self) -> usize;
+
+    /// Embeds at most [`Self::max_batch_size`] texts in a single request.
+    fn embed_batch(&self, texts: Vec<String>) -> BoxFuture<'static, Result<Vec<Embedding>>>;
+}
+
+impl dyn EmbeddingModel {
+    /// Embeds any number of texts, splitting them into as many requests as the
+    /// model's batch size requires.
+    ///
+    /// Requests are subject to the rate limits of the model's provider.
+    pub fn embed(&self, texts: Vec<String>) -> BoxFuture<'static, Result<Vec<Embedding>>> {
+        let batches = texts
+            .chunks(self.max_batch_size().max(1))
+            .map(|batch| self.embed_batch(batch.to_vec()))
+            .collect::<Vec<_>>();
+        async move {
+            let embeddings = futures::future::try_join_all(batches).await?;
+            Ok(embeddings.into_iter().flatten().collect())
+        }
+        .boxed()
+    }
+}
+
+/// A normalized embedding vector.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Embedding(Vec<f32>);
+
+impl Embedding {
+    pub fn new(mut embedding: Vec<f32>) -> Self {
+        let len = embedding.len();
+        let mut norm = 0f32;
+
+        for i in 0..len {
+            norm += embedding[i] * embedding[i];
+        }
+
+        norm = norm.sqrt();
+        for dimension in &mut embedding {
+            *dimension /= norm;
+        }
+
+        Self(embedding)
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn similarity(&self, others: &[Embedding]) -> (f32, usize) {
+        debug_assert!(others.iter().all(|other| self.0.len() == other.0.len()));
+        others
+            .iter()
+            .enumerate()
+            .map(|(index, other)| {
+                let dot_product: f32 = self
+                    .0
+                    .iter()
+                    .copied()
+                    .zip(other.0.iter().copied())
+                    .map(|(a, b)| a * b)
+                    .sum();
+                (dot_product, index)
+            })
+            .max_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal))
+            .unwrap_or((0.0, 0))
+    }
+}
+
+impl fmt::Display for Embedding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits_to_display = 3;
+
+        // Start the Embedding display format
+        write!(f, "Embedding(sized: {}; values: [", self.len())?;
+
+        for (index, value) in self.0.iter().enumerate().take(digits_to_display) {
+            // Lead with comma if not the first element
+            if index != 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{:.3}", value)?;
+        }
+        if self.len() > digits_to_display {
+            write!(f, "...")?;
+        }
+        write!(f, "])")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_provider::FakeEmbeddingModel;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_normalize_embedding() {
+        let normalized = Embedding::new(vec![1.0, 1.0, 1.0]);
+        let value: f32 = 1.0 / 3.0_f32.sqrt();
+        assert_eq!(normalized, Embedding(vec![value; 3]));
+    }
+
+    #[test]
+    fn test_embed_in_batches() {
+        let model = Arc::new(FakeEmbeddingModel {
+            max_batch_size: 2,
+            batches: Default::default(),
+        });
+        let texts = ["a", "bb", "ccc", "dddd", "eeeee"].map(str::to_string);
+
+        let embedding_model: Arc<dyn EmbeddingModel> = model.clone();
+        let embeddings = smol::block_on(embedding_model.embed(texts.to_vec())).unwrap();
+
+        assert_eq!(
+            embeddings,
+            texts
+                .iter()
+                .map(|text| Embedding::new(vec![text.len() as f32, 1.]))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            *model.batches.lock(),
+            [
+                vec!["a".to_string(), "bb".to_string()],
+                vec!["ccc".to_string(), "dddd".to_string()],
+                vec!["eeeee".to_string()],
+            ]
+        );
+    }
+}
@@ -1,8 +1,8 @@
 use crate::{
-    AuthenticateError, LanguageModel, LanguageModelCompletionError, LanguageModelCompletionEvent,
-    LanguageModelId, LanguageModelName, LanguageModelProvider, LanguageModelProviderId,
-    LanguageModelProviderName, LanguageModelProviderState, LanguageModelRequest,
-    LanguageModelToolChoice,
+    AuthenticateError, Embedding, EmbeddingModel, LanguageModel, LanguageModelCompletionError,
+    LanguageModelCompletionEvent, LanguageModelId, LanguageModelName, LanguageModelProvider,
+    LanguageModelProviderId, LanguageModelProviderName, LanguageModelProviderState,
+    LanguageModelRequest, LanguageModelToolChoice,
 };
 use futures::{FutureExt, StreamExt, channel::mpsc, future::BoxFuture, stream::BoxStream};
 use gpui::{AnyView, App, AsyncApp, Entity, Task, Window};
@@ -58,6 +58,13 @@ impl LanguageModelProvider for FakeLanguageModelProvider {
         vec![Arc::new(FakeLanguageModel::default())]
     }
 
+    fn provided_embedding_models(&self, _: &App) -> Vec<Arc<dyn EmbeddingModel>> {
+        vec![Arc::new(FakeEmbeddingModel {
+            max_batch_size: 16,
+            batches: Default::default(),
+        })]
+    }
+
     fn is_authenticated(&self, _: &App) -> bool {
         true
     }
@@ -201,3 +208,36 @@ impl LanguageModel for FakeLanguageModel {
         self
     }
 }
+
+#[derive(Default)]
+pub struct FakeEmbeddingModel {
+    pub max_batch_size: usize,
+    pub batches: Mutex<Vec<Vec<String>>>,
+}
+
+impl EmbeddingModel for FakeEmbeddingModel {
+    fn id(&self) -> LanguageModelId {
+        LanguageModelId::from("fake-embedding".to_string())
+    }
+
+    fn name(&self) -> LanguageModelName {
+        LanguageModelName::from("Fake Embedding".to_string())
+    }
+
+    fn provider_id(&self) -> LanguageModelProviderId {
+        provider_id()
+    }
+
+    fn max_batch_size(&self) -> usize {
+        self.max_batch_size
+    }
+
+    fn embed_batch(&self, texts: Vec<String>) -> BoxFuture<'static, Result<Vec<Embedding>>> {
+        let embeddings = texts
+            .iter()
+            .map(|text| Embedding::new(vec![text.len() as f32, 1.]))
+            .collect();
+        self.batches.lock().push(texts);
+        futures::future::ready(Ok(embeddings)).boxed()
+    }
+}
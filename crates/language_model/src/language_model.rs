@@ -1,8 +1,11 @@
+mod embedding;
 mod model;
 mod rate_limiter;
 mod registry;
 mod request;
+mod request_log;
 mod role;
+mod structured_output;
 mod telemetry;
 
 #[cfg(any(test, feature = "test-support"))]
@@ -13,6 +16,7 @@ use client::Client;
 use futures::FutureExt;
 use futures::{StreamExt, future::BoxFuture, stream::BoxStream};
 use gpui::{AnyElement, AnyView, App, AsyncApp, SharedString, Task, Window};
+use http_client::Url;
 use http_client::http::{HeaderMap, HeaderValue};
 use icons::IconName;
 use parking_lot::Mutex;
@@ -29,11 +33,14 @@ use zed_llm_client::{
     MODEL_REQUESTS_USAGE_LIMIT_HEADER_NAME, UsageLimit,
 };
 
+pub use crate::embedding::*;
 pub use crate::model::*;
 pub use crate::rate_limiter::*;
 pub use crate::registry::*;
 pub use crate::request::*;
+pub use crate::request_log::*;
 pub use crate::role::*;
+pub use crate::structured_output::*;
 pub use crate::telemetry::*;
 
 pub const ZED_CLOUD_PROVIDER_ID: &str = "zed.dev";
@@ -247,6 +254,11 @@ pub trait LanguageModel: Send + Sync {
         false
     }
 
+    /// Whether this model can constrain its output to a JSON schema.
+    fn supports_structured_output(&self) -> bool {
+        false
+    }
+
     fn tool_input_format(&self) -> LanguageModelToolSchemaFormat {
         LanguageModelToolSchemaFormat::JsonSchema
     }
@@ -374,6 +386,9 @@ pub trait LanguageModelProvider: 'static {
     fn recommended_models(&self, _cx: &App) -> Vec<Arc<dyn LanguageModel>> {
         Vec::new()
     }
+    fn provided_embedding_models(&self, _cx: &App) -> Vec<Arc<dyn EmbeddingModel>> {
+        Vec::new()
+    }
     fn is_authenticated(&self, cx: &App) -> bool;
     fn authenticate(&self, cx: &mut App) -> Task<Result<(), AuthenticateError>>;
     fn configuration_view(&self, window: &mut Window, cx: &mut App) -> AnyView;
@@ -388,6 +403,17 @@ pub trait LanguageModelProvider: 'static {
         None
     }
     fn reset_credentials(&self, cx: &mut App) -> Task<Result<()>>;
+    /// The URL that requests to this provider's models are sent to, if they're sent over HTTP.
+    fn api_url(&self, _cx: &App) -> Option<Url> {
+        None
+    }
+    /// Transcribes WAV-encoded speech into text, for providers that offer speech-to-text.
+    fn transcribe(&self, _wav: Vec<u8>, _cx: &App) -> Task<Result<String>> {
+        Task::ready(Err(anyhow::anyhow!(
+            "{} doesn't support speech-to-text",
+            self.name().0
+        )))
+    }
 }
 
 #[derive(PartialEq, Eq)]
@@ -1,21 +1,183 @@
 use anyhow::Result;
+use collections::{HashMap, VecDeque};
 use futures::Stream;
-use smol::lock::{Semaphore, SemaphoreGuardArc};
+use parking_lot::Mutex;
+use smol::Timer;
 use std::{
     future::Future,
     pin::Pin,
-    sync::Arc,
-    task::{Context, Poll},
+    sync::{Arc, LazyLock},
+    task::{Context, Poll, Waker},
+    time::{Duration, Instant},
 };
 
+use crate::LanguageModelProviderId;
+
+const RATE_LIMIT_WINDOW: Duration = Duration::from_secs(60);
+
+/// Limits applied to all requests made to a single language model provider.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RequestLimits {
+    /// The maximum number of requests that may be in flight at once.
+    pub max_concurrent_requests: usize,
+    /// The maximum number of requests that may be started within a minute.
+    pub requests_per_minute: Option<usize>,
+}
+
+impl Default for RequestLimits {
+    fn default() -> Self {
+        Self {
+            max_concurrent_requests: 4,
+            requests_per_minute: None,
+        }
+    }
+}
+
+#[derive(Default)]
+struct ProviderRateLimiters {
+    limits: HashMap<LanguageModelProviderId, RequestLimits>,
+    limiters: HashMap<LanguageModelProviderId, RateLimiter>,
+    embedding_limiters: HashMap<LanguageModelProviderId, RateLimiter>,
+}
+
+static PROVIDER_RATE_LIMITERS: LazyLock<Mutex<ProviderRateLimiters>> =
+    LazyLock::new(Default::default);
+
+/// Identifies a request queued by a [`RateLimiter`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct QueuedRequestId(usize);
+
+/// Queues requests so that no more than the configured number of requests are
+/// in flight, or started within the last minute, at any given time.
+///
+/// Requests are started in the order in which they were queued.
 #[derive(Clone)]
 pub struct RateLimiter {
-    semaphore: Arc<Semaphore>,
+    state: Arc<Mutex<RateLimiterState>>,
+}
+
+struct RateLimiterState {
+    limits: RequestLimits,
+    in_flight: usize,
+    queue: VecDeque<usize>,
+    next_ticket: usize,
+    /// The tickets of the requests started within the last minute, and when they started.
+    recent_starts: VecDeque<(usize, Instant)>,
+    wakers: Vec<Waker>,
+}
+
+enum StartRequest {
+    Started,
+    Wait,
+    WaitUntil(Instant),
+}
+
+impl RateLimiterState {
+    fn try_start(&mut self, ticket: usize, now: Instant) -> StartRequest {
+        if self.queue.front() != Some(&ticket)
+            || self.in_flight >= self.limits.max_concurrent_requests.max(1)
+        {
+            return StartRequest::Wait;
+        }
+
+        while self
+            .recent_starts
+            .front()
+            .is_some_and(|(_, start)| now.duration_since(*start) >= RATE_LIMIT_WINDOW)
+        {
+            self.recent_starts.pop_front();
+        }
+
+        if let Some(requests_per_minute) = self.limits.requests_per_minute {
+            if self.recent_starts.len() >= requests_per_minute.max(1) {
+                if let Some((_, oldest_start)) = self.recent_starts.front() {
+                    return StartRequest::WaitUntil(*oldest_start + RATE_LIMIT_WINDOW);
+                }
+            }
+        }
+
+        self.queue.pop_front();
+        self.in_flight += 1;
+        self.recent_starts.push_back((ticket, now));
+        // The next request in the queue may be able to start right away.
+        self.wake_all();
+        StartRequest::Started
+    }
+
+    fn wake_all(&mut self) {
+        for waker in self.wakers.drain(..) {
+            waker.wake();
+        }
+    }
+}
+
+/// Keeps a request's slot in the [`RateLimiter`] occupied until dropped.
+struct RequestSlot {
+    state: Arc<Mutex<RateLimiterState>>,
+}
+
+impl Drop for RequestSlot {
+    fn drop(&mut self) {
+        let mut state = self.state.lock();
+        state.in_flight = state.in_flight.saturating_sub(1);
+        state.wake_all();
+    }
+}
+
+struct QueuedRequest {
+    state: Arc<Mutex<RateLimiterState>>,
+    ticket: usize,
+    started: bool,
+    timer: Option<Timer>,
+}
+
+impl Future for QueuedRequest {
+    type Output = RequestSlot;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        loop {
+            let mut state = this.state.lock();
+            match state.try_start(this.ticket, Instant::now()) {
+                StartRequest::Started => {
+                    this.started = true;
+                    return Poll::Ready(RequestSlot {
+                        state: this.state.clone(),
+                    });
+                }
+                StartRequest::Wait => {
+                    this.timer = None;
+                    state.wakers.push(cx.waker().clone());
+                    return Poll::Pending;
+                }
+                StartRequest::WaitUntil(instant) => {
+                    state.wakers.push(cx.waker().clone());
+                    drop(state);
+                    let timer = this.timer.get_or_insert_with(|| Timer::at(instant));
+                    timer.set_at(instant);
+                    if Pin::new(timer).poll(cx).is_pending() {
+                        return Poll::Pending;
+                    }
+                    this.timer = None;
+                }
+            }
+        }
+    }
+}
+
+impl Drop for QueuedRequest {
+    fn drop(&mut self) {
+        if !self.started {
+            let mut state = self.state.lock();
+            state.queue.retain(|ticket| *ticket != self.ticket);
+            state.wake_all();
+        }
+    }
 }
 
 pub struct RateLimitGuard<T> {
     inner: T,
-    _guard: SemaphoreGuardArc,
+    _slot: RequestSlot,
 }
 
 impl<T> Stream for RateLimitGuard<T>
@@ -31,8 +193,121 @@ where
 
 impl RateLimiter {
     pub fn new(limit: usize) -> Self {
+        Self::with_limits(RequestLimits {
+            max_concurrent_requests: limit,
+            requests_per_minute: None,
+        })
+    }
+
+    pub fn with_limits(limits: RequestLimits) -> Self {
         Self {
-            semaphore: Arc::new(Semaphore::new(limit)),
+            state: Arc::new(Mutex::new(RateLimiterState {
+                limits,
+                in_flight: 0,
+                queue: VecDeque::default(),
+                next_ticket: 0,
+                recent_starts: VecDeque::default(),
+                wakers: Vec::new(),
+            })),
+        }
+    }
+
+    /// Returns the rate limiter shared by every request made to the given provider.
+    pub fn for_provider(provider_id: &LanguageModelProviderId) -> Self {
+        let mut registry = PROVIDER_RATE_LIMITERS.lock();
+        let limits = registry
+            .limits
+            .get(provider_id)
+            .copied()
+            .unwrap_or_default();
+        registry
+            .limiters
+            .entry(provider_id.clone())
+            .or_insert_with(|| Self::with_limits(limits))
+            .clone()
+    }
+
+    /// Returns the rate limiter shared by every embedding request made to the given provider.
+    ///
+    /// Embeddings are queued separately from completions, so that indexing a project doesn't
+    /// hold up the requests the user is waiting on. Both are subject to the provider's limits.
+    pub fn for_provider_embeddings(provider_id: &LanguageModelProviderId) -> Self {
+        let mut registry = PROVIDER_RATE_LIMITERS.lock();
+        let limits = registry
+            .limits
+            .get(provider_id)
+            .copied()
+            .unwrap_or_default();
+        registry
+            .embedding_limiters
+            .entry(provider_id.clone())
+            .or_insert_with(|| Self::with_limits(limits))
+            .clone()
+    }
+
+    /// Replaces the limits of every provider's rate limiters. Providers not
+    /// present in `limits` fall back to [`RequestLimits::default`].
+    pub fn set_provider_limits(limits: HashMap<LanguageModelProviderId, RequestLimits>) {
+        let mut registry = PROVIDER_RATE_LIMITERS.lock();
+        for (provider_id, limiter) in registry.limiters.iter().chain(&registry.embedding_limiters) {
+            limiter.set_limits(limits.get(provider_id).copied().unwrap_or_default());
+        }
+        registry.limits = limits;
+    }
+
+    pub fn set_limits(&self, limits: RequestLimits) {
+        let mut state = self.state.lock();
+        if state.limits != limits {
+            state.limits = limits;
+            state.wake_all();
+        }
+    }
+
+    /// The number of requests waiting for a slot to become available.
+    pub fn queued_requests(&self) -> usize {
+        self.state.lock().queue.len()
+    }
+
+    /// Returns the id the next request queued by this limiter will get.
+    ///
+    /// Providers queue a request as soon as its completion is requested, so callers can take
+    /// the id right before requesting a completion to follow their request through the queue.
+    pub fn next_request_id(&self) -> QueuedRequestId {
+        QueuedRequestId(self.state.lock().next_ticket)
+    }
+
+    /// Returns the position of a request among the requests waiting for a slot, starting at 1,
+    /// or `None` if the request has already started or isn't queued.
+    pub fn queue_position(&self, id: QueuedRequestId) -> Option<usize> {
+        self.state
+            .lock()
+            .queue
+            .iter()
+            .position(|ticket| *ticket == id.0)
+            .map(|ix| ix + 1)
+    }
+
+    /// Returns when a request got its slot and was sent, or `None` if it hasn't started yet
+    /// or started more than a minute ago.
+    pub fn started_at(&self, id: QueuedRequestId) -> Option<Instant> {
+        self.state
+            .lock()
+            .recent_starts
+            .iter()
+            .find(|(ticket, _)| *ticket == id.0)
+            .map(|(_, started_at)| *started_at)
+    }
+
+    fn enqueue(&self) -> QueuedRequest {
+        let mut state = self.state.lock();
+        let ticket = state.next_ticket;
+        state.next_ticket += 1;
+        state.queue.push_back(ticket);
+        QueuedRequest {
+            state: self.state.clone(),
+            ticket,
+            started: false,
+            timer: None,
         }
     }
 
@@ -40,11 +315,11 @@ impl RateLimiter {
     where
         Fut: 'a + Future<Output = Result<T>>,
     {
-        let guard = self.semaphore.acquire_arc();
+        let slot = self.enqueue();
         async move {
-            let guard = guard.await;
+            let slot = slot.await;
             let result = future.await?;
-            drop(guard);
+            drop(slot);
             Ok(result)
         }
     }
@@ -57,14 +332,149 @@ impl RateLimiter {
         Fut: 'a + Future<Output = Result<T>>,
         T: Stream,
     {
-        let guard = self.semaphore.acquire_arc();
+        let slot = self.enqueue();
         async move {
-            let guard = guard.await;
+            let slot = slot.await;
             let inner = future.await?;
-            Ok(RateLimitGuard {
-                inner,
-                _guard: guard,
-            })
+            Ok(RateLimitGuard { inner, _slot: slot })
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::{FutureExt, channel::oneshot};
+
+    #[test]
+    fn test_max_concurrent_requests() {
+        let limiter = RateLimiter::new(1);
+        let (first_tx, first_rx) = oneshot::channel::<()>();
+        let mut first = limiter
+            .run(async move {
+                first_rx.await.ok();
+                Ok(1)
+            })
+            .boxed_local();
+        let mut second = limiter.run(async { Ok(2) }).boxed_local();
+
+        assert!((&mut first).now_or_never().is_none());
+        assert!((&mut second).now_or_never().is_none());
+        assert_eq!(limiter.queued_requests(), 1);
+
+        first_tx.send(()).ok();
+        assert_eq!(first.now_or_never().and_then(Result::ok), Some(1));
+        assert_eq!(limiter.queued_requests(), 1);
+        assert_eq!(second.now_or_never().and_then(Result::ok), Some(2));
+        assert_eq!(limiter.queued_requests(), 0);
+    }
+
+    #[test]
+    fn test_requests_per_minute() {
+        let limiter = RateLimiter::with_limits(RequestLimits {
+            max_concurrent_requests: 4,
+            requests_per_minute: Some(1),
+        });
+
+        let first = limiter.run(async { Ok(1) }).now_or_never();
+        assert_eq!(first.and_then(Result::ok), Some(1));
+
+        let mut second = limiter.run(async { Ok(2) }).boxed_local();
+        assert!((&mut second).now_or_never().is_none());
+        assert_eq!(limiter.queued_requests(), 1);
+
+        limiter.set_limits(RequestLimits {
+            max_concurrent_requests: 4,
+            requests_per_minute: None,
+        });
+        assert_eq!(second.now_or_never().and_then(Result::ok), Some(2));
+    }
+
+    #[test]
+    fn test_queue_position() {
+        let limiter = RateLimiter::new(1);
+        let (first_tx, first_rx) = oneshot::channel::<()>();
+        let first_id = limiter.next_request_id();
+        let mut first = limiter
+            .run(async move {
+                first_rx.await.ok();
+                Ok(1)
+            })
+            .boxed_local();
+        let second_id = limiter.next_request_id();
+        let mut second = limiter.run(async { Ok(2) }).boxed_local();
+        let third_id = limiter.next_request_id();
+        let mut third = limiter.run(async { Ok(3) }).boxed_local();
+
+        assert!((&mut first).now_or_never().is_none());
+        assert!((&mut second).now_or_never().is_none());
+        assert!((&mut third).now_or_never().is_none());
+        assert_eq!(limiter.queue_position(first_id), None);
+        assert_eq!(limiter.queue_position(second_id), Some(1));
+        assert_eq!(limiter.queue_position(third_id), Some(2));
+        assert!(limiter.started_at(first_id).is_some());
+        assert_eq!(limiter.started_at(second_id), None);
+
+        first_tx.send(()).ok();
+        assert_eq!(first.now_or_never().and_then(Result::ok), Some(1));
+        assert_eq!(second.now_or_never().and_then(Result::ok), Some(2));
+        assert_eq!(limiter.queue_position(second_id), None);
+        assert!(limiter.started_at(second_id) >= limiter.started_at(first_id));
+        assert_eq!(limiter.queue_position(third_id), Some(1));
+        assert_eq!(third.now_or_never().and_then(Result::ok), Some(3));
+        assert_eq!(limiter.queue_position(third_id), None);
+    }
+
+    #[test]
+    fn test_embeddings_are_queued_separately() {
+        let provider_id = LanguageModelProviderId("embeddings-test".into());
+        RateLimiter::for_provider(&provider_id).set_limits(RequestLimits {
+            max_concurrent_requests: 1,
+            requests_per_minute: None,
+        });
+        let completions = RateLimiter::for_provider(&provider_id);
+        let embeddings = RateLimiter::for_provider_embeddings(&provider_id);
+
+        let (embedding_tx, embedding_rx) = oneshot::channel::<()>();
+        let mut embedding = embeddings
+            .run(async move {
+                embedding_rx.await.ok();
+                Ok(1)
+            })
+            .boxed_local();
+        assert!((&mut embedding).now_or_never().is_none());
+
+        // A completion can start while an embedding request is in flight.
+        let completion = completions.run(async { Ok(2) }).now_or_never();
+        assert_eq!(completion.and_then(Result::ok), Some(2));
+
+        embedding_tx.send(()).ok();
+        assert_eq!(embedding.now_or_never().and_then(Result::ok), Some(1));
+    }
+
+    #[test]
+    fn test_dropping_queued_request() {
+        let limiter = RateLimiter::new(1);
+        let (first_tx, first_rx) = oneshot::channel::<()>();
+        let mut first = limiter
+            .run(async move {
+                first_rx.await.ok();
+                Ok(1)
+            })
+            .boxed_local();
+        let mut second = limiter.run(async { Ok(2) }).boxed_local();
+        let mut third = limiter.run(async { Ok(3) }).boxed_local();
+
+        assert!((&mut first).now_or_never().is_none());
+        assert!((&mut second).now_or_never().is_none());
+        assert!((&mut third).now_or_never().is_none());
+        assert_eq!(limiter.queued_requests(), 2);
+
+        drop(second);
+        assert_eq!(limiter.queued_requests(), 1);
+
+        first_tx.send(()).ok();
+        assert_eq!(first.now_or_never().and_then(Result::ok), Some(1));
+        assert_eq!(third.now_or_never().and_then(Result::ok), Some(3));
+    }
+}
@@ -1,5 +1,5 @@
 use crate::{
-    LanguageModel, LanguageModelId, LanguageModelProvider, LanguageModelProviderId,
+    EmbeddingModel, LanguageModel, LanguageModelId, LanguageModelProvider, LanguageModelProviderId,
     LanguageModelProviderState,
 };
 use collections::BTreeMap;
@@ -161,6 +161,15 @@ impl LanguageModelRegistry {
             .flat_map(|provider| provider.provided_models(cx))
     }
 
+    pub fn available_embedding_models<'a>(
+        &'a self,
+        cx: &'a App,
+    ) -> impl Iterator<Item = Arc<dyn EmbeddingModel>> + 'a {
+        self.providers
+            .values()
+            .flat_map(|provider| provider.provided_embedding_models(cx))
+    }
+
     pub fn provider(&self, id: &LanguageModelProviderId) -> Option<Arc<dyn LanguageModelProvider>> {
         self.providers.get(id).cloned()
     }
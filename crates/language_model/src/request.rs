@@ -2,7 +2,7 @@ use std::io::{Cursor, Write};
 use std::sync::Arc;
 
 use crate::role::Role;
-use crate::{LanguageModelToolUse, LanguageModelToolUseId};
+use crate::{LanguageModelResponseSchema, LanguageModelToolUse, LanguageModelToolUseId};
 use anyhow::Result;
 use base64::write::EncoderWriter;
 use gpui::{
@@ -391,6 +391,8 @@ pub struct LanguageModelRequest {
     pub tool_choice: Option<LanguageModelToolChoice>,
     pub stop: Vec<String>,
     pub temperature: Option<f32>,
+    /// When set, the response must be a JSON value conforming to this schema.
+    pub response_schema: Option<LanguageModelResponseSchema>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq)]
@@ -0,0 +1,258 @@
+use anyhow::Result;
+use futures::{FutureExt, StreamExt, future::BoxFuture, stream::BoxStream};
+use parking_lot::Mutex;
+use serde_json::{Value, json};
+use std::{
+    fs::{self, File, OpenOptions},
+    io::Write,
+    sync::{
+        LazyLock,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        mpsc,
+    },
+    time::{SystemTime, UNIX_EPOCH},
+};
+use util::ResultExt;
+
+use crate::{
+    LanguageModelCompletionError, LanguageModelCompletionEvent, LanguageModelId,
+    LanguageModelProviderId, LanguageModelRequest,
+};
+
+/// The language model log is rotated once it grows beyond this size.
+const MAX_LOG_FILE_LEN: u64 = 10 * 1024 * 1024;
+
+const REDACTED: &str = "[redacted]";
+
+static REQUEST_LOGGING_ENABLED: AtomicBool = AtomicBool::new(false);
+static NEXT_REQUEST_ID: AtomicUsize = AtomicUsize::new(0);
+static LOG_WRITER: LazyLock<Mutex<Option<mpsc::Sender<String>>>> = LazyLock::new(Default::default);
+
+/// Enables writing the payload and the streamed response of every completion
+/// request to [`paths::language_model_log_file`].
+pub fn set_request_logging_enabled(enabled: bool) {
+    REQUEST_LOGGING_ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+pub fn is_request_logging_enabled() -> bool {
+    REQUEST_LOGGING_ENABLED.load(Ordering::Relaxed)
+}
+
+/// A completion request that is written to the language model log, along with
+/// the events it produces, when request logging is enabled.
+pub struct LoggedRequest {
+    id: Option<usize>,
+}
+
+impl LoggedRequest {
+    pub fn start(
+        provider_id: &LanguageModelProviderId,
+        model_id: &LanguageModelId,
+        request: &LanguageModelRequest,
+    ) -> Self {
+        if !is_request_logging_enabled() {
+            return Self { id: None };
+        }
+
+        let id = NEXT_REQUEST_ID.fetch_add(1, Ordering::Relaxed);
+        let Some(mut payload) = serde_json::to_value(request).log_err() else {
+            return Self { id: None };
+        };
+        redact_secrets(&mut payload);
+        write_record(json!({
+            "request_id": id,
+            "type": "request",
+            "provider": provider_id.0,
+            "model": model_id.0,
+            "payload": payload,
+        }));
+        Self { id: Some(id) }
+    }
+
+    pub fn wrap(
+        self,
+        response: BoxFuture<
+            'static,
+            Result<
+                BoxStream<
+                    'static,
+                    Result<LanguageModelCompletionEvent, LanguageModelCompletionError>,
+                >,
+            >,
+        >,
+    ) -> BoxFuture<
+        'static,
+        Result<
+            BoxStream<'static, Result<LanguageModelCompletionEvent, LanguageModelCompletionError>>,
+        >,
+    > {
+        let Some(id) = self.id else {
+            return response;
+        };
+
+        async move {
+            match response.await {
+                Ok(events) => Ok(events
+                    .inspect(move |event| match event {
+                        Ok(event) => {
+                            if let Some(mut event) = serde_json::to_value(event).log_err() {
+                                redact_secrets(&mut event);
+                                write_record(json!({
+                                    "request_id": id,
+                                    "type": "event",
+                                    "event": event,
+                                }));
+                            }
+                        }
+                        Err(error) => write_error(id, error),
+                    })
+                    .boxed()),
+                Err(error) => {
+                    write_error(id, &error);
+                    Err(error)
+                }
+            }
+        }
+        .boxed()
+    }
+}
+
+fn write_error(id: usize, error: &dyn std::fmt::Display) {
+    write_record(json!({
+        "request_id": id,
+        "type": "error",
+        "error": error.to_string(),
+    }));
+}
+
+fn write_record(mut record: Value) {
+    if let Some(record) = record.as_object_mut() {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_millis() as u64);
+        record.insert("timestamp".into(), json!(timestamp));
+    }
+
+    let mut writer = LOG_WRITER.lock();
+    let sender = writer.get_or_insert_with(spawn_log_writer);
+    if sender.send(record.to_string()).is_err() {
+        // The writer thread exited, so start a new one on the next write.
+        *writer = None;
+    }
+}
+
+fn spawn_log_writer() -> mpsc::Sender<String> {
+    let (tx, rx) = mpsc::channel::<String>();
+    std::thread::Builder::new()
+        .name("LanguageModelLog".into())
+        .spawn(move || {
+            let mut file = None;
+            for line in rx {
+                if write_line(&mut file, &line).log_err().is_none() {
+                    file = None;
+                }
+            }
+        })
+        .log_err();
+    tx
+}
+
+fn write_line(file: &mut Option<File>, line: &str) -> Result<()> {
+    let log_file = paths::language_model_log_file();
+    let len = match file {
+        Some(file) => file.metadata()?.len(),
+        None => fs::metadata(log_file).map_or(0, |metadata| metadata.len()),
+    };
+    if len > MAX_LOG_FILE_LEN {
+        *file = None;
+        fs::rename(log_file, paths::old_language_model_log_file())?;
+    }
+
+    let file = match file {
+        Some(file) => file,
+        None => {
+            fs::create_dir_all(paths::logs_dir())?;
+            file.insert(
+                OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(log_file)?,
+            )
+        }
+    };
+    writeln!(file, "{line}")?;
+    Ok(())
+}
+
+/// Replaces credentials that may appear in request payloads or responses.
+fn redact_secrets(value: &mut Value) {
+    match value {
+        Value::Object(object) => {
+            for (key, value) in object.iter_mut() {
+                if is_secret_key(key) {
+                    *value = Value::String(REDACTED.into());
+                } else {
+                    redact_secrets(value);
+                }
+            }
+        }
+        Value::Array(values) => values.iter_mut().for_each(redact_secrets),
+        Value::String(text) => {
+            if looks_like_secret(text) {
+                *text = REDACTED.into();
+            }
+        }
+        Value::Null | Value::Bool(_) | Value::Number(_) => {}
+    }
+}
+
+fn is_secret_key(key: &str) -> bool {
+    let key = key.to_ascii_lowercase();
+    key == "authorization"
+        || key == "token"
+        || key.ends_with("_token")
+        || key.contains("api_key")
+        || key.contains("apikey")
+        || key.contains("secret")
+        || key.contains("password")
+}
+
+fn looks_like_secret(text: &str) -> bool {
+    let is_key_like = |prefix: &str| {
+        text.starts_with(prefix) && text.len() >= 20 && !text.contains(char::is_whitespace)
+    };
+    text.starts_with("Bearer ") || is_key_like("sk-") || is_key_like("ghu_")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact_secrets() {
+        let mut value = json!({
+            "api_key": "abc",
+            "headers": { "Authorization": "Bearer abc" },
+            "access_token": "abc",
+            "max_tokens": 1024,
+            "messages": [
+                { "content": "sk-0123456789abcdefghijklmnop" },
+                { "content": "sk-learn is a python library" }
+            ]
+        });
+        redact_secrets(&mut value);
+        assert_eq!(
+            value,
+            json!({
+                "api_key": REDACTED,
+                "headers": { "Authorization": REDACTED },
+                "access_token": REDACTED,
+                "max_tokens": 1024,
+                "messages": [
+                    { "content": REDACTED },
+                    { "content": "sk-learn is a python library" }
+                ]
+            })
+        );
+    }
+}
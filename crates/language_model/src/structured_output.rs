@@ -0,0 +1,226 @@
+use std::sync::Arc;
+
+use anyhow::{Context as _, Result, anyhow};
+use futures::{FutureExt as _, StreamExt as _, future::BoxFuture};
+use gpui::AsyncApp;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use zed_llm_client::CompletionRequestStatus;
+
+use crate::{
+    LanguageModel, LanguageModelCompletionEvent, LanguageModelRequest, LanguageModelRequestMessage,
+    RequestUsage, Role,
+};
+
+/// How many times we ask a model without native structured output support to
+/// fix a response that doesn't match the schema.
+const MAX_REPAIR_ATTEMPTS: usize = 1;
+
+/// A JSON schema that the model's response must conform to.
+#[derive(Debug, PartialEq, Hash, Clone, Serialize, Deserialize)]
+pub struct LanguageModelResponseSchema {
+    pub name: String,
+    pub schema: serde_json::Value,
+}
+
+impl LanguageModelResponseSchema {
+    pub fn for_type<T: JsonSchema>() -> Self {
+        // Providers only support a subset of JSON schema for constrained decoding,
+        // so avoid references and `null` union types.
+        let mut generator = schemars::r#gen::SchemaSettings::draft07()
+            .with(|settings| {
+                settings.meta_schema = None;
+                settings.inline_subschemas = true;
+                settings.option_add_null_type = false;
+            })
+            .into_generator();
+        let mut schema = serde_json::to_value(generator.root_schema_for::<T>())
+            .unwrap_or(serde_json::Value::Null);
+        if let Some(object) = schema.as_object_mut() {
+            object.remove("title");
+            object.remove("definitions");
+        }
+
+        Self {
+            name: T::schema_name(),
+            schema,
+        }
+    }
+}
+
+/// A response that was deserialized from a model's structured output.
+#[derive(Debug)]
+pub struct LanguageModelStructuredResponse<T> {
+    pub value: T,
+    pub usage: Option<RequestUsage>,
+}
+
+impl dyn LanguageModel {
+    /// Requests a response that deserializes to `T`.
+    ///
+    /// Models that support structured output are constrained to `T`'s JSON schema. Other
+    /// models are instructed to follow the schema, and asked to correct their response if
+    /// it can't be deserialized.
+    pub fn request_structured<T: DeserializeOwned + JsonSchema + 'static>(
+        self: &Arc<Self>,
+        mut request: LanguageModelRequest,
+        cx: &AsyncApp,
+    ) -> BoxFuture<'static, Result<LanguageModelStructuredResponse<T>>> {
+        let model = self.clone();
+        let cx = cx.clone();
+        let schema = LanguageModelResponseSchema::for_type::<T>();
+        let constrained = model.supports_structured_output();
+        let mut repair_attempts = if constrained { 0 } else { MAX_REPAIR_ATTEMPTS };
+
+        if constrained {
+            request.response_schema = Some(schema);
+        } else {
+            request.messages.push(LanguageModelRequestMessage {
+                role: Role::User,
+                content: vec![
+                    format!(
+                        "Respond only with a JSON value that conforms to the following JSON schema, \
+                        without any surrounding prose or code fences:\n{}",
+                        schema.schema
+                    )
+                    .into(),
+                ],
+                cache: false,
+            });
+        }
+
+        async move {
+            let mut usage = None;
+            loop {
+                let mut events = model.stream_completion(request.clone(), &cx).await?;
+                let mut text = String::new();
+                while let Some(event) = events.next().await {
+                    match event? {
+                        LanguageModelCompletionEvent::Text(chunk) => text.push_str(&chunk),
+                        LanguageModelCompletionEvent::StatusUpdate(
+                            CompletionRequestStatus::UsageUpdated { amount, limit },
+                        ) => {
+                            usage = Some(RequestUsage {
+                                limit,
+                                amount: amount as i32,
+                            });
+                        }
+                        _ => {}
+                    }
+                }
+
+                match parse_structured_response::<T>(&text) {
+                    Ok(value) => return Ok(LanguageModelStructuredResponse { value, usage }),
+                    Err(error) if repair_attempts > 0 => {
+                        repair_attempts -= 1;
+                        log::debug!(
+                            "repairing structured response from {}: {error}",
+                            model.id().0
+                        );
+                        request.messages.push(LanguageModelRequestMessage {
+                            role: Role::Assistant,
+                            content: vec![text.into()],
+                            cache: false,
+                        });
+                        request.messages.push(LanguageModelRequestMessage {
+                            role: Role::User,
+                            content: vec![
+                                format!(
+                                    "That response doesn't conform to the schema: {error}. \
+                                    Respond again with only the corrected JSON value."
+                                )
+                                .into(),
+                            ],
+                            cache: false,
+                        });
+                    }
+                    Err(error) => {
+                        return Err(error).with_context(|| {
+                            format!("invalid structured response from {}", model.id().0)
+                        });
+                    }
+                }
+            }
+        }
+        .boxed()
+    }
+}
+
+/// Deserializes a structured response, tolerating the prose and code fences that
+/// models tend to wrap JSON in when they aren't constrained.
+fn parse_structured_response<T: DeserializeOwned>(text: &str) -> Result<T> {
+    let text = text.trim();
+    let error = match serde_json::from_str(text) {
+        Ok(value) => return Ok(value),
+        Err(error) => error,
+    };
+
+    let start = text.find(['{', '[']);
+    let end = text.rfind(['}', ']']);
+    if let Some((start, end)) = start.zip(end).filter(|(start, end)| start < end) {
+        if let Ok(value) = serde_json::from_str(&text[start..=end]) {
+            return Ok(value);
+        }
+    }
+
+    Err(anyhow!(error))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_provider::FakeLanguageModel;
+    use gpui::TestAppContext;
+
+    #[derive(Debug, PartialEq, Deserialize, JsonSchema)]
+    struct Title {
+        title: String,
+    }
+
+    #[test]
+    fn test_parse_structured_response() {
+        let expected = Title {
+            title: "Fix the build".into(),
+        };
+        assert_eq!(
+            parse_structured_response::<Title>(r#"{"title": "Fix the build"}"#).unwrap(),
+            expected
+        );
+        assert_eq!(
+            parse_structured_response::<Title>(
+                "Here you go:\n```json\n{\"title\": \"Fix the build\"}\n```"
+            )
+            .unwrap(),
+            expected
+        );
+        assert!(parse_structured_response::<Title>("Fix the build").is_err());
+    }
+
+    #[gpui::test]
+    async fn test_request_structured_repairs_invalid_response(cx: &mut TestAppContext) {
+        let fake_model = Arc::new(FakeLanguageModel::default());
+        let model: Arc<dyn LanguageModel> = fake_model.clone();
+
+        let response = cx.update(|cx| {
+            model.request_structured::<Title>(LanguageModelRequest::default(), &cx.to_async())
+        });
+        let response = cx.executor().spawn(response);
+        cx.run_until_parked();
+        fake_model.stream_last_completion_response("Fix the build");
+        fake_model.end_last_completion_stream();
+        cx.run_until_parked();
+
+        let repair_request = fake_model.pending_completions().pop().unwrap();
+        assert_eq!(repair_request.messages.len(), 3);
+        assert_eq!(repair_request.messages[1].role, Role::Assistant);
+        fake_model.stream_last_completion_response(r#"{"title": "Fix the build"}"#);
+        fake_model.end_last_completion_stream();
+
+        assert_eq!(
+            response.await.unwrap().value,
+            Title {
+                title: "Fix the build".into()
+            }
+        );
+    }
+}
@@ -15,6 +15,7 @@ use crate::provider::bedrock::BedrockLanguageModelProvider;
 use crate::provider::cloud::CloudLanguageModelProvider;
 use crate::provider::copilot_chat::CopilotChatLanguageModelProvider;
 use crate::provider::google::GoogleLanguageModelProvider;
+use crate::provider::llama_cpp::LlamaCppLanguageModelProvider;
 use crate::provider::lmstudio::LmStudioLanguageModelProvider;
 use crate::provider::mistral::MistralLanguageModelProvider;
 use crate::provider::ollama::OllamaLanguageModelProvider;
@@ -57,6 +58,10 @@ fn register_language_model_providers(
         LmStudioLanguageModelProvider::new(client.http_client(), cx),
         cx,
     );
+    registry.register_provider(
+        LlamaCppLanguageModelProvider::new(client.http_client(), cx),
+        cx,
+    );
     registry.register_provider(
         DeepSeekLanguageModelProvider::new(client.http_client(), cx),
         cx,
@@ -4,6 +4,7 @@ pub mod cloud;
 pub mod copilot_chat;
 pub mod deepseek;
 pub mod google;
+pub mod llama_cpp;
 pub mod lmstudio;
 pub mod mistral;
 pub mod ollama;
@@ -13,13 +13,13 @@ use futures::{FutureExt, StreamExt, future::BoxFuture, stream::BoxStream};
 use gpui::{
     AnyView, App, AsyncApp, Context, Entity, FontStyle, Subscription, Task, TextStyle, WhiteSpace,
 };
-use http_client::HttpClient;
+use http_client::{HttpClient, Url};
 use language_model::{
     AuthenticateError, LanguageModel, LanguageModelCacheConfiguration,
     LanguageModelCompletionError, LanguageModelId, LanguageModelKnownError, LanguageModelName,
     LanguageModelProvider, LanguageModelProviderId, LanguageModelProviderName,
     LanguageModelProviderState, LanguageModelRequest, LanguageModelToolChoice,
-    LanguageModelToolResultContent, MessageContent, RateLimiter, Role,
+    LanguageModelToolResultContent, LoggedRequest, MessageContent, RateLimiter, Role,
 };
 use language_model::{LanguageModelCompletionEvent, LanguageModelToolUse, StopReason};
 use schemars::JsonSchema;
@@ -204,7 +204,9 @@ impl AnthropicLanguageModelProvider {
             model,
             state: self.state.clone(),
             http_client: self.http_client.clone(),
-            request_limiter: RateLimiter::new(4),
+            request_limiter: RateLimiter::for_provider(&LanguageModelProviderId(
+                PROVIDER_ID.into(),
+            )),
         })
     }
 }
@@ -230,6 +232,10 @@ impl LanguageModelProvider for AnthropicLanguageModelProvider {
         IconName::AiAnthropic
     }
 
+    fn api_url(&self, cx: &App) -> Option<Url> {
+        Url::parse(&AllLanguageModelSettings::get_global(cx).anthropic.api_url).ok()
+    }
+
     fn default_model(&self, _cx: &App) -> Option<Arc<dyn LanguageModel>> {
         Some(self.create_language_model(anthropic::Model::default()))
     }
@@ -475,6 +481,7 @@ impl LanguageModel for AnthropicModel {
             BoxStream<'static, Result<LanguageModelCompletionEvent, LanguageModelCompletionError>>,
         >,
     > {
+        let logged_request = LoggedRequest::start(&self.provider_id(), &self.id(), &request);
         let request = into_anthropic(
             request,
             self.model.request_id().into(),
@@ -492,7 +499,7 @@ impl LanguageModel for AnthropicModel {
                 })?;
             Ok(AnthropicEventMapper::new().map_stream(response))
         });
-        async move { Ok(future.await?.boxed()) }.boxed()
+        logged_request.wrap(async move { Ok(future.await?.boxed()) }.boxed())
     }
 
     fn cache_configuration(&self) -> Option<LanguageModelCacheConfiguration> {
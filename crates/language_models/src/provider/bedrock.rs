@@ -36,8 +36,8 @@ use language_model::{
     LanguageModelCompletionError, LanguageModelCompletionEvent, LanguageModelId, LanguageModelName,
     LanguageModelProvider, LanguageModelProviderId, LanguageModelProviderName,
     LanguageModelProviderState, LanguageModelRequest, LanguageModelToolChoice,
-    LanguageModelToolResultContent, LanguageModelToolUse, MessageContent, RateLimiter, Role,
-    TokenUsage,
+    LanguageModelToolResultContent, LanguageModelToolUse, LoggedRequest, MessageContent,
+    RateLimiter, Role, TokenUsage,
 };
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -267,7 +267,9 @@ impl BedrockLanguageModelProvider {
             handler: self.handler.clone(),
             state: self.state.clone(),
             client: OnceCell::new(),
-            request_limiter: RateLimiter::new(4),
+            request_limiter: RateLimiter::for_provider(&LanguageModelProviderId(
+                PROVIDER_ID.into(),
+            )),
         })
     }
 }
@@ -529,6 +531,7 @@ impl LanguageModel for BedrockModel {
             BoxStream<'static, Result<LanguageModelCompletionEvent, LanguageModelCompletionError>>,
         >,
     > {
+        let logged_request = LoggedRequest::start(&self.provider_id(), &self.id(), &request);
         let Ok(region) = cx.read_entity(&self.state, |state, _cx| {
             // Get region - from credentials or directly from settings
             let credentials_region = state.credentials.as_ref().map(|s| s.region.clone());
@@ -573,7 +576,7 @@ impl LanguageModel for BedrockModel {
                 owned_handle,
             ))
         });
-        async move { Ok(future.await?.boxed()) }.boxed()
+        logged_request.wrap(async move { Ok(future.await?.boxed()) }.boxed())
     }
 
     fn cache_configuration(&self) -> Option<LanguageModelCacheConfiguration> {
@@ -8,14 +8,16 @@ use google_ai::GoogleModelMode;
 use gpui::{
     AnyElement, AnyView, App, AsyncApp, Context, Entity, SemanticVersion, Subscription, Task,
 };
-use http_client::{AsyncBody, HttpClient, Method, Response, StatusCode};
+use http_client::{
+    AsyncBody, HttpClient, HttpRequestExt as _, Method, RequestPriority, Response, StatusCode, Url,
+};
 use language_model::{
     AuthenticateError, LanguageModel, LanguageModelCacheConfiguration,
     LanguageModelCompletionError, LanguageModelId, LanguageModelKnownError, LanguageModelName,
     LanguageModelProviderId, LanguageModelProviderName, LanguageModelProviderState,
     LanguageModelProviderTosView, LanguageModelRequest, LanguageModelToolChoice,
-    LanguageModelToolSchemaFormat, ModelRequestLimitReachedError, RateLimiter, RequestUsage,
-    ZED_CLOUD_PROVIDER_ID,
+    LanguageModelToolSchemaFormat, LoggedRequest, ModelRequestLimitReachedError, RateLimiter,
+    RequestUsage, ZED_CLOUD_PROVIDER_ID,
 };
 use language_model::{
     LanguageModelCompletionEvent, LanguageModelProvider, LlmApiToken, PaymentRequiredError,
@@ -336,7 +338,9 @@ impl CloudLanguageModelProvider {
             model,
             llm_api_token: llm_api_token.clone(),
             client: self.client.clone(),
-            request_limiter: RateLimiter::new(4),
+            request_limiter: RateLimiter::for_provider(&LanguageModelProviderId(
+                ZED_CLOUD_PROVIDER_ID.into(),
+            )),
         })
     }
 }
@@ -362,6 +366,10 @@ impl LanguageModelProvider for CloudLanguageModelProvider {
         IconName::AiZed
     }
 
+    fn api_url(&self, _cx: &App) -> Option<Url> {
+        self.client.http_client().build_zed_llm_url("/", &[]).ok()
+    }
+
     fn default_model(&self, cx: &App) -> Option<Arc<dyn LanguageModel>> {
         let default_model = self.state.read(cx).default_model.clone()?;
         let llm_api_token = self.state.read(cx).llm_api_token.clone();
@@ -553,7 +561,8 @@ impl CloudLanguageModel {
         loop {
             let request_builder = http_client::Request::builder()
                 .method(Method::POST)
-                .uri(http_client.build_zed_llm_url("/completions", &[])?.as_ref());
+                .uri(http_client.build_zed_llm_url("/completions", &[])?.as_ref())
+                .priority(RequestPriority::Interactive);
             let request_builder = if let Some(app_version) = app_version {
                 request_builder.header(ZED_VERSION_HEADER_NAME, app_version.to_string())
             } else {
@@ -699,6 +708,17 @@ impl LanguageModel for CloudLanguageModel {
         self.model.supports_max_mode
     }
 
+    fn supports_structured_output(&self) -> bool {
+        match self.model.provider {
+            zed_llm_client::LanguageModelProvider::Anthropic => false,
+            zed_llm_client::LanguageModelProvider::OpenAi => {
+                open_ai::Model::from_id(&self.model.id.0)
+                    .is_ok_and(|model| model.supports_structured_output())
+            }
+            zed_llm_client::LanguageModelProvider::Google => true,
+        }
+    }
+
     fn telemetry_id(&self) -> String {
         format!("zed.dev/{}", self.model.id)
     }
@@ -809,6 +829,7 @@ impl LanguageModel for CloudLanguageModel {
             BoxStream<'static, Result<LanguageModelCompletionEvent, LanguageModelCompletionError>>,
         >,
     > {
+        let logged_request = LoggedRequest::start(&self.provider_id(), &self.id(), &request);
         let thread_id = request.thread_id.clone();
         let prompt_id = request.prompt_id.clone();
         let intent = request.intent;
@@ -878,7 +899,7 @@ impl LanguageModel for CloudLanguageModel {
                         move |event| mapper.map_event(event),
                     ))
                 });
-                async move { Ok(future.await?.boxed()) }.boxed()
+                logged_request.wrap(async move { Ok(future.await?.boxed()) }.boxed())
             }
             zed_llm_client::LanguageModelProvider::OpenAi => {
                 let client = self.client.clone();
@@ -920,7 +941,7 @@ impl LanguageModel for CloudLanguageModel {
                         move |event| mapper.map_event(event),
                     ))
                 });
-                async move { Ok(future.await?.boxed()) }.boxed()
+                logged_request.wrap(async move { Ok(future.await?.boxed()) }.boxed())
             }
             zed_llm_client::LanguageModelProvider::Google => {
                 let client = self.client.clone();
@@ -959,7 +980,7 @@ impl LanguageModel for CloudLanguageModel {
                         move |event| mapper.map_event(event),
                     ))
                 });
-                async move { Ok(future.await?.boxed()) }.boxed()
+                logged_request.wrap(async move { Ok(future.await?.boxed()) }.boxed())
             }
         }
     }
@@ -22,8 +22,8 @@ use language_model::{
     LanguageModelId, LanguageModelName, LanguageModelProvider, LanguageModelProviderId,
     LanguageModelProviderName, LanguageModelProviderState, LanguageModelRequest,
     LanguageModelRequestMessage, LanguageModelToolChoice, LanguageModelToolResultContent,
-    LanguageModelToolSchemaFormat, LanguageModelToolUse, MessageContent, RateLimiter, Role,
-    StopReason,
+    LanguageModelToolSchemaFormat, LanguageModelToolUse, LoggedRequest, MessageContent,
+    RateLimiter, Role, StopReason,
 };
 use settings::SettingsStore;
 use std::time::Duration;
@@ -76,7 +76,9 @@ impl CopilotChatLanguageModelProvider {
     fn create_language_model(&self, model: CopilotChatModel) -> Arc<dyn LanguageModel> {
         Arc::new(CopilotChatLanguageModel {
             model,
-            request_limiter: RateLimiter::new(4),
+            request_limiter: RateLimiter::for_provider(&LanguageModelProviderId(
+                PROVIDER_ID.into(),
+            )),
         })
     }
 }
@@ -255,6 +257,7 @@ impl LanguageModel for CopilotChatLanguageModel {
             BoxStream<'static, Result<LanguageModelCompletionEvent, LanguageModelCompletionError>>,
         >,
     > {
+        let logged_request = LoggedRequest::start(&self.provider_id(), &self.id(), &request);
         if let Some(message) = request.messages.last() {
             if message.contents_empty() {
                 const EMPTY_PROMPT_MSG: &str =
@@ -277,20 +280,18 @@ impl LanguageModel for CopilotChatLanguageModel {
         };
         let is_streaming = copilot_request.stream;
 
-        let request_limiter = self.request_limiter.clone();
-        let future = cx.spawn(async move |cx| {
-            let request = CopilotChat::stream_completion(copilot_request, cx.clone());
-            request_limiter
-                .stream(async move {
-                    let response = request.await?;
-                    Ok(map_to_language_model_completion_events(
-                        response,
-                        is_streaming,
-                    ))
-                })
-                .await
+        let request = CopilotChat::stream_completion(copilot_request, cx.clone());
+        // Queue the request right away, like the other providers, so that callers can follow its
+        // position in the queue.
+        let request = self.request_limiter.stream(async move {
+            let response = request.await?;
+            Ok(map_to_language_model_completion_events(
+                response,
+                is_streaming,
+            ))
         });
-        async move { Ok(future.await?.boxed()) }.boxed()
+        let future = cx.spawn(async move |_| request.await);
+        logged_request.wrap(async move { Ok(future.await?.boxed()) }.boxed())
     }
 }
 
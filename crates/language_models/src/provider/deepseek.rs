@@ -8,13 +8,13 @@ use gpui::{
     AnyView, AppContext as _, AsyncApp, Entity, FontStyle, Subscription, Task, TextStyle,
     WhiteSpace,
 };
-use http_client::HttpClient;
+use http_client::{HttpClient, Url};
 use language_model::{
     AuthenticateError, LanguageModel, LanguageModelCompletionError, LanguageModelCompletionEvent,
     LanguageModelId, LanguageModelName, LanguageModelProvider, LanguageModelProviderId,
     LanguageModelProviderName, LanguageModelProviderState, LanguageModelRequest,
-    LanguageModelToolChoice, LanguageModelToolResultContent, LanguageModelToolUse, MessageContent,
-    RateLimiter, Role, StopReason,
+    LanguageModelToolChoice, LanguageModelToolResultContent, LanguageModelToolUse, LoggedRequest,
+    MessageContent, RateLimiter, Role, StopReason,
 };
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -159,7 +159,9 @@ impl DeepSeekLanguageModelProvider {
             model,
             state: self.state.clone(),
             http_client: self.http_client.clone(),
-            request_limiter: RateLimiter::new(4),
+            request_limiter: RateLimiter::for_provider(&LanguageModelProviderId(
+                PROVIDER_ID.into(),
+            )),
         }) as Arc<dyn LanguageModel>
     }
 }
@@ -185,6 +187,10 @@ impl LanguageModelProvider for DeepSeekLanguageModelProvider {
         IconName::AiDeepSeek
     }
 
+    fn api_url(&self, cx: &App) -> Option<Url> {
+        Url::parse(&AllLanguageModelSettings::get_global(cx).deepseek.api_url).ok()
+    }
+
     fn default_model(&self, _cx: &App) -> Option<Arc<dyn LanguageModel>> {
         Some(self.create_language_model(deepseek::Model::default()))
     }
@@ -350,14 +356,17 @@ impl LanguageModel for DeepSeekLanguageModel {
             BoxStream<'static, Result<LanguageModelCompletionEvent, LanguageModelCompletionError>>,
         >,
     > {
+        let logged_request = LoggedRequest::start(&self.provider_id(), &self.id(), &request);
         let request = into_deepseek(request, &self.model, self.max_output_tokens());
         let stream = self.stream_completion(request, cx);
 
-        async move {
-            let mapper = DeepSeekEventMapper::new();
-            Ok(mapper.map_stream(stream.await?).boxed())
-        }
-        .boxed()
+        logged_request.wrap(
+            async move {
+                let mapper = DeepSeekEventMapper::new();
+                Ok(mapper.map_stream(stream.await?).boxed())
+            }
+            .boxed(),
+        )
     }
 }
 
@@ -10,7 +10,7 @@ use google_ai::{
 use gpui::{
     AnyView, App, AsyncApp, Context, Entity, FontStyle, Subscription, Task, TextStyle, WhiteSpace,
 };
-use http_client::HttpClient;
+use http_client::{HttpClient, Url};
 use language_model::{
     AuthenticateError, LanguageModelCompletionError, LanguageModelCompletionEvent,
     LanguageModelToolChoice, LanguageModelToolSchemaFormat, LanguageModelToolUse,
@@ -19,7 +19,7 @@ use language_model::{
 use language_model::{
     LanguageModel, LanguageModelId, LanguageModelName, LanguageModelProvider,
     LanguageModelProviderId, LanguageModelProviderName, LanguageModelProviderState,
-    LanguageModelRequest, RateLimiter, Role,
+    LanguageModelRequest, LoggedRequest, RateLimiter, Role,
 };
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -192,7 +192,9 @@ impl GoogleLanguageModelProvider {
             model,
             state: self.state.clone(),
             http_client: self.http_client.clone(),
-            request_limiter: RateLimiter::new(4),
+            request_limiter: RateLimiter::for_provider(&LanguageModelProviderId(
+                PROVIDER_ID.into(),
+            )),
         })
     }
 }
@@ -218,6 +220,10 @@ impl LanguageModelProvider for GoogleLanguageModelProvider {
         IconName::AiGoogle
     }
 
+    fn api_url(&self, cx: &App) -> Option<Url> {
+        Url::parse(&AllLanguageModelSettings::get_global(cx).google.api_url).ok()
+    }
+
     fn default_model(&self, _cx: &App) -> Option<Arc<dyn LanguageModel>> {
         Some(self.create_language_model(google_ai::Model::default()))
     }
@@ -260,7 +266,9 @@ impl LanguageModelProvider for GoogleLanguageModelProvider {
                     model,
                     state: self.state.clone(),
                     http_client: self.http_client.clone(),
-                    request_limiter: RateLimiter::new(4),
+                    request_limiter: RateLimiter::for_provider(&LanguageModelProviderId(
+                        PROVIDER_ID.into(),
+                    )),
                 }) as Arc<dyn LanguageModel>
             })
             .collect()
@@ -349,6 +357,10 @@ impl LanguageModel for GoogleLanguageModel {
         true
     }
 
+    fn supports_structured_output(&self) -> bool {
+        true
+    }
+
     fn supports_tool_choice(&self, choice: LanguageModelToolChoice) -> bool {
         match choice {
             LanguageModelToolChoice::Auto
@@ -411,6 +423,7 @@ impl LanguageModel for GoogleLanguageModel {
             >,
         >,
     > {
+        let logged_request = LoggedRequest::start(&self.provider_id(), &self.id(), &request);
         let request = into_google(request, self.model.id().to_string(), self.model.mode());
         let request = self.stream_completion(request, cx);
         let future = self.request_limiter.stream(async move {
@@ -419,7 +432,7 @@ impl LanguageModel for GoogleLanguageModel {
                 .map_err(|err| LanguageModelCompletionError::Other(anyhow!(err)))?;
             Ok(GoogleEventMapper::new().map_stream(response))
         });
-        async move { Ok(future.await?.boxed()) }.boxed()
+        logged_request.wrap(async move { Ok(future.await?.boxed()) }.boxed())
     }
 }
 
@@ -545,6 +558,13 @@ pub fn into_google(
             },
             top_p: None,
             top_k: None,
+            response_mime_type: request
+                .response_schema
+                .is_some()
+                .then(|| "application/json".to_string()),
+            response_schema: request
+                .response_schema
+                .map(|response_schema| response_schema.schema),
         }),
         safety_settings: None,
         tools: (request.tools.len() > 0).then(|| {
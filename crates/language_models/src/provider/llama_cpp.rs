@@ -0,0 +1,710 @@
+use anyhow::{Result, anyhow};
+use collections::{HashMap, HashSet};
+use futures::{FutureExt, StreamExt, channel::mpsc, future::BoxFuture, future::Shared};
+use gpui::{AnyView, App, AsyncApp, Context, Entity, Subscription, Task};
+use http_client::{DownloadManager, DownloadProgress, DownloadRequest, HttpClient};
+use language_model::{
+    AuthenticateError, LanguageModel, LanguageModelCompletionError, LanguageModelCompletionEvent,
+    LanguageModelId, LanguageModelName, LanguageModelProvider, LanguageModelProviderId,
+    LanguageModelProviderName, LanguageModelProviderState, LanguageModelRequest,
+    LanguageModelToolChoice, LoggedRequest, RateLimiter,
+};
+use llama_cpp::{DEFAULT_SERVER_BINARY, LlamaServer, ServerOptions};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsStore};
+use std::path::PathBuf;
+use std::sync::Arc;
+use ui::{ButtonLike, Indicator, List, prelude::*};
+use util::ResultExt;
+
+use crate::AllLanguageModelSettings;
+use crate::provider::open_ai::{OpenAiEventMapper, into_open_ai};
+use crate::ui::InstructionListItem;
+
+const LLAMA_CPP_INSTALL_URL: &str = "https://github.com/ggml-org/llama.cpp#quick-start";
+
+const PROVIDER_ID: &str = "llama_cpp";
+const PROVIDER_NAME: &str = "llama.cpp";
+
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct LlamaCppSettings {
+    pub server_path: Option<PathBuf>,
+    pub available_models: Vec<AvailableModel>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct AvailableModel {
+    pub name: String,
+    pub display_name: Option<String>,
+    /// A GGUF file on disk. When this isn't set, the model is downloaded from `url`.
+    pub path: Option<PathBuf>,
+    /// Where to download the GGUF file from.
+    pub url: Option<String>,
+    /// The expected SHA-256 of the downloaded file, as a hex string.
+    pub sha256: Option<String>,
+    pub max_tokens: usize,
+    #[serde(default)]
+    pub supports_tools: bool,
+}
+
+impl AvailableModel {
+    /// Where the model's GGUF file is, or will be once it is downloaded.
+    fn file_path(&self) -> Option<PathBuf> {
+        if let Some(path) = &self.path {
+            return Some(path.clone());
+        }
+        let url = self.url.as_ref()?;
+        let file_name = url
+            .split(['?', '#'])
+            .next()?
+            .rsplit('/')
+            .next()
+            .filter(|file_name| !file_name.is_empty())?;
+        Some(paths::llama_cpp_models_dir().join(file_name))
+    }
+
+    fn display_name(&self) -> &str {
+        self.display_name.as_deref().unwrap_or(&self.name)
+    }
+}
+
+enum ModelDownload {
+    InProgress {
+        progress: Option<DownloadProgress>,
+        _task: Task<()>,
+    },
+    Failed(SharedString),
+}
+
+type SharedServerTask = Shared<Task<Result<Arc<LlamaServer>, Arc<anyhow::Error>>>>;
+
+pub struct LlamaCppLanguageModelProvider {
+    http_client: Arc<dyn HttpClient>,
+    state: Entity<State>,
+}
+
+pub struct State {
+    http_client: Arc<dyn HttpClient>,
+    download_manager: DownloadManager,
+    server_binary: Option<PathBuf>,
+    installed_models: HashSet<String>,
+    downloads: HashMap<String, ModelDownload>,
+    /// Only one model is kept loaded at a time, since each one can take up most of the
+    /// machine's memory.
+    server: Option<(ServerOptions, SharedServerTask)>,
+    refresh_task: Option<Task<()>>,
+    _subscription: Subscription,
+}
+
+impl State {
+    fn is_authenticated(&self) -> bool {
+        self.server_binary.is_some()
+    }
+
+    /// Looks for the server binary and for the models that are already on disk.
+    fn refresh(&mut self, cx: &mut Context<Self>) -> Task<()> {
+        let settings = AllLanguageModelSettings::get_global(cx).llama_cpp.clone();
+        cx.spawn(async move |this, cx| {
+            let (server_binary, installed_models) = cx
+                .background_spawn(async move {
+                    let server_binary = match settings.server_path {
+                        Some(path) => path.is_file().then_some(path),
+                        None => which::which(DEFAULT_SERVER_BINARY).ok(),
+                    };
+                    let installed_models = settings
+                        .available_models
+                        .iter()
+                        .filter(|model| model.file_path().is_some_and(|path| path.is_file()))
+                        .map(|model| model.name.clone())
+                        .collect::<HashSet<_>>();
+                    (server_binary, installed_models)
+                })
+                .await;
+
+            this.update(cx, |this, cx| {
+                this.server_binary = server_binary;
+                this.installed_models = installed_models;
+                cx.notify();
+            })
+            .ok();
+        })
+    }
+
+    fn restart_refresh_task(&mut self, cx: &mut Context<Self>) {
+        let task = self.refresh(cx);
+        self.refresh_task = Some(task);
+    }
+
+    fn authenticate(&mut self, cx: &mut Context<Self>) -> Task<Result<(), AuthenticateError>> {
+        if self.is_authenticated() {
+            return Task::ready(Ok(()));
+        }
+
+        let refresh = self.refresh(cx);
+        cx.spawn(async move |this, cx| {
+            refresh.await;
+            if this.read_with(cx, |this, _| this.is_authenticated())? {
+                Ok(())
+            } else {
+                Err(anyhow!("Could not find {DEFAULT_SERVER_BINARY}").into())
+            }
+        })
+    }
+
+    fn download_model(&mut self, model: AvailableModel, cx: &mut Context<Self>) {
+        if matches!(
+            self.downloads.get(&model.name),
+            Some(ModelDownload::InProgress { .. })
+        ) {
+            return;
+        }
+        let (Some(url), Some(destination)) = (model.url.clone(), model.file_path()) else {
+            return;
+        };
+
+        let (progress_tx, mut progress_rx) = mpsc::unbounded();
+        let mut request = DownloadRequest::new(url, destination).progress(progress_tx);
+        if let Some(sha256) = model.sha256.clone() {
+            request = request.sha256(sha256);
+        }
+        let download_manager = self.download_manager.clone();
+        let download = cx.background_spawn(async move { download_manager.download(request).await });
+
+        let name = model.name.clone();
+        let task = cx.spawn(async move |this, cx| {
+            while let Some(progress) = progress_rx.next().await {
+                let Ok(()) = this.update(cx, |this, cx| {
+                    if let Some(ModelDownload::InProgress {
+                        progress: current, ..
+                    }) = this.downloads.get_mut(&name)
+                    {
+                        *current = Some(progress);
+                        cx.notify();
+                    }
+                }) else {
+                    return;
+                };
+            }
+
+            let result = download.await;
+            this.update(cx, |this, cx| {
+                match result {
+                    Ok(()) => {
+                        this.downloads.remove(&name);
+                        this.installed_models.insert(name);
+                    }
+                    Err(error) => {
+                        log::error!("failed to download {name}: {error:#}");
+                        this.downloads
+                            .insert(name, ModelDownload::Failed(format!("{error:#}").into()));
+                    }
+                }
+                cx.notify();
+            })
+            .ok();
+        });
+
+        self.downloads.insert(
+            model.name,
+            ModelDownload::InProgress {
+                progress: None,
+                _task: task,
+            },
+        );
+        cx.notify();
+    }
+
+    /// Returns the server for `model`, starting it first if another model is loaded.
+    fn server_for_model(
+        &mut self,
+        model: &AvailableModel,
+        cx: &mut Context<Self>,
+    ) -> Result<SharedServerTask> {
+        let binary = self
+            .server_binary
+            .clone()
+            .ok_or_else(|| anyhow!("Could not find {DEFAULT_SERVER_BINARY}"))?;
+        let model_path = model
+            .file_path()
+            .ok_or_else(|| anyhow!("{} has neither a path nor a url", model.name))?;
+        if !self.installed_models.contains(&model.name) {
+            return Err(anyhow!(
+                "{} hasn't been downloaded yet. Download it from the llama.cpp settings in the agent panel.",
+                model.display_name()
+            ));
+        }
+
+        let options = ServerOptions {
+            binary,
+            model: model_path,
+            context_size: model.max_tokens,
+        };
+        if let Some((running_options, server)) = &self.server {
+            if *running_options == options {
+                return Ok(server.clone());
+            }
+        }
+
+        let http_client = self.http_client.clone();
+        let server = cx
+            .background_spawn({
+                let options = options.clone();
+                async move {
+                    LlamaServer::start(http_client.as_ref(), options)
+                        .await
+                        .map(Arc::new)
+                        .map_err(Arc::new)
+                }
+            })
+            .shared();
+        // Dropping the previous server stops its process.
+        self.server = Some((options, server.clone()));
+        Ok(server)
+    }
+}
+
+impl LlamaCppLanguageModelProvider {
+    pub fn new(http_client: Arc<dyn HttpClient>, cx: &mut App) -> Self {
+        let this = Self {
+            http_client: http_client.clone(),
+            state: cx.new(|cx| {
+                let subscription = cx.observe_global::<SettingsStore>({
+                    let mut settings = AllLanguageModelSettings::get_global(cx).llama_cpp.clone();
+                    move |this: &mut State, cx| {
+                        let new_settings = &AllLanguageModelSettings::get_global(cx).llama_cpp;
+                        if &settings != new_settings {
+                            settings = new_settings.clone();
+                            this.restart_refresh_task(cx);
+                            cx.notify();
+                        }
+                    }
+                });
+
+                State {
+                    download_manager: DownloadManager::global(http_client.clone()),
+                    http_client,
+                    server_binary: None,
+                    installed_models: HashSet::default(),
+                    downloads: HashMap::default(),
+                    server: None,
+                    refresh_task: None,
+                    _subscription: subscription,
+                }
+            }),
+        };
+        this.state
+            .update(cx, |state, cx| state.restart_refresh_task(cx));
+        this
+    }
+}
+
+impl LanguageModelProviderState for LlamaCppLanguageModelProvider {
+    type ObservableEntity = State;
+
+    fn observable_entity(&self) -> Option<Entity<Self::ObservableEntity>> {
+        Some(self.state.clone())
+    }
+}
+
+impl LanguageModelProvider for LlamaCppLanguageModelProvider {
+    fn id(&self) -> LanguageModelProviderId {
+        LanguageModelProviderId(PROVIDER_ID.into())
+    }
+
+    fn name(&self) -> LanguageModelProviderName {
+        LanguageModelProviderName(PROVIDER_NAME.into())
+    }
+
+    fn icon(&self) -> IconName {
+        IconName::Ai
+    }
+
+    fn default_model(&self, _: &App) -> Option<Arc<dyn LanguageModel>> {
+        // Selecting a model loads it into memory, which shouldn't happen without the user asking.
+        None
+    }
+
+    fn default_fast_model(&self, _: &App) -> Option<Arc<dyn LanguageModel>> {
+        None
+    }
+
+    fn provided_models(&self, cx: &App) -> Vec<Arc<dyn LanguageModel>> {
+        let installed_models = &self.state.read(cx).installed_models;
+        AllLanguageModelSettings::get_global(cx)
+            .llama_cpp
+            .available_models
+            .iter()
+            .filter(|model| installed_models.contains(&model.name))
+            .map(|model| {
+                Arc::new(LlamaCppLanguageModel {
+                    id: LanguageModelId::from(model.name.clone()),
+                    model: model.clone(),
+                    state: self.state.clone(),
+                    http_client: self.http_client.clone(),
+                    request_limiter: RateLimiter::for_provider(&LanguageModelProviderId(
+                        PROVIDER_ID.into(),
+                    )),
+                }) as Arc<dyn LanguageModel>
+            })
+            .collect()
+    }
+
+    fn is_authenticated(&self, cx: &App) -> bool {
+        self.state.read(cx).is_authenticated()
+    }
+
+    fn authenticate(&self, cx: &mut App) -> Task<Result<(), AuthenticateError>> {
+        self.state.update(cx, |state, cx| state.authenticate(cx))
+    }
+
+    fn configuration_view(&self, _window: &mut Window, cx: &mut App) -> AnyView {
+        let state = self.state.clone();
+        cx.new(|cx| ConfigurationView::new(state, cx)).into()
+    }
+
+    fn reset_credentials(&self, cx: &mut App) -> Task<Result<()>> {
+        let refresh = self.state.update(cx, |state, cx| state.refresh(cx));
+        cx.background_spawn(async move {
+            refresh.await;
+            Ok(())
+        })
+    }
+}
+
+pub struct LlamaCppLanguageModel {
+    id: LanguageModelId,
+    model: AvailableModel,
+    state: Entity<State>,
+    http_client: Arc<dyn HttpClient>,
+    request_limiter: RateLimiter,
+}
+
+impl LanguageModel for LlamaCppLanguageModel {
+    fn id(&self) -> LanguageModelId {
+        self.id.clone()
+    }
+
+    fn name(&self) -> LanguageModelName {
+        LanguageModelName::from(self.model.display_name().to_string())
+    }
+
+    fn provider_id(&self) -> LanguageModelProviderId {
+        LanguageModelProviderId(PROVIDER_ID.into())
+    }
+
+    fn provider_name(&self) -> LanguageModelProviderName {
+        LanguageModelProviderName(PROVIDER_NAME.into())
+    }
+
+    fn supports_tools(&self) -> bool {
+        self.model.supports_tools
+    }
+
+    fn supports_tool_choice(&self, choice: LanguageModelToolChoice) -> bool {
+        self.supports_tools()
+            && match choice {
+                LanguageModelToolChoice::Auto => true,
+                LanguageModelToolChoice::Any => true,
+                LanguageModelToolChoice::None => true,
+            }
+    }
+
+    fn supports_images(&self) -> bool {
+        false
+    }
+
+    fn telemetry_id(&self) -> String {
+        format!("llama_cpp/{}", self.model.name)
+    }
+
+    fn max_token_count(&self) -> usize {
+        self.model.max_tokens
+    }
+
+    fn count_tokens(
+        &self,
+        request: LanguageModelRequest,
+        _cx: &App,
+    ) -> BoxFuture<'static, Result<usize>> {
+        // llama-server can only tokenize once the model is loaded, so estimate instead.
+        let token_count = request
+            .messages
+            .iter()
+            .map(|msg| msg.string_contents().split_whitespace().count())
+            .sum::<usize>();
+
+        let estimated_tokens = (token_count as f64 * 0.75) as usize;
+        async move { Ok(estimated_tokens) }.boxed()
+    }
+
+    fn stream_completion(
+        &self,
+        request: LanguageModelRequest,
+        cx: &AsyncApp,
+    ) -> BoxFuture<
+        'static,
+        Result<
+            futures::stream::BoxStream<
+                'static,
+                Result<LanguageModelCompletionEvent, LanguageModelCompletionError>,
+            >,
+        >,
+    > {
+        let logged_request = LoggedRequest::start(&self.provider_id(), &self.id(), &request);
+        let request = into_open_ai(
+            request,
+            &open_ai::Model::Custom {
+                name: self.model.name.clone(),
+                display_name: self.model.display_name.clone(),
+                max_tokens: self.model.max_tokens,
+                max_output_tokens: None,
+                max_completion_tokens: None,
+            },
+            None,
+        );
+
+        let server = cx
+            .update(|cx| {
+                self.state
+                    .update(cx, |state, cx| state.server_for_model(&self.model, cx))
+            })
+            .and_then(|server| server);
+        let server = match server {
+            Ok(server) => server,
+            Err(error) => return futures::future::ready(Err(error)).boxed(),
+        };
+        let http_client = self.http_client.clone();
+        let completions = self.request_limiter.stream(async move {
+            let server = server.await.map_err(|error| anyhow!("{error:#}"))?;
+            open_ai::stream_completion(http_client.as_ref(), server.api_url(), "", request).await
+        });
+
+        logged_request.wrap(
+            async move {
+                let mapper = OpenAiEventMapper::new();
+                Ok(mapper.map_stream(completions.await?.boxed()).boxed())
+            }
+            .boxed(),
+        )
+    }
+}
+
+struct ConfigurationView {
+    state: Entity<State>,
+    loading_task: Option<Task<()>>,
+}
+
+impl ConfigurationView {
+    pub fn new(state: Entity<State>, cx: &mut Context<Self>) -> Self {
+        let loading_task = Some(cx.spawn({
+            let state = state.clone();
+            async move |this, cx| {
+                if let Some(task) = state.update(cx, |state, cx| state.refresh(cx)).log_err() {
+                    task.await;
+                }
+                this.update(cx, |this, cx| {
+                    this.loading_task = None;
+                    cx.notify();
+                })
+                .log_err();
+            }
+        }));
+
+        cx.observe(&state, |_, _, cx| cx.notify()).detach();
+
+        Self {
+            state,
+            loading_task,
+        }
+    }
+
+    fn render_model(
+        &self,
+        index: usize,
+        model: &AvailableModel,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let state = self.state.read(cx);
+        let status = if state.installed_models.contains(&model.name) {
+            h_flex()
+                .gap_1()
+                .child(Indicator::dot().color(Color::Success))
+                .child(
+                    Label::new("Installed")
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+                .into_any_element()
+        } else if let Some(ModelDownload::InProgress { progress, .. }) =
+            state.downloads.get(&model.name)
+        {
+            let label = match progress {
+                Some(DownloadProgress {
+                    downloaded,
+                    total: Some(total),
+                }) if *total > 0 => format!("Downloading… {}%", downloaded * 100 / total),
+                _ => "Downloading…".to_string(),
+            };
+            Label::new(label)
+                .size(LabelSize::Small)
+                .color(Color::Muted)
+                .into_any_element()
+        } else if model.url.is_some() {
+            let failure = match state.downloads.get(&model.name) {
+                Some(ModelDownload::Failed(error)) => Some(error.clone()),
+                _ => None,
+            };
+            let model = model.clone();
+            h_flex()
+                .id(("download-status", index))
+                .gap_1()
+                .when_some(failure, |this, error| {
+                    this.child(
+                        Icon::new(IconName::Warning)
+                            .size(IconSize::Small)
+                            .color(Color::Warning),
+                    )
+                    .tooltip(ui::Tooltip::text(error))
+                })
+                .child(
+                    Button::new(("download-model", index), "Download")
+                        .icon(IconName::Download)
+                        .icon_position(IconPosition::Start)
+                        .icon_size(IconSize::XSmall)
+                        .on_click(cx.listener(move |this, _, _window, cx| {
+                            let model = model.clone();
+                            this.state
+                                .update(cx, |state, cx| state.download_model(model, cx));
+                        })),
+                )
+                .into_any_element()
+        } else {
+            Label::new("File not found")
+                .size(LabelSize::Small)
+                .color(Color::Error)
+                .into_any_element()
+        };
+
+        h_flex()
+            .id(("llama-cpp-model", index))
+            .w_full()
+            .justify_between()
+            .child(Label::new(model.display_name().to_string()))
+            .child(status)
+    }
+}
+
+impl Render for ConfigurationView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        if self.loading_task.is_some() {
+            return div().child(Label::new("Looking for llama.cpp…")).into_any();
+        }
+
+        let is_authenticated = self.state.read(cx).is_authenticated();
+        let models = AllLanguageModelSettings::get_global(cx)
+            .llama_cpp
+            .available_models
+            .clone();
+
+        v_flex()
+            .gap_2()
+            .child(
+                v_flex()
+                    .gap_1()
+                    .child(Label::new(
+                        "Run GGUF models on this machine with llama.cpp.",
+                    ))
+                    .child(
+                        List::new()
+                            .child(InstructionListItem::text_only(format!(
+                                "Install llama.cpp so that `{DEFAULT_SERVER_BINARY}` is on your PATH, or set `language_models.llama_cpp.server_path`."
+                            )))
+                            .child(InstructionListItem::text_only(
+                                "Add models to `language_models.llama_cpp.available_models`, either with the `path` of a GGUF file or a `url` to download it from.",
+                            )),
+                    ),
+            )
+            .children(
+                models
+                    .iter()
+                    .enumerate()
+                    .map(|(index, model)| self.render_model(index, model, cx)),
+            )
+            .child(
+                h_flex()
+                    .w_full()
+                    .justify_between()
+                    .gap_2()
+                    .child(
+                        Button::new("install-llama-cpp", "Install llama.cpp")
+                            .style(ButtonStyle::Subtle)
+                            .icon(IconName::ArrowUpRight)
+                            .icon_size(IconSize::XSmall)
+                            .icon_color(Color::Muted)
+                            .on_click(move |_, _window, cx| cx.open_url(LLAMA_CPP_INSTALL_URL)),
+                    )
+                    .map(|this| {
+                        if is_authenticated {
+                            this.child(
+                                ButtonLike::new("found")
+                                    .disabled(true)
+                                    .cursor_style(gpui::CursorStyle::Arrow)
+                                    .child(
+                                        h_flex()
+                                            .gap_2()
+                                            .child(Indicator::dot().color(Color::Success))
+                                            .child(Label::new("Found llama-server"))
+                                            .into_any_element(),
+                                    ),
+                            )
+                        } else {
+                            this.child(
+                                Button::new("retry-llama-cpp", "Retry")
+                                    .icon_position(IconPosition::Start)
+                                    .icon_size(IconSize::XSmall)
+                                    .icon(IconName::RotateCw)
+                                    .on_click(cx.listener(move |this, _, _window, cx| {
+                                        this.state
+                                            .update(cx, |state, cx| state.refresh(cx))
+                                            .detach();
+                                    })),
+                            )
+                        }
+                    }),
+            )
+            .into_any()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_model_file_path() {
+        let mut model = AvailableModel {
+            name: "qwen2.5-coder-7b".into(),
+            display_name: None,
+            path: None,
+            url: Some(
+                "https://huggingface.co/Qwen/Qwen2.5-Coder-7B-Instruct-GGUF/resolve/main/qwen2.5-coder-7b-instruct-q4_k_m.gguf?download=true"
+                    .into(),
+            ),
+            sha256: None,
+            max_tokens: 32768,
+            supports_tools: true,
+        };
+        assert_eq!(
+            model.file_path(),
+            Some(paths::llama_cpp_models_dir().join("qwen2.5-coder-7b-instruct-q4_k_m.gguf"))
+        );
+
+        model.path = Some(PathBuf::from("/models/qwen.gguf"));
+        assert_eq!(model.file_path(), Some(PathBuf::from("/models/qwen.gguf")));
+
+        model.path = None;
+        model.url = Some("https://example.com/models/".into());
+        assert_eq!(model.file_path(), None);
+    }
+}
@@ -10,12 +10,12 @@ use language_model::{
     StopReason,
 };
 use language_model::{
-    LanguageModel, LanguageModelId, LanguageModelName, LanguageModelProvider,
-    LanguageModelProviderId, LanguageModelProviderName, LanguageModelProviderState,
-    LanguageModelRequest, RateLimiter, Role,
+    Embedding, EmbeddingModel, LanguageModel, LanguageModelId, LanguageModelName,
+    LanguageModelProvider, LanguageModelProviderId, LanguageModelProviderName,
+    LanguageModelProviderState, LanguageModelRequest, LoggedRequest, RateLimiter, Role,
 };
 use lmstudio::{
-    ChatCompletionRequest, ChatMessage, ModelType, ResponseStreamEvent, get_models,
+    ChatCompletionRequest, ChatMessage, ModelType, ResponseStreamEvent, embed, get_models,
     stream_chat_completion,
 };
 use schemars::JsonSchema;
@@ -59,6 +59,7 @@ pub struct LmStudioLanguageModelProvider {
 pub struct State {
     http_client: Arc<dyn HttpClient>,
     available_models: Vec<lmstudio::Model>,
+    available_embedding_models: Vec<String>,
     fetch_model_task: Option<Task<Result<()>>>,
     _subscription: Subscription,
 }
@@ -77,9 +78,17 @@ impl State {
         cx.spawn(async move |this, cx| {
             let models = get_models(http_client.as_ref(), &api_url, None).await?;
 
+            let (embedding_models, models): (Vec<_>, Vec<_>) = models
+                .into_iter()
+                .partition(|model| model.r#type == ModelType::Embeddings);
+            let mut embedding_models = embedding_models
+                .into_iter()
+                .map(|model| model.id)
+                .collect::<Vec<_>>();
+            embedding_models.sort();
+
             let mut models: Vec<lmstudio::Model> = models
                 .into_iter()
-                .filter(|model| model.r#type != ModelType::Embeddings)
                 .map(|model| {
                     lmstudio::Model::new(
                         &model.id,
@@ -94,6 +103,7 @@ impl State {
 
             this.update(cx, |this, cx| {
                 this.available_models = models;
+                this.available_embedding_models = embedding_models;
                 cx.notify();
             })
         })
@@ -134,6 +144,7 @@ impl LmStudioLanguageModelProvider {
                 State {
                     http_client,
                     available_models: Default::default(),
+                    available_embedding_models: Default::default(),
                     fetch_model_task: None,
                     _subscription: subscription,
                 }
@@ -210,12 +221,36 @@ impl LanguageModelProvider for LmStudioLanguageModelProvider {
                     id: LanguageModelId::from(model.name.clone()),
                     model: model.clone(),
                     http_client: self.http_client.clone(),
-                    request_limiter: RateLimiter::new(4),
+                    request_limiter: RateLimiter::for_provider(&LanguageModelProviderId(
+                        PROVIDER_ID.into(),
+                    )),
                 }) as Arc<dyn LanguageModel>
             })
             .collect()
     }
 
+    fn provided_embedding_models(&self, cx: &App) -> Vec<Arc<dyn EmbeddingModel>> {
+        let api_url = AllLanguageModelSettings::get_global(cx)
+            .lmstudio
+            .api_url
+            .clone();
+        self.state
+            .read(cx)
+            .available_embedding_models
+            .iter()
+            .map(|name| {
+                Arc::new(LmStudioEmbeddingModel {
+                    name: name.clone(),
+                    api_url: api_url.clone(),
+                    http_client: self.http_client.clone(),
+                    request_limiter: RateLimiter::for_provider_embeddings(
+                        &LanguageModelProviderId(PROVIDER_ID.into()),
+                    ),
+                }) as Arc<dyn EmbeddingModel>
+            })
+            .collect()
+    }
+
     fn is_authenticated(&self, cx: &App) -> bool {
         self.state.read(cx).is_authenticated()
     }
@@ -234,6 +269,53 @@ impl LanguageModelProvider for LmStudioLanguageModelProvider {
     }
 }
 
+pub struct LmStudioEmbeddingModel {
+    name: String,
+    api_url: String,
+    http_client: Arc<dyn HttpClient>,
+    request_limiter: RateLimiter,
+}
+
+impl EmbeddingModel for LmStudioEmbeddingModel {
+    fn id(&self) -> LanguageModelId {
+        LanguageModelId::from(self.name.clone())
+    }
+
+    fn name(&self) -> LanguageModelName {
+        LanguageModelName::from(self.name.clone())
+    }
+
+    fn provider_id(&self) -> LanguageModelProviderId {
+        LanguageModelProviderId(PROVIDER_ID.into())
+    }
+
+    fn max_batch_size(&self) -> usize {
+        256
+    }
+
+    fn embed_batch(&self, texts: Vec<String>) -> BoxFuture<'static, Result<Vec<Embedding>>> {
+        let http_client = self.http_client.clone();
+        let api_url = self.api_url.clone();
+        let name = self.name.clone();
+        self.request_limiter
+            .run(async move {
+                let response = embed(
+                    http_client.as_ref(),
+                    &api_url,
+                    &name,
+                    texts.iter().map(String::as_str),
+                )
+                .await?;
+                Ok(response
+                    .data
+                    .into_iter()
+                    .map(|data| Embedding::new(data.embedding))
+                    .collect())
+            })
+            .boxed()
+    }
+}
+
 pub struct LmStudioLanguageModel {
     id: LanguageModelId,
     model: lmstudio::Model,
@@ -420,13 +502,16 @@ impl LanguageModel for LmStudioLanguageModel {
             BoxStream<'static, Result<LanguageModelCompletionEvent, LanguageModelCompletionError>>,
         >,
     > {
+        let logged_request = LoggedRequest::start(&self.provider_id(), &self.id(), &request);
         let request = self.to_lmstudio_request(request);
         let completions = self.stream_completion(request, cx);
-        async move {
-            let mapper = LmStudioEventMapper::new();
-            Ok(mapper.map_stream(completions.await?).boxed())
-        }
-        .boxed()
+        logged_request.wrap(
+            async move {
+                let mapper = LmStudioEventMapper::new();
+                Ok(mapper.map_stream(completions.await?).boxed())
+            }
+            .boxed(),
+        )
     }
 }
 
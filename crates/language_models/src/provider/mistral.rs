@@ -7,13 +7,13 @@ use futures::{FutureExt, StreamExt, future::BoxFuture};
 use gpui::{
     AnyView, App, AsyncApp, Context, Entity, FontStyle, Subscription, Task, TextStyle, WhiteSpace,
 };
-use http_client::HttpClient;
+use http_client::{HttpClient, Url};
 use language_model::{
     AuthenticateError, LanguageModel, LanguageModelCompletionError, LanguageModelCompletionEvent,
     LanguageModelId, LanguageModelName, LanguageModelProvider, LanguageModelProviderId,
     LanguageModelProviderName, LanguageModelProviderState, LanguageModelRequest,
-    LanguageModelToolChoice, LanguageModelToolResultContent, LanguageModelToolUse, MessageContent,
-    RateLimiter, Role, StopReason,
+    LanguageModelToolChoice, LanguageModelToolResultContent, LanguageModelToolUse, LoggedRequest,
+    MessageContent, RateLimiter, Role, StopReason,
 };
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -157,7 +157,9 @@ impl MistralLanguageModelProvider {
             model,
             state: self.state.clone(),
             http_client: self.http_client.clone(),
-            request_limiter: RateLimiter::new(4),
+            request_limiter: RateLimiter::for_provider(&LanguageModelProviderId(
+                PROVIDER_ID.into(),
+            )),
         })
     }
 }
@@ -183,6 +185,10 @@ impl LanguageModelProvider for MistralLanguageModelProvider {
         IconName::AiMistral
     }
 
+    fn api_url(&self, cx: &App) -> Option<Url> {
+        Url::parse(&AllLanguageModelSettings::get_global(cx).mistral.api_url).ok()
+    }
+
     fn default_model(&self, _cx: &App) -> Option<Arc<dyn LanguageModel>> {
         Some(self.create_language_model(mistral::Model::default()))
     }
@@ -227,7 +233,9 @@ impl LanguageModelProvider for MistralLanguageModelProvider {
                     model,
                     state: self.state.clone(),
                     http_client: self.http_client.clone(),
-                    request_limiter: RateLimiter::new(4),
+                    request_limiter: RateLimiter::for_provider(&LanguageModelProviderId(
+                        PROVIDER_ID.into(),
+                    )),
                 }) as Arc<dyn LanguageModel>
             })
             .collect()
@@ -365,6 +373,7 @@ impl LanguageModel for MistralLanguageModel {
             BoxStream<'static, Result<LanguageModelCompletionEvent, LanguageModelCompletionError>>,
         >,
     > {
+        let logged_request = LoggedRequest::start(&self.provider_id(), &self.id(), &request);
         let request = into_mistral(
             request,
             self.model.id().to_string(),
@@ -372,12 +381,14 @@ impl LanguageModel for MistralLanguageModel {
         );
         let stream = self.stream_completion(request, cx);
 
-        async move {
-            let stream = stream.await?;
-            let mapper = MistralEventMapper::new();
-            Ok(mapper.map_stream(stream).boxed())
-        }
-        .boxed()
+        logged_request.wrap(
+            async move {
+                let stream = stream.await?;
+                let mapper = MistralEventMapper::new();
+                Ok(mapper.map_stream(stream).boxed())
+            }
+            .boxed(),
+        )
     }
 }
 
@@ -819,6 +830,7 @@ mod tests {
             intent: None,
             mode: None,
             stop: Vec::new(),
+            response_schema: None,
         };
 
         let model_name = "mistral-medium-latest".to_string();
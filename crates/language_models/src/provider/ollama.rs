@@ -4,15 +4,15 @@ use futures::{Stream, TryFutureExt, stream};
 use gpui::{AnyView, App, AsyncApp, Context, Subscription, Task};
 use http_client::HttpClient;
 use language_model::{
-    AuthenticateError, LanguageModel, LanguageModelCompletionError, LanguageModelCompletionEvent,
-    LanguageModelId, LanguageModelName, LanguageModelProvider, LanguageModelProviderId,
-    LanguageModelProviderName, LanguageModelProviderState, LanguageModelRequest,
-    LanguageModelRequestTool, LanguageModelToolChoice, LanguageModelToolUse,
-    LanguageModelToolUseId, MessageContent, RateLimiter, Role, StopReason,
+    AuthenticateError, Embedding, EmbeddingModel, LanguageModel, LanguageModelCompletionError,
+    LanguageModelCompletionEvent, LanguageModelId, LanguageModelName, LanguageModelProvider,
+    LanguageModelProviderId, LanguageModelProviderName, LanguageModelProviderState,
+    LanguageModelRequest, LanguageModelRequestTool, LanguageModelToolChoice, LanguageModelToolUse,
+    LanguageModelToolUseId, LoggedRequest, MessageContent, RateLimiter, Role, StopReason,
 };
 use ollama::{
     ChatMessage, ChatOptions, ChatRequest, ChatResponseDelta, KeepAlive, OllamaFunctionTool,
-    OllamaToolCall, get_models, show_model, stream_chat_completion,
+    OllamaToolCall, embed, get_models, show_model, stream_chat_completion,
 };
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -65,6 +65,7 @@ pub struct OllamaLanguageModelProvider {
 pub struct State {
     http_client: Arc<dyn HttpClient>,
     available_models: Vec<ollama::Model>,
+    available_embedding_models: Vec<String>,
     fetch_model_task: Option<Task<Result<()>>>,
     _subscription: Subscription,
 }
@@ -83,29 +84,35 @@ impl State {
         cx.spawn(async move |this, cx| {
             let models = get_models(http_client.as_ref(), &api_url, None).await?;
 
-            let tasks = models
+            // Since there is no metadata from the Ollama API
+            // indicating which models are embedding models,
+            // simply treat models with "-embed" in their name as such
+            let (embedding_models, models): (Vec<_>, Vec<_>) = models
                 .into_iter()
-                // Since there is no metadata from the Ollama API
-                // indicating which models are embedding models,
-                // simply filter out models with "-embed" in their name
-                .filter(|model| !model.name.contains("-embed"))
-                .map(|model| {
-                    let http_client = Arc::clone(&http_client);
-                    let api_url = api_url.clone();
-                    async move {
-                        let name = model.name.as_str();
-                        let capabilities = show_model(http_client.as_ref(), &api_url, name).await?;
-                        let ollama_model = ollama::Model::new(
-                            name,
-                            None,
-                            None,
-                            Some(capabilities.supports_tools()),
-                            Some(capabilities.supports_vision()),
-                            Some(capabilities.supports_thinking()),
-                        );
-                        Ok(ollama_model)
-                    }
-                });
+                .partition(|model| model.name.contains("-embed"));
+            let mut embedding_models = embedding_models
+                .into_iter()
+                .map(|model| model.name)
+                .collect::<Vec<_>>();
+            embedding_models.sort();
+
+            let tasks = models.into_iter().map(|model| {
+                let http_client = Arc::clone(&http_client);
+                let api_url = api_url.clone();
+                async move {
+                    let name = model.name.as_str();
+                    let capabilities = show_model(http_client.as_ref(), &api_url, name).await?;
+                    let ollama_model = ollama::Model::new(
+                        name,
+                        None,
+                        None,
+                        Some(capabilities.supports_tools()),
+                        Some(capabilities.supports_vision()),
+                        Some(capabilities.supports_thinking()),
+                    );
+                    Ok(ollama_model)
+                }
+            });
 
             // Rate-limit capability fetches
             // since there is an arbitrary number of models available
@@ -120,6 +127,7 @@ impl State {
 
             this.update(cx, |this, cx| {
                 this.available_models = ollama_models;
+                this.available_embedding_models = embedding_models;
                 cx.notify();
             })
         })
@@ -160,6 +168,7 @@ impl OllamaLanguageModelProvider {
                 State {
                     http_client,
                     available_models: Default::default(),
+                    available_embedding_models: Default::default(),
                     fetch_model_task: None,
                     _subscription: subscription,
                 }
@@ -235,7 +244,9 @@ impl LanguageModelProvider for OllamaLanguageModelProvider {
                     id: LanguageModelId::from(model.name.clone()),
                     model: model.clone(),
                     http_client: self.http_client.clone(),
-                    request_limiter: RateLimiter::new(4),
+                    request_limiter: RateLimiter::for_provider(&LanguageModelProviderId(
+                        PROVIDER_ID.into(),
+                    )),
                 }) as Arc<dyn LanguageModel>
             })
             .collect::<Vec<_>>();
@@ -243,6 +254,28 @@ impl LanguageModelProvider for OllamaLanguageModelProvider {
         models
     }
 
+    fn provided_embedding_models(&self, cx: &App) -> Vec<Arc<dyn EmbeddingModel>> {
+        let api_url = AllLanguageModelSettings::get_global(cx)
+            .ollama
+            .api_url
+            .clone();
+        self.state
+            .read(cx)
+            .available_embedding_models
+            .iter()
+            .map(|name| {
+                Arc::new(OllamaEmbeddingModel {
+                    name: name.clone(),
+                    api_url: api_url.clone(),
+                    http_client: self.http_client.clone(),
+                    request_limiter: RateLimiter::for_provider_embeddings(
+                        &LanguageModelProviderId(PROVIDER_ID.into()),
+                    ),
+                }) as Arc<dyn EmbeddingModel>
+            })
+            .collect()
+    }
+
     fn is_authenticated(&self, cx: &App) -> bool {
         self.state.read(cx).is_authenticated()
     }
@@ -262,6 +295,53 @@ impl LanguageModelProvider for OllamaLanguageModelProvider {
     }
 }
 
+pub struct OllamaEmbeddingModel {
+    name: String,
+    api_url: String,
+    http_client: Arc<dyn HttpClient>,
+    request_limiter: RateLimiter,
+}
+
+impl EmbeddingModel for OllamaEmbeddingModel {
+    fn id(&self) -> LanguageModelId {
+        LanguageModelId::from(self.name.clone())
+    }
+
+    fn name(&self) -> LanguageModelName {
+        LanguageModelName::from(self.name.clone())
+    }
+
+    fn provider_id(&self) -> LanguageModelProviderId {
+        LanguageModelProviderId(PROVIDER_ID.into())
+    }
+
+    fn max_batch_size(&self) -> usize {
+        64
+    }
+
+    fn embed_batch(&self, texts: Vec<String>) -> BoxFuture<'static, Result<Vec<Embedding>>> {
+        let http_client = self.http_client.clone();
+        let api_url = self.api_url.clone();
+        let name = self.name.clone();
+        self.request_limiter
+            .run(async move {
+                let response = embed(
+                    http_client.as_ref(),
+                    &api_url,
+                    &name,
+                    texts.iter().map(String::as_str),
+                )
+                .await?;
+                Ok(response
+                    .embeddings
+                    .into_iter()
+                    .map(Embedding::new)
+                    .collect())
+            })
+            .boxed()
+    }
+}
+
 pub struct OllamaLanguageModel {
     id: LanguageModelId,
     model: ollama::Model,
@@ -336,6 +416,9 @@ impl OllamaLanguageModel {
             }),
             think: self.model.supports_thinking,
             tools: request.tools.into_iter().map(tool_into_ollama).collect(),
+            format: request
+                .response_schema
+                .map(|response_schema| response_schema.schema),
         }
     }
 }
@@ -365,6 +448,10 @@ impl LanguageModel for OllamaLanguageModel {
         self.model.supports_vision.unwrap_or(false)
     }
 
+    fn supports_structured_output(&self) -> bool {
+        true
+    }
+
     fn supports_tool_choice(&self, choice: LanguageModelToolChoice) -> bool {
         match choice {
             LanguageModelToolChoice::Auto => false,
@@ -408,6 +495,7 @@ impl LanguageModel for OllamaLanguageModel {
             BoxStream<'static, Result<LanguageModelCompletionEvent, LanguageModelCompletionError>>,
         >,
     > {
+        let logged_request = LoggedRequest::start(&self.provider_id(), &self.id(), &request);
         let request = self.to_ollama_request(request);
 
         let http_client = self.http_client.clone();
@@ -424,7 +512,7 @@ impl LanguageModel for OllamaLanguageModel {
             Ok(stream)
         });
 
-        future.map_ok(|f| f.boxed()).boxed()
+        logged_request.wrap(future.map_ok(|f| f.boxed()).boxed())
     }
 }
 
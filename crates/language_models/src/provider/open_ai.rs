@@ -7,13 +7,13 @@ use futures::{FutureExt, StreamExt, future::BoxFuture};
 use gpui::{
     AnyView, App, AsyncApp, Context, Entity, FontStyle, Subscription, Task, TextStyle, WhiteSpace,
 };
-use http_client::HttpClient;
+use http_client::{HttpClient, Url};
 use language_model::{
-    AuthenticateError, LanguageModel, LanguageModelCompletionError, LanguageModelCompletionEvent,
-    LanguageModelId, LanguageModelName, LanguageModelProvider, LanguageModelProviderId,
-    LanguageModelProviderName, LanguageModelProviderState, LanguageModelRequest,
-    LanguageModelToolChoice, LanguageModelToolResultContent, LanguageModelToolUse, MessageContent,
-    RateLimiter, Role, StopReason,
+    AuthenticateError, Embedding, EmbeddingModel, LanguageModel, LanguageModelCompletionError,
+    LanguageModelCompletionEvent, LanguageModelId, LanguageModelName, LanguageModelProvider,
+    LanguageModelProviderId, LanguageModelProviderName, LanguageModelProviderState,
+    LanguageModelRequest, LanguageModelToolChoice, LanguageModelToolResultContent,
+    LanguageModelToolUse, LoggedRequest, MessageContent, RateLimiter, Role, StopReason,
 };
 use open_ai::{ImageUrl, Model, ResponseStreamEvent, stream_completion};
 use schemars::JsonSchema;
@@ -156,7 +156,9 @@ impl OpenAiLanguageModelProvider {
             model,
             state: self.state.clone(),
             http_client: self.http_client.clone(),
-            request_limiter: RateLimiter::new(4),
+            request_limiter: RateLimiter::for_provider(&LanguageModelProviderId(
+                PROVIDER_ID.into(),
+            )),
         })
     }
 }
@@ -182,6 +184,10 @@ impl LanguageModelProvider for OpenAiLanguageModelProvider {
         IconName::AiOpenAi
     }
 
+    fn api_url(&self, cx: &App) -> Option<Url> {
+        Url::parse(&AllLanguageModelSettings::get_global(cx).openai.api_url).ok()
+    }
+
     fn default_model(&self, _cx: &App) -> Option<Arc<dyn LanguageModel>> {
         Some(self.create_language_model(open_ai::Model::default()))
     }
@@ -190,6 +196,28 @@ impl LanguageModelProvider for OpenAiLanguageModelProvider {
         Some(self.create_language_model(open_ai::Model::default_fast()))
     }
 
+    fn provided_embedding_models(&self, cx: &App) -> Vec<Arc<dyn EmbeddingModel>> {
+        let api_url = AllLanguageModelSettings::get_global(cx)
+            .openai
+            .api_url
+            .clone();
+        let api_key = self.state.read(cx).api_key.clone();
+        [
+            open_ai::OpenAiEmbeddingModel::TextEmbedding3Small,
+            open_ai::OpenAiEmbeddingModel::TextEmbedding3Large,
+        ]
+        .into_iter()
+        .map(|model| {
+            Arc::new(OpenAiEmbeddingModel::new(
+                self.http_client.clone(),
+                model,
+                api_url.clone(),
+                api_key.clone(),
+            )) as Arc<dyn EmbeddingModel>
+        })
+        .collect()
+    }
+
     fn provided_models(&self, cx: &App) -> Vec<Arc<dyn LanguageModel>> {
         let mut models = BTreeMap::default();
 
@@ -239,6 +267,26 @@ impl LanguageModelProvider for OpenAiLanguageModelProvider {
     fn reset_credentials(&self, cx: &mut App) -> Task<Result<()>> {
         self.state.update(cx, |state, cx| state.reset_api_key(cx))
     }
+
+    fn transcribe(&self, wav: Vec<u8>, cx: &App) -> Task<Result<String>> {
+        let http_client = self.http_client.clone();
+        let api_url = AllLanguageModelSettings::get_global(cx)
+            .openai
+            .api_url
+            .clone();
+        let api_key = self.state.read(cx).api_key.clone();
+        cx.background_spawn(async move {
+            let api_key = api_key.context("Missing OpenAI API Key")?;
+            open_ai::transcribe(
+                http_client.as_ref(),
+                &api_url,
+                &api_key,
+                open_ai::DEFAULT_TRANSCRIPTION_MODEL,
+                wav,
+            )
+            .await
+        })
+    }
 }
 
 pub struct OpenAiLanguageModel {
@@ -300,6 +348,10 @@ impl LanguageModel for OpenAiLanguageModel {
         false
     }
 
+    fn supports_structured_output(&self) -> bool {
+        self.model.supports_structured_output()
+    }
+
     fn supports_tool_choice(&self, choice: LanguageModelToolChoice) -> bool {
         match choice {
             LanguageModelToolChoice::Auto => true,
@@ -341,13 +393,87 @@ impl LanguageModel for OpenAiLanguageModel {
             >,
         >,
     > {
+        let logged_request = LoggedRequest::start(&self.provider_id(), &self.id(), &request);
         let request = into_open_ai(request, &self.model, self.max_output_tokens());
         let completions = self.stream_completion(request, cx);
-        async move {
-            let mapper = OpenAiEventMapper::new();
-            Ok(mapper.map_stream(completions.await?).boxed())
+        logged_request.wrap(
+            async move {
+                let mapper = OpenAiEventMapper::new();
+                Ok(mapper.map_stream(completions.await?).boxed())
+            }
+            .boxed(),
+        )
+    }
+}
+
+pub struct OpenAiEmbeddingModel {
+    model: open_ai::OpenAiEmbeddingModel,
+    http_client: Arc<dyn HttpClient>,
+    api_url: String,
+    api_key: Option<String>,
+    request_limiter: RateLimiter,
+}
+
+impl OpenAiEmbeddingModel {
+    pub fn new(
+        http_client: Arc<dyn HttpClient>,
+        model: open_ai::OpenAiEmbeddingModel,
+        api_url: String,
+        api_key: Option<String>,
+    ) -> Self {
+        Self {
+            model,
+            http_client,
+            api_url,
+            api_key,
+            request_limiter: RateLimiter::for_provider_embeddings(&LanguageModelProviderId(
+                PROVIDER_ID.into(),
+            )),
         }
-        .boxed()
+    }
+}
+
+impl EmbeddingModel for OpenAiEmbeddingModel {
+    fn id(&self) -> LanguageModelId {
+        LanguageModelId::from(self.model.id().to_string())
+    }
+
+    fn name(&self) -> LanguageModelName {
+        LanguageModelName::from(self.model.id().to_string())
+    }
+
+    fn provider_id(&self) -> LanguageModelProviderId {
+        LanguageModelProviderId(PROVIDER_ID.into())
+    }
+
+    fn max_batch_size(&self) -> usize {
+        // From https://platform.openai.com/docs/api-reference/embeddings/create
+        2048
+    }
+
+    fn embed_batch(&self, texts: Vec<String>) -> BoxFuture<'static, Result<Vec<Embedding>>> {
+        let http_client = self.http_client.clone();
+        let api_url = self.api_url.clone();
+        let api_key = self.api_key.clone();
+        let model = self.model;
+        self.request_limiter
+            .run(async move {
+                let api_key = api_key.context("Missing OpenAI API Key")?;
+                let response = open_ai::embed(
+                    http_client.as_ref(),
+                    &api_url,
+                    &api_key,
+                    model,
+                    texts.iter().map(String::as_str),
+                )
+                .await?;
+                Ok(response
+                    .data
+                    .into_iter()
+                    .map(|data| Embedding::new(data.embedding))
+                    .collect())
+            })
+            .boxed()
     }
 }
 
@@ -460,6 +586,15 @@ pub fn into_open_ai(
             LanguageModelToolChoice::Any => open_ai::ToolChoice::Required,
             LanguageModelToolChoice::None => open_ai::ToolChoice::None,
         }),
+        response_format: request.response_schema.map(|response_schema| {
+            open_ai::ResponseFormat::JsonSchema {
+                json_schema: open_ai::JsonSchemaFormat {
+                    name: response_schema.name,
+                    schema: response_schema.schema,
+                    strict: false,
+                },
+            }
+        }),
     }
 }
 
@@ -871,6 +1006,7 @@ mod tests {
             tool_choice: None,
             stop: vec![],
             temperature: None,
+            response_schema: None,
         };
 
         // Validate that all models are supported by tiktoken-rs
@@ -1,12 +1,17 @@
+use std::path::PathBuf;
 use std::sync::Arc;
 
 use anyhow::Result;
+use collections::HashMap;
 use gpui::App;
-use language_model::LanguageModelCacheConfiguration;
+use language_model::{
+    LanguageModelCacheConfiguration, LanguageModelProviderId, RateLimiter, RequestLimits,
+    set_request_logging_enabled,
+};
 use project::Fs;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use settings::{Settings, SettingsSources, update_settings_file};
+use settings::{Settings, SettingsSources, SettingsStore, update_settings_file};
 
 use crate::provider::{
     self,
@@ -16,6 +21,7 @@ use crate::provider::{
     copilot_chat::CopilotChatSettings,
     deepseek::DeepSeekSettings,
     google::GoogleSettings,
+    llama_cpp::LlamaCppSettings,
     lmstudio::LmStudioSettings,
     mistral::MistralSettings,
     ollama::OllamaSettings,
@@ -27,6 +33,10 @@ use crate::provider::{
 pub fn init(fs: Arc<dyn Fs>, cx: &mut App) {
     AllLanguageModelSettings::register(cx);
 
+    apply_request_settings(cx);
+    cx.observe_global::<SettingsStore>(apply_request_settings)
+        .detach();
+
     if AllLanguageModelSettings::get_global(cx)
         .openai
         .needs_setting_migration
@@ -56,6 +66,12 @@ pub fn init(fs: Arc<dyn Fs>, cx: &mut App) {
     }
 }
 
+fn apply_request_settings(cx: &mut App) {
+    let settings = AllLanguageModelSettings::get_global(cx);
+    RateLimiter::set_provider_limits(settings.request_limits.clone());
+    set_request_logging_enabled(settings.log_requests);
+}
+
 #[derive(Default)]
 pub struct AllLanguageModelSettings {
     pub anthropic: AnthropicSettings,
@@ -67,8 +83,11 @@ pub struct AllLanguageModelSettings {
     pub google: GoogleSettings,
     pub copilot_chat: CopilotChatSettings,
     pub lmstudio: LmStudioSettings,
+    pub llama_cpp: LlamaCppSettings,
     pub deepseek: DeepSeekSettings,
     pub mistral: MistralSettings,
+    pub request_limits: HashMap<LanguageModelProviderId, RequestLimits>,
+    pub log_requests: bool,
 }
 
 #[derive(Default, Clone, Debug, Serialize, Deserialize, PartialEq, JsonSchema)]
@@ -77,6 +96,7 @@ pub struct AllLanguageModelSettingsContent {
     pub bedrock: Option<AmazonBedrockSettingsContent>,
     pub ollama: Option<OllamaSettingsContent>,
     pub lmstudio: Option<LmStudioSettingsContent>,
+    pub llama_cpp: Option<LlamaCppSettingsContent>,
     pub openai: Option<OpenAiSettingsContent>,
     pub open_router: Option<OpenRouterSettingsContent>,
     #[serde(rename = "zed.dev")]
@@ -85,6 +105,25 @@ pub struct AllLanguageModelSettingsContent {
     pub deepseek: Option<DeepseekSettingsContent>,
     pub copilot_chat: Option<CopilotChatSettingsContent>,
     pub mistral: Option<MistralSettingsContent>,
+    /// Limits applied to the requests made to each provider, keyed by provider ID.
+    pub request_limits: Option<HashMap<String, RequestLimitsContent>>,
+    /// Whether to write the payload and response of every request to the
+    /// language model log, which can be opened with `dev: open language model log`.
+    ///
+    /// Default: false
+    pub log_requests: Option<bool>,
+}
+
+#[derive(Default, Clone, Debug, Serialize, Deserialize, PartialEq, JsonSchema)]
+pub struct RequestLimitsContent {
+    /// The maximum number of requests to the provider that may be in flight at once.
+    ///
+    /// Default: 4
+    pub max_concurrent_requests: Option<usize>,
+    /// The maximum number of requests to the provider that may be started within a minute.
+    ///
+    /// Default: unlimited
+    pub requests_per_minute: Option<usize>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, JsonSchema)]
@@ -185,6 +224,13 @@ pub struct LmStudioSettingsContent {
     pub available_models: Option<Vec<provider::lmstudio::AvailableModel>>,
 }
 
+#[derive(Default, Clone, Debug, Serialize, Deserialize, PartialEq, JsonSchema)]
+pub struct LlamaCppSettingsContent {
+    /// The path to llama.cpp's `llama-server` binary. When unset, it is looked up on the `PATH`.
+    pub server_path: Option<PathBuf>,
+    pub available_models: Option<Vec<provider::llama_cpp::AvailableModel>>,
+}
+
 #[derive(Default, Clone, Debug, Serialize, Deserialize, PartialEq, JsonSchema)]
 pub struct DeepseekSettingsContent {
     pub api_url: Option<String>,
@@ -359,6 +405,21 @@ impl settings::Settings for AllLanguageModelSettings {
                 lmstudio.as_ref().and_then(|s| s.available_models.clone()),
             );
 
+            // llama.cpp
+            let llama_cpp = value.llama_cpp.clone();
+
+            merge(
+                &mut settings.llama_cpp.server_path,
+                llama_cpp
+                    .as_ref()
+                    .and_then(|s| s.server_path.clone())
+                    .map(Some),
+            );
+            merge(
+                &mut settings.llama_cpp.available_models,
+                llama_cpp.as_ref().and_then(|s| s.available_models.clone()),
+            );
+
             // DeepSeek
             let deepseek = value.deepseek.clone();
 
@@ -431,6 +492,23 @@ impl settings::Settings for AllLanguageModelSettings {
                     .as_ref()
                     .and_then(|s| s.available_models.clone()),
             );
+
+            merge(&mut settings.log_requests, value.log_requests);
+
+            // Request limits
+            for (provider_id, limits) in value.request_limits.iter().flatten() {
+                let settings = settings
+                    .request_limits
+                    .entry(LanguageModelProviderId(provider_id.clone().into()))
+                    .or_default();
+                merge(
+                    &mut settings.max_concurrent_requests,
+                    limits.max_concurrent_requests,
+                );
+                if limits.requests_per_minute.is_some() {
+                    settings.requests_per_minute = limits.requests_per_minute;
+                }
+            }
         }
 
         Ok(settings)
@@ -29,6 +29,8 @@ use std::{
 use task::{AdapterSchemas, TaskTemplate, TaskTemplates, VariableName};
 use util::{ResultExt, archive::extract_zip, fs::remove_matching, maybe, merge_json_value_into};
 
+use crate::download_server_release;
+
 const SERVER_PATH: &str =
     "node_modules/vscode-langservers-extracted/bin/vscode-json-language-server";
 
@@ -460,18 +462,21 @@ impl LspAdapter for NodeVersionAdapter {
         let destination_container_path =
             container_dir.join(format!("{}-{}-tmp", Self::SERVER_NAME, version.name));
         if fs::metadata(&destination_path).await.is_err() {
-            let mut response = delegate
-                .http_client()
-                .get(&version.url, Default::default(), true)
-                .await
-                .context("downloading release")?;
+            let archive_path = download_server_release(
+                delegate,
+                &version.url,
+                &format!("{}-{}", Self::SERVER_NAME, version.name),
+            )
+            .await?;
+            let archive = fs::File::open(&archive_path).await?;
             if version.url.ends_with(".zip") {
-                extract_zip(&destination_container_path, response.body_mut()).await?;
+                extract_zip(&destination_container_path, archive).await?;
             } else if version.url.ends_with(".tar.gz") {
-                let decompressed_bytes = GzipDecoder::new(BufReader::new(response.body_mut()));
+                let decompressed_bytes = GzipDecoder::new(BufReader::new(archive));
                 let archive = Archive::new(decompressed_bytes);
                 archive.unpack(&destination_container_path).await?;
             }
+            fs::remove_file(&archive_path).await.log_err();
 
             fs::copy(
                 destination_container_path.join(format!(
@@ -1,5 +1,6 @@
 use anyhow::Context as _;
 use gpui::{App, UpdateGlobal};
+use http_client::{DownloadManager, DownloadRequest};
 use json::json_task_context;
 use node_runtime::NodeRuntime;
 use python::PyprojectTomlManifestProvider;
@@ -7,7 +8,7 @@ use rust::CargoManifestProvider;
 use rust_embed::RustEmbed;
 use settings::SettingsStore;
 use smol::stream::StreamExt;
-use std::{str, sync::Arc};
+use std::{path::PathBuf, str, sync::Arc};
 use util::{ResultExt, asset_str};
 
 pub use language::*;
@@ -347,6 +348,22 @@ fn register_language(
     );
 }
 
+/// Downloads a language server release into the temporary directory, rather than the
+/// server's container directory, so that an interrupted download is never mistaken for a
+/// cached binary. An interrupted download is resumed on the next attempt.
+async fn download_server_release(
+    delegate: &dyn LspAdapterDelegate,
+    url: &str,
+    file_name: &str,
+) -> anyhow::Result<PathBuf> {
+    let path = paths::temp_dir().join("language-servers").join(file_name);
+    DownloadManager::global(delegate.http_client())
+        .download(DownloadRequest::new(url, &path))
+        .await
+        .with_context(|| format!("downloading release from {url}"))?;
+    Ok(path)
+}
+
 #[cfg(any(test, feature = "test-support"))]
 pub fn language(name: &str, grammar: tree_sitter::Language) -> Arc<Language> {
     Arc::new(
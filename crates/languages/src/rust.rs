@@ -26,6 +26,7 @@ use util::archive::extract_zip;
 use util::merge_json_value_into;
 use util::{ResultExt, fs::remove_matching, maybe};
 
+use crate::download_server_release;
 use crate::language_settings::language_settings;
 
 pub struct RustLspAdapter;
@@ -186,22 +187,23 @@ impl LspAdapter for RustLspAdapter {
         if fs::metadata(&server_path).await.is_err() {
             remove_matching(&container_dir, |entry| entry != destination_path).await;
 
-            let mut response = delegate
-                .http_client()
-                .get(&version.url, Default::default(), true)
-                .await
-                .with_context(|| format!("downloading release from {}", version.url))?;
+            let archive_path = download_server_release(
+                delegate,
+                &version.url,
+                &format!("rust-analyzer-{}", version.name),
+            )
+            .await?;
+            let archive = fs::File::open(&archive_path).await?;
             match Self::GITHUB_ASSET_KIND {
                 AssetKind::TarGz => {
-                    let decompressed_bytes = GzipDecoder::new(BufReader::new(response.body_mut()));
+                    let decompressed_bytes = GzipDecoder::new(BufReader::new(archive));
                     let archive = async_tar::Archive::new(decompressed_bytes);
                     archive.unpack(&destination_path).await.with_context(|| {
                         format!("extracting {} to {:?}", version.url, destination_path)
                     })?;
                 }
                 AssetKind::Gz => {
-                    let mut decompressed_bytes =
-                        GzipDecoder::new(BufReader::new(response.body_mut()));
+                    let mut decompressed_bytes = GzipDecoder::new(BufReader::new(archive));
                     let mut file =
                         fs::File::create(&destination_path).await.with_context(|| {
                             format!(
@@ -216,13 +218,14 @@ impl LspAdapter for RustLspAdapter {
                         })?;
                 }
                 AssetKind::Zip => {
-                    extract_zip(&destination_path, response.body_mut())
+                    extract_zip(&destination_path, archive)
                         .await
                         .with_context(|| {
                             format!("unzipping {} to {:?}", version.url, destination_path)
                         })?;
                 }
             };
+            fs::remove_file(&archive_path).await.log_err();
 
             // todo("windows")
             #[cfg(not(windows))]
@@ -26,6 +26,8 @@ use util::archive::extract_zip;
 use util::merge_json_value_into;
 use util::{ResultExt, fs::remove_matching, maybe};
 
+use crate::download_server_release;
+
 pub(crate) struct TypeScriptContextProvider {
     last_package_json: PackageJsonContents,
 }
@@ -820,22 +822,23 @@ impl LspAdapter for EsLintLspAdapter {
         if fs::metadata(&server_path).await.is_err() {
             remove_matching(&container_dir, |entry| entry != destination_path).await;
 
-            let mut response = delegate
-                .http_client()
-                .get(&version.url, Default::default(), true)
-                .await
-                .context("downloading release")?;
+            let archive_path = download_server_release(
+                delegate,
+                &version.url,
+                &format!("vscode-eslint-{}", version.name),
+            )
+            .await?;
+            let archive = fs::File::open(&archive_path).await?;
             match Self::GITHUB_ASSET_KIND {
                 AssetKind::TarGz => {
-                    let decompressed_bytes = GzipDecoder::new(BufReader::new(response.body_mut()));
+                    let decompressed_bytes = GzipDecoder::new(BufReader::new(archive));
                     let archive = Archive::new(decompressed_bytes);
                     archive.unpack(&destination_path).await.with_context(|| {
                         format!("extracting {} to {:?}", version.url, destination_path)
                     })?;
                 }
                 AssetKind::Gz => {
-                    let mut decompressed_bytes =
-                        GzipDecoder::new(BufReader::new(response.body_mut()));
+                    let mut decompressed_bytes = GzipDecoder::new(BufReader::new(archive));
                     let mut file =
                         fs::File::create(&destination_path).await.with_context(|| {
                             format!(
@@ -850,13 +853,14 @@ impl LspAdapter for EsLintLspAdapter {
                         })?;
                 }
                 AssetKind::Zip => {
-                    extract_zip(&destination_path, response.body_mut())
+                    extract_zip(&destination_path, archive)
                         .await
                         .with_context(|| {
                             format!("unzipping {} to {:?}", version.url, destination_path)
                         })?;
                 }
             }
+            fs::remove_file(&archive_path).await.log_err();
 
             let mut dir = fs::read_dir(&destination_path).await?;
             let first = dir.next().await.context("missing first file")??;
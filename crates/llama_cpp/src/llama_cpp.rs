@@ -0,0 +1,116 @@
+use anyhow::{Context as _, Result, bail};
+use futures::AsyncReadExt as _;
+use http_client::{AsyncBody, HttpClient, Method, Request as HttpRequest, StatusCode};
+use std::{
+    net::{Ipv4Addr, TcpListener},
+    path::PathBuf,
+    process::Stdio,
+    time::{Duration, Instant},
+};
+
+/// The name of llama.cpp's server binary, which is looked up on the `PATH` when no other
+/// location is configured.
+pub const DEFAULT_SERVER_BINARY: &str = "llama-server";
+
+/// Loading a large model from a slow disk can take a while, so this is deliberately generous.
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(180);
+const HEALTH_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ServerOptions {
+    pub binary: PathBuf,
+    /// The GGUF file to load.
+    pub model: PathBuf,
+    /// The size of the context window, in tokens.
+    pub context_size: usize,
+}
+
+/// A `llama-server` process serving a single model on a local port.
+///
+/// The server exposes an OpenAI-compatible API at [`LlamaServer::api_url`], and is stopped
+/// when this is dropped.
+pub struct LlamaServer {
+    api_url: String,
+    options: ServerOptions,
+    _process: smol::process::Child,
+}
+
+impl LlamaServer {
+    /// Starts a server and waits until it has finished loading the model.
+    pub async fn start(http_client: &dyn HttpClient, options: ServerOptions) -> Result<Self> {
+        let port = unused_port()?;
+        let mut process = util::command::new_smol_command(&options.binary)
+            .arg("--model")
+            .arg(&options.model)
+            .args(["--host", &Ipv4Addr::LOCALHOST.to_string()])
+            .args(["--port", &port.to_string()])
+            .args(["--ctx-size", &options.context_size.to_string()])
+            // Use the chat template embedded in the model, which is needed for tool calls.
+            .arg("--jinja")
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .kill_on_drop(true)
+            .spawn()
+            .with_context(|| format!("failed to start {}", options.binary.display()))?;
+
+        let base_url = format!("http://{}:{port}", Ipv4Addr::LOCALHOST);
+        let started_at = Instant::now();
+        loop {
+            if let Some(status) = process.try_status()? {
+                bail!(
+                    "{} exited with {status} while loading {}",
+                    options.binary.display(),
+                    options.model.display()
+                );
+            }
+            if is_healthy(http_client, &base_url).await {
+                break;
+            }
+            if started_at.elapsed() > STARTUP_TIMEOUT {
+                bail!("timed out loading {}", options.model.display());
+            }
+            smol::Timer::after(HEALTH_POLL_INTERVAL).await;
+        }
+
+        Ok(Self {
+            api_url: format!("{base_url}/v1"),
+            options,
+            _process: process,
+        })
+    }
+
+    /// The base URL of the server's OpenAI-compatible API.
+    pub fn api_url(&self) -> &str {
+        &self.api_url
+    }
+
+    pub fn options(&self) -> &ServerOptions {
+        &self.options
+    }
+}
+
+/// Returns whether the server at `base_url` has loaded its model and is ready for requests.
+///
+/// The `/health` endpoint responds with 503 while the model is still loading.
+async fn is_healthy(http_client: &dyn HttpClient, base_url: &str) -> bool {
+    let Ok(request) = HttpRequest::builder()
+        .method(Method::GET)
+        .uri(format!("{base_url}/health"))
+        .body(AsyncBody::default())
+    else {
+        return false;
+    };
+    let Ok(mut response) = http_client.send(request).await else {
+        return false;
+    };
+    let mut body = String::new();
+    response.body_mut().read_to_string(&mut body).await.ok();
+    response.status() == StatusCode::OK
+}
+
+fn unused_port() -> Result<u16> {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+        .context("failed to find a free port for llama-server")?;
+    Ok(listener.local_addr()?.port())
+}
@@ -1,6 +1,9 @@
 use anyhow::{Context as _, Result};
 use futures::{AsyncBufReadExt, AsyncReadExt, StreamExt, io::BufReader, stream::BoxStream};
-use http_client::{AsyncBody, HttpClient, Method, Request as HttpRequest, http};
+use http_client::{
+    AsyncBody, HttpClient, HttpRequestExt as _, Method, Request as HttpRequest, RequestPriority,
+    http,
+};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::{convert::TryFrom, time::Duration};
@@ -321,6 +324,7 @@ pub async fn stream_chat_completion(
     let request_builder = http::Request::builder()
         .method(Method::POST)
         .uri(uri)
+        .priority(RequestPriority::Interactive)
         .header("Content-Type", "application/json");
 
     let request = request_builder.body(AsyncBody::from(serde_json::to_string(&request)?))?;
@@ -363,6 +367,52 @@ pub async fn stream_chat_completion(
     }
 }
 
+#[derive(Serialize)]
+struct EmbeddingsRequest<'a> {
+    model: &'a str,
+    input: Vec<&'a str>,
+}
+
+#[derive(Deserialize)]
+pub struct EmbeddingsResponse {
+    pub data: Vec<EmbeddingData>,
+}
+
+#[derive(Deserialize)]
+pub struct EmbeddingData {
+    pub embedding: Vec<f32>,
+}
+
+pub async fn embed(
+    client: &dyn HttpClient,
+    api_url: &str,
+    model: &str,
+    texts: impl IntoIterator<Item = &str>,
+) -> Result<EmbeddingsResponse> {
+    let uri = format!("{api_url}/embeddings");
+    let request = EmbeddingsRequest {
+        model,
+        input: texts.into_iter().collect(),
+    };
+    let request = HttpRequest::builder()
+        .method(Method::POST)
+        .uri(uri)
+        .header("Content-Type", "application/json")
+        .body(AsyncBody::from(serde_json::to_string(&request)?))?;
+
+    let mut response = client.send(request).await?;
+    let mut body = String::new();
+    response.body_mut().read_to_string(&mut body).await?;
+
+    anyhow::ensure!(
+        response.status().is_success(),
+        "Failed to connect to LM Studio API: {} {}",
+        response.status(),
+        body,
+    );
+    serde_json::from_str(&body).context("Unable to parse LM Studio embeddings response")
+}
+
 pub async fn get_models(
     client: &dyn HttpClient,
     api_url: &str,
@@ -1,6 +1,8 @@
 use anyhow::{Result, anyhow};
 use futures::{AsyncBufReadExt, AsyncReadExt, StreamExt, io::BufReader, stream::BoxStream};
-use http_client::{AsyncBody, HttpClient, Method, Request as HttpRequest};
+use http_client::{
+    AsyncBody, HttpClient, HttpRequestExt as _, Method, Request as HttpRequest, RequestPriority,
+};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::convert::TryFrom;
@@ -339,6 +341,7 @@ pub async fn stream_completion(
     let request_builder = HttpRequest::builder()
         .method(Method::POST)
         .uri(uri)
+        .priority(RequestPriority::Interactive)
         .header("Content-Type", "application/json")
         .header("Authorization", format!("Bearer {}", api_key));
 
@@ -69,6 +69,8 @@ pub struct MultiBuffer {
     // only used by consumers using `set_excerpts_for_buffer`
     excerpts_by_path: BTreeMap<PathKey, Vec<ExcerptId>>,
     paths_by_excerpt: HashMap<ExcerptId, PathKey>,
+    /// Paths that are only shown as a placeholder until their excerpts are materialized.
+    deferred_excerpts: BTreeMap<PathKey, DeferredExcerpts>,
     diffs: HashMap<BufferId, DiffState>,
     // all_diff_hunks_expanded: bool,
     subscriptions: Topic,
@@ -80,6 +82,12 @@ pub struct MultiBuffer {
     buffer_changed_since_sync: Rc<Cell<bool>>,
 }
 
+struct DeferredExcerpts {
+    buffer: Entity<Buffer>,
+    ranges: Vec<Range<text::Anchor>>,
+    context_line_count: u32,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum MultiOrSingleBufferOffsetRange {
     Single(Range<usize>),
@@ -603,6 +611,7 @@ impl MultiBuffer {
             title: None,
             excerpts_by_path: Default::default(),
             paths_by_excerpt: Default::default(),
+            deferred_excerpts: Default::default(),
             buffer_changed_since_sync: Default::default(),
             history: History {
                 next_transaction_id: clock::Lamport::default(),
@@ -620,6 +629,7 @@ impl MultiBuffer {
             buffers: Default::default(),
             excerpts_by_path: Default::default(),
             paths_by_excerpt: Default::default(),
+            deferred_excerpts: Default::default(),
             diffs: HashMap::default(),
             subscriptions: Default::default(),
             singleton: false,
@@ -666,6 +676,7 @@ impl MultiBuffer {
             buffers: RefCell::new(buffers),
             excerpts_by_path: Default::default(),
             paths_by_excerpt: Default::default(),
+            deferred_excerpts: Default::default(),
             diffs: diff_bases,
             subscriptions: Default::default(),
             singleton: self.singleton,
@@ -1660,6 +1671,7 @@ impl MultiBuffer {
         counts: Vec<usize>,
         cx: &mut Context<Self>,
     ) -> (Vec<Range<Anchor>>, bool) {
+        self.deferred_excerpts.remove(&path);
         let (excerpt_ids, added_a_new_excerpt) =
             self.update_path_excerpts(path, buffer, &buffer_snapshot, new, cx);
 
@@ -1862,11 +1874,140 @@ impl MultiBuffer {
         (excerpt_ids, added_a_new_excerpt)
     }
 
+    /// Like [`Self::set_excerpts_for_path`], but unless the path's excerpts have already been
+    /// materialized, only the first line of the first range is shown as a placeholder until
+    /// [`Self::materialize_excerpts_for_path`] is called. No ranges are returned for deferred paths.
+    ///
+    /// Building excerpts for hundreds of paths up front is slow, so this lets callers only
+    /// build the ones that are scrolled into view.
+    pub fn set_deferred_excerpts_for_path(
+        &mut self,
+        path: PathKey,
+        buffer: Entity<Buffer>,
+        ranges: impl IntoIterator<Item = Range<Point>>,
+        context_line_count: u32,
+        cx: &mut Context<Self>,
+    ) -> (Vec<Range<Anchor>>, bool) {
+        let ranges = ranges.into_iter().collect::<Vec<_>>();
+        let is_materialized = self.excerpts_by_path.contains_key(&path) && !self.is_deferred(&path);
+        let Some(first_range) = ranges.first().filter(|_| !is_materialized) else {
+            return self.set_excerpts_for_path(path, buffer, ranges, context_line_count, cx);
+        };
+
+        let placeholder =
+            Point::new(first_range.start.row, 0)..Point::new(first_range.start.row, 0);
+        let (_, added_a_new_excerpt) =
+            self.set_excerpts_for_path(path.clone(), buffer.clone(), [placeholder], 0, cx);
+        let buffer_snapshot = buffer.read(cx).snapshot();
+        self.deferred_excerpts.insert(
+            path,
+            DeferredExcerpts {
+                buffer,
+                ranges: ranges
+                    .iter()
+                    .map(|range| {
+                        buffer_snapshot.anchor_before(range.start)
+                            ..buffer_snapshot.anchor_after(range.end)
+                    })
+                    .collect(),
+                context_line_count,
+            },
+        );
+        (Vec::new(), added_a_new_excerpt)
+    }
+
+    pub fn is_deferred(&self, path: &PathKey) -> bool {
+        self.deferred_excerpts.contains_key(path)
+    }
+
+    pub fn has_deferred_excerpts(&self) -> bool {
+        !self.deferred_excerpts.is_empty()
+    }
+
+    /// Replaces the placeholder for a path passed to [`Self::set_deferred_excerpts_for_path`]
+    /// with its excerpts. Returns `false` if the path wasn't deferred.
+    pub fn materialize_excerpts_for_path(
+        &mut self,
+        path: &PathKey,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        let Some(deferred) = self.deferred_excerpts.remove(path) else {
+            return false;
+        };
+        let buffer_snapshot = deferred.buffer.read(cx).snapshot();
+        let ranges = deferred
+            .ranges
+            .iter()
+            .map(|range| {
+                range.start.to_point(&buffer_snapshot)..range.end.to_point(&buffer_snapshot)
+            })
+            .collect::<Vec<_>>();
+        self.set_excerpts_for_path(
+            path.clone(),
+            deferred.buffer,
+            ranges,
+            deferred.context_line_count,
+            cx,
+        );
+        true
+    }
+
+    /// Materializes the deferred paths shown in `range`, along with up to `margin` deferred
+    /// paths on either side of them so they're ready before they're scrolled into view.
+    /// Returns whether any path was materialized.
+    pub fn materialize_excerpts_in_range(
+        &mut self,
+        range: Range<Point>,
+        margin: usize,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        if self.deferred_excerpts.is_empty() {
+            return false;
+        }
+
+        let snapshot = self.snapshot(cx);
+        let shown_paths = snapshot
+            .range_to_buffer_ranges(range)
+            .into_iter()
+            .filter_map(|(_, _, excerpt_id)| self.paths_by_excerpt.get(&excerpt_id))
+            .collect::<Vec<_>>();
+        let (Some(first_path), Some(last_path)) = (
+            shown_paths.iter().copied().min().cloned(),
+            shown_paths.iter().copied().max().cloned(),
+        ) else {
+            return false;
+        };
+
+        let mut paths = self
+            .deferred_excerpts
+            .range(first_path.clone()..=last_path.clone())
+            .map(|(path, _)| path.clone())
+            .collect::<Vec<_>>();
+        paths.extend(
+            self.deferred_excerpts
+                .range(..first_path)
+                .rev()
+                .take(margin)
+                .map(|(path, _)| path.clone()),
+        );
+        paths.extend(
+            self.deferred_excerpts
+                .range((Bound::Excluded(last_path), Bound::Unbounded))
+                .take(margin)
+                .map(|(path, _)| path.clone()),
+        );
+        for path in &paths {
+            self.materialize_excerpts_for_path(path, cx);
+        }
+        !paths.is_empty()
+    }
+
     pub fn paths(&self) -> impl Iterator<Item = PathKey> + '_ {
         self.excerpts_by_path.keys().cloned()
     }
 
     pub fn remove_excerpts_for_path(&mut self, path: PathKey, cx: &mut Context<Self>) {
+        self.deferred_excerpts.remove(&path);
         if let Some(to_remove) = self.excerpts_by_path.remove(&path) {
             self.remove_excerpts(to_remove, cx)
         }
@@ -1796,6 +1796,112 @@ fn test_set_excerpts_for_buffer(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+fn test_deferred_excerpts_for_path(cx: &mut TestAppContext) {
+    let text = indoc! {"
+        zero
+        one
+        two
+        three
+        four
+        five
+        six
+        seven
+    "};
+    let paths = ["a", "b", "c"].map(|path| PathKey::namespaced(0, Path::new(path).into()));
+    let buffers = paths.clone().map(|_| cx.new(|cx| Buffer::local(text, cx)));
+
+    let multibuffer = cx.new(|_| MultiBuffer::new(Capability::ReadWrite));
+    multibuffer.update(cx, |multibuffer, cx| {
+        for (path, buffer) in paths.iter().zip(&buffers) {
+            let (ranges, _) = multibuffer.set_deferred_excerpts_for_path(
+                path.clone(),
+                buffer.clone(),
+                vec![Point::row_range(3..4)],
+                1,
+                cx,
+            );
+            assert!(ranges.is_empty());
+            assert!(multibuffer.is_deferred(path));
+        }
+    });
+    assert_excerpts_match(
+        &multibuffer,
+        cx,
+        indoc! {"
+            -----
+            three
+            -----
+            three
+            -----
+            three
+        "},
+    );
+
+    // Only the paths in the range and the given number of paths around them are materialized.
+    multibuffer.update(cx, |multibuffer, cx| {
+        assert!(multibuffer.materialize_excerpts_in_range(Point::zero()..Point::zero(), 1, cx));
+        assert!(!multibuffer.is_deferred(&paths[0]));
+        assert!(!multibuffer.is_deferred(&paths[1]));
+        assert!(multibuffer.is_deferred(&paths[2]));
+    });
+    assert_excerpts_match(
+        &multibuffer,
+        cx,
+        indoc! {"
+            -----
+            two
+            three
+            four
+            five
+            -----
+            two
+            three
+            four
+            five
+            -----
+            three
+        "},
+    );
+
+    // Materialized paths are updated eagerly, and deferred ones keep their latest ranges.
+    multibuffer.update(cx, |multibuffer, cx| {
+        multibuffer.set_deferred_excerpts_for_path(
+            paths[0].clone(),
+            buffers[0].clone(),
+            vec![Point::row_range(0..1)],
+            0,
+            cx,
+        );
+        multibuffer.set_deferred_excerpts_for_path(
+            paths[2].clone(),
+            buffers[2].clone(),
+            vec![Point::row_range(6..7)],
+            0,
+            cx,
+        );
+        assert!(multibuffer.materialize_excerpts_for_path(&paths[2], cx));
+        assert!(!multibuffer.has_deferred_excerpts());
+    });
+    assert_excerpts_match(
+        &multibuffer,
+        cx,
+        indoc! {"
+            -----
+            zero
+            one
+            -----
+            two
+            three
+            four
+            five
+            -----
+            six
+            seven
+        "},
+    );
+}
+
 #[gpui::test]
 fn test_set_excerpts_for_buffer_rename(cx: &mut TestAppContext) {
     let buf1 = cx.new(|cx| {
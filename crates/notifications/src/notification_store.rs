@@ -5,11 +5,15 @@ use collections::HashMap;
 use db::smol::stream::StreamExt;
 use gpui::{App, AppContext as _, AsyncApp, Context, Entity, EventEmitter, Global, Task};
 use rpc::{Notification, TypedEnvelope, proto};
-use std::{ops::Range, sync::Arc};
+use std::{ops::Range, sync::Arc, time::Duration};
 use sum_tree::{Bias, SumTree};
 use time::OffsetDateTime;
 use util::ResultExt;
 
+/// Notifications created on this client are given ids above any the server assigns, so they
+/// never collide with server notifications and are listed as the newest.
+const FIRST_LOCAL_NOTIFICATION_ID: u64 = 1 << 63;
+
 pub fn init(client: Arc<Client>, user_store: Entity<UserStore>, cx: &mut App) {
     let notification_store = cx.new(|cx| NotificationStore::new(client, user_store, cx));
     cx.set_global(GlobalNotificationStore(notification_store));
@@ -26,6 +30,8 @@ pub struct NotificationStore {
     channel_store: Entity<ChannelStore>,
     notifications: SumTree<NotificationEntry>,
     loaded_all_notifications: bool,
+    next_local_notification_id: u64,
+    snoozed_notifications: HashMap<u64, Task<()>>,
     _watch_connection_status: Task<Option<()>>,
     _subscriptions: Vec<client::Subscription>,
 }
@@ -69,7 +75,52 @@ struct Count(usize);
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
 struct NotificationId(u64);
 
+/// How long a notification can be snoozed for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SnoozeDuration {
+    FiveMinutes,
+    FifteenMinutes,
+    OneHour,
+}
+
+impl SnoozeDuration {
+    pub const ALL: [SnoozeDuration; 3] = [
+        SnoozeDuration::FiveMinutes,
+        SnoozeDuration::FifteenMinutes,
+        SnoozeDuration::OneHour,
+    ];
+
+    pub fn duration(self) -> Duration {
+        match self {
+            SnoozeDuration::FiveMinutes => Duration::from_secs(5 * 60),
+            SnoozeDuration::FifteenMinutes => Duration::from_secs(15 * 60),
+            SnoozeDuration::OneHour => Duration::from_secs(60 * 60),
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SnoozeDuration::FiveMinutes => "5 minutes",
+            SnoozeDuration::FifteenMinutes => "15 minutes",
+            SnoozeDuration::OneHour => "1 hour",
+        }
+    }
+
+    pub fn short_label(self) -> &'static str {
+        match self {
+            SnoozeDuration::FiveMinutes => "5m",
+            SnoozeDuration::FifteenMinutes => "15m",
+            SnoozeDuration::OneHour => "1h",
+        }
+    }
+}
+
 impl NotificationStore {
+    pub fn try_global(cx: &App) -> Option<Entity<Self>> {
+        cx.try_global::<GlobalNotificationStore>()
+            .map(|store| store.0.clone())
+    }
+
     pub fn global(cx: &App) -> Entity<Self> {
         cx.global::<GlobalNotificationStore>().0.clone()
     }
@@ -100,6 +151,8 @@ impl NotificationStore {
             channel_store: ChannelStore::global(cx),
             notifications: Default::default(),
             loaded_all_notifications: false,
+            next_local_notification_id: FIRST_LOCAL_NOTIFICATION_ID,
+            snoozed_notifications: Default::default(),
             channel_messages: Default::default(),
             _watch_connection_status: watch_connection_status,
             _subscriptions: vec![
@@ -146,6 +199,72 @@ impl NotificationStore {
         None
     }
 
+    pub fn is_local_notification(&self, id: u64) -> bool {
+        id >= FIRST_LOCAL_NOTIFICATION_ID
+    }
+
+    /// Adds a notification that originated on this client rather than on the server.
+    pub fn add_local_notification(&mut self, notification: Notification, cx: &mut Context<Self>) {
+        let id = self.next_local_notification_id;
+        self.next_local_notification_id += 1;
+        let entry = NotificationEntry {
+            id,
+            notification,
+            timestamp: OffsetDateTime::now_utc(),
+            is_read: false,
+            response: None,
+        };
+        self.splice_notifications([(id, Some(entry))], true, cx);
+    }
+
+    pub fn mark_local_notification_read(&mut self, id: u64, cx: &mut Context<Self>) {
+        let Some(mut entry) = self
+            .notification_for_id(id)
+            .filter(|entry| self.is_local_notification(entry.id) && !entry.is_read)
+            .cloned()
+        else {
+            return;
+        };
+        entry.is_read = true;
+        self.splice_notifications([(id, Some(entry))], false, cx);
+    }
+
+    pub fn is_snoozed(&self, id: u64) -> bool {
+        self.snoozed_notifications.contains_key(&id)
+    }
+
+    /// Hides a notification, then delivers it again as a new notification once `duration`
+    /// has passed, so that it pops up the same way it did the first time.
+    pub fn snooze_notification(
+        &mut self,
+        id: u64,
+        duration: SnoozeDuration,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(entry) = self.notification_for_id(id).cloned() else {
+            return;
+        };
+        self.splice_notifications([(id, None)], false, cx);
+        self.snoozed_notifications.insert(
+            id,
+            cx.spawn(async move |this, cx| {
+                cx.background_executor().timer(duration.duration()).await;
+                this.update(cx, |this, cx| {
+                    if this.snoozed_notifications.remove(&id).is_none() {
+                        return;
+                    }
+                    let entry = NotificationEntry {
+                        timestamp: OffsetDateTime::now_utc(),
+                        is_read: false,
+                        ..entry
+                    };
+                    this.splice_notifications([(id, Some(entry))], true, cx);
+                })
+                .ok();
+            }),
+        );
+    }
+
     pub fn load_more_notifications(
         &self,
         clear_old: bool,
@@ -218,6 +337,8 @@ impl NotificationStore {
         mut cx: AsyncApp,
     ) -> Result<()> {
         this.update(&mut cx, |this, cx| {
+            this.snoozed_notifications
+                .remove(&envelope.payload.notification_id);
             this.splice_notifications([(envelope.payload.notification_id, None)], false, cx);
             Ok(())
         })?
@@ -302,6 +423,12 @@ impl NotificationStore {
                     user_ids.push(sender_id);
                     message_ids.push(message_id);
                 }
+                Notification::CollaboratorEditing {
+                    collaborator_id, ..
+                } => {
+                    user_ids.push(collaborator_id);
+                }
+                Notification::AgentTaskFinished { .. } => {}
             }
         }
 
@@ -341,6 +468,10 @@ impl NotificationStore {
                     }
                 }));
 
+            let notifications = notifications
+                .into_iter()
+                .filter(|notification| !this.is_snoozed(notification.id))
+                .collect::<Vec<_>>();
             this.splice_notifications(
                 notifications
                     .into_iter()
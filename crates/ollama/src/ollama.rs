@@ -1,6 +1,9 @@
 use anyhow::{Context as _, Result};
 use futures::{AsyncBufReadExt, AsyncReadExt, StreamExt, io::BufReader, stream::BoxStream};
-use http_client::{AsyncBody, HttpClient, Method, Request as HttpRequest, http};
+use http_client::{
+    AsyncBody, HttpClient, HttpRequestExt as _, Method, Request as HttpRequest, RequestPriority,
+    http,
+};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::time::Duration;
@@ -152,6 +155,9 @@ pub struct ChatRequest {
     pub options: Option<ChatOptions>,
     pub tools: Vec<OllamaTool>,
     pub think: Option<bool>,
+    /// A JSON schema that the response must conform to.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<Value>,
 }
 
 impl ChatRequest {
@@ -278,6 +284,7 @@ pub async fn stream_chat_completion(
     let request_builder = http::Request::builder()
         .method(Method::POST)
         .uri(uri)
+        .priority(RequestPriority::Interactive)
         .header("Content-Type", "application/json");
 
     let request = request_builder.body(AsyncBody::from(serde_json::to_string(&request)?))?;
@@ -357,6 +364,47 @@ pub async fn show_model(client: &dyn HttpClient, api_url: &str, model: &str) ->
     Ok(details)
 }
 
+#[derive(Serialize)]
+struct EmbedRequest<'a> {
+    model: &'a str,
+    input: Vec<&'a str>,
+}
+
+#[derive(Deserialize)]
+pub struct EmbedResponse {
+    pub embeddings: Vec<Vec<f32>>,
+}
+
+pub async fn embed(
+    client: &dyn HttpClient,
+    api_url: &str,
+    model: &str,
+    texts: impl IntoIterator<Item = &str>,
+) -> Result<EmbedResponse> {
+    let uri = format!("{api_url}/api/embed");
+    let request = EmbedRequest {
+        model,
+        input: texts.into_iter().collect(),
+    };
+    let request = HttpRequest::builder()
+        .method(Method::POST)
+        .uri(uri)
+        .header("Content-Type", "application/json")
+        .body(AsyncBody::from(serde_json::to_string(&request)?))?;
+
+    let mut response = client.send(request).await?;
+    let mut body = String::new();
+    response.body_mut().read_to_string(&mut body).await?;
+
+    anyhow::ensure!(
+        response.status().is_success(),
+        "Failed to connect to Ollama API: {} {}",
+        response.status(),
+        body,
+    );
+    serde_json::from_str(&body).context("Unable to parse Ollama embedding response")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -532,6 +580,7 @@ mod tests {
             options: None,
             think: None,
             tools: vec![],
+            format: None,
         };
 
         let serialized = serde_json::to_string(&request).unwrap();
@@ -552,6 +601,7 @@ mod tests {
             options: None,
             think: None,
             tools: vec![],
+            format: None,
         };
 
         let serialized = serde_json::to_string(&request).unwrap();
@@ -573,6 +623,7 @@ mod tests {
             options: None,
             think: None,
             tools: vec![],
+            format: None,
         };
 
         let serialized = serde_json::to_string(&request).unwrap();
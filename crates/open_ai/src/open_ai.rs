@@ -4,7 +4,9 @@ use futures::{
     io::BufReader,
     stream::{self, BoxStream},
 };
-use http_client::{AsyncBody, HttpClient, Method, Request as HttpRequest};
+use http_client::{
+    AsyncBody, HttpClient, HttpRequestExt as _, Method, Request as HttpRequest, RequestPriority,
+};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::{
@@ -210,6 +212,22 @@ impl Model {
             _ => false,
         }
     }
+
+    /// Returns whether the given model supports the `response_format` parameter with a JSON schema.
+    pub fn supports_structured_output(&self) -> bool {
+        match self {
+            Self::FourOmni
+            | Self::FourOmniMini
+            | Self::FourPointOne
+            | Self::FourPointOneMini
+            | Self::FourPointOneNano
+            | Self::O1
+            | Self::O3Mini
+            | Self::O3
+            | Self::O4Mini => true,
+            _ => false,
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -229,6 +247,23 @@ pub struct Request {
     pub parallel_tool_calls: Option<bool>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub tools: Vec<ToolDefinition>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_format: Option<ResponseFormat>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ResponseFormat {
+    JsonSchema { json_schema: JsonSchemaFormat },
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JsonSchemaFormat {
+    pub name: String,
+    pub schema: serde_json::Value,
+    /// Strict mode requires every property to be required, which schemas for
+    /// optional fields don't satisfy.
+    pub strict: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -620,6 +655,7 @@ pub async fn stream_completion(
     let request_builder = HttpRequest::builder()
         .method(Method::POST)
         .uri(uri)
+        .priority(RequestPriority::Interactive)
         .header("Content-Type", "application/json")
         .header("Authorization", format!("Bearer {}", api_key));
 
@@ -686,6 +722,15 @@ pub enum OpenAiEmbeddingModel {
     TextEmbedding3Large,
 }
 
+impl OpenAiEmbeddingModel {
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::TextEmbedding3Small => "text-embedding-3-small",
+            Self::TextEmbedding3Large => "text-embedding-3-large",
+        }
+    }
+}
+
 #[derive(Serialize)]
 struct OpenAiEmbeddingRequest<'a> {
     model: OpenAiEmbeddingModel,
@@ -740,3 +785,67 @@ pub fn embed<'a>(
         Ok(response)
     }
 }
+
+pub const DEFAULT_TRANSCRIPTION_MODEL: &str = "whisper-1";
+
+const TRANSCRIPTION_BOUNDARY: &str = "zed-transcription-boundary";
+
+#[derive(Deserialize)]
+struct TranscriptionResponse {
+    text: String,
+}
+
+/// Transcribes WAV-encoded audio with the `/audio/transcriptions` endpoint.
+///
+/// Servers that implement this endpoint locally, such as whisper.cpp's, usually don't require an
+/// API key, so the `Authorization` header is only sent when `api_key` isn't empty.
+pub async fn transcribe(
+    client: &dyn HttpClient,
+    api_url: &str,
+    api_key: &str,
+    model: &str,
+    wav: Vec<u8>,
+) -> Result<String> {
+    let uri = format!("{api_url}/audio/transcriptions");
+
+    let mut body = Vec::with_capacity(wav.len() + 512);
+    for (name, value) in [("model", model), ("response_format", "json")] {
+        body.extend_from_slice(
+            format!(
+                "--{TRANSCRIPTION_BOUNDARY}\r\nContent-Disposition: form-data; name=\"{name}\"\r\n\r\n{value}\r\n"
+            )
+            .as_bytes(),
+        );
+    }
+    body.extend_from_slice(
+        format!(
+            "--{TRANSCRIPTION_BOUNDARY}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"speech.wav\"\r\nContent-Type: audio/wav\r\n\r\n"
+        )
+        .as_bytes(),
+    );
+    body.extend_from_slice(&wav);
+    body.extend_from_slice(format!("\r\n--{TRANSCRIPTION_BOUNDARY}--\r\n").as_bytes());
+
+    let mut request_builder = HttpRequest::builder().method(Method::POST).uri(uri).header(
+        "Content-Type",
+        format!("multipart/form-data; boundary={TRANSCRIPTION_BOUNDARY}"),
+    );
+    if !api_key.is_empty() {
+        request_builder = request_builder.header("Authorization", format!("Bearer {}", api_key));
+    }
+
+    let request = request_builder.body(AsyncBody::from(body))?;
+    let mut response = client.send(request).await?;
+    let mut body = String::new();
+    response.body_mut().read_to_string(&mut body).await?;
+
+    anyhow::ensure!(
+        response.status().is_success(),
+        "error during transcription, status: {:?}, body: {:?}",
+        response.status(),
+        body
+    );
+    let response: TranscriptionResponse =
+        serde_json::from_str(&body).context("failed to parse transcription response")?;
+    Ok(response.text)
+}
@@ -165,6 +165,18 @@ pub fn old_log_file() -> &'static PathBuf {
     OLD_LOG_FILE.get_or_init(|| logs_dir().join("Zed.log.old"))
 }
 
+/// Returns the path to the `language_models.log` file.
+pub fn language_model_log_file() -> &'static PathBuf {
+    static LANGUAGE_MODEL_LOG_FILE: OnceLock<PathBuf> = OnceLock::new();
+    LANGUAGE_MODEL_LOG_FILE.get_or_init(|| logs_dir().join("language_models.log"))
+}
+
+/// Returns the path to the `language_models.log.old` file.
+pub fn old_language_model_log_file() -> &'static PathBuf {
+    static OLD_LANGUAGE_MODEL_LOG_FILE: OnceLock<PathBuf> = OnceLock::new();
+    OLD_LANGUAGE_MODEL_LOG_FILE.get_or_init(|| logs_dir().join("language_models.log.old"))
+}
+
 /// Returns the path to the database directory.
 pub fn database_dir() -> &'static PathBuf {
     static DATABASE_DIR: OnceLock<PathBuf> = OnceLock::new();
@@ -350,6 +362,12 @@ pub fn debug_adapters_dir() -> &'static PathBuf {
     DEBUG_ADAPTERS_DIR.get_or_init(|| data_dir().join("debug_adapters"))
 }
 
+/// Returns the path to the directory where GGUF models for llama.cpp are downloaded to.
+pub fn llama_cpp_models_dir() -> &'static PathBuf {
+    static LLAMA_CPP_MODELS_DIR: OnceLock<PathBuf> = OnceLock::new();
+    LLAMA_CPP_MODELS_DIR.get_or_init(|| data_dir().join("llama_cpp_models"))
+}
+
 /// Returns the path to the Copilot directory.
 pub fn copilot_dir() -> &'static PathBuf {
     static COPILOT_DIR: OnceLock<PathBuf> = OnceLock::new();
@@ -1,9 +1,11 @@
 mod conflict_set;
 pub mod git_traversal;
+mod status_cache;
 
 use crate::{
     ProjectEnvironment, ProjectItem, ProjectPath,
     buffer_store::{BufferStore, BufferStoreEvent},
+    project_settings::ProjectSettings,
     worktree_store::{WorktreeStore, WorktreeStoreEvent},
 };
 use anyhow::{Context as _, Result, anyhow, bail};
@@ -19,13 +21,14 @@ use futures::{
     future::{self, Shared},
 };
 use git::{
-    BuildPermalinkParams, GitHostingProviderRegistry, WORK_DIRECTORY_REPO_PATH,
+    BuildPermalinkParams, GitHostingProviderRegistry, GitRemote, WORK_DIRECTORY_REPO_PATH,
     blame::Blame,
     parse_git_remote_url,
     repository::{
-        Branch, CommitDetails, CommitDiff, CommitFile, CommitOptions, DiffType, GitRepository,
-        GitRepositoryCheckpoint, PushOptions, Remote, RemoteCommandOutput, RepoPath, ResetMode,
-        UpstreamTrackingStatus,
+        ApplyCommitMode, ApplyCommitOutcome, Branch, CommitDetails, CommitDiff, CommitFile,
+        CommitOptions, CommitSummary, DiffType, GitRepository, GitRepositoryCheckpoint,
+        PushOptions, Remote, RemoteCommandOutput, RenameKind, RenamedPath, RepoPath, ResetMode,
+        Submodule, SubmoduleStatus, UpstreamTrackingStatus,
     },
     status::{
         FileStatus, GitSummary, StatusCode, TrackedStatus, UnmergedStatus, UnmergedStatusCode,
@@ -41,11 +44,17 @@ use language::{
 };
 use parking_lot::Mutex;
 use postage::stream::Stream as _;
+use rand::Rng;
 use rpc::{
     AnyProtoClient, TypedEnvelope,
-    proto::{self, FromProto, SSH_PROJECT_ID, ToProto, git_reset, split_repository_update},
+    proto::{
+        self, FromProto, SSH_PROJECT_ID, ToProto, git_apply_commit, git_reset,
+        split_repository_update,
+    },
 };
 use serde::Deserialize;
+use settings::{Settings as _, SettingsStore};
+use status_cache::{StatusCache, StatusCacheKey};
 use std::{
     cmp::Ordering,
     collections::{BTreeSet, VecDeque},
@@ -57,7 +66,7 @@ use std::{
         Arc,
         atomic::{self, AtomicU64},
     },
-    time::Instant,
+    time::{Duration, Instant},
 };
 use sum_tree::{Edit, SumTree, TreeSet};
 use text::{Bias, BufferId};
@@ -78,6 +87,7 @@ pub struct GitStore {
         HashMap<(BufferId, DiffKind), Shared<Task<Result<Entity<BufferDiff>, Arc<anyhow::Error>>>>>,
     diffs: HashMap<BufferId, Entity<BufferGitState>>,
     shared_diffs: HashMap<proto::PeerId, HashMap<BufferId, SharedDiffs>>,
+    auto_fetch: Option<(Duration, Task<()>)>,
     _subscriptions: Vec<Subscription>,
 }
 
@@ -268,6 +278,11 @@ pub struct Repository {
     job_id: JobId,
     askpass_delegates: Arc<Mutex<HashMap<u64, AskPassDelegate>>>,
     latest_askpass_id: u64,
+    status_cache: Option<StatusCache>,
+    /// The directory shared by all of a repository's linked worktrees, which is the
+    /// `.git` directory unless the repository is a linked worktree or a submodule.
+    /// Only known for local repositories.
+    common_dir_abs_path: Option<Arc<Path>>,
 }
 
 impl std::ops::Deref for Repository {
@@ -292,7 +307,14 @@ pub enum RepositoryState {
 
 #[derive(Clone, Debug)]
 pub enum RepositoryEvent {
-    Updated { full_scan: bool },
+    Updated {
+        full_scan: bool,
+    },
+    /// The git status of some paths changed. `paths` is `None` after a full status
+    /// scan, when any path may have changed.
+    StatusesChanged {
+        paths: Option<Arc<[RepoPath]>>,
+    },
     MergeHeadsChanged,
 }
 
@@ -393,9 +415,10 @@ impl GitStore {
         let _subscriptions = vec![
             cx.subscribe(&worktree_store, Self::on_worktree_store_event),
             cx.subscribe(&buffer_store, Self::on_buffer_store_event),
+            cx.observe_global::<SettingsStore>(Self::update_auto_fetch),
         ];
 
-        GitStore {
+        let mut this = GitStore {
             state,
             buffer_store,
             worktree_store,
@@ -405,7 +428,68 @@ impl GitStore {
             loading_diffs: HashMap::default(),
             shared_diffs: HashMap::default(),
             diffs: HashMap::default(),
+            auto_fetch: None,
+        };
+        this.update_auto_fetch(cx);
+        this
+    }
+
+    /// Starts or stops fetching the active repository in the background,
+    /// according to the `git.auto_fetch` setting.
+    fn update_auto_fetch(&mut self, cx: &mut Context<Self>) {
+        // Guests in a shared project leave fetching to the host.
+        let interval = match self.state {
+            GitStoreState::Remote { .. } => None,
+            GitStoreState::Local { .. } | GitStoreState::Ssh { .. } => {
+                ProjectSettings::get_global(cx).git.auto_fetch_interval()
+            }
+        };
+        let Some(interval) = interval else {
+            self.auto_fetch = None;
+            return;
+        };
+        if self
+            .auto_fetch
+            .as_ref()
+            .is_some_and(|(running_interval, _)| *running_interval == interval)
+        {
+            return;
         }
+
+        let task = cx.spawn(async move |this, cx| {
+            let mut consecutive_failures = 0;
+            loop {
+                let delay =
+                    auto_fetch_delay(interval, consecutive_failures, &mut rand::thread_rng());
+                cx.background_executor().timer(delay).await;
+                let Ok(fetch) = this.update(cx, |this, cx| this.fetch_in_background(cx)) else {
+                    break;
+                };
+                let Some(fetch) = fetch else {
+                    continue;
+                };
+                match fetch.await {
+                    Ok(Ok(_)) => consecutive_failures = 0,
+                    Ok(Err(error)) => {
+                        log::warn!("background git fetch failed: {error:#}");
+                        consecutive_failures += 1;
+                    }
+                    Err(_canceled) => consecutive_failures += 1,
+                }
+            }
+        });
+        self.auto_fetch = Some((interval, task));
+    }
+
+    fn fetch_in_background(
+        &mut self,
+        cx: &mut Context<Self>,
+    ) -> Option<oneshot::Receiver<Result<RemoteCommandOutput>>> {
+        let repository = self.active_repository()?;
+        // Dropping the reply channel fails any credential prompt, since nobody is
+        // around to answer it. Fetches that need credentials must be run by hand.
+        let askpass = AskPassDelegate::new(&mut cx.to_async(), |_, _, _| {});
+        Some(repository.update(cx, |repository, cx| repository.fetch(askpass, cx)))
     }
 
     pub fn init(client: &AnyProtoClient) {
@@ -417,13 +501,21 @@ impl GitStore {
         client.add_entity_request_handler(Self::handle_push);
         client.add_entity_request_handler(Self::handle_pull);
         client.add_entity_request_handler(Self::handle_fetch);
+        client.add_entity_request_handler(Self::handle_get_submodules);
+        client.add_entity_request_handler(Self::handle_update_submodules);
         client.add_entity_request_handler(Self::handle_stage);
         client.add_entity_request_handler(Self::handle_unstage);
         client.add_entity_request_handler(Self::handle_commit);
         client.add_entity_request_handler(Self::handle_reset);
+        client.add_entity_request_handler(Self::handle_apply_commit);
         client.add_entity_request_handler(Self::handle_show);
         client.add_entity_request_handler(Self::handle_load_commit_diff);
+        client.add_entity_request_handler(Self::handle_file_history);
+        client.add_entity_request_handler(Self::handle_diff_refs);
+        client.add_entity_request_handler(Self::handle_renamed_paths);
         client.add_entity_request_handler(Self::handle_checkout_files);
+        client.add_entity_request_handler(Self::handle_stash);
+        client.add_entity_request_handler(Self::handle_stash_pop);
         client.add_entity_request_handler(Self::handle_open_commit_message_buffer);
         client.add_entity_request_handler(Self::handle_set_index_text);
         client.add_entity_request_handler(Self::handle_askpass);
@@ -1205,6 +1297,12 @@ impl GitStore {
                     update.new_work_directory_abs_path.clone()
                 {
                     existing.update(cx, |existing, cx| {
+                        if existing.snapshot.work_directory_abs_path != new_work_directory_abs_path
+                        {
+                            if let Some(status_cache) = &mut existing.status_cache {
+                                status_cache.invalidate();
+                            }
+                        }
                         existing.snapshot.work_directory_abs_path = new_work_directory_abs_path;
                         existing.schedule_scan(updates_tx.clone(), cx);
                     });
@@ -1342,29 +1440,57 @@ impl GitStore {
                     let recv = repo.update(cx, |repo, cx| {
                         log::debug!("hunks changed for {}", path.display());
                         repo.spawn_set_index_text_job(
-                            path,
+                            path.clone(),
                             new_index_text.as_ref().map(|rope| rope.to_string()),
                             Some(hunk_staging_operation_count),
                             cx,
                         )
                     });
                     let diff = diff.downgrade();
-                    cx.spawn(async move |this, cx| {
-                        if let Ok(Err(error)) = cx.background_spawn(recv).await {
-                            diff.update(cx, |diff, cx| {
-                                diff.clear_pending_hunks(cx);
-                            })
-                            .ok();
-                            this.update(cx, |_, cx| cx.emit(GitStoreEvent::IndexWriteError(error)))
+                    cx.spawn(
+                        async move |this, cx| match cx.background_spawn(recv).await {
+                            Ok(Ok(())) => {
+                                // Refresh the status of the staged path right away, rather than
+                                // waiting for the index change to trigger a full scan.
+                                this.update(cx, |this, cx| {
+                                    this.refresh_statuses_for_paths(&repo, vec![path], cx)
+                                })
                                 .ok();
-                        }
-                    })
+                            }
+                            Ok(Err(error)) => {
+                                diff.update(cx, |diff, cx| {
+                                    diff.clear_pending_hunks(cx);
+                                })
+                                .ok();
+                                this.update(cx, |_, cx| {
+                                    cx.emit(GitStoreEvent::IndexWriteError(error))
+                                })
+                                .ok();
+                            }
+                            Err(_) => {}
+                        },
+                    )
                     .detach();
                 }
             }
         }
     }
 
+    fn refresh_statuses_for_paths(
+        &self,
+        repo: &Entity<Repository>,
+        paths: Vec<RepoPath>,
+        cx: &mut Context<Self>,
+    ) {
+        let GitStoreState::Local { downstream, .. } = &self.state else {
+            return;
+        };
+        let updates_tx = downstream
+            .as_ref()
+            .map(|downstream| downstream.updates_tx.clone());
+        repo.update(cx, |repo, cx| repo.paths_changed(paths, updates_tx, cx));
+    }
+
     fn local_worktree_git_repos_changed(
         &mut self,
         worktree: Entity<Worktree>,
@@ -1575,6 +1701,54 @@ impl GitStore {
         })
     }
 
+    async fn handle_get_submodules(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitGetSubmodules>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitGetSubmodulesResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let submodules = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.submodules()
+            })?
+            .await??;
+
+        Ok(proto::GitGetSubmodulesResponse {
+            submodules: submodules.iter().map(submodule_to_proto).collect(),
+        })
+    }
+
+    async fn handle_update_submodules(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitUpdateSubmodules>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::RemoteMessageResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let askpass_id = envelope.payload.askpass_id;
+
+        let askpass = make_remote_delegate(
+            this,
+            envelope.payload.project_id,
+            repository_id,
+            askpass_id,
+            &mut cx,
+        );
+
+        let remote_output = repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.update_submodules(askpass, cx)
+            })?
+            .await??;
+
+        Ok(proto::RemoteMessageResponse {
+            stdout: remote_output.stdout,
+            stderr: remote_output.stderr,
+        })
+    }
+
     async fn handle_push(
         this: Entity<Self>,
         envelope: TypedEnvelope<proto::Push>,
@@ -1664,7 +1838,11 @@ impl GitStore {
 
         repository_handle
             .update(&mut cx, |repository_handle, cx| {
-                repository_handle.stage_entries(entries, cx)
+                if envelope.payload.intent_to_add {
+                    repository_handle.intent_to_add_entries(entries, cx)
+                } else {
+                    repository_handle.stage_entries(entries, cx)
+                }
             })?
             .await?;
         Ok(proto::Ack {})
@@ -1737,6 +1915,7 @@ impl GitStore {
                     name.zip(email),
                     CommitOptions {
                         amend: options.amend,
+                        signoff: options.signoff,
                     },
                     cx,
                 )
@@ -1848,6 +2027,33 @@ impl GitStore {
         })
     }
 
+    async fn handle_file_history(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitFileHistory>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitFileHistoryResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let path = RepoPath(Arc::<Path>::from_proto(envelope.payload.path));
+
+        let commits = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.file_history(path)
+            })?
+            .await??;
+
+        Ok(proto::GitFileHistoryResponse {
+            commits: commits
+                .into_iter()
+                .map(|commit| proto::CommitSummary {
+                    sha: commit.sha.to_string(),
+                    subject: commit.subject.to_string(),
+                    commit_timestamp: commit.commit_timestamp,
+                })
+                .collect(),
+        })
+    }
+
     async fn handle_load_commit_diff(
         this: Entity<Self>,
         envelope: TypedEnvelope<proto::LoadCommitDiff>,
@@ -1874,6 +2080,58 @@ impl GitStore {
         })
     }
 
+    async fn handle_renamed_paths(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitRenamedPaths>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitRenamedPathsResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let renamed_paths = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.renamed_paths()
+            })?
+            .await??;
+        Ok(proto::GitRenamedPathsResponse {
+            renamed_paths: renamed_paths
+                .into_iter()
+                .map(|renamed_path| proto::RenamedPath {
+                    old_path: renamed_path.old_path.as_ref().to_proto(),
+                    new_path: renamed_path.new_path.as_ref().to_proto(),
+                    is_copy: renamed_path.kind == RenameKind::Copied,
+                    similarity: renamed_path.similarity as u32,
+                })
+                .collect(),
+        })
+    }
+
+    async fn handle_diff_refs(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitDiffRefs>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::LoadCommitDiffResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let diff = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.diff_refs(envelope.payload.base, envelope.payload.head)
+            })?
+            .await??;
+        Ok(proto::LoadCommitDiffResponse {
+            files: diff
+                .files
+                .into_iter()
+                .map(|file| proto::CommitFile {
+                    path: file.path.to_string(),
+                    old_text: file.old_text,
+                    new_text: file.new_text,
+                })
+                .collect(),
+        })
+    }
+
     async fn handle_reset(
         this: Entity<Self>,
         envelope: TypedEnvelope<proto::GitReset>,
@@ -1895,6 +2153,30 @@ impl GitStore {
         Ok(proto::Ack {})
     }
 
+    async fn handle_apply_commit(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitApplyCommit>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitApplyCommitResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let mode = match envelope.payload.mode() {
+            git_apply_commit::Mode::CherryPick => ApplyCommitMode::CherryPick,
+            git_apply_commit::Mode::Revert => ApplyCommitMode::Revert,
+        };
+
+        let outcome = repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.apply_commit(envelope.payload.commit, mode, cx)
+            })?
+            .await??;
+        Ok(proto::GitApplyCommitResponse {
+            conflicted: outcome == ApplyCommitOutcome::Conflicted,
+            empty: outcome == ApplyCommitOutcome::Empty,
+        })
+    }
+
     async fn handle_checkout_files(
         this: Entity<Self>,
         envelope: TypedEnvelope<proto::GitCheckoutFiles>,
@@ -1917,6 +2199,44 @@ impl GitStore {
         Ok(proto::Ack {})
     }
 
+    async fn handle_stash(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitStash>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let paths = envelope
+            .payload
+            .paths
+            .iter()
+            .map(|s| RepoPath::from_str(s))
+            .collect();
+
+        repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.stash_paths(paths, cx)
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_stash_pop(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitStashPop>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.stash_pop(cx)
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
+
     async fn handle_open_commit_message_buffer(
         this: Entity<Self>,
         envelope: TypedEnvelope<proto::OpenCommitMessageBuffer>,
@@ -2002,6 +2322,13 @@ impl GitStore {
         let diff_type = match envelope.payload.diff_type() {
             proto::git_diff::DiffType::HeadToIndex => DiffType::HeadToIndex,
             proto::git_diff::DiffType::HeadToWorktree => DiffType::HeadToWorktree,
+            proto::git_diff::DiffType::MergeBase => DiffType::MergeBase {
+                base_ref: envelope
+                    .payload
+                    .base_ref
+                    .clone()
+                    .context("missing base ref for merge base diff")?,
+            },
         };
 
         let mut diff = repository_handle
@@ -2768,7 +3095,10 @@ impl Repository {
         cx: &mut Context<Self>,
     ) -> Self {
         let snapshot = RepositorySnapshot::empty(id, work_directory_abs_path.clone());
+        let status_cache =
+            StatusCache::new(fs.clone(), &repository_dir_abs_path, &common_dir_abs_path);
         Repository {
+            common_dir_abs_path: Some(common_dir_abs_path.clone()),
             this: cx.weak_entity(),
             git_store,
             snapshot,
@@ -2776,6 +3106,7 @@ impl Repository {
             askpass_delegates: Default::default(),
             paths_needing_status_update: Default::default(),
             latest_askpass_id: 0,
+            status_cache: Some(status_cache),
             job_sender: Repository::spawn_local_git_worker(
                 work_directory_abs_path,
                 dot_git_abs_path,
@@ -2810,6 +3141,8 @@ impl Repository {
             latest_askpass_id: 0,
             active_jobs: Default::default(),
             job_id: 0,
+            status_cache: None,
+            common_dir_abs_path: None,
         }
     }
 
@@ -2817,6 +3150,13 @@ impl Repository {
         self.git_store.upgrade()
     }
 
+    /// The path of the repository's `info/exclude` file, which holds ignore patterns
+    /// that aren't committed. Only known for local repositories.
+    pub fn exclude_file_abs_path(&self) -> Option<PathBuf> {
+        let common_dir = self.common_dir_abs_path.as_ref()?;
+        Some(common_dir.join(*git::REPO_EXCLUDE))
+    }
+
     fn reload_buffer_diff_bases(&mut self, cx: &mut Context<Self>) {
         let this = cx.weak_entity();
         let git_store = self.git_store.clone();
@@ -3208,6 +3548,68 @@ impl Repository {
         )
     }
 
+    pub fn stash_paths(
+        &mut self,
+        paths: Vec<RepoPath>,
+        _cx: &mut App,
+    ) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+
+        self.send_job(
+            Some("git stash push".into()),
+            move |git_repo, _| async move {
+                match git_repo {
+                    RepositoryState::Local {
+                        backend,
+                        environment,
+                        ..
+                    } => backend.stash_paths(paths, environment.clone()).await,
+                    RepositoryState::Remote { project_id, client } => {
+                        client
+                            .request(proto::GitStash {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                paths: paths
+                                    .into_iter()
+                                    .map(|p| p.to_string_lossy().to_string())
+                                    .collect(),
+                            })
+                            .await?;
+
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
+    pub fn stash_pop(&mut self, _cx: &mut App) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+
+        self.send_job(
+            Some("git stash pop".into()),
+            move |git_repo, _| async move {
+                match git_repo {
+                    RepositoryState::Local {
+                        backend,
+                        environment,
+                        ..
+                    } => backend.stash_pop(environment.clone()).await,
+                    RepositoryState::Remote { project_id, client } => {
+                        client
+                            .request(proto::GitStashPop {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                            })
+                            .await?;
+
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
     pub fn reset(
         &mut self,
         commit: String,
@@ -3243,6 +3645,53 @@ impl Repository {
         })
     }
 
+    pub fn apply_commit(
+        &mut self,
+        commit: String,
+        mode: ApplyCommitMode,
+        _cx: &mut App,
+    ) -> oneshot::Receiver<Result<ApplyCommitOutcome>> {
+        let id = self.id;
+
+        self.send_job(
+            Some(format!("git {} {}", mode.name(), commit).into()),
+            move |git_repo, _| async move {
+                match git_repo {
+                    RepositoryState::Local {
+                        backend,
+                        environment,
+                        ..
+                    } => backend.apply_commit(commit, mode, environment).await,
+                    RepositoryState::Remote { project_id, client } => {
+                        let response = client
+                            .request(proto::GitApplyCommit {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                commit,
+                                mode: match mode {
+                                    ApplyCommitMode::CherryPick => {
+                                        git_apply_commit::Mode::CherryPick.into()
+                                    }
+                                    ApplyCommitMode::Revert => {
+                                        git_apply_commit::Mode::Revert.into()
+                                    }
+                                },
+                            })
+                            .await?;
+
+                        Ok(if response.conflicted {
+                            ApplyCommitOutcome::Conflicted
+                        } else if response.empty {
+                            ApplyCommitOutcome::Empty
+                        } else {
+                            ApplyCommitOutcome::Applied
+                        })
+                    }
+                }
+            },
+        )
+    }
+
     pub fn show(&mut self, commit: String) -> oneshot::Receiver<Result<CommitDetails>> {
         let id = self.id;
         self.send_job(None, move |git_repo, _cx| async move {
@@ -3269,6 +3718,38 @@ impl Repository {
         })
     }
 
+    pub fn file_history(
+        &mut self,
+        path: RepoPath,
+    ) -> oneshot::Receiver<Result<Vec<CommitSummary>>> {
+        let id = self.id;
+        self.send_job(None, move |git_repo, _cx| async move {
+            match git_repo {
+                RepositoryState::Local { backend, .. } => backend.file_history(path).await,
+                RepositoryState::Remote { project_id, client } => {
+                    let response = client
+                        .request(proto::GitFileHistory {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            path: path.as_ref().to_proto(),
+                        })
+                        .await?;
+
+                    Ok(response
+                        .commits
+                        .into_iter()
+                        .map(|commit| CommitSummary {
+                            sha: commit.sha.into(),
+                            subject: commit.subject.into(),
+                            commit_timestamp: commit.commit_timestamp,
+                            has_parent: true,
+                        })
+                        .collect())
+                }
+            }
+        })
+    }
+
     pub fn load_commit_diff(&mut self, commit: String) -> oneshot::Receiver<Result<CommitDiff>> {
         let id = self.id;
         self.send_job(None, move |git_repo, cx| async move {
@@ -3300,6 +3781,75 @@ impl Repository {
         })
     }
 
+    pub fn renamed_paths(&mut self) -> oneshot::Receiver<Result<Vec<RenamedPath>>> {
+        let id = self.id;
+        self.send_job(None, move |git_repo, _cx| async move {
+            match git_repo {
+                RepositoryState::Local { backend, .. } => backend.renamed_paths().await,
+                RepositoryState::Remote { project_id, client } => {
+                    let response = client
+                        .request(proto::GitRenamedPaths {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                        })
+                        .await?;
+
+                    Ok(response
+                        .renamed_paths
+                        .into_iter()
+                        .map(|renamed_path| RenamedPath {
+                            old_path: RepoPath(Arc::<Path>::from_proto(renamed_path.old_path)),
+                            new_path: RepoPath(Arc::<Path>::from_proto(renamed_path.new_path)),
+                            kind: if renamed_path.is_copy {
+                                RenameKind::Copied
+                            } else {
+                                RenameKind::Renamed
+                            },
+                            similarity: renamed_path.similarity.min(100) as u8,
+                        })
+                        .collect())
+                }
+            }
+        })
+    }
+
+    /// Loads the changes between two refs, which may be branches, tags, or commits.
+    pub fn diff_refs(
+        &mut self,
+        base: String,
+        head: String,
+    ) -> oneshot::Receiver<Result<CommitDiff>> {
+        let id = self.id;
+        self.send_job(None, move |git_repo, cx| async move {
+            match git_repo {
+                RepositoryState::Local { backend, .. } => backend.diff_refs(base, head, cx).await,
+                RepositoryState::Remote {
+                    client, project_id, ..
+                } => {
+                    let response = client
+                        .request(proto::GitDiffRefs {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            base,
+                            head,
+                        })
+                        .await?;
+                    Ok(CommitDiff {
+                        files: response
+                            .files
+                            .into_iter()
+                            .map(|file| CommitFile {
+                                path: Path::new(&file.path).into(),
+                                old_text: file.old_text,
+                                new_text: file.new_text,
+                            })
+                            .collect(),
+                    })
+                }
+            }
+        })
+    }
+
     fn buffer_store(&self, cx: &App) -> Option<Entity<BufferStore>> {
         Some(self.git_store.upgrade()?.read(cx).buffer_store.clone())
     }
@@ -3356,6 +3906,7 @@ impl Repository {
                                         .into_iter()
                                         .map(|repo_path| repo_path.as_ref().to_proto())
                                         .collect(),
+                                    intent_to_add: false,
                                 })
                                 .await
                                 .context("sending stage request")?;
@@ -3371,6 +3922,45 @@ impl Repository {
         })
     }
 
+    /// Adds untracked entries to the index without their contents, so that their
+    /// hunks can then be staged individually.
+    pub fn intent_to_add_entries(
+        &mut self,
+        entries: Vec<RepoPath>,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<()>> {
+        if entries.is_empty() {
+            return Task::ready(Ok(()));
+        }
+        let id = self.id;
+        let job = self.send_job(None, move |git_repo, _cx| async move {
+            match git_repo {
+                RepositoryState::Local {
+                    backend,
+                    environment,
+                    ..
+                } => backend.intent_to_add_paths(entries, environment).await,
+                RepositoryState::Remote { project_id, client } => {
+                    client
+                        .request(proto::Stage {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            paths: entries
+                                .into_iter()
+                                .map(|repo_path| repo_path.as_ref().to_proto())
+                                .collect(),
+                            intent_to_add: true,
+                        })
+                        .await
+                        .context("sending intent to add request")?;
+
+                    Ok(())
+                }
+            }
+        });
+        cx.spawn(async move |_, _| job.await?)
+    }
+
     pub fn unstage_entries(
         &self,
         entries: Vec<RepoPath>,
@@ -3487,6 +4077,7 @@ impl Repository {
                             email: email.map(String::from),
                             options: Some(proto::commit::CommitOptions {
                                 amend: options.amend,
+                                signoff: options.signoff,
                             }),
                         })
                         .await
@@ -3539,6 +4130,69 @@ impl Repository {
         })
     }
 
+    pub fn submodules(&mut self) -> oneshot::Receiver<Result<Vec<Submodule>>> {
+        let id = self.id;
+        self.send_job(None, move |repo, _cx| async move {
+            match repo {
+                RepositoryState::Local { backend, .. } => backend.submodules().await,
+                RepositoryState::Remote { project_id, client } => {
+                    let response = client
+                        .request(proto::GitGetSubmodules {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                        })
+                        .await?;
+
+                    Ok(response.submodules.iter().map(proto_to_submodule).collect())
+                }
+            }
+        })
+    }
+
+    pub fn update_submodules(
+        &mut self,
+        askpass: AskPassDelegate,
+        _cx: &mut App,
+    ) -> oneshot::Receiver<Result<RemoteCommandOutput>> {
+        let askpass_delegates = self.askpass_delegates.clone();
+        let askpass_id = util::post_inc(&mut self.latest_askpass_id);
+        let id = self.id;
+
+        self.send_job(
+            Some("git submodule update --init --recursive".into()),
+            move |git_repo, cx| async move {
+                match git_repo {
+                    RepositoryState::Local {
+                        backend,
+                        environment,
+                        ..
+                    } => backend.update_submodules(askpass, environment, cx).await,
+                    RepositoryState::Remote { project_id, client } => {
+                        askpass_delegates.lock().insert(askpass_id, askpass);
+                        let _defer = util::defer(|| {
+                            let askpass_delegate = askpass_delegates.lock().remove(&askpass_id);
+                            debug_assert!(askpass_delegate.is_some());
+                        });
+
+                        let response = client
+                            .request(proto::GitUpdateSubmodules {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                askpass_id,
+                            })
+                            .await
+                            .context("sending submodule update request")?;
+
+                        Ok(RemoteCommandOutput {
+                            stdout: response.stdout,
+                            stderr: response.stderr,
+                        })
+                    }
+                }
+            },
+        )
+    }
+
     pub fn push(
         &mut self,
         branch: SharedString,
@@ -3790,6 +4444,112 @@ impl Repository {
         })
     }
 
+    /// Returns the hosted remote that the branch's upstream lives on, along with the name of the
+    /// branch on that remote.
+    ///
+    /// Hosted remotes are only resolved for local repositories.
+    pub fn upstream_hosted_remote(
+        &mut self,
+        branch: &Branch,
+    ) -> oneshot::Receiver<Result<Option<(GitRemote, SharedString)>>> {
+        let upstream = branch.upstream.clone();
+        self.send_job(None, move |repo, cx| async move {
+            let Some((remote_name, branch_name)) = upstream.as_ref().and_then(|upstream| {
+                let remote_name = upstream.remote_name()?;
+                let branch_name = upstream
+                    .stripped_ref_name()?
+                    .strip_prefix(remote_name)?
+                    .strip_prefix('/')?;
+                Some((remote_name, branch_name))
+            }) else {
+                return Ok(None);
+            };
+
+            match repo {
+                RepositoryState::Local { backend, .. } => {
+                    let Some(remote_url) = backend.remote_url(remote_name) else {
+                        return Ok(None);
+                    };
+                    let provider_registry =
+                        cx.update(GitHostingProviderRegistry::default_global)?;
+                    Ok(parse_git_remote_url(provider_registry, &remote_url).map(
+                        |(host, remote)| {
+                            let remote = GitRemote {
+                                host,
+                                owner: remote.owner.to_string(),
+                                repo: remote.repo.to_string(),
+                            };
+                            (remote, SharedString::from(branch_name.to_string()))
+                        },
+                    ))
+                }
+                RepositoryState::Remote { .. } => Ok(None),
+            }
+        })
+    }
+
+    /// Fetches the head of the given pull request from the `upstream` remote (or `origin`, if
+    /// there is no `upstream`) into a local `pr-<number>` branch and switches to it, returning
+    /// the name of the branch.
+    pub fn checkout_pull_request(
+        &mut self,
+        number: u32,
+        askpass: AskPassDelegate,
+    ) -> oneshot::Receiver<Result<String>> {
+        let branch_name = format!("pr-{number}");
+        self.send_job(
+            Some(format!("git checkout pull request #{number}").into()),
+            move |repo, cx| async move {
+                match repo {
+                    RepositoryState::Local {
+                        backend,
+                        environment,
+                        ..
+                    } => {
+                        let (remote_name, remote_url) = ["upstream", "origin"]
+                            .into_iter()
+                            .find_map(|remote_name| {
+                                Some((remote_name, backend.remote_url(remote_name)?))
+                            })
+                            .context("repository has no upstream or origin remote")?;
+                        let provider_registry =
+                            cx.update(GitHostingProviderRegistry::default_global)?;
+                        let (provider, _) = parse_git_remote_url(provider_registry, &remote_url)
+                            .with_context(|| {
+                                format!("{remote_name} is not on a known Git hosting provider")
+                            })?;
+                        let pull_request_ref =
+                            provider.pull_request_ref(number).with_context(|| {
+                                format!(
+                                    "{} does not support checking out pull requests",
+                                    provider.name()
+                                )
+                            })?;
+
+                        // Force-update the branch so that checking the pull request out again
+                        // picks up commits pushed since.
+                        backend
+                            .fetch_refspec(
+                                remote_name.to_string(),
+                                format!("+{pull_request_ref}:refs/heads/{branch_name}"),
+                                askpass,
+                                environment,
+                                cx,
+                            )
+                            .await?;
+                        backend.change_branch(branch_name.clone()).await?;
+                        Ok(branch_name)
+                    }
+                    RepositoryState::Remote { .. } => {
+                        anyhow::bail!(
+                            "checking out pull requests is not supported in remote projects"
+                        )
+                    }
+                }
+            },
+        )
+    }
+
     pub fn branches(&mut self) -> oneshot::Receiver<Result<Vec<Branch>>> {
         let id = self.id;
         self.send_job(None, move |repo, _| async move {
@@ -3821,18 +4581,21 @@ impl Repository {
             match repo {
                 RepositoryState::Local { backend, .. } => backend.diff(diff_type).await,
                 RepositoryState::Remote { project_id, client } => {
+                    let (diff_type, base_ref) = match diff_type {
+                        DiffType::HeadToIndex => (proto::git_diff::DiffType::HeadToIndex, None),
+                        DiffType::HeadToWorktree => {
+                            (proto::git_diff::DiffType::HeadToWorktree, None)
+                        }
+                        DiffType::MergeBase { base_ref } => {
+                            (proto::git_diff::DiffType::MergeBase, Some(base_ref))
+                        }
+                    };
                     let response = client
                         .request(proto::GitDiff {
                             project_id: project_id.0,
                             repository_id: id.to_proto(),
-                            diff_type: match diff_type {
-                                DiffType::HeadToIndex => {
-                                    proto::git_diff::DiffType::HeadToIndex.into()
-                                }
-                                DiffType::HeadToWorktree => {
-                                    proto::git_diff::DiffType::HeadToWorktree.into()
-                                }
-                            },
+                            diff_type: diff_type.into(),
+                            base_ref,
                         })
                         .await?;
 
@@ -3972,6 +4735,7 @@ impl Repository {
         if update.is_last_update {
             self.snapshot.scan_id = update.scan_id;
         }
+        cx.emit(RepositoryEvent::StatusesChanged { paths: None });
         cx.emit(RepositoryEvent::Updated { full_scan: true });
         Ok(())
     }
@@ -4026,18 +4790,22 @@ impl Repository {
                 let RepositoryState::Local { backend, .. } = state else {
                     bail!("not a local repository")
                 };
-                let (snapshot, events) = this
+                let (snapshot, events, status_cache_key) = this
                     .read_with(&mut cx, |this, _| {
                         compute_snapshot(
                             this.id,
                             this.work_directory_abs_path.clone(),
                             this.snapshot.clone(),
+                            this.status_cache.clone(),
                             backend.clone(),
                         )
                     })?
                     .await?;
                 this.update(&mut cx, |this, cx| {
                     this.snapshot = snapshot.clone();
+                    if let Some(status_cache) = &mut this.status_cache {
+                        status_cache.set_key(status_cache_key);
+                    }
                     for event in events {
                         cx.emit(event);
                     }
@@ -4289,20 +5057,28 @@ impl Repository {
                     .await;
 
                 this.update(&mut cx, |this, cx| {
-                    if !changed_path_statuses.is_empty() {
-                        this.snapshot
-                            .statuses_by_path
-                            .edit(changed_path_statuses, &());
-                        this.snapshot.scan_id += 1;
-                        if let Some(updates_tx) = updates_tx {
-                            updates_tx
-                                .unbounded_send(DownstreamUpdate::UpdateRepository(
-                                    this.snapshot.clone(),
-                                ))
-                                .ok();
-                        }
+                    if changed_path_statuses.is_empty() {
+                        return;
                     }
-                    cx.emit(RepositoryEvent::Updated { full_scan: false });
+                    let paths = changed_path_statuses
+                        .iter()
+                        .map(|edit| match edit {
+                            Edit::Insert(entry) => entry.repo_path.clone(),
+                            Edit::Remove(PathKey(path)) => RepoPath(path.clone()),
+                        })
+                        .collect();
+                    this.snapshot
+                        .statuses_by_path
+                        .edit(changed_path_statuses, &());
+                    this.snapshot.scan_id += 1;
+                    if let Some(updates_tx) = updates_tx {
+                        updates_tx
+                            .unbounded_send(DownstreamUpdate::UpdateRepository(
+                                this.snapshot.clone(),
+                            ))
+                            .ok();
+                    }
+                    cx.emit(RepositoryEvent::StatusesChanged { paths: Some(paths) });
                 })
             },
         );
@@ -4367,6 +5143,19 @@ fn get_permalink_in_rust_registry_src(
     Ok(permalink)
 }
 
+/// Failed background fetches back off up to this many doublings of the interval.
+const MAX_AUTO_FETCH_BACKOFF_EXPONENT: u32 = 4;
+
+/// Returns how long to wait before the next background fetch.
+///
+/// The interval doubles with each consecutive failure, so that an unreachable
+/// remote isn't retried as often. Up to a tenth of the delay is added at
+/// random, so that several open projects don't all fetch at the same moment.
+fn auto_fetch_delay(interval: Duration, consecutive_failures: u32, rng: &mut impl Rng) -> Duration {
+    let delay = interval * (1 << consecutive_failures.min(MAX_AUTO_FETCH_BACKOFF_EXPONENT));
+    delay + delay.mul_f64(rng.gen_range(0.0..0.1))
+}
+
 fn serialize_blame_buffer_response(blame: Option<git::blame::Blame>) -> proto::BlameBufferResponse {
     let Some(blame) = blame else {
         return proto::BlameBufferResponse {
@@ -4514,6 +5303,34 @@ fn proto_to_branch(proto: &proto::Branch) -> git::repository::Branch {
     }
 }
 
+fn submodule_to_proto(submodule: &Submodule) -> proto::GitSubmodule {
+    let status = match submodule.status {
+        SubmoduleStatus::UpToDate => proto::git_submodule::Status::UpToDate,
+        SubmoduleStatus::Uninitialized => proto::git_submodule::Status::Uninitialized,
+        SubmoduleStatus::OutOfSync => proto::git_submodule::Status::OutOfSync,
+        SubmoduleStatus::Conflicted => proto::git_submodule::Status::Conflicted,
+    };
+    proto::GitSubmodule {
+        path: submodule.path.as_ref().to_proto(),
+        sha: submodule.sha.to_string(),
+        status: status as i32,
+    }
+}
+
+fn proto_to_submodule(proto: &proto::GitSubmodule) -> Submodule {
+    let status = match proto::git_submodule::Status::from_i32(proto.status) {
+        Some(proto::git_submodule::Status::UpToDate) | None => SubmoduleStatus::UpToDate,
+        Some(proto::git_submodule::Status::Uninitialized) => SubmoduleStatus::Uninitialized,
+        Some(proto::git_submodule::Status::OutOfSync) => SubmoduleStatus::OutOfSync,
+        Some(proto::git_submodule::Status::Conflicted) => SubmoduleStatus::Conflicted,
+    };
+    Submodule {
+        path: RepoPath(Arc::<Path>::from_proto(proto.path.clone())),
+        sha: proto.sha.clone().into(),
+        status,
+    }
+}
+
 fn commit_details_to_proto(commit: &CommitDetails) -> proto::GitCommitDetails {
     proto::GitCommitDetails {
         sha: commit.sha.to_string(),
@@ -4538,30 +5355,50 @@ async fn compute_snapshot(
     id: RepositoryId,
     work_directory_abs_path: Arc<Path>,
     prev_snapshot: RepositorySnapshot,
+    status_cache: Option<StatusCache>,
     backend: Arc<dyn GitRepository>,
-) -> Result<(RepositorySnapshot, Vec<RepositoryEvent>)> {
+) -> Result<(
+    RepositorySnapshot,
+    Vec<RepositoryEvent>,
+    Option<StatusCacheKey>,
+)> {
     let mut events = Vec::new();
     let branches = backend.branches().await?;
     let branch = branches.into_iter().find(|branch| branch.is_head);
-    let statuses = backend.status(&[WORK_DIRECTORY_REPO_PATH.clone()]).await?;
-    let statuses_by_path = SumTree::from_iter(
-        statuses
-            .entries
-            .iter()
-            .map(|(repo_path, status)| StatusEntry {
-                repo_path: repo_path.clone(),
-                status: *status,
-            }),
-        &(),
-    );
+
+    // Read the key before scanning, so that changes made during the scan cause another one.
+    let status_cache_key = match &status_cache {
+        Some(status_cache) => status_cache.current_key(backend.as_ref()).await,
+        None => None,
+    };
+    let statuses_by_path = if status_cache
+        .as_ref()
+        .is_some_and(|status_cache| status_cache.is_fresh(status_cache_key.as_ref()))
+    {
+        log::debug!("index and HEAD unchanged, reusing git statuses");
+        prev_snapshot.statuses_by_path.clone()
+    } else {
+        let statuses = backend.status(&[WORK_DIRECTORY_REPO_PATH.clone()]).await?;
+        SumTree::from_iter(
+            statuses
+                .entries
+                .iter()
+                .map(|(repo_path, status)| StatusEntry {
+                    repo_path: repo_path.clone(),
+                    status: *status,
+                }),
+            &(),
+        )
+    };
+    let statuses_changed = statuses_by_path != prev_snapshot.statuses_by_path;
     let (merge_details, merge_heads_changed) =
         MergeDetails::load(&backend, &statuses_by_path, &prev_snapshot).await?;
     log::debug!("new merge details (changed={merge_heads_changed:?}): {merge_details:?}");
 
-    if merge_heads_changed
-        || branch != prev_snapshot.branch
-        || statuses_by_path != prev_snapshot.statuses_by_path
-    {
+    if statuses_changed {
+        events.push(RepositoryEvent::StatusesChanged { paths: None });
+    }
+    if merge_heads_changed || branch != prev_snapshot.branch || statuses_changed {
         events.push(RepositoryEvent::Updated { full_scan: true });
     }
 
@@ -4587,7 +5424,7 @@ async fn compute_snapshot(
         merge: merge_details,
     };
 
-    Ok((snapshot, events))
+    Ok((snapshot, events, status_cache_key))
 }
 
 fn status_from_proto(
@@ -4721,3 +5558,27 @@ fn tracked_status_to_proto(code: StatusCode) -> i32 {
         StatusCode::Unmodified => proto::GitStatus::Unmodified as _,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{SeedableRng as _, rngs::StdRng};
+
+    #[test]
+    fn test_auto_fetch_delay() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let interval = Duration::from_secs(300);
+        for (consecutive_failures, expected) in
+            [(0, 300), (1, 600), (2, 1200), (4, 4800), (10, 4800)]
+        {
+            for _ in 0..10 {
+                let delay = auto_fetch_delay(interval, consecutive_failures, &mut rng);
+                let expected = Duration::from_secs(expected);
+                assert!(
+                    delay >= expected && delay < expected.mul_f64(1.1),
+                    "{delay:?} after {consecutive_failures} failures"
+                );
+            }
+        }
+    }
+}
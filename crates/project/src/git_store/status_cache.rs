@@ -0,0 +1,89 @@
+use fs::{Fs, MTime};
+use git::repository::GitRepository;
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// Decides whether a full `git status` scan of a local repository can be skipped.
+///
+/// Changes to files in the working directory are already applied incrementally from
+/// worktree events, so a full rescan is only needed when something that affects the
+/// status of *every* path changed: the index, HEAD, the repository's config, or one of
+/// the exclude files.
+#[derive(Clone)]
+pub(crate) struct StatusCache {
+    fs: Arc<dyn Fs>,
+    index_path: Arc<Path>,
+    config_path: Arc<Path>,
+    exclude_path: Arc<Path>,
+    key: Option<StatusCacheKey>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct StatusCacheKey {
+    index_mtime: MTime,
+    index_len: u64,
+    config_mtime: Option<MTime>,
+    exclude_mtime: Option<MTime>,
+    /// `core.excludesFile` can be set in the global config, which isn't otherwise
+    /// tracked, so its resolved path is part of the key along with its mtime.
+    excludes_file: Option<(PathBuf, Option<MTime>)>,
+    head_sha: Option<String>,
+}
+
+impl StatusCache {
+    pub fn new(
+        fs: Arc<dyn Fs>,
+        repository_dir_abs_path: &Path,
+        common_dir_abs_path: &Path,
+    ) -> Self {
+        Self {
+            fs,
+            index_path: repository_dir_abs_path.join("index").into(),
+            config_path: common_dir_abs_path.join("config").into(),
+            exclude_path: common_dir_abs_path.join("info").join("exclude").into(),
+            key: None,
+        }
+    }
+
+    /// Reads the current key from disk. Returns `None` when the index can't be
+    /// inspected, in which case statuses are never considered cached.
+    pub async fn current_key(&self, backend: &dyn GitRepository) -> Option<StatusCacheKey> {
+        let index = self.fs.metadata(&self.index_path).await.ok()??;
+        let config_mtime = self.mtime(&self.config_path).await;
+        let exclude_mtime = self.mtime(&self.exclude_path).await;
+        let excludes_file = match backend.excludes_file() {
+            Some(path) => {
+                let mtime = self.mtime(&path).await;
+                Some((path, mtime))
+            }
+            None => None,
+        };
+        let head_sha = backend.head_sha().await;
+        Some(StatusCacheKey {
+            index_mtime: index.mtime,
+            index_len: index.len,
+            config_mtime,
+            exclude_mtime,
+            excludes_file,
+            head_sha,
+        })
+    }
+
+    async fn mtime(&self, path: &Path) -> Option<MTime> {
+        Some(self.fs.metadata(path).await.ok()??.mtime)
+    }
+
+    pub fn is_fresh(&self, key: Option<&StatusCacheKey>) -> bool {
+        key.is_some() && self.key.as_ref() == key
+    }
+
+    pub fn set_key(&mut self, key: Option<StatusCacheKey>) {
+        self.key = key;
+    }
+
+    pub fn invalidate(&mut self) {
+        self.key = None;
+    }
+}
@@ -212,10 +212,18 @@ pub fn is_image_file(project: &Entity<Project>, path: &ProjectPath, cx: &App) ->
             .map(str::to_lowercase)
     });
 
-    match ext {
-        Some(ext) => Img::extensions().contains(&ext.as_str()) && !ext.contains("svg"),
-        None => false,
-    }
+    ext.is_some_and(|ext| is_image_extension(&ext))
+}
+
+/// Returns whether a path outside of any worktree points to an image that can be opened.
+pub fn is_image_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(OsStr::to_str)
+        .is_some_and(|ext| is_image_extension(&ext.to_lowercase()))
+}
+
+fn is_image_extension(ext: &str) -> bool {
+    Img::extensions().contains(&ext) && !ext.contains("svg")
 }
 
 impl ProjectItem for ImageItem {
@@ -685,7 +693,7 @@ impl LocalImageStore {
     }
 }
 
-fn create_gpui_image(content: Vec<u8>) -> anyhow::Result<Arc<gpui::Image>> {
+pub fn create_gpui_image(content: Vec<u8>) -> anyhow::Result<Arc<gpui::Image>> {
     let format = image::guess_format(&content)?;
 
     Ok(Arc::new(gpui::Image::from_bytes(
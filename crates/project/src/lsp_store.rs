@@ -3858,7 +3858,8 @@ impl LspStore {
     ) {
         match evt {
             extension::Event::ExtensionInstalled(_)
-            | extension::Event::ConfigureExtensionRequested(_) => return,
+            | extension::Event::ConfigureExtensionRequested(_)
+            | extension::Event::ExtensionCapabilitiesChanged(_) => return,
             extension::Event::ExtensionsInstalledChanged => {}
         }
         if self.as_local().is_none() {
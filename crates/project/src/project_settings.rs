@@ -246,6 +246,11 @@ pub struct GitSettings {
     ///
     /// Default: staged_hollow
     pub hunk_style: Option<GitHunkStyleSetting>,
+    /// Whether to periodically fetch the remotes of the active repository
+    /// in the background.
+    ///
+    /// Default: disabled
+    pub auto_fetch: Option<AutoFetchSettings>,
 }
 
 impl GitSettings {
@@ -267,6 +272,21 @@ impl GitSettings {
         }
     }
 
+    /// Returns how often to fetch in the background, if at all.
+    pub fn auto_fetch_interval(&self) -> Option<Duration> {
+        match self.auto_fetch {
+            Some(AutoFetchSettings {
+                enabled: true,
+                interval_seconds,
+            }) => Some(Duration::from_secs(
+                interval_seconds
+                    .unwrap_or(DEFAULT_AUTO_FETCH_INTERVAL_SECONDS)
+                    .max(MIN_AUTO_FETCH_INTERVAL_SECONDS),
+            )),
+            _ => None,
+        }
+    }
+
     pub fn show_inline_commit_summary(&self) -> bool {
         match self.inline_blame {
             Some(InlineBlameSettings {
@@ -323,6 +343,24 @@ pub struct InlineBlameSettings {
     pub show_commit_summary: bool,
 }
 
+const DEFAULT_AUTO_FETCH_INTERVAL_SECONDS: u64 = 300;
+const MIN_AUTO_FETCH_INTERVAL_SECONDS: u64 = 60;
+
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct AutoFetchSettings {
+    /// Whether to fetch in the background.
+    ///
+    /// Default: false
+    #[serde(default)]
+    pub enabled: bool,
+    /// How often to fetch, in seconds. Intervals shorter than a minute
+    /// are rounded up to a minute.
+    ///
+    /// Default: 300
+    pub interval_seconds: Option<u64>,
+}
+
 #[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 pub struct BinarySettings {
     pub path: Option<String>,
@@ -12,7 +12,7 @@ use fs::FakeFs;
 use futures::{StreamExt, future};
 use git::{
     GitHostingProviderRegistry,
-    repository::RepoPath,
+    repository::{ApplyCommitMode, ApplyCommitOutcome, RepoPath},
     status::{StatusCode, TrackedStatus},
 };
 use git2::RepositoryInitOptions;
@@ -7855,6 +7855,92 @@ async fn test_repository_subfolder_git_status(
     });
 }
 
+#[gpui::test]
+async fn test_git_status_cache(executor: gpui::BackgroundExecutor, cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(executor);
+    fs.insert_tree(
+        path!("/root"),
+        json!({
+            "my-repo": {
+                ".git": {
+                    "config": "",
+                    "index": "",
+                    "info": {
+                        "exclude": "",
+                    },
+                },
+                "a.txt": "a",
+                "b.txt": "b",
+            },
+        }),
+    )
+    .await;
+    fs.set_status_for_repo(
+        path!("/root/my-repo/.git").as_ref(),
+        &[("a.txt".as_ref(), FileStatus::Untracked)],
+    );
+
+    let project = Project::test(fs.clone(), [path!("/root/my-repo").as_ref()], cx).await;
+    project
+        .update(cx, |project, cx| project.git_scans_complete(cx))
+        .await;
+    cx.run_until_parked();
+
+    let repository = project.read_with(cx, |project, cx| {
+        project.repositories(cx).values().next().unwrap().clone()
+    });
+    repository.read_with(cx, |repository, _cx| {
+        assert_eq!(
+            repository.status_for_path(&"a.txt".into()).unwrap().status,
+            FileStatus::Untracked
+        );
+    });
+
+    // Each of these files affects the status of every path, so writing any of them
+    // invalidates the cached statuses.
+    let invalidating_files = [
+        path!("/root/my-repo/.git/index"),
+        path!("/root/my-repo/.git/config"),
+        path!("/root/my-repo/.git/info/exclude"),
+    ];
+    let mut untracked = "a.txt";
+    for (ix, invalidating_file) in invalidating_files.into_iter().enumerate() {
+        let previously_untracked = untracked;
+        untracked = if untracked == "a.txt" {
+            "b.txt"
+        } else {
+            "a.txt"
+        };
+        fs.set_status_for_repo(
+            path!("/root/my-repo/.git").as_ref(),
+            &[(untracked.as_ref(), FileStatus::Untracked)],
+        );
+        fs.insert_file(invalidating_file, ix.to_string().into_bytes())
+            .await;
+        project
+            .update(cx, |project, cx| project.git_scans_complete(cx))
+            .await;
+        cx.run_until_parked();
+        repository.read_with(cx, |repository, _cx| {
+            assert_eq!(
+                repository
+                    .status_for_path(&untracked.into())
+                    .unwrap()
+                    .status,
+                FileStatus::Untracked,
+                "after writing {invalidating_file}"
+            );
+            assert_eq!(
+                repository.status_for_path(&previously_untracked.into()),
+                None,
+                "after writing {invalidating_file}"
+            );
+        });
+    }
+}
+
 // TODO: this test is flaky (especially on Windows but at least sometimes on all platforms).
 #[cfg(any())]
 #[gpui::test]
@@ -8003,6 +8089,98 @@ async fn test_update_gitignore(cx: &mut gpui::TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_apply_commit(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree(
+        path!("/root"),
+        json!({
+            ".git": {},
+            "a.txt": "one",
+            "b.txt": "two",
+        }),
+    )
+    .await;
+
+    let dot_git = Path::new(path!("/root/.git"));
+    fs.set_head_and_index_for_repo(
+        dot_git,
+        &[
+            ("a.txt".into(), "one".into()),
+            ("b.txt".into(), "two".into()),
+        ],
+    );
+    fs.set_ref_contents_for_repo(
+        dot_git,
+        "def~1",
+        &[
+            ("a.txt".into(), "one".into()),
+            ("b.txt".into(), "two".into()),
+        ],
+    );
+    fs.set_ref_contents_for_repo(
+        dot_git,
+        "def",
+        &[
+            ("a.txt".into(), "one".into()),
+            ("b.txt".into(), "TWO".into()),
+        ],
+    );
+    fs.set_ref_contents_for_repo(dot_git, "fed~1", &[("a.txt".into(), "ein".into())]);
+    fs.set_ref_contents_for_repo(dot_git, "fed", &[("a.txt".into(), "uno".into())]);
+
+    let project = Project::test(fs.clone(), [path!("/root").as_ref()], cx).await;
+    cx.executor().run_until_parked();
+
+    let repository = project.read_with(cx, |project, cx| {
+        project.repositories(cx).values().next().unwrap().clone()
+    });
+    let apply_commit = |commit: &str, mode: ApplyCommitMode, cx: &mut gpui::TestAppContext| {
+        repository.update(cx, |repository, cx| {
+            repository.apply_commit(commit.into(), mode, cx)
+        })
+    };
+
+    let outcome = apply_commit("def", ApplyCommitMode::CherryPick, cx)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(outcome, ApplyCommitOutcome::Applied);
+    assert_eq!(fs.load(path!("/root/b.txt").as_ref()).await.unwrap(), "TWO");
+
+    // The commit's changes are already on HEAD, which isn't a conflict.
+    let outcome = apply_commit("def", ApplyCommitMode::CherryPick, cx)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(outcome, ApplyCommitOutcome::Empty);
+
+    let outcome = apply_commit("def", ApplyCommitMode::Revert, cx)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(outcome, ApplyCommitOutcome::Applied);
+    assert_eq!(fs.load(path!("/root/b.txt").as_ref()).await.unwrap(), "two");
+
+    let outcome = apply_commit("def", ApplyCommitMode::Revert, cx)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(outcome, ApplyCommitOutcome::Empty);
+
+    let outcome = apply_commit("fed", ApplyCommitMode::CherryPick, cx)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(outcome, ApplyCommitOutcome::Conflicted);
+    cx.executor().run_until_parked();
+    repository.read_with(cx, |repository, _| {
+        let status = repository.status_for_path(&"a.txt".into()).unwrap().status;
+        assert!(status.is_conflicted());
+    });
+}
+
 // NOTE:
 // This test always fails on Windows, because on Windows, unlike on Unix, you can't rename
 // a directory which some program has already open.
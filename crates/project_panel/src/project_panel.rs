@@ -31,7 +31,7 @@ use menu::{Confirm, SelectFirst, SelectLast, SelectNext, SelectPrevious};
 use project::{
     Entry, EntryKind, Fs, GitEntry, GitEntryRef, GitTraversal, Project, ProjectEntryId,
     ProjectPath, Worktree, WorktreeId,
-    git_store::{GitStoreEvent, git_traversal::ChildEntriesGitIter},
+    git_store::{GitStoreEvent, RepositoryEvent, git_traversal::ChildEntriesGitIter},
     relativize_path,
 };
 use project_panel_settings::{
@@ -327,7 +327,7 @@ impl ProjectPanel {
             .detach();
 
             cx.subscribe(&git_store, |this, _, event, cx| match event {
-                GitStoreEvent::RepositoryUpdated(_, _, _)
+                GitStoreEvent::RepositoryUpdated(_, RepositoryEvent::StatusesChanged { .. }, _)
                 | GitStoreEvent::RepositoryAdded(_)
                 | GitStoreEvent::RepositoryRemoved(_) => {
                     this.update_visible_entries(None, cx);
@@ -3830,6 +3830,11 @@ impl ProjectPanel {
 
         let filename_text_color = details.filename_text_color;
         let diagnostic_severity = details.diagnostic_severity;
+        let git_status_badge = if settings.git_status_badges && !details.is_ignored {
+            git_status_badge(details.git_status, kind, details.is_expanded)
+        } else {
+            None
+        };
         let item_colors = get_item_color(cx);
 
         let canonical_path = details
@@ -4153,28 +4158,44 @@ impl ProjectPanel {
                         }
                     })
                     .selectable(false)
-                    .when_some(canonical_path, |this, path| {
-                        this.end_slot::<AnyElement>(
-                            div()
-                                .id("symlink_icon")
-                                .pr_3()
-                                .tooltip(move |window, cx| {
-                                    Tooltip::with_meta(
-                                        path.to_string(),
-                                        None,
-                                        "Symbolic Link",
-                                        window,
-                                        cx,
-                                    )
-                                })
-                                .child(
-                                    Icon::new(IconName::ArrowUpRight)
-                                        .size(IconSize::Indicator)
-                                        .color(filename_text_color),
-                                )
-                                .into_any_element(),
-                        )
-                    })
+                    .when(
+                        canonical_path.is_some() || git_status_badge.is_some(),
+                        |this| {
+                            this.end_slot::<AnyElement>(
+                                h_flex()
+                                    .gap_1()
+                                    .pr_3()
+                                    .when_some(git_status_badge, |this, badge| {
+                                        this.child(
+                                            Label::new(badge)
+                                                .size(LabelSize::Small)
+                                                .color(filename_text_color),
+                                        )
+                                    })
+                                    .when_some(canonical_path, |this, path| {
+                                        this.child(
+                                            div()
+                                                .id("symlink_icon")
+                                                .tooltip(move |window, cx| {
+                                                    Tooltip::with_meta(
+                                                        path.to_string(),
+                                                        None,
+                                                        "Symbolic Link",
+                                                        window,
+                                                        cx,
+                                                    )
+                                                })
+                                                .child(
+                                                    Icon::new(IconName::ArrowUpRight)
+                                                        .size(IconSize::Indicator)
+                                                        .color(filename_text_color),
+                                                ),
+                                        )
+                                    })
+                                    .into_any_element(),
+                            )
+                        },
+                    )
                     .child(if let Some(icon) = &icon {
                         if let Some((_, decoration_color)) =
                             entry_diagnostic_aware_icon_decoration_and_color(diagnostic_severity)
@@ -4680,6 +4701,22 @@ impl ProjectPanel {
     }
 }
 
+/// Files show the letter of their most significant change, collapsed directories show
+/// how many changed files they contain, since those files aren't visible.
+fn git_status_badge(
+    git_status: GitSummary,
+    kind: EntryKind,
+    is_expanded: bool,
+) -> Option<SharedString> {
+    if kind.is_dir() {
+        (!is_expanded && git_status.count > 0).then(|| git_status.count.to_string().into())
+    } else {
+        git_status
+            .status_letter()
+            .map(|letter| letter.to_string().into())
+    }
+}
+
 fn item_width_estimate(depth: usize, item_text_chars: usize, is_symlink: bool) -> usize {
     const ICON_SIZE_FACTOR: usize = 2;
     let mut item_width = depth * ICON_SIZE_FACTOR + item_text_chars;
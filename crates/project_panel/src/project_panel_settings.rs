@@ -38,6 +38,7 @@ pub struct ProjectPanelSettings {
     pub file_icons: bool,
     pub folder_icons: bool,
     pub git_status: bool,
+    pub git_status_badges: bool,
     pub indent_size: f32,
     pub indent_guides: IndentGuidesSettings,
     pub auto_reveal_entries: bool,
@@ -122,6 +123,12 @@ pub struct ProjectPanelSettingsContent {
     ///
     /// Default: true
     pub git_status: Option<bool>,
+    /// Whether to show a git status letter next to changed files, and the number
+    /// of changed files next to collapsed directories. Has no effect when
+    /// `git_status` is disabled.
+    ///
+    /// Default: true
+    pub git_status_badges: Option<bool>,
     /// Amount of indentation (in pixels) for nested items.
     ///
     /// Default: 20
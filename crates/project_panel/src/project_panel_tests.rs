@@ -4639,6 +4639,40 @@ async fn test_selection_fallback_to_next_highest_worktree(cx: &mut gpui::TestApp
     );
 }
 
+#[test]
+fn test_git_status_badge() {
+    use git::status::TrackedSummary;
+
+    let modified = GitSummary {
+        worktree: TrackedSummary::MODIFIED,
+        count: 1,
+        ..GitSummary::UNCHANGED
+    };
+    assert_eq!(
+        git_status_badge(modified, EntryKind::File, false).as_deref(),
+        Some("M")
+    );
+    assert_eq!(
+        git_status_badge(GitSummary::UNTRACKED, EntryKind::File, false).as_deref(),
+        Some("U")
+    );
+    assert_eq!(
+        git_status_badge(GitSummary::CONFLICT + modified, EntryKind::File, false).as_deref(),
+        Some("C")
+    );
+    assert_eq!(
+        git_status_badge(GitSummary::UNCHANGED, EntryKind::File, false),
+        None
+    );
+
+    let directory = modified + GitSummary::UNTRACKED + GitSummary::UNTRACKED;
+    assert_eq!(
+        git_status_badge(directory, EntryKind::Dir, false).as_deref(),
+        Some("3")
+    );
+    assert_eq!(git_status_badge(directory, EntryKind::Dir, true), None);
+}
+
 fn toggle_expand_dir(
     panel: &Entity<ProjectPanel>,
     path: impl AsRef<Path>,
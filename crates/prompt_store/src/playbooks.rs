@@ -0,0 +1,83 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use gpui::SharedString;
+use handlebars::Handlebars;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct PlaybookId(pub Uuid);
+
+impl PlaybookId {
+    pub fn new() -> PlaybookId {
+        PlaybookId(Uuid::new_v4())
+    }
+}
+
+/// A named prompt template for a recurring agent workflow, such as writing tests for the selected
+/// code. Running a playbook starts a new thread with its rendered template as the first message.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Playbook {
+    pub id: PlaybookId,
+    pub title: SharedString,
+    /// A handlebars template, rendered with [`PlaybookVariables`].
+    pub template: String,
+    /// The agent profile to switch to before running the playbook.
+    #[serde(default)]
+    pub profile_id: Option<SharedString>,
+    pub saved_at: DateTime<Utc>,
+}
+
+/// The values available to a playbook's template, taken from the active editor when it runs.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct PlaybookVariables {
+    pub selection: Option<String>,
+    /// The path of the active file, relative to its worktree.
+    pub file: Option<String>,
+    pub language: Option<String>,
+}
+
+impl Playbook {
+    pub fn render(&self, variables: &PlaybookVariables) -> Result<String> {
+        let mut handlebars = Handlebars::new();
+        // The rendered prompt is sent to the model as is, so code in the selection must not be
+        // HTML-escaped.
+        handlebars.register_escape_fn(handlebars::no_escape);
+        Ok(handlebars.render_template(&self.template, variables)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_playbook_render() {
+        let playbook = Playbook {
+            id: PlaybookId::new(),
+            title: "Write tests".into(),
+            template: "Write tests for this {{language}} code in `{{file}}`:\n\n{{selection}}\n\
+                {{#if language}}Use the usual test layout.{{/if}}"
+                .into(),
+            profile_id: None,
+            saved_at: Utc::now(),
+        };
+
+        let rendered = playbook
+            .render(&PlaybookVariables {
+                selection: Some("fn a() -> bool { 1 < 2 }".into()),
+                file: Some("src/lib.rs".into()),
+                language: Some("Rust".into()),
+            })
+            .unwrap();
+        assert_eq!(
+            rendered,
+            "Write tests for this Rust code in `src/lib.rs`:\n\n\
+                fn a() -> bool { 1 < 2 }\nUse the usual test layout."
+        );
+
+        let rendered = playbook.render(&PlaybookVariables::default()).unwrap();
+        assert_eq!(rendered, "Write tests for this  code in ``:\n\n\n");
+    }
+}
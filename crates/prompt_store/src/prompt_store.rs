@@ -1,3 +1,4 @@
+mod playbooks;
 mod prompts;
 
 use anyhow::{Context as _, Result, anyhow};
@@ -13,20 +14,35 @@ use heed::{
     Database, RoTxn,
     types::{SerdeBincode, SerdeJson, Str},
 };
+use language::LanguageName;
 use parking_lot::RwLock;
+pub use playbooks::*;
 pub use prompts::*;
 use rope::Rope;
 use serde::{Deserialize, Serialize};
 use std::{
     cmp::Reverse,
     future::Future,
-    path::PathBuf,
+    path::{Path, PathBuf},
     sync::{Arc, atomic::AtomicBool},
+    time::Duration,
 };
 use text::LineEnding;
-use util::ResultExt;
+use util::{ResultExt, paths::PathMatcher};
 use uuid::Uuid;
 
+/// The maximum number of revisions kept for each prompt. Older revisions are discarded.
+const MAX_REVISIONS_PER_PROMPT: usize = 50;
+
+/// The rules library saves continuously while typing, so a save replaces the latest revision
+/// when it follows the previous save within this interval. Pausing for longer keeps the latest
+/// revision around, so that there's always a version from before the current burst of edits.
+const REVISION_IDLE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// The longest span of continuous editing that a single revision covers, so that a long editing
+/// session without pauses still leaves versions to go back to.
+const MAX_REVISION_SPAN: Duration = Duration::from_secs(5 * 60);
+
 /// Init starts loading the PromptStore in the background and assigns
 /// a shared future to a global.
 pub fn init(cx: &mut App) {
@@ -49,6 +65,182 @@ pub struct PromptMetadata {
     pub title: Option<SharedString>,
     pub default: bool,
     pub saved_at: DateTime<Utc>,
+    #[serde(default)]
+    pub scope: RuleScope,
+}
+
+/// Limits a rule to threads involving files in certain languages or paths. A rule with an empty
+/// scope applies to every thread.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RuleScope {
+    #[serde(default)]
+    pub languages: Vec<LanguageName>,
+    /// Globs matched against paths relative to their worktree.
+    #[serde(default)]
+    pub paths: Vec<SharedString>,
+}
+
+/// A file that's part of a thread, either as attached context, as the active file, or because a
+/// tool operated on it.
+#[derive(Clone, Debug)]
+pub struct ScopedFile {
+    /// The root name of the file's worktree.
+    pub root_name: String,
+    /// The path relative to the file's worktree.
+    pub path: Arc<Path>,
+    pub language: Option<LanguageName>,
+}
+
+impl RuleScope {
+    pub fn is_empty(&self) -> bool {
+        self.languages.is_empty() && self.paths.is_empty()
+    }
+
+    /// Parses a comma-separated list of language names and path globs. Entries containing a
+    /// path separator, a dot, or glob syntax are treated as paths.
+    pub fn parse(text: &str) -> Self {
+        let mut scope = Self::default();
+        for entry in text
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+        {
+            if entry.contains(['/', '\\', '.', '*', '?', '[', '{']) {
+                scope.paths.push(SharedString::from(entry.to_string()));
+            } else {
+                scope.languages.push(LanguageName::new(entry));
+            }
+        }
+        scope
+    }
+
+    /// Returns whether any of the files is in one of the scope's languages and paths.
+    pub fn matches_any(&self, files: &[ScopedFile]) -> bool {
+        if self.is_empty() {
+            return true;
+        }
+
+        let path_matcher = if self.paths.is_empty() {
+            None
+        } else {
+            match PathMatcher::new(&self.paths) {
+                Ok(path_matcher) => Some(path_matcher),
+                Err(error) => {
+                    log::error!("invalid path glob in rule scope: {error}");
+                    return false;
+                }
+            }
+        };
+        files.iter().any(|file| {
+            let matches_language = self.languages.is_empty()
+                || file.language.as_ref().is_some_and(|language| {
+                    self.languages.iter().any(|scope_language| {
+                        scope_language
+                            .as_ref()
+                            .eq_ignore_ascii_case(language.as_ref())
+                    })
+                });
+            let matches_path = path_matcher
+                .as_ref()
+                .is_none_or(|path_matcher| path_matcher.is_match(&file.path));
+            matches_language && matches_path
+        })
+    }
+}
+
+impl std::fmt::Display for RuleScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let entries = self
+            .languages
+            .iter()
+            .map(|language| language.as_ref())
+            .chain(self.paths.iter().map(|path| path.as_ref()));
+        for (ix, entry) in entries.enumerate() {
+            if ix > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{entry}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A snapshot of a prompt's title and body, recorded whenever the prompt is saved.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PromptRevision {
+    pub title: Option<SharedString>,
+    pub body: String,
+    pub saved_at: DateTime<Utc>,
+    /// When the first of the saves folded into this revision happened.
+    #[serde(default)]
+    pub first_saved_at: Option<DateTime<Utc>>,
+}
+
+impl PromptRevision {
+    /// Returns the number of lines added and removed when going from this revision to `text`.
+    pub fn line_changes(&self, text: &str) -> (usize, usize) {
+        let mut revision_lines = HashMap::<&str, usize>::default();
+        for line in self.body.lines() {
+            *revision_lines.entry(line).or_default() += 1;
+        }
+
+        let mut added = 0;
+        for line in text.lines() {
+            match revision_lines.get_mut(line) {
+                Some(count) if *count > 0 => *count -= 1,
+                _ => added += 1,
+            }
+        }
+        let removed = revision_lines.values().sum();
+        (added, removed)
+    }
+}
+
+fn push_revision(revisions: &mut Vec<PromptRevision>, mut revision: PromptRevision) {
+    let elapsed_since = |time: DateTime<Utc>| (revision.saved_at - time).to_std().ok();
+    let coalesce = revisions.last().and_then(|latest| {
+        let first_saved_at = latest.first_saved_at.unwrap_or(latest.saved_at);
+        let is_same_burst = elapsed_since(latest.saved_at)? < REVISION_IDLE_INTERVAL
+            && elapsed_since(first_saved_at)? < MAX_REVISION_SPAN;
+        is_same_burst.then_some(first_saved_at)
+    });
+    if let Some(first_saved_at) = coalesce {
+        revisions.pop();
+        revision.first_saved_at = Some(first_saved_at);
+    }
+    revisions.push(revision);
+    if revisions.len() > MAX_REVISIONS_PER_PROMPT {
+        revisions.drain(..revisions.len() - MAX_REVISIONS_PER_PROMPT);
+    }
+}
+
+/// A portable collection of user rules, used to share rules between users and installations.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RulesBundle {
+    pub version: u32,
+    pub rules: Vec<RulesBundleEntry>,
+}
+
+impl RulesBundle {
+    pub const VERSION: u32 = 1;
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RulesBundleEntry {
+    pub title: Option<SharedString>,
+    #[serde(default)]
+    pub default: bool,
+    #[serde(default, skip_serializing_if = "RuleScope::is_empty")]
+    pub scope: RuleScope,
+    pub body: String,
+}
+
+/// What to do when an imported rule has the same title as an existing one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RulesImportConflict {
+    Skip,
+    Overwrite,
+    KeepBoth,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -95,6 +287,8 @@ pub struct PromptStore {
     metadata_cache: RwLock<MetadataCache>,
     metadata: Database<SerdeJson<PromptId>, SerdeJson<PromptMetadata>>,
     bodies: Database<SerdeJson<PromptId>, Str>,
+    revisions: Database<SerdeJson<PromptId>, SerdeJson<Vec<PromptRevision>>>,
+    playbooks: Database<SerdeJson<PlaybookId>, SerdeJson<Playbook>>,
 }
 
 pub struct PromptsUpdatedEvent;
@@ -159,13 +353,15 @@ impl PromptStore {
             let db_env = unsafe {
                 heed::EnvOpenOptions::new()
                     .map_size(1024 * 1024 * 1024) // 1GB
-                    .max_dbs(4) // Metadata and bodies (possibly v1 of both as well)
+                    .max_dbs(6) // Metadata, bodies, revisions and playbooks (possibly v1 of the first two as well)
                     .open(db_path)?
             };
 
             let mut txn = db_env.write_txn()?;
             let metadata = db_env.create_database(&mut txn, Some("metadata.v2"))?;
             let bodies = db_env.create_database(&mut txn, Some("bodies.v2"))?;
+            let revisions = db_env.create_database(&mut txn, Some("revisions.v1"))?;
+            let playbooks = db_env.create_database(&mut txn, Some("playbooks.v1"))?;
 
             // Remove edit workflow prompt, as we decided to opt into it using
             // a slash command instead.
@@ -185,6 +381,8 @@ impl PromptStore {
                 metadata_cache: RwLock::new(metadata_cache),
                 metadata,
                 bodies,
+                revisions,
+                playbooks,
             })
         })
     }
@@ -250,6 +448,7 @@ impl PromptStore {
                         title: metadata_v1.title.clone(),
                         default: metadata_v1.default,
                         saved_at: metadata_v1.saved_at,
+                        scope: RuleScope::default(),
                     },
                 )?;
                 bodies_db.put(&mut txn, &prompt_id_v2, &body_v1)?;
@@ -272,6 +471,18 @@ impl PromptStore {
         })
     }
 
+    /// Returns the saved revisions of a prompt, newest first.
+    pub fn revisions(&self, id: PromptId, cx: &App) -> Task<Result<Vec<PromptRevision>>> {
+        let env = self.env.clone();
+        let revisions = self.revisions;
+        cx.background_spawn(async move {
+            let txn = env.read_txn()?;
+            let mut revisions = revisions.get(&txn, &id)?.unwrap_or_default();
+            revisions.reverse();
+            Ok(revisions)
+        })
+    }
+
     pub fn all_prompt_metadata(&self) -> Vec<PromptMetadata> {
         self.metadata_cache.read().metadata.clone()
     }
@@ -293,12 +504,14 @@ impl PromptStore {
         let db_connection = self.env.clone();
         let bodies = self.bodies;
         let metadata = self.metadata;
+        let revisions = self.revisions;
 
         let task = cx.background_spawn(async move {
             let mut txn = db_connection.write_txn()?;
 
             metadata.delete(&mut txn, &id)?;
             bodies.delete(&mut txn, &id)?;
+            revisions.delete(&mut txn, &id)?;
 
             txn.commit()?;
             anyhow::Ok(())
@@ -333,6 +546,112 @@ impl PromptStore {
         Some(metadata.id)
     }
 
+    /// Returns the existing user rules whose title matches one of the rules in the bundle.
+    pub fn conflicting_titles(&self, bundle: &RulesBundle) -> Vec<SharedString> {
+        bundle
+            .rules
+            .iter()
+            .filter_map(|entry| entry.title.clone())
+            .filter(|title| self.user_id_for_title(title).is_some())
+            .collect()
+    }
+
+    fn user_id_for_title(&self, title: &str) -> Option<PromptId> {
+        self.id_for_title(title).filter(|id| !id.is_built_in())
+    }
+
+    pub fn export_bundle(&self, cx: &App) -> Task<Result<RulesBundle>> {
+        let env = self.env.clone();
+        let bodies = self.bodies;
+        let metadata = self
+            .all_prompt_metadata()
+            .into_iter()
+            .filter(|metadata| !metadata.id.is_built_in())
+            .collect::<Vec<_>>();
+        cx.background_spawn(async move {
+            let txn = env.read_txn()?;
+            let mut rules = Vec::with_capacity(metadata.len());
+            for metadata in metadata {
+                let Some(body) = bodies.get(&txn, &metadata.id)? else {
+                    continue;
+                };
+                rules.push(RulesBundleEntry {
+                    title: metadata.title,
+                    default: metadata.default,
+                    scope: metadata.scope,
+                    body: body.to_string(),
+                });
+            }
+            Ok(RulesBundle {
+                version: RulesBundle::VERSION,
+                rules,
+            })
+        })
+    }
+
+    /// Saves the rules of the bundle, returning the ids of the rules that were imported,
+    /// including existing rules that were overwritten.
+    pub fn import_bundle(
+        &self,
+        bundle: RulesBundle,
+        on_conflict: RulesImportConflict,
+        cx: &Context<Self>,
+    ) -> Task<Result<Vec<PromptId>>> {
+        if bundle.version > RulesBundle::VERSION {
+            return Task::ready(Err(anyhow!(
+                "rules bundle version {} is newer than the supported version {}",
+                bundle.version,
+                RulesBundle::VERSION
+            )));
+        }
+
+        let mut ids = Vec::new();
+        let mut saves = Vec::new();
+        for entry in bundle.rules {
+            let existing_id = entry
+                .title
+                .as_ref()
+                .and_then(|title| self.user_id_for_title(title));
+            let (id, title) = match (existing_id, on_conflict) {
+                (None, _) => (PromptId::new(), entry.title),
+                (Some(_), RulesImportConflict::Skip) => continue,
+                (Some(id), RulesImportConflict::Overwrite) => (id, entry.title),
+                (Some(_), RulesImportConflict::KeepBoth) => {
+                    let title = entry.title.unwrap_or_default();
+                    let mut i = 1;
+                    let unique_title = loop {
+                        let candidate = if i == 1 {
+                            format!("{title} (imported)")
+                        } else {
+                            format!("{title} (imported {i})")
+                        };
+                        if self.user_id_for_title(&candidate).is_none() {
+                            break candidate;
+                        }
+                        i += 1;
+                    };
+                    (PromptId::new(), Some(unique_title.into()))
+                }
+            };
+            ids.push(id);
+            saves.push(self.save_with_scope(
+                id,
+                title,
+                entry.default,
+                entry.scope,
+                entry.body.into(),
+                cx,
+            ));
+        }
+
+        cx.spawn(async move |_, _| {
+            for save in saves {
+                save.await?;
+            }
+            Ok(ids)
+        })
+    }
+
     pub fn search(
         &self,
         query: String,
@@ -371,6 +690,7 @@ impl PromptStore {
         })
     }
 
+    /// Saves a prompt, keeping its existing scope.
     pub fn save(
         &self,
         id: PromptId,
@@ -378,6 +698,19 @@ impl PromptStore {
         default: bool,
         body: Rope,
         cx: &Context<Self>,
+    ) -> Task<Result<()>> {
+        let scope = self.scope(id);
+        self.save_with_scope(id, title, default, scope, body, cx)
+    }
+
+    fn save_with_scope(
+        &self,
+        id: PromptId,
+        title: Option<SharedString>,
+        default: bool,
+        scope: RuleScope,
+        body: Rope,
+        cx: &Context<Self>,
     ) -> Task<Result<()>> {
         if id.is_built_in() {
             return Task::ready(Err(anyhow!("built-in prompts cannot be saved")));
@@ -388,18 +721,33 @@ impl PromptStore {
             title,
             default,
             saved_at: Utc::now(),
+            scope,
         };
         self.metadata_cache.write().insert(prompt_metadata.clone());
 
         let db_connection = self.env.clone();
         let bodies = self.bodies;
         let metadata = self.metadata;
+        let revisions_db = self.revisions;
 
         let task = cx.background_spawn(async move {
             let mut txn = db_connection.write_txn()?;
 
+            let body = body.to_string();
+            let mut revisions = revisions_db.get(&txn, &id)?.unwrap_or_default();
+            push_revision(
+                &mut revisions,
+                PromptRevision {
+                    title: prompt_metadata.title.clone(),
+                    body: body.clone(),
+                    saved_at: prompt_metadata.saved_at,
+                    first_saved_at: None,
+                },
+            );
+
             metadata.put(&mut txn, &id, &prompt_metadata)?;
-            bodies.put(&mut txn, &id, &body.to_string())?;
+            bodies.put(&mut txn, &id, &body)?;
+            revisions_db.put(&mut txn, &id, &revisions)?;
 
             txn.commit()?;
 
@@ -413,6 +761,55 @@ impl PromptStore {
         })
     }
 
+    /// Returns all playbooks, sorted by title.
+    pub fn playbooks(&self, cx: &App) -> Task<Result<Vec<Playbook>>> {
+        let env = self.env.clone();
+        let playbooks_db = self.playbooks;
+        cx.background_spawn(async move {
+            let txn = env.read_txn()?;
+            let mut playbooks = playbooks_db
+                .iter(&txn)?
+                .map(|result| Ok(result?.1))
+                .collect::<Result<Vec<_>>>()?;
+            playbooks.sort_by(|a, b| a.title.cmp(&b.title));
+            Ok(playbooks)
+        })
+    }
+
+    pub fn save_playbook(&self, playbook: Playbook, cx: &Context<Self>) -> Task<Result<()>> {
+        let env = self.env.clone();
+        let playbooks = self.playbooks;
+        let task = cx.background_spawn(async move {
+            let mut txn = env.write_txn()?;
+            playbooks.put(&mut txn, &playbook.id, &playbook)?;
+            txn.commit()?;
+            anyhow::Ok(())
+        });
+
+        cx.spawn(async move |this, cx| {
+            task.await?;
+            this.update(cx, |_, cx| cx.emit(PromptsUpdatedEvent)).ok();
+            anyhow::Ok(())
+        })
+    }
+
+    pub fn delete_playbook(&self, id: PlaybookId, cx: &Context<Self>) -> Task<Result<()>> {
+        let env = self.env.clone();
+        let playbooks = self.playbooks;
+        let task = cx.background_spawn(async move {
+            let mut txn = env.write_txn()?;
+            playbooks.delete(&mut txn, &id)?;
+            txn.commit()?;
+            anyhow::Ok(())
+        });
+
+        cx.spawn(async move |this, cx| {
+            task.await?;
+            this.update(cx, |_, cx| cx.emit(PromptsUpdatedEvent)).ok();
+            anyhow::Ok(())
+        })
+    }
+
     pub fn save_metadata(
         &self,
         id: PromptId,
@@ -429,11 +826,17 @@ impl PromptStore {
                 .and_then(|metadata| metadata.title.clone());
         }
 
+        let scope = cache
+            .metadata_by_id
+            .get(&id)
+            .map(|metadata| metadata.scope.clone())
+            .unwrap_or_default();
         let prompt_metadata = PromptMetadata {
             id,
             title,
             default,
             saved_at: Utc::now(),
+            scope,
         };
 
         cache.insert(prompt_metadata.clone());
@@ -455,9 +858,288 @@ impl PromptStore {
             anyhow::Ok(())
         })
     }
+
+    pub fn save_scope(
+        &self,
+        id: PromptId,
+        scope: RuleScope,
+        cx: &Context<Self>,
+    ) -> Task<Result<()>> {
+        let Some(mut prompt_metadata) = self.metadata(id) else {
+            return Task::ready(Err(anyhow!("prompt not found")));
+        };
+        if id.is_built_in() {
+            return Task::ready(Err(anyhow!("built-in prompts cannot be scoped")));
+        }
+        prompt_metadata.scope = scope;
+        self.metadata_cache.write().insert(prompt_metadata.clone());
+
+        let db_connection = self.env.clone();
+        let metadata = self.metadata;
+
+        let task = cx.background_spawn(async move {
+            let mut txn = db_connection.write_txn()?;
+            metadata.put(&mut txn, &id, &prompt_metadata)?;
+            txn.commit()?;
+
+            anyhow::Ok(())
+        });
+
+        cx.spawn(async move |this, cx| {
+            task.await?;
+            this.update(cx, |_, cx| cx.emit(PromptsUpdatedEvent)).ok();
+            anyhow::Ok(())
+        })
+    }
+
+    fn scope(&self, id: PromptId) -> RuleScope {
+        self.metadata(id)
+            .map(|metadata| metadata.scope)
+            .unwrap_or_default()
+    }
 }
 
 /// Wraps a shared future to a prompt store so it can be assigned as a context global.
 pub struct GlobalPromptStore(Shared<Task<Result<Entity<PromptStore>, Arc<anyhow::Error>>>>);
 
 impl Global for GlobalPromptStore {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+
+    fn revision(body: &str, saved_at: DateTime<Utc>) -> PromptRevision {
+        PromptRevision {
+            title: None,
+            body: body.to_string(),
+            saved_at,
+            first_saved_at: None,
+        }
+    }
+
+    fn bodies(revisions: &[PromptRevision]) -> Vec<&str> {
+        revisions.iter().map(|r| r.body.as_str()).collect()
+    }
+
+    #[test]
+    fn test_push_revision_coalesces_rapid_saves() {
+        let start = Utc::now();
+        let at = |seconds| start + chrono::Duration::seconds(seconds);
+        let mut revisions = Vec::new();
+        push_revision(&mut revisions, revision("one", at(0)));
+        push_revision(&mut revisions, revision("two", at(10)));
+        assert_eq!(bodies(&revisions), ["two"]);
+
+        // Pausing keeps the latest revision, so the next burst of edits can be undone.
+        push_revision(&mut revisions, revision("three", at(60)));
+        push_revision(&mut revisions, revision("four", at(70)));
+        assert_eq!(bodies(&revisions), ["two", "four"]);
+        assert_eq!(revisions[1].first_saved_at, Some(at(60)));
+    }
+
+    #[test]
+    fn test_push_revision_splits_long_editing_sessions() {
+        let start = Utc::now();
+        let mut revisions = Vec::new();
+        // Save every 20 seconds for 11 minutes without ever pausing.
+        for ix in 0..=33 {
+            push_revision(
+                &mut revisions,
+                revision(&ix.to_string(), start + chrono::Duration::seconds(ix * 20)),
+            );
+        }
+        assert_eq!(bodies(&revisions), ["14", "29", "33"]);
+    }
+
+    #[test]
+    fn test_push_revision_caps_history() {
+        let start = Utc::now();
+        let mut revisions = Vec::new();
+        for ix in 0..MAX_REVISIONS_PER_PROMPT + 10 {
+            push_revision(
+                &mut revisions,
+                revision(&ix.to_string(), start + chrono::Duration::hours(ix as i64)),
+            );
+        }
+        assert_eq!(revisions.len(), MAX_REVISIONS_PER_PROMPT);
+        assert_eq!(revisions.first().unwrap().body, "10");
+        assert_eq!(
+            revisions.last().unwrap().body,
+            (MAX_REVISIONS_PER_PROMPT + 9).to_string()
+        );
+    }
+
+    #[test]
+    fn test_revision_line_changes() {
+        let revision = revision("a\nb\nc\nb", Utc::now());
+        assert_eq!(revision.line_changes("a\nb\nc\nb"), (0, 0));
+        assert_eq!(revision.line_changes("a\nb\nc\nb\nd"), (1, 0));
+        assert_eq!(revision.line_changes("a\nc"), (0, 2));
+        assert_eq!(revision.line_changes("a\nB\nc\nb"), (1, 1));
+        assert_eq!(revision.line_changes(""), (0, 4));
+    }
+
+    async fn new_store(dir: &tempfile::TempDir, cx: &mut TestAppContext) -> Entity<PromptStore> {
+        let store = cx
+            .update(|cx| PromptStore::new(dir.path().join("prompts-library-db.0.mdb"), cx))
+            .await
+            .unwrap();
+        cx.new(|_| store)
+    }
+
+    async fn user_rules(
+        store: &Entity<PromptStore>,
+        cx: &mut TestAppContext,
+    ) -> Vec<(String, String)> {
+        let metadata = store.read_with(cx, |store, _| store.all_prompt_metadata());
+        let mut rules = Vec::new();
+        for metadata in metadata {
+            if metadata.id.is_built_in() {
+                continue;
+            }
+            let body = store
+                .read_with(cx, |store, cx| store.load(metadata.id, cx))
+                .await
+                .unwrap();
+            rules.push((metadata.title.unwrap_or_default().to_string(), body));
+        }
+        rules.sort();
+        rules
+    }
+
+    #[gpui::test]
+    async fn test_rules_bundle_round_trip(cx: &mut TestAppContext) {
+        let source_dir = tempfile::tempdir().unwrap();
+        let source = new_store(&source_dir, cx).await;
+        source
+            .update(cx, |store, cx| {
+                store.save(
+                    PromptId::new(),
+                    Some("Style".into()),
+                    true,
+                    "Use tabs.".into(),
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+        source
+            .update(cx, |store, cx| {
+                store.save_with_scope(
+                    PromptId::new(),
+                    Some("Rust".into()),
+                    false,
+                    RuleScope {
+                        languages: vec!["Rust".into()],
+                        paths: Vec::new(),
+                    },
+                    "Avoid unwrap.".into(),
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+
+        let bundle = source
+            .read_with(cx, |store, cx| store.export_bundle(cx))
+            .await
+            .unwrap();
+        let json = serde_json::to_string(&bundle).unwrap();
+        let bundle: RulesBundle = serde_json::from_str(&json).unwrap();
+
+        let target_dir = tempfile::tempdir().unwrap();
+        let target = new_store(&target_dir, cx).await;
+        let ids = target
+            .update(cx, |store, cx| {
+                store.import_bundle(bundle, RulesImportConflict::Skip, cx)
+            })
+            .await
+            .unwrap();
+        assert_eq!(ids.len(), 2);
+        assert_eq!(user_rules(&target, cx).await, user_rules(&source, cx).await);
+
+        target.read_with(cx, |store, _| {
+            let rust_id = store.id_for_title("Rust").unwrap();
+            assert_eq!(
+                store.scope(rust_id),
+                RuleScope {
+                    languages: vec!["Rust".into()],
+                    paths: Vec::new(),
+                }
+            );
+            let style_id = store.id_for_title("Style").unwrap();
+            assert!(store.metadata(style_id).unwrap().default);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_rules_bundle_import_conflicts(cx: &mut TestAppContext) {
+        let dir = tempfile::tempdir().unwrap();
+        let store = new_store(&dir, cx).await;
+        let existing_id = PromptId::new();
+        store
+            .update(cx, |store, cx| {
+                store.save(
+                    existing_id,
+                    Some("Style".into()),
+                    false,
+                    "Use tabs.".into(),
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+
+        let bundle = RulesBundle {
+            version: RulesBundle::VERSION,
+            rules: vec![RulesBundleEntry {
+                title: Some("Style".into()),
+                default: false,
+                scope: RuleScope::default(),
+                body: "Use spaces.".into(),
+            }],
+        };
+        let import = |on_conflict: RulesImportConflict, cx: &mut TestAppContext| {
+            store.update(cx, |store, cx| {
+                store.import_bundle(bundle.clone(), on_conflict, cx)
+            })
+        };
+
+        let ids = import(RulesImportConflict::Skip, cx).await.unwrap();
+        assert_eq!(ids, []);
+        assert_eq!(
+            user_rules(&store, cx).await,
+            [("Style".to_string(), "Use tabs.".to_string())]
+        );
+
+        let ids = import(RulesImportConflict::Overwrite, cx).await.unwrap();
+        assert_eq!(ids, [existing_id]);
+        assert_eq!(
+            user_rules(&store, cx).await,
+            [("Style".to_string(), "Use spaces.".to_string())]
+        );
+
+        import(RulesImportConflict::KeepBoth, cx).await.unwrap();
+        import(RulesImportConflict::KeepBoth, cx).await.unwrap();
+        assert_eq!(
+            user_rules(&store, cx).await,
+            [
+                ("Style".to_string(), "Use spaces.".to_string()),
+                ("Style (imported 2)".to_string(), "Use spaces.".to_string()),
+                ("Style (imported)".to_string(), "Use spaces.".to_string()),
+            ]
+        );
+
+        let newer_bundle = RulesBundle {
+            version: RulesBundle::VERSION + 1,
+            rules: Vec::new(),
+        };
+        let result = store
+            .update(cx, |store, cx| {
+                store.import_bundle(newer_bundle, RulesImportConflict::Skip, cx)
+            })
+            .await;
+        assert!(result.is_err());
+    }
+}
@@ -16,7 +16,7 @@ use std::{
 use text::LineEnding;
 use util::{ResultExt, get_system_shell};
 
-use crate::UserPromptId;
+use crate::{RuleScope, ScopedFile, UserPromptId};
 
 #[derive(Debug, Clone, Serialize)]
 pub struct ProjectContext {
@@ -26,6 +26,10 @@ pub struct ProjectContext {
     pub user_rules: Vec<UserRulesContext>,
     /// `!user_rules.is_empty()` - provided as a field because handlebars can't do this.
     pub has_user_rules: bool,
+    /// Facts about the project that the agent remembered in earlier threads.
+    pub memories: Vec<String>,
+    /// `!memories.is_empty()` - provided as a field because handlebars can't do this.
+    pub has_memories: bool,
     pub os: String,
     pub arch: String,
     pub shell: String,
@@ -35,17 +39,51 @@ impl ProjectContext {
     pub fn new(worktrees: Vec<WorktreeContext>, default_user_rules: Vec<UserRulesContext>) -> Self {
         let has_rules = worktrees
             .iter()
-            .any(|worktree| worktree.rules_file.is_some());
+            .any(|worktree| worktree.rules_files().next().is_some());
         Self {
             worktrees,
             has_rules,
             has_user_rules: !default_user_rules.is_empty(),
             user_rules: default_user_rules,
+            memories: Vec::new(),
+            has_memories: false,
             os: std::env::consts::OS.to_string(),
             arch: std::env::consts::ARCH.to_string(),
             shell: get_system_shell(),
         }
     }
+
+    pub fn with_memories(mut self, memories: Vec<String>) -> Self {
+        self.has_memories = !memories.is_empty();
+        self.memories = memories;
+        self
+    }
+
+    /// Returns a copy without the user rules whose scope doesn't match any of `files`, and without
+    /// the nested rules files whose directory doesn't contain any of them.
+    pub fn scoped_to_files(&self, files: &[ScopedFile]) -> Self {
+        let mut project_context = self.clone();
+        project_context
+            .user_rules
+            .retain(|user_rules| user_rules.scope.matches_any(files));
+        project_context.has_user_rules = !project_context.user_rules.is_empty();
+        for worktree in &mut project_context.worktrees {
+            worktree.nested_rules_files.retain(|rules_file| {
+                let directory = rules_file
+                    .path_in_worktree
+                    .parent()
+                    .unwrap_or(Path::new(""));
+                files.iter().any(|file| {
+                    file.root_name == worktree.root_name && file.path.starts_with(directory)
+                })
+            });
+        }
+        project_context.has_rules = project_context
+            .worktrees
+            .iter()
+            .any(|worktree| worktree.rules_files().next().is_some());
+        project_context
+    }
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -69,12 +107,24 @@ pub struct UserRulesContext {
     pub uuid: UserPromptId,
     pub title: Option<String>,
     pub contents: String,
+    #[serde(skip)]
+    pub scope: RuleScope,
 }
 
 #[derive(Debug, Clone, Serialize)]
 pub struct WorktreeContext {
     pub root_name: String,
     pub rules_file: Option<RulesFileContext>,
+    /// Rules files found in subdirectories of the worktree. These only apply to files beneath the
+    /// directory containing them, and are ordered so that shallower directories come first.
+    pub nested_rules_files: Vec<RulesFileContext>,
+}
+
+impl WorktreeContext {
+    /// Returns the root rules file followed by the nested ones, in increasing order of precedence.
+    pub fn rules_files(&self) -> impl Iterator<Item = &RulesFileContext> {
+        self.rules_file.iter().chain(self.nested_rules_files.iter())
+    }
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -460,11 +510,17 @@ mod test {
                 text: "".into(),
                 project_entry_id: 0,
             }),
+            nested_rules_files: vec![RulesFileContext {
+                path_in_worktree: Path::new("crates/foo/AGENTS.md").into(),
+                text: "Nested rules contents".into(),
+                project_entry_id: 1,
+            }],
         }];
         let default_user_rules = vec![UserRulesContext {
             uuid: UserPromptId(Uuid::nil()),
             title: Some("Rules title".into()),
             contents: "Rules contents".into(),
+            scope: RuleScope::default(),
         }];
         let project_context = ProjectContext::new(worktrees, default_user_rules);
         let model_context = ModelContext {
@@ -478,6 +534,121 @@ mod test {
             prompt.contains("Rules contents"),
             "Expected default user rules to be in rendered prompt"
         );
+        assert!(
+            prompt.contains("`path/crates/foo/AGENTS.md`")
+                && prompt.contains("Nested rules contents"),
+            "Expected nested rules files to be in rendered prompt"
+        );
+    }
+
+    #[test]
+    fn test_scoped_user_rules() {
+        let user_rules = |title: &str, scope: &str| UserRulesContext {
+            uuid: UserPromptId::new(),
+            title: Some(title.into()),
+            contents: String::new(),
+            scope: RuleScope::parse(scope),
+        };
+        let project_context = ProjectContext::new(
+            Vec::new(),
+            vec![
+                user_rules("Everywhere", ""),
+                user_rules("Rust", "rust"),
+                user_rules("Rust tests", "Rust, tests/**"),
+                user_rules("Frontend", "web/**/*.ts, web/**/*.tsx"),
+            ],
+        );
+        let titles = |files: &[ScopedFile]| {
+            project_context
+                .scoped_to_files(files)
+                .user_rules
+                .into_iter()
+                .filter_map(|user_rules| user_rules.title)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(titles(&[]), ["Everywhere"]);
+        assert_eq!(
+            titles(&[ScopedFile {
+                root_name: "project".into(),
+                path: Path::new("src/main.rs").into(),
+                language: Some("Rust".into()),
+            }]),
+            ["Everywhere", "Rust"]
+        );
+        assert_eq!(
+            titles(&[
+                ScopedFile {
+                    root_name: "project".into(),
+                    path: Path::new("tests/integration.rs").into(),
+                    language: Some("Rust".into()),
+                },
+                ScopedFile {
+                    root_name: "project".into(),
+                    path: Path::new("web/src/app.tsx").into(),
+                    language: Some("TSX".into()),
+                },
+            ]),
+            ["Everywhere", "Rust", "Rust tests", "Frontend"]
+        );
+    }
+
+    #[test]
+    fn test_scoped_nested_rules_files() {
+        let rules_file = |path: &str| RulesFileContext {
+            path_in_worktree: Path::new(path).into(),
+            text: String::new(),
+            project_entry_id: 0,
+        };
+        let project_context = ProjectContext::new(
+            vec![WorktreeContext {
+                root_name: "project".into(),
+                rules_file: None,
+                nested_rules_files: vec![
+                    rules_file("crates/AGENTS.md"),
+                    rules_file("crates/foo/AGENTS.md"),
+                    rules_file("web/.rules"),
+                ],
+            }],
+            Vec::new(),
+        );
+        let scoped = |files: &[(&str, &str)]| {
+            let files = files
+                .iter()
+                .map(|(root_name, path)| ScopedFile {
+                    root_name: root_name.to_string(),
+                    path: Path::new(path).into(),
+                    language: None,
+                })
+                .collect::<Vec<_>>();
+            let project_context = project_context.scoped_to_files(&files);
+            let paths = project_context.worktrees[0]
+                .nested_rules_files
+                .iter()
+                .map(|rules_file| rules_file.path_in_worktree.to_string_lossy().into_owned())
+                .collect::<Vec<_>>();
+            (project_context.has_rules, paths)
+        };
+
+        assert_eq!(scoped(&[]), (false, Vec::<String>::new()));
+        assert_eq!(
+            scoped(&[("project", "crates/foo/src/lib.rs")]),
+            (
+                true,
+                vec!["crates/AGENTS.md".into(), "crates/foo/AGENTS.md".into()]
+            )
+        );
+        assert_eq!(
+            scoped(&[
+                ("project", "crates/bar/src/lib.rs"),
+                ("project", "web/app.ts")
+            ]),
+            (true, vec!["crates/AGENTS.md".into(), "web/.rules".into()])
+        );
+        assert_eq!(
+            scoped(&[("other", "crates/foo/src/lib.rs")]),
+            (false, Vec::<String>::new())
+        );
     }
 
     #[test]
@@ -485,6 +656,7 @@ mod test {
         let worktrees = vec![WorktreeContext {
             root_name: "path".into(),
             rules_file: None,
+            nested_rules_files: Vec::new(),
         }];
         let default_user_rules = vec![];
         let project_context = ProjectContext::new(worktrees, default_user_rules);
@@ -144,6 +144,8 @@ messages!(
     (JoinChannelChatResponse, Foreground),
     (JoinProject, Foreground),
     (JoinProjectResponse, Foreground),
+    (GetProjectPreview, Background),
+    (GetProjectPreviewResponse, Background),
     (JoinRoom, Foreground),
     (JoinRoomResponse, Foreground),
     (LanguageServerLog, Foreground),
@@ -161,6 +163,11 @@ messages!(
     (ListToolchainsResponse, Foreground),
     (LoadCommitDiff, Foreground),
     (LoadCommitDiffResponse, Foreground),
+    (GitFileHistory, Background),
+    (GitDiffRefs, Background),
+    (GitRenamedPaths, Background),
+    (GitRenamedPathsResponse, Background),
+    (GitFileHistoryResponse, Background),
     (LspExtExpandMacro, Background),
     (LspExtExpandMacroResponse, Background),
     (LspExtOpenDocs, Background),
@@ -283,7 +290,11 @@ messages!(
     (RemoveRepository, Foreground),
     (UsersResponse, Foreground),
     (GitReset, Background),
+    (GitApplyCommit, Background),
+    (GitApplyCommitResponse, Background),
     (GitCheckoutFiles, Background),
+    (GitStash, Background),
+    (GitStashPop, Background),
     (GitShow, Background),
     (GitCommitDetails, Background),
     (SetIndexText, Background),
@@ -291,6 +302,9 @@ messages!(
     (Fetch, Background),
     (GetRemotes, Background),
     (GetRemotesResponse, Background),
+    (GitGetSubmodules, Background),
+    (GitGetSubmodulesResponse, Background),
+    (GitUpdateSubmodules, Background),
     (Pull, Background),
     (RemoteMessageResponse, Background),
     (AskPassRequest, Background),
@@ -364,10 +378,14 @@ request_messages!(
     (JoinChannelBuffer, JoinChannelBufferResponse),
     (JoinChannelChat, JoinChannelChatResponse),
     (JoinProject, JoinProjectResponse),
+    (GetProjectPreview, GetProjectPreviewResponse),
     (JoinRoom, JoinRoomResponse),
     (LeaveChannelBuffer, Ack),
     (LeaveRoom, Ack),
     (LoadCommitDiff, LoadCommitDiffResponse),
+    (GitFileHistory, GitFileHistoryResponse),
+    (GitDiffRefs, LoadCommitDiffResponse),
+    (GitRenamedPaths, GitRenamedPathsResponse),
     (MarkNotificationRead, Ack),
     (MoveChannel, Ack),
     (OnTypeFormatting, OnTypeFormattingResponse),
@@ -454,11 +472,16 @@ request_messages!(
     (RegisterBufferWithLanguageServers, Ack),
     (GitShow, GitCommitDetails),
     (GitReset, Ack),
+    (GitApplyCommit, GitApplyCommitResponse),
     (GitCheckoutFiles, Ack),
+    (GitStash, Ack),
+    (GitStashPop, Ack),
     (SetIndexText, Ack),
     (Push, RemoteMessageResponse),
     (Fetch, RemoteMessageResponse),
     (GetRemotes, GetRemotesResponse),
+    (GitGetSubmodules, GitGetSubmodulesResponse),
+    (GitUpdateSubmodules, RemoteMessageResponse),
     (Pull, RemoteMessageResponse),
     (AskPassRequest, AskPassResponse),
     (GitCreateBranch, Ack),
@@ -511,6 +534,9 @@ entity_messages!(
     LeaveProject,
     LinkedEditingRange,
     LoadCommitDiff,
+    GitFileHistory,
+    GitDiffRefs,
+    GitRenamedPaths,
     MultiLspQuery,
     RestartLanguageServers,
     StopLanguageServers,
@@ -577,12 +603,17 @@ entity_messages!(
     RegisterBufferWithLanguageServers,
     GitShow,
     GitReset,
+    GitApplyCommit,
     GitCheckoutFiles,
+    GitStash,
+    GitStashPop,
     SetIndexText,
 
     Push,
     Fetch,
     GetRemotes,
+    GitGetSubmodules,
+    GitUpdateSubmodules,
     Pull,
     AskPassRequest,
     GitChangeBranch,
@@ -0,0 +1,265 @@
+use fuzzy::{StringMatch, StringMatchCandidate, match_strings};
+use gpui::{
+    App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Subscription, Task,
+    WeakEntity, Window,
+};
+use picker::{Picker, PickerDelegate};
+use std::sync::Arc;
+use ui::{HighlightedLabel, ListItem, ListItemSpacing, Tooltip, prelude::*};
+use util::ResultExt;
+use workspace::{ModalView, SerializedLayoutProfile, WORKSPACE_DB, Workspace};
+use zed_actions::layout_profiles::Toggle;
+
+pub struct LayoutProfiles {
+    picker: Entity<Picker<LayoutProfilesDelegate>>,
+    _subscription: Subscription,
+}
+
+impl LayoutProfiles {
+    pub(crate) fn register(
+        workspace: &mut Workspace,
+        _window: Option<&mut Window>,
+        _: &mut Context<Workspace>,
+    ) {
+        workspace.register_action(|workspace, _: &Toggle, window, cx| {
+            let handle = cx.entity().downgrade();
+            workspace.toggle_modal(window, cx, |window, cx| Self::new(handle, window, cx));
+        });
+    }
+
+    fn new(workspace: WeakEntity<Workspace>, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let profiles = WORKSPACE_DB.layout_profiles().log_err().unwrap_or_default();
+        let delegate = LayoutProfilesDelegate::new(workspace, profiles);
+        let picker = cx.new(|cx| Picker::uniform_list(delegate, window, cx));
+        let _subscription = cx.subscribe(&picker, |_, _, _, cx| cx.emit(DismissEvent));
+        Self {
+            picker,
+            _subscription,
+        }
+    }
+}
+
+impl Render for LayoutProfiles {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+impl Focusable for LayoutProfiles {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for LayoutProfiles {}
+impl ModalView for LayoutProfiles {}
+
+pub struct LayoutProfilesDelegate {
+    workspace: WeakEntity<Workspace>,
+    profiles: Vec<SerializedLayoutProfile>,
+    matches: Vec<StringMatch>,
+    query: String,
+    selected_index: usize,
+}
+
+impl LayoutProfilesDelegate {
+    fn new(workspace: WeakEntity<Workspace>, profiles: Vec<SerializedLayoutProfile>) -> Self {
+        Self {
+            workspace,
+            profiles,
+            matches: Vec::new(),
+            query: String::new(),
+            selected_index: 0,
+        }
+    }
+
+    /// The name under which the current layout would be saved, shown as the first
+    /// entry whenever the query doesn't name an existing profile.
+    fn new_profile_name(&self) -> Option<&str> {
+        let name = self.query.trim();
+        (!name.is_empty() && !self.profiles.iter().any(|profile| profile.name == name))
+            .then_some(name)
+    }
+
+    fn profile_for_index(&self, ix: usize) -> Option<&SerializedLayoutProfile> {
+        let ix = ix.checked_sub(self.new_profile_name().is_some() as usize)?;
+        let mat = self.matches.get(ix)?;
+        self.profiles.get(mat.candidate_id)
+    }
+
+    fn save_profile(&self, name: String, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        self.workspace
+            .update(cx, |workspace, cx| {
+                workspace
+                    .save_layout_profile(name, window, cx)
+                    .detach_and_log_err(cx);
+            })
+            .log_err();
+    }
+
+    fn delete_profile(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(profile) = self.profile_for_index(ix) else {
+            return;
+        };
+        let name = profile.name.clone();
+        cx.spawn_in(window, async move |picker, cx| {
+            WORKSPACE_DB.delete_layout_profile(name.clone()).await?;
+            picker.update_in(cx, |picker, window, cx| {
+                picker
+                    .delegate
+                    .profiles
+                    .retain(|profile| profile.name != name);
+                picker.refresh(window, cx);
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+}
+
+impl PickerDelegate for LayoutProfilesDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        "Apply a layout profile or type a name to save the current layout…".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len() + self.new_profile_name().is_some() as usize
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let background = cx.background_executor().clone();
+        let candidates = self
+            .profiles
+            .iter()
+            .enumerate()
+            .map(|(id, profile)| StringMatchCandidate::new(id, &profile.name))
+            .collect::<Vec<_>>();
+        cx.spawn_in(window, async move |this, cx| {
+            let matches = if query.trim().is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                match_strings(
+                    &candidates,
+                    query.trim(),
+                    false,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await
+            };
+
+            this.update(cx, |this, cx| {
+                let delegate = &mut this.delegate;
+                delegate.query = query;
+                delegate.matches = matches;
+                delegate.selected_index = 0;
+                cx.notify();
+            })
+            .log_err();
+        })
+    }
+
+    /// Secondary confirmation overwrites the selected profile with the current layout.
+    fn confirm(&mut self, secondary: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        if let Some(name) = self.new_profile_name() {
+            if self.selected_index == 0 {
+                self.save_profile(name.to_string(), window, cx);
+                cx.emit(DismissEvent);
+                return;
+            }
+        }
+
+        let Some(profile) = self.profile_for_index(self.selected_index).cloned() else {
+            return;
+        };
+        if secondary {
+            self.save_profile(profile.name, window, cx);
+        } else {
+            self.workspace
+                .update(cx, |workspace, cx| {
+                    workspace.apply_layout_profile(profile, window, cx);
+                })
+                .log_err();
+        }
+        cx.emit(DismissEvent);
+    }
+
+    fn dismissed(&mut self, _window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn no_matches_text(&self, _window: &mut Window, _cx: &mut App) -> Option<SharedString> {
+        Some("No layout profiles saved yet. Type a name to save the current layout.".into())
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        if let Some(name) = self.new_profile_name().filter(|_| ix == 0) {
+            return Some(
+                ListItem::new(ix)
+                    .inset(true)
+                    .spacing(ListItemSpacing::Sparse)
+                    .toggle_state(selected)
+                    .start_slot(Icon::new(IconName::Plus).color(Color::Muted))
+                    .child(Label::new(format!("Save current layout as \"{name}\""))),
+            );
+        }
+
+        let mat = self
+            .matches
+            .get(ix - self.new_profile_name().is_some() as usize)?;
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .start_slot(Icon::new(IconName::Screen).color(Color::Muted))
+                .child(HighlightedLabel::new(
+                    mat.string.clone(),
+                    mat.positions.clone(),
+                ))
+                .end_slot(
+                    IconButton::new(("delete-layout-profile", ix), IconName::Trash)
+                        .icon_size(IconSize::Small)
+                        .tooltip(Tooltip::text("Delete Profile"))
+                        .on_click(cx.listener(move |picker, _, window, cx| {
+                            cx.stop_propagation();
+                            picker.delegate.delete_profile(ix, window, cx);
+                        })),
+                ),
+        )
+    }
+}
@@ -1,4 +1,5 @@
 pub mod disconnected_overlay;
+mod layout_profiles;
 mod remote_servers;
 mod ssh_config;
 mod ssh_connections;
@@ -11,6 +12,7 @@ use gpui::{
     Action, AnyElement, App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable,
     Subscription, Task, WeakEntity, Window,
 };
+use layout_profiles::LayoutProfiles;
 use ordered_float::OrderedFloat;
 use picker::{
     Picker, PickerDelegate,
@@ -65,6 +67,7 @@ pub fn init(cx: &mut App) {
     });
 
     cx.observe_new(DisconnectedOverlay::register).detach();
+    cx.observe_new(LayoutProfiles::register).detach();
 }
 
 pub struct RecentProjects {
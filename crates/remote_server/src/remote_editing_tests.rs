@@ -1581,6 +1581,7 @@ async fn test_remote_agent_fs_tool_calls(cx: &mut TestAppContext, server_cx: &mu
         path: "project/b.txt".into(),
         start_line: None,
         end_line: None,
+        symbol: None,
     };
     let exists_result = cx.update(|cx| {
         ReadFileTool::run(
@@ -1601,6 +1602,7 @@ async fn test_remote_agent_fs_tool_calls(cx: &mut TestAppContext, server_cx: &mu
         path: "project/c.txt".into(),
         start_line: None,
         end_line: None,
+        symbol: None,
     };
     let does_not_exist_result = cx.update(|cx| {
         ReadFileTool::run(
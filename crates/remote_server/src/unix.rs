@@ -272,6 +272,8 @@ fn start_server(
         }
     })
     .detach();
+    cx.on_app_quit(|_| async { ReqwestClient::shutdown_shared_runtime() })
+        .detach();
 
     cx.spawn(async move |cx| {
         let mut stdin_incoming = listeners.stdin.incoming();
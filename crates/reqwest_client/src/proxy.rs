@@ -1,4 +1,4 @@
-//! client proxy
+//! Tunnels for connections that reqwest does not make itself, such as WebSockets.
 
 mod http_proxy;
 mod socks_proxy;
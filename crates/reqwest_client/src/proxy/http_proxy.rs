@@ -1,5 +1,6 @@
 use anyhow::{Context, Result};
 use base64::Engine;
+use http_client::Url;
 use httparse::{EMPTY_HEADER, Response};
 use tokio::{
     io::{AsyncBufReadExt, AsyncWriteExt, BufStream},
@@ -9,7 +10,6 @@ use tokio::{
 use tokio_native_tls::{TlsConnector, native_tls};
 #[cfg(not(any(target_os = "windows", target_os = "macos")))]
 use tokio_rustls::TlsConnector;
-use url::Url;
 
 use super::AsyncReadWrite;
 
@@ -163,7 +163,7 @@ where
 
 #[cfg(test)]
 mod tests {
-    use url::Url;
+    use http_client::Url;
 
     use super::{HttpProxyAuthorization, HttpProxyType, parse_http_proxy};
 
@@ -127,7 +127,7 @@ pub(super) async fn connect_socks_proxy_stream(
 
 #[cfg(test)]
 mod tests {
-    use url::Url;
+    use http_client::Url;
 
     use super::*;
 
@@ -1,56 +1,163 @@
+mod proxy;
+
+use std::collections::HashMap;
 use std::error::Error;
-use std::sync::{LazyLock, OnceLock};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, LazyLock, Mutex, PoisonError};
 use std::{any::type_name, borrow::Cow, mem, pin::Pin, task::Poll, time::Duration};
 
-use anyhow::anyhow;
+use anyhow::{Context as _, anyhow};
+use async_tungstenite::tungstenite::client::IntoClientRequest as _;
 use bytes::{BufMut, Bytes, BytesMut};
-use futures::{AsyncRead, TryStreamExt as _};
-use http_client::{RedirectPolicy, Url, http};
+use futures::{AsyncRead, SinkExt as _, StreamExt as _, TryStreamExt as _};
+use http_client::{RedirectPolicy, RequestPriority, Url, WebSocket, http};
+use proxy::connect_proxy_stream;
 use regex::Regex;
 use reqwest::{
     header::{HeaderMap, HeaderValue},
     redirect,
 };
 use smol::future::FutureExt;
+use tokio::net::TcpStream;
 
 const DEFAULT_CAPACITY: usize = 4096;
-static RUNTIME: OnceLock<tokio::runtime::Runtime> = OnceLock::new();
+const RUNTIME_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(1);
+const HTTP2_KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(30);
+const POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+const WARM_UP_TIMEOUT: Duration = Duration::from_secs(10);
+/// How long background transfers pause before each chunk while interactive requests are waiting
+/// for their first bytes. They're slowed down rather than stopped, so that a slow server can't
+/// stall them entirely.
+const BACKGROUND_THROTTLE_DELAY: Duration = Duration::from_millis(100);
+static RUNTIME: Mutex<Option<tokio::runtime::Runtime>> = Mutex::new(None);
 static REDACT_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"key=[^&]+").unwrap());
+static CLIENT_POOL: LazyLock<Mutex<HashMap<ClientConfig, ReqwestClient>>> =
+    LazyLock::new(Default::default);
+/// The number of interactive requests that haven't received the first chunk of their response.
+/// That's the latency the user notices, such as the time to the first token of a completion, and
+/// once it arrives the rest of a stream is paced by the server rather than by bandwidth. It's
+/// shared by all clients, as they compete for the same bandwidth.
+static INTERACTIVE_REQUESTS: AtomicUsize = AtomicUsize::new(0);
+
+/// The configuration a [`ReqwestClient`] was built with, used to share clients
+/// (and their connection pools) between subsystems that configure them identically.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum ClientConfig {
+    Default,
+    UserAgent(String),
+    ProxyAndUserAgent { proxy: Option<Url>, agent: String },
+}
 
+#[derive(Clone)]
 pub struct ReqwestClient {
     client: reqwest::Client,
+    /// Sends interactive requests, which go to LLM providers. Its idle connections are kept
+    /// alive (and opened early by `warm_up`) so that the time to the first token doesn't
+    /// include a handshake, without holding connections open to every other host.
+    interactive_client: reqwest::Client,
     proxy: Option<Url>,
+    /// Sent with WebSocket handshakes, which don't go through `client`.
+    user_agent: Option<HeaderValue>,
     handle: tokio::runtime::Handle,
 }
 
 impl ReqwestClient {
     fn builder() -> reqwest::ClientBuilder {
+        // HTTP/2 is negotiated during the TLS handshake, so HTTPS connections to LLM providers
+        // use it without prior knowledge. Forcing it would break plain HTTP servers, such as
+        // local model runners, that only speak HTTP/1.1.
         reqwest::Client::builder()
             .use_rustls_tls()
             .connect_timeout(Duration::from_secs(10))
     }
 
+    /// Builds the clients for ordinary and interactive requests with the same configuration.
+    fn build(
+        configure: impl Fn(reqwest::ClientBuilder) -> reqwest::ClientBuilder,
+    ) -> anyhow::Result<Self> {
+        let client = configure(Self::builder()).build()?;
+        let interactive_client = configure(
+            Self::builder()
+                // Ping idle HTTP/2 connections so that ones opened by `warm_up` aren't dropped
+                // by proxies or NATs before they're used.
+                .http2_keep_alive_interval(HTTP2_KEEP_ALIVE_INTERVAL)
+                .http2_keep_alive_while_idle(true)
+                .pool_idle_timeout(POOL_IDLE_TIMEOUT),
+        )
+        .build()?;
+        let mut client = ReqwestClient::from(client);
+        client.interactive_client = interactive_client;
+        Ok(client)
+    }
+
+    /// Returns the pooled client for the given configuration, building it if needed.
+    fn pooled(
+        config: ClientConfig,
+        build: impl FnOnce() -> anyhow::Result<Self>,
+    ) -> anyhow::Result<Self> {
+        // Clients created inside a Tokio runtime are bound to that runtime, so only
+        // the ones running on our shared runtime can safely be handed out again.
+        if tokio::runtime::Handle::try_current().is_ok() {
+            return build();
+        }
+
+        let mut pool = CLIENT_POOL.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(client) = pool.get(&config) {
+            return Ok(client.clone());
+        }
+        let client = build()?;
+        pool.insert(config, client.clone());
+        Ok(client)
+    }
+
     pub fn new() -> Self {
-        Self::builder()
-            .build()
+        Self::pooled(ClientConfig::Default, || Self::build(|builder| builder))
             .expect("Failed to initialize HTTP client")
-            .into()
     }
 
     pub fn user_agent(agent: &str) -> anyhow::Result<Self> {
-        let mut map = HeaderMap::new();
-        map.insert(http::header::USER_AGENT, HeaderValue::from_str(agent)?);
-        let client = Self::builder().default_headers(map).build()?;
-        Ok(client.into())
+        Self::pooled(ClientConfig::UserAgent(agent.to_string()), || {
+            let user_agent = HeaderValue::from_str(agent)?;
+            let mut map = HeaderMap::new();
+            map.insert(http::header::USER_AGENT, user_agent.clone());
+            let mut client = Self::build(|builder| builder.default_headers(map.clone()))?;
+            client.user_agent = Some(user_agent);
+            Ok(client)
+        })
     }
 
     pub fn proxy_and_user_agent(proxy: Option<Url>, agent: &str) -> anyhow::Result<Self> {
-        let mut map = HeaderMap::new();
-        map.insert(http::header::USER_AGENT, HeaderValue::from_str(agent)?);
-        let mut client = Self::builder().default_headers(map);
-        let client_has_proxy;
+        let config = ClientConfig::ProxyAndUserAgent {
+            proxy: proxy.clone(),
+            agent: agent.to_string(),
+        };
+        Self::pooled(config, || Self::build_with_proxy(proxy, agent))
+    }
+
+    /// Shuts down the runtime shared by clients that were created outside of a Tokio
+    /// runtime, giving in-flight requests a moment to finish. Clients created before
+    /// this call will fail to send requests afterwards.
+    ///
+    /// Must not be called from within an asynchronous Tokio context.
+    pub fn shutdown_shared_runtime() {
+        CLIENT_POOL
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+        let runtime = RUNTIME
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take();
+        if let Some(runtime) = runtime {
+            runtime.shutdown_timeout(RUNTIME_SHUTDOWN_TIMEOUT);
+        }
+    }
 
-        if let Some(proxy) = proxy.as_ref().and_then(|proxy_url| {
+    fn build_with_proxy(proxy: Option<Url>, agent: &str) -> anyhow::Result<Self> {
+        let user_agent = HeaderValue::from_str(agent)?;
+        let mut map = HeaderMap::new();
+        map.insert(http::header::USER_AGENT, user_agent.clone());
+        let reqwest_proxy = proxy.as_ref().and_then(|proxy_url| {
             reqwest::Proxy::all(proxy_url.clone())
                 .inspect_err(|e| {
                     log::error!(
@@ -60,18 +167,18 @@ impl ReqwestClient {
                     )
                 })
                 .ok()
-        }) {
-            client = client.proxy(proxy);
-            client_has_proxy = true;
-        } else {
-            client_has_proxy = false;
-        };
+        });
+        let client_has_proxy = reqwest_proxy.is_some();
 
-        let client = client
-            .use_preconfigured_tls(http_client_tls::tls_config())
-            .build()?;
-        let mut client: ReqwestClient = client.into();
+        let mut client = Self::build(|mut builder| {
+            builder = builder.default_headers(map.clone());
+            if let Some(proxy) = reqwest_proxy.clone() {
+                builder = builder.proxy(proxy);
+            }
+            builder.use_preconfigured_tls(http_client_tls::tls_config())
+        })?;
         client.proxy = client_has_proxy.then_some(proxy).flatten();
+        client.user_agent = Some(user_agent);
         Ok(client)
     }
 }
@@ -79,8 +186,9 @@ impl ReqwestClient {
 impl From<reqwest::Client> for ReqwestClient {
     fn from(client: reqwest::Client) -> Self {
         let handle = tokio::runtime::Handle::try_current().unwrap_or_else(|_| {
-            log::debug!("no tokio runtime found, creating one for Reqwest...");
-            let runtime = RUNTIME.get_or_init(|| {
+            let mut runtime = RUNTIME.lock().unwrap_or_else(PoisonError::into_inner);
+            let runtime = runtime.get_or_insert_with(|| {
+                log::debug!("no tokio runtime found, creating one for Reqwest...");
                 tokio::runtime::Builder::new_multi_thread()
                     // Since we now have two executors, let's try to keep our footprint small
                     .worker_threads(1)
@@ -92,9 +200,11 @@ impl From<reqwest::Client> for ReqwestClient {
             runtime.handle().clone()
         });
         Self {
+            interactive_client: client.clone(),
             client,
             handle,
             proxy: None,
+            user_agent: None,
         }
     }
 }
@@ -195,6 +305,29 @@ pub fn poll_read_buf(
     Poll::Ready(Ok(n))
 }
 
+/// Counts an interactive request as waiting for its response for as long as it's alive.
+struct InteractiveRequestGuard;
+
+impl InteractiveRequestGuard {
+    fn new() -> Self {
+        INTERACTIVE_REQUESTS.fetch_add(1, Ordering::SeqCst);
+        Self
+    }
+}
+
+impl Drop for InteractiveRequestGuard {
+    fn drop(&mut self) {
+        INTERACTIVE_REQUESTS.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Slows down a background transfer while interactive requests are waiting for a response.
+async fn throttle_background_transfer() {
+    if INTERACTIVE_REQUESTS.load(Ordering::SeqCst) > 0 {
+        smol::Timer::after(BACKGROUND_THROTTLE_DELAY).await;
+    }
+}
+
 fn redact_error(mut error: reqwest::Error) -> reqwest::Error {
     if let Some(url) = error.url_mut() {
         if let Some(query) = url.query() {
@@ -223,8 +356,18 @@ impl http_client::HttpClient for ReqwestClient {
         anyhow::Result<http_client::Response<http_client::AsyncBody>>,
     > {
         let (parts, body) = req.into_parts();
-
-        let mut request = self.client.request(parts.method, parts.uri.to_string());
+        let priority = parts
+            .extensions
+            .get::<RequestPriority>()
+            .copied()
+            .unwrap_or_default();
+
+        let client = if priority == RequestPriority::Interactive {
+            &self.interactive_client
+        } else {
+            &self.client
+        };
+        let mut request = client.request(parts.method, parts.uri.to_string());
         request = request.headers(parts.headers);
         if let Some(redirect_policy) = parts.extensions.get::<RedirectPolicy>() {
             request = request.redirect_policy(match redirect_policy {
@@ -243,6 +386,12 @@ impl http_client::HttpClient for ReqwestClient {
 
         let handle = self.handle.clone();
         async move {
+            let mut interactive_request_guard =
+                (priority == RequestPriority::Interactive).then(InteractiveRequestGuard::new);
+            if priority == RequestPriority::Background {
+                throttle_background_transfer().await;
+            }
+
             let mut response = handle
                 .spawn(async { request.send().await })
                 .await?
@@ -256,6 +405,16 @@ impl http_client::HttpClient for ReqwestClient {
 
             let bytes = response
                 .bytes_stream()
+                .then(move |chunk| {
+                    interactive_request_guard.take();
+                    async move {
+                        if priority == RequestPriority::Background {
+                            throttle_background_transfer().await;
+                        }
+                        chunk
+                    }
+                })
+                .boxed()
                 .map_err(|e| futures::io::Error::new(futures::io::ErrorKind::Other, e))
                 .into_async_read();
             let body = http_client::AsyncBody::from_reader(bytes);
@@ -264,13 +423,106 @@ impl http_client::HttpClient for ReqwestClient {
         }
         .boxed()
     }
+
+    fn warm_up(&self, url: &Url) -> futures::future::BoxFuture<'static, anyhow::Result<()>> {
+        let origin = url.origin();
+        if !origin.is_tuple() {
+            let url = url.clone();
+            return async move { Err(anyhow!("can't connect to {url} ahead of time")) }.boxed();
+        }
+
+        let request = self
+            .interactive_client
+            .head(origin.ascii_serialization())
+            .timeout(WARM_UP_TIMEOUT);
+        let handle = self.handle.clone();
+        async move {
+            // Any response, even an error status, means that the connection was established
+            // and has been returned to the pool.
+            handle
+                .spawn(async move { request.send().await })
+                .await?
+                .map_err(redact_error)?;
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn connect_websocket(
+        &self,
+        url: &Url,
+        headers: http::HeaderMap,
+    ) -> futures::future::BoxFuture<'static, anyhow::Result<WebSocket>> {
+        let url = url.clone();
+        let proxy = self.proxy.clone();
+        let user_agent = self.user_agent.clone();
+        let handle = self.handle.clone();
+        async move {
+            handle
+                .spawn(async move {
+                    let host = url
+                        .host_str()
+                        .zip(url.port_or_known_default())
+                        .with_context(|| format!("missing host in WebSocket url {url}"))?;
+                    let stream = match &proxy {
+                        Some(proxy) => connect_proxy_stream(proxy, host).await?,
+                        None => Box::new(TcpStream::connect(host).await?),
+                    };
+
+                    // Let `tungstenite` build the handshake request, so that it
+                    // generates the `Sec-WebSocket-Key` header and friends.
+                    let mut request = url.as_str().into_client_request()?;
+                    if let Some(user_agent) = user_agent {
+                        request
+                            .headers_mut()
+                            .insert(http::header::USER_AGENT, user_agent);
+                    }
+                    request.headers_mut().extend(headers);
+
+                    let (stream, _) =
+                        async_tungstenite::tokio::client_async_tls_with_connector_and_config(
+                            request,
+                            stream,
+                            Some(Arc::new(http_client_tls::tls_config()).into()),
+                            None,
+                        )
+                        .await?;
+                    let stream: WebSocket = Box::new(
+                        stream
+                            .map_err(anyhow::Error::from)
+                            .sink_map_err(anyhow::Error::from),
+                    );
+                    anyhow::Ok(stream)
+                })
+                .await?
+        }
+        .boxed()
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use http_client::{HttpClient, Url};
+    use std::io::{BufRead as _, BufReader, Read as _, Write as _};
+    use std::net::TcpListener;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex, mpsc};
+    use std::thread;
+    use std::time::Instant;
+
+    use futures::AsyncReadExt as _;
+    use http_client::{AsyncBody, HttpClient, HttpRequestExt as _, RequestPriority, Url, http};
+
+    use crate::{
+        BACKGROUND_THROTTLE_DELAY, CLIENT_POOL, ClientConfig, INTERACTIVE_REQUESTS,
+        InteractiveRequestGuard, ReqwestClient, throttle_background_transfer,
+    };
 
-    use crate::ReqwestClient;
+    /// Serializes the tests that depend on the global count of interactive requests.
+    static INTERACTIVE_REQUESTS_LOCK: Mutex<()> = Mutex::new(());
+
+    fn interactive_request_count() -> usize {
+        INTERACTIVE_REQUESTS.load(std::sync::atomic::Ordering::SeqCst)
+    }
 
     #[test]
     fn test_proxy_uri() {
@@ -302,6 +554,165 @@ mod tests {
         assert_eq!(client.proxy(), Some(&proxy));
     }
 
+    #[test]
+    fn test_client_pool() {
+        let proxy = Url::parse("http://localhost:10810").unwrap();
+        let config = ClientConfig::ProxyAndUserAgent {
+            proxy: Some(proxy.clone()),
+            agent: "pool test".into(),
+        };
+
+        let client = ReqwestClient::proxy_and_user_agent(Some(proxy.clone()), "pool test").unwrap();
+        assert_eq!(client.proxy(), Some(&proxy));
+        assert!(CLIENT_POOL.lock().unwrap().contains_key(&config));
+
+        let pooled_client =
+            ReqwestClient::proxy_and_user_agent(Some(proxy.clone()), "pool test").unwrap();
+        assert_eq!(pooled_client.proxy(), Some(&proxy));
+
+        let other_client = ReqwestClient::proxy_and_user_agent(None, "pool test").unwrap();
+        assert_eq!(other_client.proxy(), None);
+    }
+
+    #[test]
+    fn test_interactive_request_guard() {
+        let _lock = INTERACTIVE_REQUESTS_LOCK.lock().unwrap();
+        let initial_count = interactive_request_count();
+        let first = InteractiveRequestGuard::new();
+        let second = InteractiveRequestGuard::new();
+        assert_eq!(interactive_request_count(), initial_count + 2);
+        drop(first);
+        assert_eq!(interactive_request_count(), initial_count + 1);
+        drop(second);
+        assert_eq!(interactive_request_count(), initial_count);
+    }
+
+    #[test]
+    fn test_background_transfers_are_throttled() {
+        let _lock = INTERACTIVE_REQUESTS_LOCK.lock().unwrap();
+        let throttle_duration = || {
+            let start = Instant::now();
+            futures::executor::block_on(throttle_background_transfer());
+            start.elapsed()
+        };
+
+        assert!(throttle_duration() < BACKGROUND_THROTTLE_DELAY);
+        let guard = InteractiveRequestGuard::new();
+        assert!(throttle_duration() >= BACKGROUND_THROTTLE_DELAY);
+        drop(guard);
+        assert!(throttle_duration() < BACKGROUND_THROTTLE_DELAY);
+    }
+
+    #[test]
+    fn test_interactive_requests_throttle_until_first_chunk() {
+        let _lock = INTERACTIVE_REQUESTS_LOCK.lock().unwrap();
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let (finish_tx, finish_rx) = mpsc::channel::<()>();
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0; 1024];
+            let len = stream.read(&mut request).unwrap();
+            assert!(request[..len].starts_with(b"GET / "));
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n5\r\nfirst\r\n")
+                .unwrap();
+            finish_rx.recv().unwrap();
+            stream.write_all(b"6\r\nsecond\r\n0\r\n\r\n").unwrap();
+        });
+
+        let client = ReqwestClient::new();
+        let request = http::Request::builder()
+            .uri(url)
+            .priority(RequestPriority::Interactive)
+            .body(AsyncBody::empty())
+            .unwrap();
+        let initial_count = interactive_request_count();
+        futures::executor::block_on(async {
+            let mut response = client.send(request).await.unwrap();
+            assert_eq!(interactive_request_count(), initial_count + 1);
+
+            let mut first = [0; 5];
+            response.body_mut().read_exact(&mut first).await.unwrap();
+            assert_eq!(&first, b"first");
+            assert_eq!(interactive_request_count(), initial_count);
+
+            finish_tx.send(()).unwrap();
+            let mut rest = String::new();
+            response.body_mut().read_to_string(&mut rest).await.unwrap();
+            assert_eq!(rest, "second");
+        });
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_warm_up_connection_is_reused_by_interactive_requests() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = Url::parse(&format!("http://{}/", listener.local_addr().unwrap())).unwrap();
+        let connections = Arc::new(AtomicUsize::new(0));
+        thread::spawn({
+            let connections = connections.clone();
+            move || {
+                for stream in listener.incoming() {
+                    connections.fetch_add(1, Ordering::SeqCst);
+                    let mut stream = stream.unwrap();
+                    thread::spawn(move || {
+                        let mut reader = BufReader::new(stream.try_clone().unwrap());
+                        loop {
+                            let mut request_line = String::new();
+                            if reader.read_line(&mut request_line).unwrap_or(0) == 0 {
+                                break;
+                            }
+                            let mut header = String::new();
+                            while reader.read_line(&mut header).is_ok() && header != "\r\n" {
+                                header.clear();
+                            }
+                            let response: &[u8] = if request_line.starts_with("HEAD ") {
+                                b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n"
+                            } else {
+                                b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok"
+                            };
+                            stream.write_all(response).unwrap();
+                        }
+                    });
+                }
+            }
+        });
+
+        let client = ReqwestClient::new();
+        let get = |priority| {
+            let request = http::Request::builder()
+                .uri(url.as_str())
+                .priority(priority)
+                .body(AsyncBody::empty())
+                .unwrap();
+            let client = client.clone();
+            async move {
+                let mut body = String::new();
+                let mut response = client.send(request).await.unwrap();
+                response.body_mut().read_to_string(&mut body).await.unwrap();
+                assert_eq!(body, "ok");
+            }
+        };
+        futures::executor::block_on(async {
+            client.warm_up(&url).await.unwrap();
+            assert_eq!(connections.load(Ordering::SeqCst), 1);
+            get(RequestPriority::Interactive).await;
+            assert_eq!(connections.load(Ordering::SeqCst), 1);
+
+            // Other requests don't share the connections kept alive for LLM providers.
+            get(RequestPriority::Normal).await;
+            assert_eq!(connections.load(Ordering::SeqCst), 2);
+        });
+    }
+
+    #[test]
+    fn test_warm_up_requires_host() {
+        let client = ReqwestClient::new();
+        let url = Url::parse("data:text/plain,hello").unwrap();
+        assert!(futures::executor::block_on(client.warm_up(&url)).is_err());
+    }
+
     #[test]
     fn test_invalid_proxy_uri() {
         let proxy = Url::parse("socks://127.0.0.1:20170").unwrap();
@@ -38,6 +38,25 @@ pub enum Notification {
         sender_id: u64,
         channel_id: u64,
     },
+    /// A call participant started editing the buffer that's focused on this client.
+    ///
+    /// These are created by the client from the edits it observes, and are never
+    /// stored by the server.
+    CollaboratorEditing {
+        #[serde(rename = "entity_id")]
+        project_id: u64,
+        collaborator_id: u64,
+        path: String,
+    },
+    /// A scheduled agent task finished running.
+    ///
+    /// These are created by the client when the task's thread stops, and are never
+    /// stored by the server.
+    AgentTaskFinished {
+        task_name: String,
+        thread_id: String,
+        summary: String,
+    },
 }
 
 impl Notification {
@@ -96,6 +115,16 @@ mod tests {
                 channel_id: 30,
                 message_id: 1,
             },
+            Notification::CollaboratorEditing {
+                project_id: 7,
+                collaborator_id: 3,
+                path: "src/main.rs".into(),
+            },
+            Notification::AgentTaskFinished {
+                task_name: "Summarize TODOs".into(),
+                thread_id: "a1b2c3".into(),
+                summary: "Found 3 new TODOs.".into(),
+            },
         ] {
             let message = notification.to_proto();
             let deserialized = Notification::from_proto(&message).unwrap();
@@ -1,11 +1,15 @@
 use anyhow::Result;
+use buffer_diff::BufferDiff;
 use collections::{HashMap, HashSet};
 use editor::CompletionProvider;
-use editor::{CurrentLineHighlight, Editor, EditorElement, EditorEvent, EditorStyle, actions::Tab};
+use editor::{
+    CurrentLineHighlight, Editor, EditorElement, EditorEvent, EditorStyle, MultiBuffer,
+    actions::Tab,
+};
 use gpui::{
-    Action, App, Bounds, Entity, EventEmitter, Focusable, PromptLevel, Subscription, Task,
-    TextStyle, TitlebarOptions, WindowBounds, WindowHandle, WindowOptions, actions, point, size,
-    transparent_black,
+    Action, App, Bounds, Entity, EventEmitter, Focusable, PathPromptOptions, PromptLevel,
+    Subscription, Task, TextStyle, TitlebarOptions, WindowBounds, WindowHandle, WindowOptions,
+    actions, point, size, transparent_black,
 };
 use language::{Buffer, LanguageRegistry, language_settings::SoftWrap};
 use language_model::{
@@ -21,8 +25,8 @@ use std::sync::atomic::AtomicBool;
 use std::time::Duration;
 use theme::ThemeSettings;
 use ui::{
-    Context, IconButtonShape, KeyBinding, ListItem, ListItemSpacing, ParentElement, Render,
-    SharedString, Styled, Tooltip, Window, div, prelude::*,
+    Context, ContextMenu, IconButtonShape, KeyBinding, ListItem, ListItemSpacing, ParentElement,
+    PopoverMenu, Render, SharedString, Styled, Tooltip, Window, div, prelude::*,
 };
 use util::{ResultExt, TryFutureExt};
 use workspace::Workspace;
@@ -36,7 +40,14 @@ pub fn init(cx: &mut App) {
 
 actions!(
     rules_library,
-    [NewRule, DeleteRule, DuplicateRule, ToggleDefaultRule]
+    [
+        NewRule,
+        DeleteRule,
+        DuplicateRule,
+        ToggleDefaultRule,
+        ImportRules,
+        ExportRules
+    ]
 );
 
 const BUILT_IN_TOOLTIP_TEXT: &'static str = concat!(
@@ -154,13 +165,24 @@ pub struct RulesLibrary {
 struct RuleEditor {
     title_editor: Entity<Editor>,
     body_editor: Entity<Editor>,
+    scope_editor: Entity<Editor>,
     token_count: Option<usize>,
     pending_token_count: Task<Option<()>>,
     next_title_and_body_to_save: Option<(String, Rope)>,
     pending_save: Option<Task<Option<()>>>,
+    revisions: Vec<PromptRevision>,
+    pending_revisions_load: Task<Option<()>>,
+    revision_diff: Option<RevisionDiff>,
     _subscriptions: Vec<Subscription>,
 }
 
+/// A read-only view of the changes between a previous version of a rule and its
+/// current body.
+struct RevisionDiff {
+    revision: PromptRevision,
+    editor: Entity<Editor>,
+}
+
 struct RulePickerDelegate {
     store: Entity<PromptStore>,
     selected_index: usize,
@@ -261,6 +283,7 @@ impl PickerDelegate for RulePickerDelegate {
         let rule = self.matches.get(ix)?;
         let default = rule.default;
         let prompt_id = rule.id;
+        let scope = (!rule.scope.is_empty()).then(|| rule.scope.to_string());
 
         let element = ListItem::new(ix)
             .inset(true)
@@ -269,8 +292,15 @@ impl PickerDelegate for RulePickerDelegate {
             .child(
                 h_flex()
                     .h_5()
+                    .gap_1p5()
                     .line_height(relative(1.))
-                    .child(Label::new(rule.title.clone().unwrap_or("Untitled".into()))),
+                    .child(Label::new(rule.title.clone().unwrap_or("Untitled".into())))
+                    .children(scope.map(|scope| {
+                        Label::new(scope)
+                            .size(LabelSize::Small)
+                            .color(Color::Muted)
+                            .truncate()
+                    })),
             )
             .end_slot::<IconButton>(default.then(|| {
                 IconButton::new("toggle-default-rule", IconName::StarFilled)
@@ -507,6 +537,7 @@ impl RulesLibrary {
                             this.update_in(cx, |this, window, cx| {
                                 this.picker
                                     .update(cx, |picker, cx| picker.refresh(window, cx));
+                                this.load_revisions(prompt_id, cx);
                                 cx.notify();
                             })?;
 
@@ -528,12 +559,129 @@ impl RulesLibrary {
         }
     }
 
+    pub fn export_rules(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let bundle = self.store.read(cx).export_bundle(cx);
+        let path = cx.prompt_for_new_path(util::paths::home_dir());
+        cx.spawn_in(window, async move |_, cx| {
+            let Some(path) = path.await?? else {
+                return anyhow::Ok(());
+            };
+            let bundle = bundle.await?;
+            let json = serde_json::to_string_pretty(&bundle)?;
+            cx.background_spawn(async move { std::fs::write(path, json) })
+                .await?;
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    pub fn import_rules(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let paths = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+        });
+        cx.spawn_in(window, async move |this, cx| {
+            let Some(path) = paths.await??.and_then(|paths| paths.into_iter().next()) else {
+                return anyhow::Ok(());
+            };
+            let json = cx
+                .background_spawn(async move { std::fs::read_to_string(path) })
+                .await?;
+            let bundle = serde_json::from_str::<RulesBundle>(&json)?;
+
+            let conflicting_titles = this.update(cx, |this, cx| {
+                this.store.read(cx).conflicting_titles(&bundle)
+            })?;
+            let on_conflict = if conflicting_titles.is_empty() {
+                RulesImportConflict::Skip
+            } else {
+                let answer = this.update_in(cx, |_, window, cx| {
+                    window.prompt(
+                        PromptLevel::Warning,
+                        &format!(
+                            "{} imported rule(s) have the same title as existing rules",
+                            conflicting_titles.len()
+                        ),
+                        Some(&*conflicting_titles.join("\n")),
+                        &["Skip", "Overwrite", "Keep Both", "Cancel"],
+                        cx,
+                    )
+                })?;
+                match answer.await.ok() {
+                    Some(0) => RulesImportConflict::Skip,
+                    Some(1) => RulesImportConflict::Overwrite,
+                    Some(2) => RulesImportConflict::KeepBoth,
+                    _ => return anyhow::Ok(()),
+                }
+            };
+
+            // Let edits to open rules finish saving first, so that they can't overwrite the
+            // imported rules afterwards.
+            let pending_saves = this.update(cx, |this, _| {
+                this.rule_editors
+                    .values_mut()
+                    .filter_map(|rule_editor| rule_editor.pending_save.take())
+                    .collect::<Vec<_>>()
+            })?;
+            for pending_save in pending_saves {
+                pending_save.await;
+            }
+
+            let import = this.update(cx, |this, cx| {
+                this.store
+                    .update(cx, |store, cx| store.import_bundle(bundle, on_conflict, cx))
+            })?;
+            let imported_ids = import.await?;
+            this.update_in(cx, |this, window, cx| {
+                // Overwritten rules may be open, so reload them from the store.
+                for id in imported_ids {
+                    if this.rule_editors.remove(&id).is_some() && this.active_rule_id == Some(id) {
+                        this.active_rule_id = None;
+                        this.load_rule(id, false, window, cx);
+                    }
+                }
+                this.picker
+                    .update(cx, |picker, cx| picker.refresh(window, cx));
+                cx.notify();
+            })?;
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
     pub fn delete_active_rule(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         if let Some(active_rule_id) = self.active_rule_id {
             self.delete_rule(active_rule_id, window, cx);
         }
     }
 
+    fn save_rule_scope(
+        &mut self,
+        prompt_id: PromptId,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(rule_editor) = self.rule_editors.get(&prompt_id) else {
+            return;
+        };
+        let scope = RuleScope::parse(&rule_editor.scope_editor.read(cx).text(cx));
+        if self
+            .store
+            .read(cx)
+            .metadata(prompt_id)
+            .is_some_and(|metadata| metadata.scope == scope)
+        {
+            return;
+        }
+
+        self.store
+            .update(cx, |store, cx| store.save_scope(prompt_id, scope, cx))
+            .detach_and_log_err(cx);
+        self.picker
+            .update(cx, |picker, cx| picker.refresh(window, cx));
+    }
+
     pub fn duplicate_active_rule(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         if let Some(active_rule_id) = self.active_rule_id {
             self.duplicate_rule(active_rule_id, window, cx);
@@ -597,6 +745,19 @@ impl RulesLibrary {
                             }
                             editor
                         });
+                        let scope_editor = cx.new(|cx| {
+                            let mut editor = Editor::single_line(window, cx);
+                            editor.set_placeholder_text(
+                                "All files. Limit to languages or paths, like: Rust, src/**/*.ts",
+                                cx,
+                            );
+                            editor.set_text(rule_metadata.scope.to_string(), window, cx);
+                            if prompt_id.is_built_in() {
+                                editor.set_read_only(true);
+                            }
+                            editor.set_show_edit_predictions(Some(false), window, cx);
+                            editor
+                        });
                         let body_editor = cx.new(|cx| {
                             let buffer = cx.new(|cx| {
                                 let mut buffer = Buffer::local(rule, cx);
@@ -641,21 +802,35 @@ impl RulesLibrary {
                                     )
                                 },
                             ),
+                            cx.subscribe_in(
+                                &scope_editor,
+                                window,
+                                move |this, _, event, window, cx| {
+                                    if let EditorEvent::BufferEdited = event {
+                                        this.save_rule_scope(prompt_id, window, cx);
+                                    }
+                                },
+                            ),
                         ];
                         this.rule_editors.insert(
                             prompt_id,
                             RuleEditor {
                                 title_editor,
                                 body_editor,
+                                scope_editor,
                                 next_title_and_body_to_save: None,
                                 pending_save: None,
                                 token_count: None,
                                 pending_token_count: Task::ready(None),
+                                revisions: Vec::new(),
+                                pending_revisions_load: Task::ready(None),
+                                revision_diff: None,
                                 _subscriptions,
                             },
                         );
                         this.set_active_rule(Some(prompt_id), window, cx);
                         this.count_tokens(prompt_id, window, cx);
+                        this.load_revisions(prompt_id, cx);
                     }
                     Err(error) => {
                         // TODO: we should show the error in the UI.
@@ -667,6 +842,160 @@ impl RulesLibrary {
         }
     }
 
+    fn load_revisions(&mut self, prompt_id: PromptId, cx: &mut Context<Self>) {
+        let Some(rule_editor) = self.rule_editors.get_mut(&prompt_id) else {
+            return;
+        };
+        let revisions = self.store.read(cx).revisions(prompt_id, cx);
+        rule_editor.pending_revisions_load = cx.spawn(async move |this, cx| {
+            let revisions = revisions.await.log_err()?;
+            this.update(cx, |this, cx| {
+                if let Some(rule_editor) = this.rule_editors.get_mut(&prompt_id) {
+                    rule_editor.revisions = revisions;
+                    cx.notify();
+                }
+            })
+            .ok()
+        });
+    }
+
+    /// Shows the changes between `revision` and the rule's current body in place of
+    /// the body editor.
+    pub fn compare_rule_revision(
+        &mut self,
+        prompt_id: PromptId,
+        revision: PromptRevision,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(rule_editor) = self.rule_editors.get(&prompt_id) else {
+            return;
+        };
+        let body_buffer = rule_editor
+            .body_editor
+            .read(cx)
+            .buffer()
+            .read(cx)
+            .as_singleton();
+        let language = body_buffer.and_then(|buffer| buffer.read(cx).language().cloned());
+        let language_registry = self.language_registry.clone();
+        let current_body = rule_editor.body_editor.read(cx).text(cx);
+        let new_buffer = |text: String, cx: &mut Context<Self>| {
+            cx.new(|cx| {
+                let mut buffer = Buffer::local(text, cx);
+                buffer.set_language(language.clone(), cx);
+                buffer.set_language_registry(language_registry.clone());
+                buffer
+            })
+        };
+        let buffer = new_buffer(current_body, cx);
+        let base_buffer = new_buffer(revision.body.clone(), cx);
+        let snapshot = buffer.read(cx).text_snapshot();
+        let diff = cx.new(|cx| BufferDiff::new(&snapshot, cx));
+        let diff_computed = diff.update(cx, |diff, cx| {
+            diff.set_base_text_buffer(base_buffer, snapshot, cx)
+        });
+
+        // Wait for the diff so that the comparison doesn't first appear without any changes.
+        cx.spawn_in(window, async move |this, cx| {
+            diff_computed.await.ok();
+            this.update_in(cx, |this, window, cx| {
+                this.show_revision_diff(prompt_id, revision, buffer, diff, window, cx)
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn show_revision_diff(
+        &mut self,
+        prompt_id: PromptId,
+        revision: PromptRevision,
+        buffer: Entity<Buffer>,
+        diff: Entity<BufferDiff>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.rule_editors.contains_key(&prompt_id) {
+            return;
+        }
+        let multibuffer = cx.new(|cx| {
+            let mut multibuffer = MultiBuffer::singleton(buffer, cx);
+            multibuffer.add_diff(diff, cx);
+            multibuffer
+        });
+        let editor = cx.new(|cx| {
+            let mut editor = Editor::for_multibuffer(multibuffer, None, window, cx);
+            editor.set_read_only(true);
+            editor.set_show_edit_predictions(Some(false), window, cx);
+            editor.set_soft_wrap_mode(SoftWrap::EditorWidth, cx);
+            editor.set_show_wrap_guides(false, cx);
+            editor.set_show_indent_guides(false, cx);
+            editor.set_current_line_highlight(Some(CurrentLineHighlight::None));
+            editor.set_expand_all_diff_hunks(cx);
+            editor
+        });
+
+        if let Some(rule_editor) = self.rule_editors.get_mut(&prompt_id) {
+            rule_editor.revision_diff = Some(RevisionDiff { revision, editor });
+        }
+        cx.notify();
+    }
+
+    fn close_revision_diff(&mut self, prompt_id: PromptId, cx: &mut Context<Self>) {
+        if let Some(rule_editor) = self.rule_editors.get_mut(&prompt_id) {
+            rule_editor.revision_diff = None;
+        }
+        cx.notify();
+    }
+
+    pub fn restore_rule_revision(
+        &mut self,
+        prompt_id: PromptId,
+        revision: PromptRevision,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(rule_editor) = self.rule_editors.get(&prompt_id) else {
+            return;
+        };
+        let current_body = rule_editor.body_editor.read(cx).text(cx);
+        let (added_since, removed_since) = revision.line_changes(&current_body);
+        let confirmation = window.prompt(
+            PromptLevel::Warning,
+            &format!(
+                "Restore the version saved on {}?",
+                revision
+                    .saved_at
+                    .with_timezone(&chrono::Local)
+                    .format("%b %-d, %Y %-I:%M %p")
+            ),
+            Some(&*format!(
+                "Restoring will remove {added_since} line(s) and add back {removed_since} line(s)."
+            )),
+            &["Restore", "Cancel"],
+            cx,
+        );
+
+        cx.spawn_in(window, async move |this, cx| {
+            if confirmation.await.ok() == Some(0) {
+                this.update_in(cx, |this, window, cx| {
+                    let Some(rule_editor) = this.rule_editors.get(&prompt_id) else {
+                        return;
+                    };
+                    rule_editor.title_editor.update(cx, |editor, cx| {
+                        editor.set_text(revision.title.unwrap_or_default(), window, cx)
+                    });
+                    rule_editor
+                        .body_editor
+                        .update(cx, |editor, cx| editor.set_text(revision.body, window, cx));
+                    this.close_revision_diff(prompt_id, cx);
+                })?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
     fn set_active_rule(
         &mut self,
         prompt_id: Option<PromptId>,
@@ -948,6 +1277,7 @@ impl RulesLibrary {
                                     tool_choice: None,
                                     stop: Vec::new(),
                                     temperature: None,
+                                    response_schema: None,
                                 },
                                 cx,
                             )
@@ -982,6 +1312,29 @@ impl RulesLibrary {
                     .w_full()
                     .flex_none()
                     .justify_end()
+                    .gap_1()
+                    .child(
+                        IconButton::new("import-rules", IconName::Download)
+                            .style(ButtonStyle::Transparent)
+                            .shape(IconButtonShape::Square)
+                            .tooltip(move |window, cx| {
+                                Tooltip::for_action("Import Rules", &ImportRules, window, cx)
+                            })
+                            .on_click(|_, window, cx| {
+                                window.dispatch_action(Box::new(ImportRules), cx);
+                            }),
+                    )
+                    .child(
+                        IconButton::new("export-rules", IconName::FileCreate)
+                            .style(ButtonStyle::Transparent)
+                            .shape(IconButtonShape::Square)
+                            .tooltip(move |window, cx| {
+                                Tooltip::for_action("Export Rules", &ExportRules, window, cx)
+                            })
+                            .on_click(|_, window, cx| {
+                                window.dispatch_action(Box::new(ExportRules), cx);
+                            }),
+                    )
                     .child(
                         IconButton::new("new-rule", IconName::Plus)
                             .style(ButtonStyle::Transparent)
@@ -997,6 +1350,114 @@ impl RulesLibrary {
             .child(div().flex_grow().child(self.picker.clone()))
     }
 
+    fn render_rule_history_menu(
+        &self,
+        prompt_id: PromptId,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let this = cx.entity().downgrade();
+        let revisions = self
+            .rule_editors
+            .get(&prompt_id)
+            .map(|rule_editor| rule_editor.revisions.clone())
+            .unwrap_or_default();
+
+        PopoverMenu::new("rule-history-menu")
+            .trigger_with_tooltip(
+                IconButton::new("rule-history", IconName::HistoryRerun)
+                    .icon_size(IconSize::Small)
+                    .disabled(revisions.len() < 2),
+                Tooltip::text("Rule History"),
+            )
+            .anchor(gpui::Corner::TopRight)
+            .menu(move |window, cx| {
+                let this = this.clone();
+                let revisions = revisions.clone();
+                Some(ContextMenu::build(window, cx, move |mut menu, _, _| {
+                    menu = menu.header("Compare with a Previous Version");
+                    // The first revision is the current content of the rule.
+                    for revision in revisions.into_iter().skip(1) {
+                        let label = revision
+                            .saved_at
+                            .with_timezone(&chrono::Local)
+                            .format("%b %-d, %Y %-I:%M %p")
+                            .to_string();
+                        let this = this.clone();
+                        menu = menu.entry(label, None, move |window, cx| {
+                            this.update(cx, |this, cx| {
+                                this.compare_rule_revision(prompt_id, revision.clone(), window, cx)
+                            })
+                            .ok();
+                        });
+                    }
+                    menu
+                }))
+            })
+    }
+
+    fn render_revision_diff(
+        &self,
+        prompt_id: PromptId,
+        revision_diff: &RevisionDiff,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let saved_at = revision_diff
+            .revision
+            .saved_at
+            .with_timezone(&chrono::Local)
+            .format("%b %-d, %Y %-I:%M %p");
+        let revision = revision_diff.revision.clone();
+
+        v_flex()
+            .h_full()
+            .flex_1()
+            .child(
+                h_flex()
+                    .py_1()
+                    .px_2p5()
+                    .gap_2()
+                    .justify_between()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(
+                        Label::new(format!("Changes since the version saved on {saved_at}"))
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .child(
+                        h_flex()
+                            .gap_1()
+                            .child(
+                                Button::new("restore-revision", "Restore")
+                                    .label_size(LabelSize::Small)
+                                    .on_click(cx.listener(move |this, _, window, cx| {
+                                        this.restore_rule_revision(
+                                            prompt_id,
+                                            revision.clone(),
+                                            window,
+                                            cx,
+                                        )
+                                    })),
+                            )
+                            .child(
+                                Button::new("close-revision-diff", "Close")
+                                    .label_size(LabelSize::Small)
+                                    .on_click(cx.listener(move |this, _, _, cx| {
+                                        this.close_revision_diff(prompt_id, cx)
+                                    })),
+                            ),
+                    ),
+            )
+            .child(
+                h_flex()
+                    .py_2()
+                    .pl_2p5()
+                    .h_full()
+                    .flex_1()
+                    .child(revision_diff.editor.clone()),
+            )
+    }
+
     fn render_active_rule(&mut self, cx: &mut Context<RulesLibrary>) -> gpui::Stateful<Div> {
         div()
             .w_2_3()
@@ -1145,6 +1606,9 @@ impl RulesLibrary {
                                                 })
                                                 .into_any_element()
                                         })
+                                        .when(!prompt_id.is_built_in(), |this| {
+                                            this.child(self.render_rule_history_menu(prompt_id, cx))
+                                        })
                                         .child(
                                             IconButton::new("duplicate-rule", IconName::BookCopy)
                                                 .icon_size(IconSize::Small)
@@ -1199,6 +1663,30 @@ impl RulesLibrary {
                                         ),
                                 ),
                         )
+                        .when(!prompt_id.is_built_in(), |this| {
+                            this.child(
+                                h_flex()
+                                    .id("rule-scope")
+                                    .pt_1()
+                                    .px_2p5()
+                                    .gap_1p5()
+                                    .child(
+                                        Icon::new(IconName::Filter)
+                                            .size(IconSize::Small)
+                                            .color(Color::Muted),
+                                    )
+                                    .child(rule_editor.scope_editor.clone())
+                                    .tooltip(move |window, cx| {
+                                        Tooltip::with_meta(
+                                            "Rule Scope",
+                                            None,
+                                            "When this is a default rule, it's only included in threads whose active file or attached context matches one of these languages or path globs.",
+                                            window,
+                                            cx,
+                                        )
+                                    }),
+                            )
+                        })
                         .child(
                             div()
                                 .on_action(cx.listener(Self::focus_picker))
@@ -1206,14 +1694,18 @@ impl RulesLibrary {
                                 .on_action(cx.listener(Self::move_up_from_body))
                                 .flex_grow()
                                 .h_full()
-                                .child(
-                                    h_flex()
+                                .child(match &rule_editor.revision_diff {
+                                    Some(revision_diff) => self
+                                        .render_revision_diff(prompt_id, revision_diff, cx)
+                                        .into_any_element(),
+                                    None => h_flex()
                                         .py_2()
                                         .pl_2p5()
                                         .h_full()
                                         .flex_1()
-                                        .child(rule_editor.body_editor.clone()),
-                                ),
+                                        .child(rule_editor.body_editor.clone())
+                                        .into_any_element(),
+                                }),
                         ),
                 )
             }))
@@ -1238,6 +1730,8 @@ impl Render for RulesLibrary {
             .on_action(cx.listener(|this, &ToggleDefaultRule, window, cx| {
                 this.toggle_default_for_active_rule(window, cx)
             }))
+            .on_action(cx.listener(|this, &ImportRules, window, cx| this.import_rules(window, cx)))
+            .on_action(cx.listener(|this, &ExportRules, window, cx| this.export_rules(window, cx)))
             .size_full()
             .overflow_hidden()
             .font(ui_font)
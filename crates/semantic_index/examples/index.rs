@@ -3,8 +3,9 @@ use futures::channel::oneshot;
 use gpui::Application;
 use http_client::HttpClientWithUrl;
 use language::language_settings::AllLanguageSettings;
+use language_models::provider::open_ai::OpenAiEmbeddingModel;
 use project::Project;
-use semantic_index::{OpenAiEmbeddingModel, OpenAiEmbeddingProvider, SemanticDb};
+use semantic_index::{LanguageModelEmbeddingProvider, SemanticDb};
 use settings::SettingsStore;
 use std::{
     path::{Path, PathBuf},
@@ -48,12 +49,14 @@ fn main() {
 
         let api_key = std::env::var("OPENAI_API_KEY").expect("OPENAI_API_KEY not set");
 
-        let embedding_provider = Arc::new(OpenAiEmbeddingProvider::new(
-            http.clone(),
-            OpenAiEmbeddingModel::TextEmbedding3Small,
-            open_ai::OPEN_AI_API_URL.to_string(),
-            api_key,
-        ));
+        let embedding_provider = Arc::new(LanguageModelEmbeddingProvider::new(Arc::new(
+            OpenAiEmbeddingModel::new(
+                http.clone(),
+                open_ai::OpenAiEmbeddingModel::TextEmbedding3Small,
+                open_ai::OPEN_AI_API_URL.to_string(),
+                Some(api_key),
+            ),
+        )));
 
         cx.spawn(async move |cx| {
             let semantic_index = SemanticDb::new(
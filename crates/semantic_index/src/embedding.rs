@@ -1,16 +1,10 @@
-mod lmstudio;
-mod ollama;
-mod open_ai;
-
-pub use lmstudio::*;
-pub use ollama::*;
-pub use open_ai::*;
 use sha2::{Digest, Sha256};
 
 use anyhow::Result;
 use futures::{FutureExt, future::BoxFuture};
-use serde::{Deserialize, Serialize};
-use std::{fmt, future};
+pub use language_model::Embedding;
+use language_model::EmbeddingModel;
+use std::{future, sync::Arc};
 
 /// Trait for embedding providers. Texts in, vectors out.
 pub trait EmbeddingProvider: Sync + Send {
@@ -18,71 +12,6 @@ pub trait EmbeddingProvider: Sync + Send {
     fn batch_size(&self) -> usize;
 }
 
-#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
-pub struct Embedding(Vec<f32>);
-
-impl Embedding {
-    pub fn new(mut embedding: Vec<f32>) -> Self {
-        let len = embedding.len();
-        let mut norm = 0f32;
-
-        for i in 0..len {
-            norm += embedding[i] * embedding[i];
-        }
-
-        norm = norm.sqrt();
-        for dimension in &mut embedding {
-            *dimension /= norm;
-        }
-
-        Self(embedding)
-    }
-
-    fn len(&self) -> usize {
-        self.0.len()
-    }
-
-    pub fn similarity(&self, others: &[Embedding]) -> (f32, usize) {
-        debug_assert!(others.iter().all(|other| self.0.len() == other.0.len()));
-        others
-            .iter()
-            .enumerate()
-            .map(|(index, other)| {
-                let dot_product: f32 = self
-                    .0
-                    .iter()
-                    .copied()
-                    .zip(other.0.iter().copied())
-                    .map(|(a, b)| a * b)
-                    .sum();
-                (dot_product, index)
-            })
-            .max_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal))
-            .unwrap_or((0.0, 0))
-    }
-}
-
-impl fmt::Display for Embedding {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let digits_to_display = 3;
-
-        // Start the Embedding display format
-        write!(f, "Embedding(sized: {}; values: [", self.len())?;
-
-        for (index, value) in self.0.iter().enumerate().take(digits_to_display) {
-            // Lead with comma if not the first element
-            if index != 0 {
-                write!(f, ", ")?;
-            }
-            write!(f, "{:.3}", value)?;
-        }
-        if self.len() > digits_to_display {
-            write!(f, "...")?;
-        }
-        write!(f, "])")
-    }
-}
-
 #[derive(Debug)]
 pub struct TextToEmbed<'a> {
     pub text: &'a str,
@@ -99,6 +28,28 @@ impl<'a> TextToEmbed<'a> {
     }
 }
 
+/// Embeds texts with one of the embedding models offered by a language model provider.
+pub struct LanguageModelEmbeddingProvider {
+    model: Arc<dyn EmbeddingModel>,
+}
+
+impl LanguageModelEmbeddingProvider {
+    pub fn new(model: Arc<dyn EmbeddingModel>) -> Self {
+        Self { model }
+    }
+}
+
+impl EmbeddingProvider for LanguageModelEmbeddingProvider {
+    fn embed<'a>(&'a self, texts: &'a [TextToEmbed<'a>]) -> BoxFuture<'a, Result<Vec<Embedding>>> {
+        self.model
+            .embed(texts.iter().map(|text| text.text.to_string()).collect())
+    }
+
+    fn batch_size(&self) -> usize {
+        self.model.max_batch_size()
+    }
+}
+
 pub struct FakeEmbeddingProvider;
 
 impl EmbeddingProvider for FakeEmbeddingProvider {
@@ -120,15 +71,3 @@ impl EmbeddingProvider for FakeEmbeddingProvider {
         16
     }
 }
-
-#[cfg(test)]
-mod test {
-    use super::*;
-
-    #[gpui::test]
-    fn test_normalize_embedding() {
-        let normalized = Embedding::new(vec![1.0, 1.0, 1.0]);
-        let value: f32 = 1.0 / 3.0_f32.sqrt();
-        assert_eq!(normalized, Embedding(vec![value; 3]));
-    }
-}
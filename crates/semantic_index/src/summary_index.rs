@@ -570,6 +570,7 @@ impl SummaryIndex {
             tool_choice: None,
             stop: Vec::new(),
             temperature: None,
+            response_schema: None,
         };
 
         let code_len = code.len();
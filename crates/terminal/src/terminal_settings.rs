@@ -48,6 +48,7 @@ pub struct TerminalSettings {
     pub max_scroll_history_lines: Option<usize>,
     pub toolbar: Toolbar,
     pub scrollbar: ScrollbarSettings,
+    pub encrypt_persisted_data: bool,
 }
 
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
@@ -223,6 +224,12 @@ pub struct TerminalSettingsContent {
     pub toolbar: Option<ToolbarContent>,
     /// Scrollbar-related settings
     pub scrollbar: Option<ScrollbarSettingsContent>,
+    /// Whether to encrypt the data Zed stores to restore terminals, such as
+    /// their working directories, with a key kept in the system keychain.
+    /// Existing data is migrated when this setting changes.
+    ///
+    /// Default: false
+    pub encrypt_persisted_data: Option<bool>,
 }
 
 impl settings::Settings for TerminalSettings {
@@ -1,15 +1,26 @@
-use anyhow::Result;
+use anyhow::{Context as _, Result, anyhow};
 use async_recursion::async_recursion;
 use collections::HashSet;
-use futures::{StreamExt as _, stream::FuturesUnordered};
-use gpui::{AppContext as _, AsyncWindowContext, Axis, Entity, Task, WeakEntity};
+use futures::FutureExt as _;
+use futures::future::{Shared, join_all};
+use gpui::{AppContext as _, AsyncWindowContext, Axis, Entity, Global, Task, WeakEntity};
 use project::{Project, terminals::TerminalKind};
+use ring::aead::{AES_256_GCM, Aad, LessSafeKey, NONCE_LEN, Nonce, UnboundKey};
+use ring::rand::{SecureRandom as _, SystemRandom};
 use serde::{Deserialize, Serialize};
+use settings::{Settings as _, SettingsStore};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use terminal::terminal_settings::TerminalSettings;
 use ui::{App, Context, Pixels, Window};
 use util::ResultExt as _;
 
-use db::{define_connection, query, sqlez::statement::Statement, sqlez_macros::sql};
+use db::{
+    define_connection, query,
+    sqlez::bindable::{Bind, StaticColumnCount},
+    sqlez::statement::Statement,
+    sqlez_macros::sql,
+};
 use workspace::{
     ItemHandle, ItemId, Member, Pane, PaneAxis, PaneGroup, SerializableItem as _, Workspace,
     WorkspaceDb, WorkspaceId,
@@ -20,6 +31,196 @@ use crate::{
     terminal_panel::{TerminalPanel, new_terminal_pane},
 };
 
+const ENCRYPTION_KEY_URL: &str = "zed://terminal-persistence";
+const ENCRYPTION_KEY_USERNAME: &str = "encryption-key";
+
+pub(crate) fn init(cx: &mut App) {
+    let mut encrypt = TerminalSettings::get_global(cx).encrypt_persisted_data;
+    migrate_working_directories(encrypt, cx);
+    cx.observe_global::<SettingsStore>(move |cx| {
+        let new_encrypt = TerminalSettings::get_global(cx).encrypt_persisted_data;
+        if new_encrypt != encrypt {
+            encrypt = new_encrypt;
+            migrate_working_directories(encrypt, cx);
+        }
+    })
+    .detach();
+}
+
+/// Brings the persisted working directories in line with the `terminal.encrypt_persisted_data`
+/// setting, so that enabling it also covers the terminals that were saved before.
+fn migrate_working_directories(encrypt: bool, cx: &mut App) {
+    cx.spawn(async move |cx| {
+        let needs_migration = cx
+            .background_spawn(async move {
+                if encrypt {
+                    TERMINAL_DB.has_plaintext_working_directories()
+                } else {
+                    TERMINAL_DB.has_encrypted_working_directories()
+                }
+            })
+            .await?;
+        // Reading the keychain may prompt the user, so only do it when there's something to
+        // migrate.
+        if !needs_migration {
+            return Ok(());
+        }
+
+        let key = cx
+            .update(encryption_key)?
+            .await
+            .context("terminal encryption key is unavailable")?;
+        cx.background_spawn(
+            async move { TERMINAL_DB.migrate_working_directories(&key, encrypt).await },
+        )
+        .await
+    })
+    .detach_and_log_err(cx);
+}
+
+/// The key used to encrypt the data in [`TerminalDb`], which is stored in the system keychain.
+pub struct EncryptionKey(LessSafeKey);
+
+impl EncryptionKey {
+    const LEN: usize = 32;
+
+    fn new(bytes: &[u8]) -> Result<Self> {
+        let key = UnboundKey::new(&AES_256_GCM, bytes)
+            .map_err(|_| anyhow!("invalid terminal encryption key"))?;
+        Ok(Self(LessSafeKey::new(key)))
+    }
+
+    fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        let mut nonce = [0; NONCE_LEN];
+        SystemRandom::new()
+            .fill(&mut nonce)
+            .map_err(|_| anyhow!("failed to generate a nonce"))?;
+        let mut ciphertext = plaintext.to_vec();
+        self.0
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::empty(),
+                &mut ciphertext,
+            )
+            .map_err(|_| anyhow!("failed to encrypt terminal data"))?;
+
+        let mut encrypted = nonce.to_vec();
+        encrypted.extend(ciphertext);
+        Ok(encrypted)
+    }
+
+    fn decrypt(&self, encrypted: &[u8]) -> Result<Vec<u8>> {
+        anyhow::ensure!(
+            encrypted.len() >= NONCE_LEN,
+            "encrypted terminal data is too short"
+        );
+        let (nonce, ciphertext) = encrypted.split_at(NONCE_LEN);
+        let nonce = Nonce::try_assume_unique_for_key(nonce)
+            .map_err(|_| anyhow!("invalid nonce in encrypted terminal data"))?;
+        let mut ciphertext = ciphertext.to_vec();
+        let plaintext = self
+            .0
+            .open_in_place(nonce, Aad::empty(), &mut ciphertext)
+            .map_err(|_| anyhow!("failed to decrypt terminal data"))?;
+        Ok(plaintext.to_vec())
+    }
+}
+
+struct GlobalEncryptionKey(Shared<Task<Option<Arc<EncryptionKey>>>>);
+
+impl Global for GlobalEncryptionKey {}
+
+/// Loads the key for encrypting terminal data from the keychain, creating it the first time
+/// it's needed.
+pub(crate) fn encryption_key(cx: &mut App) -> Shared<Task<Option<Arc<EncryptionKey>>>> {
+    if let Some(key) = cx.try_global::<GlobalEncryptionKey>() {
+        return key.0.clone();
+    }
+
+    let read_credentials = cx.read_credentials(ENCRYPTION_KEY_URL);
+    let key = cx
+        .spawn(async move |cx| {
+            let key = async {
+                if let Some((_, bytes)) = read_credentials.await? {
+                    return EncryptionKey::new(&bytes);
+                }
+
+                let mut bytes = [0; EncryptionKey::LEN];
+                SystemRandom::new()
+                    .fill(&mut bytes)
+                    .map_err(|_| anyhow!("failed to generate a terminal encryption key"))?;
+                cx.update(|cx| {
+                    cx.write_credentials(ENCRYPTION_KEY_URL, ENCRYPTION_KEY_USERNAME, &bytes)
+                })?
+                .await?;
+                EncryptionKey::new(&bytes)
+            }
+            .await;
+            key.log_err().map(Arc::new)
+        })
+        .shared();
+    cx.set_global(GlobalEncryptionKey(key.clone()));
+    key
+}
+
+/// A terminal's working directory, as stored in [`TerminalDb`].
+#[derive(Debug)]
+pub enum StoredWorkingDirectory {
+    Plain(PathBuf),
+    Encrypted(Vec<u8>),
+}
+
+impl StoredWorkingDirectory {
+    pub fn encrypt(path: &Path, key: &EncryptionKey) -> Result<Self> {
+        Ok(Self::Encrypted(
+            key.encrypt(path.to_string_lossy().as_bytes())?,
+        ))
+    }
+
+    pub fn decrypt(&self, key: &EncryptionKey) -> Result<PathBuf> {
+        match self {
+            Self::Plain(path) => Ok(path.clone()),
+            Self::Encrypted(encrypted) => {
+                let plaintext = key.decrypt(encrypted)?;
+                Ok(PathBuf::from(String::from_utf8(plaintext)?))
+            }
+        }
+    }
+
+    fn from_columns(plain: Option<PathBuf>, encrypted: Option<Vec<u8>>) -> Option<Self> {
+        match (plain, encrypted) {
+            (_, Some(encrypted)) => Some(Self::Encrypted(encrypted)),
+            (Some(plain), None) => Some(Self::Plain(plain)),
+            (None, None) => None,
+        }
+    }
+}
+
+impl StaticColumnCount for StoredWorkingDirectory {
+    fn column_count() -> usize {
+        3
+    }
+}
+
+/// Binds the `working_directory`, `working_directory_path` and `encrypted_working_directory`
+/// columns, leaving the ones that don't apply `NULL`.
+impl Bind for StoredWorkingDirectory {
+    fn bind(&self, statement: &Statement, start_index: i32) -> Result<i32> {
+        match self {
+            Self::Plain(path) => {
+                let next_index = statement.bind(path, start_index)?;
+                let next_index = statement.bind(&path.to_string_lossy().to_string(), next_index)?;
+                statement.bind(&None::<Vec<u8>>, next_index)
+            }
+            Self::Encrypted(encrypted) => {
+                let next_index = statement.bind(&None::<PathBuf>, start_index)?;
+                let next_index = statement.bind(&None::<String>, next_index)?;
+                statement.bind(encrypted, next_index)
+            }
+        }
+    }
+}
+
 pub(crate) fn serialize_pane_group(
     pane_group: &PaneGroup,
     active_pane: &Entity<Pane>,
@@ -284,8 +485,7 @@ async fn deserialize_terminal_views(
     item_ids: &[u64],
     cx: &mut AsyncWindowContext,
 ) -> Vec<Entity<TerminalView>> {
-    let mut items = Vec::with_capacity(item_ids.len());
-    let mut deserialized_items = item_ids
+    let deserialized_items = item_ids
         .iter()
         .map(|item_id| {
             cx.update(|window, cx| {
@@ -300,13 +500,14 @@ async fn deserialize_terminal_views(
             })
             .unwrap_or_else(|e| Task::ready(Err(e.context("no window present"))))
         })
-        .collect::<FuturesUnordered<_>>();
-    while let Some(item) = deserialized_items.next().await {
-        if let Some(item) = item.log_err() {
-            items.push(item);
-        }
-    }
-    items
+        .collect::<Vec<_>>();
+    // Await all items together, but keep them in their serialized order so that
+    // the tabs are restored in the same order they were in.
+    join_all(deserialized_items)
+        .await
+        .into_iter()
+        .filter_map(|item| item.log_err())
+        .collect()
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -345,7 +546,36 @@ pub(crate) struct SerializedPane {
     pub pinned_count: usize,
 }
 
-#[derive(Debug)]
+/// The splits of a terminal panel, as stored in layout profiles. Unlike
+/// [`SerializedPaneGroup`], it doesn't refer to any terminals, as those belong to a
+/// project, while profiles are applied to any project.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) enum SerializedPaneLayout {
+    Pane,
+    Group {
+        axis: SerializedAxis,
+        flexes: Option<Vec<f32>>,
+        children: Vec<SerializedPaneLayout>,
+    },
+}
+
+pub(crate) fn serialize_pane_layout(member: &Member) -> SerializedPaneLayout {
+    match member {
+        Member::Axis(PaneAxis {
+            axis,
+            members,
+            flexes,
+            bounding_boxes: _,
+        }) => SerializedPaneLayout::Group {
+            axis: SerializedAxis(*axis),
+            flexes: Some(flexes.lock().clone()),
+            children: members.iter().map(serialize_pane_layout).collect(),
+        },
+        Member::Pane(_) => SerializedPaneLayout::Pane,
+    }
+}
+
+#[derive(Debug, PartialEq)]
 pub(crate) struct SerializedAxis(pub Axis);
 
 impl Serialize for SerializedAxis {
@@ -412,6 +642,9 @@ define_connection! {
             ALTER TABLE terminals ADD COLUMN working_directory_path TEXT;
             UPDATE terminals SET working_directory_path = CAST(working_directory AS TEXT);
         ),
+        sql! (
+            ALTER TABLE terminals ADD COLUMN encrypted_working_directory BLOB;
+        ),
     ];
 }
 
@@ -432,36 +665,163 @@ impl TerminalDb {
         &self,
         item_id: ItemId,
         workspace_id: WorkspaceId,
-        working_directory: PathBuf,
+        working_directory: StoredWorkingDirectory,
     ) -> Result<()> {
         log::debug!(
             "Saving working directory {working_directory:?} for item {item_id} in workspace {workspace_id:?}"
         );
         let query =
-            "INSERT INTO terminals(item_id, workspace_id, working_directory, working_directory_path)
-            VALUES (?1, ?2, ?3, ?4)
+            "INSERT INTO terminals(item_id, workspace_id, working_directory, working_directory_path, encrypted_working_directory)
+            VALUES (?1, ?2, ?3, ?4, ?5)
             ON CONFLICT DO UPDATE SET
                 item_id = ?1,
                 workspace_id = ?2,
                 working_directory = ?3,
-                working_directory_path = ?4"
+                working_directory_path = ?4,
+                encrypted_working_directory = ?5"
         ;
         self.write(move |conn| {
             let mut statement = Statement::prepare(conn, query)?;
             let mut next_index = statement.bind(&item_id, 1)?;
             next_index = statement.bind(&workspace_id, next_index)?;
-            next_index = statement.bind(&working_directory, next_index)?;
-            statement.bind(&working_directory.to_string_lossy().to_string(), next_index)?;
+            statement.bind(&working_directory, next_index)?;
             statement.exec()
         })
         .await
     }
 
+    pub fn get_working_directory(
+        &self,
+        item_id: ItemId,
+        workspace_id: WorkspaceId,
+    ) -> Result<Option<StoredWorkingDirectory>> {
+        Ok(self
+            .get_working_directory_columns(item_id, workspace_id)?
+            .and_then(|(plain, encrypted)| StoredWorkingDirectory::from_columns(plain, encrypted)))
+    }
+
     query! {
-        pub fn get_working_directory(item_id: ItemId, workspace_id: WorkspaceId) -> Result<Option<PathBuf>> {
-            SELECT working_directory
+        fn get_working_directory_columns(item_id: ItemId, workspace_id: WorkspaceId) -> Result<Option<(Option<PathBuf>, Option<Vec<u8>>)>> {
+            SELECT working_directory, encrypted_working_directory
             FROM terminals
             WHERE item_id = ? AND workspace_id = ?
         }
     }
+
+    query! {
+        fn get_all_working_directory_columns() -> Result<Vec<(WorkspaceId, ItemId, Option<PathBuf>, Option<Vec<u8>>)>> {
+            SELECT workspace_id, item_id, working_directory, encrypted_working_directory
+            FROM terminals
+        }
+    }
+
+    query! {
+        fn has_plaintext_working_directories() -> Result<bool> {
+            SELECT EXISTS(SELECT 1 FROM terminals WHERE working_directory IS NOT NULL)
+        }
+    }
+
+    query! {
+        fn has_encrypted_working_directories() -> Result<bool> {
+            SELECT EXISTS(SELECT 1 FROM terminals WHERE encrypted_working_directory IS NOT NULL)
+        }
+    }
+
+    /// Encrypts every plaintext working directory with `key`, or decrypts every encrypted one
+    /// when `encrypt` is false. Rows that can't be decrypted with `key` are left untouched.
+    async fn migrate_working_directories(&self, key: &EncryptionKey, encrypt: bool) -> Result<()> {
+        let mut migrated_rows = Vec::new();
+        for (workspace_id, item_id, plain, encrypted) in self.get_all_working_directory_columns()? {
+            let Some(working_directory) = StoredWorkingDirectory::from_columns(plain, encrypted)
+            else {
+                continue;
+            };
+            let migrated = match (&working_directory, encrypt) {
+                (StoredWorkingDirectory::Plain(path), true) => {
+                    StoredWorkingDirectory::encrypt(path, key)?
+                }
+                (StoredWorkingDirectory::Encrypted(_), false) => {
+                    match working_directory.decrypt(key).log_err() {
+                        Some(path) => StoredWorkingDirectory::Plain(path),
+                        None => continue,
+                    }
+                }
+                _ => continue,
+            };
+            migrated_rows.push((workspace_id, item_id, migrated));
+        }
+        if migrated_rows.is_empty() {
+            return Ok(());
+        }
+
+        log::info!(
+            "{} {} persisted terminal working directories",
+            if encrypt { "Encrypting" } else { "Decrypting" },
+            migrated_rows.len()
+        );
+        let query = "UPDATE terminals
+            SET working_directory = ?1, working_directory_path = ?2, encrypted_working_directory = ?3
+            WHERE workspace_id = ?4 AND item_id = ?5";
+        self.write(move |conn| {
+            // Replacing a value leaves the old one in the page's free space, and in the WAL's
+            // copies of the page, so have SQLite zero freed space and then empty the WAL.
+            conn.exec("PRAGMA secure_delete = ON")?()?;
+            let result = migrated_rows.into_iter().try_for_each(
+                |(workspace_id, item_id, working_directory)| {
+                    let mut statement = Statement::prepare(conn, query)?;
+                    let next_index = statement.bind(&working_directory, 1)?;
+                    let next_index = statement.bind(&workspace_id, next_index)?;
+                    statement.bind(&item_id, next_index)?;
+                    statement.exec()
+                },
+            );
+            conn.exec("PRAGMA secure_delete = OFF")?()?;
+            result?;
+            conn.exec("PRAGMA wal_checkpoint(TRUNCATE)")?()
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encryption_key_round_trip() {
+        let key = EncryptionKey::new(&[7; EncryptionKey::LEN]).unwrap();
+        let path = Path::new("/home/user/secret-project");
+
+        let stored = StoredWorkingDirectory::encrypt(path, &key).unwrap();
+        let StoredWorkingDirectory::Encrypted(encrypted) = &stored else {
+            panic!("expected an encrypted working directory");
+        };
+        assert!(
+            !encrypted
+                .windows(b"secret".len())
+                .any(|window| window == b"secret")
+        );
+        assert_eq!(stored.decrypt(&key).unwrap(), path);
+
+        // Encrypting the same path twice uses a different nonce.
+        let StoredWorkingDirectory::Encrypted(other) =
+            StoredWorkingDirectory::encrypt(path, &key).unwrap()
+        else {
+            panic!("expected an encrypted working directory");
+        };
+        assert_ne!(encrypted, &other);
+
+        let other_key = EncryptionKey::new(&[8; EncryptionKey::LEN]).unwrap();
+        assert!(stored.decrypt(&other_key).is_err());
+
+        let mut tampered = encrypted.clone();
+        if let Some(last) = tampered.last_mut() {
+            *last ^= 1;
+        }
+        assert!(
+            StoredWorkingDirectory::Encrypted(tampered)
+                .decrypt(&key)
+                .is_err()
+        );
+    }
 }
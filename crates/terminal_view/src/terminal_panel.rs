@@ -1,9 +1,13 @@
-use std::{cmp, ops::ControlFlow, path::PathBuf, process::ExitStatus, sync::Arc, time::Duration};
+use std::{
+    cmp, collections::VecDeque, ops::ControlFlow, path::PathBuf, process::ExitStatus, sync::Arc,
+    time::Duration,
+};
 
 use crate::{
     TerminalView, default_working_directory,
     persistence::{
-        SerializedItems, SerializedTerminalPanel, deserialize_terminal_panel, serialize_pane_group,
+        SerializedItems, SerializedPaneLayout, SerializedTerminalPanel, deserialize_terminal_panel,
+        serialize_pane_group, serialize_pane_layout,
     },
 };
 use breadcrumbs::Breadcrumbs;
@@ -31,10 +35,10 @@ use ui::{
 use util::{ResultExt, TryFutureExt};
 use workspace::{
     ActivateNextPane, ActivatePane, ActivatePaneDown, ActivatePaneLeft, ActivatePaneRight,
-    ActivatePaneUp, ActivatePreviousPane, DraggedSelection, DraggedTab, ItemId, MoveItemToPane,
-    MoveItemToPaneInDirection, NewTerminal, Pane, PaneGroup, SplitDirection, SplitDown, SplitLeft,
-    SplitRight, SplitUp, SwapPaneDown, SwapPaneLeft, SwapPaneRight, SwapPaneUp, ToggleZoom,
-    Workspace,
+    ActivatePaneUp, ActivatePreviousPane, DraggedSelection, DraggedTab, ItemId, Member,
+    MoveItemToPane, MoveItemToPaneInDirection, NewTerminal, Pane, PaneAxis, PaneGroup,
+    SplitDirection, SplitDown, SplitLeft, SplitRight, SplitUp, SwapPaneDown, SwapPaneLeft,
+    SwapPaneRight, SwapPaneUp, ToggleZoom, Workspace,
     dock::{DockPosition, Panel, PanelEvent, PanelHandle},
     item::SerializableItem,
     move_active_item, move_item, pane,
@@ -390,7 +394,10 @@ impl TerminalPanel {
                 window.focus(&new_pane.focus_handle(cx));
             }
             pane::Event::Focus => {
-                self.active_pane = pane.clone();
+                if &self.active_pane != pane {
+                    self.active_pane = pane.clone();
+                    self.serialize(cx);
+                }
             }
             pane::Event::ItemPinned | pane::Event::ItemUnpinned => {
                 self.serialize(cx);
@@ -458,6 +465,89 @@ impl TerminalPanel {
         Some(pane)
     }
 
+    /// Splits the panel like `layout`, reusing its existing panes in order. A new
+    /// terminal is opened for each pane the panel didn't have, and the terminals of
+    /// panes that don't fit the layout are moved to its last pane.
+    fn apply_pane_layout(
+        &mut self,
+        layout: &SerializedPaneLayout,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let mut panes = self
+            .center
+            .panes()
+            .into_iter()
+            .cloned()
+            .collect::<VecDeque<_>>();
+        let Some(root) = self.build_pane_layout(layout, &mut panes, window, cx) else {
+            return;
+        };
+        self.center = PaneGroup::with_root(root);
+
+        let Some(last_pane) = self.center.panes().last().map(|pane| (*pane).clone()) else {
+            return;
+        };
+        for pane in panes {
+            let items = pane
+                .read(cx)
+                .items()
+                .map(|item| item.boxed_clone())
+                .collect::<Vec<_>>();
+            for item in items {
+                pane.update(cx, |pane, cx| {
+                    pane.remove_item(item.item_id(), false, false, window, cx)
+                });
+                last_pane.update(cx, |pane, cx| {
+                    pane.add_item(item, false, false, None, window, cx)
+                });
+            }
+        }
+        if !self.center.panes().contains(&&self.active_pane) {
+            self.active_pane = self.center.first_pane();
+        }
+        self.serialize(cx);
+        cx.notify();
+    }
+
+    fn build_pane_layout(
+        &mut self,
+        layout: &SerializedPaneLayout,
+        panes: &mut VecDeque<Entity<Pane>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Option<Member> {
+        match layout {
+            SerializedPaneLayout::Pane => {
+                let pane = match panes.pop_front() {
+                    Some(pane) => pane,
+                    None => self.new_pane_with_cloned_active_terminal(window, cx)?,
+                };
+                Some(Member::Pane(pane))
+            }
+            SerializedPaneLayout::Group {
+                axis,
+                flexes,
+                children,
+            } => {
+                let mut members = Vec::new();
+                for child in children {
+                    members.extend(self.build_pane_layout(child, panes, window, cx));
+                }
+                match members.len() {
+                    0 => None,
+                    1 => members.pop(),
+                    // Mismatched flexes, from panes that couldn't be created, are reset.
+                    _ => Some(Member::Axis(PaneAxis::load(
+                        axis.0,
+                        members,
+                        flexes.clone(),
+                    ))),
+                }
+            }
+        }
+    }
+
     pub fn open_terminal(
         workspace: &mut Workspace,
         action: &workspace::OpenTerminal,
@@ -1452,6 +1542,21 @@ impl Panel for TerminalPanel {
     fn activation_priority(&self) -> u32 {
         1
     }
+
+    fn layout(&self, _cx: &App) -> Option<serde_json::Value> {
+        serde_json::to_value(serialize_pane_layout(&self.center.root)).log_err()
+    }
+
+    fn apply_layout(
+        &mut self,
+        layout: serde_json::Value,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(layout) = serde_json::from_value::<SerializedPaneLayout>(layout).log_err() {
+            self.apply_pane_layout(&layout, window, cx);
+        }
+    }
 }
 
 struct TerminalProvider(Entity<TerminalPanel>);
@@ -14,7 +14,7 @@ use gpui::{
     impl_actions,
 };
 use itertools::Itertools;
-use persistence::TERMINAL_DB;
+use persistence::{StoredWorkingDirectory, TERMINAL_DB};
 use project::{Entry, Metadata, Project, search::SearchQuery, terminals::TerminalKind};
 use schemars::JsonSchema;
 use task::TaskId;
@@ -84,6 +84,7 @@ pub fn init(cx: &mut App) {
     assistant_slash_command::init(cx);
     terminal_panel::init(cx);
     terminal::init(cx);
+    persistence::init(cx);
 
     register_serializable_item::<TerminalView>(cx);
 
@@ -1718,9 +1719,21 @@ impl SerializableItem for TerminalView {
 
         if let Some((cwd, workspace_id)) = terminal.working_directory().zip(self.workspace_id) {
             self.cwd_serialized = true;
+            let encryption_key = TerminalSettings::get_global(cx)
+                .encrypt_persisted_data
+                .then(|| persistence::encryption_key(cx));
             Some(cx.background_spawn(async move {
+                let working_directory = match encryption_key {
+                    Some(key) => {
+                        let key = key
+                            .await
+                            .context("terminal encryption key is unavailable")?;
+                        StoredWorkingDirectory::encrypt(&cwd, &key)?
+                    }
+                    None => StoredWorkingDirectory::Plain(cwd),
+                };
                 TERMINAL_DB
-                    .save_working_directory(item_id, workspace_id, cwd)
+                    .save_working_directory(item_id, workspace_id, working_directory)
                     .await
             }))
         } else {
@@ -1742,12 +1755,21 @@ impl SerializableItem for TerminalView {
     ) -> Task<anyhow::Result<Entity<Self>>> {
         let window_handle = window.window_handle();
         window.spawn(cx, async move |cx| {
+            let from_db = match TERMINAL_DB
+                .get_working_directory(item_id, workspace_id)
+                .log_err()
+                .flatten()
+            {
+                Some(StoredWorkingDirectory::Plain(path)) => Some(path),
+                Some(working_directory @ StoredWorkingDirectory::Encrypted(_)) => {
+                    let key = cx.update(|_window, cx| persistence::encryption_key(cx))?;
+                    key.await
+                        .and_then(|key| working_directory.decrypt(&key).log_err())
+                }
+                None => None,
+            };
             let cwd = cx
                 .update(|_window, cx| {
-                    let from_db = TERMINAL_DB
-                        .get_working_directory(item_id, workspace_id)
-                        .log_err()
-                        .flatten();
                     if from_db
                         .as_ref()
                         .is_some_and(|from_db| !from_db.as_os_str().is_empty())
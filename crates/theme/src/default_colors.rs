@@ -27,6 +27,33 @@ const REMOVED_COLOR: Hsla = Hsla {
     a: 1.0,
 };
 
+// Colors from the Okabe-Ito palette, which remain distinguishable with the
+// common forms of color vision deficiency.
+const ACCESSIBLE_ADDED_COLOR: Hsla = Hsla {
+    h: 202. / 360.,
+    s: 0.77,
+    l: 0.63,
+    a: 1.0,
+};
+const ACCESSIBLE_MODIFIED_COLOR: Hsla = Hsla {
+    h: 41. / 360.,
+    s: 1.0,
+    l: 0.45,
+    a: 1.0,
+};
+const ACCESSIBLE_REMOVED_COLOR: Hsla = Hsla {
+    h: 26. / 360.,
+    s: 1.0,
+    l: 0.42,
+    a: 1.0,
+};
+const ACCESSIBLE_CONFLICT_COLOR: Hsla = Hsla {
+    h: 326. / 360.,
+    s: 0.45,
+    l: 0.64,
+    a: 1.0,
+};
+
 /// The default colors for the theme.
 ///
 /// Themes that do not specify all colors are refined off of these defaults.
@@ -282,6 +309,18 @@ impl ThemeColors {
     }
 }
 
+impl ThemeColors {
+    /// Replaces the version control colors with ones that are distinguishable
+    /// by users with color vision deficiencies.
+    pub fn apply_accessible_version_control_palette(&mut self) {
+        self.version_control_added = ACCESSIBLE_ADDED_COLOR;
+        self.version_control_deleted = ACCESSIBLE_REMOVED_COLOR;
+        self.version_control_modified = ACCESSIBLE_MODIFIED_COLOR;
+        self.version_control_renamed = ACCESSIBLE_MODIFIED_COLOR;
+        self.version_control_conflict = ACCESSIBLE_CONFLICT_COLOR;
+    }
+}
+
 type StaticColorScale = [&'static str; 12];
 
 struct StaticColorScaleSet {
@@ -78,6 +78,17 @@ impl From<String> for UiDensity {
     }
 }
 
+/// The palette used for version control statuses.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum VersionControlPalette {
+    /// The colors defined by the active theme.
+    #[default]
+    Theme,
+    /// Colors that remain distinguishable with common forms of color blindness.
+    Accessible,
+}
+
 impl From<UiDensity> for String {
     fn from(val: UiDensity) -> Self {
         match val {
@@ -131,6 +142,8 @@ pub struct ThemeSettings {
     pub ui_density: UiDensity,
     /// The amount of fading applied to unnecessary code.
     pub unnecessary_code_fade: f32,
+    /// The palette used for version control statuses.
+    pub version_control_palette: VersionControlPalette,
 }
 
 impl ThemeSettings {
@@ -436,6 +449,13 @@ pub struct ThemeSettingsContent {
     #[serde(default)]
     pub unnecessary_code_fade: Option<f32>,
 
+    /// The palette to use for version control statuses in the git panel,
+    /// the editor gutter, and the scrollbar.
+    ///
+    /// Individual colors can be overridden with `experimental.theme_overrides`.
+    #[serde(default)]
+    pub version_control_palette: Option<VersionControlPalette>,
+
     /// EXPERIMENTAL: Overrides for the current theme.
     ///
     /// These values will override the ones on the current theme specified in `theme`.
@@ -658,6 +678,15 @@ impl ThemeSettings {
 
     /// Applies the theme overrides, if there are any, to the current theme.
     pub fn apply_theme_overrides(&mut self) {
+        if self.version_control_palette == VersionControlPalette::Accessible {
+            let mut base_theme = (*self.active_theme).clone();
+            base_theme
+                .styles
+                .colors
+                .apply_accessible_version_control_palette();
+            self.active_theme = Arc::new(base_theme);
+        }
+
         if let Some(theme_overrides) = &self.theme_overrides {
             let mut base_theme = (*self.active_theme).clone();
 
@@ -859,6 +888,7 @@ impl settings::Settings for ThemeSettings {
                 .unwrap_or_else(|| themes.get_icon_theme(DEFAULT_ICON_THEME_NAME).unwrap()),
             ui_density: defaults.ui_density.unwrap_or(UiDensity::Default),
             unnecessary_code_fade: defaults.unnecessary_code_fade.unwrap_or(0.0),
+            version_control_palette: defaults.version_control_palette.unwrap_or_default(),
         };
 
         for value in sources
@@ -914,6 +944,10 @@ impl settings::Settings for ThemeSettings {
                 }
             }
 
+            merge(
+                &mut this.version_control_palette,
+                value.version_control_palette,
+            );
             this.theme_overrides.clone_from(&value.theme_overrides);
             this.apply_theme_overrides();
 
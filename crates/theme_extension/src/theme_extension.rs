@@ -2,7 +2,7 @@ use std::path::PathBuf;
 use std::sync::Arc;
 
 use anyhow::Result;
-use extension::{ExtensionHostProxy, ExtensionThemeProxy};
+use extension::{ExtensionHostProxy, ExtensionIconThemeProxy, ExtensionThemeProxy};
 use fs::Fs;
 use gpui::{App, BackgroundExecutor, SharedString, Task};
 use theme::{ThemeRegistry, ThemeSettings};
@@ -12,12 +12,15 @@ pub fn init(
     theme_registry: Arc<ThemeRegistry>,
     executor: BackgroundExecutor,
 ) {
-    extension_host_proxy.register_theme_proxy(ThemeRegistryProxy {
+    let proxy = ThemeRegistryProxy {
         theme_registry,
         executor,
-    });
+    };
+    extension_host_proxy.register_icon_theme_proxy(proxy.clone());
+    extension_host_proxy.register_theme_proxy(proxy);
 }
 
+#[derive(Clone)]
 struct ThemeRegistryProxy {
     theme_registry: Arc<ThemeRegistry>,
     executor: BackgroundExecutor,
@@ -48,7 +51,9 @@ impl ExtensionThemeProxy for ThemeRegistryProxy {
     fn reload_current_theme(&self, cx: &mut App) {
         ThemeSettings::reload_current_theme(cx)
     }
+}
 
+impl ExtensionIconThemeProxy for ThemeRegistryProxy {
     fn list_icon_theme_names(
         &self,
         icon_theme_path: PathBuf,
@@ -17,3 +17,17 @@ pub fn theme_is_transparent(cx: &mut App) -> bool {
         WindowBackgroundAppearance::Transparent | WindowBackgroundAppearance::Blurred
     )
 }
+
+/// Returns the [WindowBackgroundAppearance] to use for pop-up windows such as
+/// notifications.
+///
+/// Follows the theme, like the main windows do, but falls back to an opaque
+/// background when the user asked the system to reduce transparency or
+/// increase contrast.
+pub fn popup_window_background(cx: &App) -> WindowBackgroundAppearance {
+    if cx.should_reduce_transparency() || cx.should_increase_contrast() {
+        WindowBackgroundAppearance::Opaque
+    } else {
+        cx.theme().styles.window_background_appearance
+    }
+}
@@ -1,4 +1,4 @@
-use gpui::{App, Styled, hsla};
+use gpui::{App, Styled, WindowBackgroundAppearance, hsla};
 
 use crate::ElevationIndex;
 use crate::prelude::*;
@@ -86,6 +86,27 @@ pub trait StyledExt: Styled + Sized {
         elevated_borderless(self, cx, ElevationIndex::ModalSurface)
     }
 
+    /// The surface of a pop-up window, such as a notification, whose window was
+    /// opened with [`popup_window_background`](crate::popup_window_background).
+    ///
+    /// Behaves like [`elevation_3`](Self::elevation_3) on transparent windows.
+    /// Opaque windows can't show rounded corners or a shadow, so the surface
+    /// fills the window instead.
+    fn popup_window_surface(self, cx: &App) -> Self {
+        let border_color = if cx.should_increase_contrast() {
+            cx.theme().colors().border
+        } else {
+            cx.theme().colors().border_variant
+        };
+        match crate::popup_window_background(cx) {
+            WindowBackgroundAppearance::Opaque => self
+                .bg(cx.theme().colors().elevated_surface_background)
+                .border_1()
+                .border_color(border_color),
+            _ => elevated(self, cx, ElevationIndex::ModalSurface).border_color(border_color),
+        }
+    }
+
     /// The theme's primary border color.
     fn border_primary(self, cx: &mut App) -> Self {
         self.border_color(cx.theme().colors().border)
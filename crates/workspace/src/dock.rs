@@ -12,7 +12,7 @@ use gpui::{
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use settings::SettingsStore;
-use std::sync::Arc;
+use std::{collections::BTreeMap, sync::Arc};
 use ui::{ContextMenu, Divider, DividerColor, IconButton, Tooltip, h_flex};
 use ui::{prelude::*, right_click_menu};
 
@@ -58,6 +58,19 @@ pub trait Panel: Focusable + EventEmitter<PanelEvent> + Render + Sized {
     fn enabled(&self, _cx: &App) -> bool {
         true
     }
+    /// The arrangement of the panel's contents to store in a layout profile, such as
+    /// how the panel is split. Returns `None` for panels without such an arrangement.
+    fn layout(&self, _cx: &App) -> Option<serde_json::Value> {
+        None
+    }
+    /// Re-arranges the panel's contents according to a value returned by [`Panel::layout`].
+    fn apply_layout(
+        &mut self,
+        _layout: serde_json::Value,
+        _window: &mut Window,
+        _cx: &mut Context<Self>,
+    ) {
+    }
 }
 
 pub trait PanelHandle: Send + Sync {
@@ -81,6 +94,8 @@ pub trait PanelHandle: Send + Sync {
     fn to_any(&self) -> AnyView;
     fn activation_priority(&self, cx: &App) -> u32;
     fn enabled(&self, cx: &App) -> bool;
+    fn layout(&self, cx: &App) -> Option<serde_json::Value>;
+    fn apply_layout(&self, layout: serde_json::Value, window: &mut Window, cx: &mut App);
     fn move_to_next_position(&self, window: &mut Window, cx: &mut App) {
         let current_position = self.position(window, cx);
         let next_position = [
@@ -181,6 +196,14 @@ where
     fn enabled(&self, cx: &App) -> bool {
         self.read(cx).enabled(cx)
     }
+
+    fn layout(&self, cx: &App) -> Option<serde_json::Value> {
+        self.read(cx).layout(cx)
+    }
+
+    fn apply_layout(&self, layout: serde_json::Value, window: &mut Window, cx: &mut App) {
+        self.update(cx, |this, cx| this.apply_layout(layout, window, cx))
+    }
 }
 
 impl From<&dyn PanelHandle> for AnyView {
@@ -722,6 +745,57 @@ impl Dock {
             }
         }
     }
+
+    pub(crate) fn panel_sizes(&self, window: &Window, cx: &App) -> Vec<(String, f32)> {
+        self.panel_entries
+            .iter()
+            .map(|entry| {
+                (
+                    entry.panel.persistent_name().to_string(),
+                    entry.panel.size(window, cx).0,
+                )
+            })
+            .collect()
+    }
+
+    pub(crate) fn restore_panel_sizes(
+        &mut self,
+        sizes: &BTreeMap<String, f32>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        for entry in &mut self.panel_entries {
+            if let Some(size) = sizes.get(entry.panel.persistent_name()) {
+                let size = px(*size).max(RESIZE_HANDLE_SIZE).round();
+                entry.panel.set_size(Some(size), window, cx);
+            }
+        }
+        cx.notify();
+    }
+
+    pub(crate) fn panel_layouts(&self, cx: &App) -> Vec<(String, serde_json::Value)> {
+        self.panel_entries
+            .iter()
+            .filter_map(|entry| {
+                let layout = entry.panel.layout(cx)?;
+                Some((entry.panel.persistent_name().to_string(), layout))
+            })
+            .collect()
+    }
+
+    pub(crate) fn restore_panel_layouts(
+        &mut self,
+        layouts: &BTreeMap<String, serde_json::Value>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        for entry in &self.panel_entries {
+            if let Some(layout) = layouts.get(entry.panel.persistent_name()) {
+                entry.panel.apply_layout(layout.clone(), window, cx);
+            }
+        }
+        cx.notify();
+    }
 }
 
 impl Render for Dock {
@@ -954,6 +1028,7 @@ pub mod test {
         pub active: bool,
         pub focus_handle: FocusHandle,
         pub size: Pixels,
+        pub layout: Option<serde_json::Value>,
     }
     actions!(test, [ToggleTestPanel]);
 
@@ -967,6 +1042,7 @@ pub mod test {
                 active: false,
                 focus_handle: cx.focus_handle(),
                 size: px(300.),
+                layout: None,
             }
         }
     }
@@ -1030,6 +1106,19 @@ pub mod test {
         fn activation_priority(&self) -> u32 {
             100
         }
+
+        fn layout(&self, _cx: &App) -> Option<serde_json::Value> {
+            self.layout.clone()
+        }
+
+        fn apply_layout(
+            &mut self,
+            layout: serde_json::Value,
+            _window: &mut Window,
+            _cx: &mut Context<Self>,
+        ) {
+            self.layout = Some(layout);
+        }
     }
 
     impl Focusable for TestPanel {
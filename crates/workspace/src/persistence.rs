@@ -35,7 +35,9 @@ use model::{
     SerializedSshProject, SerializedWorkspace,
 };
 
-use self::model::{DockStructure, LocalPathsOrder, SerializedWorkspaceLocation};
+use self::model::{
+    DockStructure, LocalPathsOrder, SerializedLayoutProfile, SerializedWorkspaceLocation,
+};
 
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub(crate) struct SerializedAxis(pub(crate) gpui::Axis);
@@ -542,6 +544,22 @@ define_connection! {
         ALTER TABLE breakpoints ADD COLUMN condition TEXT;
         ALTER TABLE breakpoints ADD COLUMN hit_condition TEXT;
     ),
+    sql!(
+        CREATE TABLE layout_profiles (
+            name TEXT PRIMARY KEY NOT NULL,
+            left_dock_visible INTEGER,
+            left_dock_active_panel TEXT,
+            left_dock_zoom INTEGER,
+            right_dock_visible INTEGER,
+            right_dock_active_panel TEXT,
+            right_dock_zoom INTEGER,
+            bottom_dock_visible INTEGER,
+            bottom_dock_active_panel TEXT,
+            bottom_dock_zoom INTEGER,
+            panel_sizes TEXT,
+            panel_layouts TEXT
+        ) STRICT;
+    ),
     ];
 }
 
@@ -979,6 +997,52 @@ impl WorkspaceDb {
         }
     }
 
+    query! {
+        pub async fn save_layout_profile(profile: SerializedLayoutProfile) -> Result<()> {
+            INSERT OR REPLACE INTO layout_profiles(
+                name,
+                left_dock_visible,
+                left_dock_active_panel,
+                left_dock_zoom,
+                right_dock_visible,
+                right_dock_active_panel,
+                right_dock_zoom,
+                bottom_dock_visible,
+                bottom_dock_active_panel,
+                bottom_dock_zoom,
+                panel_sizes,
+                panel_layouts
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
+        }
+    }
+
+    query! {
+        pub fn layout_profiles() -> Result<Vec<SerializedLayoutProfile>> {
+            SELECT
+                name,
+                left_dock_visible,
+                left_dock_active_panel,
+                left_dock_zoom,
+                right_dock_visible,
+                right_dock_active_panel,
+                right_dock_zoom,
+                bottom_dock_visible,
+                bottom_dock_active_panel,
+                bottom_dock_zoom,
+                panel_sizes,
+                panel_layouts
+            FROM layout_profiles
+            ORDER BY name
+        }
+    }
+
+    query! {
+        pub async fn delete_layout_profile(name: String) -> Result<()> {
+            DELETE FROM layout_profiles
+            WHERE name = ?
+        }
+    }
+
     query! {
         fn ssh_projects() -> Result<Vec<SerializedSshProject>> {
             SELECT id, host, port, paths, user
@@ -1460,7 +1524,7 @@ mod tests {
     use std::time::Duration;
 
     use super::*;
-    use crate::persistence::model::SerializedWorkspace;
+    use crate::persistence::model::{DockData, SerializedWorkspace};
     use crate::persistence::model::{SerializedItem, SerializedPane, SerializedPaneGroup};
     use gpui;
 
@@ -1736,6 +1800,58 @@ mod tests {
         assert!(empty_breakpoints.is_none());
     }
 
+    #[gpui::test]
+    async fn test_layout_profiles() {
+        zlog::init_test();
+
+        let db = WorkspaceDb::open_test_db("test_layout_profiles").await;
+
+        let review = SerializedLayoutProfile {
+            name: "Review".to_string(),
+            docks: DockStructure {
+                left: DockData {
+                    visible: true,
+                    active_panel: Some("GitPanel".to_string()),
+                    zoom: false,
+                },
+                right: DockData::default(),
+                bottom: DockData {
+                    visible: true,
+                    active_panel: Some("TerminalPanel".to_string()),
+                    zoom: true,
+                },
+            },
+            panel_sizes: BTreeMap::from_iter([
+                ("GitPanel".to_string(), 420.),
+                ("TerminalPanel".to_string(), 300.),
+            ]),
+            panel_layouts: BTreeMap::from_iter([(
+                "TerminalPanel".to_string(),
+                serde_json::json!({ "Group": { "axis": "horizontal", "children": ["Pane", "Pane"] } }),
+            )]),
+        };
+        let focus = SerializedLayoutProfile {
+            name: "Focus".to_string(),
+            docks: DockStructure::default(),
+            panel_sizes: BTreeMap::default(),
+            panel_layouts: BTreeMap::default(),
+        };
+
+        db.save_layout_profile(review.clone()).await.unwrap();
+        db.save_layout_profile(focus.clone()).await.unwrap();
+        assert_eq!(db.layout_profiles().unwrap(), vec![focus, review.clone()]);
+
+        let mut updated_review = review;
+        updated_review
+            .panel_sizes
+            .insert("GitPanel".to_string(), 360.);
+        db.save_layout_profile(updated_review.clone())
+            .await
+            .unwrap();
+        db.delete_layout_profile("Focus".to_string()).await.unwrap();
+        assert_eq!(db.layout_profiles().unwrap(), vec![updated_review]);
+    }
+
     #[gpui::test]
     async fn test_next_id_stability() {
         zlog::init_test();
@@ -330,6 +330,51 @@ impl Bind for DockData {
     }
 }
 
+/// A named snapshot of a workspace's dock layout that can be re-applied to other projects.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct SerializedLayoutProfile {
+    pub name: String,
+    pub(crate) docks: DockStructure,
+    /// Panel sizes in pixels, keyed by the panel's persistent name.
+    pub(crate) panel_sizes: BTreeMap<String, f32>,
+    /// How panels arrange their contents, such as the terminal panel's splits, keyed
+    /// by the panel's persistent name.
+    pub(crate) panel_layouts: BTreeMap<String, serde_json::Value>,
+}
+
+impl Column for SerializedLayoutProfile {
+    fn column(statement: &mut Statement, start_index: i32) -> Result<(Self, i32)> {
+        let (name, next_index) = String::column(statement, start_index)?;
+        let (docks, next_index) = DockStructure::column(statement, next_index)?;
+        let (panel_sizes, next_index) = Option::<String>::column(statement, next_index)?;
+        let panel_sizes = panel_sizes
+            .and_then(|panel_sizes| serde_json::from_str(&panel_sizes).log_err())
+            .unwrap_or_default();
+        let (panel_layouts, next_index) = Option::<String>::column(statement, next_index)?;
+        let panel_layouts = panel_layouts
+            .and_then(|panel_layouts| serde_json::from_str(&panel_layouts).log_err())
+            .unwrap_or_default();
+        Ok((
+            SerializedLayoutProfile {
+                name,
+                docks,
+                panel_sizes,
+                panel_layouts,
+            },
+            next_index,
+        ))
+    }
+}
+
+impl Bind for SerializedLayoutProfile {
+    fn bind(&self, statement: &Statement, start_index: i32) -> Result<i32> {
+        let next_index = statement.bind(&self.name, start_index)?;
+        let next_index = statement.bind(&self.docks, next_index)?;
+        let next_index = statement.bind(&serde_json::to_string(&self.panel_sizes)?, next_index)?;
+        statement.bind(&serde_json::to_string(&self.panel_layouts)?, next_index)
+    }
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub(crate) enum SerializedPaneGroup {
     Group {
@@ -63,7 +63,7 @@ use persistence::{
 };
 pub use persistence::{
     DB as WORKSPACE_DB, WorkspaceDb, delete_unloaded_items,
-    model::{ItemId, LocalPaths, SerializedWorkspaceLocation},
+    model::{ItemId, LocalPaths, SerializedLayoutProfile, SerializedWorkspaceLocation},
 };
 use postage::stream::Stream;
 use project::{
@@ -87,7 +87,7 @@ use std::{
     borrow::Cow,
     cell::RefCell,
     cmp,
-    collections::hash_map::DefaultHasher,
+    collections::{BTreeMap, hash_map::DefaultHasher},
     env,
     hash::{Hash, Hasher},
     path::{Path, PathBuf},
@@ -4990,60 +4990,6 @@ impl Workspace {
             }
         }
 
-        fn build_serialized_docks(
-            this: &Workspace,
-            window: &mut Window,
-            cx: &mut App,
-        ) -> DockStructure {
-            let left_dock = this.left_dock.read(cx);
-            let left_visible = left_dock.is_open();
-            let left_active_panel = left_dock
-                .active_panel()
-                .map(|panel| panel.persistent_name().to_string());
-            let left_dock_zoom = left_dock
-                .active_panel()
-                .map(|panel| panel.is_zoomed(window, cx))
-                .unwrap_or(false);
-
-            let right_dock = this.right_dock.read(cx);
-            let right_visible = right_dock.is_open();
-            let right_active_panel = right_dock
-                .active_panel()
-                .map(|panel| panel.persistent_name().to_string());
-            let right_dock_zoom = right_dock
-                .active_panel()
-                .map(|panel| panel.is_zoomed(window, cx))
-                .unwrap_or(false);
-
-            let bottom_dock = this.bottom_dock.read(cx);
-            let bottom_visible = bottom_dock.is_open();
-            let bottom_active_panel = bottom_dock
-                .active_panel()
-                .map(|panel| panel.persistent_name().to_string());
-            let bottom_dock_zoom = bottom_dock
-                .active_panel()
-                .map(|panel| panel.is_zoomed(window, cx))
-                .unwrap_or(false);
-
-            DockStructure {
-                left: DockData {
-                    visible: left_visible,
-                    active_panel: left_active_panel,
-                    zoom: left_dock_zoom,
-                },
-                right: DockData {
-                    visible: right_visible,
-                    active_panel: right_active_panel,
-                    zoom: right_dock_zoom,
-                },
-                bottom: DockData {
-                    visible: bottom_visible,
-                    active_panel: bottom_active_panel,
-                    zoom: bottom_dock_zoom,
-                },
-            }
-        }
-
         if let Some(location) = self.serialize_workspace_location(cx) {
             let breakpoints = self.project.update(cx, |project, cx| {
                 project
@@ -5075,6 +5021,58 @@ impl Workspace {
         Task::ready(())
     }
 
+    /// Saves the current dock layout and panel sizes as a named profile,
+    /// replacing any existing profile with the same name.
+    pub fn save_layout_profile(
+        &self,
+        name: String,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Task<Result<()>> {
+        let mut panel_sizes = BTreeMap::new();
+        let mut panel_layouts = BTreeMap::new();
+        for dock in self.all_docks() {
+            let dock = dock.read(cx);
+            panel_sizes.extend(dock.panel_sizes(window, cx));
+            panel_layouts.extend(dock.panel_layouts(cx));
+        }
+        let profile = SerializedLayoutProfile {
+            name,
+            docks: build_serialized_docks(self, window, cx),
+            panel_sizes,
+            panel_layouts,
+        };
+        cx.background_spawn(async move { persistence::DB.save_layout_profile(profile).await })
+    }
+
+    pub fn apply_layout_profile(
+        &mut self,
+        profile: SerializedLayoutProfile,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let docks = [
+            (&self.left_dock, profile.docks.left),
+            (&self.right_dock, profile.docks.right),
+            (&self.bottom_dock, profile.docks.bottom),
+        ];
+        for (dock, dock_data) in docks {
+            dock.update(cx, |dock, cx| {
+                if !dock_data.zoom {
+                    if let Some(panel) = dock.active_panel() {
+                        panel.set_zoomed(false, window, cx);
+                    }
+                }
+                dock.serialized_dock = Some(dock_data);
+                dock.restore_state(window, cx);
+                dock.restore_panel_sizes(&profile.panel_sizes, window, cx);
+                dock.restore_panel_layouts(&profile.panel_layouts, window, cx);
+            });
+        }
+        cx.notify();
+        self.serialize_workspace(window, cx);
+    }
+
     fn serialize_workspace_location(&self, cx: &App) -> Option<SerializedWorkspaceLocation> {
         if let Some(ssh_project) = &self.serialized_ssh_project {
             Some(SerializedWorkspaceLocation::Ssh(ssh_project.clone()))
@@ -5585,6 +5583,56 @@ impl Workspace {
     }
 }
 
+fn build_serialized_docks(this: &Workspace, window: &mut Window, cx: &mut App) -> DockStructure {
+    let left_dock = this.left_dock.read(cx);
+    let left_visible = left_dock.is_open();
+    let left_active_panel = left_dock
+        .active_panel()
+        .map(|panel| panel.persistent_name().to_string());
+    let left_dock_zoom = left_dock
+        .active_panel()
+        .map(|panel| panel.is_zoomed(window, cx))
+        .unwrap_or(false);
+
+    let right_dock = this.right_dock.read(cx);
+    let right_visible = right_dock.is_open();
+    let right_active_panel = right_dock
+        .active_panel()
+        .map(|panel| panel.persistent_name().to_string());
+    let right_dock_zoom = right_dock
+        .active_panel()
+        .map(|panel| panel.is_zoomed(window, cx))
+        .unwrap_or(false);
+
+    let bottom_dock = this.bottom_dock.read(cx);
+    let bottom_visible = bottom_dock.is_open();
+    let bottom_active_panel = bottom_dock
+        .active_panel()
+        .map(|panel| panel.persistent_name().to_string());
+    let bottom_dock_zoom = bottom_dock
+        .active_panel()
+        .map(|panel| panel.is_zoomed(window, cx))
+        .unwrap_or(false);
+
+    DockStructure {
+        left: DockData {
+            visible: left_visible,
+            active_panel: left_active_panel,
+            zoom: left_dock_zoom,
+        },
+        right: DockData {
+            visible: right_visible,
+            active_panel: right_active_panel,
+            zoom: right_dock_zoom,
+        },
+        bottom: DockData {
+            visible: bottom_visible,
+            active_panel: bottom_active_panel,
+            zoom: bottom_dock_zoom,
+        },
+    }
+}
+
 fn leader_border_for_pane(
     follower_states: &HashMap<CollaboratorId, FollowerState>,
     pane: &Entity<Pane>,
@@ -8799,6 +8847,51 @@ mod tests {
         }
     }
 
+    #[gpui::test]
+    async fn test_apply_layout_profile(cx: &mut gpui::TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project, window, cx));
+
+        let panel = workspace.update_in(cx, |workspace, window, cx| {
+            let panel = cx.new(|cx| TestPanel::new(DockPosition::Left, cx));
+            workspace.add_panel(panel.clone(), window, cx);
+            panel
+        });
+
+        let profile = SerializedLayoutProfile {
+            name: "Review".to_string(),
+            docks: DockStructure {
+                left: DockData {
+                    visible: true,
+                    active_panel: Some("TestPanel".to_string()),
+                    zoom: false,
+                },
+                ..DockStructure::default()
+            },
+            panel_sizes: BTreeMap::from_iter([("TestPanel".to_string(), 420.)]),
+            panel_layouts: BTreeMap::from_iter([("TestPanel".to_string(), json!({ "splits": 2 }))]),
+        };
+        workspace.update_in(cx, |workspace, window, cx| {
+            workspace.apply_layout_profile(profile, window, cx);
+
+            let left_dock = workspace.left_dock().read(cx);
+            assert_eq!(
+                left_dock.visible_panel().unwrap().panel_id(),
+                panel.panel_id()
+            );
+            assert_eq!(panel.read(cx).size, px(420.));
+            assert_eq!(panel.read(cx).layout, Some(json!({ "splits": 2 })));
+            assert_eq!(
+                left_dock.panel_layouts(cx),
+                vec![("TestPanel".to_string(), json!({ "splits": 2 }))]
+            );
+        });
+    }
+
     #[gpui::test]
     async fn test_panels(cx: &mut gpui::TestAppContext) {
         init_test(cx);
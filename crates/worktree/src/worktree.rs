@@ -19,7 +19,7 @@ use futures::{
 };
 use fuzzy::CharBag;
 use git::{
-    COMMIT_MESSAGE, DOT_GIT, FSMONITOR_DAEMON, GITIGNORE, INDEX_LOCK, LFS_DIR,
+    COMMIT_MESSAGE, DOT_GIT, FSMONITOR_DAEMON, GITIGNORE, INDEX_LOCK, LFS_DIR, REPO_EXCLUDE,
     repository::RepoPath, status::GitSummary,
 };
 use gpui::{
@@ -359,6 +359,9 @@ pub struct LocalSnapshot {
     /// All of the gitignore files in the worktree, indexed by their relative path.
     /// The boolean indicates whether the gitignore needs to be updated.
     ignores_by_parent_abs_path: HashMap<Arc<Path>, (Arc<Gitignore>, bool)>,
+    /// The rules from each repository's `.git/info/exclude`, indexed by the absolute path
+    /// of its working directory. The boolean indicates whether the rules need to be updated.
+    repo_excludes_by_work_dir_abs_path: HashMap<Arc<Path>, (Arc<Gitignore>, bool)>,
     /// All of the git repositories in the worktree, indexed by the project entry
     /// id of their parent directory.
     git_repositories: TreeMap<ProjectEntryId, LocalRepositoryEntry>,
@@ -510,6 +513,7 @@ impl Worktree {
         cx.new(move |cx: &mut Context<Worktree>| {
             let mut snapshot = LocalSnapshot {
                 ignores_by_parent_abs_path: Default::default(),
+                repo_excludes_by_work_dir_abs_path: Default::default(),
                 git_repositories: Default::default(),
                 snapshot: Snapshot::new(
                     cx.entity_id().as_u64(),
@@ -2801,6 +2805,10 @@ impl LocalSnapshot {
                 } else {
                     new_ignores.push((ancestor, None));
                 }
+                // Pushed after the `.gitignore`, so that the `.gitignore` takes precedence.
+                if let Some((exclude, _)) = self.repo_excludes_by_work_dir_abs_path.get(ancestor) {
+                    new_ignores.push((ancestor, Some(exclude.clone())));
+                }
             }
             if ancestor.join(*DOT_GIT).exists() {
                 break;
@@ -3184,6 +3192,24 @@ impl BackgroundScannerState {
             watcher.add(&repository_dir_abs_path).log_err();
         }
 
+        match smol::block_on(build_repo_exclude(
+            &common_dir_abs_path,
+            &work_directory_abs_path,
+            fs,
+        )) {
+            Ok(exclude) => {
+                self.snapshot.repo_excludes_by_work_dir_abs_path.insert(
+                    work_directory_abs_path.as_path().into(),
+                    (Arc::new(exclude), false),
+                );
+            }
+            Err(error) => {
+                log::error!(
+                    "error loading git excludes for {work_directory_abs_path:?} - {error:?}"
+                );
+            }
+        }
+
         let work_directory_id = work_dir_entry.id;
 
         let local_repository = LocalRepositoryEntry {
@@ -3231,6 +3257,25 @@ async fn build_gitignore(abs_path: &Path, fs: &dyn Fs) -> Result<Gitignore> {
     Ok(builder.build()?)
 }
 
+/// Builds the ignore rules in a repository's `.git/info/exclude`, which apply
+/// relative to its working directory.
+async fn build_repo_exclude(
+    common_dir_abs_path: &Path,
+    work_directory_abs_path: &Path,
+    fs: &dyn Fs,
+) -> Result<Gitignore> {
+    let exclude_abs_path = common_dir_abs_path.join(*REPO_EXCLUDE);
+    if !fs.is_file(&exclude_abs_path).await {
+        return Ok(Gitignore::empty());
+    }
+    let contents = fs.load(&exclude_abs_path).await?;
+    let mut builder = GitignoreBuilder::new(work_directory_abs_path);
+    for line in contents.lines() {
+        builder.add_line(Some(exclude_abs_path.clone()), line)?;
+    }
+    Ok(builder.build()?)
+}
+
 impl Deref for Worktree {
     type Target = Snapshot;
 
@@ -4023,6 +4068,7 @@ impl BackgroundScanner {
 
         let mut relative_paths = Vec::with_capacity(abs_paths.len());
         let mut dot_git_abs_paths = Vec::new();
+        let mut changed_exclude_dot_git_abs_paths = Vec::new();
         abs_paths.sort_unstable();
         abs_paths.dedup_by(|a, b| a.starts_with(b));
         abs_paths.retain(|abs_path| {
@@ -4048,6 +4094,11 @@ impl BackgroundScanner {
                     }
 
                     is_git_related = true;
+                    if REPO_EXCLUDE.starts_with(&path_in_git_dir)
+                        && !changed_exclude_dot_git_abs_paths.contains(&dot_git_abs_path)
+                    {
+                        changed_exclude_dot_git_abs_paths.push(dot_git_abs_path.clone());
+                    }
                     if !dot_git_abs_paths.contains(&dot_git_abs_path) {
                         dot_git_abs_paths.push(dot_git_abs_path);
                     }
@@ -4116,6 +4167,10 @@ impl BackgroundScanner {
         )
         .await;
 
+        if !changed_exclude_dot_git_abs_paths.is_empty() {
+            self.reload_repo_excludes(&changed_exclude_dot_git_abs_paths)
+                .await;
+        }
         self.update_ignore_statuses(scan_job_tx).await;
         self.scan_dirs(false, scan_job_rx).await;
 
@@ -4308,6 +4363,13 @@ impl BackgroundScanner {
                     self.fs.as_ref(),
                     self.watcher.as_ref(),
                 );
+                if let Some((exclude, _)) = state
+                    .snapshot
+                    .repo_excludes_by_work_dir_abs_path
+                    .get(&job.abs_path)
+                {
+                    ignore_stack = ignore_stack.append(job.abs_path.clone(), exclude.clone());
+                }
             } else if child_name == *GITIGNORE {
                 match build_gitignore(&child_abs_path, self.fs.as_ref()).await {
                     Ok(ignore) => {
@@ -4604,6 +4666,50 @@ impl BackgroundScanner {
         Some(())
     }
 
+    async fn reload_repo_excludes(&self, common_dir_abs_paths: &[PathBuf]) {
+        let repositories = self
+            .state
+            .lock()
+            .snapshot
+            .git_repositories
+            .iter()
+            .filter(|(_, repo)| {
+                common_dir_abs_paths
+                    .iter()
+                    .any(|path| path.as_path() == repo.common_dir_abs_path.as_ref())
+            })
+            .map(|(_, repo)| {
+                (
+                    repo.work_directory_abs_path.clone(),
+                    repo.common_dir_abs_path.clone(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        for (work_directory_abs_path, common_dir_abs_path) in repositories {
+            match build_repo_exclude(
+                &common_dir_abs_path,
+                &work_directory_abs_path,
+                self.fs.as_ref(),
+            )
+            .await
+            {
+                Ok(exclude) => {
+                    self.state
+                        .lock()
+                        .snapshot
+                        .repo_excludes_by_work_dir_abs_path
+                        .insert(work_directory_abs_path, (Arc::new(exclude), true));
+                }
+                Err(error) => {
+                    log::error!(
+                        "error loading git excludes for {work_directory_abs_path:?} - {error:?}"
+                    );
+                }
+            }
+        }
+    }
+
     async fn update_ignore_statuses(&self, scan_job_tx: Sender<ScanJob>) {
         let mut ignores_to_update = Vec::new();
         let (ignore_queue_tx, ignore_queue_rx) = channel::unbounded();
@@ -4629,6 +4735,22 @@ impl BackgroundScanner {
                     }
                     true
                 });
+            for (work_dir_abs_path, (_, needs_update)) in
+                snapshot.repo_excludes_by_work_dir_abs_path.iter_mut()
+            {
+                if !mem::take(needs_update) {
+                    continue;
+                }
+                match work_dir_abs_path.strip_prefix(abs_path.as_path()) {
+                    Ok(work_dir_path) => {
+                        if snapshot.snapshot.entry_for_path(work_dir_path).is_some() {
+                            ignores_to_update.push(work_dir_abs_path.clone());
+                        }
+                    }
+                    // The repository contains the whole worktree.
+                    Err(_) => ignores_to_update.push(abs_path.as_path().into()),
+                }
+            }
 
             ignores_to_update.sort_unstable();
             let mut ignores_to_update = ignores_to_update.into_iter().peekable();
@@ -4687,6 +4809,12 @@ impl BackgroundScanner {
         log::trace!("update ignore status {:?}", job.abs_path);
 
         let mut ignore_stack = job.ignore_stack;
+        if let Some((exclude, _)) = snapshot
+            .repo_excludes_by_work_dir_abs_path
+            .get(&job.abs_path)
+        {
+            ignore_stack = ignore_stack.append(job.abs_path.clone(), exclude.clone());
+        }
         if let Some((ignore, _)) = snapshot.ignores_by_parent_abs_path.get(&job.abs_path) {
             ignore_stack = ignore_stack.append(job.abs_path.clone(), ignore.clone());
         }
@@ -4819,6 +4947,14 @@ impl BackgroundScanner {
         snapshot
             .git_repositories
             .retain(|work_directory_id, _| ids_to_preserve.contains(work_directory_id));
+        let work_dir_abs_paths = snapshot
+            .git_repositories
+            .iter()
+            .map(|(_, repo)| repo.work_directory_abs_path.clone())
+            .collect::<HashSet<_>>();
+        snapshot
+            .repo_excludes_by_work_dir_abs_path
+            .retain(|work_dir_abs_path, _| work_dir_abs_paths.contains(work_dir_abs_path));
     }
 
     async fn progress_timer(&self, running: bool) {
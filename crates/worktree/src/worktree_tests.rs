@@ -680,6 +680,87 @@ async fn test_dirs_no_longer_ignored(cx: &mut TestAppContext) {
     assert_eq!(read_dir_count_3 - read_dir_count_2, 2);
 }
 
+#[gpui::test]
+async fn test_repo_excludes(cx: &mut TestAppContext) {
+    init_test(cx);
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree(
+        "/root",
+        json!({
+            ".git": {
+                "info": {
+                    "exclude": "*.log\n",
+                },
+            },
+            ".gitignore": "!keep.log\n",
+            "build.log": "",
+            "keep.log": "",
+            "notes.txt": "",
+            "src": {
+                "debug.log": "",
+            },
+        }),
+    )
+    .await;
+
+    let tree = Worktree::local(
+        Path::new("/root"),
+        true,
+        fs.clone(),
+        Default::default(),
+        &mut cx.to_async(),
+    )
+    .await
+    .unwrap();
+    cx.read(|cx| tree.read(cx).as_local().unwrap().scan_complete())
+        .await;
+    cx.executor().run_until_parked();
+
+    // The .gitignore takes precedence over the repository's excludes.
+    tree.read_with(cx, |tree, _| {
+        assert_eq!(
+            tree.entries(true, 0)
+                .map(|e| (e.path.as_ref(), e.is_ignored))
+                .collect::<Vec<_>>(),
+            &[
+                (Path::new(""), false),
+                (Path::new(".gitignore"), false),
+                (Path::new("build.log"), true),
+                (Path::new("keep.log"), false),
+                (Path::new("notes.txt"), false),
+                (Path::new("src"), false),
+                (Path::new("src/debug.log"), true),
+            ]
+        );
+    });
+
+    fs.save(
+        "/root/.git/info/exclude".as_ref(),
+        &"notes.txt\n".into(),
+        Default::default(),
+    )
+    .await
+    .unwrap();
+    cx.executor().run_until_parked();
+
+    tree.read_with(cx, |tree, _| {
+        assert_eq!(
+            tree.entries(true, 0)
+                .map(|e| (e.path.as_ref(), e.is_ignored))
+                .collect::<Vec<_>>(),
+            &[
+                (Path::new(""), false),
+                (Path::new(".gitignore"), false),
+                (Path::new("build.log"), false),
+                (Path::new("keep.log"), false),
+                (Path::new("notes.txt"), true),
+                (Path::new("src"), false),
+                (Path::new("src/debug.log"), false),
+            ]
+        );
+    });
+}
+
 #[gpui::test]
 async fn test_write_file(cx: &mut TestAppContext) {
     init_test(cx);
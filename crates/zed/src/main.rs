@@ -14,6 +14,7 @@ use extension_host::ExtensionStore;
 use fs::{Fs, RealFs};
 use futures::{StreamExt, channel::oneshot, future};
 use git::GitHostingProviderRegistry;
+use git_ui::commit_view::CommitView;
 use gpui::{App, AppContext as _, Application, AsyncApp, UpdateGlobal as _};
 
 use gpui_tokio::Tokio;
@@ -44,7 +45,9 @@ use theme::{
 use util::{ConnectionResult, ResultExt, TryFutureExt, maybe};
 use uuid::Uuid;
 use welcome::{BaseKeymap, FIRST_OPEN, show_welcome_view};
-use workspace::{AppState, SerializedWorkspaceLocation, WorkspaceSettings, WorkspaceStore};
+use workspace::{
+    AppState, SerializedWorkspaceLocation, Workspace, WorkspaceSettings, WorkspaceStore,
+};
 use zed::{
     OpenListener, OpenRequest, app_menus, build_window_options, derive_paths_with_position,
     handle_cli_connection, handle_keymap_file_changes, handle_settings_changed,
@@ -396,6 +399,8 @@ Error: Running Zed as root or via sudo is unsupported.
                 .expect("could not start HTTP client")
         };
         cx.set_http_client(Arc::new(http));
+        cx.on_app_quit(|_| async { ReqwestClient::shutdown_shared_runtime() })
+            .detach();
 
         <dyn Fs>::set_global(fs.clone(), cx);
 
@@ -603,7 +608,11 @@ Error: Running Zed as root or via sudo is unsupported.
             let client = app_state.client.clone();
             move |cx| {
                 for &mut window in cx.windows().iter_mut() {
-                    let background_appearance = cx.theme().window_background_appearance();
+                    let background_appearance = if window.downcast::<Workspace>().is_some() {
+                        cx.theme().window_background_appearance()
+                    } else {
+                        ui::popup_window_background(cx)
+                    };
                     window
                         .update(cx, |_, window, _| {
                             window.set_background_appearance(background_appearance)
@@ -762,7 +771,28 @@ fn handle_open_request(request: OpenRequest, app_state: Arc<AppState>, cx: &mut
         }));
     }
 
-    if !request.open_channel_notes.is_empty() || request.join_channel.is_some() {
+    if !request.open_commits.is_empty() {
+        cx.spawn(async move |mut cx| {
+            let result = maybe!(async {
+                if let Some(task) = task {
+                    task.await?;
+                }
+                let workspace_window =
+                    workspace::get_any_active_workspace(app_state, cx.clone()).await?;
+                workspace_window.update(cx, |workspace, window, cx| {
+                    for sha in request.open_commits {
+                        CommitView::open_sha(sha, workspace, window, cx)?;
+                    }
+                    anyhow::Ok(())
+                })?
+            })
+            .await;
+            if let Err(err) = result {
+                fail_to_open_window_async(err, &mut cx);
+            }
+        })
+        .detach();
+    } else if !request.open_channel_notes.is_empty() || request.join_channel.is_some() {
         cx.spawn(async move |mut cx| {
             let result = maybe!(async {
                 if let Some(task) = task {
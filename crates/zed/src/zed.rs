@@ -134,6 +134,11 @@ pub fn init(cx: &mut App) {
             open_telemetry_log_file(workspace, window, cx);
         });
     });
+    cx.on_action(|_: &zed_actions::dev::OpenLanguageModelLog, cx| {
+        with_active_or_new_workspace(cx, |workspace, window, cx| {
+            open_language_model_log_file(workspace, window, cx);
+        });
+    });
     cx.on_action(|&zed_actions::OpenKeymap, cx| {
         with_active_or_new_workspace(cx, |_, window, cx| {
             open_settings_file(
@@ -472,6 +477,7 @@ fn initialize_panels(
         let project_panel = ProjectPanel::load(workspace_handle.clone(), cx.clone());
         let outline_panel = OutlinePanel::load(workspace_handle.clone(), cx.clone());
         let terminal_panel = TerminalPanel::load(workspace_handle.clone(), cx.clone());
+        let git_panel = GitPanel::load(workspace_handle.clone(), cx.clone());
         let channels_panel =
             collab_ui::collab_panel::CollabPanel::load(workspace_handle.clone(), cx.clone());
         let chat_panel =
@@ -485,6 +491,7 @@ fn initialize_panels(
             project_panel,
             outline_panel,
             terminal_panel,
+            git_panel,
             channels_panel,
             chat_panel,
             notification_panel,
@@ -492,6 +499,7 @@ fn initialize_panels(
             project_panel,
             outline_panel,
             terminal_panel,
+            git_panel,
             channels_panel,
             chat_panel,
             notification_panel,
@@ -501,6 +509,7 @@ fn initialize_panels(
             workspace.add_panel(project_panel, window, cx);
             workspace.add_panel(outline_panel, window, cx);
             workspace.add_panel(terminal_panel, window, cx);
+            workspace.add_panel(git_panel, window, cx);
             workspace.add_panel(channels_panel, window, cx);
             workspace.add_panel(chat_panel, window, cx);
             workspace.add_panel(notification_panel, window, cx);
@@ -518,12 +527,6 @@ fn initialize_panels(
                 )
                 .detach()
             });
-
-            let entity = cx.entity();
-            let project = workspace.project().clone();
-            let app_state = workspace.app_state().clone();
-            let git_panel = cx.new(|cx| GitPanel::new(entity, project, app_state, window, cx));
-            workspace.add_panel(git_panel, window, cx);
         })?;
 
         let is_assistant2_enabled = !cfg!(test);
@@ -1668,6 +1671,77 @@ fn open_telemetry_log_file(
     }).detach();
 }
 
+fn open_language_model_log_file(
+    workspace: &mut Workspace,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    const MAX_LANGUAGE_MODEL_LOG_LEN: usize = 5 * 1024 * 1024;
+
+    workspace
+        .with_local_workspace(window, cx, move |workspace, window, cx| {
+            let app_state = workspace.app_state().clone();
+            cx.spawn_in(window, async move |workspace, cx| {
+                let (old_log, new_log) = futures::join!(
+                    app_state.fs.load(paths::old_language_model_log_file()),
+                    app_state.fs.load(paths::language_model_log_file())
+                );
+                let log = old_log.into_iter().chain(new_log).collect::<String>();
+
+                let mut start_offset = log.len().saturating_sub(MAX_LANGUAGE_MODEL_LOG_LEN);
+                if start_offset > 0 {
+                    if let Some(newline_offset) = log[start_offset..].find('\n') {
+                        start_offset += newline_offset + 1;
+                    }
+                }
+                let content = if log.is_empty() {
+                    concat!(
+                        "// No language model requests have been logged yet.\n",
+                        "// Enable `language_models.log_requests` in your settings to log them.\n",
+                    )
+                    .to_string()
+                } else {
+                    log[start_offset..].to_string()
+                };
+                let json = app_state
+                    .languages
+                    .language_for_name("JSON")
+                    .await
+                    .log_err();
+
+                workspace
+                    .update_in(cx, |workspace, window, cx| {
+                        let project = workspace.project().clone();
+                        let buffer = project.update(cx, |project, cx| {
+                            project.create_local_buffer(&content, json, cx)
+                        });
+                        let buffer = cx.new(|cx| {
+                            MultiBuffer::singleton(buffer, cx)
+                                .with_title("Language Model Log".into())
+                        });
+                        workspace.add_item_to_active_pane(
+                            Box::new(cx.new(|cx| {
+                                let mut editor =
+                                    Editor::for_multibuffer(buffer, Some(project), window, cx);
+                                editor.set_read_only(true);
+                                editor.set_breadcrumb_header(
+                                    paths::language_model_log_file().display().to_string(),
+                                );
+                                editor
+                            })),
+                            None,
+                            true,
+                            window,
+                            cx,
+                        );
+                    })
+                    .log_err();
+            })
+            .detach();
+        })
+        .detach();
+}
+
 fn open_bundled_file(
     workspace: &Workspace,
     text: Cow<'static, str>,
@@ -33,6 +33,7 @@ pub struct OpenRequest {
     pub open_paths: Vec<String>,
     pub open_channel_notes: Vec<(u64, Option<String>)>,
     pub join_channel: Option<u64>,
+    pub open_commits: Vec<String>,
     pub ssh_connection: Option<SshConnectionOptions>,
     pub dock_menu_action: Option<usize>,
 }
@@ -103,7 +104,15 @@ impl OpenRequest {
 
     fn parse_request_path(&mut self, request_path: &str) -> Result<()> {
         let mut parts = request_path.split('/');
-        if parts.next() == Some("channel") {
+        let first = parts.next();
+        if first == Some("git") {
+            if let (Some("commit"), Some(sha)) = (parts.next(), parts.next()) {
+                if !sha.is_empty() && sha.chars().all(|c| c.is_ascii_hexdigit()) {
+                    self.open_commits.push(sha.to_string());
+                    return Ok(());
+                }
+            }
+        } else if first == Some("channel") {
             if let Some(slug) = parts.next() {
                 if let Some(id_str) = slug.split('-').next_back() {
                     if let Ok(channel_id) = id_str.parse::<u64>() {
@@ -567,6 +576,22 @@ mod tests {
         assert_eq!(request.open_paths, vec!["/"]);
     }
 
+    #[gpui::test]
+    fn test_parse_git_commit_url(cx: &mut TestAppContext) {
+        let _app_state = init_test(cx);
+        let request = cx.update(|cx| {
+            OpenRequest::parse(
+                vec![
+                    "zed://git/commit/3f2a9c1".into(),
+                    "zed://git/commit/not-a-sha".into(),
+                ],
+                cx,
+            )
+            .unwrap()
+        });
+        assert_eq!(request.open_commits, vec!["3f2a9c1"]);
+    }
+
     #[gpui::test]
     async fn test_open_workspace_with_directory(cx: &mut TestAppContext) {
         let app_state = init_test(cx);
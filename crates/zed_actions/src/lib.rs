@@ -114,7 +114,7 @@ impl_actions!(
 pub mod dev {
     use gpui::actions;
 
-    actions!(dev, [ToggleInspector]);
+    actions!(dev, [OpenLanguageModelLog, ToggleInspector]);
 }
 
 pub mod workspace {
@@ -154,6 +154,12 @@ pub mod jj {
     actions!(jj, [BookmarkList]);
 }
 
+pub mod layout_profiles {
+    use gpui::actions;
+
+    actions!(layout_profiles, [Toggle]);
+}
+
 pub mod command_palette {
     use gpui::actions;
 
@@ -197,12 +203,23 @@ pub mod icon_theme_selector {
 }
 
 pub mod agent {
-    use gpui::actions;
+    use gpui::{actions, impl_actions};
+    use schemars::JsonSchema;
+    use serde::Deserialize;
 
     actions!(
         agent,
         [OpenConfiguration, OpenOnboardingModal, ResetOnboarding]
     );
+
+    /// Opens a saved thread in the agent panel.
+    #[derive(PartialEq, Clone, Default, Debug, Deserialize, JsonSchema)]
+    #[serde(deny_unknown_fields)]
+    pub struct OpenThread {
+        pub thread_id: String,
+    }
+
+    impl_actions!(agent, [OpenThread]);
 }
 
 pub mod assistant {
@@ -23,7 +23,9 @@ use gpui::{
     App, AppContext as _, AsyncApp, Context, Entity, EntityId, Global, SemanticVersion,
     Subscription, Task, WeakEntity, actions,
 };
-use http_client::{AsyncBody, HttpClient, Method, Request, Response};
+use http_client::{
+    AsyncBody, HttpClient, HttpRequestExt as _, Method, Request, RequestPriority, Response,
+};
 use input_excerpt::excerpt_for_cursor_position;
 use language::{
     Anchor, Buffer, BufferSnapshot, EditPreview, OffsetRangeExt, ToOffset, ToPoint, text_diff,
@@ -764,7 +766,9 @@ and then another
             let mut did_retry = false;
 
             loop {
-                let request_builder = http_client::Request::builder().method(Method::POST);
+                let request_builder = http_client::Request::builder()
+                    .method(Method::POST)
+                    .priority(RequestPriority::Interactive);
                 let request_builder =
                     if let Ok(predict_edits_url) = std::env::var("ZED_PREDICT_EDITS_URL") {
                         request_builder.uri(predict_edits_url)